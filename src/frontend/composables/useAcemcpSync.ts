@@ -1,4 +1,4 @@
-import type { ProjectIndexStatus, ProjectsIndexStatus } from '../types/tauri'
+import type { ProjectIndexStatus, ProjectsIndexStatus, WatchedProject } from '../types/tauri'
 import { invoke } from '@tauri-apps/api/core'
 import { computed, onUnmounted, ref } from 'vue'
 
@@ -129,8 +129,8 @@ export function useAcemcpSync() {
   // 获取正在监听的项目列表
   async function fetchWatchingProjects() {
     try {
-      const result = await invoke<string[]>('get_watching_projects')
-      watchingProjects.value = result
+      const result = await invoke<WatchedProject[]>('get_watching_projects')
+      watchingProjects.value = result.map(p => p.project_root)
     }
     catch (err) {
       console.error('获取监听项目列表失败:', err)
@@ -1,5 +1,7 @@
+import type { ValidationReport } from './useValidationReport'
 import { invoke } from '@tauri-apps/api/core'
 import { computed, ref, watch } from 'vue'
+import { formatFieldIssue } from './useValidationReport'
 
 export interface FontInfo {
   font_family: string
@@ -121,7 +123,9 @@ export function useFontManager() {
    */
   async function setFontFamily(fontFamily: string) {
     try {
-      await invoke('set_font_family', { fontFamily })
+      const report = await invoke('set_font_family', { fontFamily }) as ValidationReport
+      if (report?.issues?.length)
+        throw new Error(formatFieldIssue(report.issues[0]))
       fontConfig.value.font_family = fontFamily
       applyFontVariables()
     }
@@ -136,7 +140,9 @@ export function useFontManager() {
    */
   async function setFontSize(fontSize: string) {
     try {
-      await invoke('set_font_size', { fontSize })
+      const report = await invoke('set_font_size', { fontSize }) as ValidationReport
+      if (report?.issues?.length)
+        throw new Error(formatFieldIssue(report.issues[0]))
       fontConfig.value.font_size = fontSize
       applyFontVariables()
     }
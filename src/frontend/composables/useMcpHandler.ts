@@ -56,6 +56,17 @@ export function useMcpHandler() {
       console.error('获取Telegram配置失败:', error)
       // 配置获取失败时，保持默认行为（显示弹窗）
     }
+    try {
+      const slackConfig = await invoke('get_slack_config')
+      // 如果Slack启用且配置了隐藏前端弹窗，则不显示前端弹窗
+      if (slackConfig && (slackConfig as any).enabled && (slackConfig as any).hide_frontend_popup) {
+        shouldShowFrontendPopup = false
+        console.log('🔕 根据Slack配置，隐藏前端弹窗')
+      }
+    }
+    catch (error) {
+      console.error('获取Slack配置失败:', error)
+    }
 
     // 根据配置决定是否显示前端弹窗
     if (shouldShowFrontendPopup) {
@@ -64,7 +75,7 @@ export function useMcpHandler() {
       showMcpPopup.value = true
     }
     else {
-      console.log('🔕 跳过前端弹窗显示，仅使用Telegram交互')
+      console.log('🔕 跳过前端弹窗显示，仅使用Telegram或Slack交互')
     }
 
     // 播放音频通知（无论是否显示弹窗都播放）
@@ -89,6 +100,20 @@ export function useMcpHandler() {
     catch (error) {
       console.error('启动Telegram同步失败:', error)
     }
+
+    // 启动Slack同步（Slack作为额外的回复渠道）
+    try {
+      if (request?.message) {
+        await invoke('start_slack_sync', {
+          message: request.message,
+          menu: request.menu || [],
+          chalkboard: request.chalkboard || false,
+        })
+      }
+    }
+    catch (error) {
+      console.error('启动Slack同步失败:', error)
+    }
   }
 
   /**
@@ -0,0 +1,41 @@
+import { listen } from '@tauri-apps/api/event'
+
+interface PantryUsageWarning {
+  total_bytes: number
+  max_bytes: number
+  percent: number
+}
+
+let unlistenPantryWarning: (() => void) | null = null
+
+/**
+ * 设置附件缓存用量警告监听器
+ */
+export async function setupPantryWarningListener(messageInstance: any): Promise<void> {
+  removePantryWarningListener()
+
+  try {
+    unlistenPantryWarning = await listen<PantryUsageWarning>('pantry://usage-warning', (event) => {
+      const { total_bytes, max_bytes, percent } = event.payload
+      const usedMb = (total_bytes / (1024 * 1024)).toFixed(1)
+      const maxMb = (max_bytes / (1024 * 1024)).toFixed(1)
+      messageInstance?.warning(`附件缓存已使用 ${percent}%（${usedMb} / ${maxMb} MB），超出上限后最早的附件将被淘汰，可在设置中清理`, {
+        duration: 5000,
+        closable: true,
+      })
+    })
+  }
+  catch (error) {
+    console.error('设置附件缓存警告监听器失败:', error)
+  }
+}
+
+/**
+ * 移除附件缓存用量警告监听器
+ */
+export function removePantryWarningListener(): void {
+  if (unlistenPantryWarning) {
+    unlistenPantryWarning()
+    unlistenPantryWarning = null
+  }
+}
@@ -20,6 +20,7 @@ export interface ProxyInfo {
   proxy_type: string
   host: string
   port: number
+  username?: string
 }
 
 /**
@@ -1,4 +1,6 @@
+import type { ValidationReport } from './useValidationReport'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow'
 import { ref } from 'vue'
 
@@ -212,11 +214,12 @@ function createSettings() {
   async function updateWindowSize(size: { width: number, height: number, fixed: boolean }) {
     try {
       // update_window_size 命令已经会同时更新 WindowConfig 中的所有相关设置
-      await invoke('update_window_size', { sizeUpdate: size })
+      const report = await invoke('update_window_size', { sizeUpdate: size }) as ValidationReport
 
-      // 更新本地状态
-      windowWidth.value = size.width
-      windowHeight.value = size.height
+      // 更新本地状态（超出范围的尺寸以后端修正后的值为准）
+      const clamped = Object.fromEntries((report?.issues || []).map(issue => [issue.field, issue.clamped]))
+      windowWidth.value = typeof clamped.width === 'number' ? clamped.width : size.width
+      windowHeight.value = typeof clamped.height === 'number' ? clamped.height : size.height
       fixedWindowSize.value = size.fixed
 
       // 根据模式设置监听器
@@ -391,6 +394,7 @@ function createSettings() {
 
   // 窗口焦点监听器
   let windowFocusUnlisten: (() => void) | null = null
+  let configReloadedUnlisten: (() => void) | null = null
 
   // 设置窗口焦点监听
   async function setupWindowFocusListener() {
@@ -403,6 +407,12 @@ function createSettings() {
           reloadAllSettings()
         }
       })
+
+      // 配置文件被外部修改时后端已重新加载，这里只需同步前端设置
+      configReloadedUnlisten = await listen('config_reloaded', () => {
+        loadWindowSettings()
+        loadWindowConfig()
+      })
     }
     catch (error) {
       console.error('设置窗口焦点监听器失败:', error)
@@ -415,6 +425,10 @@ function createSettings() {
       windowFocusUnlisten()
       windowFocusUnlisten = null
     }
+    if (configReloadedUnlisten) {
+      configReloadedUnlisten()
+      configReloadedUnlisten = null
+    }
   }
 
   return {
@@ -1,7 +1,11 @@
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { computed, ref } from 'vue'
 import { applyThemeVariables, getTheme } from '../theme'
 
+// 主题偏好（light / dark / auto），设置页面据此高亮当前选项
+export const themePreference = ref('')
+
 export function useTheme() {
   // 不设置默认主题，等待从config.json加载
   const currentTheme = ref('')
@@ -23,10 +27,11 @@ export function useTheme() {
   // 切换主题
   async function setTheme(theme: string) {
     try {
-      // 先保存到后端
-      await invoke('set_theme', { theme })
+      // 先保存到后端，返回实际使用的主题（auto 时为系统主题）
+      const effective = await invoke('set_theme', { theme }) as string
+      themePreference.value = theme
       // 保存成功后应用前端主题
-      applyTheme(theme)
+      applyTheme(effective)
     }
     catch (error) {
       console.error('保存主题设置失败:', error)
@@ -37,7 +42,8 @@ export function useTheme() {
   // 加载主题设置
   async function loadTheme() {
     try {
-      const theme = await invoke('get_theme')
+      themePreference.value = await invoke('get_theme') as string
+      const theme = await invoke('get_effective_theme')
       // 确保主题值有效
       const validTheme = (theme === 'light' || theme === 'dark') ? theme : 'dark'
 
@@ -51,6 +57,19 @@ export function useTheme() {
     }
   }
 
+  // 跟随系统时，系统主题切换由后端推送
+  listen<string>('theme-changed', (event) => {
+    if (themePreference.value === 'auto')
+      applyTheme(event.payload)
+  }).catch((error) => {
+    console.error('设置主题切换监听器失败:', error)
+  })
+
+  // 配置文件被外部修改后重新读取主题
+  listen('config_reloaded', () => loadTheme()).catch((error) => {
+    console.error('设置配置重载监听器失败:', error)
+  })
+
   // 立即尝试加载主题，避免延迟
   loadTheme().catch(() => {
     // 如果加载失败，应用默认主题
@@ -59,6 +78,7 @@ export function useTheme() {
 
   return {
     currentTheme,
+    themePreference,
     naiveTheme,
     setTheme,
     loadTheme,
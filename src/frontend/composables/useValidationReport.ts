@@ -0,0 +1,43 @@
+// 修改配置的命令返回的校验报告
+
+export interface FieldIssue {
+  field: string
+  rejected: unknown
+  reason: string
+  // 修正后实际保存的值，为空表示该字段未保存
+  clamped: unknown | null
+}
+
+export interface ValidationReport {
+  issues: FieldIssue[]
+}
+
+/**
+ * 把单个字段的问题格式化为一行提示
+ */
+export function formatFieldIssue(issue: FieldIssue): string {
+  const saved = issue.clamped === null || issue.clamped === undefined
+    ? '未保存'
+    : `已修正为 ${issue.clamped}`
+  return `${issue.reason}（输入 ${JSON.stringify(issue.rejected)}，${saved}）`
+}
+
+/**
+ * 按字段名整理提示，用于在对应输入项旁显示
+ */
+export function issuesByField(report: ValidationReport | null | undefined): Record<string, string> {
+  const result: Record<string, string> = {}
+  for (const issue of report?.issues || [])
+    result[issue.field] = formatFieldIssue(issue)
+  return result
+}
+
+/**
+ * 有问题时弹出警告，返回是否存在问题
+ */
+export function notifyValidationIssues(messageInstance: any, report: ValidationReport | null | undefined): boolean {
+  const issues = report?.issues || []
+  for (const issue of issues)
+    messageInstance?.warning(`${issue.field}: ${formatFieldIssue(issue)}`)
+  return issues.length > 0
+}
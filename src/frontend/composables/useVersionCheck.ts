@@ -10,6 +10,15 @@ interface VersionInfo {
   releaseNotes: string
   // 网络状态信息（新增）
   networkStatus?: NetworkStatus
+  // 当前版本之后各版本的更新说明
+  changelog?: ReleaseNote[]
+}
+
+// 单个版本的更新说明
+export interface ReleaseNote {
+  version: string
+  published_at: string
+  notes: string
 }
 
 // 网络状态信息接口
@@ -38,6 +47,7 @@ interface UpdateInfo {
   download_url: string
   // 网络状态信息（新增）
   network_status: NetworkStatus
+  changelog?: ReleaseNote[]
 }
 
 interface UpdateProgress {
@@ -137,6 +147,7 @@ function mapUpdateInfoToVersionInfo(updateInfo: UpdateInfo): VersionInfo {
     releaseUrl: updateInfo.download_url,
     releaseNotes: updateInfo.release_notes,
     networkStatus: updateInfo.network_status,
+    changelog: updateInfo.changelog,
   }
 }
 
@@ -206,6 +206,29 @@ export const MCP_TOOLS_CONFIG: ToolPromptConfig[] = [
       darkIconBg: 'dark:bg-orange-900',
     },
   },
+
+  // history_recall - past interactions
+  {
+    id: 'history_recall',
+    name: 'History Recall',
+    description: 'Recall past interactions for the current project',
+    prompt: {
+      base: '',
+      whenToUse: [
+        'Continuity: check what the user decided or asked for in earlier sessions',
+      ],
+      howToUse: [
+        'Pass the project root path; narrow with query/days/tag',
+      ],
+    },
+    ui: {
+      enabled: true,
+      canDisable: true,
+      icon: 'i-carbon-recently-viewed text-lg text-teal-600 dark:text-teal-400',
+      iconBg: 'bg-teal-100',
+      darkIconBg: 'dark:bg-teal-900',
+    },
+  },
 ]
 
 // ----------------------------------------------------------------------------
@@ -9,6 +9,12 @@ export const API_EXAMPLES = {
   proxy_example: 'https://your-proxy.com/bot',
 } as const
 
+/** Webhook 接收服务默认监听地址 */
+export const DEFAULT_WEBHOOK_LISTEN_ADDR = '127.0.0.1:8443'
+
+/** Webhook 接收服务的路径 */
+export const WEBHOOK_PATH = '/telegram/webhook'
+
 /** 默认 Telegram 配置 */
 export const DEFAULT_CONFIG = {
   enabled: false,
@@ -16,4 +22,9 @@ export const DEFAULT_CONFIG = {
   chat_id: '',
   hide_frontend_popup: false,
   api_base_url: API_BASE_URL,
+  webhook_enabled: false,
+  webhook_url: '',
+  webhook_listen_addr: DEFAULT_WEBHOOK_LISTEN_ADDR,
+  webhook_secret: '',
+  recipients: [],
 } as const
@@ -65,6 +65,7 @@ export const MCP_TOOLS = {
   store: 'store',
   index: 'index',
   docs: 'docs',
+  historyRecall: 'history_recall',
 } as const
 
 // 字体大小常量
@@ -25,11 +25,30 @@ export interface CustomPrompt {
   current_state?: boolean // 当前开关状态
 }
 
+// prompt 模板变量
+export interface PromptTemplateVariable {
+  name: string
+  builtin: boolean // 内置变量（project、date、clipboard 等）自动取值
+  default?: string | null
+}
+
+// prompt 模板渲染结果
+export interface RenderedPrompt {
+  text: string
+  missing: string[] // 没有取值的变量
+}
+
 // 自定义prompt配置
 export interface CustomPromptConfig {
   prompts: CustomPrompt[]
   enabled: boolean
   maxPrompts: number
+  usage?: Record<string, PromptUsage>
+}
+
+export interface PromptUsage {
+  count: number
+  last_used_at?: string | null
 }
 
 export interface PopupState {
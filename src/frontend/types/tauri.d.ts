@@ -3,7 +3,7 @@ declare module '@tauri-apps/plugin-shell' {
 }
 
 // Acemcp 索引状态类型定义
-export type IndexStatus = 'idle' | 'indexing' | 'synced' | 'failed'
+export type IndexStatus = 'idle' | 'indexing' | 'synced' | 'failed' | 'paused'
 
 export interface ProjectIndexStatus {
   project_root: string
@@ -17,6 +17,101 @@ export interface ProjectIndexStatus {
   last_failure_time: string | null
   last_error: string | null
   directory_stats: Record<string, [number, number]> // 目录路径 -> [总文件数, 已索引文件数]
+  stale?: boolean // 项目根目录已不存在
+  quarantined?: QuarantinedFile[] // 反复上传失败而被隔离的文件
+}
+
+// 被隔离的文件（后续索引跳过，修复后可手动重试）
+export interface QuarantinedFile {
+  path: string
+  attempts: number
+  reasons: string[]
+  last_failed_at: string | null
+}
+
+// 项目文件监听状态（超大仓库可能降级监听）
+export interface WatchedProject {
+  project_root: string
+  degraded: boolean
+  degraded_reason: string | null
+  watched_dirs: number
+  total_dirs: number
+  total_files: number
+  excluded_dirs: string[] // 自动排除的大型忽略目录（相对路径）
+}
+
+// 索引运行历史
+export interface IndexRunRecord {
+  started_at: string | null
+  finished_at: string | null
+  duration_ms: number
+  success: boolean
+  files_changed: number
+  files_deleted: number
+  blobs_uploaded: number
+  bytes_uploaded: number
+  failed_batches: number
+  failed_files: number
+  error: string | null
+}
+
+export interface IndexHistory {
+  project_root: string
+  runs: IndexRunRecord[] // 最新的在前
+  summary: {
+    total_runs: number
+    failed_runs: number
+    recent_avg_duration_ms: number | null
+    previous_avg_duration_ms: number | null
+    slowing_down: boolean
+    last_failure_at: string | null
+  }
+}
+
+// 索引预览（dry-run）结果
+export interface IndexPreview {
+  project_root: string
+  backend: string
+  files: number
+  blobs: number
+  bytes: number
+  new_blobs: number // 实际会上传的 blob 数
+  new_bytes: number
+  excluded: Record<string, { count: number, samples: string[] }> // 排除原因 -> 统计
+  secret_files: string[]
+  largest_files: [string, number][]
+}
+
+// 过滤规则诊断（为什么某个文件没有被索引）
+export interface FilterExplanation {
+  project_root: string
+  path: string
+  exists: boolean
+  is_dir: boolean
+  indexable: boolean
+  reason: string | null // gitignore / exclude_pattern / opt_out_marker / extension / too_large / binary / unreadable / secret_skipped / outside_project
+  rule: string | null
+  detail: string | null
+}
+
+// 检索后端健康检查
+export type HealthStatus = 'available' | 'unauthorized' | 'rate_limited' | 'error' | 'unreachable'
+
+export interface HealthSample {
+  checked_at: string
+  status: HealthStatus
+  available: boolean
+  latency_ms: number | null
+  status_code: number | null
+  error: string | null
+}
+
+export interface HealthReport {
+  base_url: string | null
+  via_proxy: boolean
+  samples: HealthSample[] // 最新的在前
+  availability: number | null // 0-1
+  avg_latency_ms: number | null
 }
 
 export interface ProjectsIndexStatus {
@@ -4,7 +4,6 @@ use crate::app::{setup::setup_application, commands::*};
 use crate::log_important;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
-use std::time::Duration;
 use tauri::Builder;
 
 /// 构建Tauri应用
@@ -12,6 +11,15 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_autostart::init(
+            tauri_plugin_autostart::MacosLauncher::LaunchAgent,
+            Some(vec![crate::ui::BACKGROUND_ARG]),
+        ))
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(crate::ui::global_shortcut::handle_global_shortcut)
+                .build(),
+        )
 
         .manage(AppState::default())
         .manage(AudioController {
@@ -24,6 +32,17 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             set_always_on_top,
             sync_window_state,
             reload_config,
+            export_config_file,
+            import_config_file,
+            get_config_profiles,
+            create_config_profile,
+            switch_config_profile,
+            delete_config_profile,
+            get_registered_projects,
+            save_registered_project,
+            remove_registered_project,
+            get_keychain_secrets,
+            set_keychain_secrets,
 
             // 音频命令
             get_audio_notification_enabled,
@@ -39,6 +58,7 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             // 主题和窗口命令
             get_theme,
             set_theme,
+            get_effective_theme,
             get_window_config,
             set_window_config,
             get_reply_config,
@@ -50,6 +70,18 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             get_current_window_size,
             apply_window_constraints,
             update_window_size,
+            center_window,
+            move_window_to_cursor_monitor,
+            get_launch_at_login,
+            set_launch_at_login,
+            get_background_mode,
+            set_background_mode,
+
+            // 配置向导命令
+            detect_mcp_clients,
+            generate_mcp_client_config,
+            install_mcp_client_config,
+            verify_mcp_connectivity,
 
             // 字体命令
             get_font_config,
@@ -63,23 +95,67 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             // MCP 命令
             get_mcp_tools_config,
             set_mcp_tool_enabled,
+            get_mcp_project_tools,
+            set_mcp_project_tool_enabled,
             get_mcp_tools_status,
             reset_mcp_tools_config,
             get_interaction_wait_ms,
             set_interaction_wait_ms,
             list_bistro_journal_entries,
+            list_bistro_journal_sessions,
+            list_bistro_journal_projects,
+            get_bistro_journal_stats,
+            get_bistro_journal_encryption,
+            set_bistro_journal_encryption,
+            get_bistro_journal_webhook_config,
+            save_bistro_journal_webhook_config,
+            get_notify_webhook_config,
+            save_notify_webhook_config,
+            test_notify_webhook,
+            get_notify_webhook_variables,
+            get_bistro_journal_sync_config,
+            save_bistro_journal_sync_config,
+            push_bistro_journal_sync,
+            pull_bistro_journal_sync,
+            set_bistro_journal_entry_labels,
+            update_bistro_journal_entry_markdown,
+            redact_bistro_journal_entry,
+            list_bistro_journal_tags,
+            search_bistro_journal_entries,
             get_bistro_journal_entry,
             delete_bistro_journal_entry,
             delete_bistro_journal_by_time_range,
+            list_bistro_journal_trash,
+            restore_bistro_journal_trash,
+            empty_bistro_journal_trash,
+            get_bistro_journal_trash_retention,
+            set_bistro_journal_trash_retention,
             export_bistro_journal_entry_zip,
             export_bistro_journal_by_time_range_zip,
+            export_bistro_journal_report,
+            import_bistro_journal_archive,
+            repair_bistro_journal,
             send_mcp_response,
             get_cli_args,
             read_mcp_request,
             stash_ingredient_bytes_cmd,
             stash_ingredient_base64_cmd,
             discard_spice_cmd,
+            begin_stash_upload_cmd,
+            append_stash_upload_cmd,
+            commit_stash_upload_cmd,
+            abort_stash_upload_cmd,
+            stash_ingredient_file_cmd,
+            clear_pantry_cache_cmd,
+            list_pantry_items_cmd,
+            remove_pantry_items_cmd,
+            get_pantry_encryption,
+            set_pantry_encryption,
+            get_pantry_janitor_config,
+            save_pantry_janitor_config,
+            get_pantry_metrics,
             read_clipboard_ingredients_cached,
+            copy_to_clipboard_cmd,
             build_mcp_continue_response,
             create_test_popup,
             
@@ -94,6 +170,11 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             crate::mcp::tools::acemcp::commands::get_acemcp_index_status,
             crate::mcp::tools::acemcp::commands::get_all_acemcp_index_status,
             crate::mcp::tools::acemcp::commands::get_acemcp_project_files_status,
+            crate::mcp::tools::acemcp::commands::get_acemcp_index_history,
+            crate::mcp::tools::acemcp::commands::get_acemcp_health,
+            crate::mcp::tools::acemcp::commands::check_acemcp_health,
+            crate::mcp::tools::acemcp::commands::preview_acemcp_index,
+            crate::mcp::tools::acemcp::commands::explain_acemcp_path_filter,
             crate::mcp::tools::acemcp::commands::trigger_acemcp_index_update,
             crate::mcp::tools::acemcp::commands::get_auto_index_enabled,
             crate::mcp::tools::acemcp::commands::set_auto_index_enabled,
@@ -102,7 +183,16 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             crate::mcp::tools::acemcp::commands::start_project_watching,
             crate::mcp::tools::acemcp::commands::stop_project_watching,
             crate::mcp::tools::acemcp::commands::stop_all_watching,
+            crate::mcp::tools::acemcp::commands::pause_acemcp_indexing,
+            crate::mcp::tools::acemcp::commands::resume_acemcp_indexing,
+            crate::mcp::tools::acemcp::commands::get_paused_acemcp_projects,
+            crate::mcp::tools::acemcp::commands::get_acemcp_endpoints,
+            crate::mcp::tools::acemcp::commands::save_acemcp_endpoints,
+            crate::mcp::tools::acemcp::commands::set_acemcp_project_endpoint,
             crate::mcp::tools::acemcp::commands::remove_acemcp_project_index,
+            crate::mcp::tools::acemcp::commands::retry_acemcp_quarantined_files,
+            crate::mcp::tools::acemcp::commands::get_stale_acemcp_projects,
+            crate::mcp::tools::acemcp::commands::remove_stale_acemcp_projects,
             crate::mcp::tools::acemcp::commands::check_directory_exists,
             // acemcp 代理命令
             crate::mcp::tools::acemcp::commands::detect_acemcp_proxy,
@@ -112,6 +202,13 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             crate::mcp::tools::docs::commands::test_docs_connection,
             crate::mcp::tools::docs::commands::get_docs_config,
             crate::mcp::tools::docs::commands::save_docs_config,
+            crate::mcp::tools::docs::commands::clear_docs_cache,
+            crate::mcp::tools::docs::commands::get_docs_library_registry,
+            crate::mcp::tools::docs::commands::save_docs_library_registry,
+            crate::mcp::tools::docs::commands::resolve_docs_library,
+            crate::mcp::tools::docs::commands::get_docs_query_history,
+            crate::mcp::tools::docs::commands::rerun_docs_query,
+            crate::mcp::tools::docs::commands::clear_docs_query_history,
 
             // 自定义prompt命令
             get_custom_prompt_config,
@@ -121,6 +218,14 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             set_custom_prompt_enabled,
             update_custom_prompt_order,
             update_conditional_prompt_state,
+            export_custom_prompt_pack,
+            preview_custom_prompt_pack,
+            import_custom_prompt_pack,
+            record_custom_prompt_usage,
+            get_custom_prompt_usage_report,
+            sort_custom_prompts_by_usage,
+            get_prompt_template_variables,
+            render_prompt_template,
 
             // 快捷键命令
             get_shortcut_config,
@@ -137,6 +242,12 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
             auto_get_chat_id,
             start_telegram_sync,
 
+            // Slack 命令
+            get_slack_config,
+            set_slack_config,
+            test_slack_connection_cmd,
+            start_slack_sync,
+
             // 系统命令
             open_external_url,
             exit_app,
@@ -159,6 +270,9 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
         .setup(|app| {
             let app_handle = app.handle().clone();
 
+            // 索引进度事件推送
+            crate::mcp::tools::acemcp::progress::set_progress_app_handle(app_handle.clone());
+
             // 应用初始化
             tauri::async_runtime::block_on(async {
                 if let Err(e) = setup_application(&app_handle).await {
@@ -166,17 +280,25 @@ pub fn build_tauri_app() -> Builder<tauri::Wry> {
                 }
             });
 
-            match crate::mcp::clean_expired_pantry_items(Duration::from_secs(60 * 60 * 24 * 7)) {
-                Ok(deleted) => {
-                    if deleted > 0 {
-                        log::info!("已清理过期食材: {} 项", deleted);
-                    }
-                }
-                Err(e) => {
-                    log::warn!("清理过期食材失败: {}", e);
-                }
+            // 托盘图标与后台模式
+            if let Err(e) = crate::ui::setup_background_mode(&app_handle) {
+                log_important!(warn, "初始化托盘失败: {}", e);
             }
 
+            // 注册全局快捷键
+            for error in crate::ui::global_shortcut::register_global_shortcuts(&app_handle) {
+                log_important!(warn, "全局快捷键未生效: {}", error);
+            }
+
+            // 外部修改配置文件后自动重新加载
+            if let Err(e) = crate::config::start_config_watcher(app_handle.clone()) {
+                log_important!(warn, "启动配置文件监听失败: {}", e);
+            }
+
+            // 食材缓存后台清理与用量警告
+            crate::mcp::pantry_metrics::set_pantry_app_handle(app_handle.clone());
+            crate::mcp::spawn_pantry_janitor();
+
             Ok(())
         })
 }
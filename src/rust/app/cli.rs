@@ -1,7 +1,10 @@
-use crate::config::load_standalone_telegram_config;
+use crate::config::load_standalone_config;
+use crate::slack::handle_slack_only_mcp_request;
 use crate::telegram::handle_telegram_only_mcp_request;
 use crate::log_important;
 use crate::app::builder::run_tauri_app;
+use crate::mcp::tools::acemcp::preview::render_preview;
+use crate::mcp::tools::acemcp::AcemcpTool;
 use anyhow::Result;
 
 /// 处理命令行参数
@@ -11,6 +14,7 @@ pub fn handle_cli_args() -> Result<()> {
     // Parse arguments
     let mut request_file: Option<String> = None;
     let mut response_file: Option<String> = None;
+    let mut background = false;
     let mut i = 1;
     
     while i < args.len() {
@@ -32,6 +36,19 @@ pub fn handle_cli_args() -> Result<()> {
                     std::process::exit(1);
                 }
             }
+            "--background" => {
+                // 开机自启时附带，由应用初始化时读取
+                background = true;
+                i += 1;
+            }
+            "--index-preview" => {
+                if i + 1 < args.len() {
+                    return handle_index_preview(&args[i + 1]);
+                } else {
+                    eprintln!("--index-preview requires a project path");
+                    std::process::exit(1);
+                }
+            }
             "--response-file" => {
                 if i + 1 < args.len() {
                     response_file = Some(args[i + 1].clone());
@@ -50,7 +67,7 @@ pub fn handle_cli_args() -> Result<()> {
     }
     
     // No arguments - start GUI normally
-    if request_file.is_none() && response_file.is_none() && args.len() == 1 {
+    if request_file.is_none() && response_file.is_none() && (args.len() == 1 || background) {
         run_tauri_app();
         return Ok(());
     }
@@ -73,10 +90,10 @@ pub fn handle_cli_args() -> Result<()> {
 
 /// 处理MCP请求
 fn handle_mcp_request(request_file: &str) -> Result<()> {
-    // 检查Telegram配置，决定是否启用纯Telegram模式
-    match load_standalone_telegram_config() {
-        Ok(telegram_config) => {
-            if telegram_config.enabled && telegram_config.hide_frontend_popup {
+    // 检查Telegram与Slack配置，决定是否启用纯Telegram或Slack模式
+    match load_standalone_config() {
+        Ok(config) => {
+            if config.telegram_config.enabled && config.telegram_config.hide_frontend_popup {
                 // 纯Telegram模式：不启动GUI，直接处理
                 if let Err(e) = tokio::runtime::Runtime::new()
                     .unwrap()
@@ -85,13 +102,22 @@ fn handle_mcp_request(request_file: &str) -> Result<()> {
                     log_important!(error, "处理Telegram请求失败: {}", e);
                     std::process::exit(1);
                 }
+            } else if config.slack_config.enabled && config.slack_config.hide_frontend_popup {
+                // 纯Slack模式：不启动GUI，直接处理
+                if let Err(e) = tokio::runtime::Runtime::new()
+                    .unwrap()
+                    .block_on(handle_slack_only_mcp_request(request_file))
+                {
+                    log_important!(error, "处理Slack请求失败: {}", e);
+                    std::process::exit(1);
+                }
             } else {
                 // 正常模式：启动GUI处理弹窗
                 run_tauri_app();
             }
         }
         Err(e) => {
-            log_important!(warn, "加载Telegram配置失败: {}，使用默认GUI模式", e);
+            log_important!(warn, "加载配置失败: {}，使用默认GUI模式", e);
             // 配置加载失败时，使用默认行为（启动GUI）
             run_tauri_app();
         }
@@ -99,6 +125,21 @@ fn handle_mcp_request(request_file: &str) -> Result<()> {
     Ok(())
 }
 
+/// 索引预览：只收集与分块，不上传，打印将要发送的内容统计
+fn handle_index_preview(project_root: &str) -> Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    match runtime.block_on(AcemcpTool::preview_index(project_root.to_string())) {
+        Ok(preview) => {
+            println!("{}", render_preview(&preview));
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("索引预览失败: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
 /// 显示帮助信息
 fn print_help() {
     println!("devkit-ui - Development Toolkit");
@@ -106,6 +147,8 @@ fn print_help() {
     println!("Usage:");
     println!("  devkit-ui                    Start settings UI");
     println!("  devkit-ui --mcp-request <file>  Process MCP request");
+    println!("  devkit-ui --background       Start hidden in the system tray");
+    println!("  devkit-ui --index-preview <project>  Dry-run code indexing (nothing is uploaded)");
     println!("  devkit-ui --help             Show this help");
     println!("  devkit-ui --version          Show version");
 }
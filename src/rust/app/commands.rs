@@ -7,6 +7,10 @@ pub use crate::mcp::commands::*;
 pub use crate::telegram::commands::*;
 pub use crate::telegram::handle_telegram_only_mcp_request;
 
+// Slack 命令
+pub use crate::slack::commands::*;
+pub use crate::slack::handle_slack_only_mcp_request;
+
 // UI 命令
 pub use crate::ui::{
     commands::*,
@@ -17,4 +21,7 @@ pub use crate::ui::{
     updater::*,
     exit::*,
     exit_handler::*,
+    background::*,
+    onboarding::*,
+    prompt_template::*,
 };
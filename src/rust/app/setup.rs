@@ -1,6 +1,7 @@
 use crate::config::{AppState, load_config_and_apply_window_settings};
 use crate::ui::{initialize_audio_asset_manager, setup_window_event_listeners};
 use crate::ui::exit_handler::setup_exit_handlers;
+use crate::constants::mcp;
 use crate::log_important;
 use tauri::{AppHandle, Manager};
 
@@ -21,6 +22,36 @@ pub async fn setup_application(app_handle: &AppHandle) -> Result<(), String> {
     // 设置窗口事件监听器
     setup_window_event_listeners(app_handle);
 
+    // 检测已从磁盘删除的索引项目（仅标记，不自动删除）
+    match crate::mcp::tools::acemcp::stale::reconcile_stale_projects() {
+        Ok(stale) if !stale.is_empty() => {
+            log_important!(info, "检测到 {} 个已失效的索引项目: {:?}", stale.len(), stale);
+        }
+        Ok(_) => {}
+        Err(e) => log_important!(warn, "检测失效索引项目失败: {}", e),
+    }
+
+    // 主程序启动时监听登记表中开启自动监听的项目（需对该项目启用 index 工具）
+    if !crate::ui::background::is_popup_mode() {
+        let project_roots: Vec<String> = state
+            .config
+            .lock()
+            .map(|config| {
+                crate::config::auto_watch_projects(&config)
+                    .into_iter()
+                    .filter(|root| config.mcp_config.is_tool_enabled_for(mcp::TOOL_INDEX, std::slice::from_ref(root)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        if !project_roots.is_empty() {
+            log_important!(info, "自动监听 {} 个登记项目", project_roots.len());
+            tauri::async_runtime::spawn(crate::mcp::tools::acemcp::commands::watch_registered_projects(project_roots));
+        }
+    }
+
+    // 启动检索后端健康检查（间隔由 acemcp_health_check_interval_secs 控制）
+    crate::mcp::tools::acemcp::health::ensure_health_monitor();
+
     // 设置退出处理器
     if let Err(e) = setup_exit_handlers(app_handle) {
         log_important!(warn, "设置退出处理器失败: {}", e);
@@ -0,0 +1,231 @@
+// 配置导入导出与备份
+// 导出时可将令牌、密码等密钥替换为占位符；导入时按字段合并到当前配置，占位符保留本机已有的值。
+// 数组按元素整体替换，带 name 字段的对象（如 acemcp 端点）按名称与本机配置对应后再合并。
+// 导入写入前把当前 config.json 复制到 backups 目录
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use super::migration::migrate_config;
+use super::secrets::SECRET_FIELDS;
+use super::settings::AppConfig;
+use super::storage::get_standalone_config_path;
+use crate::constants::validation;
+
+/// 导出时替换密钥的占位符
+pub const REDACTED_PLACEHOLDER: &str = "<redacted>";
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigImportResult {
+    /// 导入前的配置备份路径（之前没有配置文件时为空）
+    pub backup_path: Option<String>,
+    /// 导入文件中已脱敏、本机也没有对应值的密钥数，需要重新填写
+    pub missing_secrets: usize,
+}
+
+/// 将非空密钥替换为占位符，返回替换的数量
+pub fn redact_secrets(value: &mut Value) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, v)| match v {
+                Value::String(s) if SECRET_FIELDS.contains(&key.as_str()) && !s.is_empty() => {
+                    *s = REDACTED_PLACEHOLDER.to_string();
+                    1
+                }
+                _ => redact_secrets(v),
+            })
+            .sum(),
+        Value::Array(items) => items.iter_mut().map(redact_secrets).sum(),
+        _ => 0,
+    }
+}
+
+fn is_placeholder(value: &Value) -> bool {
+    value.as_str() == Some(REDACTED_PLACEHOLDER)
+}
+
+/// 清空无法对应到本机值的占位符，返回清空的数量
+fn clear_placeholders(value: &mut Value) -> usize {
+    match value {
+        Value::String(s) if s == REDACTED_PLACEHOLDER => {
+            s.clear();
+            1
+        }
+        Value::Object(map) => map.values_mut().map(clear_placeholders).sum(),
+        Value::Array(items) => items.iter_mut().map(clear_placeholders).sum(),
+        _ => 0,
+    }
+}
+
+fn item_name(value: &Value) -> Option<&str> {
+    value.get("name").and_then(Value::as_str)
+}
+
+/// 把 patch 合并到 base，返回没有本机值可保留的占位符数量
+pub fn merge_config_value(base: &mut Value, patch: Value) -> usize {
+    match (base, patch) {
+        (Value::Object(base_map), Value::Object(patch_map)) => {
+            let mut missing = 0;
+            for (key, mut value) in patch_map {
+                match base_map.get_mut(&key) {
+                    Some(existing) if is_placeholder(&value) => {
+                        if existing.as_str().unwrap_or_default().is_empty() {
+                            missing += 1;
+                        }
+                    }
+                    Some(existing) => missing += merge_config_value(existing, value),
+                    None => {
+                        missing += clear_placeholders(&mut value);
+                        base_map.insert(key, value);
+                    }
+                }
+            }
+            missing
+        }
+        (Value::Array(base_items), Value::Array(patch_items)) => {
+            let mut missing = 0;
+            let merged = patch_items
+                .into_iter()
+                .map(|mut item| {
+                    let matched = item_name(&item)
+                        .and_then(|name| base_items.iter().find(|b| item_name(b) == Some(name)))
+                        .cloned();
+                    match matched {
+                        Some(mut existing) => {
+                            missing += merge_config_value(&mut existing, item);
+                            existing
+                        }
+                        None => {
+                            missing += clear_placeholders(&mut item);
+                            item
+                        }
+                    }
+                })
+                .collect();
+            *base_items = merged;
+            missing
+        }
+        (base, mut patch) => {
+            let missing = clear_placeholders(&mut patch);
+            *base = patch;
+            missing
+        }
+    }
+}
+
+/// 导出配置到指定文件
+pub fn export_config(config: &AppConfig, path: &Path, redact: bool) -> Result<usize> {
+    let mut value = serde_json::to_value(config)?;
+    let redacted = if redact { redact_secrets(&mut value) } else { 0 };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, serde_json::to_string_pretty(&value)?)?;
+    log::info!("配置已导出到: {:?}（脱敏 {} 项）", path, redacted);
+    Ok(redacted)
+}
+
+/// 检查合并后的配置是否可用
+fn validate_imported_config(config: &AppConfig) -> Result<()> {
+    let mut result = validation::ValidationResult::new();
+
+    if !validation::is_valid_theme(&config.ui_config.theme) {
+        result.add_error(format!("无效的主题: {}", config.ui_config.theme));
+    }
+    let window = &config.ui_config.window_config;
+    result.merge(validation::validate_window_config(window.current_width(), window.current_height(), None, None));
+    result.merge(validation::validate_ingredient_limits(
+        config.reply_config.ingredient_max_mb,
+        config.reply_config.ingredients_total_max_mb,
+    ));
+
+    if result.is_valid {
+        Ok(())
+    } else {
+        Err(anyhow!(result.errors.join("；")))
+    }
+}
+
+/// 读取导入文件并合并到当前配置，返回合并后的配置与缺失的密钥数
+pub fn merge_imported_config(current: &AppConfig, path: &Path) -> Result<(AppConfig, usize)> {
+    let content = fs::read_to_string(path).map_err(|e| anyhow!("读取导入文件失败: {}", e))?;
+    let mut patch: Value = serde_json::from_str(&content).map_err(|e| anyhow!("导入文件不是有效的 JSON: {}", e))?;
+    if !patch.is_object() {
+        return Err(anyhow!("导入文件不是有效的配置文件"));
+    }
+    // 其他机器导出的旧版本配置先升级，再与本机配置合并
+    migrate_config(&mut patch);
+
+    let mut merged = serde_json::to_value(current)?;
+    let missing = merge_config_value(&mut merged, patch);
+    let config: AppConfig = serde_json::from_value(merged).map_err(|e| anyhow!("导入的配置格式不正确: {}", e))?;
+    validate_imported_config(&config)?;
+    Ok((config, missing))
+}
+
+/// 备份当前配置文件到 backups 目录，没有配置文件时返回 None
+pub fn backup_config_file() -> Result<Option<PathBuf>> {
+    let config_path = get_standalone_config_path()?;
+    if !config_path.exists() {
+        return Ok(None);
+    }
+
+    let backup_dir = config_path
+        .parent()
+        .ok_or_else(|| anyhow!("无法获取配置目录"))?
+        .join("backups");
+    fs::create_dir_all(&backup_dir)?;
+    let backup_path = backup_dir.join(format!("config-{}.json", chrono::Local::now().format("%Y%m%d-%H%M%S")));
+    fs::copy(&config_path, &backup_path)?;
+    log::info!("配置已备份到: {:?}", backup_path);
+    Ok(Some(backup_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_redact_and_merge() {
+        let mut exported = json!({
+            "mcp_config": { "acemcp_token": "secret", "docs_api_key": "", "acemcp_batch_size": 10 },
+            "telegram_config": { "bot_token": "bot" },
+        });
+        assert_eq!(redact_secrets(&mut exported), 2);
+        assert_eq!(exported["mcp_config"]["acemcp_token"], REDACTED_PLACEHOLDER);
+        assert_eq!(exported["mcp_config"]["docs_api_key"], "");
+
+        let mut local = json!({
+            "mcp_config": { "acemcp_token": "local", "acemcp_batch_size": 5 },
+            "telegram_config": { "bot_token": "" },
+        });
+        assert_eq!(merge_config_value(&mut local, exported), 1);
+        assert_eq!(local["mcp_config"]["acemcp_token"], "local");
+        assert_eq!(local["mcp_config"]["acemcp_batch_size"], 10);
+        assert_eq!(local["mcp_config"]["docs_api_key"], "");
+    }
+
+    #[test]
+    fn test_merge_named_items() {
+        let mut local = json!({ "endpoints": [
+            { "name": "work", "token": "w" },
+            { "name": "old", "token": "o" },
+        ] });
+        let patch = json!({ "endpoints": [
+            { "name": "home", "token": REDACTED_PLACEHOLDER },
+            { "name": "work", "token": REDACTED_PLACEHOLDER, "base_url": "https://w" },
+        ] });
+        assert_eq!(merge_config_value(&mut local, patch), 1);
+        assert_eq!(local["endpoints"], json!([
+            { "name": "home", "token": "" },
+            { "name": "work", "token": "w", "base_url": "https://w" },
+        ]));
+    }
+}
@@ -0,0 +1,176 @@
+// 多进程安全的配置写入
+// 主程序、弹窗进程和 MCP/HTTP 服务可能同时保存 config.json。写入时先获取锁文件，
+// 再写临时文件并改名替换，避免读到写了一半的内容；若磁盘上的配置在本进程上次读写之后被其他进程修改，
+// 以上次读写的内容为基准做三方合并，双方都修改的字段以本次保存为准并记录警告
+
+use anyhow::{bail, Result};
+use once_cell::sync::Lazy;
+use serde_json::{Map, Value};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+
+/// 等待锁的最长时间
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 锁文件超过该时间未释放时视为持有进程已异常退出
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// 本进程最近一次读写的配置文件内容，作为三方合并的基准
+static LAST_CONTENT: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+/// 记录本进程读写的配置内容
+pub(crate) fn remember_config_content(content: &str) {
+    *LAST_CONTENT.lock().unwrap_or_else(|e| e.into_inner()) = Some(content.to_string());
+}
+
+pub(crate) fn last_config_content() -> Option<String> {
+    LAST_CONTENT.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// 配置文件锁，释放时删除锁文件
+pub struct ConfigFileLock {
+    path: PathBuf,
+}
+
+impl ConfigFileLock {
+    pub fn acquire(config_path: &Path) -> Result<Self> {
+        let mut path = config_path.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        log::warn!("清理过期的配置锁文件: {:?}", path);
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        bail!("配置文件正被其他进程写入，请稍后重试");
+                    }
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for ConfigFileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn is_stale(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
+/// 先写同目录下的临时文件再改名替换
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+
+    let result = (|| -> Result<()> {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(content.as_bytes())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn merge_value(base: Option<&Value>, theirs: Value, ours: Value, path: &str, conflicts: &mut Vec<String>) -> Value {
+    if ours == theirs || base == Some(&theirs) {
+        return ours;
+    }
+    if base == Some(&ours) {
+        return theirs;
+    }
+    match (theirs, ours) {
+        (Value::Object(mut theirs), Value::Object(ours)) => {
+            let mut merged = Map::new();
+            for (key, our_value) in ours {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                let value = match theirs.remove(&key) {
+                    Some(their_value) => {
+                        let base_value = base.and_then(|b| b.get(&key));
+                        merge_value(base_value, their_value, our_value, &child, conflicts)
+                    }
+                    None => our_value,
+                };
+                merged.insert(key, value);
+            }
+            // 只在磁盘上存在的字段：基准中没有的是其他进程新增的（如新版本写入），原样保留；
+            // 基准中有的是本进程删除的（如删除 MCP 服务器），其他进程未修改时随之删除
+            for (key, their_value) in theirs {
+                if base.and_then(|b| b.get(&key)).is_none_or(|base_value| *base_value != their_value) {
+                    merged.insert(key, their_value);
+                }
+            }
+            Value::Object(merged)
+        }
+        (_, ours) => {
+            conflicts.push(path.to_string());
+            ours
+        }
+    }
+}
+
+/// 三方合并：base 为本进程上次读写的内容，theirs 为磁盘上的当前内容，ours 为本次要保存的内容；
+/// 返回合并结果与双方都修改过的字段路径
+pub fn merge_concurrent(base: &Value, theirs: Value, ours: Value) -> (Value, Vec<String>) {
+    let mut conflicts = Vec::new();
+    let merged = merge_value(Some(base), theirs, ours, "", &mut conflicts);
+    (merged, conflicts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_merge_concurrent() {
+        let base = json!({ "ui": { "theme": "dark", "top": true }, "mcp": { "batch": 10 } });
+        // 其他进程改了 batch 和 theme，本进程改了 top 和 theme
+        let theirs = json!({ "ui": { "theme": "light", "top": true }, "mcp": { "batch": 20 }, "extra": 1 });
+        let ours = json!({ "ui": { "theme": "auto", "top": false }, "mcp": { "batch": 10 } });
+
+        let (merged, conflicts) = merge_concurrent(&base, theirs, ours);
+        assert_eq!(merged, json!({ "ui": { "theme": "auto", "top": false }, "mcp": { "batch": 20 }, "extra": 1 }));
+        assert_eq!(conflicts, vec!["ui.theme"]);
+    }
+
+    #[test]
+    fn test_merge_concurrent_keeps_deletions() {
+        let base = json!({ "servers": { "a": { "cmd": "x" }, "b": { "cmd": "y" }, "c": { "cmd": "z" } } });
+        // 本进程删除了 a 和 b，其他进程修改了 b 并新增了 d
+        let theirs = json!({ "servers": { "a": { "cmd": "x" }, "b": { "cmd": "y2" }, "c": { "cmd": "z" }, "d": { "cmd": "w" } } });
+        let ours = json!({ "servers": { "c": { "cmd": "z" } } });
+
+        let (merged, conflicts) = merge_concurrent(&base, theirs, ours);
+        assert_eq!(merged, json!({ "servers": { "b": { "cmd": "y2" }, "c": { "cmd": "z" }, "d": { "cmd": "w" } } }));
+        assert!(conflicts.is_empty());
+    }
+}
@@ -0,0 +1,91 @@
+// 环境变量覆盖 MCP 配置
+// mcp_config 的任意字段都可以用 SANSHU_<字段名大写> 环境变量覆盖（如 SANSHU_ACEMCP_BASE_URL），
+// 便于容器或无界面部署不修改配置文件即可配置。值优先按 JSON 解析（数字、布尔、数组、对象），
+// 不符合字段类型时按字符串处理；仍无法匹配的值忽略并记录警告
+
+use serde_json::Value;
+
+use super::settings::McpConfig;
+
+/// 环境变量前缀
+pub const ENV_PREFIX: &str = "SANSHU_";
+
+/// 字段对应的环境变量名
+pub fn env_var_name(field: &str) -> String {
+    format!("{}{}", ENV_PREFIX, field.to_ascii_uppercase())
+}
+
+/// 按候选值依次尝试覆盖字段，成功时返回覆盖后的配置
+fn try_override(config: &Value, field: &str, raw: &str) -> Option<McpConfig> {
+    let mut candidates = serde_json::from_str::<Value>(raw)
+        .ok()
+        .into_iter()
+        .chain(std::iter::once(Value::String(raw.to_string())));
+    candidates.find_map(|candidate| {
+        let mut value = config.clone();
+        value[field] = candidate;
+        serde_json::from_value(value).ok()
+    })
+}
+
+/// 用 lookup 提供的环境变量覆盖配置，返回被覆盖的字段名
+pub fn apply_env_overrides_with(config: &mut McpConfig, lookup: impl Fn(&str) -> Option<String>) -> Vec<String> {
+    let Ok(mut value) = serde_json::to_value(&*config) else {
+        return Vec::new();
+    };
+    let fields: Vec<String> = value
+        .as_object()
+        .map(|map| map.keys().cloned().collect())
+        .unwrap_or_default();
+
+    let mut applied = Vec::new();
+    for field in fields {
+        let name = env_var_name(&field);
+        let Some(raw) = lookup(&name) else {
+            continue;
+        };
+        match try_override(&value, &field, raw.trim()) {
+            Some(overridden) => {
+                *config = overridden;
+                value = serde_json::to_value(&*config).unwrap_or(value);
+                applied.push(field);
+            }
+            None => log::warn!("环境变量 {} 的值与字段 {} 的类型不匹配，已忽略", name, field),
+        }
+    }
+    applied
+}
+
+/// 用 SANSHU_* 环境变量覆盖 MCP 配置
+pub fn apply_env_overrides(config: &mut McpConfig) {
+    let applied = apply_env_overrides_with(config, |name| std::env::var(name).ok());
+    if !applied.is_empty() {
+        log::debug!("以下 MCP 配置由环境变量覆盖: {}", applied.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_mcp_config;
+
+    #[test]
+    fn test_apply_env_overrides() {
+        let mut config = default_mcp_config();
+        let applied = apply_env_overrides_with(&mut config, |name| match name {
+            "SANSHU_ACEMCP_BASE_URL" => Some("https://ace.example.com".to_string()),
+            "SANSHU_ACEMCP_BATCH_SIZE" => Some("20".to_string()),
+            "SANSHU_DOCS_API_KEY" => Some("12345".to_string()),
+            "SANSHU_ACEMCP_EXCLUDE_PATTERNS" => Some("[\"target\", \"node_modules\"]".to_string()),
+            "SANSHU_INTERACTION_WAIT_MS" => Some("soon".to_string()),
+            _ => None,
+        });
+        assert_eq!(applied.len(), 4);
+        assert_eq!(config.acemcp_base_url.as_deref(), Some("https://ace.example.com"));
+        assert_eq!(config.acemcp_batch_size, Some(20));
+        // 数字形式的字符串字段按字符串处理
+        assert_eq!(config.docs_api_key.as_deref(), Some("12345"));
+        assert_eq!(config.acemcp_exclude_patterns.as_ref().map(Vec::len), Some(2));
+        assert_eq!(config.interaction_wait_ms, default_mcp_config().interaction_wait_ms);
+    }
+}
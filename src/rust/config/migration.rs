@@ -0,0 +1,122 @@
+// 配置版本迁移
+// config.json 记录 config_version，加载时按版本依次执行迁移步骤，逐项升级改名的字段和变更的默认值并记录日志。
+// 新增字段仍由 serde 默认值补齐；比当前程序更新的配置文件原样加载，不做降级
+
+use serde_json::{Map, Value};
+
+/// 当前配置版本
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
+
+/// 迁移步骤：修改配置对象，返回每项变更的说明
+type MigrationStep = fn(&mut Map<String, Value>) -> Vec<String>;
+
+/// 第 i 项把版本 i 升级到 i + 1
+const MIGRATIONS: &[MigrationStep] = &[migrate_v0_to_v1];
+
+/// 配置文件中记录的版本，没有该字段的旧配置视为版本 0
+pub fn config_version(value: &Value) -> u32 {
+    value
+        .get("config_version")
+        .and_then(Value::as_u64)
+        .map_or(0, |v| v.min(u32::MAX as u64) as u32)
+}
+
+/// 把配置升级到当前版本，返回是否发生了迁移（需要写回文件）
+pub fn migrate_config(value: &mut Value) -> bool {
+    let from = config_version(value);
+    if from > CURRENT_CONFIG_VERSION {
+        log::warn!("配置文件版本 v{} 高于当前程序支持的 v{}，按现有字段加载", from, CURRENT_CONFIG_VERSION);
+        return false;
+    }
+    if from == CURRENT_CONFIG_VERSION {
+        return false;
+    }
+    let Some(root) = value.as_object_mut() else {
+        return false;
+    };
+
+    for (version, step) in MIGRATIONS.iter().enumerate().skip(from as usize) {
+        for change in step(root) {
+            log::info!("配置迁移 v{} -> v{}: {}", version, version + 1, change);
+        }
+    }
+    root.insert("config_version".to_string(), CURRENT_CONFIG_VERSION.into());
+    log::info!("配置已从 v{} 升级到 v{}", from, CURRENT_CONFIG_VERSION);
+    true
+}
+
+/// 字段改名：旧字段存在且新字段不存在时搬移，旧字段总是移除
+fn rename_field(obj: &mut Map<String, Value>, old: &str, new: &str) -> bool {
+    let Some(value) = obj.remove(old) else {
+        return false;
+    };
+    if obj.contains_key(new) {
+        return false;
+    }
+    obj.insert(new.to_string(), value);
+    true
+}
+
+fn object_at<'a>(root: &'a mut Map<String, Value>, path: &[&str]) -> Option<&'a mut Map<String, Value>> {
+    path.iter()
+        .try_fold(root, |obj, key| obj.get_mut(*key).and_then(Value::as_object_mut))
+}
+
+/// v0 -> v1：自定义 prompt 的 default_state 改名为 current_state；
+/// 增强快捷键的旧默认值 Shift+Enter 改为 Ctrl+Shift+Enter
+fn migrate_v0_to_v1(root: &mut Map<String, Value>) -> Vec<String> {
+    let mut changes = Vec::new();
+
+    if let Some(prompts) = root
+        .get_mut("custom_prompt_config")
+        .and_then(|c| c.get_mut("prompts"))
+        .and_then(Value::as_array_mut)
+    {
+        for prompt in prompts.iter_mut().filter_map(Value::as_object_mut) {
+            if rename_field(prompt, "default_state", "current_state") {
+                let name = prompt.get("name").and_then(Value::as_str).unwrap_or_default();
+                changes.push(format!("自定义 prompt「{}」的 default_state 改名为 current_state", name));
+            }
+        }
+    }
+
+    if let Some(key) = object_at(root, &["shortcut_config", "shortcuts", "enhance", "key_combination"]) {
+        let flag = |key: &Map<String, Value>, name: &str| key.get(name).and_then(Value::as_bool).unwrap_or(false);
+        let is_old_default = key.get("key").and_then(Value::as_str) == Some("Enter")
+            && flag(key, "shift")
+            && !flag(key, "ctrl")
+            && !flag(key, "alt")
+            && !flag(key, "meta");
+        if is_old_default {
+            key.insert("ctrl".to_string(), Value::Bool(true));
+            changes.push("增强快捷键从 Shift+Enter 更新为 Ctrl+Shift+Enter".to_string());
+        }
+    }
+
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_v0() {
+        let mut config = json!({
+            "custom_prompt_config": { "prompts": [{ "name": "a", "default_state": true }] },
+            "shortcut_config": { "shortcuts": { "enhance": { "key_combination": {
+                "key": "Enter", "ctrl": false, "alt": false, "shift": true, "meta": false
+            } } } },
+        });
+        assert!(migrate_config(&mut config));
+        assert_eq!(config_version(&config), CURRENT_CONFIG_VERSION);
+        assert_eq!(config["custom_prompt_config"]["prompts"][0], json!({ "name": "a", "current_state": true }));
+        assert_eq!(config["shortcut_config"]["shortcuts"]["enhance"]["key_combination"]["ctrl"], true);
+
+        // 已是当前版本时不再修改
+        assert!(!migrate_config(&mut config));
+        let mut newer = json!({ "config_version": CURRENT_CONFIG_VERSION + 1 });
+        assert!(!migrate_config(&mut newer));
+    }
+}
@@ -1,5 +1,22 @@
+pub mod backup;
+pub mod concurrent;
+pub mod env_overrides;
+pub mod migration;
+pub mod profiles;
+pub mod projects;
+pub mod prompt_pack;
+pub mod prompt_usage;
+pub mod secrets;
 pub mod settings;
 pub mod storage;
+pub mod watcher;
 
+pub use backup::*;
+pub use migration::*;
+pub use profiles::*;
+pub use projects::*;
+pub use prompt_pack::*;
+pub use prompt_usage::*;
 pub use settings::*;
 pub use storage::*;
+pub use watcher::start_config_watcher;
@@ -0,0 +1,155 @@
+// 配置档案
+// 不同场景（如工作 / 个人）可以保存各自的 MCP 工具、acemcp 与代理设置。
+// 顶层 mcp_config / proxy_config 始终是当前档案的设置，切换档案时先存回当前档案再载入目标档案。
+// MCP 服务进程可通过 DEVKIT_PROFILE 环境变量在内存中改用指定档案，不影响主程序的当前档案
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+
+use super::settings::{AppConfig, McpConfig, ProxyConfig};
+
+/// 未创建任何档案时当前设置所属的档案名
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 指定 MCP 服务进程所用档案的环境变量
+pub const PROFILE_ENV_VAR: &str = "DEVKIT_PROFILE";
+
+/// 档案名长度上限
+const MAX_PROFILE_NAME_LEN: usize = 32;
+
+/// 单个档案保存的设置
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ConfigProfile {
+    pub mcp_config: McpConfig,
+    pub proxy_config: ProxyConfig,
+}
+
+/// 档案列表
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigProfiles {
+    pub active: String,
+    pub names: Vec<String>,
+}
+
+pub fn default_active_profile() -> String {
+    DEFAULT_PROFILE.to_string()
+}
+
+fn current_profile(config: &AppConfig) -> ConfigProfile {
+    ConfigProfile {
+        mcp_config: config.mcp_config.clone(),
+        proxy_config: config.proxy_config.clone(),
+    }
+}
+
+fn apply_profile(config: &mut AppConfig, profile: ConfigProfile) {
+    config.mcp_config = profile.mcp_config;
+    config.proxy_config = profile.proxy_config;
+}
+
+/// 档案名只允许字母、数字、下划线和连字符
+pub fn validate_profile_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.len() > MAX_PROFILE_NAME_LEN {
+        return Err(anyhow!("档案名长度需在 1-{} 个字符之间", MAX_PROFILE_NAME_LEN));
+    }
+    if !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+        return Err(anyhow!("档案名只能包含字母、数字、下划线和连字符: {}", name));
+    }
+    Ok(())
+}
+
+pub fn list_profiles(config: &AppConfig) -> ConfigProfiles {
+    let mut names: Vec<String> = config.profiles.keys().cloned().collect();
+    if !names.contains(&config.active_profile) {
+        names.push(config.active_profile.clone());
+    }
+    names.sort();
+    ConfigProfiles {
+        active: config.active_profile.clone(),
+        names,
+    }
+}
+
+/// 以当前设置新建档案（不切换）
+pub fn create_profile(config: &mut AppConfig, name: &str) -> Result<()> {
+    validate_profile_name(name)?;
+    if name == config.active_profile || config.profiles.contains_key(name) {
+        return Err(anyhow!("档案已存在: {}", name));
+    }
+    let profile = current_profile(config);
+    config.profiles.insert(name.to_string(), profile);
+    Ok(())
+}
+
+/// 切换到指定档案：当前设置存回当前档案，再载入目标档案
+pub fn switch_profile(config: &mut AppConfig, name: &str) -> Result<()> {
+    if name == config.active_profile {
+        return Ok(());
+    }
+    let target = config
+        .profiles
+        .remove(name)
+        .ok_or_else(|| anyhow!("档案不存在: {}", name))?;
+
+    let previous = current_profile(config);
+    config.profiles.insert(config.active_profile.clone(), previous);
+    apply_profile(config, target);
+    config.active_profile = name.to_string();
+    Ok(())
+}
+
+/// 删除档案，不能删除当前档案
+pub fn delete_profile(config: &mut AppConfig, name: &str) -> Result<()> {
+    if name == config.active_profile {
+        return Err(anyhow!("不能删除当前使用的档案: {}", name));
+    }
+    config
+        .profiles
+        .remove(name)
+        .map(|_| ())
+        .ok_or_else(|| anyhow!("档案不存在: {}", name))
+}
+
+/// 按 DEVKIT_PROFILE 环境变量在内存中改用指定档案，档案不存在时保持当前设置
+pub fn apply_profile_override(config: &mut AppConfig) {
+    let Ok(name) = std::env::var(PROFILE_ENV_VAR) else {
+        return;
+    };
+    let name = name.trim();
+    if name.is_empty() || name == config.active_profile {
+        return;
+    }
+    match config.profiles.get(name).cloned() {
+        Some(profile) => {
+            log::info!("按环境变量 {} 使用配置档案: {}", PROFILE_ENV_VAR, name);
+            apply_profile(config, profile);
+            config.active_profile = name.to_string();
+        }
+        None => log::warn!("环境变量 {} 指定的配置档案不存在: {}，使用当前档案", PROFILE_ENV_VAR, name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_switch_profile() {
+        let mut config = AppConfig::default();
+        config.mcp_config.acemcp_token = Some("personal".to_string());
+        create_profile(&mut config, "work").unwrap();
+        assert!(create_profile(&mut config, "work").is_err());
+        assert!(create_profile(&mut config, "a b").is_err());
+
+        switch_profile(&mut config, "work").unwrap();
+        config.mcp_config.acemcp_token = Some("work".to_string());
+        switch_profile(&mut config, DEFAULT_PROFILE).unwrap();
+        assert_eq!(config.mcp_config.acemcp_token.as_deref(), Some("personal"));
+        assert_eq!(config.profiles["work"].mcp_config.acemcp_token.as_deref(), Some("work"));
+        assert_eq!(list_profiles(&config).names, vec!["default", "work"]);
+
+        assert!(delete_profile(&mut config, DEFAULT_PROFILE).is_err());
+        delete_profile(&mut config, "work").unwrap();
+        assert!(switch_profile(&mut config, "work").is_err());
+    }
+}
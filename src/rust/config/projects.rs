@@ -0,0 +1,169 @@
+// 项目登记表
+// 记录常用项目的路径、显示名称、默认启用的 MCP 工具和是否在启动时自动监听，供各处的项目选择列表使用。
+// 项目使用的 acemcp 端点仍保存在 mcp_config.acemcp_project_endpoints 中，登记表只负责读写，避免两处记录不一致；
+// 从登记表移除项目不会删除其索引和端点分配
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use super::settings::AppConfig;
+
+/// 登记的项目
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ProjectEntry {
+    pub path: String, // 规范化后的项目根路径（唯一）
+    pub name: String, // 显示名称，为空时使用目录名
+    #[serde(default)]
+    pub default_tools: Vec<String>, // 默认启用的 MCP 工具 id
+    #[serde(default)]
+    pub auto_watch: bool, // 主程序启动时自动开始文件监听
+}
+
+/// 项目列表中的一项
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectInfo {
+    #[serde(flatten)]
+    pub entry: ProjectEntry,
+    /// 分配的索引端点，为空时使用默认端点
+    pub acemcp_endpoint: Option<String>,
+    /// 项目目录是否仍然存在
+    pub exists: bool,
+}
+
+fn dir_name(path: &str) -> String {
+    path.rsplit('/')
+        .find(|part| !part.is_empty())
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// 按显示名称排序的项目列表
+pub fn list_projects(config: &AppConfig) -> Vec<ProjectInfo> {
+    let assignments = config.mcp_config.acemcp_project_endpoints.as_ref();
+    let mut projects: Vec<ProjectInfo> = config
+        .projects
+        .iter()
+        .map(|entry| ProjectInfo {
+            entry: entry.clone(),
+            acemcp_endpoint: assignments.and_then(|a| a.get(&entry.path)).cloned(),
+            exists: Path::new(&entry.path).is_dir(),
+        })
+        .collect();
+    projects.sort_by_key(|p| p.entry.name.to_lowercase());
+    projects
+}
+
+/// 新增或更新项目（按路径匹配），同时设置其索引端点；返回端点是否变化
+pub fn upsert_project(config: &mut AppConfig, mut entry: ProjectEntry, acemcp_endpoint: Option<String>) -> Result<bool> {
+    if entry.path.trim().is_empty() {
+        return Err(anyhow!("项目路径不能为空"));
+    }
+    entry.name = entry.name.trim().to_string();
+    if entry.name.is_empty() {
+        entry.name = dir_name(&entry.path);
+    }
+    entry.default_tools.sort();
+    entry.default_tools.dedup();
+    if let Some(tool) = entry
+        .default_tools
+        .iter()
+        .find(|tool| !config.mcp_config.tools.contains_key(tool.as_str()))
+    {
+        return Err(anyhow!("未知的 MCP 工具: {}", tool));
+    }
+
+    let acemcp_endpoint = acemcp_endpoint.filter(|name| !name.trim().is_empty());
+    if let Some(name) = &acemcp_endpoint {
+        let exists = config
+            .mcp_config
+            .acemcp_endpoints
+            .as_ref()
+            .is_some_and(|eps| eps.iter().any(|ep| &ep.name == name));
+        if !exists {
+            return Err(anyhow!("索引端点不存在: {}", name));
+        }
+    }
+
+    let assignments = config.mcp_config.acemcp_project_endpoints.get_or_insert_with(Default::default);
+    let previous = match &acemcp_endpoint {
+        Some(name) => assignments.insert(entry.path.clone(), name.clone()),
+        None => assignments.remove(&entry.path),
+    };
+
+    match config.projects.iter_mut().find(|p| p.path == entry.path) {
+        Some(existing) => *existing = entry,
+        None => config.projects.push(entry),
+    }
+    Ok(previous != acemcp_endpoint)
+}
+
+/// 从登记表中移除项目
+pub fn remove_project(config: &mut AppConfig, path: &str) -> Result<()> {
+    let before = config.projects.len();
+    config.projects.retain(|p| p.path != path);
+    if config.projects.len() == before {
+        return Err(anyhow!("项目未登记: {}", path));
+    }
+    Ok(())
+}
+
+/// 需要在启动时自动监听的项目路径（跳过已不存在的目录）
+pub fn auto_watch_projects(config: &AppConfig) -> Vec<String> {
+    config
+        .projects
+        .iter()
+        .filter(|p| p.auto_watch && Path::new(&p.path).is_dir())
+        .map(|p| p.path.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::AcemcpEndpoint;
+
+    fn entry(path: &str, name: &str) -> ProjectEntry {
+        ProjectEntry {
+            path: path.to_string(),
+            name: name.to_string(),
+            default_tools: vec![],
+            auto_watch: false,
+        }
+    }
+
+    #[test]
+    fn test_upsert_project() {
+        let mut config = AppConfig::default();
+        config.mcp_config.acemcp_endpoints = Some(vec![AcemcpEndpoint {
+            name: "work".to_string(),
+            base_url: "https://example.com".to_string(),
+            token: String::new(),
+            proxy_enabled: None,
+            proxy_host: None,
+            proxy_port: None,
+            proxy_type: None,
+            proxy_username: None,
+            proxy_password: None,
+        }]);
+
+        assert!(!upsert_project(&mut config, entry("/code/api", " "), None).unwrap());
+        assert_eq!(config.projects[0].name, "api");
+
+        assert!(upsert_project(&mut config, entry("/code/api", "API"), Some("work".to_string())).unwrap());
+        assert_eq!(config.projects.len(), 1);
+        let listed = list_projects(&config);
+        assert_eq!(listed[0].entry.name, "API");
+        assert_eq!(listed[0].acemcp_endpoint.as_deref(), Some("work"));
+
+        assert!(upsert_project(&mut config, entry("/code/web", ""), Some("missing".to_string())).is_err());
+        let mut with_tool = entry("/code/web", "");
+        with_tool.default_tools = vec!["unknown".to_string()];
+        assert!(upsert_project(&mut config, with_tool, None).is_err());
+
+        remove_project(&mut config, "/code/api").unwrap();
+        assert!(remove_project(&mut config, "/code/api").is_err());
+        // 端点分配保留
+        assert_eq!(config.mcp_config.acemcp_project_endpoints.unwrap()["/code/api"], "work");
+    }
+}
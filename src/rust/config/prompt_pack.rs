@@ -0,0 +1,228 @@
+// 自定义 prompt 分享包
+// 选中的 prompt 导出为独立的 JSON 文件，团队成员导入后即可使用同一套回复模板。
+// 导入时总是重新生成 id，避免与本机已有的 prompt 冲突；与本机同名的 prompt 按用户的选择处理：
+// 跳过、重命名为“名称 (2)”或覆盖本机的同名 prompt（保留本机 id 与排序）
+
+use anyhow::{anyhow, bail, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::settings::{CustomPrompt, CustomPromptConfig};
+
+/// 分享包的格式标识
+pub const PROMPT_PACK_FORMAT: &str = "devkit-prompt-pack";
+
+/// 当前分享包版本
+pub const PROMPT_PACK_VERSION: u32 = 1;
+
+/// 分享包
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PromptPack {
+    pub format: String,
+    pub version: u32,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub exported_at: String,
+    pub prompts: Vec<CustomPrompt>,
+}
+
+/// 同名 prompt 的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptConflictAction {
+    Skip,
+    Rename,
+    Overwrite,
+}
+
+/// 导入前预览中的一项
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptPackItem {
+    pub id: String,
+    pub name: String,
+    pub r#type: String,
+    /// 本机已有同名 prompt
+    pub conflict: bool,
+}
+
+/// 导入前预览
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptPackPreview {
+    pub name: String,
+    pub description: Option<String>,
+    pub exported_at: String,
+    pub prompts: Vec<PromptPackItem>,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PromptPackImportResult {
+    pub added: usize,
+    pub renamed: usize,
+    pub overwritten: usize,
+    pub skipped: usize,
+}
+
+/// 打包选中的 prompt（按当前排序）
+pub fn build_prompt_pack(config: &CustomPromptConfig, prompt_ids: &[String], name: &str) -> Result<PromptPack> {
+    if prompt_ids.is_empty() {
+        bail!("请选择要导出的 prompt");
+    }
+    if let Some(missing) = prompt_ids.iter().find(|id| !config.prompts.iter().any(|p| &p.id == *id)) {
+        bail!("未找到指定的prompt: {}", missing);
+    }
+    let mut prompts: Vec<CustomPrompt> = config
+        .prompts
+        .iter()
+        .filter(|p| prompt_ids.contains(&p.id))
+        .cloned()
+        .collect();
+    prompts.sort_by_key(|p| p.sort_order);
+
+    Ok(PromptPack {
+        format: PROMPT_PACK_FORMAT.to_string(),
+        version: PROMPT_PACK_VERSION,
+        name: name.trim().to_string(),
+        description: None,
+        exported_at: chrono::Utc::now().to_rfc3339(),
+        prompts,
+    })
+}
+
+pub fn write_prompt_pack(pack: &PromptPack, path: &Path) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(pack)?)?;
+    Ok(())
+}
+
+pub fn read_prompt_pack(path: &Path) -> Result<PromptPack> {
+    let content = fs::read_to_string(path)?;
+    let pack: PromptPack = serde_json::from_str(&content).map_err(|e| anyhow!("不是有效的 prompt 分享包: {}", e))?;
+    if pack.format != PROMPT_PACK_FORMAT {
+        bail!("不是 prompt 分享包: {}", pack.format);
+    }
+    if pack.version > PROMPT_PACK_VERSION {
+        bail!("分享包版本 {} 高于当前支持的版本 {}，请升级后再导入", pack.version, PROMPT_PACK_VERSION);
+    }
+    Ok(pack)
+}
+
+/// 列出分享包中的 prompt，并标出与本机同名的项
+pub fn preview_prompt_pack(config: &CustomPromptConfig, pack: &PromptPack) -> PromptPackPreview {
+    PromptPackPreview {
+        name: pack.name.clone(),
+        description: pack.description.clone(),
+        exported_at: pack.exported_at.clone(),
+        prompts: pack
+            .prompts
+            .iter()
+            .map(|p| PromptPackItem {
+                id: p.id.clone(),
+                name: p.name.clone(),
+                r#type: p.r#type.clone(),
+                conflict: config.prompts.iter().any(|existing| existing.name == p.name),
+            })
+            .collect(),
+    }
+}
+
+/// 导入分享包；resolutions 按分享包中的 prompt id 指定同名时的处理方式，未指定的使用 default_action
+pub fn import_prompt_pack(
+    config: &mut CustomPromptConfig,
+    pack: PromptPack,
+    default_action: PromptConflictAction,
+    resolutions: &HashMap<String, PromptConflictAction>,
+) -> Result<PromptPackImportResult> {
+    let mut result = PromptPackImportResult::default();
+    let mut next_order = config.prompts.iter().map(|p| p.sort_order).max().unwrap_or(0) + 1;
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut additions = Vec::new();
+
+    for mut prompt in pack.prompts {
+        let action = resolutions.get(&prompt.id).copied().unwrap_or(default_action);
+        prompt.updated_at = now.clone();
+
+        if let Some(existing) = config.prompts.iter_mut().find(|p| p.name == prompt.name) {
+            match action {
+                PromptConflictAction::Skip => {
+                    result.skipped += 1;
+                    continue;
+                }
+                PromptConflictAction::Overwrite => {
+                    prompt.id = existing.id.clone();
+                    prompt.sort_order = existing.sort_order;
+                    prompt.created_at = existing.created_at.clone();
+                    *existing = prompt;
+                    result.overwritten += 1;
+                    continue;
+                }
+                PromptConflictAction::Rename => {
+                    result.renamed += 1;
+                }
+            }
+        }
+
+        // 重命名为 “名称 (2)” 等未被占用的名称；同一个包内也可能重名，已加入的 prompt 一并检查
+        let taken = |name: &str, additions: &[CustomPrompt]| {
+            config.prompts.iter().chain(additions.iter()).any(|p| p.name == name)
+        };
+        if taken(&prompt.name, &additions) {
+            let base = prompt.name.clone();
+            prompt.name = (2..)
+                .map(|n| format!("{} ({})", base, n))
+                .find(|candidate| !taken(candidate, &additions))
+                .unwrap_or(base);
+        }
+        prompt.id = uuid::Uuid::new_v4().to_string();
+        prompt.sort_order = next_order;
+        prompt.created_at = now.clone();
+        next_order += 1;
+        additions.push(prompt);
+    }
+
+    if config.prompts.len() + additions.len() > config.max_prompts as usize {
+        bail!(
+            "导入后将有 {} 个 prompt，超过上限 {}",
+            config.prompts.len() + additions.len(),
+            config.max_prompts
+        );
+    }
+    result.added = additions.len();
+    config.prompts.extend(additions);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_custom_prompt_config;
+
+    #[test]
+    fn test_import_prompt_pack() {
+        let mut config = default_custom_prompt_config();
+        let existing = config.prompts[0].clone();
+        let pack = build_prompt_pack(&config, std::slice::from_ref(&existing.id), "team").unwrap();
+        assert!(build_prompt_pack(&config, &["missing".to_string()], "team").is_err());
+        assert!(preview_prompt_pack(&config, &pack).prompts[0].conflict);
+
+        let before = config.prompts.len();
+        let result = import_prompt_pack(&mut config, pack.clone(), PromptConflictAction::Skip, &HashMap::new()).unwrap();
+        assert_eq!((result.added, result.skipped), (0, 1));
+
+        let result = import_prompt_pack(&mut config, pack.clone(), PromptConflictAction::Rename, &HashMap::new()).unwrap();
+        assert_eq!((result.added, result.renamed), (1, 1));
+        assert_eq!(config.prompts.len(), before + 1);
+        let renamed = config.prompts.last().unwrap();
+        assert_eq!(renamed.name, format!("{} (2)", existing.name));
+        assert_ne!(renamed.id, existing.id);
+
+        let resolutions = HashMap::from([(existing.id.clone(), PromptConflictAction::Overwrite)]);
+        let result = import_prompt_pack(&mut config, pack, PromptConflictAction::Skip, &resolutions).unwrap();
+        assert_eq!(result.overwritten, 1);
+        assert_eq!(config.prompts[0].id, existing.id);
+        assert_eq!(config.prompts.len(), before + 1);
+    }
+}
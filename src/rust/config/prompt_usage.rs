@@ -0,0 +1,144 @@
+// 自定义 prompt 使用统计
+// 每次插入 prompt 时累计次数并记录时间，统计与 prompt 一起保存在 custom_prompt_config.usage 中。
+// 据此可按使用频率排序，并列出长期未使用的 prompt 帮助清理：
+// 从未使用的 prompt 以创建时间起算，创建不久的不算作未使用
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use super::settings::CustomPromptConfig;
+
+/// 默认视为长期未使用的天数
+pub const DEFAULT_UNUSED_DAYS: u32 = 90;
+
+/// unused_days 的上限（避免日期计算溢出）
+pub const MAX_UNUSED_DAYS: u32 = 3650;
+
+/// 单个 prompt 的使用情况
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptUsageStat {
+    pub id: String,
+    pub name: String,
+    pub count: u64,
+    pub last_used_at: Option<String>,
+}
+
+/// 使用统计报告
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptUsageReport {
+    /// 按使用次数从多到少排列，次数相同时最近使用的在前
+    pub by_frequency: Vec<PromptUsageStat>,
+    /// 超过 unused_days 天未使用的 prompt
+    pub unused: Vec<PromptUsageStat>,
+    pub unused_days: u32,
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+/// 记录一次使用，prompt 不存在时返回 false
+pub fn record_prompt_usage(config: &mut CustomPromptConfig, prompt_id: &str, now: DateTime<Utc>) -> bool {
+    if !config.prompts.iter().any(|p| p.id == prompt_id) {
+        return false;
+    }
+    let usage = config.usage.entry(prompt_id.to_string()).or_default();
+    usage.count += 1;
+    usage.last_used_at = Some(now.to_rfc3339());
+    true
+}
+
+/// 清理已删除 prompt 的统计
+pub fn prune_prompt_usage(config: &mut CustomPromptConfig) {
+    let CustomPromptConfig { prompts, usage, .. } = config;
+    usage.retain(|id, _| prompts.iter().any(|p| &p.id == id));
+}
+
+fn usage_stats(config: &CustomPromptConfig) -> Vec<PromptUsageStat> {
+    let mut stats: Vec<PromptUsageStat> = config
+        .prompts
+        .iter()
+        .map(|p| {
+            let usage = config.usage.get(&p.id).cloned().unwrap_or_default();
+            PromptUsageStat {
+                id: p.id.clone(),
+                name: p.name.clone(),
+                count: usage.count,
+                last_used_at: usage.last_used_at,
+            }
+        })
+        .collect();
+    let last_used = |stat: &PromptUsageStat| stat.last_used_at.as_deref().and_then(parse_time);
+    stats.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| last_used(b).cmp(&last_used(a))));
+    stats
+}
+
+/// 生成使用统计报告
+pub fn prompt_usage_report(config: &CustomPromptConfig, unused_days: u32, now: DateTime<Utc>) -> PromptUsageReport {
+    let unused_days = unused_days.min(MAX_UNUSED_DAYS);
+    let cutoff = now - Duration::days(unused_days as i64);
+    let by_frequency = usage_stats(config);
+    let unused = by_frequency
+        .iter()
+        .filter(|stat| {
+            let last_used = config.usage.get(&stat.id).and_then(|u| u.last_used_at.as_deref()).and_then(parse_time);
+            // 从未使用时以创建时间为准，创建时间无法解析的视为很久以前创建
+            let since = last_used.or_else(|| {
+                config
+                    .prompts
+                    .iter()
+                    .find(|p| p.id == stat.id)
+                    .and_then(|p| parse_time(&p.created_at))
+            });
+            since.is_none_or(|t| t < cutoff)
+        })
+        .cloned()
+        .collect();
+    PromptUsageReport { by_frequency, unused, unused_days }
+}
+
+/// 按使用频率重新排序 prompt（更新 sort_order）
+pub fn sort_prompts_by_usage(config: &mut CustomPromptConfig) {
+    let order: Vec<String> = usage_stats(config).into_iter().map(|stat| stat.id).collect();
+    for prompt in config.prompts.iter_mut() {
+        if let Some(index) = order.iter().position(|id| id == &prompt.id) {
+            prompt.sort_order = index as i32 + 1;
+        }
+    }
+    config.prompts.sort_by_key(|p| p.sort_order);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::default_custom_prompt_config;
+
+    #[test]
+    fn test_prompt_usage_report() {
+        let now = Utc::now();
+        let mut config = default_custom_prompt_config();
+        for (i, prompt) in config.prompts.iter_mut().enumerate() {
+            prompt.id = format!("p{}", i);
+            prompt.created_at = (now - Duration::days(200)).to_rfc3339();
+        }
+        config.prompts[0].created_at = now.to_rfc3339();
+        let last = config.prompts.last().unwrap().id.clone();
+
+        assert!(record_prompt_usage(&mut config, &last, now));
+        assert!(record_prompt_usage(&mut config, &last, now));
+        assert!(record_prompt_usage(&mut config, "p1", now - Duration::days(100)));
+        assert!(!record_prompt_usage(&mut config, "missing", now));
+
+        let report = prompt_usage_report(&config, DEFAULT_UNUSED_DAYS, now);
+        assert_eq!(report.by_frequency[0].id, last);
+        assert_eq!(report.by_frequency[0].count, 2);
+        // p0 刚创建，p1 上次使用在 100 天前
+        assert!(!report.unused.iter().any(|s| s.id == "p0" || s.id == last));
+        assert!(report.unused.iter().any(|s| s.id == "p1"));
+        assert_eq!(prompt_usage_report(&config, u32::MAX, now).unused_days, MAX_UNUSED_DAYS);
+
+        sort_prompts_by_usage(&mut config);
+        assert_eq!(config.prompts[0].id, last);
+        assert_eq!(config.prompts[0].sort_order, 1);
+    }
+}
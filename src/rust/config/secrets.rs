@@ -0,0 +1,211 @@
+// 配置密钥存储
+// 开启后 acemcp 令牌、Docs API 密钥、Telegram / Slack Token 等密钥保存在系统钥匙串中，config.json 里只留占位符。
+// 加载时把占位符替换回真实值，其他模块照常读取内存中的配置。
+// 每次写入新密钥时占位符带上新的随机版本号（`<keychain:xxxxxxxx>`），其他进程发现占位符变化后重新读取钥匙串。
+// 钥匙串不可用时密钥继续以明文保存在配置文件中，读取失败的占位符视为未填写
+
+use anyhow::Result;
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+const KEYCHAIN_SERVICE: &str = "devkit-config";
+
+/// 配置文件中代替钥匙串密钥的占位符（旧版本写入的不带版本号）
+pub const KEYCHAIN_PLACEHOLDER: &str = "<keychain>";
+
+/// 带版本号的占位符前缀
+const VERSIONED_PLACEHOLDER_PREFIX: &str = "<keychain:";
+
+/// 视为密钥的字段名
+pub(crate) const SECRET_FIELDS: &[&str] = &[
+    "acemcp_token",
+    "acemcp_proxy_password",
+    "history_webhook_secret",
+    "notify_webhook_secret",
+    "history_sync_password",
+    "docs_api_key",
+    "bot_token",
+    "app_token",
+    "webhook_secret",
+    "token",
+    "proxy_password",
+];
+
+/// 已读写过的钥匙串条目及其对应的占位符
+struct CachedSecret {
+    placeholder: String,
+    secret: String,
+}
+
+/// 账户名 -> 已读写过的密钥；占位符不变时不再访问钥匙串
+static SECRET_CACHE: Lazy<Mutex<HashMap<String, CachedSecret>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn is_placeholder(value: &str) -> bool {
+    value == KEYCHAIN_PLACEHOLDER || (value.starts_with(VERSIONED_PLACEHOLDER_PREFIX) && value.ends_with('>'))
+}
+
+fn new_placeholder() -> String {
+    let version = uuid::Uuid::new_v4().simple().to_string();
+    format!("{}{}>", VERSIONED_PLACEHOLDER_PREFIX, &version[..8])
+}
+
+/// 遍历配置中的密钥字段，account 为字段路径（数组中带 name 的元素以名称代替下标）
+fn visit_secrets(value: &mut Value, path: &str, f: &mut impl FnMut(&str, &mut String)) {
+    match value {
+        Value::Object(map) => {
+            for (key, v) in map.iter_mut() {
+                let child = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                match v {
+                    Value::String(s) if SECRET_FIELDS.contains(&key.as_str()) => f(&child, s),
+                    _ => visit_secrets(v, &child, f),
+                }
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                let segment = item
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map_or_else(|| index.to_string(), str::to_string);
+                visit_secrets(item, &format!("{}.{}", path, segment), f);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn keychain_entry(account: &str) -> Result<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, account).map_err(|e| anyhow::anyhow!("无法访问系统钥匙串: {}", e))
+}
+
+/// 检查系统钥匙串是否可用
+pub fn check_keychain() -> Result<()> {
+    match keychain_entry("availability-check")?.get_password() {
+        Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => anyhow::bail!("系统钥匙串不可用: {}", e),
+    }
+}
+
+/// 把非空密钥写入钥匙串并替换为占位符，返回写入的数量；写入失败的密钥保留在配置中
+pub fn store_secrets(value: &mut Value) -> usize {
+    let mut cache = SECRET_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut stored = 0;
+    visit_secrets(value, "", &mut |account, secret| {
+        if secret.is_empty() || is_placeholder(secret) {
+            return;
+        }
+        let placeholder = match cache.get(account) {
+            Some(cached) if cached.secret == *secret => cached.placeholder.clone(),
+            _ => {
+                let written = keychain_entry(account)
+                    .and_then(|entry| entry.set_password(secret).map_err(|e| anyhow::anyhow!("{}", e)));
+                if let Err(e) = written {
+                    log::warn!("密钥 {} 写入系统钥匙串失败，继续保存在配置文件中: {}", account, e);
+                    return;
+                }
+                let placeholder = new_placeholder();
+                cache.insert(
+                    account.to_string(),
+                    CachedSecret { placeholder: placeholder.clone(), secret: secret.clone() },
+                );
+                placeholder
+            }
+        };
+        *secret = placeholder;
+        stored += 1;
+    });
+    stored
+}
+
+/// 把占位符替换为钥匙串中的密钥，读取失败时置空
+///
+/// 缓存按占位符校验：其他进程轮换密钥后占位符随之改变，此时重新读取钥匙串
+pub fn resolve_secrets(value: &mut Value) {
+    let mut cache = SECRET_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    visit_secrets(value, "", &mut |account, secret| {
+        if !is_placeholder(secret) {
+            return;
+        }
+        if let Some(cached) = cache.get(account).filter(|cached| cached.placeholder == *secret) {
+            *secret = cached.secret.clone();
+            return;
+        }
+        match keychain_entry(account).and_then(|entry| entry.get_password().map_err(|e| anyhow::anyhow!("{}", e))) {
+            Ok(password) => {
+                cache.insert(
+                    account.to_string(),
+                    CachedSecret { placeholder: secret.clone(), secret: password.clone() },
+                );
+                *secret = password;
+            }
+            Err(e) => {
+                log::warn!("从系统钥匙串读取密钥 {} 失败，按未填写处理: {}", account, e);
+                secret.clear();
+            }
+        }
+    });
+}
+
+/// 删除配置中各密钥在钥匙串中的条目（改回保存在配置文件时调用），返回删除的数量
+pub fn clear_keychain_secrets(value: &Value) -> usize {
+    let mut value = value.clone();
+    let mut cache = SECRET_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    let mut removed = 0;
+    visit_secrets(&mut value, "", &mut |account, _| {
+        cache.remove(account);
+        let deleted = keychain_entry(account)
+            .and_then(|entry| entry.delete_password().map_err(|e| anyhow::anyhow!("{}", e)));
+        if deleted.is_ok() {
+            removed += 1;
+        }
+    });
+    removed
+}
+
+/// 配置中非空密钥的数量
+pub fn count_secrets(value: &Value) -> usize {
+    let mut value = value.clone();
+    let mut count = 0;
+    visit_secrets(&mut value, "", &mut |_, secret| {
+        if !secret.is_empty() {
+            count += 1;
+        }
+    });
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_visit_secrets() {
+        let mut config = json!({
+            "mcp_config": {
+                "acemcp_token": "a",
+                "acemcp_endpoints": [{ "name": "work", "token": "w" }],
+                "acemcp_batch_size": 10,
+            },
+            "telegram_config": { "bot_token": "" },
+        });
+        let mut accounts = Vec::new();
+        visit_secrets(&mut config, "", &mut |account, _| accounts.push(account.to_string()));
+        accounts.sort();
+        assert_eq!(
+            accounts,
+            vec!["mcp_config.acemcp_endpoints.work.token", "mcp_config.acemcp_token", "telegram_config.bot_token"]
+        );
+        assert_eq!(count_secrets(&config), 2);
+    }
+
+    #[test]
+    fn test_placeholder_versions() {
+        let (a, b) = (new_placeholder(), new_placeholder());
+        assert_ne!(a, b);
+        assert!(is_placeholder(&a) && is_placeholder(KEYCHAIN_PLACEHOLDER));
+        assert!(!is_placeholder("sk-live") && !is_placeholder("<keychain"));
+    }
+}
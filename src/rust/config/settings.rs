@@ -1,10 +1,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Mutex;
-use crate::constants::{window, theme, audio, mcp, telegram, font};
+use crate::constants::{window, theme, audio, mcp, slack, telegram, font};
+use super::profiles::{default_active_profile, ConfigProfile};
+use super::projects::ProjectEntry;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AppConfig {
+    #[serde(default)]
+    pub config_version: u32, // 配置版本，旧配置文件缺失时为 0，加载时迁移到当前版本
     #[serde(default = "default_ui_config")]
     pub ui_config: UiConfig, // UI相关配置（主题、窗口、置顶等）
     #[serde(default = "default_audio_config")]
@@ -15,19 +19,29 @@ pub struct AppConfig {
     pub mcp_config: McpConfig, // MCP工具配置
     #[serde(default = "default_telegram_config")]
     pub telegram_config: TelegramConfig, // Telegram Bot配置
+    #[serde(default = "default_slack_config")]
+    pub slack_config: SlackConfig, // Slack Bot配置
     #[serde(default = "default_custom_prompt_config")]
     pub custom_prompt_config: CustomPromptConfig, // 自定义prompt配置
     #[serde(default = "default_shortcut_config")]
     pub shortcut_config: ShortcutConfig, // 自定义快捷键配置
     #[serde(default = "default_proxy_config")]
     pub proxy_config: ProxyConfig, // 代理配置
+    #[serde(default = "default_active_profile")]
+    pub active_profile: String, // 当前配置档案名
+    #[serde(default)]
+    pub profiles: HashMap<String, ConfigProfile>, // 其他配置档案（档案名 -> MCP 与代理设置），当前档案的设置即顶层配置
+    #[serde(default)]
+    pub keychain_secrets: bool, // 令牌、密码等密钥保存在系统钥匙串中，默认保存在配置文件
+    #[serde(default)]
+    pub projects: Vec<ProjectEntry>, // 登记的常用项目
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UiConfig {
     // 主题设置
     #[serde(default = "default_theme")]
-    pub theme: String, // "light", "dark"
+    pub theme: String, // "light", "dark", "auto"（跟随系统）
 
     // 字体设置
     #[serde(default = "default_font_config")]
@@ -40,6 +54,10 @@ pub struct UiConfig {
     // 置顶设置
     #[serde(default = "default_always_on_top")]
     pub always_on_top: bool,
+
+    // 后台模式：启动时隐藏到托盘，关闭窗口时不退出
+    #[serde(default)]
+    pub background_mode: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -86,6 +104,17 @@ pub struct WindowConfig {
     pub free_width: f64,
     #[serde(default = "default_free_height")]
     pub free_height: f64,
+
+    // 窗口位置（按显示器布局分别记录，键为布局签名）
+    #[serde(default)]
+    pub positions: HashMap<String, WindowPosition>,
+}
+
+/// 窗口左上角位置（物理像素）
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct WindowPosition {
+    pub x: i32,
+    pub y: i32,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -104,12 +133,17 @@ pub struct ReplyConfig {
     pub auto_continue_threshold: u32, // 字符数阈值
     #[serde(default = "default_continue_prompt")]
     pub continue_prompt: String, // 继续回复的提示词
+    #[serde(default = "default_ingredient_max_mb")]
+    pub ingredient_max_mb: u32, // 单个食材大小上限（MB），文本与压缩包不超过各自的默认上限
+    #[serde(default = "default_ingredients_total_max_mb")]
+    pub ingredients_total_max_mb: u32, // 单次提交的食材总大小上限（MB）
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct McpConfig {
     #[serde(default = "default_mcp_tools")]
     pub tools: HashMap<String, bool>, // MCP工具启用状态
+    pub project_tools: Option<HashMap<String, HashMap<String, bool>>>, // 按项目覆盖工具启用状态（项目路径 -> 工具 id -> 是否启用），未覆盖的工具沿用全局设置
     pub acemcp_base_url: Option<String>, // acemcp API端点URL
     pub acemcp_token: Option<String>, // acemcp认证令牌
     pub acemcp_batch_size: Option<u32>, // acemcp批处理大小
@@ -125,11 +159,78 @@ pub struct McpConfig {
     pub acemcp_proxy_type: Option<String>, // 代理类型: "http" | "https" | "socks5"
     pub acemcp_proxy_username: Option<String>, // 代理用户名（可选）
     pub acemcp_proxy_password: Option<String>, // 代理密码（可选）
+    pub acemcp_backend: Option<String>, // 索引后端: "remote"（默认）| "local"（本地离线 BM25）
+    pub acemcp_upload_concurrency: Option<u32>, // 并发上传批次数，默认 4
+    pub acemcp_workspace_groups: Option<HashMap<String, Vec<String>>>, // 工作区分组（分组名 -> 项目路径列表），用于多项目联合检索
+    pub acemcp_search_cache_ttl_secs: Option<u64>, // 检索结果缓存有效期（秒），默认 300，0 表示禁用
+    pub acemcp_max_file_size_kb: Option<u64>, // 单文件大小上限（KB），默认 1024，0 表示不限制
+    pub acemcp_skip_binary_files: Option<bool>, // 是否跳过二进制内容文件（NUL 字节/非法 UTF-8 嗅探），默认 true
+    pub acemcp_upload_compression: Option<String>, // 上传压缩: "off"（默认）| "gzip" | "auto"
+    pub acemcp_max_upload_mbps: Option<f64>, // 上传带宽上限（MB/s），0 或未设置表示不限制
+    pub acemcp_max_concurrent_reads: Option<u32>, // 同时读取的文件数上限，默认 4
+    pub acemcp_index_niceness: Option<u8>, // 索引 CPU 让出程度 0-19，默认 0
+    pub acemcp_reindex_schedules: Option<Vec<String>>, // 定时重新索引窗口（cron 表达式，如 "0 2 * * *"）
+    pub acemcp_schedule_only: Option<bool>, // 仅在定时窗口内索引，文件监听不立即触发
+    pub acemcp_watch_max_dirs: Option<usize>, // 文件监听目录数上限，超过后降级监听，默认 8192
+    pub acemcp_watch_max_files: Option<usize>, // 文件监听文件数上限，超过后仅监听根目录，默认 200000
+    pub acemcp_query_rewrite: Option<bool>, // 检索前改写查询（去代码块围栏、展开缩写），默认关闭
+    pub acemcp_query_prefer_english: Option<bool>, // 后端偏好英文：改写时为中文术语追加英文关键词
+    pub acemcp_query_abbreviations: Option<HashMap<String, String>>, // 自定义缩写表（缩写 -> 全称），优先于内置表
+    pub acemcp_secret_scan: Option<String>, // 上传前密钥扫描: "redact"（默认）| "skip" | "off"
+    pub acemcp_health_check_interval_secs: Option<u64>, // 后端健康检查间隔（秒），0 表示关闭，默认 300
+    pub acemcp_cache_encryption: Option<bool>, // 加密 ~/.acemcp/data 下的本地缓存（密钥保存在系统钥匙串），默认关闭
+    pub acemcp_endpoints: Option<Vec<AcemcpEndpoint>>, // 额外的远程索引端点（多租户）
+    pub acemcp_project_endpoints: Option<HashMap<String, String>>, // 项目路径 -> 端点名称，未分配的项目使用默认端点
+    pub history_encryption: Option<bool>, // 加密保存交互历史（密钥保存在系统钥匙串），默认关闭
+    pub history_trash_retention_days: Option<u32>, // 删除的历史记录在回收站中保留的天数，0 表示直接彻底删除，默认 30
+    pub history_webhook_url: Option<String>, // 保存历史记录时 POST JSON 摘要的地址，为空时不推送
+    pub history_webhook_include_images: Option<bool>, // Webhook 中内嵌图片（base64），默认关闭
+    pub history_webhook_secret: Option<String>, // Webhook 签名密钥（HMAC-SHA256，可选）
+    pub notify_webhook_url: Option<String>, // 打开请求和提交回复时 POST 通知的地址，为空时不通知
+    pub notify_webhook_template: Option<String>, // 通知请求体模板（{{变量}}），为空时发送默认 JSON
+    pub notify_webhook_secret: Option<String>, // 通知签名密钥（HMAC-SHA256，可选）
+    pub history_sync_backend: Option<String>, // 历史同步后端: "directory" | "webdav" | "s3"，为空时不同步
+    pub history_sync_target: Option<String>, // 同步目录路径、WebDAV 集合 URL 或 S3 地址（https://host/bucket[/prefix]）
+    pub history_sync_username: Option<String>, // WebDAV 用户名 / S3 Access Key
+    pub history_sync_password: Option<String>, // WebDAV 密码 / S3 Secret Key
+    pub history_sync_region: Option<String>, // S3 区域，默认 us-east-1
+    pub pantry_max_bytes: Option<u64>, // 食材缓存总大小上限（字节），超出时淘汰最久未使用的食材，默认 512MB，0 表示不限制
+    pub pantry_cleanup_interval_minutes: Option<u64>, // 食材缓存后台清理间隔（分钟），0 表示只在启动时清理，默认 60
+    pub pantry_max_age_hours: Option<u64>, // 食材缓存保留时长（小时），默认 168（7 天）
+    pub pantry_encryption: Option<bool>, // 加密缓存的食材内容（密钥保存在系统钥匙串），默认关闭
+    pub pantry_warn_percent: Option<u8>, // 食材缓存用量达到上限的该百分比时提醒，默认 80
+    pub ingredient_image_max_dimension: Option<u32>, // 图片食材最长边上限（像素），超过时等比缩小，默认 0 不缩放
+    pub ingredient_image_format: Option<String>, // 图片食材转码格式: "keep"（默认）| "png" | "jpeg" | "webp"
+    pub ingredient_image_quality: Option<u8>, // JPEG 质量 1-100，默认 85
+    pub ingredient_animation: Option<String>, // 动图处理: "keep"（默认，超过大小上限时取首帧）| "first_frame" | "contact_sheet"
+    pub ingredient_animation_frames: Option<u32>, // 拼图帧数 2-16，默认 4
+    pub clipboard_backend: Option<String>, // 剪贴板读取后端: "auto"（默认）| "arboard" | "wayland" | "x11"
     pub docs_api_key: Option<String>, // Docs API密钥 (可选，免费使用时可为空)
+    pub docs_base_url: Option<String>, // Docs API 基础 URL（自建或镜像服务），为空时使用官方地址
+    pub docs_cache_ttl_secs: Option<u64>, // Docs 查询结果缓存有效期（秒），0 表示不缓存，默认 86400
+    pub docs_library_aliases: Option<HashMap<String, String>>, // 库别名（如 "react" -> "facebook/react"），查询前解析
+    pub docs_favorites: Option<Vec<String>>, // 收藏的库标识符
+    pub docs_topic_filter: Option<bool>, // 按主题关键词过滤返回的章节，默认开启
+    pub docs_topic_filter_context: Option<usize>, // 主题过滤时额外保留命中章节前后的章节数，默认 0
+    pub docs_max_chars: Option<usize>, // 单次 Docs 响应的字符数上限，超出时智能截断，0 表示不限制，默认 60000
     #[serde(default = "default_interaction_wait_ms")]
     pub interaction_wait_ms: u64, // 单次等待阈值（毫秒），0 表示无限等待
 }
 
+// acemcp 远程索引端点（多租户，如工作/个人账号分别使用不同端点）
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct AcemcpEndpoint {
+    pub name: String, // 端点名称（唯一）
+    pub base_url: String,
+    pub token: String,
+    pub proxy_enabled: Option<bool>, // 未设置时沿用全局代理配置
+    pub proxy_host: Option<String>,
+    pub proxy_port: Option<u16>,
+    pub proxy_type: Option<String>,
+    pub proxy_username: Option<String>,
+    pub proxy_password: Option<String>,
+}
+
 // 自定义prompt结构
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CustomPrompt {
@@ -159,6 +260,15 @@ pub struct CustomPromptConfig {
     pub enabled: bool,
     #[serde(default = "default_custom_prompt_max_prompts")]
     pub max_prompts: u32,
+    #[serde(default)]
+    pub usage: HashMap<String, PromptUsage>, // 使用统计（prompt id -> 统计）
+}
+
+// 自定义prompt使用统计
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct PromptUsage {
+    pub count: u64,
+    pub last_used_at: Option<String>, // RFC3339
 }
 
 // 快捷键配置
@@ -202,6 +312,40 @@ pub struct TelegramConfig {
     pub hide_frontend_popup: bool, // 是否隐藏前端弹窗，仅使用Telegram交互
     #[serde(default = "default_telegram_api_base_url")]
     pub api_base_url: String, // Telegram API基础URL
+    #[serde(default = "default_telegram_webhook_enabled")]
+    pub webhook_enabled: bool, // 通过webhook接收更新（关闭时使用长轮询）
+    #[serde(default)]
+    pub webhook_url: String, // Telegram回调的公网HTTPS地址
+    #[serde(default = "default_telegram_webhook_listen_addr")]
+    pub webhook_listen_addr: String, // 本地接收服务的监听地址
+    #[serde(default)]
+    pub webhook_secret: String, // 校验回调请求的secret token，为空时自动生成
+    #[serde(default)]
+    pub recipients: Vec<TelegramRecipient>, // 同时接收请求的其他聊天（如团队群组）
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SlackConfig {
+    #[serde(default = "default_slack_enabled")]
+    pub enabled: bool, // 是否启用Slack（启用后请求同时发送到Slack）
+    #[serde(default = "default_slack_hide_frontend_popup")]
+    pub hide_frontend_popup: bool, // 是否隐藏前端弹窗，仅使用Slack交互
+    #[serde(default)]
+    pub bot_token: String, // Bot Token（xoxb-）
+    #[serde(default)]
+    pub app_token: String, // App-Level Token（xapp-），用于Socket Mode
+    #[serde(default)]
+    pub channel_id: String, // 频道ID，或用户ID（私信）
+}
+
+// Telegram额外接收者
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TelegramRecipient {
+    pub chat_id: String, // Chat ID（群组为负数）
+    #[serde(default)]
+    pub name: String, // 显示名称
+    #[serde(default)]
+    pub allowed_user_ids: Vec<u64>, // 允许回复的用户ID，为空时聊天中所有人都可回复
 }
 
 /// 代理配置
@@ -244,14 +388,20 @@ pub struct AppState {
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            config_version: super::migration::CURRENT_CONFIG_VERSION,
             ui_config: default_ui_config(),
             audio_config: default_audio_config(),
             reply_config: default_reply_config(),
             mcp_config: default_mcp_config(),
             telegram_config: default_telegram_config(),
+            slack_config: default_slack_config(),
             custom_prompt_config: default_custom_prompt_config(),
             shortcut_config: default_shortcut_config(),
             proxy_config: default_proxy_config(),
+            active_profile: default_active_profile(),
+            profiles: HashMap::new(),
+            keychain_secrets: false,
+            projects: Vec::new(),
         }
     }
 }
@@ -274,6 +424,7 @@ pub fn default_ui_config() -> UiConfig {
         font_config: default_font_config(),
         window_config: default_window_config(),
         always_on_top: default_always_on_top(),
+        background_mode: false,
     }
 }
 
@@ -287,6 +438,7 @@ pub fn default_audio_config() -> AudioConfig {
 pub fn default_mcp_config() -> McpConfig {
     McpConfig {
         tools: default_mcp_tools(),
+        project_tools: None,
         acemcp_base_url: None,
         acemcp_token: None,
         acemcp_batch_size: None,
@@ -302,7 +454,60 @@ pub fn default_mcp_config() -> McpConfig {
         acemcp_proxy_type: None,
         acemcp_proxy_username: None,
         acemcp_proxy_password: None,
+        acemcp_backend: None, // 默认使用远程后端
+        acemcp_upload_concurrency: None, // 使用默认值 4
+        acemcp_workspace_groups: None,
+        acemcp_search_cache_ttl_secs: None, // 使用默认值 300 秒
+        acemcp_max_file_size_kb: None, // 使用默认值 1024KB
+        acemcp_skip_binary_files: None, // 默认跳过二进制文件
+        acemcp_upload_compression: None, // 默认不压缩
+        acemcp_max_upload_mbps: None, // 默认不限速
+        acemcp_max_concurrent_reads: None, // 使用默认值 4
+        acemcp_index_niceness: None, // 默认不让出 CPU
+        acemcp_reindex_schedules: None,
+        acemcp_schedule_only: None, // 默认由文件监听触发索引
+        acemcp_watch_max_dirs: None, // 使用默认值 8192
+        acemcp_watch_max_files: None, // 使用默认值 200000
+        acemcp_query_rewrite: None, // 默认不改写查询
+        acemcp_query_prefer_english: None,
+        acemcp_query_abbreviations: None,
+        acemcp_secret_scan: None, // 默认脱敏后上传
+        acemcp_health_check_interval_secs: None, // 使用默认值 300
+        acemcp_cache_encryption: None, // 默认明文保存
+        acemcp_endpoints: None,
+        acemcp_project_endpoints: None,
+        history_encryption: None, // 默认明文保存
+        history_trash_retention_days: None, // 使用默认值 30
+        history_webhook_url: None, // 默认不推送
+        history_webhook_include_images: None, // 默认不含图片
+        history_webhook_secret: None,
+        notify_webhook_url: None, // 默认不通知
+        notify_webhook_template: None, // 默认发送 JSON 负载
+        notify_webhook_secret: None,
+        history_sync_backend: None, // 默认不同步
+        history_sync_target: None,
+        history_sync_username: None,
+        history_sync_password: None,
+        history_sync_region: None,
+        pantry_max_bytes: None, // 使用默认值 512MB
+        pantry_cleanup_interval_minutes: None, // 使用默认值 60
+        pantry_max_age_hours: None, // 使用默认值 168
+        pantry_encryption: None, // 默认明文保存
+        pantry_warn_percent: None, // 使用默认值 80
+        ingredient_image_max_dimension: None, // 默认不缩放
+        ingredient_image_format: None, // 默认保持原格式
+        ingredient_image_quality: None, // 使用默认值 85
+        ingredient_animation: None, // 默认保留动图
+        ingredient_animation_frames: None, // 使用默认值 4
+        clipboard_backend: None, // 按平台自动选择
         docs_api_key: None,
+        docs_base_url: None, // 使用官方地址
+        docs_cache_ttl_secs: None, // 使用默认值 86400
+        docs_library_aliases: None,
+        docs_favorites: None,
+        docs_topic_filter: None, // 默认开启
+        docs_topic_filter_context: None,
+        docs_max_chars: None, // 使用默认值 60000
         interaction_wait_ms: default_interaction_wait_ms(),
     }
 }
@@ -318,6 +523,21 @@ pub fn default_telegram_config() -> TelegramConfig {
         chat_id: default_telegram_chat_id(),
         hide_frontend_popup: default_telegram_hide_frontend_popup(),
         api_base_url: default_telegram_api_base_url(),
+        webhook_enabled: default_telegram_webhook_enabled(),
+        webhook_url: String::new(),
+        webhook_listen_addr: default_telegram_webhook_listen_addr(),
+        webhook_secret: String::new(),
+        recipients: Vec::new(),
+    }
+}
+
+pub fn default_slack_config() -> SlackConfig {
+    SlackConfig {
+        enabled: default_slack_enabled(),
+        hide_frontend_popup: default_slack_hide_frontend_popup(),
+        bot_token: String::new(),
+        app_token: String::new(),
+        channel_id: String::new(),
     }
 }
 
@@ -326,6 +546,7 @@ pub fn default_custom_prompt_config() -> CustomPromptConfig {
         prompts: default_custom_prompts(),
         enabled: default_custom_prompt_enabled(),
         max_prompts: default_custom_prompt_max_prompts(),
+        usage: HashMap::new(),
     }
 }
 
@@ -357,6 +578,7 @@ pub fn default_window_config() -> WindowConfig {
         fixed_height: window::DEFAULT_HEIGHT,
         free_width: window::DEFAULT_WIDTH,
         free_height: window::DEFAULT_HEIGHT,
+        positions: HashMap::new(),
     }
 }
 
@@ -365,6 +587,8 @@ pub fn default_reply_config() -> ReplyConfig {
         enable_continue_reply: mcp::DEFAULT_CONTINUE_REPLY_ENABLED,
         auto_continue_threshold: mcp::DEFAULT_AUTO_CONTINUE_THRESHOLD,
         continue_prompt: mcp::DEFAULT_CONTINUE_PROMPT.to_string(),
+        ingredient_max_mb: mcp::DEFAULT_INGREDIENT_MAX_MB,
+        ingredients_total_max_mb: mcp::DEFAULT_INGREDIENTS_TOTAL_MAX_MB,
     }
 }
 
@@ -400,12 +624,21 @@ pub fn default_continue_prompt() -> String {
     mcp::DEFAULT_CONTINUE_PROMPT.to_string()
 }
 
+pub fn default_ingredient_max_mb() -> u32 {
+    mcp::DEFAULT_INGREDIENT_MAX_MB
+}
+
+pub fn default_ingredients_total_max_mb() -> u32 {
+    mcp::DEFAULT_INGREDIENTS_TOTAL_MAX_MB
+}
+
 pub fn default_mcp_tools() -> HashMap<String, bool> {
     let mut tools = HashMap::new();
     tools.insert(mcp::TOOL_CACHE.to_string(), true);
     tools.insert(mcp::TOOL_STORE.to_string(), true);
     tools.insert(mcp::TOOL_INDEX.to_string(), false);
     tools.insert(mcp::TOOL_DOCS.to_string(), true);
+    tools.insert(mcp::TOOL_HISTORY.to_string(), true);
     tools
 }
 
@@ -437,6 +670,14 @@ pub fn default_free_height() -> f64 {
     window::DEFAULT_HEIGHT
 }
 
+pub fn default_slack_enabled() -> bool {
+    slack::DEFAULT_ENABLED
+}
+
+pub fn default_slack_hide_frontend_popup() -> bool {
+    slack::DEFAULT_HIDE_FRONTEND_POPUP
+}
+
 pub fn default_telegram_enabled() -> bool {
     telegram::DEFAULT_ENABLED
 }
@@ -457,6 +698,14 @@ pub fn default_telegram_api_base_url() -> String {
     telegram::API_BASE_URL.to_string()
 }
 
+pub fn default_telegram_webhook_enabled() -> bool {
+    telegram::DEFAULT_WEBHOOK_ENABLED
+}
+
+pub fn default_telegram_webhook_listen_addr() -> String {
+    telegram::DEFAULT_WEBHOOK_LISTEN_ADDR.to_string()
+}
+
 impl WindowConfig {
     // 获取当前模式的宽度
     pub fn current_width(&self) -> f64 {
@@ -488,6 +737,37 @@ impl WindowConfig {
     }
 }
 
+impl McpConfig {
+    // 全局工具启用状态，未配置的工具视为启用
+    pub fn is_tool_enabled(&self, tool_id: &str) -> bool {
+        self.tools.get(tool_id).copied().unwrap_or(true)
+    }
+
+    // 请求涉及的所有项目都启用该工具时才可用；项目有覆盖时以覆盖为准，没有项目上下文时使用全局设置
+    pub fn is_tool_enabled_for(&self, tool_id: &str, project_roots: &[String]) -> bool {
+        if project_roots.is_empty() {
+            return self.is_tool_enabled(tool_id);
+        }
+        project_roots.iter().all(|root| {
+            self.project_tools
+                .as_ref()
+                .and_then(|projects| projects.get(root))
+                .and_then(|tools| tools.get(tool_id))
+                .copied()
+                .unwrap_or_else(|| self.is_tool_enabled(tool_id))
+        })
+    }
+
+    // 全局或任一项目启用了该工具（列出工具时还不知道请求的项目）
+    pub fn is_tool_enabled_anywhere(&self, tool_id: &str) -> bool {
+        self.is_tool_enabled(tool_id)
+            || self
+                .project_tools
+                .as_ref()
+                .is_some_and(|projects| projects.values().any(|tools| tools.get(tool_id) == Some(&true)))
+    }
+}
+
 // 字体配置默认值函数
 pub fn default_font_config() -> FontConfig {
     FontConfig {
@@ -734,6 +1014,55 @@ pub fn default_shortcuts() -> HashMap<String, ShortcutBinding> {
         scope: "popup".to_string(),
     });
 
+    // 全局快捷键（系统级热键，容易与其他应用冲突，默认不启用）
+    shortcuts.insert("global_show_window".to_string(), ShortcutBinding {
+        id: "global_show_window".to_string(),
+        name: "唤起窗口".to_string(),
+        description: "在任意应用中把窗口切换到前台".to_string(),
+        action: "show_window".to_string(),
+        key_combination: ShortcutKey {
+            key: "Space".to_string(),
+            ctrl: true,
+            alt: true,
+            shift: false,
+            meta: false,
+        },
+        enabled: false,
+        scope: "global".to_string(),
+    });
+
+    shortcuts.insert("global_paste_clipboard".to_string(), ShortcutBinding {
+        id: "global_paste_clipboard".to_string(),
+        name: "粘贴剪贴板食材".to_string(),
+        description: "唤起弹窗并添加剪贴板中的图片或文件".to_string(),
+        action: "paste_clipboard".to_string(),
+        key_combination: ShortcutKey {
+            key: "V".to_string(),
+            ctrl: true,
+            alt: true,
+            shift: false,
+            meta: false,
+        },
+        enabled: false,
+        scope: "global".to_string(),
+    });
+
+    shortcuts.insert("global_continue".to_string(), ShortcutBinding {
+        id: "global_continue".to_string(),
+        name: "全局继续".to_string(),
+        description: "在任意应用中直接发送弹窗的继续回复".to_string(),
+        action: "global_continue".to_string(),
+        key_combination: ShortcutKey {
+            key: "Enter".to_string(),
+            ctrl: true,
+            alt: true,
+            shift: false,
+            meta: false,
+        },
+        enabled: false,
+        scope: "global".to_string(),
+    });
+
     shortcuts
 }
 
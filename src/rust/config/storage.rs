@@ -1,9 +1,15 @@
 use anyhow::Result;
 use std::fs;
 use std::path::PathBuf;
-use tauri::{AppHandle, LogicalSize, Manager, State};
+use tauri::{AppHandle, Emitter, LogicalSize, Manager, State};
 
+use super::backup::backup_config_file;
+use super::env_overrides::apply_env_overrides;
+use super::migration::migrate_config;
+use super::profiles::apply_profile_override;
+use super::secrets::{resolve_secrets, store_secrets};
 use super::settings::{AppConfig, AppState, default_shortcuts};
+use super::concurrent::{last_config_content, merge_concurrent, remember_config_content, write_atomic, ConfigFileLock};
 
 pub fn get_config_path(_app: &AppHandle) -> Result<PathBuf> {
     // 使用与独立配置相同的路径，确保一致性
@@ -18,18 +24,54 @@ pub async fn save_config(state: &State<'_, AppState>, app: &AppHandle) -> Result
         fs::create_dir_all(parent)?;
     }
 
-    let config = state
-        .config
-        .lock()
-        .map_err(|e| anyhow::anyhow!("获取配置失败: {}", e))?;
-    let config_json = serde_json::to_string_pretty(&*config)?;
+    let mut value = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| anyhow::anyhow!("获取配置失败: {}", e))?;
+        let mut value = serde_json::to_value(&*config)?;
+        if config.keychain_secrets {
+            store_secrets(&mut value);
+        }
+        value
+    };
 
-    // 写入文件
-    fs::write(&config_path, config_json)?;
+    // 持锁期间完成读取、合并与写入，避免与其他进程交错；等待锁时会休眠重试，放到阻塞线程中避免占用异步工作线程
+    let _lock = {
+        let config_path = config_path.clone();
+        tokio::task::spawn_blocking(move || ConfigFileLock::acquire(&config_path))
+            .await
+            .map_err(|e| anyhow::anyhow!("获取配置文件锁失败: {}", e))??
+    };
 
-    // 强制刷新文件系统缓存
-    if let Ok(file) = std::fs::OpenOptions::new().write(true).open(&config_path) {
-        let _ = file.sync_all();
+    // 其他进程在本进程上次读写之后修改过配置文件时，三方合并双方的修改
+    let mut merged_external = false;
+    if let (Ok(disk), Some(base)) = (fs::read_to_string(&config_path), last_config_content()) {
+        if disk != base {
+            if let (Ok(theirs), Ok(base)) = (serde_json::from_str(&disk), serde_json::from_str(&base)) {
+                let (merged, conflicts) = merge_concurrent(&base, theirs, std::mem::take(&mut value));
+                if !conflicts.is_empty() {
+                    log::warn!("配置文件同时被其他进程修改，以下字段以本次保存为准: {}", conflicts.join(", "));
+                }
+                value = merged;
+                merged_external = true;
+            }
+        }
+    }
+    let config_json = serde_json::to_string_pretty(&value)?;
+
+    // 写入文件（记录内容，避免配置监听把自己的写入当作外部变更）
+    write_atomic(&config_path, &config_json)?;
+    remember_config_content(&config_json);
+
+    // 合并进来的其他进程的修改同步到内存和前端
+    if merged_external {
+        let (config, _) = parse_config(&config_json)?;
+        *state
+            .config
+            .lock()
+            .map_err(|e| anyhow::anyhow!("获取配置失败: {}", e))? = config;
+        let _ = app.emit("config_reloaded", ());
     }
 
     log::debug!("配置已保存到: {:?}", config_path);
@@ -43,16 +85,24 @@ pub async fn load_config(state: &State<'_, AppState>, app: &AppHandle) -> Result
 
     if config_path.exists() {
         let config_json = fs::read_to_string(&config_path)?;
-        let mut config: AppConfig = serde_json::from_str(&config_json)?;
-
-        // 合并默认快捷键配置，确保新的默认快捷键被添加
-        merge_default_shortcuts(&mut config);
+        let (config, migrated) = parse_config(&config_json)?;
+        remember_config_content(&config_json);
+
+        {
+            let mut config_guard = state
+                .config
+                .lock()
+                .map_err(|e| anyhow::anyhow!("获取配置锁失败: {}", e))?;
+            *config_guard = config;
+        }
 
-        let mut config_guard = state
-            .config
-            .lock()
-            .map_err(|e| anyhow::anyhow!("获取配置锁失败: {}", e))?;
-        *config_guard = config;
+        // 升级后的配置写回文件，写入前保留旧版本的备份
+        if migrated {
+            if let Err(e) = backup_config_file() {
+                log::warn!("备份升级前的配置失败: {}", e);
+            }
+            save_config(state, app).await?;
+        }
     }
 
     Ok(())
@@ -114,6 +164,9 @@ pub async fn load_config_and_apply_window_settings(
         if let Err(_e) = window.set_size(LogicalSize::new(target_width, target_height)) {
             // 静默处理窗口大小设置失败
         }
+
+        // 恢复当前显示器布局下记录的窗口位置
+        crate::ui::window::restore_window_position(&window, &window_config);
     }
 
     Ok(())
@@ -124,19 +177,32 @@ pub fn load_standalone_config() -> Result<AppConfig> {
     let config_path = get_standalone_config_path()?;
 
     if config_path.exists() {
-        let config_json = fs::read_to_string(config_path)?;
-        let mut config: AppConfig = serde_json::from_str(&config_json)?;
-
-        // 合并默认快捷键配置
-        merge_default_shortcuts(&mut config);
-
+        // 独立进程只在内存中升级，由主程序负责写回文件
+        let (mut config, _) = parse_config(&fs::read_to_string(&config_path)?)?;
+        apply_profile_override(&mut config);
+        apply_env_overrides(&mut config.mcp_config);
         Ok(config)
     } else {
-        // 如果配置文件不存在，返回默认配置
-        Ok(AppConfig::default())
+        // 如果配置文件不存在，返回默认配置（无界面部署可完全由环境变量配置）
+        let mut config = AppConfig::default();
+        apply_env_overrides(&mut config.mcp_config);
+        Ok(config)
     }
 }
 
+/// 解析配置文件内容并升级到当前版本，返回配置与是否发生了迁移
+fn parse_config(config_json: &str) -> Result<(AppConfig, bool)> {
+    let mut value: serde_json::Value = serde_json::from_str(config_json)?;
+    let migrated = migrate_config(&mut value);
+    resolve_secrets(&mut value);
+    let mut config: AppConfig = serde_json::from_value(value)?;
+
+    // 合并默认快捷键配置，确保新的默认快捷键被添加
+    merge_default_shortcuts(&mut config);
+
+    Ok((config, migrated))
+}
+
 /// 独立加载Telegram配置（用于MCP模式下的配置检查）
 pub fn load_standalone_telegram_config() -> Result<super::settings::TelegramConfig> {
     let config = load_standalone_config()?;
@@ -144,7 +210,7 @@ pub fn load_standalone_telegram_config() -> Result<super::settings::TelegramConf
 }
 
 /// 获取独立配置文件路径（不依赖Tauri）
-fn get_standalone_config_path() -> Result<PathBuf> {
+pub(crate) fn get_standalone_config_path() -> Result<PathBuf> {
     // 使用标准的配置目录
     let config_dir = dirs::config_dir()
         .ok_or_else(|| anyhow::anyhow!("无法获取配置目录"))?
@@ -160,24 +226,9 @@ fn get_standalone_config_path() -> Result<PathBuf> {
 fn merge_default_shortcuts(config: &mut AppConfig) {
     let default_shortcuts = default_shortcuts();
 
-    // 遍历所有默认快捷键
+    // 遍历所有默认快捷键（旧默认值的更新见配置迁移）
     for (key, default_binding) in default_shortcuts {
-        if !config.shortcut_config.shortcuts.contains_key(&key) {
-            // 如果用户配置中不存在，则添加
-            config.shortcut_config.shortcuts.insert(key, default_binding);
-        } else if key == "enhance" {
-            // 特殊处理：更新增强快捷键的默认值从 Shift+Enter 到 Ctrl+Shift+Enter
-            let existing_binding = config.shortcut_config.shortcuts.get(&key).unwrap();
-
-            // 检查是否是旧的默认值 (Shift+Enter)
-            if existing_binding.key_combination.key == "Enter"
-                && !existing_binding.key_combination.ctrl
-                && existing_binding.key_combination.shift
-                && !existing_binding.key_combination.alt
-                && !existing_binding.key_combination.meta {
-                // 更新为新的默认值 (Ctrl+Shift+Enter)
-                config.shortcut_config.shortcuts.insert(key, default_binding);
-            }
-        }
+        // 如果用户配置中不存在，则添加
+        config.shortcut_config.shortcuts.entry(key).or_insert(default_binding);
     }
 }
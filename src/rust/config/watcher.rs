@@ -0,0 +1,87 @@
+// 配置文件监听
+// 外部编辑 config.json 或其他进程保存配置后，主程序自动重新加载到内存并推送 config_reloaded 事件。
+// 监听配置目录而不是文件本身，编辑器以“写临时文件再改名”的方式保存时也能收到事件；
+// 内容与本进程最近一次读写的一致时不重新加载，避免自己保存配置触发循环。
+// MCP 独立进程每次使用时都从文件读取配置，不需要监听
+
+use anyhow::{anyhow, Result};
+use notify_debouncer_full::{
+    new_debouncer,
+    notify::{RecommendedWatcher, RecursiveMode, Watcher},
+    DebounceEventResult, Debouncer, FileIdMap,
+};
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::concurrent::last_config_content;
+use super::settings::AppState;
+use super::storage::{get_config_path, load_config};
+
+/// 配置变更后等待的时间，合并编辑器保存时的多次写入
+const CONFIG_WATCH_DEBOUNCE_MS: u64 = 500;
+
+/// 保持监听器存活
+static CONFIG_WATCHER: Lazy<Mutex<Option<Debouncer<RecommendedWatcher, FileIdMap>>>> = Lazy::new(|| Mutex::new(None));
+
+fn reload_if_changed(app: &AppHandle, config_path: &Path) {
+    let Ok(content) = fs::read_to_string(config_path) else {
+        return;
+    };
+    // 与本进程最近一次读写的内容相同，说明是自己的写入
+    if last_config_content().as_deref() == Some(content.as_str()) {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AppState>();
+        match load_config(&state, &app).await {
+            Ok(()) => {
+                log::info!("检测到配置文件变更，已重新加载");
+                crate::ui::global_shortcut::register_global_shortcuts(&app);
+                if let Err(e) = app.emit("config_reloaded", ()) {
+                    log::warn!("推送配置重新加载事件失败: {}", e);
+                }
+            }
+            // 编辑器保存过程中可能读到不完整的内容，等待下一次变更
+            Err(e) => log::warn!("配置文件已变更，但重新加载失败: {}", e),
+        }
+    });
+}
+
+/// 启动配置文件监听（GUI 启动时调用一次）
+pub fn start_config_watcher(app: AppHandle) -> Result<()> {
+    let config_path = get_config_path(&app)?;
+    let config_dir = config_path
+        .parent()
+        .ok_or_else(|| anyhow!("无法获取配置目录"))?
+        .to_path_buf();
+    let file_name = config_path.file_name().map(|n| n.to_os_string());
+
+    let watched_path = config_path.clone();
+    let mut debouncer = new_debouncer(
+        Duration::from_millis(CONFIG_WATCH_DEBOUNCE_MS),
+        None,
+        move |result: DebounceEventResult| match result {
+            Ok(events) => {
+                let touched = events
+                    .iter()
+                    .flat_map(|event| event.paths.iter())
+                    .any(|path| path.file_name().map(|n| n.to_os_string()) == file_name);
+                if touched {
+                    reload_if_changed(&app, &watched_path);
+                }
+            }
+            Err(errors) => log::debug!("配置文件监听错误: {:?}", errors),
+        },
+    )?;
+    debouncer.watcher().watch(&config_dir, RecursiveMode::NonRecursive)?;
+
+    *CONFIG_WATCHER.lock().unwrap_or_else(|e| e.into_inner()) = Some(debouncer);
+    log::info!("已开始监听配置文件: {:?}", config_path);
+    Ok(())
+}
@@ -12,8 +12,11 @@ pub const TOOL_INDEX: &str = "index";
 /// Docs tool identifier
 pub const TOOL_DOCS: &str = "docs";
 
+/// History recall tool identifier
+pub const TOOL_HISTORY: &str = "history_recall";
+
 /// 默认启用的工具列表
-pub const DEFAULT_ENABLED_TOOLS: &[&str] = &[TOOL_CACHE, TOOL_STORE, TOOL_INDEX, TOOL_DOCS];
+pub const DEFAULT_ENABLED_TOOLS: &[&str] = &[TOOL_CACHE, TOOL_STORE, TOOL_INDEX, TOOL_DOCS, TOOL_HISTORY];
 
 /// 继续回复默认启用状态
 pub const DEFAULT_CONTINUE_REPLY_ENABLED: bool = true;
@@ -24,6 +27,19 @@ pub const DEFAULT_AUTO_CONTINUE_THRESHOLD: u32 = 1000;
 /// 默认继续提示词
 pub const DEFAULT_CONTINUE_PROMPT: &str = "请按照最佳实践继续";
 
+/// 单个食材默认大小上限 (MB)
+pub const DEFAULT_INGREDIENT_MAX_MB: u32 = 8;
+
+/// 单次提交的食材总大小默认上限 (MB)
+pub const DEFAULT_INGREDIENTS_TOTAL_MAX_MB: u32 = 16;
+
+/// 单个食材大小上限的可选范围 (MB)
+pub const MIN_INGREDIENT_MAX_MB: u32 = 1;
+pub const MAX_INGREDIENT_MAX_MB: u32 = 64;
+
+/// 食材总大小上限的最大值 (MB)
+pub const MAX_INGREDIENTS_TOTAL_MAX_MB: u32 = 256;
+
 /// MCP 请求超时时间 (ms)
 pub const REQUEST_TIMEOUT_MS: u64 = 30000;
 
@@ -66,7 +82,8 @@ impl Default for McpConfig {
                 McpToolConfig::new(TOOL_CACHE, true, false),
                 McpToolConfig::new(TOOL_STORE, true, true),
                 McpToolConfig::new(TOOL_INDEX, false, true),
-                McpToolConfig::new(TOOL_DOCS, true, true)
+                McpToolConfig::new(TOOL_DOCS, true, true),
+                McpToolConfig::new(TOOL_HISTORY, true, true)
             ],
             continue_reply_enabled: DEFAULT_CONTINUE_REPLY_ENABLED,
             auto_continue_threshold: DEFAULT_AUTO_CONTINUE_THRESHOLD,
@@ -128,5 +145,5 @@ pub fn get_default_mcp_config() -> McpConfig {
 
 /// 检查是否为有效的工具 ID
 pub fn is_valid_tool_id(tool_id: &str) -> bool {
-    matches!(tool_id, TOOL_CACHE | TOOL_STORE | TOOL_INDEX | TOOL_DOCS)
+    matches!(tool_id, TOOL_CACHE | TOOL_STORE | TOOL_INDEX | TOOL_DOCS | TOOL_HISTORY)
 }
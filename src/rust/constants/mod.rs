@@ -6,6 +6,7 @@ pub mod audio;
 pub mod font;
 pub mod mcp;
 pub mod network;
+pub mod slack;
 pub mod telegram;
 pub mod theme;
 pub mod ui;
@@ -24,4 +25,5 @@ pub use window::*;
 // 这些模块有重复的常量名，使用模块限定访问
 // pub use mcp::*;
 // pub use network::*;
+// pub use slack::*;
 // pub use telegram::*;
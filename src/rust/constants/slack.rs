@@ -0,0 +1,34 @@
+// Slack 相关常量
+
+/// 默认 Slack 启用状态
+pub const DEFAULT_ENABLED: bool = false;
+
+/// Slack Web API 基础 URL
+pub const API_BASE_URL: &str = "https://slack.com/api";
+
+/// Bot Token 前缀（用于发送和更新消息）
+pub const BOT_TOKEN_PREFIX: &str = "xoxb-";
+
+/// App-Level Token 前缀（用于 Socket Mode 连接，需要 connections:write 权限）
+pub const APP_TOKEN_PREFIX: &str = "xapp-";
+
+/// section 块文本的最大长度
+pub const MAX_SECTION_TEXT_LENGTH: usize = 3000;
+
+/// 按钮文本的最大长度
+pub const MAX_BUTTON_TEXT_LENGTH: usize = 75;
+
+/// 每行选项按钮数量
+pub const OPTION_BUTTONS_PER_ROW: usize = 5;
+
+/// 请求超时时间 (秒)
+pub const REQUEST_TIMEOUT_SECS: u64 = 30;
+
+/// Socket Mode 断开后的重连间隔 (秒)
+pub const RECONNECT_DELAY_SECS: u64 = 5;
+
+/// 默认不隐藏前端弹窗（Slack 作为额外的回复渠道）
+pub const DEFAULT_HIDE_FRONTEND_POPUP: bool = false;
+
+/// Socket Mode 转发服务端口（仅监听 127.0.0.1，占用该端口的进程持有 Socket Mode 连接）
+pub const RELAY_PORT: u16 = 47391;
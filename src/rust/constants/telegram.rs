@@ -15,6 +15,18 @@ pub const DEFAULT_HIDE_FRONTEND_POPUP: bool = false;
 /// Telegram API 基础 URL
 pub const API_BASE_URL: &str = "https://api.telegram.org/bot";
 
+/// 默认使用长轮询接收更新（不启用 webhook）
+pub const DEFAULT_WEBHOOK_ENABLED: bool = false;
+
+/// webhook 接收服务默认监听地址（通常由反向代理转发 Telegram 的 HTTPS 请求）
+pub const DEFAULT_WEBHOOK_LISTEN_ADDR: &str = "127.0.0.1:8443";
+
+/// webhook 接收服务的路径
+pub const WEBHOOK_PATH: &str = "/telegram/webhook";
+
+/// Telegram 在 webhook 请求中携带 secret token 的请求头
+pub const WEBHOOK_SECRET_HEADER: &str = "X-Telegram-Bot-Api-Secret-Token";
+
 /// 消息最大长度
 pub const MAX_MESSAGE_LENGTH: usize = 4096;
 
@@ -9,8 +9,11 @@ pub const LIGHT: &str = "light";
 /// 深色主题
 pub const DARK: &str = "dark";
 
+/// 跟随系统主题
+pub const AUTO: &str = "auto";
+
 /// 可用主题列表
-pub const AVAILABLE_THEMES: &[&str] = &[LIGHT, DARK];
+pub const AVAILABLE_THEMES: &[&str] = &[LIGHT, DARK, AUTO];
 
 // 主题配置结构体
 #[derive(Debug, Clone)]
@@ -63,3 +66,26 @@ pub fn is_valid_theme(theme: &str) -> bool {
 pub fn get_default_theme() -> &'static str {
     DEFAULT
 }
+
+/// 解析实际使用的主题：auto 时采用系统主题，无效值回退到默认主题
+pub fn resolve_theme(preference: &str, system: &str) -> &'static str {
+    let theme = if preference == AUTO { system } else { preference };
+    match theme {
+        LIGHT => LIGHT,
+        DARK => DARK,
+        _ => DEFAULT,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_theme() {
+        assert_eq!(resolve_theme(LIGHT, DARK), LIGHT);
+        assert_eq!(resolve_theme(AUTO, LIGHT), LIGHT);
+        assert_eq!(resolve_theme(AUTO, DARK), DARK);
+        assert_eq!(resolve_theme("sepia", LIGHT), DEFAULT);
+    }
+}
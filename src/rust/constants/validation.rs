@@ -5,6 +5,10 @@ use super::audio;
 use super::theme;
 use super::telegram;
 use super::network;
+use super::mcp;
+use super::font;
+use serde::Serialize;
+use std::fmt::Display;
 
 // 通用验证函数
 
@@ -112,6 +116,114 @@ impl Default for ValidationResult {
     }
 }
 
+/// 单个字段的校验问题，供前端在对应输入项旁提示
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldIssue {
+    /// 字段名（与配置中的字段名一致）
+    pub field: String,
+    /// 被拒绝的原始值
+    pub rejected: serde_json::Value,
+    pub reason: String,
+    /// 修正后实际保存的值，为空表示该字段未保存
+    pub clamped: Option<serde_json::Value>,
+}
+
+/// 修改配置的命令返回的校验报告，所有值都有效时 issues 为空
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationReport {
+    pub issues: Vec<FieldIssue>,
+}
+
+fn to_json_value(value: impl Serialize) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+impl ValidationReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// 记录被拒绝且未保存的值
+    pub fn reject(&mut self, field: &str, rejected: impl Serialize, reason: impl Into<String>) {
+        self.issues.push(FieldIssue {
+            field: field.to_string(),
+            rejected: to_json_value(rejected),
+            reason: reason.into(),
+            clamped: None,
+        });
+    }
+
+    /// 记录修正后保存的值
+    pub fn clamp(&mut self, field: &str, rejected: impl Serialize, clamped: impl Serialize, reason: impl Into<String>) {
+        self.issues.push(FieldIssue {
+            field: field.to_string(),
+            rejected: to_json_value(rejected),
+            reason: reason.into(),
+            clamped: Some(to_json_value(clamped)),
+        });
+    }
+
+    /// 把数值限制在 [min, max] 内，超出范围时记录修正
+    pub fn clamp_range<T>(&mut self, field: &str, value: T, min: T, max: T) -> T
+    where
+        T: PartialOrd + Copy + Display + Serialize,
+    {
+        if value < min {
+            self.clamp(field, value, min, format!("不能小于 {}", min));
+            min
+        } else if value > max {
+            self.clamp(field, value, max, format!("不能大于 {}", max));
+            max
+        } else {
+            value
+        }
+    }
+}
+
+/// 把窗口尺寸限制在允许范围内
+pub fn check_window_size(
+    report: &mut ValidationReport,
+    fields: (&str, &str),
+    width: f64,
+    height: f64,
+) -> (f64, f64) {
+    (
+        report.clamp_range(fields.0, width, window::MIN_WIDTH, window::MAX_WIDTH),
+        report.clamp_range(fields.1, height, window::MIN_HEIGHT, window::MAX_HEIGHT),
+    )
+}
+
+/// 把食材大小上限限制在允许范围内，总上限不小于单个上限
+pub fn check_ingredient_limits(report: &mut ValidationReport, max_mb: u32, total_max_mb: u32) -> (u32, u32) {
+    let max_mb = report.clamp_range(
+        "ingredient_max_mb",
+        max_mb,
+        mcp::MIN_INGREDIENT_MAX_MB,
+        mcp::MAX_INGREDIENT_MAX_MB,
+    );
+    let total_max_mb = report.clamp_range(
+        "ingredients_total_max_mb",
+        total_max_mb,
+        max_mb,
+        mcp::MAX_INGREDIENTS_TOTAL_MAX_MB,
+    );
+    (max_mb, total_max_mb)
+}
+
+/// 验证字体系列是否为支持的选项
+pub fn is_valid_font_family(font_family: &str) -> bool {
+    font::FONT_FAMILIES.iter().any(|(id, _, _)| *id == font_family)
+}
+
+/// 验证字体大小是否为支持的选项
+pub fn is_valid_font_size(font_size: &str) -> bool {
+    font::FONT_SIZES.iter().any(|(id, _, _)| *id == font_size)
+}
+
 // 综合验证函数
 
 /// 验证窗口配置
@@ -151,6 +263,30 @@ pub fn validate_audio_config(url: &str, volume: f32, enabled: bool) -> Validatio
     result
 }
 
+/// 验证食材大小上限：单个上限在允许范围内，总上限不小于单个上限
+pub fn validate_ingredient_limits(max_mb: u32, total_max_mb: u32) -> ValidationResult {
+    let mut result = ValidationResult::new();
+
+    if !is_in_range(max_mb, mcp::MIN_INGREDIENT_MAX_MB, mcp::MAX_INGREDIENT_MAX_MB) {
+        result.add_error(format!(
+            "单个食材大小上限需在 {}-{} MB 之间: {}",
+            mcp::MIN_INGREDIENT_MAX_MB,
+            mcp::MAX_INGREDIENT_MAX_MB,
+            max_mb
+        ));
+    }
+
+    if !is_in_range(total_max_mb, max_mb, mcp::MAX_INGREDIENTS_TOTAL_MAX_MB) {
+        result.add_error(format!(
+            "食材总大小上限需在单个上限与 {} MB 之间: {}",
+            mcp::MAX_INGREDIENTS_TOTAL_MAX_MB,
+            total_max_mb
+        ));
+    }
+
+    result
+}
+
 /// 验证网络配置
 pub fn validate_network_config(
     timeout_ms: u64,
@@ -173,3 +309,23 @@ pub fn validate_network_config(
 
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validation_report() {
+        let mut report = ValidationReport::new();
+        let (width, height) = check_window_size(&mut report, ("free_width", "free_height"), 300.0, 900.0);
+        assert_eq!((width, height), (window::MIN_WIDTH, 900.0));
+
+        let (max_mb, total_max_mb) = check_ingredient_limits(&mut report, 100, 16);
+        assert_eq!((max_mb, total_max_mb), (mcp::MAX_INGREDIENT_MAX_MB, mcp::MAX_INGREDIENT_MAX_MB));
+
+        let fields: Vec<&str> = report.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, vec!["free_width", "ingredient_max_mb", "ingredients_total_max_mb"]);
+        assert_eq!(report.issues[0].rejected, serde_json::json!(300.0));
+        assert_eq!(report.issues[0].clamped, Some(serde_json::json!(window::MIN_WIDTH)));
+    }
+}
@@ -6,9 +6,22 @@ use crate::config::{AppState, save_config};
 use crate::constants::mcp;
 use crate::mcp::{
     delete_history_entries_by_time_range, delete_history_entry, export_history_entry_zip,
-    export_history_by_time_range_zip, get_history_entry, history_base_dir, list_history_entries,
-    HistoryEntryDetail, HistoryEntrySummary,
+    export_history_by_time_range_zip, get_history_entry, history_base_dir, list_history_entries_filtered,
+    list_history_projects, list_history_sessions, list_history_tags, search_history_entries,
+    set_history_entry_labels,
+    HistoryEntryDetail, HistoryEntrySummary, HistoryListFilter, HistoryProjectCount, HistorySearchPage,
+    HistorySessionGroup, HistoryTagCount,
 };
+use crate::mcp::history_crypto;
+use crate::mcp::history_edit::{redact_history_entry, update_history_markdown};
+use crate::mcp::history_export::{export_history_report, HistoryExportFormat};
+use crate::mcp::history_import::{import_history_archive, HistoryImportResult};
+use crate::mcp::history_repair::{repair_history, HistoryRepairReport};
+use crate::mcp::history_stats::{history_stats, HistoryStats};
+use crate::mcp::history_trash::{empty_trash, list_trash, restore_from_trash, HistoryTrashItem};
+use crate::mcp::history_webhook::HistoryWebhookConfig;
+use crate::mcp::history_sync::{pull_history, push_history, HistorySyncConfig, HistorySyncResult};
+use crate::mcp::notify_webhook::{send_test_notification, NotifyWebhookConfig, TEMPLATE_VARIABLES};
 // use crate::mcp::tools::acemcp; // 已迁移到独立模块
 
 /// MCP工具配置
@@ -85,6 +98,19 @@ pub async fn get_mcp_tools_config(state: State<'_, AppState>) -> Result<Vec<MCPT
         has_config: true, // Docs 工具有配置选项
     });
 
+    // History recall tool
+    tools.push(MCPToolConfig {
+        id: mcp::TOOL_HISTORY.to_string(),
+        name: "History Recall".to_string(),
+        description: "Recall past interactions for the current project".to_string(),
+        enabled: config.mcp_config.tools.get(mcp::TOOL_HISTORY).copied().unwrap_or(true),
+        can_disable: true,
+        icon: "i-carbon-recently-viewed text-lg text-teal-600 dark:text-teal-400".to_string(),
+        icon_bg: "bg-teal-100 dark:bg-teal-900".to_string(),
+        dark_icon_bg: "dark:bg-teal-800".to_string(),
+        has_config: false,
+    });
+
     // 按启用状态排序，启用的在前
     tools.sort_by(|a, b| b.enabled.cmp(&a.enabled));
     
@@ -121,6 +147,55 @@ pub async fn set_mcp_tool_enabled(
     Ok(())
 }
 
+/// 获取按项目覆盖的工具启用状态（项目路径 -> 工具 id -> 是否启用）
+#[tauri::command]
+pub async fn get_mcp_project_tools(state: State<'_, AppState>) -> Result<HashMap<String, HashMap<String, bool>>, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.mcp_config.project_tools.clone().unwrap_or_default())
+}
+
+/// 为项目覆盖工具启用状态，enabled 为空时取消覆盖、沿用全局设置
+#[tauri::command]
+pub async fn set_mcp_project_tool_enabled(
+    project_root_path: String,
+    tool_id: String,
+    enabled: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if tool_id == mcp::TOOL_CACHE && enabled == Some(false) {
+        return Err("Cache tool is required and cannot be disabled".to_string());
+    }
+    let normalized_root = crate::mcp::tools::acemcp::mcp::normalize_project_root(project_root_path.trim());
+
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        if !config.mcp_config.tools.contains_key(&tool_id) {
+            return Err(format!("未知的 MCP 工具: {}", tool_id));
+        }
+        let projects = config.mcp_config.project_tools.get_or_insert_with(Default::default);
+        match enabled {
+            Some(enabled) => {
+                projects.entry(normalized_root.clone()).or_default().insert(tool_id.clone(), enabled);
+            }
+            None => {
+                if let Some(tools) = projects.get_mut(&normalized_root) {
+                    tools.remove(&tool_id);
+                    if tools.is_empty() {
+                        projects.remove(&normalized_root);
+                    }
+                }
+            }
+        }
+    }
+
+    save_config(&state, &app).await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    log::info!("项目 {} 的 MCP 工具 {} 状态已更新为: {:?}", normalized_root, tool_id, enabled);
+    Ok(())
+}
+
 /// 获取所有MCP工具状态
 #[tauri::command]
 pub async fn get_mcp_tools_status(state: State<'_, AppState>) -> Result<HashMap<String, bool>, String> {
@@ -179,9 +254,229 @@ pub async fn set_interaction_wait_ms(
 }
 
 #[tauri::command]
-pub async fn list_bistro_journal_entries(limit: Option<u32>) -> Result<Vec<HistoryEntrySummary>, String> {
+pub async fn list_bistro_journal_entries(
+    limit: Option<u32>,
+    tag: Option<String>,
+    starred: Option<bool>,
+    session_id: Option<String>,
+    project: Option<String>,
+) -> Result<Vec<HistoryEntrySummary>, String> {
     let limit = limit.unwrap_or(200).min(2000) as usize;
-    list_history_entries(limit).map_err(|e| format!("获取历史记录失败: {}", e))
+    let filter = HistoryListFilter {
+        tag,
+        starred_only: starred.unwrap_or(false),
+        session_id,
+        project,
+    };
+    list_history_entries_filtered(limit, &filter).map_err(|e| format!("获取历史记录失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_bistro_journal_sessions(
+    limit: Option<u32>,
+    project: Option<String>,
+) -> Result<Vec<HistorySessionGroup>, String> {
+    let limit = limit.unwrap_or(100).min(1000) as usize;
+    let filter = HistoryListFilter {
+        project,
+        ..Default::default()
+    };
+    list_history_sessions(limit, &filter).map_err(|e| format!("获取会话列表失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_bistro_journal_projects() -> Result<Vec<HistoryProjectCount>, String> {
+    list_history_projects().map_err(|e| format!("获取项目列表失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_bistro_journal_encryption(state: State<'_, AppState>) -> Result<bool, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.mcp_config.history_encryption.unwrap_or(false))
+}
+
+/// 切换历史加密：先迁移已有记录，成功后再写入配置，返回迁移的文件数
+#[tauri::command]
+pub async fn set_bistro_journal_encryption(
+    enabled: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<usize, String> {
+    let migrated = tokio::task::spawn_blocking(move || history_crypto::set_enabled(enabled))
+        .await
+        .map_err(|e| format!("迁移历史记录失败: {}", e))?
+        .map_err(|e| format!("迁移历史记录失败: {}", e))?;
+
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.mcp_config.history_encryption = Some(enabled);
+    }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    Ok(migrated)
+}
+
+#[tauri::command]
+pub async fn get_bistro_journal_webhook_config(state: State<'_, AppState>) -> Result<HistoryWebhookConfig, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(HistoryWebhookConfig::from_mcp_config(&config.mcp_config))
+}
+
+#[tauri::command]
+pub async fn save_bistro_journal_webhook_config(
+    webhook_config: HistoryWebhookConfig,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        webhook_config.apply_to(&mut config.mcp_config);
+    }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_notify_webhook_config(state: State<'_, AppState>) -> Result<NotifyWebhookConfig, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(NotifyWebhookConfig::from_mcp_config(&config.mcp_config))
+}
+
+#[tauri::command]
+pub async fn save_notify_webhook_config(
+    webhook_config: NotifyWebhookConfig,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if let Some(url) = webhook_config.url.as_deref().map(str::trim).filter(|u| !u.is_empty()) {
+        let parsed = reqwest::Url::parse(url).map_err(|e| format!("通知地址无效: {}", e))?;
+        if !matches!(parsed.scheme(), "http" | "https") {
+            return Err("通知地址必须使用 HTTP 或 HTTPS".to_string());
+        }
+    }
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        webhook_config.apply_to(&mut config.mcp_config);
+    }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))
+}
+
+/// 按当前填写的配置（未保存也可）发送一次测试通知
+#[tauri::command]
+pub async fn test_notify_webhook(webhook_config: NotifyWebhookConfig) -> Result<(), String> {
+    send_test_notification(&webhook_config)
+        .await
+        .map_err(|e| format!("发送测试通知失败: {}", e))
+}
+
+/// 通知模板可用的变量
+#[tauri::command]
+pub async fn get_notify_webhook_variables() -> Result<Vec<String>, String> {
+    Ok(TEMPLATE_VARIABLES.iter().map(|v| v.to_string()).collect())
+}
+
+#[tauri::command]
+pub async fn get_bistro_journal_sync_config(state: State<'_, AppState>) -> Result<HistorySyncConfig, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(HistorySyncConfig::from_mcp_config(&config.mcp_config))
+}
+
+#[tauri::command]
+pub async fn save_bistro_journal_sync_config(
+    sync_config: HistorySyncConfig,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        sync_config.apply_to(&mut config.mcp_config);
+    }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))
+}
+
+/// 推送本地新增的历史记录到同步后端
+#[tauri::command]
+pub async fn push_bistro_journal_sync(state: State<'_, AppState>) -> Result<HistorySyncResult, String> {
+    let sync_config = {
+        let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        HistorySyncConfig::from_mcp_config(&config.mcp_config)
+    };
+    push_history(&sync_config).await.map_err(|e| format!("推送历史记录失败: {}", e))
+}
+
+/// 从同步后端拉取其他机器新增的历史记录
+#[tauri::command]
+pub async fn pull_bistro_journal_sync(state: State<'_, AppState>) -> Result<HistorySyncResult, String> {
+    let sync_config = {
+        let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        HistorySyncConfig::from_mcp_config(&config.mcp_config)
+    };
+    pull_history(&sync_config).await.map_err(|e| format!("拉取历史记录失败: {}", e))
+}
+
+/// 检查历史目录，恢复中断的写入并隔离损坏的记录
+#[tauri::command]
+pub async fn repair_bistro_journal() -> Result<HistoryRepairReport, String> {
+    tokio::task::spawn_blocking(repair_history)
+        .await
+        .map_err(|e| format!("修复历史记录失败: {}", e))?
+        .map_err(|e| format!("修复历史记录失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_bistro_journal_stats(
+    start: Option<String>,
+    end: Option<String>,
+) -> Result<HistoryStats, String> {
+    history_stats(start, end).map_err(|e| format!("统计历史记录失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn update_bistro_journal_entry_markdown(id: String, markdown: String) -> Result<HistoryEntrySummary, String> {
+    update_history_markdown(&id, &markdown).map_err(|e| format!("保存历史记录失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn redact_bistro_journal_entry(
+    id: String,
+    spans: Option<Vec<String>>,
+    remove_images: Option<Vec<String>>,
+) -> Result<HistoryEntrySummary, String> {
+    redact_history_entry(&id, &spans.unwrap_or_default(), &remove_images.unwrap_or_default())
+        .map_err(|e| format!("脱敏失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn set_bistro_journal_entry_labels(
+    id: String,
+    tags: Option<Vec<String>>,
+    starred: Option<bool>,
+) -> Result<HistoryEntrySummary, String> {
+    set_history_entry_labels(id, tags, starred).map_err(|e| format!("更新标签失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn list_bistro_journal_tags() -> Result<Vec<HistoryTagCount>, String> {
+    list_history_tags().map_err(|e| format!("获取标签失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn search_bistro_journal_entries(
+    query: String,
+    project: Option<String>,
+    offset: Option<u32>,
+    limit: Option<u32>,
+) -> Result<HistorySearchPage, String> {
+    let offset = offset.unwrap_or(0) as usize;
+    let limit = limit.unwrap_or(50).min(500) as usize;
+    search_history_entries(&query, project.as_deref(), offset, limit).map_err(|e| format!("搜索历史记录失败: {}", e))
 }
 
 #[tauri::command]
@@ -194,6 +489,51 @@ pub async fn delete_bistro_journal_entry(id: String) -> Result<(), String> {
     delete_history_entry(id).map_err(|e| format!("删除历史记录失败: {}", e))
 }
 
+#[tauri::command]
+pub async fn list_bistro_journal_trash() -> Result<Vec<HistoryTrashItem>, String> {
+    list_trash().map_err(|e| format!("读取回收站失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn restore_bistro_journal_trash(ids: Vec<String>) -> Result<u32, String> {
+    restore_from_trash(&ids).map_err(|e| format!("恢复历史记录失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn empty_bistro_journal_trash() -> Result<u32, String> {
+    empty_trash().map_err(|e| format!("清空回收站失败: {}", e))
+}
+
+#[tauri::command]
+pub async fn get_bistro_journal_trash_retention(state: State<'_, AppState>) -> Result<u32, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config
+        .mcp_config
+        .history_trash_retention_days
+        .unwrap_or(crate::mcp::history_trash::DEFAULT_RETENTION_DAYS))
+}
+
+#[tauri::command]
+pub async fn set_bistro_journal_trash_retention(
+    days: u32,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if days > crate::mcp::history_trash::MAX_RETENTION_DAYS {
+        return Err(format!(
+            "保留天数不能超过 {} 天",
+            crate::mcp::history_trash::MAX_RETENTION_DAYS
+        ));
+    }
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.mcp_config.history_trash_retention_days = Some(days);
+    }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))
+}
+
 #[tauri::command]
 pub async fn delete_bistro_journal_by_time_range(
     start: Option<String>,
@@ -230,6 +570,35 @@ pub async fn export_bistro_journal_by_time_range_zip(
         .map_err(|e| format!("导出失败: {}", e))
 }
 
+#[tauri::command]
+pub async fn export_bistro_journal_report(
+    start: Option<String>,
+    end: Option<String>,
+    format: String,
+    embed_images: Option<bool>,
+) -> Result<String, String> {
+    let format = HistoryExportFormat::parse(&format).map_err(|e| e.to_string())?;
+    let target_dir: PathBuf = dirs::download_dir()
+        .or_else(dirs::data_dir)
+        .or_else(dirs::config_dir)
+        .unwrap_or_else(|| history_base_dir().unwrap_or_else(|_| PathBuf::from(".")));
+
+    export_history_report(start, end, format, embed_images.unwrap_or(true), target_dir)
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| format!("导出失败: {}", e))
+}
+
+/// 导入导出的历史归档（ZIP 或 JSONL），data 为文件内容的 base64
+#[tauri::command]
+pub async fn import_bistro_journal_archive(file_name: String, data: String) -> Result<HistoryImportResult, String> {
+    use base64::Engine;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| format!("文件内容解码失败: {}", e))?;
+
+    import_history_archive(&file_name, &bytes).map_err(|e| format!("导入失败: {}", e))
+}
+
 // acemcp 相关命令已迁移
 
 // 已移除 Python Web 服务相关函数，完全使用 Rust 实现
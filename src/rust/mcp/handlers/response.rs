@@ -1,7 +1,9 @@
 use anyhow::Result;
-use rmcp::model::{ErrorData as McpError, Content};
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use rmcp::model::{ErrorData as McpError, Content, ResourceContents};
 
-use crate::mcp::types::DishResponse;
+use crate::mcp::ingredient_kind::{self, IngredientKind};
+use crate::mcp::types::{DishResponse, IngredientAttachment};
 
 const MAX_SAUCE_BASE64_LEN_FOR_OUTPUT: usize = 800_000;
 
@@ -17,7 +19,32 @@ fn estimate_base64_size_str(base64_len: usize) -> String {
 }
 
 fn is_supported_dish_type(dish_type: &str) -> bool {
-    matches!(dish_type, "image/png" | "image/jpeg" | "image/webp")
+    match ingredient_kind::kind_of(dish_type) {
+        Some(IngredientKind::Image) => matches!(dish_type, "image/png" | "image/jpeg" | "image/webp"),
+        Some(_) => true,
+        None => false,
+    }
+}
+
+/// Non-image ingredients are returned as embedded resources
+fn ingredient_resource(index: usize, ingredient: &IngredientAttachment) -> Content {
+    let filename = ingredient.tag.clone().unwrap_or_else(|| {
+        format!(
+            "ingredient-{}.{}",
+            index + 1,
+            ingredient_kind::ext_for(&ingredient.dish_type).unwrap_or("bin")
+        )
+    });
+    Content::resource(ResourceContents::BlobResourceContents {
+        uri: format!(
+            "attachment://{}/{}",
+            index + 1,
+            utf8_percent_encode(&filename, NON_ALPHANUMERIC)
+        ),
+        mime_type: Some(ingredient.dish_type.clone()),
+        blob: ingredient.sauce.clone(),
+        meta: None,
+    })
 }
 
 /// Parse MCP response content
@@ -58,11 +85,15 @@ fn parse_structured_response(response: DishResponse) -> Result<Vec<Content>, Mcp
         let is_too_large = base64_len > MAX_SAUCE_BASE64_LEN_FOR_OUTPUT;
 
         if is_supported && !is_too_large {
-            result.push(Content::image(ingredient.sauce.clone(), ingredient.dish_type.clone()));
+            if ingredient_kind::kind_of(&ingredient.dish_type) == Some(IngredientKind::Image) {
+                result.push(Content::image(ingredient.sauce.clone(), ingredient.dish_type.clone()));
+            } else {
+                result.push(ingredient_resource(index, ingredient));
+            }
         }
 
         let note = if !is_supported {
-            "(已省略：食材类型不受支持，建议用 PNG/JPG/WebP)"
+            "(已省略：食材类型不受支持，图片建议用 PNG/JPG/WebP)"
         } else if is_too_large {
             "(已省略：食材过大，避免触发参数超限)"
         } else {
@@ -2,11 +2,13 @@ use anyhow::Result;
 use base64::Engine;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::io::{Read, Write};
+use std::io::Write;
 use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
+use crate::mcp::{history_blobs, history_crypto, history_index, history_trash, history_webhook};
 use crate::mcp::types::{DishResponse, PopupRequest};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -16,6 +18,16 @@ pub struct HistoryEntrySummary {
     pub request_id: Option<String>,
     pub source: Option<String>,
     pub preview: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub edited: bool,
+    #[serde(default)]
+    pub project_root_path: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -33,10 +45,13 @@ pub struct HistoryIngredient {
     pub filename: String,
     pub dish_type: String,
     pub file_path: String,
+    /// 图片加密保存时无法直接按路径显示，返回解密后的 data URL
+    #[serde(default)]
+    pub data_url: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
-struct HistoryEntryMeta {
+pub(crate) struct HistoryEntryMeta {
     pub id: String,
     pub timestamp: String,
     pub request_id: Option<String>,
@@ -45,6 +60,21 @@ struct HistoryEntryMeta {
     pub response: serde_json::Value,
     #[serde(default)]
     pub ingredient_files: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub starred: bool,
+    /// 所属的 Agent 会话（旧记录为 None）
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// 保存后是否被编辑或脱敏过
+    #[serde(default)]
+    pub edited: bool,
+    #[serde(default)]
+    pub edited_at: Option<String>,
+    /// 交互所在的项目根目录（规范化后；旧记录为 None，读取时从请求中补齐）
+    #[serde(default)]
+    pub project_root_path: Option<String>,
 }
 
 fn preview_from_meta(meta: &HistoryEntryMeta) -> String {
@@ -67,6 +97,43 @@ fn preview_from_meta(meta: &HistoryEntryMeta) -> String {
     String::new()
 }
 
+pub(crate) fn summary_from_meta(meta: &HistoryEntryMeta) -> HistoryEntrySummary {
+    HistoryEntrySummary {
+        id: meta.id.clone(),
+        timestamp: meta.timestamp.clone(),
+        request_id: meta.request_id.clone(),
+        source: meta.source.clone(),
+        preview: preview_from_meta(meta),
+        tags: meta.tags.clone(),
+        starred: meta.starred,
+        session_id: meta.session_id.clone(),
+        edited: meta.edited,
+        project_root_path: project_of(meta),
+    }
+}
+
+/// 记录所属项目：优先使用元数据中的字段，旧记录从请求中读取
+pub(crate) fn project_of(meta: &HistoryEntryMeta) -> Option<String> {
+    meta.project_root_path
+        .clone()
+        .or_else(|| meta.request.as_ref().and_then(|r| r.project_root_path.as_deref()).map(normalize_project_path))
+        .filter(|p| !p.is_empty())
+}
+
+/// 规范化项目路径：统一为正斜杠并去掉末尾的斜杠
+pub(crate) fn normalize_project_path(path: &str) -> String {
+    let normalized = path.trim().replace('\\', "/");
+    match normalized.trim_end_matches('/') {
+        "" if normalized.starts_with('/') => "/".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+/// 是否为同一项目（忽略分隔符、末尾斜杠与 ASCII 大小写，兼容 Windows 盘符）
+pub(crate) fn is_same_project(a: &str, b: &str) -> bool {
+    normalize_project_path(a).eq_ignore_ascii_case(&normalize_project_path(b))
+}
+
 pub fn history_base_dir() -> Result<PathBuf> {
     let base = dirs::data_dir()
         .or_else(dirs::config_dir)
@@ -81,12 +148,36 @@ fn entry_dir_from_id(base: &Path, id: &str) -> PathBuf {
     base.join(id)
 }
 
-fn safe_filename(ext: &str) -> String {
-    let ext = ext.trim_start_matches('.');
-    format!("{}.{}", Uuid::new_v4(), ext)
+/// 写入中的条目所在的临时目录前缀（以 . 开头，列出历史时被忽略）
+pub(crate) const STAGING_PREFIX: &str = ".staging-";
+
+/// 是否为历史条目目录（排除写入中的临时目录等隐藏目录）
+pub(crate) fn is_entry_dir(item: &fs::DirEntry) -> bool {
+    item.file_type().is_ok_and(|t| t.is_dir()) && !item.file_name().to_string_lossy().starts_with('.')
+}
+
+/// 在临时目录中写好 entry.md 与 meta.json 后整体重命名为条目目录，崩溃时不会留下半条记录
+pub(crate) fn write_entry_dir(base: &Path, meta: &HistoryEntryMeta, markdown: &str) -> Result<PathBuf> {
+    let dir = entry_dir_from_id(base, &meta.id);
+    if dir.exists() {
+        return Err(anyhow::anyhow!("历史记录已存在: {}", meta.id));
+    }
+    let staging = base.join(format!("{}{}", STAGING_PREFIX, meta.id));
+    let result = (|| -> Result<()> {
+        fs::create_dir_all(&staging)?;
+        history_crypto::write(staging.join("entry.md"), markdown)?;
+        history_crypto::write(staging.join("meta.json"), serde_json::to_string_pretty(meta)?)?;
+        fs::rename(&staging, &dir)?;
+        Ok(())
+    })();
+    if let Err(e) = result {
+        let _ = fs::remove_dir_all(&staging);
+        return Err(e);
+    }
+    Ok(dir)
 }
 
-fn ext_from_media_type(media_type: &str) -> &'static str {
+pub(crate) fn ext_from_media_type(media_type: &str) -> &'static str {
     match media_type {
         "image/png" => "png",
         "image/jpeg" => "jpg",
@@ -94,11 +185,30 @@ fn ext_from_media_type(media_type: &str) -> &'static str {
         "image/webp" => "webp",
         "image/gif" => "gif",
         "image/svg+xml" => "svg",
-        _ => "bin",
+        other => crate::mcp::ingredient_kind::ext_for(other).unwrap_or("bin"),
+    }
+}
+
+pub(crate) fn dish_type_from_filename(filename: &str) -> &'static str {
+    if filename.ends_with(".png") {
+        "image/png"
+    } else if filename.ends_with(".jpg") || filename.ends_with(".jpeg") {
+        "image/jpeg"
+    } else if filename.ends_with(".webp") {
+        "image/webp"
+    } else if filename.ends_with(".gif") {
+        "image/gif"
+    } else if filename.ends_with(".svg") {
+        "image/svg+xml"
+    } else {
+        filename
+            .rsplit_once('.')
+            .and_then(|(_, ext)| crate::mcp::ingredient_kind::dish_type_from_extension(ext))
+            .unwrap_or("application/octet-stream")
     }
 }
 
-fn build_markdown(
+pub(crate) fn build_markdown(
     request: Option<&PopupRequest>,
     response: &serde_json::Value,
     ingredient_files: &[String],
@@ -139,6 +249,9 @@ fn build_markdown(
                 }
                 out.push_str("\n");
             }
+            if let Some(responder) = r.ticket.responder {
+                out.push_str(&format!("> 回复人：{}\n\n", responder));
+            }
         }
         Err(_) => {
             if let Some(s) = response.as_str() {
@@ -154,7 +267,11 @@ fn build_markdown(
     if !ingredient_files.is_empty() {
         out.push_str("\n## 食材\n\n");
         for f in ingredient_files {
-            out.push_str(&format!("![](ingredients/{})\n\n", f));
+            if dish_type_from_filename(f).starts_with("image/") {
+                out.push_str(&format!("![](ingredients/{})\n\n", f));
+            } else {
+                out.push_str(&format!("[{}](ingredients/{})\n\n", f, f));
+            }
         }
     }
 
@@ -166,9 +283,6 @@ pub fn save_history_entry(request: Option<PopupRequest>, response: serde_json::V
 
     let now: DateTime<Utc> = Utc::now();
     let id = format!("{}-{}", now.format("%Y%m%dT%H%M%S%.3fZ"), Uuid::new_v4());
-    let dir = entry_dir_from_id(&base, &id);
-    let ingredients_dir = dir.join("ingredients");
-    fs::create_dir_all(&ingredients_dir)?;
 
     let (timestamp, request_id, source, ingredient_files) =
         match serde_json::from_value::<DishResponse>(response.clone()) {
@@ -180,10 +294,8 @@ pub fn save_history_entry(request: Option<PopupRequest>, response: serde_json::V
             let mut files = Vec::new();
             for ingredient in r.ingredients {
                 let ext = ext_from_media_type(&ingredient.dish_type);
-                let filename = safe_filename(ext);
                 let bytes = base64::engine::general_purpose::STANDARD.decode(ingredient.sauce)?;
-                fs::write(ingredients_dir.join(&filename), bytes)?;
-                files.push(filename);
+                files.push(history_blobs::store(&bytes, ext)?);
             }
 
             (ts, rid, src, files)
@@ -226,8 +338,13 @@ pub fn save_history_entry(request: Option<PopupRequest>, response: serde_json::V
     };
 
     let markdown = build_markdown(request.as_ref(), &response, &ingredient_files);
-    fs::write(dir.join("entry.md"), &markdown)?;
 
+    let session_id = request.as_ref().and_then(|r| r.session_id.clone());
+    let project_root_path = request
+        .as_ref()
+        .and_then(|r| r.project_root_path.as_deref())
+        .map(normalize_project_path)
+        .filter(|p| !p.is_empty());
     let meta = HistoryEntryMeta {
         id: id.clone(),
         timestamp,
@@ -236,43 +353,135 @@ pub fn save_history_entry(request: Option<PopupRequest>, response: serde_json::V
         request,
         response: response_for_meta,
         ingredient_files,
+        tags: Vec::new(),
+        starred: false,
+        session_id,
+        edited: false,
+        edited_at: None,
+        project_root_path,
     };
 
-    fs::write(dir.join("meta.json"), serde_json::to_string_pretty(&meta)?)?;
+    let dir = match write_entry_dir(&base, &meta, &markdown) {
+        Ok(dir) => dir,
+        Err(e) => {
+            history_blobs::release(&meta.ingredient_files);
+            return Err(e);
+        }
+    };
+    history_index::record_put(&base, &[summary_from_meta(&meta)]);
+    history_webhook::notify_saved(&dir, &meta);
 
     Ok(())
 }
 
+/// 历史列表过滤条件
+#[derive(Debug, Clone, Default)]
+pub struct HistoryListFilter {
+    /// 只保留带有该标签的记录（不区分大小写）
+    pub tag: Option<String>,
+    /// 只保留已加星标的记录
+    pub starred_only: bool,
+    /// 只保留属于该会话的记录
+    pub session_id: Option<String>,
+    /// 只保留该项目的记录
+    pub project: Option<String>,
+}
+
+impl HistoryListFilter {
+    fn matches(&self, summary: &HistoryEntrySummary) -> bool {
+        if self.starred_only && !summary.starred {
+            return false;
+        }
+        if self.session_id.is_some() && summary.session_id != self.session_id {
+            return false;
+        }
+        if let Some(project) = self.project.as_deref().filter(|p| !p.trim().is_empty()) {
+            if !summary.project_root_path.as_deref().is_some_and(|p| is_same_project(p, project)) {
+                return false;
+            }
+        }
+        match self.tag.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+            Some(tag) => summary.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+            None => true,
+        }
+    }
+}
+
+/// 标签使用统计
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryTagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// 规范化标签：去除首尾空白与空标签，不区分大小写去重
+pub fn normalize_tags(tags: Vec<String>) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim();
+        if !tag.is_empty() && !out.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            out.push(tag.to_string());
+        }
+    }
+    out
+}
+
+/// 列出历史记录摘要（最新的在前）
 pub fn list_history_entries(limit: usize) -> Result<Vec<HistoryEntrySummary>> {
+    list_history_entries_filtered(limit, &HistoryListFilter::default())
+}
+
+/// 按标签/星标过滤后列出历史记录摘要（最新的在前）
+///
+/// 摘要来自摘要索引，只解析索引中缺少的条目目录，并清理目录已不存在的索引项
+pub fn list_history_entries_filtered(limit: usize, filter: &HistoryListFilter) -> Result<Vec<HistoryEntrySummary>> {
     let base = history_base_dir()?;
-    let mut entries = Vec::new();
+    let mut index = history_index::load(&base);
 
-    for item in fs::read_dir(base)? {
+    let mut dir_ids = HashSet::new();
+    for item in fs::read_dir(&base)? {
         let item = item?;
-        if !item.file_type()?.is_dir() {
-            continue;
+        if is_entry_dir(&item) {
+            dir_ids.insert(item.file_name().to_string_lossy().to_string());
         }
-        let dir = item.path();
-        let meta_path = dir.join("meta.json");
-        if !meta_path.exists() {
-            continue;
-        }
-        if let Ok(content) = fs::read_to_string(&meta_path) {
+    }
+
+    let stale: Vec<String> = index
+        .summaries
+        .keys()
+        .filter(|id| !dir_ids.contains(*id))
+        .cloned()
+        .collect();
+    for id in &stale {
+        index.summaries.remove(id);
+    }
+
+    let mut added = Vec::new();
+    for id in dir_ids.iter().filter(|id| !index.summaries.contains_key(*id)) {
+        let meta_path = entry_dir_from_id(&base, id).join("meta.json");
+        if let Ok(content) = history_crypto::read_to_string(&meta_path) {
             if let Ok(meta) = serde_json::from_str::<HistoryEntryMeta>(&content) {
-                let preview = preview_from_meta(&meta);
-
-                entries.push(HistoryEntrySummary {
-                    id: meta.id,
-                    timestamp: meta.timestamp,
-                    request_id: meta.request_id,
-                    source: meta.source,
-                    preview,
-                });
+                added.push(summary_from_meta(&meta));
             }
         }
     }
+    for summary in &added {
+        index.summaries.insert(summary.id.clone(), summary.clone());
+    }
 
+    let mut entries: Vec<HistoryEntrySummary> = index.summaries.values().cloned().collect();
     entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+    if index.needs_compaction() {
+        if let Err(e) = history_index::compact(&base, &entries) {
+            log::warn!("重写历史索引失败: {}", e);
+        }
+    } else {
+        history_index::record_delete(&base, &stale);
+        history_index::record_put(&base, &added);
+    }
+
+    entries.retain(|e| filter.matches(e));
     if entries.len() > limit {
         entries.truncate(limit);
     }
@@ -280,51 +489,271 @@ pub fn list_history_entries(limit: usize) -> Result<Vec<HistoryEntrySummary>> {
     Ok(entries)
 }
 
+/// 同一 Agent 会话中的一组记录
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistorySessionGroup {
+    /// 会话 ID；没有会话信息的旧记录各自成组，值为 None
+    pub session_id: Option<String>,
+    pub started_at: String,
+    pub last_at: String,
+    /// 会话内的记录（按时间升序）
+    pub entries: Vec<HistoryEntrySummary>,
+}
+
+/// 按会话分组；组按最近活动时间倒序排列
+fn group_by_session(summaries: Vec<HistoryEntrySummary>) -> Vec<HistorySessionGroup> {
+    let mut groups: Vec<HistorySessionGroup> = Vec::new();
+    let mut by_session: HashMap<String, usize> = HashMap::new();
+
+    for summary in summaries {
+        let existing = summary.session_id.as_ref().and_then(|s| by_session.get(s).copied());
+        let idx = existing.unwrap_or_else(|| {
+            groups.push(HistorySessionGroup {
+                session_id: summary.session_id.clone(),
+                started_at: String::new(),
+                last_at: String::new(),
+                entries: Vec::new(),
+            });
+            if let Some(session) = &summary.session_id {
+                by_session.insert(session.clone(), groups.len() - 1);
+            }
+            groups.len() - 1
+        });
+        groups[idx].entries.push(summary);
+    }
+
+    for group in &mut groups {
+        group.entries.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        group.started_at = group.entries.first().map(|e| e.timestamp.clone()).unwrap_or_default();
+        group.last_at = group.entries.last().map(|e| e.timestamp.clone()).unwrap_or_default();
+    }
+    groups.sort_by(|a, b| b.last_at.cmp(&a.last_at));
+    groups
+}
+
+/// 列出按会话分组的历史记录（最近活动的会话在前），limit 为会话组数
+pub fn list_history_sessions(limit: usize, filter: &HistoryListFilter) -> Result<Vec<HistorySessionGroup>> {
+    let mut groups = group_by_session(list_history_entries_filtered(usize::MAX, filter)?);
+    groups.truncate(limit);
+    Ok(groups)
+}
+
+/// 全文搜索摘录中的一段文本，highlight 为 true 表示命中搜索词
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct HistorySnippetSegment {
+    pub text: String,
+    pub highlight: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistorySearchHit {
+    pub summary: HistoryEntrySummary,
+    /// 命中的字段：message / user_input / markdown
+    pub matched_fields: Vec<String>,
+    /// 每个命中字段一段带高亮的摘录
+    pub snippets: Vec<Vec<HistorySnippetSegment>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistorySearchPage {
+    /// 命中的记录总数
+    pub total: usize,
+    pub offset: usize,
+    pub hits: Vec<HistorySearchHit>,
+}
+
+/// 摘录中命中位置前后保留的字符数
+const SNIPPET_RADIUS: usize = 60;
+
+fn fold_char(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+/// 搜索词：按空白切分、转小写并去重
+fn search_terms(query: &str) -> Vec<Vec<char>> {
+    let mut terms: Vec<Vec<char>> = Vec::new();
+    for word in query.split_whitespace() {
+        let term: Vec<char> = word.chars().map(fold_char).collect();
+        if !terms.contains(&term) {
+            terms.push(term);
+        }
+    }
+    terms
+}
+
+/// 所有搜索词的命中区间（字符下标，已合并重叠部分）
+fn match_ranges(chars: &[char], terms: &[Vec<char>]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for start in 0..chars.len() {
+        for term in terms {
+            let end = start + term.len();
+            if end <= chars.len() && chars[start..end].iter().zip(term).all(|(c, t)| fold_char(*c) == *t) {
+                ranges.push((start, end));
+            }
+        }
+    }
+    ranges.sort();
+
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// 以第一个命中位置为中心截取摘录，并标出其中所有命中词
+fn highlight_snippet(chars: &[char], ranges: &[(usize, usize)]) -> Vec<HistorySnippetSegment> {
+    let Some(&(first_start, first_end)) = ranges.first() else {
+        return Vec::new();
+    };
+    let window_start = first_start.saturating_sub(SNIPPET_RADIUS);
+    let window_end = (first_end + SNIPPET_RADIUS).min(chars.len());
+
+    let text_of = |from: usize, to: usize| -> String {
+        chars[from..to].iter().map(|c| if c.is_whitespace() { ' ' } else { *c }).collect()
+    };
+    let mut segments = Vec::new();
+    let mut push = |text: String, highlight: bool| {
+        if !text.is_empty() {
+            segments.push(HistorySnippetSegment { text, highlight });
+        }
+    };
+
+    if window_start > 0 {
+        push("…".to_string(), false);
+    }
+    let mut cursor = window_start;
+    for &(start, end) in ranges.iter().filter(|(s, e)| *e > window_start && *s < window_end) {
+        let (start, end) = (start.max(window_start), end.min(window_end));
+        push(text_of(cursor, start), false);
+        push(text_of(start, end), true);
+        cursor = end;
+    }
+    push(text_of(cursor, window_end), false);
+    if window_end < chars.len() {
+        push("…".to_string(), false);
+    }
+    segments
+}
+
+fn user_input_from_response(response: &serde_json::Value) -> String {
+    match serde_json::from_value::<DishResponse>(response.clone()) {
+        Ok(r) => r.note.unwrap_or_default(),
+        Err(_) => response.as_str().map(str::to_string).unwrap_or_default(),
+    }
+}
+
+/// 在全部历史记录的请求消息、用户输入与 Markdown 中全文搜索（所有搜索词均需命中），
+/// 结果按时间倒序分页返回
+pub fn search_history_entries(
+    query: &str,
+    project: Option<&str>,
+    offset: usize,
+    limit: usize,
+) -> Result<HistorySearchPage> {
+    let terms = search_terms(query);
+    if terms.is_empty() {
+        return Ok(HistorySearchPage { total: 0, offset, hits: Vec::new() });
+    }
+
+    let base = history_base_dir()?;
+    let mut hits = Vec::new();
+
+    for item in fs::read_dir(base)? {
+        let item = item?;
+        if !is_entry_dir(&item) {
+            continue;
+        }
+        let dir = item.path();
+        let meta: HistoryEntryMeta = match history_crypto::read_to_string(dir.join("meta.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+        {
+            Some(m) => m,
+            None => continue,
+        };
+        if let Some(project) = project.filter(|p| !p.trim().is_empty()) {
+            if !project_of(&meta).is_some_and(|p| is_same_project(&p, project)) {
+                continue;
+            }
+        }
+
+        let fields = [
+            ("message", meta.request.as_ref().map(|r| r.message.clone()).unwrap_or_default()),
+            ("user_input", user_input_from_response(&meta.response)),
+            ("markdown", history_crypto::read_to_string(dir.join("entry.md")).unwrap_or_default()),
+        ];
+
+        let mut matched_fields = Vec::new();
+        let mut snippets = Vec::new();
+        let mut found = vec![false; terms.len()];
+        for (name, text) in &fields {
+            let chars: Vec<char> = text.chars().collect();
+            let ranges = match_ranges(&chars, &terms);
+            if ranges.is_empty() {
+                continue;
+            }
+            for (idx, term) in terms.iter().enumerate() {
+                found[idx] |= !match_ranges(&chars, std::slice::from_ref(term)).is_empty();
+            }
+            // Markdown 包含请求与回复，其他字段已命中时不再重复摘录
+            if *name != "markdown" || snippets.is_empty() {
+                snippets.push(highlight_snippet(&chars, &ranges));
+            }
+            matched_fields.push(name.to_string());
+        }
+
+        if found.iter().all(|f| *f) {
+            hits.push(HistorySearchHit {
+                summary: summary_from_meta(&meta),
+                matched_fields,
+                snippets,
+            });
+        }
+    }
+
+    hits.sort_by(|a, b| b.summary.timestamp.cmp(&a.summary.timestamp));
+    let total = hits.len();
+    let hits = hits.into_iter().skip(offset).take(limit).collect();
+
+    Ok(HistorySearchPage { total, offset, hits })
+}
+
 pub fn get_history_entry(id: String) -> Result<HistoryEntryDetail> {
     let base = history_base_dir()?;
     let dir = entry_dir_from_id(&base, &id);
     let meta_path = dir.join("meta.json");
 
-    let meta: HistoryEntryMeta = serde_json::from_str(&fs::read_to_string(meta_path)?)?;
-    let markdown = fs::read_to_string(dir.join("entry.md")).unwrap_or_default();
+    let meta: HistoryEntryMeta = serde_json::from_str(&history_crypto::read_to_string(meta_path)?)?;
+    let markdown = history_crypto::read_to_string(dir.join("entry.md")).unwrap_or_default();
 
     let mut ingredients = Vec::new();
-    let ingredients_dir = dir.join("ingredients");
     for filename in &meta.ingredient_files {
-        let path = ingredients_dir.join(filename);
+        let path = history_blobs::ingredient_path(&dir, filename);
         if !path.exists() {
             continue;
         }
 
-        let dish_type = if filename.ends_with(".png") {
-            "image/png"
-        } else if filename.ends_with(".jpg") || filename.ends_with(".jpeg") {
-            "image/jpeg"
-        } else if filename.ends_with(".webp") {
-            "image/webp"
-        } else if filename.ends_with(".gif") {
-            "image/gif"
-        } else if filename.ends_with(".svg") {
-            "image/svg+xml"
+        let dish_type = dish_type_from_filename(filename);
+        let data_url = if history_crypto::is_encrypted_file(&path) {
+            let data = history_crypto::read(&path)?;
+            Some(format!("data:{};base64,{}", dish_type, base64::engine::general_purpose::STANDARD.encode(data)))
         } else {
-            "application/octet-stream"
+            None
         };
 
         ingredients.push(HistoryIngredient {
             filename: filename.clone(),
             dish_type: dish_type.to_string(),
             file_path: path.to_string_lossy().to_string(),
+            data_url,
         });
     }
 
-    let preview = preview_from_meta(&meta);
-    let summary = HistoryEntrySummary {
-        id: meta.id.clone(),
-        timestamp: meta.timestamp.clone(),
-        request_id: meta.request_id.clone(),
-        source: meta.source.clone(),
-        preview,
-    };
+    let summary = summary_from_meta(&meta);
 
     Ok(HistoryEntryDetail {
         summary,
@@ -335,12 +764,100 @@ pub fn get_history_entry(id: String) -> Result<HistoryEntryDetail> {
     })
 }
 
+/// 读取记录中的单个食材，返回解密后的内容与类型
+pub fn read_history_ingredient(id: &str, filename: &str) -> Result<(Vec<u8>, String)> {
+    let base = history_base_dir()?;
+    let dir = entry_dir_from_id(&base, id);
+    let meta: HistoryEntryMeta = serde_json::from_str(&history_crypto::read_to_string(dir.join("meta.json"))?)?;
+    if !meta.ingredient_files.iter().any(|f| f == filename) {
+        return Err(anyhow::anyhow!("记录中没有该食材: {}", filename));
+    }
+    let data = history_crypto::read(history_blobs::ingredient_path(&dir, filename))?;
+    Ok((data, dish_type_from_filename(filename).to_string()))
+}
+
+/// 设置记录的标签与星标（None 表示不修改），返回更新后的摘要
+pub fn set_history_entry_labels(
+    id: String,
+    tags: Option<Vec<String>>,
+    starred: Option<bool>,
+) -> Result<HistoryEntrySummary> {
+    let base = history_base_dir()?;
+    let meta_path = entry_dir_from_id(&base, &id).join("meta.json");
+    let mut meta: HistoryEntryMeta = serde_json::from_str(&history_crypto::read_to_string(&meta_path)?)?;
+
+    if let Some(tags) = tags {
+        meta.tags = normalize_tags(tags);
+    }
+    if let Some(starred) = starred {
+        meta.starred = starred;
+    }
+    history_crypto::write(&meta_path, serde_json::to_string_pretty(&meta)?)?;
+
+    let summary = summary_from_meta(&meta);
+    history_index::record_put(&base, std::slice::from_ref(&summary));
+    Ok(summary)
+}
+
+/// 所有记录中使用过的标签及次数（按次数降序）
+pub fn list_history_tags() -> Result<Vec<HistoryTagCount>> {
+    let mut counts: Vec<HistoryTagCount> = Vec::new();
+    for summary in list_history_entries(usize::MAX)? {
+        for tag in summary.tags {
+            match counts.iter_mut().find(|c| c.tag.eq_ignore_ascii_case(&tag)) {
+                Some(count) => count.count += 1,
+                None => counts.push(HistoryTagCount { tag, count: 1 }),
+            }
+        }
+    }
+    counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    Ok(counts)
+}
+
+/// 各项目的记录数
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryProjectCount {
+    pub project_root_path: String,
+    pub count: usize,
+    /// 最近一条记录的时间
+    pub last_at: String,
+}
+
+/// 所有记录涉及的项目（最近有记录的在前）
+pub fn list_history_projects() -> Result<Vec<HistoryProjectCount>> {
+    let mut projects: Vec<HistoryProjectCount> = Vec::new();
+    for summary in list_history_entries(usize::MAX)? {
+        let Some(path) = summary.project_root_path else {
+            continue;
+        };
+        match projects.iter_mut().find(|p| is_same_project(&p.project_root_path, &path)) {
+            Some(project) => project.count += 1,
+            // 摘要按时间倒序，第一次出现即为最近一条
+            None => projects.push(HistoryProjectCount {
+                project_root_path: path,
+                count: 1,
+                last_at: summary.timestamp,
+            }),
+        }
+    }
+    Ok(projects)
+}
+
+/// 读取条目元数据（不存在或无法解析时返回 None）
+fn read_meta(dir: &Path) -> Option<HistoryEntryMeta> {
+    history_crypto::read_to_string(dir.join("meta.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
 pub fn delete_history_entry(id: String) -> Result<()> {
     let base = history_base_dir()?;
     let dir = entry_dir_from_id(&base, &id);
     if dir.exists() {
-        fs::remove_dir_all(dir)?;
+        let meta = read_meta(&dir);
+        history_trash::discard(&dir, meta.as_ref())?;
     }
+    history_index::record_delete(&base, &[id]);
     Ok(())
 }
 
@@ -360,11 +877,11 @@ pub fn delete_history_entries_by_time_range(
     };
 
     let base = history_base_dir()?;
-    let mut deleted: u32 = 0;
+    let mut deleted_ids = Vec::new();
 
     for item in fs::read_dir(&base)? {
         let item = item?;
-        if !item.file_type()?.is_dir() {
+        if !is_entry_dir(&item) {
             continue;
         }
 
@@ -374,7 +891,7 @@ pub fn delete_history_entries_by_time_range(
             continue;
         }
 
-        let meta_content = match fs::read_to_string(&meta_path) {
+        let meta_content = match history_crypto::read_to_string(&meta_path) {
             Ok(s) => s,
             Err(_) => continue,
         };
@@ -399,12 +916,80 @@ pub fn delete_history_entries_by_time_range(
             }
         }
 
-        if fs::remove_dir_all(dir).is_ok() {
-            deleted += 1;
+        match history_trash::discard(&dir, Some(&meta)) {
+            Ok(()) => deleted_ids.push(meta.id),
+            Err(e) => log::warn!("删除历史记录失败: {}", e),
         }
     }
 
-    Ok(deleted)
+    history_index::record_delete(&base, &deleted_ids);
+    Ok(deleted_ids.len() as u32)
+}
+
+pub(crate) fn parse_optional_rfc3339(value: Option<String>) -> Result<Option<DateTime<Utc>>> {
+    match value {
+        Some(s) if !s.trim().is_empty() => Ok(Some(DateTime::parse_from_rfc3339(&s)?.with_timezone(&Utc))),
+        _ => Ok(None),
+    }
+}
+
+/// 时间范围内的历史记录（条目目录与元数据），按时间升序
+pub(crate) fn entries_in_range(
+    start: Option<String>,
+    end: Option<String>,
+) -> Result<Vec<(PathBuf, HistoryEntryMeta)>> {
+    let start_ts = parse_optional_rfc3339(start)?;
+    let end_ts = parse_optional_rfc3339(end)?;
+
+    let base = history_base_dir()?;
+    let mut entries = Vec::new();
+    for item in fs::read_dir(&base)? {
+        let item = item?;
+        if !is_entry_dir(&item) {
+            continue;
+        }
+        let dir = item.path();
+        let meta: HistoryEntryMeta = match history_crypto::read_to_string(dir.join("meta.json"))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+        {
+            Some(m) => m,
+            None => continue,
+        };
+        let ts = match DateTime::parse_from_rfc3339(&meta.timestamp) {
+            Ok(t) => t.with_timezone(&Utc),
+            Err(_) => continue,
+        };
+        if start_ts.is_some_and(|s| ts < s) || end_ts.is_some_and(|e| ts > e) {
+            continue;
+        }
+        entries.push((dir, meta));
+    }
+
+    entries.sort_by(|a, b| a.1.timestamp.cmp(&b.1.timestamp));
+    Ok(entries)
+}
+
+/// 将共享目录中的图片写入归档的 `<prefix>ingredients/` 下（旧记录的图片已随条目目录写入）
+fn add_blob_ingredients_to_zip(
+    zip: &mut zip::ZipWriter<fs::File>,
+    options: zip::write::SimpleFileOptions,
+    dir: &Path,
+    meta: &HistoryEntryMeta,
+    prefix: &str,
+) -> Result<()> {
+    let mut written = HashSet::new();
+    for filename in &meta.ingredient_files {
+        if dir.join("ingredients").join(filename).exists() || !written.insert(filename) {
+            continue;
+        }
+        let Ok(data) = history_crypto::read(history_blobs::ingredient_path(dir, filename)) else {
+            continue;
+        };
+        zip.start_file(format!("{}ingredients/{}", prefix, filename), options)?;
+        zip.write_all(&data)?;
+    }
+    Ok(())
 }
 
 pub fn export_history_entry_zip(id: String, target_dir: PathBuf) -> Result<PathBuf> {
@@ -438,16 +1023,17 @@ pub fn export_history_entry_zip(id: String, target_dir: PathBuf) -> Result<PathB
                 add_dir_to_zip(zip, options, base_dir, &path)?;
             } else {
                 zip.start_file(name, options)?;
-                let mut f = fs::File::open(&path)?;
-                let mut buf = Vec::new();
-                f.read_to_end(&mut buf)?;
-                zip.write_all(&buf)?;
+                // 导出时解密，归档中保存明文
+                zip.write_all(&history_crypto::read(&path)?)?;
             }
         }
         Ok(())
     }
 
     add_dir_to_zip(&mut zip, options, &src_dir, &src_dir)?;
+    if let Some(meta) = read_meta(&src_dir) {
+        add_blob_ingredients_to_zip(&mut zip, options, &src_dir, &meta, "")?;
+    }
     zip.finish()?;
 
     Ok(zip_path)
@@ -501,10 +1087,8 @@ pub fn export_history_by_time_range_zip(
                 add_dir_to_zip_with_prefix(zip, options, base_dir, &path, prefix)?;
             } else {
                 zip.start_file(name, options)?;
-                let mut f = fs::File::open(&path)?;
-                let mut buf = Vec::new();
-                f.read_to_end(&mut buf)?;
-                zip.write_all(&buf)?;
+                // 导出时解密，归档中保存明文
+                zip.write_all(&history_crypto::read(&path)?)?;
             }
         }
         Ok(())
@@ -513,7 +1097,7 @@ pub fn export_history_by_time_range_zip(
     let mut added: u32 = 0;
     for item in fs::read_dir(&base)? {
         let item = item?;
-        if !item.file_type()?.is_dir() {
+        if !is_entry_dir(&item) {
             continue;
         }
 
@@ -523,7 +1107,7 @@ pub fn export_history_by_time_range_zip(
             continue;
         }
 
-        let meta_content = match fs::read_to_string(&meta_path) {
+        let meta_content = match history_crypto::read_to_string(&meta_path) {
             Ok(s) => s,
             Err(_) => continue,
         };
@@ -550,6 +1134,7 @@ pub fn export_history_by_time_range_zip(
 
         let entry_id = meta.id.clone();
         add_dir_to_zip_with_prefix(&mut zip, options, &dir, &dir, &format!("journal/{}", entry_id))?;
+        add_blob_ingredients_to_zip(&mut zip, options, &dir, &meta, &format!("journal/{}/", entry_id))?;
         added += 1;
     }
 
@@ -561,3 +1146,64 @@ pub fn export_history_by_time_range_zip(
 
     Ok(zip_path)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_session() {
+        let summary = |id: &str, ts: &str, session: Option<&str>| HistoryEntrySummary {
+            id: id.to_string(),
+            timestamp: ts.to_string(),
+            request_id: None,
+            source: None,
+            preview: String::new(),
+            tags: Vec::new(),
+            starred: false,
+            session_id: session.map(str::to_string),
+            edited: false,
+            project_root_path: None,
+        };
+        let groups = group_by_session(vec![
+            summary("c", "2026-01-03", Some("s1")),
+            summary("b", "2026-01-02", None),
+            summary("a", "2026-01-01", Some("s1")),
+            summary("d", "2026-01-04", Some("s2")),
+        ]);
+
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[0].session_id.as_deref(), Some("s2"));
+        assert_eq!(groups[1].session_id.as_deref(), Some("s1"));
+        assert_eq!(groups[1].started_at, "2026-01-01");
+        assert_eq!(groups[1].entries.iter().map(|e| e.id.as_str()).collect::<Vec<_>>(), vec!["a", "c"]);
+        assert!(groups[2].session_id.is_none());
+    }
+
+    #[test]
+    fn test_normalize_tags() {
+        let tags = vec![" bug ".to_string(), String::new(), "Bug".to_string(), "发布".to_string()];
+        assert_eq!(normalize_tags(tags), vec!["bug".to_string(), "发布".to_string()]);
+    }
+
+    #[test]
+    fn test_same_project() {
+        assert_eq!(normalize_project_path("C:\\work\\app\\"), "C:/work/app");
+        assert_eq!(normalize_project_path("/"), "/");
+        assert!(is_same_project("C:\\work\\app\\", "c:/work/app"));
+        assert!(is_same_project("/home/u/app", "/home/u/app/"));
+        assert!(!is_same_project("/home/u/app", "/home/u/app2"));
+    }
+
+    #[test]
+    fn test_highlight_snippet() {
+        let chars: Vec<char> = "请确认 Deploy 流程：deploy 到 staging".chars().collect();
+        let ranges = match_ranges(&chars, &search_terms("DEPLOY staging"));
+        assert_eq!(ranges.len(), 3);
+
+        let segments = highlight_snippet(&chars, &ranges);
+        let highlighted: Vec<&str> = segments.iter().filter(|s| s.highlight).map(|s| s.text.as_str()).collect();
+        assert_eq!(highlighted, vec!["Deploy", "deploy", "staging"]);
+        assert_eq!(segments.iter().map(|s| s.text.as_str()).collect::<String>(), "请确认 Deploy 流程：deploy 到 staging");
+    }
+}
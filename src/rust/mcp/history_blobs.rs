@@ -0,0 +1,125 @@
+// 历史图片去重存储
+// 同一张截图常被反复附加到多条记录中。图片按内容寻址保存在共享目录（文件名为 SHA-256 + 扩展名），
+// 条目的 ingredient_files 直接引用该文件名；refs.json 记录每个文件被引用的次数，删除条目时递减，归零后删除文件。
+// 旧记录的图片仍保存在条目自己的 ingredients/ 目录下，读取时优先使用
+
+use anyhow::Result;
+use ring::digest::{digest, SHA256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mcp::history::history_base_dir;
+use crate::mcp::history_crypto;
+
+/// 引用计数文件
+const REFS_FILE: &str = "refs.json";
+
+/// 共享图片目录（与历史目录同级，避免被当作历史条目）
+pub(crate) fn blob_dir() -> Result<PathBuf> {
+    let base = history_base_dir()?;
+    let dir = base
+        .parent()
+        .map(|p| p.join("journal-blobs"))
+        .unwrap_or_else(|| base.join("..").join("journal-blobs"));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// 按内容生成的文件名
+fn blob_name(bytes: &[u8], ext: &str) -> String {
+    format!("{}.{}", hex::encode(digest(&SHA256, bytes)), ext.trim_start_matches('.'))
+}
+
+fn load_refs(dir: &Path) -> HashMap<String, u32> {
+    fs::read_to_string(dir.join(REFS_FILE))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_refs(dir: &Path, refs: &HashMap<String, u32>) -> Result<()> {
+    let tmp = dir.join(format!("{}.tmp", REFS_FILE));
+    fs::write(&tmp, serde_json::to_string(refs)?)?;
+    fs::rename(&tmp, dir.join(REFS_FILE))?;
+    Ok(())
+}
+
+/// 保存图片（内容相同则复用已有文件）并增加引用计数，返回文件名
+pub(crate) fn store(bytes: &[u8], ext: &str) -> Result<String> {
+    let dir = blob_dir()?;
+    let name = blob_name(bytes, ext);
+    let path = dir.join(&name);
+    if !path.exists() {
+        history_crypto::write(&path, bytes)?;
+    }
+
+    let mut refs = load_refs(&dir);
+    *refs.entry(name.clone()).or_insert(0) += 1;
+    save_refs(&dir, &refs)?;
+    Ok(name)
+}
+
+/// 释放条目引用的图片；引用归零的文件被删除（没有计数记录的文件保留）
+pub(crate) fn release(filenames: &[String]) {
+    if filenames.is_empty() {
+        return;
+    }
+    let result = blob_dir().and_then(|dir| {
+        let mut refs = load_refs(&dir);
+        for name in filenames {
+            let Some(count) = refs.get_mut(name) else {
+                continue;
+            };
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                refs.remove(name);
+                let _ = fs::remove_file(dir.join(name));
+            }
+        }
+        save_refs(&dir, &refs)
+    });
+    if let Err(e) = result {
+        log::warn!("释放历史图片失败: {}", e);
+    }
+}
+
+/// 共享目录中的图片文件（不含引用计数文件）
+pub(crate) fn blob_files() -> Vec<PathBuf> {
+    let Ok(dir) = blob_dir() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && !p.file_name().is_some_and(|n| n.to_string_lossy().starts_with(REFS_FILE)))
+        .collect()
+}
+
+/// 条目中一张图片的实际路径：旧记录位于条目的 ingredients/ 目录，新记录位于共享目录
+pub(crate) fn ingredient_path(entry_dir: &Path, filename: &str) -> PathBuf {
+    let legacy = entry_dir.join("ingredients").join(filename);
+    if legacy.exists() {
+        return legacy;
+    }
+    blob_dir()
+        .map(|dir| dir.join(filename))
+        .unwrap_or(legacy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blob_name_is_content_addressed() {
+        let a = blob_name(b"same screenshot", "png");
+        assert_eq!(a, blob_name(b"same screenshot", ".png"));
+        assert_ne!(a, blob_name(b"other screenshot", "png"));
+        assert!(a.ends_with(".png"));
+        assert_eq!(a.len(), 64 + 4);
+    }
+}
@@ -0,0 +1,128 @@
+// 交互历史加密
+// 历史记录（entry.md、meta.json、共享目录中的图片与摘要索引）可能包含专有代码和截图。开启 history_encryption 后
+// 加密写入（见 utils::sealed_store，与本地索引缓存使用不同的密钥）。
+// 读取时按文件头自动识别是否加密，开关切换时会一次性迁移已有记录。
+// 历史记录由 MCP 进程写入，因此每次写入都重新读取配置，避免界面切换开关后 MCP 进程仍按旧状态写入
+
+use anyhow::Result;
+use base64::Engine;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::log_important;
+use crate::mcp::history::history_base_dir;
+use crate::utils::sealed_store::SealedStore;
+
+static STORE: SealedStore = SealedStore::new(b"BISTRO-ENC1\n", "devkit-history", "journal-encryption-key", "历史记录");
+
+/// 加密的索引行前缀（索引按行追加，因此逐行加密）
+const LINE_PREFIX: &str = "enc:";
+
+/// 当前是否开启历史加密
+pub fn is_enabled() -> bool {
+    crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.history_encryption)
+        .unwrap_or(false)
+}
+
+/// 读取历史文件（加密文件自动解密）
+pub(crate) fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    STORE.read(path)
+}
+
+/// 读取历史文件为字符串（加密文件自动解密）
+pub(crate) fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    STORE.read_to_string(path)
+}
+
+/// 写入历史文件（开启加密时加密写入）
+pub(crate) fn write(path: impl AsRef<Path>, data: impl AsRef<[u8]>) -> io::Result<()> {
+    STORE.write(path, data, is_enabled())
+}
+
+/// 文件是否以加密格式保存
+pub(crate) fn is_encrypted_file(path: impl AsRef<Path>) -> bool {
+    STORE.is_sealed_file(path)
+}
+
+/// 加密整段数据（enabled 为 false 时原样返回）
+pub(crate) fn seal_with(enabled: bool, data: Vec<u8>) -> Result<Vec<u8>> {
+    if enabled {
+        STORE.seal(&data)
+    } else {
+        Ok(data)
+    }
+}
+
+/// 解密 seal_with 生成的数据（未加密的数据原样返回）
+pub(crate) fn open(data: Vec<u8>) -> Result<Vec<u8>> {
+    if STORE.is_sealed(&data) {
+        STORE.open(&data)
+    } else {
+        Ok(data)
+    }
+}
+
+/// 编码一行索引（enabled 为 true 时加密）
+///
+/// 开关状态由调用方按批读取一次（is_enabled 每次都会重新加载配置）
+pub(crate) fn seal_line_with(enabled: bool, line: &str) -> Result<String> {
+    if !enabled {
+        return Ok(line.to_string());
+    }
+    let sealed = STORE.seal(line.as_bytes())?;
+    Ok(format!("{}{}", LINE_PREFIX, base64::engine::general_purpose::STANDARD.encode(sealed)))
+}
+
+/// 解码一行索引（加密行自动解密）
+pub(crate) fn open_line(line: &str) -> Result<String> {
+    let Some(encoded) = line.strip_prefix(LINE_PREFIX) else {
+        return Ok(line.to_string());
+    };
+    let sealed = base64::engine::general_purpose::STANDARD.decode(encoded.trim())?;
+    Ok(String::from_utf8(STORE.open(&sealed)?)?)
+}
+
+/// 切换历史加密并迁移已有记录，返回迁移的文件数
+///
+/// 摘要索引会被删除，下次列出历史时按新状态重建
+pub fn set_enabled(enabled: bool) -> Result<usize> {
+    let base = history_base_dir()?;
+    let mut files = collect_entry_files(&base);
+    files.extend(crate::mcp::history_blobs::blob_files());
+    files.extend(crate::mcp::history_trash::trash_files());
+    let migrated = STORE.migrate(files, enabled)?;
+    let _ = fs::remove_file(crate::mcp::history_index::index_path(&base));
+
+    log_important!(info, "历史记录加密已{}，迁移文件数: {}", if enabled { "开启" } else { "关闭" }, migrated);
+    Ok(migrated)
+}
+
+/// 历史目录下各条目目录中的文件（不含顶层的索引与统计文件）
+fn collect_entry_files(base: &Path) -> Vec<PathBuf> {
+    fn walk(dir: &Path, files: &mut Vec<PathBuf>) {
+        let Ok(entries) = fs::read_dir(dir) else {
+            return;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                walk(&path, files);
+            } else {
+                files.push(path);
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    if let Ok(entries) = fs::read_dir(base) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                walk(&entry.path(), &mut files);
+            }
+        }
+    }
+    files
+}
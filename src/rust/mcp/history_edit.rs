@@ -0,0 +1,131 @@
+use anyhow::Result;
+use chrono::Utc;
+use std::fs;
+use std::path::Path;
+
+use crate::mcp::history::{history_base_dir, summary_from_meta, HistoryEntryMeta, HistoryEntrySummary};
+use crate::mcp::{history_blobs, history_crypto, history_index};
+
+/// 脱敏后替换原文的占位符
+const REDACTED: &str = "[已脱敏]";
+
+fn load_entry(id: &str) -> Result<(std::path::PathBuf, HistoryEntryMeta)> {
+    let dir = history_base_dir()?.join(id);
+    let meta_path = dir.join("meta.json");
+    if !meta_path.exists() {
+        return Err(anyhow::anyhow!("历史记录不存在: {}", id));
+    }
+    let meta = serde_json::from_str(&history_crypto::read_to_string(&meta_path)?)?;
+    Ok((dir, meta))
+}
+
+/// 标记为已编辑并写回元数据与索引
+fn save_edited(dir: &Path, meta: &mut HistoryEntryMeta) -> Result<HistoryEntrySummary> {
+    meta.edited = true;
+    meta.edited_at = Some(Utc::now().to_rfc3339());
+    history_crypto::write(dir.join("meta.json"), serde_json::to_string_pretty(meta)?)?;
+
+    let summary = summary_from_meta(meta);
+    if let Some(base) = dir.parent() {
+        history_index::record_put(base, std::slice::from_ref(&summary));
+    }
+    Ok(summary)
+}
+
+/// 用新内容替换记录的 Markdown
+pub fn update_history_markdown(id: &str, markdown: &str) -> Result<HistoryEntrySummary> {
+    let (dir, mut meta) = load_entry(id)?;
+    history_crypto::write(dir.join("entry.md"), markdown)?;
+    save_edited(&dir, &mut meta)
+}
+
+/// 从记录中抹去文本片段（Markdown、请求与回复中的所有出现）并删除指定图片
+pub fn redact_history_entry(id: &str, spans: &[String], remove_images: &[String]) -> Result<HistoryEntrySummary> {
+    let spans: Vec<&str> = spans.iter().map(|s| s.as_str()).filter(|s| !s.trim().is_empty()).collect();
+    let (dir, mut meta) = load_entry(id)?;
+    let mut markdown = history_crypto::read_to_string(dir.join("entry.md")).unwrap_or_default();
+
+    markdown = redact_text(&markdown, &spans);
+    if let Some(request) = meta.request.as_mut() {
+        request.message = redact_text(&request.message, &spans);
+        if let Some(menu) = request.menu.as_mut() {
+            for item in menu.iter_mut() {
+                *item = redact_text(item, &spans);
+            }
+        }
+    }
+    redact_json(&mut meta.response, &spans);
+
+    for filename in remove_images {
+        if !meta.ingredient_files.contains(filename) {
+            continue;
+        }
+        let legacy = dir.join("ingredients").join(filename);
+        if legacy.exists() {
+            fs::remove_file(&legacy)?;
+        } else {
+            history_blobs::release(std::slice::from_ref(filename));
+        }
+        meta.ingredient_files.retain(|f| f != filename);
+        markdown = remove_image_refs(&markdown, filename);
+        if let Some(items) = meta.response.get_mut("ingredients").and_then(|v| v.as_array_mut()) {
+            items.retain(|item| item.get("tag").and_then(|t| t.as_str()) != Some(filename.as_str()));
+        }
+    }
+
+    history_crypto::write(dir.join("entry.md"), &markdown)?;
+    save_edited(&dir, &mut meta)
+}
+
+fn redact_text(text: &str, spans: &[&str]) -> String {
+    spans.iter().fold(text.to_string(), |acc, span| acc.replace(span, REDACTED))
+}
+
+/// 递归替换 JSON 中所有字符串值里的片段
+fn redact_json(value: &mut serde_json::Value, spans: &[&str]) {
+    match value {
+        serde_json::Value::String(s) => *s = redact_text(s, spans),
+        serde_json::Value::Array(items) => items.iter_mut().for_each(|v| redact_json(v, spans)),
+        serde_json::Value::Object(map) => map.values_mut().for_each(|v| redact_json(v, spans)),
+        _ => {}
+    }
+}
+
+/// 删除 Markdown 中引用该图片的行（连同其后的空行）
+fn remove_image_refs(markdown: &str, filename: &str) -> String {
+    let target = format!("ingredients/{}", filename);
+    let mut out = String::new();
+    let mut skip_blank = false;
+    for line in markdown.lines() {
+        if line.contains(&target) {
+            skip_blank = true;
+            continue;
+        }
+        if skip_blank && line.trim().is_empty() {
+            skip_blank = false;
+            continue;
+        }
+        skip_blank = false;
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact() {
+        let spans = ["sk-live-123", "hunter2"];
+        assert_eq!(redact_text("key=sk-live-123 pw=hunter2", &spans), "key=[已脱敏] pw=[已脱敏]");
+
+        let mut value = serde_json::json!({ "note": "token sk-live-123", "toppings": ["hunter2"], "n": 1 });
+        redact_json(&mut value, &spans);
+        assert_eq!(value, serde_json::json!({ "note": "token [已脱敏]", "toppings": ["[已脱敏]"], "n": 1 }));
+
+        let md = "# 回复\n\n![](ingredients/a.png)\n\n![](ingredients/b.png)\n";
+        assert_eq!(remove_image_refs(md, "a.png"), "# 回复\n\n![](ingredients/b.png)\n");
+    }
+}
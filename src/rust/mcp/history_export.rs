@@ -0,0 +1,344 @@
+use anyhow::Result;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mcp::history::{dish_type_from_filename, entries_in_range, project_of, HistoryEntryMeta};
+use crate::mcp::{history_blobs, history_crypto};
+use crate::mcp::types::PopupRequest;
+
+/// 文件链接中需要转义的字符
+const FILE_URL_SET: &AsciiSet = &CONTROLS.add(b' ').add(b'(').add(b')').add(b'#').add(b'?').add(b'%');
+
+/// 报告导出格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HistoryExportFormat {
+    Markdown,
+    Html,
+    Jsonl,
+}
+
+impl HistoryExportFormat {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "md" | "markdown" => Ok(Self::Markdown),
+            "html" => Ok(Self::Html),
+            "jsonl" => Ok(Self::Jsonl),
+            other => Err(anyhow::anyhow!("不支持的导出格式: {}", other)),
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Markdown => "md",
+            Self::Html => "html",
+            Self::Jsonl => "jsonl",
+        }
+    }
+}
+
+/// JSONL 导出中的一条记录（也是导入时读取的格式）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryExportRecord {
+    pub id: String,
+    pub timestamp: String,
+    pub request_id: Option<String>,
+    pub source: Option<String>,
+    pub request: Option<PopupRequest>,
+    pub response: serde_json::Value,
+    pub markdown: String,
+    #[serde(default)]
+    pub ingredients: Vec<HistoryExportIngredient>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub starred: bool,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub project_root_path: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct HistoryExportIngredient {
+    pub filename: String,
+    pub dish_type: String,
+    /// 图片内容（base64），仅在嵌入图片时导出
+    #[serde(default)]
+    pub data: Option<String>,
+    /// 图片的本地路径，仅在链接图片时导出
+    #[serde(default)]
+    pub file_path: Option<String>,
+}
+
+/// 将时间范围内的历史记录导出为单个 Markdown / HTML 报告或 JSONL 文件
+///
+/// embed_images 为 true 时图片以 base64 内嵌，否则链接到历史目录中的原图
+pub fn export_history_report(
+    start: Option<String>,
+    end: Option<String>,
+    format: HistoryExportFormat,
+    embed_images: bool,
+    target_dir: PathBuf,
+) -> Result<PathBuf> {
+    let entries = entries_in_range(start.clone(), end.clone())?;
+    if entries.is_empty() {
+        return Err(anyhow::anyhow!("没有匹配的历史记录"));
+    }
+
+    let records: Vec<HistoryExportRecord> = entries
+        .iter()
+        .map(|(dir, meta)| to_record(dir, meta, embed_images))
+        .collect();
+
+    let content = match format {
+        HistoryExportFormat::Markdown => render_markdown_report(&records, start.as_deref(), end.as_deref()),
+        HistoryExportFormat::Html => render_html_report(&records, start.as_deref(), end.as_deref()),
+        HistoryExportFormat::Jsonl => {
+            let mut out = String::new();
+            for record in &records {
+                out.push_str(&serde_json::to_string(record)?);
+                out.push('\n');
+            }
+            out
+        }
+    };
+
+    fs::create_dir_all(&target_dir)?;
+    let now: DateTime<Utc> = Utc::now();
+    let path = target_dir.join(format!(
+        "bistro-journal-report-{}.{}",
+        now.format("%Y%m%dT%H%M%S%.3fZ"),
+        format.extension()
+    ));
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+pub(crate) fn to_record(dir: &Path, meta: &HistoryEntryMeta, embed_images: bool) -> HistoryExportRecord {
+    let ingredients = meta
+        .ingredient_files
+        .iter()
+        .filter_map(|filename| {
+            let path = history_blobs::ingredient_path(dir, filename);
+            // 加密保存的图片无法通过链接查看，始终解密后内嵌
+            let embed = embed_images || history_crypto::is_encrypted_file(&path);
+            let data = if embed {
+                Some(base64::engine::general_purpose::STANDARD.encode(history_crypto::read(&path).ok()?))
+            } else if path.exists() {
+                None
+            } else {
+                return None;
+            };
+            Some(HistoryExportIngredient {
+                filename: filename.clone(),
+                dish_type: dish_type_from_filename(filename).to_string(),
+                file_path: (!embed).then(|| path.to_string_lossy().to_string()),
+                data,
+            })
+        })
+        .collect();
+
+    HistoryExportRecord {
+        id: meta.id.clone(),
+        timestamp: meta.timestamp.clone(),
+        request_id: meta.request_id.clone(),
+        source: meta.source.clone(),
+        request: meta.request.clone(),
+        response: meta.response.clone(),
+        markdown: history_crypto::read_to_string(dir.join("entry.md")).unwrap_or_default(),
+        ingredients,
+        tags: meta.tags.clone(),
+        starred: meta.starred,
+        session_id: meta.session_id.clone(),
+        project_root_path: project_of(meta),
+    }
+}
+
+/// 条目 Markdown 中 `ingredients/<file>` 图片引用的实际地址
+fn image_src(ingredient: &HistoryExportIngredient) -> Option<String> {
+    if let Some(data) = &ingredient.data {
+        return Some(format!("data:{};base64,{}", ingredient.dish_type, data));
+    }
+    ingredient.file_path.as_ref().map(|path| {
+        let path = path.replace('\\', "/");
+        let encoded = utf8_percent_encode(&path, FILE_URL_SET).to_string();
+        if encoded.starts_with('/') {
+            format!("file://{}", encoded)
+        } else {
+            format!("file:///{}", encoded)
+        }
+    })
+}
+
+fn resolve_images(markdown: &str, record: &HistoryExportRecord) -> String {
+    let mut out = markdown.to_string();
+    for ingredient in &record.ingredients {
+        if let Some(src) = image_src(ingredient) {
+            out = out.replace(&format!("ingredients/{}", ingredient.filename), &src);
+        }
+    }
+    out
+}
+
+/// 将条目中的标题降两级，使其归入报告中该条目的二级标题之下
+fn demote_headings(markdown: &str) -> String {
+    let mut in_code = false;
+    let mut out = String::new();
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code && line.starts_with('#') {
+            out.push_str("##");
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+fn range_label(start: Option<&str>, end: Option<&str>) -> String {
+    format!("{} ~ {}", start.unwrap_or("最早"), end.unwrap_or("最新"))
+}
+
+fn entry_title(record: &HistoryExportRecord) -> String {
+    match &record.source {
+        Some(source) if !source.is_empty() => format!("{} · {}", record.timestamp, source),
+        _ => record.timestamp.clone(),
+    }
+}
+
+fn render_markdown_report(records: &[HistoryExportRecord], start: Option<&str>, end: Option<&str>) -> String {
+    let mut out = format!(
+        "# 交互历史报告\n\n- 时间范围：{}\n- 记录数：{}\n\n",
+        range_label(start, end),
+        records.len()
+    );
+    for record in records {
+        out.push_str(&format!("---\n\n## {}\n\n", entry_title(record)));
+        out.push_str(&format!("<!-- id: {} -->\n\n", record.id));
+        out.push_str(&demote_headings(&resolve_images(&record.markdown, record)));
+        out.push('\n');
+    }
+    out
+}
+
+fn render_html_report(records: &[HistoryExportRecord], start: Option<&str>, end: Option<&str>) -> String {
+    let mut body = String::new();
+    for record in records {
+        body.push_str(&format!(
+            "<section class=\"entry\" id=\"{}\">\n<h2>{}</h2>\n{}</section>\n",
+            escape_html(&record.id),
+            escape_html(&entry_title(record)),
+            markdown_to_html(&demote_headings(&resolve_images(&record.markdown, record)))
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"zh-CN\">\n<head>\n<meta charset=\"utf-8\">\n<title>交互历史报告</title>\n<style>\n\
+        body {{ font-family: -apple-system, 'Segoe UI', sans-serif; max-width: 960px; margin: 2rem auto; padding: 0 1rem; line-height: 1.6; color: #222; }}\n\
+        .entry {{ border-top: 1px solid #ddd; padding-top: 1rem; margin-top: 1.5rem; }}\n\
+        pre {{ background: #f5f5f5; padding: .75rem; overflow-x: auto; border-radius: 4px; }}\n\
+        img {{ max-width: 100%; }}\n\
+        .meta {{ color: #666; }}\n\
+        </style>\n</head>\n<body>\n<h1>交互历史报告</h1>\n<p class=\"meta\">时间范围：{}<br>记录数：{}</p>\n{}</body>\n</html>\n",
+        escape_html(&range_label(start, end)),
+        records.len(),
+        body
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 简易 Markdown 渲染：覆盖历史条目用到的标题、列表、代码块、图片与段落，其余按纯文本转义
+fn markdown_to_html(markdown: &str) -> String {
+    let mut out = String::new();
+    let mut in_code = false;
+    let mut in_list = false;
+    let mut paragraph: Vec<String> = Vec::new();
+
+    fn flush_paragraph(out: &mut String, paragraph: &mut Vec<String>) {
+        if !paragraph.is_empty() {
+            out.push_str(&format!("<p>{}</p>\n", paragraph.join("<br>\n")));
+            paragraph.clear();
+        }
+    }
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            flush_paragraph(&mut out, &mut paragraph);
+            out.push_str(if in_code { "</code></pre>\n" } else { "<pre><code>" });
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            out.push_str(&escape_html(line));
+            out.push('\n');
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let is_item = trimmed.starts_with("- ") || trimmed.starts_with("* ");
+        if in_list && !is_item {
+            out.push_str("</ul>\n");
+            in_list = false;
+        }
+
+        let hashes = trimmed.chars().take_while(|c| *c == '#').count();
+        if trimmed.is_empty() {
+            flush_paragraph(&mut out, &mut paragraph);
+        } else if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+            flush_paragraph(&mut out, &mut paragraph);
+            out.push_str(&format!("<h{0}>{1}</h{0}>\n", hashes, escape_html(trimmed[hashes..].trim())));
+        } else if is_item {
+            flush_paragraph(&mut out, &mut paragraph);
+            if !in_list {
+                out.push_str("<ul>\n");
+                in_list = true;
+            }
+            out.push_str(&format!("<li>{}</li>\n", escape_html(&trimmed[2..])));
+        } else if let Some(src) = trimmed.strip_prefix("![](").and_then(|rest| rest.strip_suffix(')')) {
+            flush_paragraph(&mut out, &mut paragraph);
+            out.push_str(&format!("<p><img src=\"{}\" alt=\"\"></p>\n", escape_html(src)));
+        } else {
+            paragraph.push(escape_html(line));
+        }
+    }
+    flush_paragraph(&mut out, &mut paragraph);
+    if in_list {
+        out.push_str("</ul>\n");
+    }
+    if in_code {
+        out.push_str("</code></pre>\n");
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_to_html() {
+        let html = markdown_to_html("# 请求\n\n部署 <prod>?\n\n## 选项\n\n- 是\n- 否\n\n```\nlet a = 1 < 2;\n```\n![](data:image/png;base64,AAA)\n");
+        assert!(html.contains("<h1>请求</h1>"));
+        assert!(html.contains("<p>部署 &lt;prod&gt;?</p>"));
+        assert!(html.contains("<ul>\n<li>是</li>\n<li>否</li>\n</ul>"));
+        assert!(html.contains("<pre><code>let a = 1 &lt; 2;\n</code></pre>"));
+        assert!(html.contains("<img src=\"data:image/png;base64,AAA\""));
+    }
+
+    #[test]
+    fn test_demote_headings() {
+        assert_eq!(demote_headings("# 请求\n```\n# comment\n```\n"), "### 请求\n```\n# comment\n```\n");
+    }
+}
@@ -0,0 +1,252 @@
+use anyhow::Result;
+use base64::Engine;
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{Cursor, Read};
+use std::path::Path;
+use uuid::Uuid;
+
+use crate::mcp::history::{build_markdown, history_base_dir, summary_from_meta, write_entry_dir, HistoryEntryMeta};
+use crate::mcp::history_export::HistoryExportRecord;
+use crate::mcp::{history_blobs, history_index};
+
+/// ZIP 中单个文件解压后的大小上限，防止压缩炸弹耗尽内存
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 导入结果
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct HistoryImportResult {
+    /// 导入的记录数
+    pub imported: u32,
+    /// 因 ID 冲突而重新生成 ID 的记录数
+    pub renamed: u32,
+    /// 无法解析而跳过的记录数
+    pub skipped: u32,
+}
+
+/// 一条待写入的历史记录
+struct PendingEntry {
+    meta: HistoryEntryMeta,
+    markdown: Option<String>,
+    /// (文件名, 内容)
+    ingredients: Vec<(String, Vec<u8>)>,
+}
+
+/// 导入导出的历史记录：单条/按时间段导出的 ZIP，或 JSONL 导出文件
+pub fn import_history_archive(file_name: &str, bytes: &[u8]) -> Result<HistoryImportResult> {
+    let is_zip = bytes.starts_with(b"PK") || file_name.to_ascii_lowercase().ends_with(".zip");
+    let (entries, skipped) = if is_zip {
+        read_zip_entries(bytes)?
+    } else {
+        read_jsonl_entries(bytes)?
+    };
+
+    let base = history_base_dir()?;
+    let mut result = HistoryImportResult {
+        skipped,
+        ..Default::default()
+    };
+    let mut summaries = Vec::new();
+    for entry in entries {
+        match write_entry(&base, entry) {
+            Ok((summary, renamed)) => {
+                result.imported += 1;
+                if renamed {
+                    result.renamed += 1;
+                }
+                summaries.push(summary);
+            }
+            Err(e) => {
+                log::warn!("导入历史记录失败: {}", e);
+                result.skipped += 1;
+            }
+        }
+    }
+    history_index::record_put(&base, &summaries);
+
+    Ok(result)
+}
+
+/// 文件名只允许单层（拒绝路径穿越）
+fn is_safe_file_name(name: &str) -> bool {
+    !name.is_empty() && name != "." && name != ".." && !name.contains(['/', '\\', ':'])
+}
+
+/// 按条目分组 ZIP 内的文件：按时间段导出的归档位于 journal/<id>/ 下，单条导出的文件位于根目录
+fn read_zip_entries(bytes: &[u8]) -> Result<(Vec<PendingEntry>, u32)> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let mut groups: BTreeMap<String, Vec<(String, Vec<u8>)>> = BTreeMap::new();
+
+    for i in 0..archive.len() {
+        let mut file = archive.by_index(i)?;
+        if file.is_dir() {
+            continue;
+        }
+        let name = file.name().replace('\\', "/");
+        let (key, rel) = match name.strip_prefix("journal/").and_then(|rest| rest.split_once('/')) {
+            Some((id, rel)) => (id.to_string(), rel.to_string()),
+            None => (String::new(), name.clone()),
+        };
+        let buf = read_limited(&mut file, MAX_ENTRY_BYTES)
+            .map_err(|e| anyhow::anyhow!("读取 ZIP 中的文件 {} 失败: {}", name, e))?;
+        groups.entry(key).or_default().push((rel, buf));
+    }
+
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+    for (_, files) in groups {
+        let mut meta: Option<HistoryEntryMeta> = None;
+        let mut markdown = None;
+        let mut ingredients = Vec::new();
+        for (rel, data) in files {
+            match rel.as_str() {
+                "meta.json" => meta = serde_json::from_slice(&data).ok(),
+                "entry.md" => markdown = Some(String::from_utf8_lossy(&data).to_string()),
+                _ => {
+                    if let Some(filename) = rel.strip_prefix("ingredients/").filter(|f| is_safe_file_name(f)) {
+                        ingredients.push((filename.to_string(), data));
+                    }
+                }
+            }
+        }
+        match meta {
+            Some(meta) => entries.push(PendingEntry { meta, markdown, ingredients }),
+            None => skipped += 1,
+        }
+    }
+    Ok((entries, skipped))
+}
+
+/// 读取全部内容，超过 limit 字节时报错（只多读 1 字节用于判断）
+fn read_limited(reader: impl Read, limit: u64) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    reader.take(limit + 1).read_to_end(&mut buf)?;
+    if buf.len() as u64 > limit {
+        anyhow::bail!("文件超过 {} MB", limit / (1024 * 1024));
+    }
+    Ok(buf)
+}
+
+fn read_jsonl_entries(bytes: &[u8]) -> Result<(Vec<PendingEntry>, u32)> {
+    let content = String::from_utf8_lossy(bytes);
+    let mut entries = Vec::new();
+    let mut skipped = 0;
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let record: HistoryExportRecord = match serde_json::from_str(line) {
+            Ok(r) => r,
+            Err(_) => {
+                skipped += 1;
+                continue;
+            }
+        };
+
+        // 只接受内嵌的图片；不读取 file_path，避免导入文件把任意本地文件带入历史记录
+        let mut ingredients = Vec::new();
+        for ingredient in &record.ingredients {
+            let data = ingredient
+                .data
+                .as_ref()
+                .and_then(|data| base64::engine::general_purpose::STANDARD.decode(data).ok());
+            if let Some(data) = data.filter(|_| is_safe_file_name(&ingredient.filename)) {
+                ingredients.push((ingredient.filename.clone(), data));
+            }
+        }
+
+        entries.push(PendingEntry {
+            meta: HistoryEntryMeta {
+                id: record.id,
+                timestamp: record.timestamp,
+                request_id: record.request_id,
+                source: record.source,
+                request: record.request,
+                response: record.response,
+                ingredient_files: ingredients.iter().map(|(name, _)| name.clone()).collect(),
+                tags: record.tags,
+                starred: record.starred,
+                session_id: record.session_id,
+                edited: false,
+                edited_at: None,
+                project_root_path: record.project_root_path,
+            },
+            markdown: Some(record.markdown),
+            ingredients,
+        });
+    }
+    Ok((entries, skipped))
+}
+
+/// 写入一条记录；ID 已存在（或不安全）时保留时间前缀并重新生成 ID
+fn write_entry(base: &Path, entry: PendingEntry) -> Result<(crate::mcp::HistoryEntrySummary, bool)> {
+    let PendingEntry { mut meta, mut markdown, ingredients } = entry;
+
+    let renamed = !is_safe_file_name(&meta.id) || base.join(&meta.id).exists();
+    if renamed {
+        let prefix = meta
+            .id
+            .split('-')
+            .next()
+            .filter(|p| is_safe_file_name(p))
+            .map(str::to_string)
+            .unwrap_or_else(|| Utc::now().format("%Y%m%dT%H%M%S%.3fZ").to_string());
+        meta.id = format!("{}-{}", prefix, Uuid::new_v4());
+    }
+
+    // 只保留归档中确实存在的图片，按内容存入共享目录并改用新的文件名
+    meta.ingredient_files.retain(|f| ingredients.iter().any(|(name, _)| name == f));
+    let mut stored = Vec::new();
+    for filename in &meta.ingredient_files {
+        let Some((_, data)) = ingredients.iter().find(|(name, _)| name == filename) else {
+            continue;
+        };
+        let ext = filename.rsplit_once('.').map(|(_, ext)| ext).unwrap_or("bin");
+        let blob = history_blobs::store(data, ext)?;
+        if let Some(md) = markdown.as_mut() {
+            *md = md.replace(&format!("ingredients/{}", filename), &format!("ingredients/{}", blob));
+        }
+        stored.push(blob);
+    }
+    meta.ingredient_files = stored;
+
+    let markdown = markdown.unwrap_or_else(|| build_markdown(meta.request.as_ref(), &meta.response, &meta.ingredient_files));
+    if let Err(e) = write_entry_dir(base, &meta, &markdown) {
+        history_blobs::release(&meta.ingredient_files);
+        return Err(e);
+    }
+
+    Ok((summary_from_meta(&meta), renamed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_jsonl_import_ignores_file_path() {
+        let line = serde_json::json!({
+            "id": "20260101T000000.000Z-a",
+            "timestamp": "2026-01-01T00:00:00Z",
+            "request_id": null,
+            "source": null,
+            "request": null,
+            "response": "好的",
+            "markdown": "# 记录",
+            "ingredients": [
+                { "filename": "a.png", "dish_type": "image/png", "file_path": "/home/user/.ssh/id_rsa" },
+                { "filename": "b.png", "dish_type": "image/png", "data": "aGk=" }
+            ]
+        })
+        .to_string();
+        let (entries, skipped) = read_jsonl_entries(line.as_bytes()).unwrap();
+        assert_eq!(skipped, 0);
+        assert_eq!(entries[0].ingredients, vec![("b.png".to_string(), b"hi".to_vec())]);
+        assert_eq!(entries[0].meta.ingredient_files, vec!["b.png".to_string()]);
+    }
+
+    #[test]
+    fn test_read_limited() {
+        assert_eq!(read_limited(&b"abcd"[..], 4).unwrap(), b"abcd");
+        assert!(read_limited(&b"abcde"[..], 4).is_err());
+    }
+}
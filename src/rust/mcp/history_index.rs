@@ -0,0 +1,155 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::mcp::{history_crypto, HistoryEntrySummary};
+
+/// 历史记录摘要索引文件（位于历史目录下，每行一条追加记录；开启历史加密时逐行加密）
+const INDEX_FILE: &str = "index.jsonl";
+
+/// 失效行数超过有效条目数加该值时重写索引
+const COMPACT_SLACK: usize = 64;
+
+/// 索引中的一行：新增/更新摘要，或删除记录
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum IndexRecord {
+    Put { summary: HistoryEntrySummary },
+    Delete { id: String },
+}
+
+/// 回放后的索引内容
+pub struct HistoryIndex {
+    pub summaries: HashMap<String, HistoryEntrySummary>,
+    /// 索引文件的总行数（用于判断是否需要压缩）
+    lines: usize,
+}
+
+impl HistoryIndex {
+    /// 失效行过多时需要重写
+    pub fn needs_compaction(&self) -> bool {
+        self.lines > self.summaries.len() * 2 + COMPACT_SLACK
+    }
+}
+
+pub(crate) fn index_path(base: &Path) -> PathBuf {
+    base.join(INDEX_FILE)
+}
+
+/// 读取并回放索引；文件不存在时返回空索引，损坏的行会被跳过
+pub fn load(base: &Path) -> HistoryIndex {
+    let content = fs::read_to_string(index_path(base)).unwrap_or_default();
+    replay(&content)
+}
+
+fn replay(content: &str) -> HistoryIndex {
+    let mut summaries = HashMap::new();
+    let mut lines = 0;
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        lines += 1;
+        let parsed = history_crypto::open_line(line)
+            .and_then(|line| Ok(serde_json::from_str::<IndexRecord>(&line)?));
+        match parsed {
+            Ok(IndexRecord::Put { summary }) => {
+                summaries.insert(summary.id.clone(), summary);
+            }
+            Ok(IndexRecord::Delete { id }) => {
+                summaries.remove(&id);
+            }
+            Err(e) => log::debug!("跳过损坏的历史索引行: {}", e),
+        }
+    }
+    HistoryIndex { summaries, lines }
+}
+
+fn append(base: &Path, records: &[IndexRecord]) -> Result<()> {
+    if records.is_empty() {
+        return Ok(());
+    }
+    let encrypted = history_crypto::is_enabled();
+    let mut buf = String::new();
+    for record in records {
+        buf.push_str(&history_crypto::seal_line_with(encrypted, &serde_json::to_string(record)?)?);
+        buf.push('\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(index_path(base))?;
+    file.write_all(buf.as_bytes())?;
+    Ok(())
+}
+
+/// 记录新增的条目（失败只记录日志，下次列出时会自动补齐）
+pub fn record_put(base: &Path, summaries: &[HistoryEntrySummary]) {
+    let records: Vec<IndexRecord> = summaries
+        .iter()
+        .cloned()
+        .map(|summary| IndexRecord::Put { summary })
+        .collect();
+    if let Err(e) = append(base, &records) {
+        log::warn!("更新历史索引失败: {}", e);
+    }
+}
+
+/// 记录删除的条目
+pub fn record_delete(base: &Path, ids: &[String]) {
+    let records: Vec<IndexRecord> = ids.iter().cloned().map(|id| IndexRecord::Delete { id }).collect();
+    if let Err(e) = append(base, &records) {
+        log::warn!("更新历史索引失败: {}", e);
+    }
+}
+
+/// 以当前有效条目重写索引（先写临时文件再替换）
+pub fn compact(base: &Path, summaries: &[HistoryEntrySummary]) -> Result<()> {
+    let tmp = base.join(format!("{}.tmp", INDEX_FILE));
+    let encrypted = history_crypto::is_enabled();
+    let mut buf = String::new();
+    for summary in summaries {
+        let line = serde_json::to_string(&IndexRecord::Put { summary: summary.clone() })?;
+        buf.push_str(&history_crypto::seal_line_with(encrypted, &line)?);
+        buf.push('\n');
+    }
+    fs::write(&tmp, buf)?;
+    fs::rename(&tmp, index_path(base))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(id: &str) -> HistoryEntrySummary {
+        HistoryEntrySummary {
+            id: id.to_string(),
+            timestamp: "2026-01-01T00:00:00Z".to_string(),
+            request_id: None,
+            source: None,
+            preview: String::new(),
+            tags: Vec::new(),
+            starred: false,
+            session_id: None,
+            edited: false,
+            project_root_path: None,
+        }
+    }
+
+    #[test]
+    fn test_replay_index() {
+        let lines: Vec<String> = [
+            IndexRecord::Put { summary: summary("a") },
+            IndexRecord::Put { summary: summary("b") },
+            IndexRecord::Delete { id: "a".to_string() },
+        ]
+        .iter()
+        .map(|r| serde_json::to_string(r).unwrap())
+        .collect();
+        let content = format!("{}\nnot json\n", lines.join("\n"));
+
+        let index = replay(&content);
+        assert_eq!(index.lines, 4);
+        assert_eq!(index.summaries.len(), 1);
+        assert!(index.summaries.contains_key("b"));
+        assert!(!index.needs_compaction());
+    }
+}
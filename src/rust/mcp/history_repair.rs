@@ -0,0 +1,166 @@
+// 交互历史修复
+// 条目先写入临时目录再整体重命名，但旧版本写入的记录或异常退出仍可能留下不完整的条目，
+// 列出历史时这些条目会被静默跳过。修复时逐个检查条目目录：
+// - 写入中断的临时目录：内容完整且目标不存在时补完重命名，否则隔离
+// - 缺少或无法解析 meta.json 的条目：移入与历史目录同级的隔离目录（保留原文件，便于手动找回）
+// - 只缺少 entry.md 的条目：按元数据重新生成
+// - 原子写入残留的临时文件：删除
+// 加密条目因密钥不可用而无法读取时不视为损坏
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::log_important;
+use crate::mcp::history::{build_markdown, history_base_dir, HistoryEntryMeta, STAGING_PREFIX};
+use crate::mcp::{history_crypto, history_index};
+use crate::utils::sealed_store;
+
+/// 修复结果
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct HistoryRepairReport {
+    /// 检查的条目目录数
+    pub checked: u32,
+    /// 从中断的写入中恢复的条目 ID
+    pub recovered: Vec<String>,
+    /// 重新生成 entry.md 的条目 ID
+    pub rebuilt: Vec<String>,
+    /// 被隔离的目录名
+    pub quarantined: Vec<String>,
+    /// 因密钥不可用而无法检查的条目数
+    pub unreadable: u32,
+    /// 删除的临时文件数
+    pub removed_tmp_files: u32,
+    /// 隔离目录
+    pub quarantine_dir: Option<String>,
+}
+
+/// 条目目录的检查结果
+enum EntryState {
+    Ok(HistoryEntryMeta),
+    MissingMarkdown(HistoryEntryMeta),
+    Unreadable,
+    Broken(String),
+}
+
+/// 隔离目录（与历史目录同级，避免被当作历史条目）
+fn quarantine_dir(base: &Path) -> PathBuf {
+    base.parent()
+        .map(|p| p.join("journal-quarantine"))
+        .unwrap_or_else(|| base.join("..").join("journal-quarantine"))
+}
+
+fn inspect(dir: &Path) -> EntryState {
+    let meta_path = dir.join("meta.json");
+    if !meta_path.exists() {
+        return EntryState::Broken("缺少 meta.json".to_string());
+    }
+    let content = match history_crypto::read_to_string(&meta_path) {
+        Ok(content) => content,
+        Err(_) if history_crypto::is_encrypted_file(&meta_path) => return EntryState::Unreadable,
+        Err(e) => return EntryState::Broken(format!("读取 meta.json 失败: {}", e)),
+    };
+    let meta: HistoryEntryMeta = match serde_json::from_str(&content) {
+        Ok(meta) => meta,
+        Err(e) => return EntryState::Broken(format!("解析 meta.json 失败: {}", e)),
+    };
+    if dir.join("entry.md").exists() {
+        EntryState::Ok(meta)
+    } else {
+        EntryState::MissingMarkdown(meta)
+    }
+}
+
+/// 删除目录中原子写入残留的临时文件
+fn remove_tmp_files(dir: &Path) -> u32 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().ends_with(sealed_store::TMP_SUFFIX))
+        .filter(|e| fs::remove_file(e.path()).is_ok())
+        .count() as u32
+}
+
+fn move_to_quarantine(base: &Path, dir: &Path, name: &str) -> Result<()> {
+    let target_root = quarantine_dir(base);
+    fs::create_dir_all(&target_root)?;
+    let mut target = target_root.join(name.trim_start_matches('.'));
+    if target.exists() {
+        target = target_root.join(format!("{}-{}", name.trim_start_matches('.'), Utc::now().format("%Y%m%dT%H%M%S")));
+    }
+    fs::rename(dir, target)?;
+    Ok(())
+}
+
+/// 检查并修复历史目录，返回修复结果；有改动时重建摘要索引
+pub fn repair_history() -> Result<HistoryRepairReport> {
+    let base = history_base_dir()?;
+    let mut report = HistoryRepairReport::default();
+
+    let mut dirs: Vec<(String, PathBuf)> = fs::read_dir(&base)?
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| (e.file_name().to_string_lossy().to_string(), e.path()))
+        .collect();
+    // 先处理临时目录，恢复的条目不会在本轮被重复检查
+    dirs.sort_by_key(|(name, _)| !name.starts_with(STAGING_PREFIX));
+
+    for (name, dir) in dirs {
+        report.checked += 1;
+        report.removed_tmp_files += remove_tmp_files(&dir);
+
+        if let Some(id) = name.strip_prefix(STAGING_PREFIX) {
+            let target = base.join(id);
+            match inspect(&dir) {
+                EntryState::Ok(meta) if meta.id == id && !target.exists() => {
+                    fs::rename(&dir, &target)?;
+                    report.recovered.push(id.to_string());
+                }
+                _ => {
+                    move_to_quarantine(&base, &dir, &name)?;
+                    report.quarantined.push(name);
+                }
+            }
+            continue;
+        }
+        if name.starts_with('.') {
+            continue;
+        }
+
+        match inspect(&dir) {
+            EntryState::Ok(_) => {}
+            EntryState::Unreadable => report.unreadable += 1,
+            EntryState::MissingMarkdown(meta) => {
+                let markdown = build_markdown(meta.request.as_ref(), &meta.response, &meta.ingredient_files);
+                history_crypto::write(dir.join("entry.md"), markdown)?;
+                report.rebuilt.push(name);
+            }
+            EntryState::Broken(reason) => {
+                log::warn!("隔离损坏的历史记录 {}: {}", name, reason);
+                move_to_quarantine(&base, &dir, &name)?;
+                report.quarantined.push(name);
+            }
+        }
+    }
+
+    if !report.recovered.is_empty() || !report.quarantined.is_empty() {
+        let _ = fs::remove_file(history_index::index_path(&base));
+    }
+    if !report.quarantined.is_empty() {
+        report.quarantine_dir = Some(quarantine_dir(&base).to_string_lossy().to_string());
+    }
+
+    log_important!(
+        info,
+        "历史记录修复完成: 检查 {}，恢复 {}，重建 {}，隔离 {}",
+        report.checked,
+        report.recovered.len(),
+        report.rebuilt.len(),
+        report.quarantined.len()
+    );
+    Ok(report)
+}
@@ -0,0 +1,212 @@
+use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+
+use crate::mcp::history::{entries_in_range, history_base_dir, parse_optional_rfc3339};
+use crate::mcp::history_blobs;
+use crate::mcp::types::PopupRequest;
+
+/// 被取消的交互不会保存为历史记录，只在该文件中追加一行（用于统计取消率）
+const CANCELLED_FILE: &str = "cancelled.jsonl";
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CancelledRecord {
+    timestamp: String,
+    #[serde(default)]
+    request_id: Option<String>,
+    #[serde(default)]
+    session_id: Option<String>,
+}
+
+/// 单日统计
+#[derive(Debug, Serialize, Clone, Default, PartialEq)]
+pub struct HistoryDayStats {
+    /// 本地日期 YYYY-MM-DD
+    pub date: String,
+    pub completed: u32,
+    pub cancelled: u32,
+    pub images: u32,
+}
+
+/// 时间范围内的使用统计
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct HistoryStats {
+    /// 完成（已保存为历史记录）的交互数
+    pub completed: u32,
+    pub cancelled: u32,
+    /// 取消数 / (完成数 + 取消数)，没有交互时为 0
+    pub cancellation_rate: f64,
+    /// 平均响应耗时（毫秒），只统计记录了请求时间的条目
+    pub avg_latency_ms: Option<u64>,
+    pub latency_samples: u32,
+    pub image_count: u32,
+    pub image_bytes: u64,
+    /// 按日期升序
+    pub days: Vec<HistoryDayStats>,
+}
+
+/// 参与统计的一次交互
+struct StatSample {
+    day: String,
+    cancelled: bool,
+    latency_ms: Option<u64>,
+    images: u32,
+    image_bytes: u64,
+}
+
+/// 记录一次被取消的交互（失败只影响统计，由调用方记录日志）
+pub fn record_cancelled_interaction(request: Option<&PopupRequest>) -> Result<()> {
+    let base = history_base_dir()?;
+    let record = CancelledRecord {
+        timestamp: Utc::now().to_rfc3339(),
+        request_id: request.map(|r| r.id.clone()),
+        session_id: request.and_then(|r| r.session_id.clone()),
+    };
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(base.join(CANCELLED_FILE))?;
+    writeln!(file, "{}", serde_json::to_string(&record)?)?;
+    Ok(())
+}
+
+/// 汇总时间范围内的每日数量、平均响应耗时、取消率与图片量
+pub fn history_stats(start: Option<String>, end: Option<String>) -> Result<HistoryStats> {
+    let start_ts = parse_optional_rfc3339(start.clone())?;
+    let end_ts = parse_optional_rfc3339(end.clone())?;
+    let in_range = |ts: DateTime<Utc>| !(start_ts.is_some_and(|s| ts < s) || end_ts.is_some_and(|e| ts > e));
+
+    let mut samples = Vec::new();
+    for (dir, meta) in entries_in_range(start, end)? {
+        let Some(ts) = parse_time(&meta.timestamp) else {
+            continue;
+        };
+        let latency_ms = meta
+            .request
+            .as_ref()
+            .and_then(|r| r.requested_at.as_deref())
+            .and_then(parse_time)
+            .and_then(|requested| (ts - requested).num_milliseconds().try_into().ok());
+        let image_bytes = meta
+            .ingredient_files
+            .iter()
+            .filter_map(|f| fs::metadata(history_blobs::ingredient_path(&dir, f)).ok())
+            .map(|m| m.len())
+            .sum();
+
+        samples.push(StatSample {
+            day: local_day(ts),
+            cancelled: false,
+            latency_ms,
+            images: meta.ingredient_files.len() as u32,
+            image_bytes,
+        });
+    }
+
+    for record in load_cancelled(&history_base_dir()?) {
+        if let Some(ts) = parse_time(&record.timestamp).filter(|ts| in_range(*ts)) {
+            samples.push(StatSample {
+                day: local_day(ts),
+                cancelled: true,
+                latency_ms: None,
+                images: 0,
+                image_bytes: 0,
+            });
+        }
+    }
+
+    Ok(aggregate(samples))
+}
+
+fn load_cancelled(base: &Path) -> Vec<CancelledRecord> {
+    fs::read_to_string(base.join(CANCELLED_FILE))
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Utc))
+}
+
+fn local_day(ts: DateTime<Utc>) -> String {
+    ts.with_timezone(&Local).format("%Y-%m-%d").to_string()
+}
+
+fn aggregate(samples: Vec<StatSample>) -> HistoryStats {
+    let mut stats = HistoryStats::default();
+    let mut days: BTreeMap<String, HistoryDayStats> = BTreeMap::new();
+    let mut latency_total: u64 = 0;
+
+    for sample in samples {
+        let day = days.entry(sample.day.clone()).or_insert_with(|| HistoryDayStats {
+            date: sample.day.clone(),
+            ..Default::default()
+        });
+        if sample.cancelled {
+            stats.cancelled += 1;
+            day.cancelled += 1;
+            continue;
+        }
+        stats.completed += 1;
+        day.completed += 1;
+        day.images += sample.images;
+        stats.image_count += sample.images;
+        stats.image_bytes += sample.image_bytes;
+        if let Some(latency) = sample.latency_ms {
+            latency_total += latency;
+            stats.latency_samples += 1;
+        }
+    }
+
+    let total = stats.completed + stats.cancelled;
+    if total > 0 {
+        stats.cancellation_rate = stats.cancelled as f64 / total as f64;
+    }
+    if stats.latency_samples > 0 {
+        stats.avg_latency_ms = Some(latency_total / stats.latency_samples as u64);
+    }
+    stats.days = days.into_values().collect();
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(day: &str, cancelled: bool, latency_ms: Option<u64>, images: u32) -> StatSample {
+        StatSample {
+            day: day.to_string(),
+            cancelled,
+            latency_ms,
+            images,
+            image_bytes: images as u64 * 100,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_stats() {
+        let stats = aggregate(vec![
+            sample("2026-01-02", false, Some(3_000), 2),
+            sample("2026-01-01", false, None, 0),
+            sample("2026-01-01", true, None, 0),
+            sample("2026-01-02", false, Some(1_000), 1),
+        ]);
+
+        assert_eq!(stats.completed, 3);
+        assert_eq!(stats.cancelled, 1);
+        assert_eq!(stats.cancellation_rate, 0.25);
+        assert_eq!(stats.avg_latency_ms, Some(2_000));
+        assert_eq!(stats.image_count, 3);
+        assert_eq!(stats.image_bytes, 300);
+        assert_eq!(stats.days.len(), 2);
+        assert_eq!(stats.days[0].date, "2026-01-01");
+        assert_eq!(stats.days[0].cancelled, 1);
+        assert_eq!(stats.days[1].images, 3);
+    }
+}
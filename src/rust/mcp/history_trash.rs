@@ -0,0 +1,216 @@
+// 交互历史回收站
+// 删除的记录整体移入与历史目录同级的回收站目录，并在条目目录中写入删除时间；
+// 超过保留天数（history_trash_retention_days，默认 30）的记录在下次删除或查看回收站时被彻底清除。
+// 回收站中的记录仍持有共享图片的引用，彻底清除时才释放。保留天数为 0 时删除即彻底删除
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::mcp::history::{history_base_dir, summary_from_meta, HistoryEntryMeta, HistoryEntrySummary};
+use crate::mcp::{history_blobs, history_crypto, history_index};
+
+/// 默认保留天数
+pub const DEFAULT_RETENTION_DAYS: u32 = 30;
+
+/// 最长保留天数（避免日期计算溢出）
+pub const MAX_RETENTION_DAYS: u32 = 3650;
+
+/// 记录删除时间的文件（位于回收站中的条目目录内）
+const DELETED_AT_FILE: &str = "deleted_at";
+
+/// 回收站中的一条记录
+#[derive(Debug, Serialize, Clone)]
+pub struct HistoryTrashItem {
+    pub summary: HistoryEntrySummary,
+    pub deleted_at: String,
+    /// 到期后会被彻底清除
+    pub expires_at: String,
+}
+
+/// 当前保留天数
+pub fn retention_days() -> u32 {
+    crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.history_trash_retention_days)
+        .unwrap_or(DEFAULT_RETENTION_DAYS)
+        .min(MAX_RETENTION_DAYS)
+}
+
+/// 回收站目录（与历史目录同级，避免被当作历史条目）
+pub(crate) fn trash_dir() -> Result<PathBuf> {
+    let base = history_base_dir()?;
+    let dir = base
+        .parent()
+        .map(|p| p.join("journal-trash"))
+        .unwrap_or_else(|| base.join("..").join("journal-trash"));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn read_meta(dir: &Path) -> Option<HistoryEntryMeta> {
+    history_crypto::read_to_string(dir.join("meta.json"))
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+}
+
+fn deleted_at(dir: &Path) -> Option<DateTime<Utc>> {
+    fs::read_to_string(dir.join(DELETED_AT_FILE))
+        .ok()
+        .and_then(|s| DateTime::parse_from_rfc3339(s.trim()).ok())
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// 彻底删除一个条目目录并释放其图片
+fn destroy(dir: &Path, meta: Option<&HistoryEntryMeta>) -> Result<()> {
+    fs::remove_dir_all(dir)?;
+    if let Some(meta) = meta {
+        history_blobs::release(&meta.ingredient_files);
+    }
+    Ok(())
+}
+
+/// 删除历史条目：保留天数大于 0 时移入回收站，否则彻底删除
+pub(crate) fn discard(dir: &Path, meta: Option<&HistoryEntryMeta>) -> Result<()> {
+    let retention = retention_days();
+    if retention == 0 {
+        return destroy(dir, meta);
+    }
+
+    let trash = trash_dir()?;
+    purge_expired(&trash, retention);
+    let name = dir
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("无效的历史目录: {}", dir.display()))?;
+    let target = trash.join(name);
+    if target.exists() {
+        // 回收站中已有同 ID 的旧记录时以新删除的为准
+        let old_meta = read_meta(&target);
+        destroy(&target, old_meta.as_ref())?;
+    }
+    fs::rename(dir, &target)?;
+    fs::write(target.join(DELETED_AT_FILE), Utc::now().to_rfc3339())?;
+    Ok(())
+}
+
+/// 清除超过保留期的记录，返回清除数
+fn purge_expired(trash: &Path, retention: u32) -> u32 {
+    let cutoff = Utc::now() - Duration::days(retention as i64);
+    let mut purged = 0;
+    for (dir, _) in trash_entries(trash) {
+        // 没有删除时间的目录视为刚删除，等待下次清理时间写入
+        let Some(at) = deleted_at(&dir) else {
+            let _ = fs::write(dir.join(DELETED_AT_FILE), Utc::now().to_rfc3339());
+            continue;
+        };
+        if at < cutoff {
+            let meta = read_meta(&dir);
+            match destroy(&dir, meta.as_ref()) {
+                Ok(()) => purged += 1,
+                Err(e) => log::warn!("清除过期的回收站记录失败: {}", e),
+            }
+        }
+    }
+    purged
+}
+
+fn trash_entries(trash: &Path) -> Vec<(PathBuf, String)> {
+    let Ok(entries) = fs::read_dir(trash) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| (e.path(), e.file_name().to_string_lossy().to_string()))
+        .collect()
+}
+
+/// 回收站中的所有文件（用于加密迁移）
+pub(crate) fn trash_files() -> Vec<PathBuf> {
+    let Ok(trash) = trash_dir() else {
+        return Vec::new();
+    };
+    trash_entries(&trash)
+        .into_iter()
+        .flat_map(|(dir, _)| {
+            let mut files = Vec::new();
+            let mut stack = vec![dir];
+            while let Some(dir) = stack.pop() {
+                for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+                    let path = entry.path();
+                    if path.is_dir() {
+                        stack.push(path);
+                    } else if entry.file_name().to_string_lossy() != DELETED_AT_FILE {
+                        files.push(path);
+                    }
+                }
+            }
+            files
+        })
+        .collect()
+}
+
+/// 列出回收站中的记录（最近删除的在前），同时清除过期记录
+pub fn list_trash() -> Result<Vec<HistoryTrashItem>> {
+    let trash = trash_dir()?;
+    // 保留天数改为 0 之前删除的记录仍按 1 天保留
+    let retention = retention_days().max(1);
+    purge_expired(&trash, retention);
+
+    let mut items: Vec<HistoryTrashItem> = trash_entries(&trash)
+        .into_iter()
+        .filter_map(|(dir, _)| {
+            let meta = read_meta(&dir)?;
+            let at = deleted_at(&dir).unwrap_or_else(Utc::now);
+            Some(HistoryTrashItem {
+                summary: summary_from_meta(&meta),
+                deleted_at: at.to_rfc3339(),
+                expires_at: (at + Duration::days(retention as i64)).to_rfc3339(),
+            })
+        })
+        .collect();
+    items.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+    Ok(items)
+}
+
+/// 将记录从回收站恢复到历史目录，返回恢复数（历史目录中已有同 ID 记录时跳过）
+pub fn restore_from_trash(ids: &[String]) -> Result<u32> {
+    let trash = trash_dir()?;
+    let base = history_base_dir()?;
+    let mut restored = 0;
+    let mut summaries = Vec::new();
+
+    for (dir, name) in trash_entries(&trash) {
+        if !ids.contains(&name) {
+            continue;
+        }
+        let target = base.join(&name);
+        if target.exists() {
+            log::warn!("历史记录已存在，跳过恢复: {}", name);
+            continue;
+        }
+        let _ = fs::remove_file(dir.join(DELETED_AT_FILE));
+        fs::rename(&dir, &target)?;
+        restored += 1;
+        if let Some(meta) = read_meta(&target) {
+            summaries.push(summary_from_meta(&meta));
+        }
+    }
+
+    history_index::record_put(&base, &summaries);
+    Ok(restored)
+}
+
+/// 清空回收站，返回彻底删除的记录数
+pub fn empty_trash() -> Result<u32> {
+    let trash = trash_dir()?;
+    let mut removed = 0;
+    for (dir, _) in trash_entries(&trash) {
+        let meta = read_meta(&dir);
+        destroy(&dir, meta.as_ref())?;
+        removed += 1;
+    }
+    Ok(removed)
+}
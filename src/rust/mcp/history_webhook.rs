@@ -0,0 +1,149 @@
+// 交互历史 Webhook
+// 配置 history_webhook_url 后，每保存一条历史记录就向该地址 POST 一份 JSON 摘要，便于接入 Notion、内部审计等自有工具。
+// 默认不包含图片（只列出文件名与类型），开启 history_webhook_include_images 后以 base64 内嵌。
+// 配置了 history_webhook_secret 时附带 X-Bistro-Signature: sha256=<HMAC-SHA256(secret, body)> 请求头供接收方校验。
+// 发送在后台进行，失败只记录日志，不影响历史记录的保存
+
+use base64::Engine;
+use ring::hmac;
+use serde::{Deserialize, Serialize};
+
+use crate::config::McpConfig;
+use crate::mcp::history::{dish_type_from_filename, summary_from_meta, HistoryEntryMeta};
+use crate::mcp::{history_blobs, history_crypto};
+
+/// 单次推送超时（秒）
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+/// 签名请求头
+pub(crate) const SIGNATURE_HEADER: &str = "X-Bistro-Signature";
+
+/// Webhook 配置（对应 McpConfig 中的 history_webhook_* 字段）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HistoryWebhookConfig {
+    pub url: Option<String>,
+    #[serde(default)]
+    pub include_images: bool,
+    pub secret: Option<String>,
+}
+
+impl HistoryWebhookConfig {
+    pub fn from_mcp_config(config: &McpConfig) -> Self {
+        Self {
+            url: config.history_webhook_url.clone(),
+            include_images: config.history_webhook_include_images.unwrap_or(false),
+            secret: config.history_webhook_secret.clone(),
+        }
+    }
+
+    pub fn apply_to(&self, config: &mut McpConfig) {
+        config.history_webhook_url = self.url.as_ref().map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+        config.history_webhook_include_images = Some(self.include_images);
+        config.history_webhook_secret = self.secret.clone().filter(|s| !s.is_empty());
+    }
+}
+
+/// Webhook 中的一张图片
+struct WebhookImage {
+    filename: String,
+    dish_type: String,
+    data: Option<String>,
+}
+
+/// 保存历史记录后调用：已配置 Webhook 时在后台推送
+pub(crate) fn notify_saved(dir: &std::path::Path, meta: &HistoryEntryMeta) {
+    let config = match crate::config::load_standalone_config() {
+        Ok(c) => HistoryWebhookConfig::from_mcp_config(&c.mcp_config),
+        Err(_) => return,
+    };
+    let Some(url) = config.url.clone().filter(|u| !u.trim().is_empty()) else {
+        return;
+    };
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        log::debug!("没有可用的异步运行时，跳过历史 Webhook");
+        return;
+    };
+
+    let images = meta
+        .ingredient_files
+        .iter()
+        .map(|filename| WebhookImage {
+            filename: filename.clone(),
+            dish_type: dish_type_from_filename(filename).to_string(),
+            data: config
+                .include_images
+                .then(|| history_crypto::read(history_blobs::ingredient_path(dir, filename)).ok())
+                .flatten()
+                .map(|bytes| base64::engine::general_purpose::STANDARD.encode(bytes)),
+        })
+        .collect();
+    let body = build_payload(meta, images).to_string();
+    let signature = config.secret.as_deref().map(|secret| sign(secret, &body));
+
+    handle.spawn(async move {
+        if let Err(e) = send(&url, body, signature).await {
+            log::warn!("推送历史 Webhook 失败: {}", e);
+        }
+    });
+}
+
+async fn send(url: &str, body: String, signature: Option<String>) -> anyhow::Result<()> {
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()?;
+    let mut request = client.post(url).header("Content-Type", "application/json").body(body);
+    if let Some(signature) = signature {
+        request = request.header(SIGNATURE_HEADER, signature);
+    }
+    let response = request.send().await?;
+    if !response.status().is_success() {
+        anyhow::bail!("HTTP {}", response.status());
+    }
+    Ok(())
+}
+
+pub(crate) fn sign(secret: &str, body: &str) -> String {
+    let key = hmac::Key::new(hmac::HMAC_SHA256, secret.as_bytes());
+    format!("sha256={}", hex::encode(hmac::sign(&key, body.as_bytes())))
+}
+
+fn build_payload(meta: &HistoryEntryMeta, images: Vec<WebhookImage>) -> serde_json::Value {
+    let request = meta.request.as_ref();
+    serde_json::json!({
+        "event": "history.saved",
+        "entry": summary_from_meta(meta),
+        "project_root_path": request.and_then(|r| r.project_root_path.clone()),
+        "message": request.map(|r| r.message.clone()),
+        "options": request.and_then(|r| r.menu.clone()).unwrap_or_default(),
+        "selected_options": meta.response.get("toppings").cloned().unwrap_or(serde_json::json!([])),
+        "reply": meta
+            .response
+            .get("note")
+            .cloned()
+            .or_else(|| meta.response.is_string().then(|| meta.response.clone())),
+        "images": images
+            .into_iter()
+            .map(|image| {
+                let mut item = serde_json::json!({ "filename": image.filename, "dish_type": image.dish_type });
+                if let Some(data) = image.data {
+                    item["data"] = serde_json::Value::String(data);
+                }
+                item
+            })
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign() {
+        // RFC 4231 测试用例 2
+        assert_eq!(
+            sign("Jefe", "what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
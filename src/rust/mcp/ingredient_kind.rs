@@ -0,0 +1,267 @@
+// 食材类型识别
+// 食材不限于图片，PDF、文本和压缩包也可以作为附件提交。
+// 实际类型按文件头魔数识别，不直接采用前端或扩展名给出的类型；单个食材的大小上限按类别区分，
+// 图片与文档的上限及总大小上限可在继续回复配置中调整。
+// 图片以 image 内容返回给 MCP 客户端，其他类别以 resource blob 返回
+
+use crate::config::ReplyConfig;
+use crate::constants::mcp;
+
+/// 食材类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IngredientKind {
+    Image,
+    Document,
+    Text,
+    Archive,
+}
+
+impl IngredientKind {
+    /// 单个食材的默认大小上限（字节）
+    pub fn max_bytes(self) -> u64 {
+        match self {
+            IngredientKind::Image => 8 * 1024 * 1024,
+            IngredientKind::Document => 8 * 1024 * 1024,
+            IngredientKind::Text => 1024 * 1024,
+            IngredientKind::Archive => 4 * 1024 * 1024,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            IngredientKind::Image => "图片",
+            IngredientKind::Document => "文档",
+            IngredientKind::Text => "文本",
+            IngredientKind::Archive => "压缩包",
+        }
+    }
+}
+
+/// 食材大小上限（字节）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IngredientLimits {
+    pub max_bytes: u64,
+    pub max_total_bytes: u64,
+}
+
+impl Default for IngredientLimits {
+    fn default() -> Self {
+        Self::new(mcp::DEFAULT_INGREDIENT_MAX_MB, mcp::DEFAULT_INGREDIENTS_TOTAL_MAX_MB)
+    }
+}
+
+impl IngredientLimits {
+    /// 按 MB 创建，超出允许范围的值被收敛到边界
+    pub fn new(max_mb: u32, total_max_mb: u32) -> Self {
+        let max_mb = max_mb.clamp(mcp::MIN_INGREDIENT_MAX_MB, mcp::MAX_INGREDIENT_MAX_MB);
+        let total_max_mb = total_max_mb.clamp(max_mb, mcp::MAX_INGREDIENTS_TOTAL_MAX_MB);
+        Self {
+            max_bytes: max_mb as u64 * 1024 * 1024,
+            max_total_bytes: total_max_mb as u64 * 1024 * 1024,
+        }
+    }
+
+    pub fn from_reply_config(config: &ReplyConfig) -> Self {
+        Self::new(config.ingredient_max_mb, config.ingredients_total_max_mb)
+    }
+
+    pub fn load() -> Self {
+        crate::config::load_standalone_config()
+            .map(|c| Self::from_reply_config(&c.reply_config))
+            .unwrap_or_default()
+    }
+
+    /// 单个食材的上限：图片与文档使用配置值，文本与压缩包不超过各自的默认上限
+    pub fn max_bytes_for(&self, kind: IngredientKind) -> u64 {
+        match kind {
+            IngredientKind::Image | IngredientKind::Document => self.max_bytes,
+            IngredientKind::Text | IngredientKind::Archive => kind.max_bytes().min(self.max_bytes),
+        }
+    }
+}
+
+/// 已知类型：(MIME, 扩展名, 类别)，同一 MIME 的第一项扩展名为保存时使用的扩展名
+const KNOWN_TYPES: &[(&str, &str, IngredientKind)] = &[
+    ("image/png", "png", IngredientKind::Image),
+    ("image/jpeg", "jpg", IngredientKind::Image),
+    ("image/jpeg", "jpeg", IngredientKind::Image),
+    ("image/webp", "webp", IngredientKind::Image),
+    ("image/gif", "gif", IngredientKind::Image),
+    ("image/bmp", "bmp", IngredientKind::Image),
+    ("image/tiff", "tiff", IngredientKind::Image),
+    ("image/tiff", "tif", IngredientKind::Image),
+    ("image/svg+xml", "svg", IngredientKind::Image),
+    ("image/heic", "heic", IngredientKind::Image),
+    ("image/heif", "heif", IngredientKind::Image),
+    ("image/avif", "avif", IngredientKind::Image),
+    ("application/pdf", "pdf", IngredientKind::Document),
+    ("text/plain", "txt", IngredientKind::Text),
+    ("text/plain", "log", IngredientKind::Text),
+    ("text/markdown", "md", IngredientKind::Text),
+    ("text/csv", "csv", IngredientKind::Text),
+    ("application/json", "json", IngredientKind::Text),
+    ("application/zip", "zip", IngredientKind::Archive),
+    ("application/gzip", "gz", IngredientKind::Archive),
+    ("application/x-tar", "tar", IngredientKind::Archive),
+    ("application/x-7z-compressed", "7z", IngredientKind::Archive),
+];
+
+/// 文本嗅探只检查开头这么多字节
+const TEXT_SNIFF_BYTES: usize = 8 * 1024;
+
+pub fn kind_of(dish_type: &str) -> Option<IngredientKind> {
+    let dish_type = dish_type.trim().to_ascii_lowercase();
+    KNOWN_TYPES
+        .iter()
+        .find(|(mime, _, _)| *mime == dish_type)
+        .map(|(_, _, kind)| *kind)
+        .or_else(|| dish_type.starts_with("text/").then_some(IngredientKind::Text))
+}
+
+/// 保存文件时使用的扩展名
+pub fn ext_for(dish_type: &str) -> Option<&'static str> {
+    let dish_type = dish_type.trim().to_ascii_lowercase();
+    KNOWN_TYPES
+        .iter()
+        .find(|(mime, _, _)| *mime == dish_type)
+        .map(|(_, ext, _)| *ext)
+}
+
+pub fn dish_type_from_extension(ext: &str) -> Option<&'static str> {
+    let ext = ext.trim_start_matches('.').to_ascii_lowercase();
+    KNOWN_TYPES
+        .iter()
+        .find(|(_, e, _)| *e == ext)
+        .map(|(mime, _, _)| *mime)
+}
+
+/// 按文件头魔数识别类型；无法识别的二进制内容返回 None，UTF-8 文本返回 text/plain
+pub fn sniff(bytes: &[u8]) -> Option<&'static str> {
+    let magic: &[(&[u8], &str)] = &[
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF87a", "image/gif"),
+        (b"GIF89a", "image/gif"),
+        (b"II*\0", "image/tiff"),
+        (b"MM\0*", "image/tiff"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"PK\x05\x06", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+        (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    ];
+    if let Some((_, mime)) = magic.iter().find(|(prefix, _)| bytes.starts_with(prefix)) {
+        return Some(mime);
+    }
+    if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        return Some("image/webp");
+    }
+    // BMP 只有两字节签名，再检查保留字段为 0，避免把以 "BM" 开头的文本误判为图片
+    if bytes.len() >= 26 && bytes.starts_with(b"BM") && bytes[6..10] == [0, 0, 0, 0] {
+        return Some("image/bmp");
+    }
+    if bytes.len() >= 262 && &bytes[257..262] == b"ustar" {
+        return Some("application/x-tar");
+    }
+    // HEIF 系列（ISO BMFF）：第 4 字节起为 ftyp 盒，其后是主品牌
+    if bytes.len() >= 12 && &bytes[4..8] == b"ftyp" {
+        match &bytes[8..12] {
+            b"avif" | b"avis" => return Some("image/avif"),
+            b"heic" | b"heix" | b"hevc" | b"hevx" | b"heim" | b"heis" => return Some("image/heic"),
+            b"mif1" | b"msf1" => return Some("image/heif"),
+            _ => {}
+        }
+    }
+
+    let head = &bytes[..bytes.len().min(TEXT_SNIFF_BYTES)];
+    let is_text = !head.contains(&0)
+        && match std::str::from_utf8(head) {
+            Ok(_) => true,
+            // 截断处的不完整字符不算非法
+            Err(e) => e.error_len().is_none(),
+        };
+    if !is_text {
+        return None;
+    }
+    if looks_like_svg(head) {
+        return Some("image/svg+xml");
+    }
+    Some("text/plain")
+}
+
+/// 文本开头（跳过 BOM、XML 声明、注释与 DOCTYPE 后）是 <svg 元素
+fn looks_like_svg(head: &[u8]) -> bool {
+    let text = String::from_utf8_lossy(head);
+    let mut rest = text.trim_start_matches('\u{feff}').trim_start();
+    loop {
+        let skipped = if rest.starts_with("<?") {
+            rest.find("?>").map(|i| &rest[i + 2..])
+        } else if rest.starts_with("<!--") {
+            rest.find("-->").map(|i| &rest[i + 3..])
+        } else if rest.starts_with("<!") {
+            rest.find('>').map(|i| &rest[i + 1..])
+        } else {
+            break;
+        };
+        match skipped {
+            Some(next) => rest = next.trim_start(),
+            None => return false,
+        }
+    }
+    rest.starts_with("<svg")
+}
+
+/// 确定食材的实际类型：以嗅探结果为准，文本内容保留声明的文本类型（如 text/markdown）
+pub fn resolve_dish_type(bytes: &[u8], declared: &str) -> Option<String> {
+    let declared = declared.trim().to_ascii_lowercase();
+    match sniff(bytes)? {
+        "text/plain" if kind_of(&declared) == Some(IngredientKind::Text) => Some(declared),
+        sniffed => Some(sniffed.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n...."), Some("image/png"));
+        assert_eq!(sniff(b"RIFF\0\0\0\0WEBPVP8 "), Some("image/webp"));
+        assert_eq!(sniff(b"%PDF-1.7\n"), Some("application/pdf"));
+        assert_eq!(sniff(b"PK\x03\x04\x14\0"), Some("application/zip"));
+        assert_eq!(sniff("# 标题\n".as_bytes()), Some("text/plain"));
+        assert_eq!(sniff(&"中文".as_bytes()[..4]), Some("text/plain"));
+        assert_eq!(sniff(b"BMW service notes"), Some("text/plain"));
+        assert_eq!(sniff(b"\0\x01\x02\x03"), None);
+        assert_eq!(sniff(b"\0\0\0\x1cftypheic\0\0\0\0"), Some("image/heic"));
+        assert_eq!(sniff(b"\0\0\0\x1cftypavif\0\0\0\0"), Some("image/avif"));
+        assert_eq!(
+            sniff(b"<?xml version=\"1.0\"?>\n<!-- icon -->\n<svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            Some("image/svg+xml")
+        );
+        assert_eq!(sniff(b"<html><svg/></html>"), Some("text/plain"));
+    }
+
+    #[test]
+    fn test_resolve_dish_type() {
+        // 扩展名谎报的类型以内容为准
+        assert_eq!(resolve_dish_type(b"%PDF-1.4", "image/png").as_deref(), Some("application/pdf"));
+        assert_eq!(resolve_dish_type(b"{\"a\":1}", "application/json").as_deref(), Some("application/json"));
+        assert_eq!(resolve_dish_type(b"plain", "image/png").as_deref(), Some("text/plain"));
+        assert_eq!(resolve_dish_type(b"\0\0\0", "application/octet-stream"), None);
+        assert_eq!(kind_of("text/x-python"), Some(IngredientKind::Text));
+        assert_eq!(ext_for("image/jpeg"), Some("jpg"));
+    }
+
+    #[test]
+    fn test_ingredient_limits() {
+        const MB: u64 = 1024 * 1024;
+        let limits = IngredientLimits::new(32, 8);
+        assert_eq!(limits.max_bytes, 32 * MB);
+        assert_eq!(limits.max_total_bytes, 32 * MB);
+        assert_eq!(limits.max_bytes_for(IngredientKind::Text), MB);
+        assert_eq!(IngredientLimits::new(0, 1000).max_total_bytes, 256 * MB);
+        assert_eq!(IngredientLimits::new(2, 16).max_bytes_for(IngredientKind::Archive), 2 * MB);
+    }
+}
@@ -1,6 +1,21 @@
 pub mod commands;
 pub mod pantry;
+pub mod pantry_crypto;
+pub mod pantry_metrics;
+pub mod ingredient_kind;
 pub mod history;
+pub mod history_index;
+pub mod history_crypto;
+pub mod history_blobs;
+pub mod history_edit;
+pub mod history_export;
+pub mod history_import;
+pub mod history_repair;
+pub mod history_stats;
+pub mod history_sync;
+pub mod history_trash;
+pub mod history_webhook;
+pub mod notify_webhook;
 pub mod server;
 pub mod tools;
 pub mod types;
@@ -0,0 +1,289 @@
+// 通知 Webhook
+// 配置 notify_webhook_url 后，打开请求（prompt.opened）与提交回复（prompt.answered）时向该地址 POST 通知，
+// 适用于 Mattermost、飞书、钉钉等只需要收到提醒、不需要在聊天中完成回复的场景。
+// 未配置模板时发送默认 JSON；配置了模板时按 {{变量}} 渲染请求体（语法与自定义 prompt 模板相同），
+// 变量值按 JSON 字符串转义，可直接写在模板的字符串中，{{payload}} 为完整的默认 JSON（不转义）。
+// 签名方式与历史 Webhook 相同：X-Bistro-Signature: sha256=<HMAC-SHA256(secret, body)>。
+// 发送在后台进行，失败只记录日志
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::config::McpConfig;
+use crate::mcp::history_webhook::{sign, SIGNATURE_HEADER};
+use crate::mcp::types::PopupRequest;
+use crate::ui::prompt_template::render_template;
+
+/// 单次推送超时（秒）
+const REQUEST_TIMEOUT_SECS: u64 = 15;
+
+/// 通知中请求内容与回复的最大长度（字符），超出部分截断
+const MAX_TEXT_CHARS: usize = 500;
+
+pub const EVENT_OPENED: &str = "prompt.opened";
+pub const EVENT_ANSWERED: &str = "prompt.answered";
+
+/// 模板可用的变量
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "event",
+    "text",
+    "request_id",
+    "message",
+    "options",
+    "project",
+    "project_path",
+    "requested_at",
+    "timestamp",
+    "selected_options",
+    "reply",
+    "responder",
+    "station",
+    "payload",
+];
+
+/// 通知 Webhook 配置（对应 McpConfig 中的 notify_webhook_* 字段）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct NotifyWebhookConfig {
+    pub url: Option<String>,
+    pub template: Option<String>,
+    pub secret: Option<String>,
+}
+
+impl NotifyWebhookConfig {
+    pub fn from_mcp_config(config: &McpConfig) -> Self {
+        Self {
+            url: config.notify_webhook_url.clone(),
+            template: config.notify_webhook_template.clone(),
+            secret: config.notify_webhook_secret.clone(),
+        }
+    }
+
+    pub fn apply_to(&self, config: &mut McpConfig) {
+        config.notify_webhook_url = self.url.as_ref().map(|u| u.trim().to_string()).filter(|u| !u.is_empty());
+        config.notify_webhook_template = self.template.clone().filter(|t| !t.trim().is_empty());
+        config.notify_webhook_secret = self.secret.clone().filter(|s| !s.is_empty());
+    }
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_TEXT_CHARS {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(MAX_TEXT_CHARS).collect();
+    truncated.push('…');
+    truncated
+}
+
+fn project_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// 默认 JSON 负载
+pub fn build_payload(event: &str, request: Option<&PopupRequest>, response: Option<&Value>, timestamp: &str) -> Value {
+    let project_path = request.and_then(|r| r.project_root_path.clone()).filter(|p| !p.trim().is_empty());
+    let mut payload = json!({
+        "event": event,
+        "timestamp": timestamp,
+        "request_id": request.map(|r| r.id.clone()),
+        "message": request.map(|r| truncate(&r.message)),
+        "options": request.and_then(|r| r.menu.clone()).unwrap_or_default(),
+        "project": project_path.as_deref().map(project_name),
+        "project_path": project_path,
+        "requested_at": request.and_then(|r| r.requested_at.clone()),
+    });
+    if let Some(response) = response {
+        let reply = response
+            .get("note")
+            .and_then(Value::as_str)
+            .or_else(|| response.as_str())
+            .map(truncate);
+        let ticket = response.get("ticket");
+        payload["selected_options"] = response.get("toppings").cloned().unwrap_or(json!([]));
+        payload["reply"] = json!(reply);
+        payload["responder"] = ticket.and_then(|t| t.get("responder")).cloned().unwrap_or(Value::Null);
+        payload["station"] = ticket.and_then(|t| t.get("station")).cloned().unwrap_or(Value::Null);
+    }
+    payload["text"] = json!(summary_text(&payload));
+    payload
+}
+
+fn string_list(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// 适合直接发到聊天中的一段文字
+fn summary_text(payload: &Value) -> String {
+    let field = |key: &str| payload.get(key).and_then(Value::as_str).unwrap_or_default();
+    let prefix = match field("project") {
+        "" => String::new(),
+        project => format!("[{}] ", project),
+    };
+
+    if field("event") == EVENT_ANSWERED {
+        let mut text = format!("✅ {}请求已回复", prefix);
+        if !field("responder").is_empty() {
+            text.push_str(&format!("（{}）", field("responder")));
+        }
+        let selected = string_list(payload.get("selected_options"));
+        if !selected.is_empty() {
+            text.push_str(&format!("\n选择：{}", selected.join("、")));
+        }
+        if !field("reply").is_empty() {
+            text.push_str(&format!("\n{}", field("reply")));
+        }
+        text
+    } else {
+        let mut text = format!("📨 {}有新的待回复请求\n{}", prefix, field("message"));
+        let options = string_list(payload.get("options"));
+        if !options.is_empty() {
+            text.push_str(&format!("\n选项：{}", options.join("、")));
+        }
+        text
+    }
+}
+
+/// 按 JSON 字符串转义（不含两端引号）
+fn json_escape(value: &str) -> String {
+    let quoted = Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// 模板变量的取值
+fn template_values(payload: &Value) -> HashMap<String, String> {
+    let mut values: HashMap<String, String> = TEMPLATE_VARIABLES
+        .iter()
+        .map(|name| {
+            let value = match payload.get(*name) {
+                Some(Value::String(s)) => s.clone(),
+                Some(Value::Array(_)) => string_list(payload.get(*name)).join(", "),
+                _ => String::new(),
+            };
+            (name.to_string(), json_escape(&value))
+        })
+        .collect();
+    values.insert("payload".to_string(), payload.to_string());
+    values
+}
+
+/// 生成请求体与 Content-Type；渲染结果不是合法 JSON 时按纯文本发送
+pub fn render_body(template: Option<&str>, payload: &Value) -> (String, &'static str) {
+    let body = match template.filter(|t| !t.trim().is_empty()) {
+        Some(template) => render_template(template, &template_values(payload)).text,
+        None => payload.to_string(),
+    };
+    let content_type = if serde_json::from_str::<Value>(&body).is_ok() {
+        "application/json"
+    } else {
+        "text/plain; charset=utf-8"
+    };
+    (body, content_type)
+}
+
+async fn send(config: &NotifyWebhookConfig, payload: &Value) -> Result<()> {
+    let Some(url) = config.url.as_deref().map(str::trim).filter(|u| !u.is_empty()) else {
+        bail!("未配置通知地址");
+    };
+    let (body, content_type) = render_body(config.template.as_deref(), payload);
+    let client = reqwest::Client::builder()
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build()?;
+    let mut request = client.post(url).header("Content-Type", content_type);
+    if let Some(secret) = config.secret.as_deref().filter(|s| !s.is_empty()) {
+        request = request.header(SIGNATURE_HEADER, sign(secret, &body));
+    }
+    let response = request.body(body).send().await?;
+    if !response.status().is_success() {
+        bail!("HTTP {}", response.status());
+    }
+    Ok(())
+}
+
+/// 已配置通知地址时在后台推送
+fn dispatch(event: &'static str, request: Option<&PopupRequest>, response: Option<&Value>) {
+    let config = match crate::config::load_standalone_config() {
+        Ok(c) => NotifyWebhookConfig::from_mcp_config(&c.mcp_config),
+        Err(_) => return,
+    };
+    if config.url.as_deref().is_none_or(|u| u.trim().is_empty()) {
+        return;
+    }
+    let Ok(handle) = tokio::runtime::Handle::try_current() else {
+        log::debug!("没有可用的异步运行时，跳过通知 Webhook");
+        return;
+    };
+
+    let payload = build_payload(event, request, response, &chrono::Utc::now().to_rfc3339());
+    handle.spawn(async move {
+        if let Err(e) = send(&config, &payload).await {
+            log::warn!("推送通知 Webhook 失败 ({}): {}", event, e);
+        }
+    });
+}
+
+/// 请求已打开（弹窗、Telegram 或 Slack）
+pub(crate) fn notify_prompt_opened(request: &PopupRequest) {
+    dispatch(EVENT_OPENED, Some(request), None);
+}
+
+/// 已收到回复（取消的交互不通知）
+pub(crate) fn notify_prompt_answered(request: Option<&PopupRequest>, response: &Value) {
+    dispatch(EVENT_ANSWERED, request, Some(response));
+}
+
+/// 用示例请求发送一次测试通知
+pub async fn send_test_notification(config: &NotifyWebhookConfig) -> Result<()> {
+    let request = PopupRequest {
+        id: "test".to_string(),
+        message: "这是一条测试通知".to_string(),
+        menu: Some(vec!["选项一".to_string(), "选项二".to_string()]),
+        chalkboard: false,
+        project_root_path: None,
+        session_id: None,
+        requested_at: Some(chrono::Utc::now().to_rfc3339()),
+    };
+    send(config, &build_payload(EVENT_OPENED, Some(&request), None, &chrono::Utc::now().to_rfc3339())).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_body() {
+        let request = PopupRequest {
+            id: "r1".to_string(),
+            message: "是否\"继续\"？".to_string(),
+            menu: Some(vec!["是".to_string(), "否".to_string()]),
+            chalkboard: false,
+            project_root_path: Some("/work/demo".to_string()),
+            session_id: None,
+            requested_at: None,
+        };
+        let response = json!({ "note": "好的", "toppings": ["是"], "ticket": { "station": "popup", "responder": "张三" } });
+        let payload = build_payload(EVENT_ANSWERED, Some(&request), Some(&response), "2026-01-01T00:00:00Z");
+        assert_eq!(payload["project"], "demo");
+        assert_eq!(payload["text"], "✅ [demo] 请求已回复（张三）\n选择：是\n好的");
+
+        let (body, content_type) = render_body(None, &payload);
+        assert_eq!(content_type, "application/json");
+        assert_eq!(serde_json::from_str::<Value>(&body).unwrap(), payload);
+
+        let opened = build_payload(EVENT_OPENED, Some(&request), None, "2026-01-01T00:00:00Z");
+        let (body, content_type) = render_body(Some(r#"{"msg_type":"text","content":{"text":"{{text}}"}}"#), &opened);
+        assert_eq!(content_type, "application/json");
+        let parsed: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(parsed["content"]["text"], "📨 [demo] 有新的待回复请求\n是否\"继续\"？\n选项：是、否");
+
+        let (body, content_type) = render_body(Some("{{event}} {{options}}"), &opened);
+        assert_eq!((body.as_str(), content_type), ("prompt.opened 是, 否", "text/plain; charset=utf-8"));
+    }
+}
@@ -1,15 +1,162 @@
 use anyhow::Result;
+use ring::digest::{digest, SHA256};
 use serde::{Deserialize, Serialize};
-use std::fs;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
 use std::path::{Path, PathBuf};
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use uuid::Uuid;
 
+use crate::mcp::pantry_metrics;
+
+/// 默认缓存总大小上限（512MB）
+pub const DEFAULT_PANTRY_MAX_BYTES: u64 = 512 * 1024 * 1024;
+
+/// 后台清理默认间隔（分钟）
+pub const DEFAULT_CLEANUP_INTERVAL_MINUTES: u64 = 60;
+
+/// 默认保留时长（小时），超过后由后台清理删除
+pub const DEFAULT_MAX_AGE_HOURS: u64 = 24 * 7;
+
+/// 分块上传的总大小上限，超过后拒绝继续追加
+pub const MAX_UPLOAD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// 未完成的分块上传保留时长，超过后在下次开始上传时删除
+const STALE_UPLOAD_AGE: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// 容量淘汰时跳过最近使用过的食材：刚取出或刚保存的食材可能仍在被引用
+const EVICTION_GRACE_SECS: u64 = 10 * 60;
+
+/// 等待缓存锁的最长时间
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 锁文件超过该时间未释放时视为持有进程已异常退出
+const STALE_LOCK_AGE: Duration = Duration::from_secs(30);
+
+/// 缓存目录锁，释放时删除锁文件
+///
+/// 主程序、弹窗进程和 MCP 服务都会保存、丢弃和淘汰食材，引用计数的读改写需要跨进程串行执行
+struct PantryLock {
+    path: PathBuf,
+}
+
+impl PantryLock {
+    fn acquire(base: &Path) -> Result<Self> {
+        let path = base.join(".lock");
+        let deadline = Instant::now() + LOCK_TIMEOUT;
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_stale_lock(&path) {
+                        log::warn!("清理过期的食材缓存锁文件: {:?}", path);
+                        let _ = fs::remove_file(&path);
+                        continue;
+                    }
+                    if Instant::now() >= deadline {
+                        anyhow::bail!("食材缓存正被其他进程使用，请稍后重试");
+                    }
+                    std::thread::sleep(LOCK_RETRY_INTERVAL);
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for PantryLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn is_stale_lock(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK_AGE)
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PantryLabel {
     pub dish_type: String,
     pub tag: Option<String>,
     pub size_bytes: u64,
+    /// 最近一次使用时间（Unix 秒），旧版本写入的食材没有该字段，按 label.json 修改时间计
+    #[serde(default)]
+    pub last_used_at: Option<u64>,
+    /// 内容的 SHA-256，相同内容再次保存时复用已有食材
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    /// 引用数：同一内容每保存一次加 1，丢弃时减 1，归零后删除
+    #[serde(default = "default_refs")]
+    pub refs: u32,
+}
+
+fn default_refs() -> u32 {
+    1
+}
+
+/// 清理结果
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct PantryCleanup {
+    pub removed: usize,
+    pub freed_bytes: u64,
+}
+
+/// 缓存清单中的一项
+#[derive(Debug, Serialize, Clone)]
+pub struct PantryItem {
+    pub spice_id: String,
+    pub dish_type: String,
+    pub tag: Option<String>,
+    pub size_bytes: u64,
+    pub refs: u32,
+    /// 保存后经过的秒数
+    pub age_secs: u64,
+    pub last_used_at: Option<u64>,
+}
+
+/// 后台清理配置（对应 McpConfig 中的 pantry_cleanup_interval_minutes / pantry_max_age_hours）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PantryJanitorConfig {
+    /// 清理间隔（分钟），0 表示关闭后台清理
+    pub interval_minutes: u64,
+    pub max_age_hours: u64,
+}
+
+impl PantryJanitorConfig {
+    pub fn from_mcp_config(config: &crate::config::McpConfig) -> Self {
+        Self {
+            interval_minutes: config
+                .pantry_cleanup_interval_minutes
+                .unwrap_or(DEFAULT_CLEANUP_INTERVAL_MINUTES),
+            max_age_hours: config.pantry_max_age_hours.unwrap_or(DEFAULT_MAX_AGE_HOURS),
+        }
+    }
+
+    pub fn apply_to(&self, config: &mut crate::config::McpConfig) {
+        config.pantry_cleanup_interval_minutes = Some(self.interval_minutes);
+        config.pantry_max_age_hours = Some(self.max_age_hours.max(1));
+    }
+
+    pub fn load() -> Self {
+        let config = crate::config::load_standalone_config()
+            .map(|c| c.mcp_config)
+            .unwrap_or_else(|_| crate::config::default_mcp_config());
+        Self::from_mcp_config(&config)
+    }
+
+    pub fn max_age(&self) -> Duration {
+        Duration::from_secs(self.max_age_hours.max(1) * 60 * 60)
+    }
 }
 
 pub fn pantry_base_dir() -> Result<PathBuf> {
@@ -27,50 +174,205 @@ fn spice_dir(base: &Path, spice_id: &str) -> PathBuf {
     base.join(spice_id)
 }
 
+/// 分块上传的临时目录（与缓存目录同级，避免被当作食材）
+fn upload_dir() -> Result<PathBuf> {
+    let base = pantry_base_dir()?;
+    let dir = base
+        .parent()
+        .map(|p| p.join("pantry-uploads"))
+        .unwrap_or_else(|| base.join("..").join("pantry-uploads"));
+    fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn upload_path(upload_id: &str) -> Result<PathBuf> {
+    // 上传 ID 由 begin_stash_upload 生成，只接受 UUID，防止路径穿越
+    let id = Uuid::parse_str(upload_id).map_err(|_| anyhow::anyhow!("无效的上传 ID: {}", upload_id))?;
+    Ok(upload_dir()?.join(format!("{}.part", id)))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 当前缓存总大小上限，0 表示不限制
+pub fn pantry_max_bytes() -> u64 {
+    crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.pantry_max_bytes)
+        .unwrap_or(DEFAULT_PANTRY_MAX_BYTES)
+}
+
+fn read_label(dir: &Path) -> Result<PantryLabel> {
+    Ok(serde_json::from_str(&fs::read_to_string(dir.join("label.json"))?)?)
+}
+
+fn write_label(dir: &Path, label: &PantryLabel) -> Result<()> {
+    fs::write(dir.join("label.json"), serde_json::to_string(label)?)?;
+    Ok(())
+}
+
+/// 由类型与内容摘要生成 spice_id，相同内容再次保存时直接定位到已有食材，无需遍历缓存目录
+fn content_spice_id(content_hash: &str, dish_type: &str) -> String {
+    let key = format!("{}:{}", dish_type, content_hash);
+    hex::encode(&digest(&SHA256, key.as_bytes()).as_ref()[..16])
+}
+
+/// 保存食材并返回 spice_id；内容与已有食材相同时返回已有的 spice_id 并增加其引用数
 pub fn stash_ingredient_bytes(bytes: &[u8], dish_type: &str, tag: Option<String>) -> Result<String> {
     let base = pantry_base_dir()?;
-    let spice_id = Uuid::new_v4().to_string();
+    let _lock = PantryLock::acquire(&base)?;
+    let content_hash = hex::encode(digest(&SHA256, bytes));
+    let spice_id = content_spice_id(&content_hash, dish_type);
     let dir = spice_dir(&base, &spice_id);
+
+    if let Ok(mut label) = read_label(&dir) {
+        if dir.join("ingredient.bin").exists() {
+            label.refs = label.refs.saturating_add(1);
+            label.last_used_at = Some(unix_now());
+            write_label(&dir, &label)?;
+            pantry_metrics::record_dedup_hit();
+            return Ok(spice_id);
+        }
+    }
+
     fs::create_dir_all(&dir)?;
 
-    fs::write(dir.join("ingredient.bin"), bytes)?;
+    crate::mcp::pantry_crypto::write(dir.join("ingredient.bin"), bytes)?;
 
     let label = PantryLabel {
         dish_type: dish_type.to_string(),
         tag,
         size_bytes: bytes.len() as u64,
+        last_used_at: Some(unix_now()),
+        content_hash: Some(content_hash),
+        refs: 1,
     };
-    fs::write(dir.join("label.json"), serde_json::to_string(&label)?)?;
+    write_label(&dir, &label)?;
+
+    match evict_over_quota(&base, pantry_max_bytes(), Some(&spice_id)) {
+        Ok(evicted) if evicted > 0 => log::info!("食材缓存超出上限，已淘汰 {} 项", evicted),
+        Ok(_) => {}
+        Err(e) => log::warn!("淘汰食材缓存失败: {}", e),
+    }
+    pantry_metrics::check_usage(pantry_total_bytes(&base).1);
 
     Ok(spice_id)
 }
 
 pub fn fetch_ingredient_bytes(spice_id: &str) -> Result<(Vec<u8>, PantryLabel)> {
     let base = pantry_base_dir()?;
+    let _lock = PantryLock::acquire(&base)?;
     let dir = spice_dir(&base, spice_id);
-    let label_str = fs::read_to_string(dir.join("label.json"))?;
-    let label: PantryLabel = serde_json::from_str(&label_str)?;
-    let bytes = fs::read(dir.join("ingredient.bin"))?;
+    let mut label = match read_label(&dir) {
+        Ok(label) => label,
+        Err(e) => {
+            pantry_metrics::record_miss();
+            return Err(e);
+        }
+    };
+    let bytes = crate::mcp::pantry_crypto::read(dir.join("ingredient.bin"))?;
+    pantry_metrics::record_hit();
+
+    label.last_used_at = Some(unix_now());
+    let _ = write_label(&dir, &label);
     Ok((bytes, label))
 }
 
+/// 开始一次分块上传，返回上传 ID；大文件分块追加后再提交，避免整个文件经过 IPC 的 JSON 序列化
+pub fn begin_stash_upload() -> Result<String> {
+    let dir = upload_dir()?;
+    let now = SystemTime::now();
+    for entry in fs::read_dir(&dir)?.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .map(|t| now.duration_since(t).unwrap_or(Duration::ZERO) > STALE_UPLOAD_AGE)
+            .unwrap_or(false);
+        if stale {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+
+    let upload_id = Uuid::new_v4().to_string();
+    fs::write(upload_path(&upload_id)?, [])?;
+    Ok(upload_id)
+}
+
+/// 追加一块数据，返回已上传的总字节数
+pub fn append_stash_upload(upload_id: &str, chunk: &[u8]) -> Result<u64> {
+    let path = upload_path(upload_id)?;
+    let current = fs::metadata(&path)
+        .map_err(|_| anyhow::anyhow!("上传不存在或已过期: {}", upload_id))?
+        .len();
+    let total = current + chunk.len() as u64;
+    if total > MAX_UPLOAD_BYTES {
+        let _ = fs::remove_file(&path);
+        anyhow::bail!("上传内容超过 {} MB", MAX_UPLOAD_BYTES / (1024 * 1024));
+    }
+    let mut file = fs::OpenOptions::new().append(true).open(&path)?;
+    file.write_all(chunk)?;
+    Ok(total)
+}
+
+/// 结束上传并取出全部内容（临时文件随即删除）
+pub fn take_stash_upload(upload_id: &str) -> Result<Vec<u8>> {
+    let path = upload_path(upload_id)?;
+    let bytes = fs::read(&path).map_err(|_| anyhow::anyhow!("上传不存在或已过期: {}", upload_id))?;
+    let _ = fs::remove_file(&path);
+    Ok(bytes)
+}
+
+/// 放弃上传
+pub fn abort_stash_upload(upload_id: &str) -> Result<()> {
+    let path = upload_path(upload_id)?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+/// 丢弃一次引用，引用归零时删除食材
 pub fn discard_spice(spice_id: &str) -> Result<()> {
     let base = pantry_base_dir()?;
+    let _lock = PantryLock::acquire(&base)?;
     let dir = spice_dir(&base, spice_id);
-    if dir.exists() {
-        let _ = fs::remove_dir_all(dir);
+    if !dir.exists() {
+        return Ok(());
     }
+    if let Ok(mut label) = read_label(&dir) {
+        if label.refs > 1 {
+            label.refs -= 1;
+            return write_label(&dir, &label);
+        }
+    }
+    let _ = fs::remove_dir_all(dir);
     Ok(())
 }
 
-pub fn clean_expired_pantry_items(max_age: Duration) -> Result<usize> {
+fn dir_size(dir: &Path) -> u64 {
+    fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|e| e.metadata().ok())
+        .filter(|m| m.is_file())
+        .map(|m| m.len())
+        .sum()
+}
+
+pub fn clean_expired_pantry_items(max_age: Duration) -> Result<PantryCleanup> {
     let base = pantry_base_dir()?;
+    let _lock = PantryLock::acquire(&base)?;
     let now = SystemTime::now();
-    let mut deleted = 0usize;
+    let mut cleanup = PantryCleanup::default();
 
     let entries = match fs::read_dir(&base) {
         Ok(v) => v,
-        Err(_) => return Ok(0),
+        Err(_) => return Ok(cleanup),
     };
 
     for entry in entries.flatten() {
@@ -94,10 +396,262 @@ pub fn clean_expired_pantry_items(max_age: Duration) -> Result<usize> {
             continue;
         }
 
+        let size = dir_size(&path);
+        if fs::remove_dir_all(&path).is_ok() {
+            cleanup.removed += 1;
+            cleanup.freed_bytes += size;
+        }
+    }
+
+    Ok(cleanup)
+}
+
+/// 列出缓存中的食材（最近保存的在前）
+pub fn list_pantry_items() -> Result<Vec<PantryItem>> {
+    let base = pantry_base_dir()?;
+    let now = SystemTime::now();
+    let mut items: Vec<PantryItem> = fs::read_dir(&base)?
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| {
+            let dir = e.path();
+            let label = read_label(&dir).ok()?;
+            let stashed_at = fs::metadata(dir.join("ingredient.bin")).and_then(|m| m.modified()).ok()?;
+            Some(PantryItem {
+                spice_id: e.file_name().to_string_lossy().to_string(),
+                dish_type: label.dish_type,
+                tag: label.tag,
+                size_bytes: label.size_bytes,
+                refs: label.refs,
+                age_secs: now.duration_since(stashed_at).unwrap_or(Duration::ZERO).as_secs(),
+                last_used_at: label.last_used_at,
+            })
+        })
+        .collect();
+    items.sort_by_key(|item| item.age_secs);
+    Ok(items)
+}
+
+/// 批量删除食材（忽略引用数），返回删除数与释放的字节数
+pub fn remove_pantry_items(spice_ids: &[String]) -> Result<PantryCleanup> {
+    let base = pantry_base_dir()?;
+    let _lock = PantryLock::acquire(&base)?;
+    let mut cleanup = PantryCleanup::default();
+    for spice_id in spice_ids {
+        // spice_id 只能是缓存目录下的一级目录名
+        if spice_id.is_empty() || spice_id.contains(['/', '\\']) || spice_id.starts_with('.') {
+            continue;
+        }
+        let dir = spice_dir(&base, spice_id);
+        if !dir.is_dir() {
+            continue;
+        }
+        let size = dir_size(&dir);
+        if fs::remove_dir_all(&dir).is_ok() {
+            cleanup.removed += 1;
+            cleanup.freed_bytes += size;
+        }
+    }
+    Ok(cleanup)
+}
+
+/// 清空食材缓存（尚未提交的食材也会被删除，仍被多处引用的食材保留）
+pub fn clear_pantry() -> Result<PantryCleanup> {
+    let base = pantry_base_dir()?;
+    let _lock = PantryLock::acquire(&base)?;
+    clear_pantry_dir(&base)
+}
+
+/// clear_pantry 的实现，调用方需持有 PantryLock
+fn clear_pantry_dir(base: &Path) -> Result<PantryCleanup> {
+    let mut cleanup = PantryCleanup::default();
+    for entry in fs::read_dir(base)?.flatten() {
+        let path = entry.path();
+        if !path.is_dir() || read_label(&path).is_ok_and(|label| label.refs > 1) {
+            continue;
+        }
+        let size = dir_size(&path);
         if fs::remove_dir_all(&path).is_ok() {
-            deleted += 1;
+            cleanup.removed += 1;
+            cleanup.freed_bytes += size;
         }
     }
+    Ok(cleanup)
+}
+
+/// 启动后台清理任务：启动时清理一次，之后按配置的间隔定期清理（每轮重新读取配置）
+pub fn spawn_pantry_janitor() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            let config = PantryJanitorConfig::load();
+            let max_age = config.max_age();
+            match tokio::task::spawn_blocking(move || clean_expired_pantry_items(max_age)).await {
+                Ok(Ok(cleanup)) if cleanup.removed > 0 => {
+                    log::info!("已清理过期食材: {} 项，释放 {} 字节", cleanup.removed, cleanup.freed_bytes);
+                }
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => log::warn!("清理过期食材失败: {}", e),
+                Err(e) => log::warn!("清理过期食材失败: {}", e),
+            }
+
+            // 关闭后台清理时仍按默认间隔检查配置，重新开启后无需重启
+            let minutes = match config.interval_minutes {
+                0 => DEFAULT_CLEANUP_INTERVAL_MINUTES,
+                m => m,
+            };
+            tokio::time::sleep(Duration::from_secs(minutes * 60)).await;
+            while PantryJanitorConfig::load().interval_minutes == 0 {
+                tokio::time::sleep(Duration::from_secs(DEFAULT_CLEANUP_INTERVAL_MINUTES * 60)).await;
+            }
+        }
+    });
+}
+
+/// 缓存中的一项食材（用于容量淘汰）
+struct PantryUsage {
+    spice_id: String,
+    size_bytes: u64,
+    last_used_at: u64,
+    refs: u32,
+}
+
+fn pantry_usage(base: &Path) -> Vec<PantryUsage> {
+    let Ok(entries) = fs::read_dir(base) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| {
+            let label_path = e.path().join("label.json");
+            let label: PantryLabel = serde_json::from_str(&fs::read_to_string(&label_path).ok()?).ok()?;
+            let last_used_at = label.last_used_at.unwrap_or_else(|| {
+                fs::metadata(&label_path)
+                    .and_then(|m| m.modified())
+                    .ok()
+                    .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0)
+            });
+            Some(PantryUsage {
+                spice_id: e.file_name().to_string_lossy().to_string(),
+                size_bytes: label.size_bytes,
+                last_used_at,
+                refs: label.refs,
+            })
+        })
+        .collect()
+}
+
+/// 缓存中的食材数与总大小
+pub(crate) fn pantry_total_bytes(base: &Path) -> (usize, u64) {
+    let items = pantry_usage(base);
+    (items.len(), items.iter().map(|i| i.size_bytes).sum())
+}
+
+/// 选出需要淘汰的食材：按最近使用时间从旧到新淘汰，直到总大小不超过上限
+///
+/// keep 指定的食材、仍被多处引用的食材以及宽限期内用过的食材不淘汰，此时总大小可能仍超过上限
+fn select_evictions(mut items: Vec<PantryUsage>, max_bytes: u64, keep: Option<&str>, now: u64) -> Vec<String> {
+    let mut total: u64 = items.iter().map(|i| i.size_bytes).sum();
+    items.sort_by_key(|i| i.last_used_at);
+
+    let mut evicted = Vec::new();
+    for item in items {
+        if total <= max_bytes {
+            break;
+        }
+        if keep == Some(item.spice_id.as_str())
+            || item.refs > 1
+            || now.saturating_sub(item.last_used_at) < EVICTION_GRACE_SECS
+        {
+            continue;
+        }
+        total = total.saturating_sub(item.size_bytes);
+        evicted.push(item.spice_id);
+    }
+    evicted
+}
 
-    Ok(deleted)
+/// 缓存总大小超过 max_bytes 时淘汰最久未使用的食材，返回淘汰数（max_bytes 为 0 表示不限制）
+pub fn enforce_pantry_quota(max_bytes: u64, keep: Option<&str>) -> Result<usize> {
+    let base = pantry_base_dir()?;
+    let _lock = PantryLock::acquire(&base)?;
+    evict_over_quota(&base, max_bytes, keep)
+}
+
+/// enforce_pantry_quota 的实现，调用方需持有 PantryLock
+fn evict_over_quota(base: &Path, max_bytes: u64, keep: Option<&str>) -> Result<usize> {
+    if max_bytes == 0 {
+        return Ok(0);
+    }
+    let evicted = select_evictions(pantry_usage(base), max_bytes, keep, unix_now())
+        .into_iter()
+        .filter(|spice_id| fs::remove_dir_all(spice_dir(base, spice_id)).is_ok())
+        .count();
+    pantry_metrics::record_evictions(evicted);
+    Ok(evicted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(spice_id: &str, size_bytes: u64, last_used_at: u64) -> PantryUsage {
+        PantryUsage { spice_id: spice_id.to_string(), size_bytes, last_used_at, refs: 1 }
+    }
+
+    const NOW: u64 = 100_000;
+
+    #[test]
+    fn test_select_evictions() {
+        let items = || vec![usage("new", 40, 300), usage("old", 40, 100), usage("mid", 40, 200)];
+        assert!(select_evictions(items(), 120, None, NOW).is_empty());
+        assert_eq!(select_evictions(items(), 80, None, NOW), vec!["old"]);
+        assert_eq!(select_evictions(items(), 50, None, NOW), vec!["old", "mid"]);
+        // 刚保存的食材即使最旧也保留
+        assert_eq!(select_evictions(items(), 50, Some("old"), NOW), vec!["mid", "new"]);
+    }
+
+    #[test]
+    fn test_select_evictions_skips_shared_and_recent() {
+        let shared = PantryUsage { refs: 2, ..usage("shared", 40, 100) };
+        let recent = usage("recent", 40, NOW - 60);
+        let items = vec![shared, recent, usage("mid", 40, 200)];
+        assert_eq!(select_evictions(items, 0, None, NOW), vec!["mid"]);
+    }
+
+    #[test]
+    fn test_content_spice_id() {
+        let hash = hex::encode(digest(&SHA256, b"png bytes"));
+        assert_eq!(content_spice_id(&hash, "image"), content_spice_id(&hash, "image"));
+        assert_ne!(content_spice_id(&hash, "image"), content_spice_id(&hash, "file"));
+        assert_eq!(content_spice_id(&hash, "image").len(), 32);
+    }
+
+    #[test]
+    fn test_clear_pantry_dir_keeps_shared() {
+        let base = std::env::temp_dir().join(format!("pantry-test-{}", Uuid::new_v4()));
+        for (spice_id, refs) in [("fresh", 1), ("shared", 2)] {
+            let dir = spice_dir(&base, spice_id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join("ingredient.bin"), b"data").unwrap();
+            let label = PantryLabel {
+                dish_type: "image".to_string(),
+                tag: None,
+                size_bytes: 4,
+                last_used_at: Some(unix_now()),
+                content_hash: None,
+                refs,
+            };
+            write_label(&dir, &label).unwrap();
+        }
+
+        // 刚保存的食材也会被删除
+        let cleanup = clear_pantry_dir(&base).unwrap();
+        assert_eq!(cleanup.removed, 1);
+        assert!(!spice_dir(&base, "fresh").exists());
+        assert!(spice_dir(&base, "shared").exists());
+        fs::remove_dir_all(&base).unwrap();
+    }
 }
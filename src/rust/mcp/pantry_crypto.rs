@@ -0,0 +1,55 @@
+// 食材缓存加密
+// pantry 中缓存的截图可能包含凭据或内部系统页面。开启 pantry_encryption 后 ingredient.bin 加密写入
+// （见 utils::sealed_store，与历史记录、本地索引缓存使用不同的密钥）。
+// label.json 仍为明文（只含类型、文件名、大小与内容摘要）。读取时按文件头自动识别是否加密，开关切换时会一次性迁移已有食材
+
+use anyhow::Result;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use once_cell::sync::Lazy;
+
+use crate::log_important;
+use crate::mcp::pantry::pantry_base_dir;
+use crate::utils::sealed_store::SealedStore;
+
+static STORE: SealedStore = SealedStore::new(b"BISTRO-PANTRY1\n", "devkit-pantry", "pantry-encryption-key", "食材缓存");
+
+/// 是否加密写入（首次使用时从配置读取，切换开关时更新）
+///
+/// 食材只由界面进程写入，每个进程读取一次即可；切换开关前已打开的弹窗仍按旧状态写入，读取按文件头识别不受影响
+static ENABLED: Lazy<AtomicBool> = Lazy::new(|| {
+    let enabled = crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.pantry_encryption)
+        .unwrap_or(false);
+    AtomicBool::new(enabled)
+});
+
+/// 当前是否开启食材缓存加密
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// 读取食材内容（加密文件自动解密）
+pub(crate) fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    STORE.read(path)
+}
+
+/// 写入食材内容（开启加密时加密写入）
+pub(crate) fn write(path: impl AsRef<Path>, data: impl AsRef<[u8]>) -> io::Result<()> {
+    STORE.write(path, data, is_enabled())
+}
+
+/// 切换食材缓存加密并迁移已有食材，返回迁移的文件数
+pub fn set_enabled(enabled: bool) -> Result<usize> {
+    let base = pantry_base_dir()?;
+    let files = fs::read_dir(&base)?.flatten().map(|entry| entry.path().join("ingredient.bin"));
+    let migrated = STORE.migrate(files, enabled)?;
+
+    ENABLED.store(enabled, Ordering::SeqCst);
+    log_important!(info, "食材缓存加密已{}，迁移文件数: {}", if enabled { "开启" } else { "关闭" }, migrated);
+    Ok(migrated)
+}
@@ -0,0 +1,140 @@
+// 食材缓存统计
+// 记录本次启动以来的命中 / 未命中 / 内容复用 / 容量淘汰次数，连同当前缓存大小提供给设置界面。
+// 缓存大小达到上限的 pantry_warn_percent（默认 80%）时推送 `pantry://usage-warning` 事件，
+// 回落到阈值以下后才会再次提醒。MCP 独立进程中没有 AppHandle，此时事件静默丢弃
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use anyhow::Result;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use crate::mcp::pantry::{pantry_base_dir, pantry_max_bytes, pantry_total_bytes};
+
+/// 缓存用量警告事件名
+pub const PANTRY_WARNING_EVENT: &str = "pantry://usage-warning";
+
+/// 默认警告阈值（占缓存上限的百分比）
+pub const DEFAULT_PANTRY_WARN_PERCENT: u8 = 80;
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+static HITS: AtomicU64 = AtomicU64::new(0);
+static MISSES: AtomicU64 = AtomicU64::new(0);
+static DEDUP_HITS: AtomicU64 = AtomicU64::new(0);
+static EVICTIONS: AtomicU64 = AtomicU64::new(0);
+
+/// 是否已经提醒过（回落到阈值以下后复位）
+static WARNED: AtomicBool = AtomicBool::new(false);
+
+/// 注册用于推送警告事件的 AppHandle（GUI 启动时调用一次）
+pub fn set_pantry_app_handle(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// 食材缓存统计
+#[derive(Debug, Clone, Serialize)]
+pub struct PantryMetrics {
+    /// 读取食材成功次数
+    pub hits: u64,
+    /// 读取时食材已不存在（过期、被淘汰或被删除）的次数
+    pub misses: u64,
+    /// 保存时内容相同、复用已有食材的次数
+    pub dedup_hits: u64,
+    /// 超出容量上限被淘汰的食材数
+    pub evictions: u64,
+    pub item_count: usize,
+    pub total_bytes: u64,
+    /// 缓存上限（字节），0 表示不限制
+    pub max_bytes: u64,
+    pub warn_percent: u8,
+}
+
+/// 缓存用量警告事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct PantryUsageWarning {
+    pub total_bytes: u64,
+    pub max_bytes: u64,
+    pub percent: u64,
+}
+
+pub(crate) fn record_hit() {
+    HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_miss() {
+    MISSES.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_dedup_hit() {
+    DEDUP_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn record_evictions(count: usize) {
+    EVICTIONS.fetch_add(count as u64, Ordering::Relaxed);
+}
+
+pub fn pantry_warn_percent() -> u8 {
+    crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.pantry_warn_percent)
+        .unwrap_or(DEFAULT_PANTRY_WARN_PERCENT)
+        .clamp(1, 100)
+}
+
+pub fn pantry_metrics() -> Result<PantryMetrics> {
+    let (item_count, total_bytes) = pantry_total_bytes(&pantry_base_dir()?);
+    Ok(PantryMetrics {
+        hits: HITS.load(Ordering::Relaxed),
+        misses: MISSES.load(Ordering::Relaxed),
+        dedup_hits: DEDUP_HITS.load(Ordering::Relaxed),
+        evictions: EVICTIONS.load(Ordering::Relaxed),
+        item_count,
+        total_bytes,
+        max_bytes: pantry_max_bytes(),
+        warn_percent: pantry_warn_percent(),
+    })
+}
+
+/// 用量占上限的百分比；上限为 0（不限制）时返回 None
+fn usage_percent(total_bytes: u64, max_bytes: u64) -> Option<u64> {
+    (max_bytes > 0).then(|| total_bytes.saturating_mul(100) / max_bytes)
+}
+
+/// 缓存大小变化后检查是否需要提醒
+pub(crate) fn check_usage(total_bytes: u64) {
+    let max_bytes = pantry_max_bytes();
+    let Some(percent) = usage_percent(total_bytes, max_bytes) else {
+        WARNED.store(false, Ordering::Relaxed);
+        return;
+    };
+    if percent < pantry_warn_percent() as u64 {
+        WARNED.store(false, Ordering::Relaxed);
+        return;
+    }
+    if WARNED.swap(true, Ordering::Relaxed) {
+        return;
+    }
+
+    log::warn!("食材缓存已使用 {}%（{} / {} 字节）", percent, total_bytes, max_bytes);
+    if let Some(app) = APP_HANDLE.get() {
+        let warning = PantryUsageWarning { total_bytes, max_bytes, percent };
+        if let Err(e) = app.emit(PANTRY_WARNING_EVENT, &warning) {
+            log::debug!("推送缓存用量警告失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_percent() {
+        assert_eq!(usage_percent(400, 1000), Some(40));
+        assert_eq!(usage_percent(1200, 1000), Some(120));
+        assert_eq!(usage_percent(u64::MAX, 1000), Some(u64::MAX / 1000));
+        assert_eq!(usage_percent(400, 0), None);
+    }
+}
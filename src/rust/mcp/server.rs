@@ -8,15 +8,85 @@ use rmcp::{
 use rmcp::model::*;
 use std::collections::HashMap;
 
-use super::tools::{InteractionTool, MemoryTool, AcemcpTool, DocsTool};
+use super::tools::{InteractionTool, MemoryTool, AcemcpTool, DocsTool, ContextTool, RecallTool};
 use super::types::{CacheRequest, StoreRequest};
+use crate::mcp::tools::context::ContextRequest;
+use crate::mcp::tools::docs::streaming::DocsProgress;
 use crate::mcp::tools::docs::types::DocsRequest;
-use crate::config::load_standalone_config;
+use crate::mcp::tools::recall::HistoryRecallRequest;
+use crate::config::{load_standalone_config, McpConfig};
+use crate::mcp::tools::acemcp::mcp::normalize_project_root;
 use crate::{log_important, log_debug};
 
+/// Request `_meta` keys a client may use to pass its own session id
+const SESSION_META_KEYS: [&str; 2] = ["sessionId", "session_id"];
+
+/// Request `_meta` keys a client may use to pass the project it works in
+const PROJECT_META_KEYS: [&str; 2] = ["projectRoot", "project_root"];
+
+/// Tool arguments that name a single project
+const PROJECT_ARG_KEYS: [&str; 2] = ["project_root_path", "project_path"];
+
+/// Projects a request refers to, used for per-project tool overrides
+#[derive(Debug, Default)]
+struct ProjectContext {
+    roots: Vec<String>,
+    workspace_group: Option<String>,
+}
+
+impl ProjectContext {
+    /// Tool arguments take precedence over `_meta`
+    fn from_request(arguments: Option<&serde_json::Map<String, serde_json::Value>>, meta: &Meta) -> Self {
+        let mut context = Self::default();
+        if let Some(args) = arguments {
+            context.roots.extend(
+                PROJECT_ARG_KEYS
+                    .iter()
+                    .filter_map(|key| args.get(*key).and_then(|v| v.as_str()))
+                    .map(str::to_string),
+            );
+            if let Some(paths) = args.get("project_root_paths").and_then(|v| v.as_array()) {
+                context.roots.extend(paths.iter().filter_map(|v| v.as_str()).map(str::to_string));
+            }
+            context.workspace_group = args.get("workspace_group").and_then(|v| v.as_str()).map(str::to_string);
+        }
+        if context.roots.is_empty() && context.workspace_group.is_none() {
+            context.roots.extend(
+                PROJECT_META_KEYS
+                    .iter()
+                    .find_map(|key| meta.get(*key).and_then(|v| v.as_str()))
+                    .map(str::to_string),
+            );
+        }
+        context.roots.retain(|root| !root.trim().is_empty());
+        context
+    }
+
+    fn is_empty(&self) -> bool {
+        self.roots.is_empty() && self.workspace_group.is_none()
+    }
+
+    /// Normalized project roots, with the workspace group expanded
+    fn resolve(&self, mcp_config: &McpConfig) -> Vec<String> {
+        let group = self
+            .workspace_group
+            .as_ref()
+            .and_then(|name| mcp_config.acemcp_workspace_groups.as_ref()?.get(name))
+            .into_iter()
+            .flatten();
+        self.roots
+            .iter()
+            .chain(group)
+            .map(|root| normalize_project_root(root.trim()))
+            .collect()
+    }
+}
+
 #[derive(Clone)]
 pub struct DevkitServer {
     enabled_tools: HashMap<String, bool>,
+    /// One stdio server process serves one agent session
+    session_id: String,
 }
 
 impl Default for DevkitServer {
@@ -36,16 +106,39 @@ impl DevkitServer {
             }
         };
 
-        Self { enabled_tools }
+        Self {
+            enabled_tools,
+            session_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Session id for a request: explicit `_meta` value first, then this server's session
+    fn session_id_for(&self, context: &RequestContext<RoleServer>) -> String {
+        SESSION_META_KEYS
+            .iter()
+            .find_map(|key| context.meta.get(*key).and_then(|v| v.as_str()))
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .unwrap_or_else(|| self.session_id.clone())
     }
 
     /// Check if tool is enabled - reads latest config
-    fn is_tool_enabled(&self, tool_name: &str) -> bool {
+    /// `project` is None when listing tools: a tool enabled for any project is listed,
+    /// and the call itself is checked against the projects it names
+    fn is_tool_enabled(&self, tool_name: &str, project: Option<&ProjectContext>) -> bool {
         // Re-read config each time to get latest state
         match load_standalone_config() {
             Ok(config) => {
-                let enabled = config.mcp_config.tools.get(tool_name).copied().unwrap_or(true);
-                log_debug!("Tool {} status: {}", tool_name, enabled);
+                let mcp_config = &config.mcp_config;
+                let enabled = match project {
+                    Some(project) if !project.is_empty() => {
+                        mcp_config.is_tool_enabled_for(tool_name, &project.resolve(mcp_config))
+                    }
+                    Some(_) => mcp_config.is_tool_enabled(tool_name),
+                    None => mcp_config.is_tool_enabled_anywhere(tool_name),
+                };
+                log_debug!("Tool {} status: {} (project: {:?})", tool_name, enabled, project);
                 enabled
             }
             Err(e) => {
@@ -84,11 +177,15 @@ impl ServerHandler for DevkitServer {
     async fn list_tools(
         &self,
         _request: Option<PaginatedRequestParam>,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<ListToolsResult, McpError> {
         use std::sync::Arc;
         use std::borrow::Cow;
 
+        // Only known when the client passes its project in `_meta`
+        let project = ProjectContext::from_request(None, &context.meta);
+        let project = (!project.is_empty()).then_some(&project);
+
         let mut tools = Vec::new();
 
         // Cache tool - stores data for async retrieval
@@ -183,13 +280,13 @@ impl ServerHandler for DevkitServer {
         }
 
         // Memory tool - only when enabled
-        if self.is_tool_enabled("store") {
+        if self.is_tool_enabled("store", project) {
             let ji_schema = serde_json::json!({
                 "type": "object",
                 "properties": {
                     "action": {
                         "type": "string",
-                        "description": "Operation type: store (add entry), recall (get project info)"
+                        "description": "Operation type: store (add entry), recall (get memories as Markdown grouped by category, with structured JSON)"
                     },
                     "project_path": {
                         "type": "string",
@@ -202,6 +299,10 @@ impl ServerHandler for DevkitServer {
                     "category": {
                         "type": "string",
                         "description": "Category: rule, preference, pattern, context"
+                    },
+                    "context_key": {
+                        "type": "string",
+                        "description": "Optional context key (e.g. git branch name) to scope entries; recall merges context entries over project-level ones"
                     }
                 },
                 "required": ["action", "project_path"]
@@ -228,15 +329,25 @@ impl ServerHandler for DevkitServer {
         }
 
         // Index tool - only when enabled
-        if self.is_tool_enabled("index") {
+        if self.is_tool_enabled("index", project) {
             tools.push(AcemcpTool::get_tool_definition());
         }
 
         // Docs tool - only when enabled
-        if self.is_tool_enabled("docs") {
+        if self.is_tool_enabled("docs", project) {
             tools.push(DocsTool::get_tool_definition());
         }
 
+        // Context tool - combines index and docs, so both must be enabled
+        if self.is_tool_enabled("index", project) && self.is_tool_enabled("docs", project) {
+            tools.push(ContextTool::get_tool_definition());
+        }
+
+        // History recall tool - only when enabled
+        if self.is_tool_enabled("history_recall", project) {
+            tools.push(RecallTool::get_tool_definition());
+        }
+
         log_debug!("Tools returned to client: {:?}", tools.iter().map(|t| &t.name).collect::<Vec<_>>());
 
         Ok(ListToolsResult {
@@ -249,9 +360,10 @@ impl ServerHandler for DevkitServer {
     async fn call_tool(
         &self,
         request: CallToolRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> Result<CallToolResult, McpError> {
         log_debug!("Tool call request: {}", request.name);
+        let project = ProjectContext::from_request(request.arguments.as_ref(), &context.meta);
 
         match request.name.as_ref() {
             "cache" => {
@@ -259,8 +371,11 @@ impl ServerHandler for DevkitServer {
                     .map(serde_json::Value::Object)
                     .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
 
-                let cache_request: CacheRequest = serde_json::from_value(arguments_value)
+                let mut cache_request: CacheRequest = serde_json::from_value(arguments_value)
                     .map_err(|e| McpError::invalid_params(format!("Parameter parse error: {}", e), None))?;
+                if cache_request.session_id.is_none() {
+                    cache_request.session_id = Some(self.session_id_for(&context));
+                }
 
                 // Use async version that returns immediately
                 InteractionTool::prompt_start(cache_request).await
@@ -270,8 +385,11 @@ impl ServerHandler for DevkitServer {
                     .map(serde_json::Value::Object)
                     .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
 
-                let cache_request: CacheRequest = serde_json::from_value(arguments_value)
+                let mut cache_request: CacheRequest = serde_json::from_value(arguments_value)
                     .map_err(|e| McpError::invalid_params(format!("Parameter parse error: {}", e), None))?;
+                if cache_request.session_id.is_none() {
+                    cache_request.session_id = Some(self.session_id_for(&context));
+                }
 
                 InteractionTool::prompt_sync(cache_request).await
             }
@@ -289,7 +407,7 @@ impl ServerHandler for DevkitServer {
             }
             "store" => {
                 // Check if store tool is enabled
-                if !self.is_tool_enabled("store") {
+                if !self.is_tool_enabled("store", Some(&project)) {
                     return Err(McpError::internal_error(
                         "Memory tool is disabled".to_string(),
                         None
@@ -306,7 +424,7 @@ impl ServerHandler for DevkitServer {
                 MemoryTool::store(store_request).await
             }
             "index" => {
-                if !self.is_tool_enabled("index") {
+                if !self.is_tool_enabled("index", Some(&project)) {
                     return Err(McpError::internal_error(
                         "Search tool is disabled".to_string(),
                         None
@@ -317,13 +435,22 @@ impl ServerHandler for DevkitServer {
                     .map(serde_json::Value::Object)
                     .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
 
+                // Multi-project mode when project_root_paths or workspace_group is given
+                let is_multi = arguments_value.get("project_root_paths").is_some()
+                    || arguments_value.get("workspace_group").is_some();
+                if is_multi {
+                    let multi_request: crate::mcp::tools::acemcp::types::AcemcpMultiRequest = serde_json::from_value(arguments_value)
+                        .map_err(|e| McpError::invalid_params(format!("Parameter parse error: {}", e), None))?;
+                    return AcemcpTool::search_context_multi(multi_request).await;
+                }
+
                 let acemcp_request: crate::mcp::tools::acemcp::types::AcemcpRequest = serde_json::from_value(arguments_value)
                     .map_err(|e| McpError::invalid_params(format!("Parameter parse error: {}", e), None))?;
 
                 AcemcpTool::search_context(acemcp_request).await
             }
             "docs" => {
-                if !self.is_tool_enabled("docs") {
+                if !self.is_tool_enabled("docs", Some(&project)) {
                     return Err(McpError::internal_error(
                         "Docs tool is disabled".to_string(),
                         None
@@ -337,7 +464,45 @@ impl ServerHandler for DevkitServer {
                 let docs_request: DocsRequest = serde_json::from_value(arguments_value)
                     .map_err(|e| McpError::invalid_params(format!("Parameter parse error: {}", e), None))?;
 
-                DocsTool::query_docs(docs_request).await
+                // Report page fetches when the client asked for progress notifications
+                let progress = context.meta.get_progress_token()
+                    .map(|token| DocsProgress::new(context.peer.clone(), token));
+
+                DocsTool::query_docs(docs_request, progress).await
+            }
+            "context" => {
+                if !self.is_tool_enabled("index", Some(&project)) || !self.is_tool_enabled("docs", Some(&project)) {
+                    return Err(McpError::internal_error(
+                        "Context tool requires both the search and docs tools to be enabled".to_string(),
+                        None
+                    ));
+                }
+
+                let arguments_value = request.arguments
+                    .map(serde_json::Value::Object)
+                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+
+                let context_request: ContextRequest = serde_json::from_value(arguments_value)
+                    .map_err(|e| McpError::invalid_params(format!("Parameter parse error: {}", e), None))?;
+
+                ContextTool::gather(context_request).await
+            }
+            "history_recall" => {
+                if !self.is_tool_enabled("history_recall", Some(&project)) {
+                    return Err(McpError::internal_error(
+                        "History recall tool is disabled".to_string(),
+                        None
+                    ));
+                }
+
+                let arguments_value = request.arguments
+                    .map(serde_json::Value::Object)
+                    .unwrap_or(serde_json::Value::Object(serde_json::Map::new()));
+
+                let recall_request: HistoryRecallRequest = serde_json::from_value(arguments_value)
+                    .map_err(|e| McpError::invalid_params(format!("Parameter parse error: {}", e), None))?;
+
+                RecallTool::recall(recall_request).await
             }
             _ => {
                 Err(McpError::invalid_request(
@@ -0,0 +1,69 @@
+// 本地缓存加密
+// ~/.acemcp/data 下的文件（projects.json、状态文件、文件清单、索引历史、本地索引等）默认明文保存，
+// 其中包含项目路径、文件列表与检查点。开启 acemcp_cache_encryption 后加密写入（见 utils::sealed_store），
+// 读取时按文件头自动识别是否加密，因此开关切换前后的文件都能正常读取；开关切换时会一次性迁移已有文件。
+// 缓存也由长期运行的 MCP 进程写入，因此每次写入都重新读取配置，避免界面切换开关后 MCP 进程仍按旧状态写入
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::log_important;
+use crate::utils::sealed_store::SealedStore;
+
+static STORE: SealedStore = SealedStore::new(b"ACEMCP-ENC1\n", "devkit-acemcp", "cache-encryption-key", "缓存");
+
+/// 本地缓存根目录
+fn data_dir() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".acemcp")
+        .join("data")
+}
+
+/// 当前是否开启缓存加密
+pub fn is_enabled() -> bool {
+    crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.acemcp_cache_encryption)
+        .unwrap_or(false)
+}
+
+/// 读取缓存文件（加密文件自动解密）
+pub(crate) fn read(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    STORE.read(path)
+}
+
+/// 读取缓存文件为字符串（加密文件自动解密）
+pub(crate) fn read_to_string(path: impl AsRef<Path>) -> io::Result<String> {
+    STORE.read_to_string(path)
+}
+
+/// 写入缓存文件（开启加密时加密写入）
+pub(crate) fn write(path: impl AsRef<Path>, data: impl AsRef<[u8]>) -> io::Result<()> {
+    STORE.write(path, data, is_enabled())
+}
+
+/// 切换缓存加密并迁移已有文件，返回迁移的文件数（开关状态由调用方随后写入配置）
+pub fn set_enabled(enabled: bool) -> anyhow::Result<usize> {
+    let migrated = STORE.migrate(collect_files(&data_dir()), enabled)?;
+
+    log_important!(info, "本地缓存加密已{}，迁移文件数: {}", if enabled { "开启" } else { "关闭" }, migrated);
+    Ok(migrated)
+}
+
+fn collect_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return files;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(collect_files(&path));
+        } else {
+            files.push(path);
+        }
+    }
+    files
+}
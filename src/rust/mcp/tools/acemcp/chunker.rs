@@ -0,0 +1,132 @@
+// 符号感知分块
+// 使用 tree-sitter 解析受支持的语言，尽量在函数/类边界处切分 blob；
+// 不支持的语言（或未启用 symbol-chunking 特性）返回 None，由调用方回退到按行切分
+
+/// 计算文件的分块行区间 `[start, end)`（行号从 0 开始）
+/// 返回 None 表示不支持该语言或解析失败
+pub(crate) fn symbol_chunk_ranges(path: &str, content: &str, max_lines: usize) -> Option<Vec<(usize, usize)>> {
+    let total_lines = content.split_inclusive('\n').count();
+    let boundaries = symbol_boundaries(path, content, max_lines)?;
+    if boundaries.is_empty() {
+        return None;
+    }
+    Some(plan_chunks(&boundaries, total_lines, max_lines))
+}
+
+/// 纯按行切分的区间（回退方案）
+pub(crate) fn line_chunk_ranges(total_lines: usize, max_lines: usize) -> Vec<(usize, usize)> {
+    plan_chunks(&[], total_lines, max_lines)
+}
+
+/// 根据候选边界规划分块：每块不超过 max_lines 行，并尽量落在最靠后的符号边界上
+/// 若区间内没有边界（单个符号超长），则在 max_lines 处硬切
+fn plan_chunks(boundaries: &[usize], total_lines: usize, max_lines: usize) -> Vec<(usize, usize)> {
+    let max_lines = max_lines.max(1);
+    let mut ranges = Vec::new();
+    let mut start = 0;
+
+    while start < total_lines {
+        let limit = start + max_lines;
+        if limit >= total_lines {
+            ranges.push((start, total_lines));
+            break;
+        }
+
+        let end = boundaries
+            .iter()
+            .copied()
+            .filter(|&b| b > start && b <= limit)
+            .max()
+            .unwrap_or(limit);
+        ranges.push((start, end));
+        start = end;
+    }
+
+    ranges
+}
+
+#[cfg(feature = "symbol-chunking")]
+fn symbol_boundaries(path: &str, content: &str, max_lines: usize) -> Option<Vec<usize>> {
+    let language = language_for_path(path)?;
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language).ok()?;
+    let tree = parser.parse(content, None)?;
+
+    let mut rows = Vec::new();
+    collect_boundaries(tree.root_node(), max_lines, 0, &mut rows);
+    rows.sort_unstable();
+    rows.dedup();
+    Some(rows)
+}
+
+#[cfg(not(feature = "symbol-chunking"))]
+fn symbol_boundaries(_path: &str, _content: &str, _max_lines: usize) -> Option<Vec<usize>> {
+    None
+}
+
+/// 根据文件扩展名选择 tree-sitter 语法
+#[cfg(feature = "symbol-chunking")]
+fn language_for_path(path: &str) -> Option<tree_sitter::Language> {
+    let ext = std::path::Path::new(path)
+        .extension()?
+        .to_str()?
+        .to_ascii_lowercase();
+
+    let language = match ext.as_str() {
+        "rs" => tree_sitter_rust::LANGUAGE.into(),
+        "py" => tree_sitter_python::LANGUAGE.into(),
+        "js" | "jsx" | "mjs" | "cjs" => tree_sitter_javascript::LANGUAGE.into(),
+        "ts" => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+        "tsx" => tree_sitter_typescript::LANGUAGE_TSX.into(),
+        "go" => tree_sitter_go::LANGUAGE.into(),
+        _ => return None,
+    };
+    Some(language)
+}
+
+/// 收集候选边界（节点起始行）
+/// 超长节点（如 impl 块、class）会继续向下展开，使其内部的方法也能作为边界
+#[cfg(feature = "symbol-chunking")]
+fn collect_boundaries(node: tree_sitter::Node, max_lines: usize, depth: usize, rows: &mut Vec<usize>) {
+    const MAX_DEPTH: usize = 4;
+
+    let mut cursor = node.walk();
+    let mut prev_comment_end: Option<usize> = None;
+
+    for child in node.named_children(&mut cursor) {
+        let start = child.start_position().row;
+        let end = child.end_position().row;
+
+        // 紧贴在符号前的注释与符号视为一体，边界取注释起始行
+        let attached_to_comment = prev_comment_end
+            .map(|comment_end| comment_end + 1 >= start)
+            .unwrap_or(false);
+        if !attached_to_comment {
+            rows.push(start);
+        }
+
+        prev_comment_end = if child.kind().contains("comment") { Some(end) } else { None };
+
+        if end - start + 1 > max_lines && depth < MAX_DEPTH {
+            collect_boundaries(child, max_lines, depth + 1, rows);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plan_chunks_prefers_symbol_boundaries() {
+        // 边界位于第 40、90、130 行，每块最多 100 行
+        let ranges = plan_chunks(&[0, 40, 90, 130], 200, 100);
+        assert_eq!(ranges, vec![(0, 90), (90, 130), (130, 200)]);
+    }
+
+    #[test]
+    fn test_plan_chunks_falls_back_to_lines() {
+        assert_eq!(line_chunk_ranges(250, 100), vec![(0, 100), (100, 200), (200, 250)]);
+        assert_eq!(line_chunk_ranges(50, 100), vec![(0, 50)]);
+    }
+}
@@ -3,7 +3,7 @@ use tauri::{AppHandle, Emitter, State};
 use crate::config::{AppState, save_config};
 use crate::network::proxy::{ProxyDetector, ProxyInfo, ProxyType};
 use super::AcemcpTool;
-use super::types::{AcemcpRequest, ProjectIndexStatus, ProjectsIndexStatus, ProjectFilesStatus, DetectedProxy, ProxySpeedTestResult, SpeedTestMetric, SpeedTestProgress, SpeedTestStageStatus};
+use super::types::{AcemcpRequest, IndexStatus, ProjectIndexStatus, ProjectsIndexStatus, ProjectFilesStatus, DetectedProxy, ProxySpeedTestResult, SpeedTestMetric, SpeedTestProgress, SpeedTestStageStatus};
 use reqwest;
 
 #[derive(Debug, serde::Deserialize)]
@@ -35,6 +35,55 @@ pub struct SaveAcemcpConfigArgs {
     pub proxy_username: Option<String>,
     #[serde(alias = "proxyPassword", alias = "proxy_password")]
     pub proxy_password: Option<String>,
+    // 索引后端
+    #[serde(alias = "backend", alias = "indexBackend")]
+    pub backend: Option<String>,
+    #[serde(alias = "uploadConcurrency", alias = "upload_concurrency")]
+    pub upload_concurrency: Option<u32>,
+    #[serde(alias = "workspaceGroups", alias = "workspace_groups")]
+    pub workspace_groups: Option<std::collections::HashMap<String, Vec<String>>>,
+    #[serde(alias = "searchCacheTtlSecs", alias = "search_cache_ttl_secs")]
+    pub search_cache_ttl_secs: Option<u64>,
+    // 文件过滤
+    #[serde(alias = "maxFileSizeKb", alias = "max_file_size_kb")]
+    pub max_file_size_kb: Option<u64>,
+    #[serde(alias = "skipBinaryFiles", alias = "skip_binary_files")]
+    pub skip_binary_files: Option<bool>,
+    #[serde(alias = "uploadCompression", alias = "upload_compression")]
+    pub upload_compression: Option<String>,
+    // 后台索引限流
+    #[serde(alias = "maxUploadMbps", alias = "max_upload_mbps")]
+    pub max_upload_mbps: Option<f64>,
+    #[serde(alias = "maxConcurrentReads", alias = "max_concurrent_reads")]
+    pub max_concurrent_reads: Option<u32>,
+    #[serde(alias = "indexNiceness", alias = "index_niceness")]
+    pub index_niceness: Option<u8>,
+    // 定时重新索引
+    #[serde(alias = "reindexSchedules", alias = "reindex_schedules")]
+    pub reindex_schedules: Option<Vec<String>>,
+    #[serde(alias = "scheduleOnly", alias = "schedule_only")]
+    pub schedule_only: Option<bool>,
+    // 文件监听上限
+    #[serde(alias = "watchMaxDirs", alias = "watch_max_dirs")]
+    pub watch_max_dirs: Option<usize>,
+    #[serde(alias = "watchMaxFiles", alias = "watch_max_files")]
+    pub watch_max_files: Option<usize>,
+    // 检索查询预处理
+    #[serde(alias = "queryRewrite", alias = "query_rewrite")]
+    pub query_rewrite: Option<bool>,
+    #[serde(alias = "queryPreferEnglish", alias = "query_prefer_english")]
+    pub query_prefer_english: Option<bool>,
+    #[serde(alias = "queryAbbreviations", alias = "query_abbreviations")]
+    pub query_abbreviations: Option<std::collections::HashMap<String, String>>,
+    // 上传前密钥扫描
+    #[serde(alias = "secretScan", alias = "secret_scan")]
+    pub secret_scan: Option<String>,
+    // 后端健康检查
+    #[serde(alias = "healthCheckIntervalSecs", alias = "health_check_interval_secs")]
+    pub health_check_interval_secs: Option<u64>,
+    // 本地缓存加密
+    #[serde(alias = "cacheEncryption", alias = "cache_encryption")]
+    pub cache_encryption: Option<bool>,
 }
 
 
@@ -56,6 +105,22 @@ pub async fn save_acemcp_config(
     }
     log::info!("规范化后的 BASE_URL: {}", base_url);
 
+    // 校验定时索引的 cron 表达式
+    if let Some(schedules) = &args.reindex_schedules {
+        super::scheduler::validate_schedules(schedules)
+            .map_err(|e| format!("无效的定时索引配置: {}", e))?;
+    }
+
+    // 切换本地缓存加密：先迁移已有文件，成功后再写入配置
+    if let Some(encrypt) = args.cache_encryption {
+        if encrypt != super::cache_crypto::is_enabled() {
+            tokio::task::spawn_blocking(move || super::cache_crypto::set_enabled(encrypt))
+                .await
+                .map_err(|e| format!("迁移本地缓存失败: {}", e))?
+                .map_err(|e| format!("迁移本地缓存失败: {}", e))?;
+        }
+    }
+
     {
         let mut config = state
             .config
@@ -76,6 +141,67 @@ pub async fn save_acemcp_config(
         config.mcp_config.acemcp_proxy_type = args.proxy_type.clone();
         config.mcp_config.acemcp_proxy_username = args.proxy_username.clone();
         config.mcp_config.acemcp_proxy_password = args.proxy_password.clone();
+        if let Some(backend) = &args.backend {
+            config.mcp_config.acemcp_backend = Some(backend.clone());
+        }
+        if let Some(concurrency) = args.upload_concurrency {
+            config.mcp_config.acemcp_upload_concurrency = Some(concurrency.clamp(1, 16));
+        }
+        if let Some(groups) = &args.workspace_groups {
+            config.mcp_config.acemcp_workspace_groups = Some(groups.clone());
+        }
+        if let Some(ttl) = args.search_cache_ttl_secs {
+            config.mcp_config.acemcp_search_cache_ttl_secs = Some(ttl);
+        }
+        if let Some(max_kb) = args.max_file_size_kb {
+            config.mcp_config.acemcp_max_file_size_kb = Some(max_kb);
+        }
+        if let Some(skip) = args.skip_binary_files {
+            config.mcp_config.acemcp_skip_binary_files = Some(skip);
+        }
+        if let Some(compression) = &args.upload_compression {
+            config.mcp_config.acemcp_upload_compression = Some(compression.clone());
+        }
+        if let Some(mbps) = args.max_upload_mbps {
+            config.mcp_config.acemcp_max_upload_mbps = Some(mbps.max(0.0));
+        }
+        if let Some(reads) = args.max_concurrent_reads {
+            config.mcp_config.acemcp_max_concurrent_reads = Some(reads.clamp(1, 64));
+        }
+        if let Some(niceness) = args.index_niceness {
+            config.mcp_config.acemcp_index_niceness = Some(niceness.min(19));
+        }
+        if let Some(schedules) = &args.reindex_schedules {
+            config.mcp_config.acemcp_reindex_schedules = Some(schedules.clone());
+        }
+        if let Some(schedule_only) = args.schedule_only {
+            config.mcp_config.acemcp_schedule_only = Some(schedule_only);
+        }
+        if let Some(max_dirs) = args.watch_max_dirs {
+            config.mcp_config.acemcp_watch_max_dirs = Some(max_dirs.max(1));
+        }
+        if let Some(max_files) = args.watch_max_files {
+            config.mcp_config.acemcp_watch_max_files = Some(max_files.max(1));
+        }
+        if let Some(rewrite) = args.query_rewrite {
+            config.mcp_config.acemcp_query_rewrite = Some(rewrite);
+        }
+        if let Some(prefer_english) = args.query_prefer_english {
+            config.mcp_config.acemcp_query_prefer_english = Some(prefer_english);
+        }
+        if let Some(abbreviations) = &args.query_abbreviations {
+            config.mcp_config.acemcp_query_abbreviations = Some(abbreviations.clone());
+        }
+        if let Some(mode) = &args.secret_scan {
+            let mode = super::secret_scan::SecretScanMode::from_config(Some(mode));
+            config.mcp_config.acemcp_secret_scan = Some(mode.as_str().to_string());
+        }
+        if let Some(interval) = args.health_check_interval_secs {
+            config.mcp_config.acemcp_health_check_interval_secs = Some(interval);
+        }
+        if let Some(encrypt) = args.cache_encryption {
+            config.mcp_config.acemcp_cache_encryption = Some(encrypt);
+        }
     }
 
     save_config(&state, &app)
@@ -338,6 +464,8 @@ pub async fn clear_acemcp_cache(_state: State<'_, AppState>) -> Result<String, S
     std::fs::create_dir_all(&cache_dir)
         .map_err(|e| format!("创建缓存目录失败: {} (路径: {})", e, cache_dir.display()))?;
 
+    super::search_cache::clear_all();
+
     let cache_path = cache_dir.to_string_lossy().to_string();
     log::info!("acemcp缓存已清除: {}", cache_path);
     Ok(cache_path)
@@ -359,6 +487,38 @@ pub struct AcemcpConfigResponse {
     pub proxy_type: String,
     pub proxy_username: String,
     pub proxy_password: String,
+    // 索引后端: "remote" | "local"
+    pub backend: String,
+    pub upload_concurrency: u32,
+    // 工作区分组（多项目联合检索）
+    pub workspace_groups: std::collections::HashMap<String, Vec<String>>,
+    // 检索结果缓存有效期（秒），0 表示禁用
+    pub search_cache_ttl_secs: u64,
+    // 文件过滤：单文件大小上限（KB，0 表示不限制）与二进制跳过开关
+    pub max_file_size_kb: u64,
+    pub skip_binary_files: bool,
+    // 上传压缩: "off" | "gzip" | "auto"
+    pub upload_compression: String,
+    // 后台索引限流：上传带宽（MB/s，0 表示不限制）、并发读取数、CPU 让出程度
+    pub max_upload_mbps: f64,
+    pub max_concurrent_reads: u32,
+    pub index_niceness: u8,
+    // 定时重新索引窗口（cron 表达式）与仅定时索引开关
+    pub reindex_schedules: Vec<String>,
+    pub schedule_only: bool,
+    // 文件监听目录数/文件数上限
+    pub watch_max_dirs: usize,
+    pub watch_max_files: usize,
+    // 检索查询预处理开关、英文偏好与自定义缩写表
+    pub query_rewrite: bool,
+    pub query_prefer_english: bool,
+    pub query_abbreviations: std::collections::HashMap<String, String>,
+    // 上传前密钥扫描: "redact" | "skip" | "off"
+    pub secret_scan: String,
+    // 后端健康检查间隔（秒），0 表示关闭
+    pub health_check_interval_secs: u64,
+    // 本地缓存加密
+    pub cache_encryption: bool,
 }
 
 #[tauri::command]
@@ -398,6 +558,26 @@ pub async fn get_acemcp_config(state: State<'_, AppState>) -> Result<AcemcpConfi
         proxy_type: config.mcp_config.acemcp_proxy_type.clone().unwrap_or_else(|| "http".to_string()),
         proxy_username: config.mcp_config.acemcp_proxy_username.clone().unwrap_or_default(),
         proxy_password: config.mcp_config.acemcp_proxy_password.clone().unwrap_or_default(),
+        backend: config.mcp_config.acemcp_backend.clone().unwrap_or_else(|| "remote".to_string()),
+        upload_concurrency: config.mcp_config.acemcp_upload_concurrency.unwrap_or(4),
+        workspace_groups: config.mcp_config.acemcp_workspace_groups.clone().unwrap_or_default(),
+        search_cache_ttl_secs: config.mcp_config.acemcp_search_cache_ttl_secs.unwrap_or(super::search_cache::DEFAULT_SEARCH_CACHE_TTL_SECS),
+        max_file_size_kb: config.mcp_config.acemcp_max_file_size_kb.unwrap_or(super::file_filter::DEFAULT_MAX_FILE_SIZE_KB),
+        skip_binary_files: config.mcp_config.acemcp_skip_binary_files.unwrap_or(true),
+        upload_compression: config.mcp_config.acemcp_upload_compression.clone().unwrap_or_else(|| "off".to_string()),
+        max_upload_mbps: config.mcp_config.acemcp_max_upload_mbps.unwrap_or(0.0),
+        max_concurrent_reads: config.mcp_config.acemcp_max_concurrent_reads.unwrap_or(super::throttle::DEFAULT_MAX_CONCURRENT_READS as u32),
+        index_niceness: config.mcp_config.acemcp_index_niceness.unwrap_or(0),
+        reindex_schedules: config.mcp_config.acemcp_reindex_schedules.clone().unwrap_or_default(),
+        schedule_only: config.mcp_config.acemcp_schedule_only.unwrap_or(false),
+        watch_max_dirs: config.mcp_config.acemcp_watch_max_dirs.unwrap_or(super::watch_plan::DEFAULT_WATCH_MAX_DIRS),
+        watch_max_files: config.mcp_config.acemcp_watch_max_files.unwrap_or(super::watch_plan::DEFAULT_WATCH_MAX_FILES),
+        query_rewrite: config.mcp_config.acemcp_query_rewrite.unwrap_or(false),
+        query_prefer_english: config.mcp_config.acemcp_query_prefer_english.unwrap_or(false),
+        query_abbreviations: config.mcp_config.acemcp_query_abbreviations.clone().unwrap_or_default(),
+        secret_scan: super::secret_scan::SecretScanMode::from_config(config.mcp_config.acemcp_secret_scan.as_deref()).as_str().to_string(),
+        health_check_interval_secs: config.mcp_config.acemcp_health_check_interval_secs.unwrap_or(super::health::DEFAULT_HEALTH_CHECK_INTERVAL_SECS),
+        cache_encryption: config.mcp_config.acemcp_cache_encryption.unwrap_or(false),
     })
 }
 
@@ -439,7 +619,9 @@ pub async fn debug_acemcp_search(
     
     let req = AcemcpRequest { 
         project_root_path: project_root_path.clone(), 
-        query: query.clone() 
+        query: query.clone(),
+        include_paths: Vec::new(),
+        exclude_paths: Vec::new(),
     };
     
     // 调用搜索函数（日志会通过 log crate 输出到日志文件）
@@ -523,7 +705,7 @@ pub async fn execute_acemcp_tool(
                 .to_string();
             
             // 执行搜索
-            let req = AcemcpRequest { project_root_path, query };
+            let req = AcemcpRequest { project_root_path, query, include_paths: Vec::new(), exclude_paths: Vec::new() };
             match AcemcpTool::search_context(req).await {
                 Ok(result) => {
                     // 转换结果为JSON
@@ -586,6 +768,57 @@ pub async fn get_acemcp_project_files_status(
         .map_err(|e| e.to_string())
 }
 
+/// 获取指定项目的索引运行历史（最新的在前）及耗时趋势
+#[tauri::command]
+pub fn get_acemcp_index_history(
+    project_root_path: String,
+    limit: Option<usize>,
+) -> Result<super::index_history::IndexHistory, String> {
+    let normalized_root = super::mcp::normalize_project_root(&project_root_path);
+    Ok(super::index_history::load_history(&normalized_root, limit))
+}
+
+/// 获取检索后端最近的健康检查记录（最新的在前）及可用率/平均延迟
+#[tauri::command]
+pub fn get_acemcp_health(limit: Option<usize>) -> Result<super::health::HealthReport, String> {
+    Ok(super::health::get_report(limit))
+}
+
+/// 立即探测一次检索后端并返回最新的健康状态
+#[tauri::command]
+pub async fn check_acemcp_health() -> Result<super::health::HealthReport, String> {
+    let config = AcemcpTool::get_acemcp_config()
+        .await
+        .map_err(|e| e.to_string())?;
+    if super::health::check_now(&config).await.is_none() {
+        return Err("未配置远程检索后端".to_string());
+    }
+    Ok(super::health::get_report(None))
+}
+
+/// 索引预览（dry-run）：统计将要上传的文件/blob/字节数与排除规则命中情况，不上传任何内容
+#[tauri::command]
+pub async fn preview_acemcp_index(project_root_path: String) -> Result<super::preview::IndexPreview, String> {
+    AcemcpTool::preview_index(project_root_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 诊断候选路径为何未被索引：返回命中的排除模式 / .gitignore 规则 / 不索引标记 / 扩展名等过滤规则
+#[tauri::command]
+pub async fn explain_acemcp_path_filter(
+    project_root_path: String,
+    path: String,
+) -> Result<super::filter_explain::FilterExplanation, String> {
+    let acemcp_config = AcemcpTool::get_acemcp_config()
+        .await
+        .map_err(|e| e.to_string())?;
+    let config = super::project_config::config_for_project(&acemcp_config, &project_root_path);
+    tokio::task::spawn_blocking(move || super::filter_explain::explain_path(&config, &project_root_path, &path))
+        .await
+        .map_err(|e| format!("过滤规则诊断任务异常退出: {}", e))
+}
+
 /// 手动触发索引更新
 #[tauri::command]
 pub async fn trigger_acemcp_index_update(project_root_path: String) -> Result<String, String> {
@@ -628,9 +861,9 @@ pub async fn set_auto_index_enabled(
 
 /// 获取当前正在监听的项目列表
 #[tauri::command]
-pub fn get_watching_projects() -> Result<Vec<String>, String> {
+pub fn get_watching_projects() -> Result<Vec<super::watch_plan::WatchedProject>, String> {
     let watcher_manager = super::watcher::get_watcher_manager();
-    Ok(watcher_manager.get_watching_projects())
+    Ok(watcher_manager.get_watching_projects_detail())
 }
 
 /// 检查指定项目是否正在监听
@@ -667,6 +900,30 @@ pub async fn start_project_watching(
         .map_err(|e| format!("启动监听失败: {}", e))
 }
 
+/// 为登记表中开启自动监听的项目启动文件监听，单个项目失败不影响其他项目
+pub(crate) async fn watch_registered_projects(project_roots: Vec<String>) {
+    if project_roots.is_empty() {
+        return;
+    }
+    let acemcp_config = match super::AcemcpTool::get_acemcp_config().await {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("获取 acemcp 配置失败，跳过自动监听: {}", e);
+            return;
+        }
+    };
+    let debounce_ms = crate::config::load_standalone_config()
+        .ok()
+        .and_then(|c| c.mcp_config.acemcp_watch_debounce_ms);
+
+    let watcher_manager = super::watcher::get_watcher_manager();
+    for root in project_roots {
+        if let Err(e) = watcher_manager.start_watching(root.clone(), acemcp_config.clone(), debounce_ms).await {
+            log::warn!("自动监听项目失败: path={}, error={}", root, e);
+        }
+    }
+}
+
 /// 停止监听指定项目
 #[tauri::command]
 pub fn stop_project_watching(project_root_path: String) -> Result<(), String> {
@@ -683,12 +940,181 @@ pub fn stop_all_watching() -> Result<(), String> {
     Ok(())
 }
 
+/// 暂停指定项目的索引
+/// 正在进行的索引会在当前批次完成后挂起，已完成的批次已写入检查点
+#[tauri::command]
+pub fn pause_acemcp_indexing(project_root_path: String) -> Result<(), String> {
+    let normalized_root = super::mcp::normalize_project_root(&project_root_path);
+    let was_paused = super::control::pause_indexing(&normalized_root).map_err(|e| format!("保存暂停状态失败: {}", e))?;
+    if !was_paused {
+        super::mcp::update_project_status(&project_root_path, |status| {
+            if status.status == IndexStatus::Indexing {
+                status.status = IndexStatus::Paused;
+            }
+        })
+        .map_err(|e| format!("更新索引状态失败: {}", e))?;
+        log::info!("已暂停项目索引: {}", normalized_root);
+    }
+    Ok(())
+}
+
+/// 恢复指定项目的索引，从最后完成的批次继续
+///
+/// 暂停的索引已随进程退出时重新发起一次索引，由已写入的检查点续传
+#[tauri::command]
+pub fn resume_acemcp_indexing(project_root_path: String) -> Result<(), String> {
+    let normalized_root = super::mcp::normalize_project_root(&project_root_path);
+    let was_paused = super::control::resume_indexing(&normalized_root).map_err(|e| format!("保存暂停状态失败: {}", e))?;
+    if !was_paused {
+        return Ok(());
+    }
+
+    if super::control::is_run_active(&normalized_root) {
+        super::mcp::update_project_status(&project_root_path, |status| {
+            if status.status == IndexStatus::Paused {
+                status.status = IndexStatus::Indexing;
+            }
+        })
+        .map_err(|e| format!("更新索引状态失败: {}", e))?;
+        log::info!("已恢复项目索引: {}", normalized_root);
+    } else {
+        log::info!("暂停的索引已结束，重新发起索引: {}", normalized_root);
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = AcemcpTool::trigger_index_update(project_root_path.clone()).await {
+                log::warn!("恢复后重新索引失败: {}", e);
+                // 未能开始索引时不再停留在暂停状态
+                let _ = super::mcp::update_project_status(&project_root_path, |status| {
+                    if status.status == IndexStatus::Paused {
+                        status.status = IndexStatus::Failed;
+                        status.last_error = Some(e.to_string());
+                    }
+                });
+            }
+        });
+    }
+    Ok(())
+}
+
+/// 获取当前处于暂停状态的项目列表
+#[tauri::command]
+pub fn get_paused_acemcp_projects() -> Result<Vec<String>, String> {
+    Ok(super::control::paused_projects())
+}
+
+/// 获取已配置的索引端点及项目分配情况
+#[derive(Debug, serde::Serialize)]
+pub struct AcemcpEndpointsResponse {
+    pub endpoints: Vec<crate::config::AcemcpEndpoint>,
+    /// 项目路径 -> 端点名称
+    pub project_endpoints: std::collections::HashMap<String, String>,
+}
+
+#[tauri::command]
+pub async fn get_acemcp_endpoints(state: State<'_, AppState>) -> Result<AcemcpEndpointsResponse, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(AcemcpEndpointsResponse {
+        endpoints: config.mcp_config.acemcp_endpoints.clone().unwrap_or_default(),
+        project_endpoints: config.mcp_config.acemcp_project_endpoints.clone().unwrap_or_default(),
+    })
+}
+
+/// 保存索引端点列表
+/// 被删除端点上的项目分配会一并移除（这些项目回退到默认端点）
+#[tauri::command]
+pub async fn save_acemcp_endpoints(
+    endpoints: Vec<crate::config::AcemcpEndpoint>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let mut names = std::collections::HashSet::new();
+    for endpoint in &endpoints {
+        if endpoint.name.trim().is_empty() {
+            return Err("端点名称不能为空".to_string());
+        }
+        if !names.insert(endpoint.name.clone()) {
+            return Err(format!("端点名称重复: {}", endpoint.name));
+        }
+    }
+
+    let orphaned: Vec<String> = {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        let mut orphaned = Vec::new();
+        if let Some(assignments) = config.mcp_config.acemcp_project_endpoints.as_mut() {
+            assignments.retain(|root, name| {
+                let keep = names.contains(name);
+                if !keep {
+                    orphaned.push(root.clone());
+                }
+                keep
+            });
+        }
+        config.mcp_config.acemcp_endpoints = Some(endpoints);
+        orphaned
+    };
+
+    // 回退到默认端点的项目需要重新上传
+    for root in &orphaned {
+        let _ = super::mcp::reset_project_blobs(root);
+    }
+
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    log::info!("已保存 {} 个索引端点", names.len());
+    Ok(())
+}
+
+/// 为项目分配索引端点（endpoint 为空时使用默认端点）
+/// 端点变化后会清空该项目已有的 blob 记录，下次索引时上传到新端点
+#[tauri::command]
+pub async fn set_acemcp_project_endpoint(
+    project_root_path: String,
+    endpoint: Option<String>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let normalized_root = super::mcp::normalize_project_root(&project_root_path);
+    let endpoint = endpoint.filter(|name| !name.trim().is_empty());
+
+    let changed = {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        if let Some(name) = &endpoint {
+            let exists = config
+                .mcp_config
+                .acemcp_endpoints
+                .as_ref()
+                .map(|eps| eps.iter().any(|ep| &ep.name == name))
+                .unwrap_or(false);
+            if !exists {
+                return Err(format!("索引端点不存在: {}", name));
+            }
+        }
+
+        let assignments = config.mcp_config.acemcp_project_endpoints.get_or_insert_with(Default::default);
+        let previous = match &endpoint {
+            Some(name) => assignments.insert(normalized_root.clone(), name.clone()),
+            None => assignments.remove(&normalized_root),
+        };
+        previous != endpoint
+    };
+
+    if changed {
+        super::mcp::reset_project_blobs(&normalized_root)
+            .map_err(|e| format!("清理项目索引记录失败: {}", e))?;
+        log::info!("项目索引端点已变更: project={}, endpoint={:?}", normalized_root, endpoint);
+    }
+
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(())
+}
+
 /// 删除指定项目的索引记录
 /// 同时清理 projects.json 和 projects_status.json 中的数据
 #[tauri::command]
 pub async fn remove_acemcp_project_index(project_root_path: String) -> Result<String, String> {
     use std::path::PathBuf;
-    use std::fs;
     use std::collections::HashMap;
 
     // 辅助函数：规范化路径 key（去除扩展路径前缀，统一使用正斜杠）
@@ -720,7 +1146,7 @@ pub async fn remove_acemcp_project_index(project_root_path: String) -> Result<St
     // 1. 从 projects.json 中删除项目的 blob 列表
     let projects_path = data_dir.join("projects.json");
     if projects_path.exists() {
-        if let Ok(data) = fs::read_to_string(&projects_path) {
+        if let Ok(data) = super::cache_crypto::read_to_string(&projects_path) {
             if let Ok(mut projects) = serde_json::from_str::<HashMap<String, Vec<String>>>(&data) {
                 // 调试日志：输出现有的 key 列表
                 let existing_keys: Vec<&String> = projects.keys().collect();
@@ -735,7 +1161,7 @@ pub async fn remove_acemcp_project_index(project_root_path: String) -> Result<St
                     log::info!("[remove_acemcp_project_index] 找到匹配的 key: {}", key);
                     projects.remove(&key);
                     if let Ok(new_data) = serde_json::to_string_pretty(&projects) {
-                        let _ = fs::write(&projects_path, new_data);
+                        let _ = super::cache_crypto::write(&projects_path, new_data);
                         log::info!("[remove_acemcp_project_index] ✓ 已从 projects.json 删除项目: {}", key);
                         projects_deleted = true;
                     }
@@ -751,7 +1177,7 @@ pub async fn remove_acemcp_project_index(project_root_path: String) -> Result<St
     // 2. 从 projects_status.json 中删除项目状态
     let status_path = data_dir.join("projects_status.json");
     if status_path.exists() {
-        if let Ok(data) = fs::read_to_string(&status_path) {
+        if let Ok(data) = super::cache_crypto::read_to_string(&status_path) {
             if let Ok(mut status) = serde_json::from_str::<serde_json::Value>(&data) {
                 if let Some(projects) = status.get_mut("projects") {
                     if let Some(map) = projects.as_object_mut() {
@@ -768,7 +1194,7 @@ pub async fn remove_acemcp_project_index(project_root_path: String) -> Result<St
                             log::info!("[remove_acemcp_project_index] 找到匹配的 key: {}", key);
                             map.remove(&key);
                             if let Ok(new_data) = serde_json::to_string_pretty(&status) {
-                                let _ = fs::write(&status_path, new_data);
+                                let _ = super::cache_crypto::write(&status_path, new_data);
                                 log::info!("[remove_acemcp_project_index] ✓ 已从 projects_status.json 删除项目: {}", key);
                                 status_deleted = true;
                             }
@@ -787,6 +1213,15 @@ pub async fn remove_acemcp_project_index(project_root_path: String) -> Result<St
     let watcher_manager = super::watcher::get_watcher_manager();
     let _ = watcher_manager.stop_watching(&normalized_root);
 
+    // 4. 删除本地离线索引文件（如果有）
+    super::local_index::remove_local_index(&project_root_path);
+
+    // 5. 删除文件内容哈希清单，下次索引时重新完整扫描
+    super::file_manifest::FileManifest::remove(&super::mcp::normalize_project_root(&project_root_path));
+    super::search_cache::invalidate_project(&super::mcp::normalize_project_root(&project_root_path));
+    super::index_history::remove_history(&super::mcp::normalize_project_root(&project_root_path));
+    super::quarantine::FailureLedger::remove(&super::mcp::normalize_project_root(&project_root_path));
+
     // 汇总删除结果
     if projects_deleted || status_deleted {
         log::info!("[remove_acemcp_project_index] 删除完成: projects.json={}, status.json={}", projects_deleted, status_deleted);
@@ -798,6 +1233,26 @@ pub async fn remove_acemcp_project_index(project_root_path: String) -> Result<St
     }
 }
 
+/// 解除文件隔离并重新索引（paths 为空时解除全部隔离文件），返回索引结果
+#[tauri::command]
+pub async fn retry_acemcp_quarantined_files(
+    project_root_path: String,
+    paths: Option<Vec<String>>,
+) -> Result<String, String> {
+    let normalized_root = super::mcp::normalize_project_root(&project_root_path);
+    let mut ledger = super::quarantine::FailureLedger::load(&normalized_root);
+    let released = ledger.release(&paths.unwrap_or_default());
+    if released.is_empty() {
+        return Ok("没有需要重试的隔离文件".to_string());
+    }
+    ledger.save(&normalized_root).map_err(|e| format!("保存隔离台账失败: {}", e))?;
+    log::info!("[retry_acemcp_quarantined_files] 已解除隔离 {} 个文件: {:?}", released.len(), released);
+
+    AcemcpTool::trigger_index_update(project_root_path)
+        .await
+        .map_err(|e| e.to_string())
+}
+
 /// 检查指定目录是否存在
 #[tauri::command]
 pub fn check_directory_exists(directory_path: String) -> Result<bool, String> {
@@ -811,13 +1266,43 @@ pub fn check_directory_exists(directory_path: String) -> Result<bool, String> {
     Ok(normalized.exists() && normalized.is_dir())
 }
 
+/// 检测根目录已不存在的项目（同时刷新状态文件中的 stale 标记）
+#[tauri::command]
+pub fn get_stale_acemcp_projects() -> Result<Vec<String>, String> {
+    super::stale::reconcile_stale_projects().map_err(|e| e.to_string())
+}
+
+/// 批量删除根目录已不存在的项目索引记录，返回已删除的项目
+#[tauri::command]
+pub async fn remove_stale_acemcp_projects() -> Result<Vec<String>, String> {
+    let stale = super::stale::reconcile_stale_projects().map_err(|e| e.to_string())?;
+    log::info!("[remove_stale_acemcp_projects] 检测到 {} 个失效项目", stale.len());
+
+    let mut removed = Vec::with_capacity(stale.len());
+    for root in stale {
+        match remove_acemcp_project_index(root.clone()).await {
+            Ok(_) => removed.push(root),
+            Err(e) => log::warn!("[remove_stale_acemcp_projects] 删除失败: {} - {}", root, e),
+        }
+    }
+    Ok(removed)
+}
+
 // ============ 代理检测和测速命令 ============
 
 /// 自动检测本地可用的代理
-/// 返回所有检测到的可用代理列表
+/// 返回所有检测到的可用代理列表（系统已配置的代理 + 本地常用端口扫描）
 #[tauri::command]
 pub async fn detect_acemcp_proxy(extra_ports: Option<Vec<u16>>) -> Result<Vec<DetectedProxy>, String> {
     log::info!("🔍 开始检测本地代理...");
+
+    // 系统已配置的代理（环境变量 / 系统代理设置 / PAC）优先
+    let mut candidates: Vec<(ProxyInfo, String)> = ProxyDetector::detect_system_proxies()
+        .await
+        .into_iter()
+        .map(|p| (p.info, p.source))
+        .collect();
+    log::info!("🔍 读取到 {} 个系统代理配置", candidates.len());
     
     // 常用代理端口列表
     let mut ports_to_check: Vec<(u16, &'static str)> = vec![
@@ -847,33 +1332,44 @@ pub async fn detect_acemcp_proxy(extra_ports: Option<Vec<u16>>) -> Result<Vec<De
         }
     }
 
-    // 并发检测所有端口（符合需求：并发检测 + 3 秒超时由 ProxyDetector 内部保证）
-    let mut tasks = tokio::task::JoinSet::new();
+    // 与系统代理重复的端口只检测一次（保留系统代理的来源标记）
+    let mut seen_urls: std::collections::HashSet<String> =
+        candidates.iter().map(|(info, _)| info.to_url()).collect();
     for (port, proxy_type_str) in ports_to_check {
-        tasks.spawn(async move {
-            let proxy_type = if proxy_type_str == "socks5" {
-                ProxyType::Socks5
-            } else {
-                ProxyType::Http
-            };
+        let proxy_type = if proxy_type_str == "socks5" {
+            ProxyType::Socks5
+        } else {
+            ProxyType::Http
+        };
+        let proxy_info = ProxyInfo::new(proxy_type, "127.0.0.1".to_string(), port);
+        if seen_urls.insert(proxy_info.to_url()) {
+            candidates.push((proxy_info, "scan".to_string()));
+        }
+    }
 
-            let proxy_info = ProxyInfo::new(proxy_type, "127.0.0.1".to_string(), port);
+    // 并发检测所有候选代理（符合需求：并发检测 + 3 秒超时由 ProxyDetector 内部保证）
+    let mut tasks = tokio::task::JoinSet::new();
+    for (proxy_info, source) in candidates {
+        tasks.spawn(async move {
             let start = std::time::Instant::now();
 
             if ProxyDetector::check_proxy(&proxy_info).await {
                 let response_time = start.elapsed().as_millis() as u64;
                 log::info!(
-                    "✅ 检测到可用代理: 127.0.0.1:{} ({}), 响应时间: {}ms",
-                    port,
-                    proxy_type_str,
+                    "✅ 检测到可用代理: {}:{} ({}, 来源: {}), 响应时间: {}ms",
+                    proxy_info.host,
+                    proxy_info.port,
+                    proxy_info.proxy_type,
+                    source,
                     response_time
                 );
 
                 Some(DetectedProxy {
-                    host: "127.0.0.1".to_string(),
-                    port,
-                    proxy_type: proxy_type_str.to_string(),
+                    host: proxy_info.host.clone(),
+                    port: proxy_info.port,
+                    proxy_type: proxy_info.proxy_type.to_string(),
                     response_time_ms: Some(response_time),
+                    source,
                 })
             } else {
                 None
@@ -964,6 +1460,7 @@ pub async fn test_acemcp_proxy_speed(
             port,
             proxy_type: p_type.clone(),
             response_time_ms: None,
+            source: "manual".to_string(),
         })
     } else {
         None
@@ -1180,7 +1677,7 @@ pub async fn test_acemcp_proxy_speed(
         
         let projects_path = super::mcp::home_projects_file();
         let projects: super::mcp::ProjectsFile = if projects_path.exists() {
-            let data = std::fs::read_to_string(&projects_path).unwrap_or_default();
+            let data = super::cache_crypto::read_to_string(&projects_path).unwrap_or_default();
             serde_json::from_str(&data).unwrap_or_default()
         } else {
             super::mcp::ProjectsFile::default()
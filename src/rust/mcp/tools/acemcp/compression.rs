@@ -0,0 +1,91 @@
+// 上传载荷压缩
+// 慢速链路下对 batch-upload 请求体进行 gzip 压缩（Content-Encoding: gzip）
+// 部分代理或服务端不支持压缩请求体，因此提供三种模式：
+// - off：不压缩（默认）
+// - gzip：始终压缩
+// - auto：尝试压缩，服务端返回 400/415 时回退为不压缩，并在本进程内记住该端点
+
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::Mutex;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use once_cell::sync::Lazy;
+
+/// 小于该大小的载荷不压缩（收益不明显）
+const MIN_COMPRESS_BYTES: usize = 1024;
+
+/// 上传压缩模式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum UploadCompression {
+    Off,
+    Gzip,
+    Auto,
+}
+
+impl UploadCompression {
+    pub(crate) fn from_config(value: Option<&str>) -> Self {
+        match value.map(|v| v.trim().to_ascii_lowercase()).as_deref() {
+            Some("gzip") => Self::Gzip,
+            Some("auto") => Self::Auto,
+            _ => Self::Off,
+        }
+    }
+}
+
+/// 已确认不接受压缩请求体的端点
+static REJECTED_ENDPOINTS: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+/// 是否应对发往 `url` 的载荷进行压缩
+pub(crate) fn should_compress(mode: UploadCompression, url: &str, payload_len: usize) -> bool {
+    match mode {
+        UploadCompression::Off => false,
+        UploadCompression::Gzip => payload_len >= MIN_COMPRESS_BYTES,
+        UploadCompression::Auto => {
+            payload_len >= MIN_COMPRESS_BYTES
+                && !REJECTED_ENDPOINTS.lock().map(|set| set.contains(url)).unwrap_or(false)
+        }
+    }
+}
+
+/// 记录端点不接受压缩请求体（auto 模式下回退）
+pub(crate) fn mark_rejected(url: &str) {
+    if let Ok(mut set) = REJECTED_ENDPOINTS.lock() {
+        set.insert(url.to_string());
+    }
+}
+
+/// gzip 压缩
+pub(crate) fn gzip(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::with_capacity(data.len() / 4), Compression::fast());
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gzip_roundtrip() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let data = "fn main() {}\n".repeat(200);
+        let compressed = gzip(data.as_bytes()).unwrap();
+        assert!(compressed.len() < data.len());
+
+        let mut decoded = String::new();
+        GzDecoder::new(&compressed[..]).read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_compression_mode_from_config() {
+        assert_eq!(UploadCompression::from_config(None), UploadCompression::Off);
+        assert_eq!(UploadCompression::from_config(Some("GZIP")), UploadCompression::Gzip);
+        assert_eq!(UploadCompression::from_config(Some("auto")), UploadCompression::Auto);
+        assert!(!should_compress(UploadCompression::Gzip, "http://x", 10));
+    }
+}
@@ -0,0 +1,166 @@
+// 索引运行控制（暂停/恢复）
+// 暂停后正在进行的索引会在下一个批次开始前挂起；已完成的批次会实时写入 projects.json 作为检查点，
+// 因此即使暂停期间进程退出，下次索引也会从最后完成的批次继续（已上传的 blob 不会重复上传）。
+// 暂停状态保存在 ~/.acemcp/data/paused_projects.json 中：界面进程切换暂停后，MCP 进程中的索引在下一个批次前
+// 读取到同一状态（跨进程无法直接唤醒，挂起的索引按固定间隔重新检查）；进程重启后暂停状态仍然保留，直到恢复。
+// 运行中的索引定期刷新 ~/.acemcp/data/running/ 下的标记文件，恢复时据此判断暂停的索引是否仍在等待
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+use ring::digest::{digest, SHA256};
+
+use once_cell::sync::Lazy;
+use tokio::sync::Notify;
+
+/// 挂起时重新检查暂停状态的间隔（其他进程恢复索引时无法直接唤醒）
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// 运行标记的刷新间隔
+const RUN_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 运行标记超过该时间未刷新时视为运行已结束（进程异常退出）
+const RUN_STALE_AFTER: Duration = Duration::from_secs(20);
+
+/// 单个项目的索引控制闸门
+pub struct IndexGate {
+    normalized_root: String,
+    notify: Notify,
+}
+
+impl IndexGate {
+    /// 是否处于暂停状态
+    pub fn is_paused(&self) -> bool {
+        load_paused().contains(&self.normalized_root)
+    }
+
+    /// 暂停时挂起，直到恢复
+    pub async fn wait_if_paused(&self) {
+        loop {
+            // 先注册等待再检查状态，避免错过 resume 的唤醒
+            let notified = self.notify.notified();
+            if !self.is_paused() {
+                return;
+            }
+            tokio::select! {
+                _ = notified => {}
+                _ = tokio::time::sleep(PAUSE_POLL_INTERVAL) => {}
+            }
+        }
+    }
+}
+
+/// 项目路径 -> 控制闸门
+static GATES: Lazy<Mutex<HashMap<String, Arc<IndexGate>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn paused_file() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let data_dir = home.join(".acemcp").join("data");
+    let _ = fs::create_dir_all(&data_dir);
+    data_dir.join("paused_projects.json")
+}
+
+/// 读取处于暂停状态的项目，不存在或损坏时视为没有暂停的项目
+fn load_paused() -> BTreeSet<String> {
+    super::cache_crypto::read_to_string(paused_file())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_paused(paused: &BTreeSet<String>) -> anyhow::Result<()> {
+    let path = paused_file();
+    if paused.is_empty() {
+        let _ = fs::remove_file(path);
+        return Ok(());
+    }
+    super::cache_crypto::write(path, serde_json::to_string(paused)?)?;
+    Ok(())
+}
+
+/// 获取项目的控制闸门（不存在则创建）
+pub fn get_index_gate(normalized_root: &str) -> Arc<IndexGate> {
+    let mut gates = GATES.lock().unwrap();
+    gates
+        .entry(normalized_root.to_string())
+        .or_insert_with(|| {
+            Arc::new(IndexGate {
+                normalized_root: normalized_root.to_string(),
+                notify: Notify::new(),
+            })
+        })
+        .clone()
+}
+
+/// 暂停项目索引，返回之前是否已处于暂停状态
+pub fn pause_indexing(normalized_root: &str) -> anyhow::Result<bool> {
+    let mut paused = load_paused();
+    if !paused.insert(normalized_root.to_string()) {
+        return Ok(true);
+    }
+    save_paused(&paused)?;
+    Ok(false)
+}
+
+/// 恢复项目索引，返回之前是否处于暂停状态
+pub fn resume_indexing(normalized_root: &str) -> anyhow::Result<bool> {
+    let mut paused = load_paused();
+    if !paused.remove(normalized_root) {
+        return Ok(false);
+    }
+    save_paused(&paused)?;
+    get_index_gate(normalized_root).notify.notify_waiters();
+    Ok(true)
+}
+
+/// 当前处于暂停状态的项目列表
+pub fn paused_projects() -> Vec<String> {
+    load_paused().into_iter().collect()
+}
+
+/// 索引运行期间持有的标记，释放时删除标记文件
+pub struct RunGuard {
+    path: PathBuf,
+    heartbeat: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for RunGuard {
+    fn drop(&mut self) {
+        self.heartbeat.abort();
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+fn run_marker_file(normalized_root: &str) -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".acemcp").join("data").join("running");
+    let _ = fs::create_dir_all(&dir);
+    let hash = hex::encode(digest(&SHA256, normalized_root.as_bytes()).as_ref());
+    dir.join(&hash[..16])
+}
+
+/// 标记项目正在索引，直到返回的标记被释放（需在 tokio 运行时中调用）
+pub fn begin_run(normalized_root: &str) -> RunGuard {
+    let path = run_marker_file(normalized_root);
+    let marker = path.clone();
+    // 同一项目的多次运行共用标记，先结束的运行删除标记后由仍在运行的一方重新写入
+    let heartbeat = tokio::spawn(async move {
+        loop {
+            let _ = fs::write(&marker, std::process::id().to_string());
+            tokio::time::sleep(RUN_HEARTBEAT_INTERVAL).await;
+        }
+    });
+    RunGuard { path, heartbeat }
+}
+
+/// 是否有进程正在索引该项目（含暂停等待中的索引）
+pub fn is_run_active(normalized_root: &str) -> bool {
+    fs::metadata(run_marker_file(normalized_root))
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age < RUN_STALE_AFTER)
+}
@@ -0,0 +1,107 @@
+// 多项目联合检索
+// 对多个项目分别检索后，按各自排名轮流合并结果，避免某个项目的结果淹没其他项目
+
+const RETRIEVAL_HEADER: &str = "The following code sections were retrieved:";
+
+/// 将检索结果文本拆分为按排名排列的代码片段（每段以 `Path: ` 行开头）
+pub(crate) fn split_retrieval_sections(text: &str) -> Vec<String> {
+    let mut sections: Vec<String> = Vec::new();
+    let mut current: Option<Vec<&str>> = None;
+
+    for line in text.lines() {
+        if line.starts_with("Path: ") {
+            if let Some(lines) = current.take() {
+                sections.push(finish_section(&lines));
+            }
+            current = Some(vec![line]);
+        } else if let Some(lines) = current.as_mut() {
+            lines.push(line);
+        }
+    }
+    if let Some(lines) = current {
+        sections.push(finish_section(&lines));
+    }
+
+    sections.retain(|s| !s.is_empty());
+    sections
+}
+
+/// 去掉片段末尾的空行和 `---` 分隔符
+fn finish_section(lines: &[&str]) -> String {
+    let mut end = lines.len();
+    while end > 0 && (lines[end - 1].trim().is_empty() || lines[end - 1].trim() == "---") {
+        end -= 1;
+    }
+    lines[..end].join("\n")
+}
+
+/// 为片段路径加上项目标签，便于区分来源
+fn label_section(label: &str, section: &str) -> String {
+    match section.strip_prefix("Path: ") {
+        Some(rest) => format!("Path: [{}] {}", label, rest),
+        None => section.to_string(),
+    }
+}
+
+/// 按排名轮流合并多个项目的片段：先取各项目第 1 名，再取第 2 名，依此类推
+pub(crate) fn merge_ranked_sections(results: &[(String, Vec<String>)]) -> Vec<String> {
+    let max_len = results.iter().map(|(_, sections)| sections.len()).max().unwrap_or(0);
+    let mut merged = Vec::new();
+    for rank in 0..max_len {
+        for (label, sections) in results {
+            if let Some(section) = sections.get(rank) {
+                merged.push(label_section(label, section));
+            }
+        }
+    }
+    merged
+}
+
+/// 渲染合并后的检索结果
+pub(crate) fn render_merged(sections: &[String]) -> String {
+    if sections.is_empty() {
+        return "No relevant code context found for your query.".to_string();
+    }
+    format!("{}\n{}", RETRIEVAL_HEADER, sections.join("\n---\n"))
+}
+
+/// 项目标签：取路径最后一段目录名
+pub(crate) fn project_label(project_root: &str) -> String {
+    project_root
+        .trim_end_matches(['/', '\\'])
+        .rsplit(['/', '\\'])
+        .next()
+        .filter(|s| !s.is_empty())
+        .unwrap_or(project_root)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_and_merge_interleaves_by_rank() {
+        let a = "The following code sections were retrieved:\nPath: src/a.rs\n1 fn a() {}\n---\nPath: src/b.rs\n1 fn b() {}\n";
+        let b = "The following code sections were retrieved:\nPath: lib/x.py\n1 def x(): pass\n\n";
+
+        let a_sections = split_retrieval_sections(a);
+        assert_eq!(a_sections, vec!["Path: src/a.rs\n1 fn a() {}", "Path: src/b.rs\n1 fn b() {}"]);
+
+        let merged = merge_ranked_sections(&[
+            ("api".to_string(), a_sections),
+            ("web".to_string(), split_retrieval_sections(b)),
+        ]);
+        assert_eq!(merged, vec![
+            "Path: [api] src/a.rs\n1 fn a() {}",
+            "Path: [web] lib/x.py\n1 def x(): pass",
+            "Path: [api] src/b.rs\n1 fn b() {}",
+        ]);
+    }
+
+    #[test]
+    fn test_project_label() {
+        assert_eq!(project_label("C:/work/sanshu/"), "sanshu");
+        assert_eq!(project_label("/home/u/repo"), "repo");
+    }
+}
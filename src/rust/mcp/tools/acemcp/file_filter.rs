@@ -0,0 +1,134 @@
+// 基于文件大小与内容嗅探的候选文件过滤
+// 扩展名白名单无法识别体积巨大的生成文件（如打包产物、锁文件），也会漏掉无扩展名的脚本：
+// - 超过大小上限的文件直接跳过
+// - 读取文件头部，包含 NUL 字节或大量控制字符的视为二进制文件
+// - 无扩展名但以 `#!` 开头的脚本视为可索引文本
+// - 子目录中放置标记文件（.acemcpignore / .noindex）时，整个子树不索引、不监听
+
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use super::types::AcemcpConfig;
+use crate::log_debug;
+
+/// 默认单文件大小上限（KB）
+pub const DEFAULT_MAX_FILE_SIZE_KB: u64 = 1024;
+
+/// 嗅探时读取的文件头部字节数
+const SNIFF_BYTES: usize = 8192;
+
+/// 目录级不索引标记文件
+pub const INDEX_OPT_OUT_MARKERS: &[&str] = &[".acemcpignore", ".noindex"];
+
+/// 目录中是否放置了不索引标记
+pub(crate) fn has_opt_out_marker(dir: &Path) -> bool {
+    INDEX_OPT_OUT_MARKERS.iter().any(|marker| dir.join(marker).is_file())
+}
+
+/// 文件是否位于带不索引标记的子目录中（检查项目根目录以下的各级父目录，不含根目录本身）
+pub(crate) fn in_opt_out_dir(path: &Path, root: &Path) -> bool {
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| *dir != root && dir.starts_with(root))
+        .any(has_opt_out_marker)
+}
+
+/// 文件内容过滤参数
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ContentLimits {
+    /// 单文件大小上限（字节），None 表示不限制
+    pub max_file_size: Option<u64>,
+    /// 是否跳过二进制文件
+    pub skip_binary: bool,
+}
+
+impl ContentLimits {
+    pub(crate) fn from_config(config: &AcemcpConfig) -> Self {
+        let max_kb = config.max_file_size_kb.unwrap_or(DEFAULT_MAX_FILE_SIZE_KB);
+        Self {
+            // 0 表示不限制
+            max_file_size: if max_kb == 0 { None } else { Some(max_kb * 1024) },
+            skip_binary: config.skip_binary_files.unwrap_or(true),
+        }
+    }
+}
+
+/// 判断候选文件是否可索引
+/// `ext_ok` 为扩展名白名单的匹配结果；未命中时仅接受无扩展名的 shebang 脚本
+pub(crate) fn passes_content_filters(path: &Path, ext_ok: bool, limits: &ContentLimits) -> bool {
+    content_filter_rejection(path, ext_ok, limits).is_none()
+}
+
+/// 候选文件被内容过滤拒绝的原因（可索引时返回 None）
+pub(crate) fn content_filter_rejection(path: &Path, ext_ok: bool, limits: &ContentLimits) -> Option<&'static str> {
+    let extensionless = path.extension().is_none();
+    if !ext_ok && !extensionless {
+        return Some("extension");
+    }
+
+    if let Some(max) = limits.max_file_size {
+        if fs::metadata(path).map(|m| m.len() > max).unwrap_or(false) {
+            log_debug!("文件超过大小上限，跳过: {:?}", path);
+            return Some("too_large");
+        }
+    }
+
+    if ext_ok && !limits.skip_binary {
+        return None;
+    }
+
+    let Some(head) = read_head(path) else { return Some("unreadable") };
+    if !ext_ok && !head.starts_with(b"#!") {
+        return Some("extension");
+    }
+    if limits.skip_binary && looks_binary(&head) {
+        log_debug!("检测到二进制内容，跳过: {:?}", path);
+        return Some("binary");
+    }
+    None
+}
+
+fn read_head(path: &Path) -> Option<Vec<u8>> {
+    let file = fs::File::open(path).ok()?;
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    file.take(SNIFF_BYTES as u64).read_to_end(&mut head).ok()?;
+    Some(head)
+}
+
+/// 二进制内容判断：含 NUL 字节，或不是合法 UTF-8 且控制字符占比超过 10%
+pub(crate) fn looks_binary(head: &[u8]) -> bool {
+    if head.contains(&0) {
+        return true;
+    }
+
+    match std::str::from_utf8(head) {
+        Ok(_) => false,
+        // 截断在多字节字符中间不算非法
+        Err(e) if e.error_len().is_none() => false,
+        Err(_) => {
+            let control = head
+                .iter()
+                .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x0c))
+                .count();
+            control * 10 > head.len()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_looks_binary() {
+        assert!(!looks_binary(b"fn main() {}\n"));
+        assert!(!looks_binary("中文注释".as_bytes()));
+        // 截断的 UTF-8 多字节字符
+        assert!(!looks_binary(&"中".as_bytes()[..2]));
+        assert!(looks_binary(b"\x7fELF\x02\x01\x01\x00"));
+        assert!(looks_binary(&[0x89, 0x50, 0x4e, 0x47, 0x01, 0x02, 0x03, 0x04]));
+        // GBK 编码文本不是合法 UTF-8，但没有控制字符
+        assert!(!looks_binary(&[0xd6, 0xd0, 0xce, 0xc4, b'a', b'b']));
+    }
+}
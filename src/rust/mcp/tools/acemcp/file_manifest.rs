@@ -0,0 +1,160 @@
+// 文件内容哈希清单
+// 为每个项目持久化「文件 -> (大小, 修改时间, 内容哈希, blob 名称)」记录：
+// - 大小与修改时间都未变化的文件直接复用上次的 blob，无需重新读取
+// - 仅被 touch（修改时间变化但内容不变）的文件通过内容哈希识别，同样跳过上传
+// - 移动/重命名的文件按「删除旧路径 + 新增新路径」处理，只上传新路径对应的 blob
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+
+/// 单个文件的记录
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub(crate) struct FileRecord {
+    /// 文件大小（字节）
+    pub size: u64,
+    /// 修改时间（Unix 毫秒）
+    pub mtime_ms: i64,
+    /// 文件内容的 SHA-256
+    pub content_hash: String,
+    /// 生成记录时使用的分块行数，变化后需要重新分块
+    pub max_lines: usize,
+    /// 该文件对应的 blob 名称
+    pub blob_names: Vec<String>,
+    /// 生成记录时使用的密钥扫描模式，变化后需要重新扫描
+    #[serde(default)]
+    pub secret_scan: Option<String>,
+}
+
+/// 项目的文件清单（相对路径 -> 记录）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct FileManifest {
+    pub files: HashMap<String, FileRecord>,
+    /// 上次索引时的 git HEAD（用于 git 增量扫描）
+    #[serde(default)]
+    pub git_head: Option<String>,
+    /// 上次扫描使用的过滤配置签名，变化后需要全量遍历
+    #[serde(default)]
+    pub scan_signature: Option<String>,
+}
+
+fn manifest_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".acemcp").join("data").join("file_manifests");
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// 项目对应的清单文件路径（以规范化路径的哈希命名）
+fn manifest_file(normalized_root: &str) -> PathBuf {
+    let hash = hex::encode(digest(&SHA256, normalized_root.as_bytes()).as_ref());
+    manifest_dir().join(format!("{}.json", &hash[..16]))
+}
+
+impl FileManifest {
+    /// 读取项目清单，不存在或损坏时返回空清单
+    pub(crate) fn load(normalized_root: &str) -> Self {
+        super::cache_crypto::read_to_string(manifest_file(normalized_root))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    /// 保存项目清单
+    pub(crate) fn save(&self, normalized_root: &str) -> anyhow::Result<()> {
+        let data = serde_json::to_string(self)?;
+        super::cache_crypto::write(manifest_file(normalized_root), data)?;
+        Ok(())
+    }
+
+    /// 删除项目清单
+    pub(crate) fn remove(normalized_root: &str) {
+        let _ = fs::remove_file(manifest_file(normalized_root));
+    }
+}
+
+/// 文件元数据指纹（大小, 修改时间毫秒）
+pub(crate) fn file_fingerprint(path: &Path) -> Option<(u64, i64)> {
+    let meta = fs::metadata(path).ok()?;
+    let mtime_ms = meta
+        .modified()
+        .ok()?
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_millis() as i64;
+    Some((meta.len(), mtime_ms))
+}
+
+/// 文件内容哈希
+pub(crate) fn content_hash(content: &str) -> String {
+    hex::encode(digest(&SHA256, content.as_bytes()).as_ref())
+}
+
+/// 扫描配置签名（扩展名、排除模式、分块行数与其他过滤参数）
+pub(crate) fn scan_signature(text_exts: &[String], exclude_patterns: &[String], max_lines: usize, filters: &str) -> String {
+    let raw = format!("{:?}|{:?}|{}|{}", text_exts, exclude_patterns, max_lines, filters);
+    content_hash(&raw)[..16].to_string()
+}
+
+/// 识别移动/重命名：新增文件的内容哈希与某个已消失路径相同
+/// 返回 (旧路径, 新路径) 列表
+pub(crate) fn detect_moves(
+    previous: &FileManifest,
+    current: &FileManifest,
+    added: &HashSet<String>,
+) -> Vec<(String, String)> {
+    let mut removed_by_hash: HashMap<&str, Vec<&str>> = HashMap::new();
+    for (path, record) in &previous.files {
+        if !current.files.contains_key(path) {
+            removed_by_hash.entry(record.content_hash.as_str()).or_default().push(path.as_str());
+        }
+    }
+
+    let mut added: Vec<&String> = added.iter().collect();
+    added.sort();
+    let mut moves = Vec::new();
+    for new_path in added {
+        let Some(record) = current.files.get(new_path) else { continue };
+        if let Some(candidates) = removed_by_hash.get_mut(record.content_hash.as_str()) {
+            if let Some(old_path) = candidates.pop() {
+                moves.push((old_path.to_string(), new_path.clone()));
+            }
+        }
+    }
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(hash: &str) -> FileRecord {
+        FileRecord {
+            size: 1,
+            mtime_ms: 0,
+            content_hash: hash.to_string(),
+            max_lines: 800,
+            blob_names: vec![],
+            secret_scan: None,
+        }
+    }
+
+    #[test]
+    fn test_detect_moves_matches_removed_paths_by_hash() {
+        let mut previous = FileManifest::default();
+        previous.files.insert("src/old.rs".to_string(), record("h1"));
+        previous.files.insert("src/keep.rs".to_string(), record("h2"));
+
+        let mut current = FileManifest::default();
+        current.files.insert("src/new.rs".to_string(), record("h1"));
+        current.files.insert("src/keep.rs".to_string(), record("h2"));
+        current.files.insert("src/fresh.rs".to_string(), record("h3"));
+
+        let added: HashSet<String> = ["src/new.rs".to_string(), "src/fresh.rs".to_string()].into_iter().collect();
+        let moves = detect_moves(&previous, &current, &added);
+        assert_eq!(moves, vec![("src/old.rs".to_string(), "src/new.rs".to_string())]);
+    }
+}
@@ -0,0 +1,206 @@
+// 过滤规则诊断
+// 回答“为什么这个文件没有被索引”：按与索引遍历相同的顺序（.gitignore → 排除模式 → 不索引标记 →
+// 扩展名/大小/二进制 → 密钥扫描跳过）检查候选路径，返回第一条命中的规则及其来源，无需翻日志
+
+use std::path::{Component, Path, PathBuf};
+
+use globset::Glob;
+use serde::Serialize;
+
+use super::file_filter::{content_filter_rejection, has_opt_out_marker, ContentLimits, INDEX_OPT_OUT_MARKERS};
+use super::mcp::{build_gitignore, normalize_project_root, read_file_with_encoding};
+use super::secret_scan::{describe as describe_secrets, scan_secrets, SecretScanMode};
+use super::types::AcemcpConfig;
+
+/// 候选路径的过滤诊断结果
+#[derive(Debug, Clone, Serialize)]
+pub struct FilterExplanation {
+    pub project_root: String,
+    /// 相对项目根目录的路径
+    pub path: String,
+    pub exists: bool,
+    pub is_dir: bool,
+    /// 是否会被索引（目录表示是否会被遍历）
+    pub indexable: bool,
+    /// 命中的规则类别：outside_project / gitignore / exclude_pattern / opt_out_marker /
+    /// extension / too_large / binary / unreadable / secret_skipped
+    pub reason: Option<String>,
+    /// 命中的具体规则（排除模式、.gitignore 规则、标记文件等）
+    pub rule: Option<String>,
+    /// 补充说明（规则来源文件、命中的路径片段等）
+    pub detail: Option<String>,
+}
+
+impl FilterExplanation {
+    fn rejected(mut self, reason: &str, rule: Option<String>, detail: Option<String>) -> Self {
+        self.indexable = false;
+        self.reason = Some(reason.to_string());
+        self.rule = rule;
+        self.detail = detail;
+        self
+    }
+}
+
+/// 诊断候选路径（可为绝对路径或相对项目根目录的路径）；会读取文件内容，调用方需放到阻塞线程中
+pub(crate) fn explain_path(config: &AcemcpConfig, project_root_path: &str, candidate: &str) -> FilterExplanation {
+    let root = PathBuf::from(normalize_project_root(project_root_path));
+    let full = resolve_candidate(&root, candidate.trim());
+    let rel = full
+        .strip_prefix(&root)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_else(|_| candidate.trim().replace('\\', "/"));
+
+    let is_dir = full.is_dir();
+    let explanation = FilterExplanation {
+        project_root: root.to_string_lossy().to_string(),
+        path: rel.clone(),
+        exists: full.exists(),
+        is_dir,
+        indexable: true,
+        reason: None,
+        rule: None,
+        detail: None,
+    };
+
+    if !full.starts_with(&root) {
+        return explanation.rejected("outside_project", None, Some("路径不在项目根目录下".to_string()));
+    }
+
+    // 1. .gitignore
+    if let Some(gitignore) = build_gitignore(&root) {
+        if let ignore::Match::Ignore(glob) = gitignore.matched_path_or_any_parents(&full, is_dir) {
+            let source = glob.from().map(|p| p.to_string_lossy().to_string());
+            return explanation.rejected("gitignore", Some(glob.original().to_string()), source);
+        }
+    }
+
+    // 2. 排除模式
+    let exclude_patterns = config.exclude_patterns.clone().unwrap_or_default();
+    if let Some((pattern, matched)) = matching_exclude_pattern(&rel, &exclude_patterns) {
+        return explanation.rejected("exclude_pattern", Some(pattern), Some(format!("命中路径片段: {}", matched)));
+    }
+
+    // 3. 不索引标记（项目根目录以下的各级目录，含目录自身）
+    let opt_out_dir = full
+        .ancestors()
+        .skip(if is_dir { 0 } else { 1 })
+        .take_while(|dir| *dir != root && dir.starts_with(&root))
+        .find(|dir| has_opt_out_marker(dir));
+    if let Some(dir) = opt_out_dir {
+        let marker = INDEX_OPT_OUT_MARKERS
+            .iter()
+            .find(|m| dir.join(m).is_file())
+            .map(|m| m.to_string());
+        let dir_rel = dir.strip_prefix(&root).unwrap_or(dir).to_string_lossy().replace('\\', "/");
+        return explanation.rejected("opt_out_marker", marker, Some(format!("标记所在目录: {}", dir_rel)));
+    }
+
+    if is_dir || !explanation.exists {
+        return explanation;
+    }
+
+    // 4. 扩展名 / 大小 / 二进制
+    let text_exts = config.text_extensions.clone().unwrap_or_default();
+    let ext = full.extension().and_then(|s| s.to_str()).map(|e| format!(".{}", e).to_lowercase());
+    let ext_ok = ext
+        .as_ref()
+        .map(|dot| text_exts.iter().any(|te| te.eq_ignore_ascii_case(dot)))
+        .unwrap_or(false);
+    let limits = ContentLimits::from_config(config);
+    if let Some(reason) = content_filter_rejection(&full, ext_ok, &limits) {
+        let (rule, detail) = match reason {
+            "extension" => (ext, Some("扩展名不在 text_extensions 中（无扩展名文件需以 #! 开头）".to_string())),
+            "too_large" => (
+                limits.max_file_size.map(|max| format!("max_file_size_kb = {}", max / 1024)),
+                None,
+            ),
+            "binary" => (Some("skip_binary_files = true".to_string()), None),
+            _ => (None, None),
+        };
+        return explanation.rejected(reason, rule, detail);
+    }
+
+    // 5. 密钥扫描（skip 模式下整个文件被跳过）
+    if SecretScanMode::from_config(config.secret_scan.as_deref()) == SecretScanMode::Skip {
+        if let Some(content) = read_file_with_encoding(&full) {
+            let secrets = scan_secrets(&content);
+            if !secrets.is_empty() {
+                return explanation.rejected(
+                    "secret_skipped",
+                    Some("secret_scan = \"skip\"".to_string()),
+                    Some(format!("检测到: {}", describe_secrets(&secrets))),
+                );
+            }
+        }
+    }
+
+    explanation
+}
+
+/// 解析候选路径：相对路径基于项目根目录，先按字面消去 "." 与 ".."，再在路径存在时解析符号链接，
+/// 避免 "../other/file" 这样的路径通过项目根目录的前缀检查
+fn resolve_candidate(root: &Path, candidate: &str) -> PathBuf {
+    let mut resolved = PathBuf::new();
+    for component in root.join(candidate).components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if matches!(resolved.components().next_back(), Some(Component::Normal(_))) {
+                    resolved.pop();
+                }
+            }
+            other => resolved.push(other),
+        }
+    }
+    PathBuf::from(normalize_project_root(&resolved.to_string_lossy()))
+}
+
+/// 找出第一个命中的排除模式，返回（模式, 命中的路径片段）
+///
+/// 与索引遍历一致：依次匹配各级父目录的相对路径、文件自身的相对路径以及每个路径组件
+fn matching_exclude_pattern(rel: &str, patterns: &[String]) -> Option<(String, String)> {
+    let components: Vec<&str> = rel.split('/').filter(|c| !c.is_empty()).collect();
+    let mut candidates: Vec<String> = (1..=components.len()).map(|n| components[..n].join("/")).collect();
+    candidates.extend(components.iter().map(|c| c.to_string()));
+
+    patterns.iter().find_map(|pattern| {
+        let matcher = Glob::new(pattern).ok()?.compile_matcher();
+        candidates
+            .iter()
+            .find(|c| matcher.is_match(c.as_str()))
+            .map(|c| (pattern.clone(), c.clone()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matching_exclude_pattern() {
+        let patterns = vec!["node_modules".to_string(), "*.min.js".to_string(), "src/generated".to_string()];
+        assert_eq!(
+            matching_exclude_pattern("web/node_modules/react/index.js", &patterns),
+            Some(("node_modules".to_string(), "node_modules".to_string()))
+        );
+        assert_eq!(
+            matching_exclude_pattern("static/app.min.js", &patterns),
+            Some(("*.min.js".to_string(), "static/app.min.js".to_string()))
+        );
+        assert_eq!(
+            matching_exclude_pattern("src/generated/api.rs", &patterns),
+            Some(("src/generated".to_string(), "src/generated".to_string()))
+        );
+        assert_eq!(matching_exclude_pattern("src/main.rs", &patterns), None);
+    }
+
+    #[test]
+    fn test_resolve_candidate_removes_parent_dirs() {
+        let root = Path::new("/nonexistent-project");
+        assert_eq!(resolve_candidate(root, "src/./main.rs"), Path::new("/nonexistent-project/src/main.rs"));
+        assert!(!resolve_candidate(root, "../other/secret.txt").starts_with(root));
+        assert!(!resolve_candidate(root, "src/../../other").starts_with(root));
+        assert!(!resolve_candidate(root, "/nonexistent-project/../etc/passwd").starts_with(root));
+        assert_eq!(resolve_candidate(root, "src/../lib.rs"), Path::new("/nonexistent-project/lib.rs"));
+    }
+}
@@ -0,0 +1,65 @@
+// Git 感知的增量索引
+// 项目是 git 仓库时，通过 `git diff`（相对上次索引的提交，含工作区改动）与未跟踪文件列表
+// 得到变更文件集合，避免每次都遍历整个目录树
+
+use std::collections::HashSet;
+use std::path::Path;
+use std::process::Command;
+
+fn git(root: &Path, args: &[&str]) -> Option<Vec<u8>> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C").arg(root).args(args);
+
+    // Windows 下避免弹出控制台窗口
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(0x08000000);
+    }
+
+    let output = cmd.output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    Some(output.stdout)
+}
+
+/// 当前 HEAD 提交，非 git 仓库或尚无提交时返回 None
+pub(crate) fn head_commit(root: &Path) -> Option<String> {
+    let out = git(root, &["rev-parse", "HEAD"])?;
+    let head = String::from_utf8_lossy(&out).trim().to_string();
+    if head.is_empty() { None } else { Some(head) }
+}
+
+/// 相对 `since` 提交发生变化的文件（路径相对于项目根目录）
+/// 包括此后的提交、暂存区与工作区改动，以及所有未跟踪文件；重命名拆分为删除+新增
+/// 任何 git 命令失败（如提交已不存在）时返回 None，由调用方回退到全量遍历
+pub(crate) fn changed_paths(root: &Path, since: &str) -> Option<HashSet<String>> {
+    let diff = git(root, &["diff", "--name-only", "--no-renames", "--relative", "-z", since])?;
+    let untracked = git(root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+
+    let mut paths = parse_nul_list(&diff);
+    paths.extend(parse_nul_list(&untracked));
+    Some(paths)
+}
+
+/// 解析 `-z` 输出（以 NUL 分隔的路径列表）
+fn parse_nul_list(out: &[u8]) -> HashSet<String> {
+    out.split(|b| *b == 0)
+        .filter(|s| !s.is_empty())
+        .map(|s| String::from_utf8_lossy(s).replace('\\', "/"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_nul_list() {
+        let paths = parse_nul_list(b"src/a.rs\0docs/read me.md\0\0");
+        assert_eq!(paths.len(), 2);
+        assert!(paths.contains("src/a.rs"));
+        assert!(paths.contains("docs/read me.md"));
+    }
+}
@@ -0,0 +1,293 @@
+// 检索后端健康检查
+// 后台定期探测配置的 base_url（走与索引/检索相同的代理与共享客户端），记录可用性与延迟，
+// 前端据此在 agent 运行前展示后端状态，而不是等到索引或检索中途失败才发现
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use reqwest::header::AUTHORIZATION;
+use serde::Serialize;
+
+use super::types::AcemcpConfig;
+use crate::log_debug;
+use crate::log_important;
+
+/// 默认检查间隔（秒）
+pub const DEFAULT_HEALTH_CHECK_INTERVAL_SECS: u64 = 300;
+
+/// 单次探测超时（秒）
+const PROBE_TIMEOUT_SECS: u64 = 10;
+
+/// 最多保留的探测记录数
+const MAX_SAMPLES: usize = 100;
+
+/// 检查关闭或未配置时的轮询间隔（秒），便于配置变更后及时生效
+const IDLE_POLL_SECS: u64 = 60;
+
+/// 探测结论
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// 收到 2xx 响应
+    Available,
+    /// 令牌无效或权限不足（401/403）
+    Unauthorized,
+    /// 被限流（429）
+    RateLimited,
+    /// 收到其他非 2xx 响应
+    Error,
+    /// 请求未能完成（连接失败、超时等）
+    Unreachable,
+}
+
+impl HealthStatus {
+    fn from_status_code(status: reqwest::StatusCode) -> Self {
+        match status.as_u16() {
+            200..=299 => Self::Available,
+            401 | 403 => Self::Unauthorized,
+            429 => Self::RateLimited,
+            _ => Self::Error,
+        }
+    }
+}
+
+/// 单次探测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSample {
+    pub checked_at: DateTime<Utc>,
+    pub status: HealthStatus,
+    /// 后端是否可用（收到 2xx 响应）
+    pub available: bool,
+    /// 往返延迟（毫秒），请求失败时为 None
+    pub latency_ms: Option<u64>,
+    pub status_code: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// 健康状态汇总
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthReport {
+    /// 探测的后端地址
+    pub base_url: Option<String>,
+    /// 是否经由代理探测
+    pub via_proxy: bool,
+    /// 最近的探测记录（最新的在前）
+    pub samples: Vec<HealthSample>,
+    /// 返回记录中的可用率（0-1），无记录时为 None
+    pub availability: Option<f64>,
+    /// 返回记录中成功探测的平均延迟（毫秒）
+    pub avg_latency_ms: Option<u64>,
+}
+
+struct HealthState {
+    base_url: Option<String>,
+    via_proxy: bool,
+    samples: VecDeque<HealthSample>,
+}
+
+static HEALTH: Lazy<Mutex<HealthState>> = Lazy::new(|| {
+    Mutex::new(HealthState {
+        base_url: None,
+        via_proxy: false,
+        samples: VecDeque::new(),
+    })
+});
+
+static MONITOR_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 探测一次后端并记录结果；未配置 base_url 或使用本地后端时返回 None
+pub async fn check_now(config: &AcemcpConfig) -> Option<HealthSample> {
+    if config.is_local_backend() {
+        return None;
+    }
+    let base_url = config.base_url.as_deref()?.trim().trim_end_matches('/').to_string();
+    if base_url.is_empty() {
+        return None;
+    }
+
+    let sample = probe(config, &base_url).await;
+    record(&base_url, config.proxy_enabled.unwrap_or(false), sample.clone());
+    Some(sample)
+}
+
+async fn probe(config: &AcemcpConfig, base_url: &str) -> HealthSample {
+    let mut sample = HealthSample {
+        checked_at: Utc::now(),
+        status: HealthStatus::Unreachable,
+        available: false,
+        latency_ms: None,
+        status_code: None,
+        error: None,
+    };
+
+    let client = match super::http_pool::shared_client(config) {
+        Ok(client) => client,
+        Err(e) => {
+            sample.error = Some(e.to_string());
+            return sample;
+        }
+    };
+
+    let mut request = client
+        .get(format!("{}/health", base_url))
+        .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS));
+    if let Some(token) = config.token.as_deref().filter(|t| !t.trim().is_empty()) {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+
+    let start = Instant::now();
+    match request.send().await {
+        Ok(response) => {
+            let status = response.status();
+            sample.latency_ms = Some(start.elapsed().as_millis() as u64);
+            sample.status_code = Some(status.as_u16());
+            sample.status = HealthStatus::from_status_code(status);
+            sample.available = sample.status == HealthStatus::Available;
+            if !sample.available {
+                sample.error = Some(format!("HTTP {}", status.as_u16()));
+            }
+        }
+        Err(e) => sample.error = Some(e.to_string()),
+    }
+    sample
+}
+
+fn record(base_url: &str, via_proxy: bool, sample: HealthSample) {
+    let Ok(mut state) = HEALTH.lock() else {
+        return;
+    };
+    // 后端地址或代理变更后，旧记录不再有参考意义
+    if state.base_url.as_deref() != Some(base_url) || state.via_proxy != via_proxy {
+        state.samples.clear();
+        state.base_url = Some(base_url.to_string());
+        state.via_proxy = via_proxy;
+    }
+    state.samples.push_front(sample);
+    state.samples.truncate(MAX_SAMPLES);
+}
+
+/// 获取最近 limit 条探测记录及汇总
+pub fn get_report(limit: Option<usize>) -> HealthReport {
+    let Ok(state) = HEALTH.lock() else {
+        return HealthReport::default();
+    };
+    let samples: Vec<HealthSample> = state
+        .samples
+        .iter()
+        .take(limit.unwrap_or(MAX_SAMPLES))
+        .cloned()
+        .collect();
+    summarize(state.base_url.clone(), state.via_proxy, samples)
+}
+
+fn summarize(base_url: Option<String>, via_proxy: bool, samples: Vec<HealthSample>) -> HealthReport {
+    let availability = if samples.is_empty() {
+        None
+    } else {
+        Some(samples.iter().filter(|s| s.available).count() as f64 / samples.len() as f64)
+    };
+    let latencies: Vec<u64> = samples
+        .iter()
+        .filter(|s| s.available)
+        .filter_map(|s| s.latency_ms)
+        .collect();
+    let avg_latency_ms = if latencies.is_empty() {
+        None
+    } else {
+        Some(latencies.iter().sum::<u64>() / latencies.len() as u64)
+    };
+
+    HealthReport {
+        base_url,
+        via_proxy,
+        samples,
+        availability,
+        avg_latency_ms,
+    }
+}
+
+/// 启动后台健康检查任务（进程内只启动一次）
+pub fn ensure_health_monitor() {
+    if MONITOR_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        log_important!(info, "后端健康检查任务已启动");
+        loop {
+            let config = match super::AcemcpTool::get_acemcp_config().await {
+                Ok(c) => c,
+                Err(e) => {
+                    log_debug!("健康检查读取配置失败: {}", e);
+                    tokio::time::sleep(Duration::from_secs(IDLE_POLL_SECS)).await;
+                    continue;
+                }
+            };
+
+            let interval = config
+                .health_check_interval_secs
+                .unwrap_or(DEFAULT_HEALTH_CHECK_INTERVAL_SECS);
+            if interval == 0 {
+                tokio::time::sleep(Duration::from_secs(IDLE_POLL_SECS)).await;
+                continue;
+            }
+
+            if let Some(sample) = check_now(&config).await {
+                if sample.available {
+                    log_debug!("后端健康检查正常: latency={:?}ms", sample.latency_ms);
+                } else {
+                    log_important!(warn, "后端健康检查失败: {}", sample.error.as_deref().unwrap_or("未知错误"));
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(available: bool, latency_ms: Option<u64>) -> HealthSample {
+        HealthSample {
+            checked_at: Utc::now(),
+            status: if available { HealthStatus::Available } else { HealthStatus::Unreachable },
+            available,
+            latency_ms,
+            status_code: None,
+            error: None,
+        }
+    }
+
+    #[test]
+    fn test_summarize_health() {
+        let report = summarize(
+            Some("https://ace.example.com".to_string()),
+            false,
+            vec![sample(true, Some(100)), sample(false, None), sample(true, Some(200)), sample(false, Some(5))],
+        );
+        assert_eq!(report.availability, Some(0.5));
+        assert_eq!(report.avg_latency_ms, Some(150));
+
+        let empty = summarize(None, false, Vec::new());
+        assert!(empty.availability.is_none());
+        assert!(empty.avg_latency_ms.is_none());
+    }
+
+    #[test]
+    fn test_status_from_code() {
+        use reqwest::StatusCode;
+        assert_eq!(HealthStatus::from_status_code(StatusCode::OK), HealthStatus::Available);
+        assert_eq!(HealthStatus::from_status_code(StatusCode::NO_CONTENT), HealthStatus::Available);
+        assert_eq!(HealthStatus::from_status_code(StatusCode::UNAUTHORIZED), HealthStatus::Unauthorized);
+        assert_eq!(HealthStatus::from_status_code(StatusCode::FORBIDDEN), HealthStatus::Unauthorized);
+        assert_eq!(HealthStatus::from_status_code(StatusCode::TOO_MANY_REQUESTS), HealthStatus::RateLimited);
+        assert_eq!(HealthStatus::from_status_code(StatusCode::NOT_FOUND), HealthStatus::Error);
+        assert_eq!(HealthStatus::from_status_code(StatusCode::BAD_GATEWAY), HealthStatus::Error);
+    }
+}
@@ -0,0 +1,95 @@
+// 结构化检索结果
+// 检索结果文本之外，同时在 CallToolResult.structured_content 中返回 {path, start_line, end_line, snippet, score} 数组，
+// 客户端可据此直接打开文件并定位到对应行，无需用正则解析文本
+
+use serde::Serialize;
+
+/// 单个检索命中
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct SearchHit {
+    /// 多项目检索时的项目标签
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// 相对项目根目录的文件路径
+    pub path: String,
+    /// 片段起始行（1 起始，片段不含行号时为 None）
+    pub start_line: Option<u32>,
+    pub end_line: Option<u32>,
+    /// 去掉行号后的代码片段
+    pub snippet: String,
+    /// 相关度分数（0-1，按返回排名折算，越大越相关）
+    pub score: f64,
+}
+
+/// 将检索片段（每段以 `Path: ` 行开头，已按排名排列）转换为结构化命中
+pub(crate) fn parse_hits(sections: &[String]) -> Vec<SearchHit> {
+    let total = sections.len();
+    sections
+        .iter()
+        .enumerate()
+        .filter_map(|(rank, section)| parse_section(section, rank, total))
+        .collect()
+}
+
+fn parse_section(section: &str, rank: usize, total: usize) -> Option<SearchHit> {
+    let mut lines = section.lines();
+    let header = lines.next()?.strip_prefix("Path: ")?.trim();
+
+    // 多项目检索的片段带有 `[label] ` 前缀
+    let (project, path) = match header.strip_prefix('[').and_then(|rest| rest.split_once("] ")) {
+        Some((label, path)) => (Some(label.to_string()), path.trim().to_string()),
+        None => (None, header.to_string()),
+    };
+
+    let mut start_line = None;
+    let mut end_line = None;
+    let mut snippet_lines = Vec::new();
+    for line in lines {
+        match split_numbered_line(line) {
+            Some((number, code)) => {
+                start_line.get_or_insert(number);
+                end_line = Some(number);
+                snippet_lines.push(code);
+            }
+            None => snippet_lines.push(line),
+        }
+    }
+
+    Some(SearchHit {
+        project,
+        path,
+        start_line,
+        end_line,
+        snippet: snippet_lines.join("\n"),
+        score: (total - rank) as f64 / total as f64,
+    })
+}
+
+/// 解析 `   12\tcode` 形式的带行号行
+fn split_numbered_line(line: &str) -> Option<(u32, &str)> {
+    let (number, code) = line.split_once('\t')?;
+    let number = number.trim().parse().ok()?;
+    Some((number, code))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hits() {
+        let hits = parse_hits(&[
+            "Path: src/a.rs\n    10\tfn a() {\n    11\t}".to_string(),
+            "Path: [web] lib/x.py\ndef x(): pass".to_string(),
+        ]);
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].path, "src/a.rs");
+        assert_eq!((hits[0].start_line, hits[0].end_line), (Some(10), Some(11)));
+        assert_eq!(hits[0].snippet, "fn a() {\n}");
+        assert_eq!(hits[0].score, 1.0);
+        assert_eq!(hits[1].project.as_deref(), Some("web"));
+        assert_eq!(hits[1].path, "lib/x.py");
+        assert_eq!(hits[1].start_line, None);
+        assert_eq!(hits[1].score, 0.5);
+    }
+}
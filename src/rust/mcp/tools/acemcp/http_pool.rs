@@ -0,0 +1,129 @@
+// 共享 HTTP 客户端
+// 以前每次上传/检索都会新建 reqwest::Client，连接无法复用，每个批次都要重新握手 TCP + TLS。
+// 这里按代理配置缓存客户端（懒初始化），开启 keep-alive 连接池，服务端支持时通过 ALPN 协商 HTTP/2，
+// 批量上传与 agent 循环中的重复检索都能复用已建立的连接
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use reqwest::Client;
+
+use super::types::AcemcpConfig;
+use crate::log_debug;
+use crate::log_important;
+
+/// 请求超时（秒）
+const REQUEST_TIMEOUT_SECS: u64 = 60;
+
+/// 空闲连接保留时间（秒）
+const POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+/// 每个主机最多保留的空闲连接数（与上传并发度匹配即可）
+const POOL_MAX_IDLE_PER_HOST: usize = 16;
+
+/// 最多缓存的客户端数（代理配置频繁变化时避免无限增长）
+const MAX_CLIENTS: usize = 8;
+
+/// 客户端缓存键：代理配置相同的请求共享同一个客户端
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientKey {
+    /// None 表示直连
+    proxy_url: Option<String>,
+    username: Option<String>,
+    password: Option<String>,
+}
+
+impl ClientKey {
+    fn from_config(config: &AcemcpConfig) -> anyhow::Result<Self> {
+        if !config.proxy_enabled.unwrap_or(false) {
+            return Ok(Self { proxy_url: None, username: None, password: None });
+        }
+
+        let host = config.proxy_host.clone().unwrap_or_else(|| "127.0.0.1".to_string());
+        let port = config.proxy_port.unwrap_or(7890);
+        let proxy_type = config.proxy_type.clone().unwrap_or_else(|| "http".to_string());
+
+        // 校验代理类型，避免拼接出无效 URL
+        match proxy_type.as_str() {
+            "http" | "https" | "socks5" => {}
+            other => anyhow::bail!("不支持的代理类型: {}（仅支持 http/https/socks5）", other),
+        }
+
+        let username = config
+            .proxy_username
+            .as_deref()
+            .map(str::trim)
+            .filter(|u| !u.is_empty())
+            .map(str::to_string);
+        let password = username
+            .as_ref()
+            .map(|_| config.proxy_password.clone().unwrap_or_default());
+
+        Ok(Self {
+            proxy_url: Some(format!("{}://{}:{}", proxy_type, host, port)),
+            username,
+            password,
+        })
+    }
+}
+
+static CLIENTS: Lazy<Mutex<HashMap<ClientKey, Client>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 获取与配置中代理设置对应的共享客户端（首次使用时创建）
+pub(crate) fn shared_client(config: &AcemcpConfig) -> anyhow::Result<Client> {
+    let key = ClientKey::from_config(config)?;
+
+    let mut clients = CLIENTS
+        .lock()
+        .map_err(|e| anyhow::anyhow!("获取 HTTP 客户端缓存失败: {}", e))?;
+    if let Some(client) = clients.get(&key) {
+        // reqwest::Client 内部为 Arc，clone 共享同一个连接池
+        return Ok(client.clone());
+    }
+
+    let client = build_client(&key)?;
+    if clients.len() >= MAX_CLIENTS {
+        clients.clear();
+    }
+    clients.insert(key, client.clone());
+    Ok(client)
+}
+
+fn build_client(key: &ClientKey) -> anyhow::Result<Client> {
+    let mut client_builder = Client::builder()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .pool_idle_timeout(Duration::from_secs(POOL_IDLE_TIMEOUT_SECS))
+        .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+        .tcp_keepalive(Duration::from_secs(60))
+        .http2_adaptive_window(true)
+        .http2_keep_alive_interval(Duration::from_secs(30))
+        .http2_keep_alive_while_idle(true);
+
+    match &key.proxy_url {
+        Some(proxy_url) => {
+            // 仅用于日志提示（避免泄露密码）
+            if key.username.is_some() {
+                log_important!(info, "🔧 创建共享 HTTP 客户端，使用代理: {}（带认证）", proxy_url);
+            } else {
+                log_important!(info, "🔧 创建共享 HTTP 客户端，使用代理: {}", proxy_url);
+            }
+
+            // 使用 Proxy::all() 让所有请求都走代理
+            let mut reqwest_proxy = reqwest::Proxy::all(proxy_url)
+                .map_err(|e| anyhow::anyhow!("创建代理失败: {}", e))?;
+
+            // 代理认证（Basic Auth）
+            if let Some(username) = key.username.as_deref() {
+                reqwest_proxy = reqwest_proxy.basic_auth(username, key.password.as_deref().unwrap_or(""));
+            }
+
+            client_builder = client_builder.proxy(reqwest_proxy);
+        }
+        None => log_debug!("创建共享 HTTP 客户端，使用直连模式（未启用代理）"),
+    }
+
+    client_builder.build()
+        .map_err(|e| anyhow::anyhow!("构建 HTTP 客户端失败: {}", e))
+}
@@ -0,0 +1,208 @@
+// 索引运行历史
+// 按项目持久化每次索引的时间线（开始/结束时间、变更文件数、上传字节数、失败情况），
+// 便于用户判断索引是否越来越慢、哪些运行失败了
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+
+use crate::log_debug;
+
+/// 每个项目最多保留的运行记录数
+const MAX_RUNS_PER_PROJECT: usize = 200;
+
+/// 趋势对比时每组使用的运行次数（最近 N 次 vs 之前 N 次）
+const TREND_WINDOW: usize = 10;
+
+/// 单次索引运行记录
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IndexRunRecord {
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    /// 耗时（毫秒）
+    pub duration_ms: u64,
+    pub success: bool,
+    /// 新增或内容变化的文件数
+    pub files_changed: usize,
+    /// 已删除的文件数
+    pub files_deleted: usize,
+    /// 上传成功的 blob 数
+    pub blobs_uploaded: usize,
+    /// 上传成功的内容字节数（压缩前）
+    pub bytes_uploaded: u64,
+    /// 上传失败的批次数
+    pub failed_batches: usize,
+    /// 上传失败的文件数
+    pub failed_files: usize,
+    /// 失败原因（整体失败或部分批次失败时）
+    pub error: Option<String>,
+}
+
+/// 运行历史的汇总趋势
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndexHistorySummary {
+    pub total_runs: usize,
+    pub failed_runs: usize,
+    /// 最近 N 次成功运行的平均耗时（毫秒）
+    pub recent_avg_duration_ms: Option<u64>,
+    /// 之前 N 次成功运行的平均耗时（毫秒）
+    pub previous_avg_duration_ms: Option<u64>,
+    /// 最近的运行是否比之前明显变慢（平均耗时增加 50% 以上）
+    pub slowing_down: bool,
+    pub last_failure_at: Option<DateTime<Utc>>,
+}
+
+/// 项目的运行历史（按时间倒序）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndexHistory {
+    pub project_root: String,
+    pub runs: Vec<IndexRunRecord>,
+    pub summary: IndexHistorySummary,
+}
+
+/// 运行期间累计的统计，由 update_index 填充
+#[derive(Debug, Default)]
+pub(crate) struct RunStats {
+    pub files_changed: usize,
+    pub files_deleted: usize,
+    pub blobs_uploaded: usize,
+    pub bytes_uploaded: u64,
+    pub failed_batches: usize,
+    pub failed_files: usize,
+    /// 部分失败时的说明（整体失败时使用返回的错误）
+    pub partial_error: Option<String>,
+}
+
+/// 历史文件读写锁（并发索引多个项目时避免交叉写入）
+static HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+fn history_file(normalized_root: &str) -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".acemcp").join("data").join("index_history");
+    let _ = fs::create_dir_all(&dir);
+    let hash = hex::encode(digest(&SHA256, normalized_root.as_bytes()).as_ref());
+    dir.join(format!("{}.json", &hash[..16]))
+}
+
+fn load_runs(normalized_root: &str) -> Vec<IndexRunRecord> {
+    super::cache_crypto::read_to_string(history_file(normalized_root))
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// 追加一次运行记录（超出上限时丢弃最早的记录）
+pub(crate) fn record_run(normalized_root: &str, record: IndexRunRecord) {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut runs = load_runs(normalized_root);
+    runs.push(record);
+    if runs.len() > MAX_RUNS_PER_PROJECT {
+        let overflow = runs.len() - MAX_RUNS_PER_PROJECT;
+        runs.drain(..overflow);
+    }
+    match serde_json::to_string(&runs) {
+        Ok(data) => {
+            if let Err(e) = super::cache_crypto::write(history_file(normalized_root), data) {
+                log_debug!("保存索引历史失败（不影响索引）: {}", e);
+            }
+        }
+        Err(e) => log_debug!("序列化索引历史失败: {}", e),
+    }
+}
+
+/// 根据运行结果生成记录
+pub(crate) fn build_record(started_at: DateTime<Utc>, stats: RunStats, result: &anyhow::Result<Vec<String>>) -> IndexRunRecord {
+    let finished_at = Utc::now();
+    let (success, error) = match result {
+        Ok(_) => (true, stats.partial_error),
+        Err(e) => (false, Some(e.to_string())),
+    };
+    IndexRunRecord {
+        started_at: Some(started_at),
+        finished_at: Some(finished_at),
+        duration_ms: (finished_at - started_at).num_milliseconds().max(0) as u64,
+        success,
+        files_changed: stats.files_changed,
+        files_deleted: stats.files_deleted,
+        blobs_uploaded: stats.blobs_uploaded,
+        bytes_uploaded: stats.bytes_uploaded,
+        failed_batches: stats.failed_batches,
+        failed_files: stats.failed_files,
+        error,
+    }
+}
+
+/// 读取项目的运行历史（最新的在前），`limit` 为返回的最大条数
+pub(crate) fn load_history(normalized_root: &str, limit: Option<usize>) -> IndexHistory {
+    let runs = {
+        let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        load_runs(normalized_root)
+    };
+    let summary = summarize(&runs);
+    let mut runs: Vec<IndexRunRecord> = runs.into_iter().rev().collect();
+    if let Some(limit) = limit {
+        runs.truncate(limit);
+    }
+    IndexHistory { project_root: normalized_root.to_string(), runs, summary }
+}
+
+/// 删除项目的运行历史
+pub(crate) fn remove_history(normalized_root: &str) {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let _ = fs::remove_file(history_file(normalized_root));
+}
+
+/// 汇总运行历史（`runs` 按时间正序）
+fn summarize(runs: &[IndexRunRecord]) -> IndexHistorySummary {
+    let successful: Vec<u64> = runs.iter().filter(|r| r.success).map(|r| r.duration_ms).collect();
+    let average = |slice: &[u64]| -> Option<u64> {
+        if slice.is_empty() { None } else { Some(slice.iter().sum::<u64>() / slice.len() as u64) }
+    };
+
+    let recent_start = successful.len().saturating_sub(TREND_WINDOW);
+    let previous_start = recent_start.saturating_sub(TREND_WINDOW);
+    let recent_avg = average(&successful[recent_start..]);
+    let previous_avg = average(&successful[previous_start..recent_start]);
+
+    IndexHistorySummary {
+        total_runs: runs.len(),
+        failed_runs: runs.iter().filter(|r| !r.success).count(),
+        recent_avg_duration_ms: recent_avg,
+        previous_avg_duration_ms: previous_avg,
+        slowing_down: matches!((recent_avg, previous_avg), (Some(r), Some(p)) if p > 0 && r * 2 > p * 3),
+        last_failure_at: runs.iter().rev().find(|r| !r.success).and_then(|r| r.finished_at),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(duration_ms: u64, success: bool) -> IndexRunRecord {
+        IndexRunRecord { duration_ms, success, ..Default::default() }
+    }
+
+    #[test]
+    fn test_summarize_detects_slowdown() {
+        let mut runs: Vec<IndexRunRecord> = (0..TREND_WINDOW).map(|_| run(1000, true)).collect();
+        runs.push(run(50, false));
+        runs.extend((0..TREND_WINDOW).map(|_| run(2000, true)));
+
+        let summary = summarize(&runs);
+        assert_eq!(summary.total_runs, 2 * TREND_WINDOW + 1);
+        assert_eq!(summary.failed_runs, 1);
+        assert_eq!(summary.recent_avg_duration_ms, Some(2000));
+        assert_eq!(summary.previous_avg_duration_ms, Some(1000));
+        assert!(summary.slowing_down);
+
+        let steady: Vec<IndexRunRecord> = (0..5).map(|_| run(1000, true)).collect();
+        let summary = summarize(&steady);
+        assert_eq!(summary.previous_avg_duration_ms, None);
+        assert!(!summary.slowing_down);
+    }
+}
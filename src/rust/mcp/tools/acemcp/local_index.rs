@@ -0,0 +1,392 @@
+// 本地离线索引后端
+// 基于 BM25 的纯 Rust 倒排索引，不依赖远程 codebase-retrieval API，适用于离线/内网环境
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::file_filter::ContentLimits;
+use super::mcp::{collect_blobs, normalize_project_root, update_project_status};
+use super::progress::{IndexProgressTracker, IndexStage};
+use super::types::{AcemcpConfig, IndexStatus};
+use crate::log_important;
+
+/// BM25 参数
+const BM25_K1: f64 = 1.2;
+const BM25_B: f64 = 0.75;
+
+/// 默认返回的结果数量
+const DEFAULT_TOP_K: usize = 8;
+
+/// 每个结果展示的最大行数
+const SNIPPET_MAX_LINES: usize = 40;
+
+/// 本地索引中的单个文档（对应一个 blob 分块）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LocalDoc {
+    /// 相对路径（不含分块后缀）
+    path: String,
+    /// 分块在文件中的起始行号（从 1 开始）
+    start_line: usize,
+    /// 分块内容
+    content: String,
+    /// 词项数量（用于长度归一化）
+    length: u32,
+}
+
+/// 本地索引文件结构
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct LocalIndex {
+    project_root: String,
+    built_at: Option<DateTime<Utc>>,
+    docs: Vec<LocalDoc>,
+    /// 倒排表：词项 -> [(文档序号, 词频)]
+    postings: HashMap<String, Vec<(u32, u32)>>,
+    avg_doc_len: f64,
+}
+
+/// 本地索引目录：~/.acemcp/data/local_index
+fn local_index_dir() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".acemcp").join("data").join("local_index");
+    let _ = fs::create_dir_all(&dir);
+    dir
+}
+
+/// 项目对应的本地索引文件路径（以规范化路径的哈希命名）
+fn local_index_file(normalized_root: &str) -> PathBuf {
+    let digest = ring::digest::digest(&ring::digest::SHA256, normalized_root.as_bytes());
+    let name = hex::encode(digest.as_ref());
+    local_index_dir().join(format!("{}.json", &name[..16]))
+}
+
+/// 删除项目的本地索引文件（项目索引被移除时调用）
+pub(crate) fn remove_local_index(project_root: &str) {
+    let path = local_index_file(&normalize_project_root(project_root));
+    if path.exists() {
+        let _ = fs::remove_file(path);
+    }
+}
+
+/// 分词：按非字母数字字符切分，拆分 snake_case / camelCase，CJK 字符逐字成词
+fn tokenize(text: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut word = String::new();
+
+    let flush = |word: &mut String, tokens: &mut Vec<String>| {
+        if word.is_empty() {
+            return;
+        }
+        push_word_tokens(word, tokens);
+        word.clear();
+    };
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            flush(&mut word, &mut tokens);
+            tokens.push(c.to_string());
+        } else if c.is_alphanumeric() || c == '_' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut tokens);
+        }
+    }
+    flush(&mut word, &mut tokens);
+
+    tokens
+}
+
+/// 将一个标识符拆分为词项（完整词 + 子词）
+fn push_word_tokens(word: &str, tokens: &mut Vec<String>) {
+    let lower = word.to_lowercase();
+    if lower.chars().count() >= 2 {
+        tokens.push(lower.clone());
+    }
+
+    // 拆分 snake_case 与 camelCase
+    let mut parts: Vec<String> = Vec::new();
+    for segment in word.split('_').filter(|s| !s.is_empty()) {
+        let mut current = String::new();
+        let mut prev_lower = false;
+        for c in segment.chars() {
+            if c.is_uppercase() && prev_lower && !current.is_empty() {
+                parts.push(std::mem::take(&mut current));
+            }
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            current.push(c);
+        }
+        if !current.is_empty() {
+            parts.push(current);
+        }
+    }
+
+    if parts.len() > 1 {
+        for part in parts {
+            let part = part.to_lowercase();
+            if part.chars().count() >= 2 && part != lower {
+                tokens.push(part);
+            }
+        }
+    }
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x4E00..=0x9FFF | 0x3400..=0x4DBF | 0x3040..=0x30FF | 0xAC00..=0xD7AF)
+}
+
+/// 去掉分块路径的后缀（`path#chunk{i}of{n}` -> `path`）
+fn strip_chunk_suffix(chunk_path: &str) -> &str {
+    match chunk_path.rfind("#chunk") {
+        Some(pos) => &chunk_path[..pos],
+        None => chunk_path,
+    }
+}
+
+/// 构建项目的本地 BM25 索引（替代远程上传）
+/// 返回值：已索引的分块路径列表
+pub(crate) async fn update_local_index(config: &AcemcpConfig, project_root_path: &str) -> Result<Vec<String>> {
+    let max_lines = config.max_lines_per_blob.unwrap_or(800) as usize;
+    let text_exts = config.text_extensions.clone().unwrap_or_default();
+    let exclude_patterns = config.exclude_patterns.clone().unwrap_or_default();
+
+    let _ = update_project_status(project_root_path, |status| {
+        status.status = IndexStatus::Indexing;
+        status.progress = 0;
+    });
+
+    log_important!(info, "=== 开始构建本地索引 === 项目路径: {}", project_root_path);
+
+    let normalized_root = normalize_project_root(project_root_path);
+    let blobs = match collect_blobs(project_root_path, &text_exts, &exclude_patterns, max_lines, &ContentLimits::from_config(config)) {
+        Ok(b) if !b.is_empty() => b,
+        result => {
+            let error = match result {
+                Err(e) => e.to_string(),
+                Ok(_) => "未在项目中找到可索引的文本文件".to_string(),
+            };
+            let _ = update_project_status(project_root_path, |status| {
+                status.status = IndexStatus::Failed;
+                status.last_error = Some(error.clone());
+                status.last_failure_time = Some(Utc::now());
+            });
+            IndexProgressTracker::new(&normalized_root, std::iter::empty())
+                .stage(IndexStage::Failed, 0, Some(error.clone()));
+            anyhow::bail!(error);
+        }
+    };
+
+    let mut tracker = IndexProgressTracker::new(&normalized_root, blobs.iter().map(|b| b.path.as_str()));
+    tracker.stage(IndexStage::Collecting, 20, None);
+
+    let _ = update_project_status(project_root_path, |status| {
+        status.total_files = blobs.len();
+        status.progress = 20;
+    });
+
+    let mut index = LocalIndex {
+        project_root: normalized_root.clone(),
+        built_at: Some(Utc::now()),
+        ..Default::default()
+    };
+
+    let mut total_len: u64 = 0;
+    let mut chunk_paths = Vec::with_capacity(blobs.len());
+    for (doc_id, blob) in blobs.iter().enumerate() {
+        let path = strip_chunk_suffix(&blob.path).to_string();
+
+        // 路径本身也参与匹配，便于按文件名/目录名检索
+        let mut term_freq: HashMap<String, u32> = HashMap::new();
+        for token in tokenize(&path).into_iter().chain(tokenize(&blob.content)) {
+            *term_freq.entry(token).or_insert(0) += 1;
+        }
+        let length: u32 = term_freq.values().sum();
+        total_len += length as u64;
+
+        for (term, tf) in term_freq {
+            index.postings.entry(term).or_default().push((doc_id as u32, tf));
+        }
+
+        index.docs.push(LocalDoc {
+            path,
+            start_line: blob.start_line.max(1),
+            content: blob.content.clone(),
+            length,
+        });
+        chunk_paths.push(blob.path.clone());
+    }
+    tracker.advance(blobs.iter().map(|b| b.path.as_str()), 90);
+    index.avg_doc_len = if index.docs.is_empty() {
+        0.0
+    } else {
+        total_len as f64 / index.docs.len() as f64
+    };
+
+    let index_path = local_index_file(&normalized_root);
+    super::cache_crypto::write(&index_path, serde_json::to_vec(&index)?)?;
+
+    let _ = update_project_status(project_root_path, |status| {
+        status.status = IndexStatus::Synced;
+        status.progress = 100;
+        status.indexed_files = blobs.len();
+        status.pending_files = 0;
+        status.last_success_time = Some(Utc::now());
+        status.last_error = None;
+    });
+    tracker.stage(IndexStage::Completed, 100, None);
+
+    log_important!(info,
+        "本地索引构建完成: docs={}, terms={}, file={:?}",
+        index.docs.len(),
+        index.postings.len(),
+        index_path
+    );
+    Ok(chunk_paths)
+}
+
+/// 使用本地 BM25 索引检索，返回与远程接口一致风格的文本
+pub(crate) fn search_local(project_root_path: &str, query: &str) -> Result<String> {
+    let normalized_root = normalize_project_root(project_root_path);
+    let index_path = local_index_file(&normalized_root);
+    if !index_path.exists() {
+        anyhow::bail!("项目尚未建立本地索引，请先执行索引操作");
+    }
+
+    let data = super::cache_crypto::read(&index_path)?;
+    let index: LocalIndex = serde_json::from_slice(&data)
+        .map_err(|e| anyhow::anyhow!("本地索引文件损坏，请重新索引: {}", e))?;
+
+    let mut query_terms = tokenize(query);
+    query_terms.sort();
+    query_terms.dedup();
+
+    let hits = rank_documents(&index, &query_terms, DEFAULT_TOP_K);
+    if hits.is_empty() {
+        return Ok("No relevant code context found for your query.".to_string());
+    }
+
+    let mut sections = Vec::new();
+    for (doc_id, _score) in hits {
+        let doc = &index.docs[doc_id];
+        sections.push(format!(
+            "Path: {}\n{}",
+            doc.path,
+            render_snippet(doc, &query_terms)
+        ));
+    }
+
+    Ok(format!(
+        "The following code sections were retrieved:\n{}",
+        sections.join("\n---\n")
+    ))
+}
+
+/// BM25 打分并返回得分最高的文档
+fn rank_documents(index: &LocalIndex, query_terms: &[String], top_k: usize) -> Vec<(usize, f64)> {
+    let n = index.docs.len() as f64;
+    let mut scores: HashMap<usize, f64> = HashMap::new();
+
+    for term in query_terms {
+        let Some(postings) = index.postings.get(term) else {
+            continue;
+        };
+        let df = postings.len() as f64;
+        let idf = (1.0 + (n - df + 0.5) / (df + 0.5)).ln();
+
+        for &(doc_id, tf) in postings {
+            let doc_len = index.docs[doc_id as usize].length as f64;
+            let tf = tf as f64;
+            let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * doc_len / index.avg_doc_len.max(1.0));
+            *scores.entry(doc_id as usize).or_insert(0.0) += idf * tf * (BM25_K1 + 1.0) / (tf + norm);
+        }
+    }
+
+    let mut ranked: Vec<(usize, f64)> = scores.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    ranked.truncate(top_k);
+    ranked
+}
+
+/// 截取文档中查询词最密集的片段，附带行号
+fn render_snippet(doc: &LocalDoc, query_terms: &[String]) -> String {
+    let lines: Vec<&str> = doc.content.lines().collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    // 找到命中词项最多的行作为窗口中心
+    let best_line = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let hits = tokenize(line)
+                .iter()
+                .filter(|t| query_terms.contains(t))
+                .count();
+            (i, hits)
+        })
+        .max_by_key(|(i, hits)| (*hits, std::cmp::Reverse(*i)))
+        .map(|(i, _)| i)
+        .unwrap_or(0);
+
+    let start = best_line.saturating_sub(SNIPPET_MAX_LINES / 4);
+    let end = usize::min(start + SNIPPET_MAX_LINES, lines.len());
+
+    lines[start..end]
+        .iter()
+        .enumerate()
+        .map(|(offset, line)| format!("{:>6}\t{}", doc.start_line + start + offset, line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_splits_identifiers() {
+        let tokens = tokenize("fn updateIndex(project_root: &str)");
+        assert!(tokens.contains(&"updateindex".to_string()));
+        assert!(tokens.contains(&"update".to_string()));
+        assert!(tokens.contains(&"index".to_string()));
+        assert!(tokens.contains(&"project_root".to_string()));
+        assert!(tokens.contains(&"root".to_string()));
+    }
+
+    #[test]
+    fn test_tokenize_cjk_chars() {
+        let tokens = tokenize("索引 index");
+        assert_eq!(tokens, vec!["索", "引", "index"]);
+    }
+
+    #[test]
+    fn test_strip_chunk_suffix() {
+        assert_eq!(strip_chunk_suffix("src/a.rs"), "src/a.rs");
+        assert_eq!(strip_chunk_suffix("src/a.rs#chunk3of4"), "src/a.rs");
+    }
+
+    #[test]
+    fn test_rank_prefers_matching_doc() {
+        let mut index = LocalIndex::default();
+        for (i, text) in ["alpha beta", "gamma delta", "beta beta gamma"].iter().enumerate() {
+            let mut tf: HashMap<String, u32> = HashMap::new();
+            for t in tokenize(text) {
+                *tf.entry(t).or_insert(0) += 1;
+            }
+            let length = tf.values().sum();
+            for (term, f) in tf {
+                index.postings.entry(term).or_default().push((i as u32, f));
+            }
+            index.docs.push(LocalDoc { path: format!("f{}", i), start_line: 1, content: text.to_string(), length });
+        }
+        index.avg_doc_len = 2.3;
+
+        let ranked = rank_documents(&index, &["beta".to_string()], 3);
+        assert_eq!(ranked.len(), 2);
+        assert_eq!(ranked[0].0, 2);
+    }
+}
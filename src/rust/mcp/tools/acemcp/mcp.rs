@@ -8,7 +8,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
-use reqwest::header::{AUTHORIZATION, CONTENT_TYPE};
+use reqwest::header::{AUTHORIZATION, CONTENT_ENCODING, CONTENT_TYPE};
 use reqwest::Client;
 use ring::digest::{Context as ShaContext, SHA256};
 use ignore::gitignore::{Gitignore, GitignoreBuilder};
@@ -18,6 +18,7 @@ use globset::{Glob, GlobSet, GlobSetBuilder};
 
 use super::types::{
     AcemcpRequest,
+    AcemcpMultiRequest,
     AcemcpConfig,
     ProjectIndexStatus,
     ProjectsIndexStatus,
@@ -26,6 +27,19 @@ use super::types::{
     FileIndexStatus,
     FileIndexStatusKind,
 };
+use super::cache_crypto;
+use super::progress::{IndexProgressTracker, IndexStage};
+use super::compression::{UploadCompression, gzip as gzip_payload, mark_rejected as mark_compression_rejected, should_compress};
+use super::file_filter::{ContentLimits, content_filter_rejection, has_opt_out_marker, in_opt_out_dir, passes_content_filters};
+use super::preview::ExclusionTally;
+use super::throttle::IndexThrottle;
+use super::file_manifest::{FileManifest, FileRecord, content_hash, detect_moves, file_fingerprint, scan_signature};
+use super::index_history::RunStats;
+use super::query_rewrite::{QueryRewriteOptions, rewrite_query};
+use super::hits::parse_hits;
+use super::path_scope::PathScope;
+use super::quarantine::FailureLedger;
+use super::secret_scan::{SecretScanMode, describe as describe_secrets, redact as redact_secrets, scan_secrets};
 use crate::log_debug;
 use crate::log_important;
 // 代理模块（在 create_acemcp_client 中使用）
@@ -41,6 +55,10 @@ impl AcemcpTool {
             request.project_root_path, request.query
         );
 
+        // 结果路径范围（include/exclude glob）
+        let scope = PathScope::new(&request.include_paths, &request.exclude_paths)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+
         // 读取配置
         let mut acemcp_config = Self::get_acemcp_config()
             .await
@@ -52,17 +70,18 @@ impl AcemcpTool {
             acemcp_config.base_url = Some(normalized);
         }
 
-        // 首次搜索时自动启动文件监听（如果尚未启动）
+        // 首次搜索时在后台启动文件监听（如果尚未启动），启动时的目录遍历不阻塞本次搜索
         let watcher_manager = super::watcher::get_watcher_manager();
         if !watcher_manager.is_watching(&request.project_root_path) {
             log_debug!("首次搜索，尝试启动文件监听");
-            if let Err(e) = watcher_manager.start_watching(
-                request.project_root_path.clone(),
-                acemcp_config.clone(),
-                None  // 使用默认防抖延迟
-            ).await {
-                log_debug!("启动文件监听失败（不影响搜索）: {}", e);
-            }
+            let project_root = request.project_root_path.clone();
+            let config = acemcp_config.clone();
+            tokio::spawn(async move {
+                // 使用默认防抖延迟
+                if let Err(e) = watcher_manager.start_watching(project_root, config, None).await {
+                    log_debug!("启动文件监听失败（不影响搜索）: {}", e);
+                }
+            });
         }
 
         // 1. 检查初始索引状态
@@ -98,7 +117,7 @@ impl AcemcpTool {
         }
 
         // 3. 执行搜索（不触发索引）
-        let search_result = match search_only(&acemcp_config, &request.project_root_path, &request.query).await {
+        let search_result = match cached_search(&acemcp_config, &request.project_root_path, &request.query).await {
             Ok(text) => text,
             Err(e) => {
                 return Ok(CallToolResult {
@@ -110,7 +129,22 @@ impl AcemcpTool {
             }
         };
 
-        // 4. 附加提示信息
+        // 4. 按路径范围过滤结果片段
+        let sections = super::federated::split_retrieval_sections(&search_result);
+        let (search_result, sections) = match &scope {
+            Some(scope) => {
+                let (kept, removed) = scope.filter_sections(sections);
+                let mut text = super::federated::render_merged(&kept);
+                if removed > 0 {
+                    text.push_str(&format!("\n\n（已按路径范围过滤 {} 个片段）", removed));
+                }
+                (text, kept)
+            }
+            None => (search_result, sections),
+        };
+        let hits = parse_hits(&sections);
+
+        // 5. 附加提示信息
         let final_result = if hint_message.is_empty() {
             search_result
         } else {
@@ -121,7 +155,102 @@ impl AcemcpTool {
             content: vec![Content::text(final_result)], 
             is_error: None,
             meta: None,
-            structured_content: None,
+            structured_content: Some(serde_json::json!({ "hits": hits })),
+        })
+    }
+
+    /// 多项目联合检索：分别检索每个项目，按排名轮流合并结果
+    pub async fn search_context_multi(request: AcemcpMultiRequest) -> Result<CallToolResult, McpError> {
+        let scope = PathScope::new(&request.include_paths, &request.exclude_paths)
+            .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+        let mut acemcp_config = Self::get_acemcp_config()
+            .await
+            .map_err(|e| McpError::internal_error(format!("获取acemcp配置失败: {}", e), None))?;
+        if let Some(base) = &acemcp_config.base_url {
+            acemcp_config.base_url = Some(normalize_base_url(base));
+        }
+
+        // 合并显式路径与工作区分组，按规范化路径去重并保持顺序
+        let mut roots: Vec<String> = request.project_root_paths.clone();
+        if let Some(group) = &request.workspace_group {
+            let group_roots = acemcp_config
+                .workspace_groups
+                .as_ref()
+                .and_then(|groups| groups.get(group))
+                .ok_or_else(|| McpError::invalid_params(format!("未找到工作区分组: {}", group), None))?;
+            roots.extend(group_roots.iter().cloned());
+        }
+        let mut seen = HashSet::new();
+        roots.retain(|root| seen.insert(normalize_project_root(root)));
+        if roots.is_empty() {
+            return Err(McpError::invalid_params("project_root_paths 或 workspace_group 至少需要提供一个项目".to_string(), None));
+        }
+
+        log_important!(info, "Acemcp多项目检索请求: projects={:?}, query={}", roots, request.query);
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for (idx, root) in roots.iter().enumerate() {
+            let config = acemcp_config.clone();
+            let root = root.clone();
+            let query = request.query.clone();
+            tasks.spawn(async move {
+                // 未索引的项目在后台启动索引，本次仅检索已有索引
+                let pending = matches!(
+                    get_initial_index_state(&root),
+                    InitialIndexState::Missing | InitialIndexState::Idle | InitialIndexState::Failed
+                );
+                if pending {
+                    if let Err(e) = ensure_initial_index_background(&config, &root).await {
+                        log_debug!("启动后台索引失败（不影响搜索）: {}", e);
+                    }
+                }
+                let result = cached_search(&config, &root, &query).await;
+                (idx, root, pending, result)
+            });
+        }
+
+        let mut outcomes = Vec::new();
+        while let Some(joined) = tasks.join_next().await {
+            if let Ok(outcome) = joined {
+                outcomes.push(outcome);
+            }
+        }
+        // 按请求顺序排列，保证合并结果稳定
+        outcomes.sort_by_key(|(idx, _, _, _)| *idx);
+
+        let mut ranked: Vec<(String, Vec<String>)> = Vec::new();
+        let mut notes: Vec<String> = Vec::new();
+        for (_, root, pending, result) in outcomes {
+            let label = super::federated::project_label(&root);
+            match result {
+                Ok(text) => {
+                    let sections = super::federated::split_retrieval_sections(&text);
+                    let sections = match &scope {
+                        Some(scope) => scope.filter_sections(sections).0,
+                        None => sections,
+                    };
+                    ranked.push((label, sections));
+                }
+                Err(e) => notes.push(format!("- [{}] {}: {}", label, root, e)),
+            }
+            if pending {
+                notes.push(format!("- [{}] 索引尚未完成，已在后台启动索引", label));
+            }
+        }
+
+        let merged = super::federated::merge_ranked_sections(&ranked);
+        let mut text = super::federated::render_merged(&merged);
+        if !notes.is_empty() {
+            text.push_str(&format!("\n\n💡 提示：\n{}", notes.join("\n")));
+        }
+
+        // 所有项目都检索失败时返回错误
+        let all_failed = ranked.is_empty();
+        Ok(CallToolResult {
+            content: vec![Content::text(text)],
+            is_error: if all_failed { Some(true) } else { None },
+            meta: None,
+            structured_content: if all_failed { None } else { Some(serde_json::json!({ "hits": parse_hits(&merged) })) },
         })
     }
 
@@ -187,6 +316,17 @@ impl AcemcpTool {
         }
     }
 
+    /// 索引预览（dry-run）：只收集与分块，不上传（供 Tauri 命令与命令行调用）
+    pub async fn preview_index(project_root_path: String) -> Result<super::preview::IndexPreview> {
+        log_important!(info, "索引预览: project_root_path={}", project_root_path);
+
+        let acemcp_config = Self::get_acemcp_config().await?;
+        let config = super::project_config::config_for_project(&acemcp_config, &project_root_path);
+        tokio::task::spawn_blocking(move || super::preview::preview_index(&config, &project_root_path))
+            .await
+            .map_err(|e| anyhow::anyhow!("索引预览任务异常退出: {}", e))?
+    }
+
     /// 获取项目索引状态（供 Tauri 命令调用）
     pub fn get_index_status(project_root_path: String) -> ProjectIndexStatus {
         get_project_status(&project_root_path)
@@ -200,7 +340,7 @@ impl AcemcpTool {
     /// 获取项目内所有可索引文件的索引状态（供 Tauri 命令调用）
     pub async fn get_project_files_status(project_root_path: String) -> anyhow::Result<ProjectFilesStatus> {
         // 读取 Acemcp 配置，主要用于获取扩展名、排除规则和分块行数
-        let acemcp_config = Self::get_acemcp_config().await?;
+        let acemcp_config = super::project_config::config_for_project(&Self::get_acemcp_config().await?, &project_root_path);
         let max_lines = acemcp_config.max_lines_per_blob.unwrap_or(800) as usize;
         let text_exts = acemcp_config.text_extensions.clone().unwrap_or_default();
         let exclude_patterns = acemcp_config.exclude_patterns.clone().unwrap_or_default();
@@ -208,7 +348,7 @@ impl AcemcpTool {
         // 读取 projects.json，获取已索引的 blob 名称集合
         let projects_path = home_projects_file();
         let projects: ProjectsFile = if projects_path.exists() {
-            let data = fs::read_to_string(&projects_path).unwrap_or_default();
+            let data = cache_crypto::read_to_string(&projects_path).unwrap_or_default();
             serde_json::from_str(&data).unwrap_or_default()
         } else {
             ProjectsFile::default()
@@ -233,6 +373,7 @@ impl AcemcpTool {
             &text_exts,
             &exclude_patterns,
             max_lines,
+            &ContentLimits::from_config(&acemcp_config),
             &existing_blob_names,
         )?;
 
@@ -264,6 +405,28 @@ impl AcemcpTool {
             proxy_type: config.mcp_config.acemcp_proxy_type,
             proxy_username: config.mcp_config.acemcp_proxy_username,
             proxy_password: config.mcp_config.acemcp_proxy_password,
+            backend: config.mcp_config.acemcp_backend,
+            upload_concurrency: config.mcp_config.acemcp_upload_concurrency,
+            workspace_groups: config.mcp_config.acemcp_workspace_groups,
+            search_cache_ttl_secs: config.mcp_config.acemcp_search_cache_ttl_secs,
+            max_file_size_kb: config.mcp_config.acemcp_max_file_size_kb,
+            skip_binary_files: config.mcp_config.acemcp_skip_binary_files,
+            upload_compression: config.mcp_config.acemcp_upload_compression,
+            max_upload_mbps: config.mcp_config.acemcp_max_upload_mbps,
+            max_concurrent_reads: config.mcp_config.acemcp_max_concurrent_reads,
+            index_niceness: config.mcp_config.acemcp_index_niceness,
+            reindex_schedules: config.mcp_config.acemcp_reindex_schedules,
+            schedule_only: config.mcp_config.acemcp_schedule_only,
+            watch_max_dirs: config.mcp_config.acemcp_watch_max_dirs,
+            watch_max_files: config.mcp_config.acemcp_watch_max_files,
+            query_rewrite: config.mcp_config.acemcp_query_rewrite,
+            query_prefer_english: config.mcp_config.acemcp_query_prefer_english,
+            query_abbreviations: config.mcp_config.acemcp_query_abbreviations,
+            secret_scan: config.mcp_config.acemcp_secret_scan,
+            health_check_interval_secs: config.mcp_config.acemcp_health_check_interval_secs,
+            cache_encryption: config.mcp_config.acemcp_cache_encryption,
+            endpoints: config.mcp_config.acemcp_endpoints,
+            project_endpoints: config.mcp_config.acemcp_project_endpoints,
         })
     }
 
@@ -280,15 +443,34 @@ impl AcemcpTool {
                 "query": {
                     "type": "string",
                     "description": "Natural language search query to find relevant code context. Returns code snippets matching the query with file paths and line numbers."
+                },
+                "project_root_paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional. Search several projects at once (e.g. sibling repos); results are merged by rank and prefixed with the project name. Use instead of project_root_path."
+                },
+                "workspace_group": {
+                    "type": "string",
+                    "description": "Optional. Name of a configured workspace group whose projects are searched together. Can be combined with project_root_paths."
+                },
+                "include_paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional. Only return snippets whose file path (relative to the project root) matches one of these globs, e.g. [\"src/**\"]. Patterns without '/' match the file name, e.g. \"*.rs\"."
+                },
+                "exclude_paths": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional. Drop snippets whose file path matches one of these globs, e.g. [\"**/tests/**\"]."
                 }
             },
-            "required": ["project_root_path", "query"]
+            "required": ["query"]
         });
 
         if let serde_json::Value::Object(schema_map) = schema {
             Tool {
                 name: Cow::Borrowed("index"),
-                description: Some(Cow::Borrowed("Code indexing and search. Query indexed files to find relevant code snippets.")),
+                description: Some(Cow::Borrowed("Code indexing and search. Query indexed files to find relevant code snippets. Pass project_root_path for one project, or project_root_paths/workspace_group to search several projects in one call. structured_content.hits lists each snippet as {path, start_line, end_line, snippet, score}.")),
                 input_schema: Arc::new(schema_map),
                 annotations: Some(ToolAnnotations {
                     title: Some("Code Index".to_string()),
@@ -335,7 +517,8 @@ pub fn get_initial_index_state(project_root: &str) -> InitialIndexState {
         IndexStatus::Idle if status.total_files == 0 => InitialIndexState::Idle,
         IndexStatus::Idle => InitialIndexState::Missing,
         IndexStatus::Synced => InitialIndexState::Synced,
-        IndexStatus::Indexing => InitialIndexState::Indexing,
+        // 暂停视为进行中：已有部分索引，恢复后会继续
+        IndexStatus::Indexing | IndexStatus::Paused => InitialIndexState::Indexing,
         IndexStatus::Failed => InitialIndexState::Failed,
     }
 }
@@ -372,9 +555,12 @@ pub async fn ensure_initial_index_background(config: &AcemcpConfig, project_root
 // ---------------- 整合 temp 逻辑：索引、上传、检索 ----------------
 
 #[derive(Serialize, Deserialize, Clone)]
-struct BlobItem {
-    path: String,
-    content: String,
+pub(crate) struct BlobItem {
+    pub(crate) path: String,
+    pub(crate) content: String,
+    /// 分块在原文件中的起始行号（从 1 开始，仅本地使用，不上传）
+    #[serde(skip)]
+    pub(crate) start_line: usize,
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -410,19 +596,15 @@ where
                 attempt += 1;
                 
                 // 检查是否为可重试的错误
-                let error_str = e.to_string();
-                let is_retryable = error_str.contains("timeout") 
-                    || error_str.contains("connection") 
-                    || error_str.contains("network")
-                    || error_str.contains("temporary");
-                
-                if attempt >= max_retries || !is_retryable {
+                if attempt >= max_retries || !is_retryable_error(&e.to_string()) {
                     log_debug!("请求失败，不再重试: {}", e);
                     return Err(e);
                 }
                 
-                let delay = base_delay_secs * 2f64.powi((attempt as i32) - 1);
-                let ms = (delay * 1000.0) as u64;
+                // 指数退避 + 随机抖动（0.5x ~ 1.5x），避免并发批次同时重试
+                let delay = (base_delay_secs * 2f64.powi((attempt as i32) - 1)).min(30.0);
+                let jitter = 0.5 + fastrand::f64();
+                let ms = (delay * jitter * 1000.0) as u64;
                 log_debug!("请求失败，准备重试({}/{}), 等待 {}ms: {}", attempt, max_retries, ms, e);
                 tokio::time::sleep(Duration::from_millis(ms)).await;
             }
@@ -434,6 +616,27 @@ where
         .unwrap_or_else(|| anyhow::anyhow!("未知错误")))
 }
 
+/// 判断错误是否为瞬时错误（网络问题、限流、服务端 5xx）
+fn is_retryable_error(error_str: &str) -> bool {
+    let lower = error_str.to_lowercase();
+    if lower.contains("timeout")
+        || lower.contains("timed out")
+        || lower.contains("connection")
+        || lower.contains("network")
+        || lower.contains("temporary")
+    {
+        return true;
+    }
+
+    // HTTP 状态码形如 "HTTP 503 Service Unavailable ..."
+    error_str
+        .strip_prefix("HTTP ")
+        .and_then(|rest| rest.split_whitespace().next())
+        .and_then(|code| code.parse::<u16>().ok())
+        .map(|code| code == 408 || code == 429 || (500..600).contains(&code))
+        .unwrap_or(false)
+}
+
 pub(crate) fn home_projects_file() -> PathBuf {
     let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
     let data_dir = home.join(".acemcp").join("data");
@@ -450,12 +653,12 @@ fn home_projects_status_file() -> PathBuf {
 }
 
 /// 读取所有项目的索引状态
-fn load_projects_status() -> ProjectsIndexStatus {
+pub(crate) fn load_projects_status() -> ProjectsIndexStatus {
     let status_path = home_projects_status_file();
     log_debug!("📂 [load_projects_status] 状态文件路径: {:?}", status_path);
     
     if status_path.exists() {
-        let data = fs::read_to_string(&status_path).unwrap_or_default();
+        let data = cache_crypto::read_to_string(&status_path).unwrap_or_default();
         log_debug!("📄 [load_projects_status] 读取到状态文件，大小: {} 字节", data.len());
         
         match serde_json::from_str::<ProjectsIndexStatus>(&data) {
@@ -475,15 +678,24 @@ fn load_projects_status() -> ProjectsIndexStatus {
 }
 
 /// 保存所有项目的索引状态
-fn save_projects_status(status: &ProjectsIndexStatus) -> Result<()> {
+pub(crate) fn save_projects_status(status: &ProjectsIndexStatus) -> Result<()> {
     let status_path = home_projects_status_file();
     let data = serde_json::to_string_pretty(status)?;
-    fs::write(status_path, data)?;
+    cache_crypto::write(status_path, data)?;
     Ok(())
 }
 
+/// 规范化项目根路径（作为 projects.json / 状态文件中的键）
+pub(crate) fn normalize_project_root(project_root: &str) -> String {
+    PathBuf::from(project_root)
+        .canonicalize()
+        .unwrap_or_else(|_| PathBuf::from(project_root))
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
 /// 更新指定项目的索引状态
-fn update_project_status<F>(project_root: &str, updater: F) -> Result<()>
+pub(crate) fn update_project_status<F>(project_root: &str, updater: F) -> Result<()>
 where
     F: FnOnce(&mut ProjectIndexStatus),
 {
@@ -526,7 +738,7 @@ fn get_project_status(project_root: &str) -> ProjectIndexStatus {
 /// 读取文件内容，支持多种编码检测
 /// 尝试的编码顺序：utf-8, gbk (包含 gb2312), windows-1252 (包含 latin-1)
 /// 如果都失败，则使用 utf-8 with errors='ignore'
-fn read_file_with_encoding(path: &Path) -> Option<String> {
+pub(crate) fn read_file_with_encoding(path: &Path) -> Option<String> {
     let mut file = fs::File::open(path).ok()?;
     let mut buf = Vec::new();
     if file.read_to_end(&mut buf).is_err() {
@@ -563,7 +775,7 @@ fn read_file_with_encoding(path: &Path) -> Option<String> {
     Some(decoded.into_owned())
 }
 
-fn sha256_hex(path: &str, content: &str) -> String {
+pub(crate) fn sha256_hex(path: &str, content: &str) -> String {
     let mut ctx = ShaContext::new(&SHA256);
     // 先更新路径的哈希，再更新内容的哈希，与Python版本保持一致
     ctx.update(path.as_bytes());
@@ -574,36 +786,36 @@ fn sha256_hex(path: &str, content: &str) -> String {
 
 /// 分割文件内容为多个 blob（如果超过最大行数）
 /// 与 Python 版本保持一致：chunk 索引从 1 开始
-fn split_content(path: &str, content: &str, max_lines: usize) -> Vec<BlobItem> {
+/// 受支持的语言优先在函数/类边界处切分，其余文件按行切分
+pub(crate) fn split_content(path: &str, content: &str, max_lines: usize) -> Vec<BlobItem> {
     let lines: Vec<&str> = content.split_inclusive('\n').collect();
     let total_lines = lines.len();
     
     // 如果文件在限制内，返回单个 blob
     if total_lines <= max_lines {
-        return vec![BlobItem { path: path.to_string(), content: content.to_string() }];
+        return vec![BlobItem { path: path.to_string(), content: content.to_string(), start_line: 1 }];
     }
 
-    // 计算需要的 chunk 数量
-    let num_chunks = (total_lines + max_lines - 1) / max_lines;
+    // 优先使用符号边界，不支持的语言回退为按行切分
+    let ranges = super::chunker::symbol_chunk_ranges(path, content, max_lines)
+        .unwrap_or_else(|| super::chunker::line_chunk_ranges(total_lines, max_lines));
+    let num_chunks = ranges.len();
     let mut blobs = Vec::new();
 
     // 按 chunk 索引分割（从 0 开始，但显示时从 1 开始）
-    for chunk_idx in 0..num_chunks {
-        let start_line = chunk_idx * max_lines;
-        let end_line = usize::min(start_line + max_lines, total_lines);
-        let chunk_lines = &lines[start_line..end_line];
-        let chunk_content = chunk_lines.join("");
+    for (chunk_idx, (start_line, end_line)) in ranges.into_iter().enumerate() {
+        let chunk_content = lines[start_line..end_line].join("");
 
         // chunk 编号从 1 开始（与 Python 版本保持一致）
         let chunk_path = format!("{}#chunk{}of{}", path, chunk_idx + 1, num_chunks);
-        blobs.push(BlobItem { path: chunk_path, content: chunk_content });
+        blobs.push(BlobItem { path: chunk_path, content: chunk_content, start_line: start_line + 1 });
     }
 
     blobs
 }
 
 /// 构建排除模式的 GlobSet
-fn build_exclude_globset(exclude_patterns: &[String]) -> Result<GlobSet> {
+pub(crate) fn build_exclude_globset(exclude_patterns: &[String]) -> Result<GlobSet> {
     let mut builder = GlobSetBuilder::new();
     for pattern in exclude_patterns {
         // 尝试将模式转换为 Glob
@@ -619,7 +831,7 @@ fn build_exclude_globset(exclude_patterns: &[String]) -> Result<GlobSet> {
 /// 检查路径是否应该被排除
 /// 使用 globset 进行完整的 fnmatch 模式匹配（与 Python 版本保持一致）
 /// Python 版本使用 fnmatch.fnmatch 检查路径的各个部分和完整路径
-fn should_exclude(path: &Path, root: &Path, exclude_globset: Option<&GlobSet>) -> bool {
+pub(crate) fn should_exclude(path: &Path, root: &Path, exclude_globset: Option<&GlobSet>) -> bool {
     if exclude_globset.is_none() {
         return false;
     }
@@ -651,7 +863,7 @@ fn should_exclude(path: &Path, root: &Path, exclude_globset: Option<&GlobSet>) -
     false
 }
 
-fn build_gitignore(root: &Path) -> Option<Gitignore> {
+pub(crate) fn build_gitignore(root: &Path) -> Option<Gitignore> {
     let mut builder = GitignoreBuilder::new(root);
     let gi_path = root.join(".gitignore");
     if gi_path.exists() {
@@ -661,7 +873,42 @@ fn build_gitignore(root: &Path) -> Option<Gitignore> {
     None
 }
 
-fn collect_blobs(root: &str, text_exts: &[String], exclude_patterns: &[String], max_lines_per_blob: usize) -> anyhow::Result<Vec<BlobItem>> {
+pub(crate) fn collect_blobs(root: &str, text_exts: &[String], exclude_patterns: &[String], max_lines_per_blob: usize, limits: &ContentLimits) -> anyhow::Result<Vec<BlobItem>> {
+    let files = walk_indexable_files(root, text_exts, exclude_patterns, limits)?;
+
+    let mut out = Vec::new();
+    let mut indexed_files = 0;
+    for (p, rel) in &files {
+        // 读取文件内容（使用多编码支持）
+        if let Some(content) = read_file_with_encoding(p) {
+            let parts = split_content(rel, &content, max_lines_per_blob);
+            let blob_count = parts.len();
+            indexed_files += 1;
+            out.extend(parts);
+            log_important!(info, "索引文件: path={}, content_length={}, blobs={}", rel, content.len(), blob_count);
+        } else {
+            log_debug!("无法读取文件: {:?}", p);
+        }
+    }
+
+    log_important!(info, "文件读取完成: 索引文件数={}, 生成blobs数={}", indexed_files, out.len());
+    Ok(out)
+}
+
+/// 遍历项目，返回所有可索引文件（绝对路径, 相对路径）
+/// 应用 .gitignore、排除模式、扩展名以及大小/二进制过滤
+fn walk_indexable_files(root: &str, text_exts: &[String], exclude_patterns: &[String], limits: &ContentLimits) -> anyhow::Result<Vec<(PathBuf, String)>> {
+    walk_indexable_files_tallied(root, text_exts, exclude_patterns, limits, &mut ExclusionTally::default())
+}
+
+/// 同 `walk_indexable_files`，并按原因统计被排除的文件/目录（用于索引预览）
+pub(crate) fn walk_indexable_files_tallied(
+    root: &str,
+    text_exts: &[String],
+    exclude_patterns: &[String],
+    limits: &ContentLimits,
+    tally: &mut ExclusionTally,
+) -> anyhow::Result<Vec<(PathBuf, String)>> {
     let root_path = PathBuf::from(root);
     if !root_path.exists() { anyhow::bail!("项目根目录不存在: {}", root); }
     
@@ -684,23 +931,33 @@ fn collect_blobs(root: &str, text_exts: &[String], exclude_patterns: &[String],
     let gitignore = build_gitignore(&root_path);
     let mut dirs_stack = vec![root_path.clone()];
     let mut scanned_files = 0;
-    let mut indexed_files = 0;
     let mut excluded_count = 0;
     
     while let Some(dir) = dirs_stack.pop() {
         let entries = match fs::read_dir(&dir) { Ok(e) => e, Err(_) => continue };
         for entry in entries.flatten() {
             let p = entry.path();
+            let rel = p.strip_prefix(&root_path).unwrap_or(&p).to_string_lossy().replace('\\', "/");
             
             // 检查 .gitignore
             if let Some(gi) = &gitignore {
-                if gi.matched_path_or_any_parents(&p, p.is_dir()).is_ignore() { continue; }
+                if gi.matched_path_or_any_parents(&p, p.is_dir()).is_ignore() {
+                    tally.record("gitignore", &rel);
+                    continue;
+                }
             }
             
             // 检查排除模式
             if p.is_dir() {
                 if should_exclude(&p, &root_path, exclude_globset.as_ref()) {
                     excluded_count += 1;
+                    tally.record("exclude_pattern", &rel);
+                    continue;
+                }
+                if has_opt_out_marker(&p) {
+                    log_debug!("目录包含不索引标记，跳过: {:?}", p);
+                    excluded_count += 1;
+                    tally.record("opt_out_marker", &rel);
                     continue;
                 }
                 dirs_stack.push(p);
@@ -710,6 +967,7 @@ fn collect_blobs(root: &str, text_exts: &[String], exclude_patterns: &[String],
             scanned_files += 1;
             if should_exclude(&p, &root_path, exclude_globset.as_ref()) {
                 excluded_count += 1;
+                tally.record("exclude_pattern", &rel);
                 log_debug!("排除文件: {:?}", p);
                 continue;
             }
@@ -719,26 +977,224 @@ fn collect_blobs(root: &str, text_exts: &[String], exclude_patterns: &[String],
                 let dot = format!(".{}", e).to_lowercase();
                 text_exts.iter().any(|te| te.eq_ignore_ascii_case(&dot))
             }).unwrap_or(false);
-            if !ext_ok { continue; }
-            
-            // 读取文件内容（使用多编码支持）
-            let rel = p.strip_prefix(&root_path).unwrap_or(&p).to_string_lossy().replace('\\', "/");
-            if let Some(content) = read_file_with_encoding(&p) {
-                let parts = split_content(&rel, &content, max_lines_per_blob);
-                let blob_count = parts.len();
-                indexed_files += 1;
-                out.extend(parts);
-                log_important!(info, "索引文件: path={}, content_length={}, blobs={}", rel, content.len(), blob_count);
-            } else {
-                log_debug!("无法读取文件: {:?}", p);
+            if let Some(reason) = content_filter_rejection(&p, ext_ok, limits) {
+                tally.record(reason, &rel);
+                continue;
             }
+            
+            out.push((p, rel));
         }
     }
     
-    log_important!(info, "文件收集完成: 扫描文件数={}, 索引文件数={}, 生成blobs数={}, 排除文件/目录数={}", scanned_files, indexed_files, out.len(), excluded_count);
+    log_important!(info, "文件收集完成: 扫描文件数={}, 可索引文件数={}, 排除文件/目录数={}", scanned_files, out.len(), excluded_count);
     Ok(out)
 }
 
+/// 增量扫描结果
+#[derive(Default)]
+struct IncrementalScan {
+    /// 新增或内容变化的文件生成的 blob
+    changed_blobs: Vec<BlobItem>,
+    /// 未变化文件直接复用的 blob 名称
+    reused_blob_names: HashSet<String>,
+    /// 本次扫描后的文件清单
+    manifest: FileManifest,
+    /// 大小与修改时间均未变化的文件数
+    unchanged_files: usize,
+    /// 修改时间变化但内容未变的文件数
+    touched_files: usize,
+    /// 检测到疑似密钥的文件（含处理方式说明）
+    secret_files: Vec<String>,
+    /// 无法读取或解码的文件（相对路径）
+    unreadable_files: Vec<String>,
+    /// 因已隔离而跳过的文件数
+    quarantined_skipped: usize,
+}
+
+/// 基于文件清单的增量扫描
+/// 只有新增或内容变化的文件才会被重新分块；旧记录的 blob 必须仍在已索引集合中才会被复用
+fn scan_changed_blobs(
+    root: &str,
+    text_exts: &[String],
+    exclude_patterns: &[String],
+    max_lines: usize,
+    limits: &ContentLimits,
+    throttle: &IndexThrottle,
+    secret_mode: SecretScanMode,
+    previous: &FileManifest,
+    existing_blob_names: &HashSet<String>,
+    quarantined: &HashSet<String>,
+) -> anyhow::Result<IncrementalScan> {
+    let root_path = PathBuf::from(root);
+    let signature = scan_signature(text_exts, exclude_patterns, max_lines, &format!("{:?}", limits));
+    let head = super::git_diff::head_commit(&root_path);
+
+    // git 仓库优先只检查变更文件，无法使用时回退到全量遍历
+    let files = match git_candidate_files(&root_path, text_exts, exclude_patterns, limits, &signature, head.as_deref(), previous) {
+        Some(files) => files,
+        None => walk_indexable_files(root, text_exts, exclude_patterns, limits)?,
+    };
+    let mut scan = IncrementalScan::default();
+    scan.manifest.git_head = head;
+    scan.manifest.scan_signature = Some(signature);
+
+    // 上一个被读取文件的处理开始时间，用于按 niceness 让出 CPU
+    let mut work_started: Option<std::time::Instant> = None;
+
+    for (p, rel) in &files {
+        if let Some(started) = work_started.take() {
+            throttle.yield_after(started.elapsed());
+        }
+
+        // 已隔离的文件：跳过，直到用户手动重试
+        if quarantined.contains(rel) {
+            scan.quarantined_skipped += 1;
+            continue;
+        }
+
+        let fingerprint = file_fingerprint(p);
+        let previous_record = previous.files.get(rel).filter(|record| {
+            record.max_lines == max_lines
+                && record.secret_scan.as_deref() == Some(secret_mode.as_str())
+                && record.blob_names.iter().all(|name| existing_blob_names.contains(name))
+        });
+
+        // 大小与修改时间均未变化：无需读取文件
+        if let (Some(record), Some((size, mtime_ms))) = (previous_record, fingerprint) {
+            if record.size == size && record.mtime_ms == mtime_ms {
+                scan.reused_blob_names.extend(record.blob_names.iter().cloned());
+                scan.manifest.files.insert(rel.clone(), record.clone());
+                scan.unchanged_files += 1;
+                continue;
+            }
+        }
+
+        let _permit = throttle.acquire_read();
+        work_started = Some(std::time::Instant::now());
+        let Some(content) = read_file_with_encoding(p) else {
+            log_debug!("无法读取文件: {:?}", p);
+            scan.unreadable_files.push(rel.clone());
+            continue;
+        };
+        let hash = content_hash(&content);
+        let (size, mtime_ms) = fingerprint.unwrap_or((content.len() as u64, 0));
+
+        // 仅被 touch：内容哈希未变，复用已有 blob 并刷新指纹
+        if let Some(record) = previous_record {
+            if record.content_hash == hash {
+                scan.reused_blob_names.extend(record.blob_names.iter().cloned());
+                scan.manifest.files.insert(rel.clone(), FileRecord { size, mtime_ms, ..record.clone() });
+                scan.touched_files += 1;
+                continue;
+            }
+        }
+
+        // 上传前扫描密钥：按配置脱敏或跳过整个文件
+        let mut content = content;
+        if secret_mode != SecretScanMode::Off {
+            let secrets = scan_secrets(&content);
+            if !secrets.is_empty() {
+                let kinds = describe_secrets(&secrets);
+                if secret_mode == SecretScanMode::Skip {
+                    log_important!(info, "检测到疑似密钥，跳过文件: path={}, 类型={}", rel, kinds);
+                    scan.secret_files.push(format!("{}（已跳过: {}）", rel, kinds));
+                    continue;
+                }
+                log_important!(info, "检测到疑似密钥，已脱敏: path={}, 命中数={}, 类型={}", rel, secrets.len(), kinds);
+                scan.secret_files.push(format!("{}（已脱敏: {}）", rel, kinds));
+                content = redact_secrets(&content, &secrets);
+            }
+        }
+
+        // 新增或内容变化：重新分块
+        let parts = split_content(rel, &content, max_lines);
+        log_important!(info, "索引文件: path={}, content_length={}, blobs={}", rel, content.len(), parts.len());
+        let blob_names = parts.iter().map(|b| sha256_hex(&b.path, &b.content)).collect();
+        scan.manifest.files.insert(rel.clone(), FileRecord {
+            size,
+            mtime_ms,
+            content_hash: hash,
+            max_lines,
+            blob_names,
+            secret_scan: Some(secret_mode.as_str().to_string()),
+        });
+        scan.changed_blobs.extend(parts);
+    }
+
+    Ok(scan)
+}
+
+/// 基于 git 变更计算候选文件：上次清单中未变更的文件 + 变更且可索引的文件
+/// 要求上次索引记录了 git HEAD 且过滤配置未变化，否则返回 None
+fn git_candidate_files(
+    root_path: &Path,
+    text_exts: &[String],
+    exclude_patterns: &[String],
+    limits: &ContentLimits,
+    signature: &str,
+    head: Option<&str>,
+    previous: &FileManifest,
+) -> Option<Vec<(PathBuf, String)>> {
+    if head.is_none() {
+        return None;
+    }
+    let last_head = previous.git_head.as_deref()?;
+    if previous.files.is_empty() || previous.scan_signature.as_deref() != Some(signature) {
+        return None;
+    }
+    let changed = super::git_diff::changed_paths(root_path, last_head)?;
+
+    let exclude_globset = if exclude_patterns.is_empty() {
+        None
+    } else {
+        build_exclude_globset(exclude_patterns).ok()
+    };
+    let gitignore = build_gitignore(root_path);
+
+    let indexable = |p: &Path| {
+        p.is_file() && is_indexable_file(p, root_path, text_exts, exclude_globset.as_ref(), gitignore.as_ref(), limits)
+    };
+    // git diff 不包含已删除的未跟踪文件与新命中 .gitignore / 不索引标记的文件，清单中的文件同样需要重新检查
+    let mut files: Vec<(PathBuf, String)> = previous
+        .files
+        .keys()
+        .filter(|rel| !changed.contains(*rel))
+        .map(|rel| (root_path.join(rel), rel.clone()))
+        .filter(|(p, _)| indexable(p))
+        .collect();
+    for rel in &changed {
+        let p = root_path.join(rel);
+        if indexable(&p) {
+            files.push((p, rel.clone()));
+        }
+    }
+
+    log_important!(info, "git 增量扫描: 基准提交={}, 变更文件数={}, 候选文件数={}", last_head, changed.len(), files.len());
+    Some(files)
+}
+
+/// 单个文件是否满足索引条件（.gitignore、排除模式、不索引标记、扩展名与大小/二进制过滤）
+fn is_indexable_file(
+    p: &Path,
+    root_path: &Path,
+    text_exts: &[String],
+    exclude_globset: Option<&GlobSet>,
+    gitignore: Option<&Gitignore>,
+    limits: &ContentLimits,
+) -> bool {
+    if let Some(gi) = gitignore {
+        if gi.matched_path_or_any_parents(p, false).is_ignore() { return false; }
+    }
+    if should_exclude(p, root_path, exclude_globset) || in_opt_out_dir(p, root_path) {
+        return false;
+    }
+    let ext_ok = p.extension().and_then(|s| s.to_str()).map(|e| {
+        let dot = format!(".{}", e).to_lowercase();
+        text_exts.iter().any(|te| te.eq_ignore_ascii_case(&dot))
+    }).unwrap_or(false);
+    passes_content_filters(p, ext_ok, limits)
+}
+
 /// 收集项目内所有可索引文件的索引状态
 ///
 /// 为避免引入新的持久化结构，这里通过重新扫描文件并复用与索引阶段相同的
@@ -748,6 +1204,7 @@ fn collect_file_statuses(
     text_exts: &[String],
     exclude_patterns: &[String],
     max_lines_per_blob: usize,
+    limits: &ContentLimits,
     existing_blob_names: &HashSet<String>,
 ) -> anyhow::Result<Vec<FileIndexStatus>> {
     let root_path = PathBuf::from(root);
@@ -789,7 +1246,7 @@ fn collect_file_statuses(
             }
 
             if p.is_dir() {
-                if should_exclude(&p, &root_path, exclude_globset.as_ref()) {
+                if should_exclude(&p, &root_path, exclude_globset.as_ref()) || has_opt_out_marker(&p) {
                     continue;
                 }
                 dirs_stack.push(p);
@@ -810,7 +1267,7 @@ fn collect_file_statuses(
                 })
                 .unwrap_or(false);
 
-            if !ext_ok {
+            if !passes_content_filters(&p, ext_ok, limits) {
                 continue;
             }
 
@@ -862,6 +1319,30 @@ fn collect_file_statuses(
 /// 只执行索引更新，不进行搜索
 /// 返回值：成功上传的 blob 名称列表
 pub(crate) async fn update_index(config: &AcemcpConfig, project_root_path: &str) -> anyhow::Result<Vec<String>> {
+    // 记录本次运行的时间线（无论成功与否）
+    let started_at = chrono::Utc::now();
+    let _run = super::control::begin_run(&normalize_project_root(project_root_path));
+    let mut stats = RunStats::default();
+    let result = update_index_inner(config, project_root_path, &mut stats).await;
+    let record = super::index_history::build_record(started_at, stats, &result);
+    super::index_history::record_run(&normalize_project_root(project_root_path), record);
+    result
+}
+
+async fn update_index_inner(config: &AcemcpConfig, project_root_path: &str, stats: &mut RunStats) -> anyhow::Result<Vec<String>> {
+    // 合并项目级覆盖配置（.sanshu/acemcp.toml / .sanshu/acemcp.json）
+    let project_config = super::project_config::config_for_project(config, project_root_path);
+    let config = &project_config;
+
+    // 本地离线后端：在本机构建 BM25 索引，不上传代码
+    if config.is_local_backend() {
+        return super::local_index::update_local_index(config, project_root_path).await;
+    }
+
+    // 按项目分配的端点（租户）路由
+    let endpoint_config = resolve_project_endpoint(config, project_root_path)?;
+    let config = &endpoint_config;
+
     let base_url = config.base_url.clone().ok_or_else(|| anyhow::anyhow!("未配置 base_url"))?;
     // 严格校验 base_url
     let has_scheme = base_url.starts_with("http://") || base_url.starts_with("https://");
@@ -873,9 +1354,10 @@ pub(crate) async fn update_index(config: &AcemcpConfig, project_root_path: &str)
     let text_exts = config.text_extensions.clone().unwrap_or_default();
     let exclude_patterns = config.exclude_patterns.clone().unwrap_or_default();
 
-    // 更新状态：开始索引
+    // 更新状态：开始索引（项目已被暂停时保持暂停状态，上传会在闸门处等待恢复）
+    let paused = super::control::get_index_gate(&normalize_project_root(project_root_path)).is_paused();
     let _ = update_project_status(project_root_path, |status| {
-        status.status = IndexStatus::Indexing;
+        status.status = if paused { IndexStatus::Paused } else { IndexStatus::Indexing };
         status.progress = 0;
     });
 
@@ -895,56 +1377,123 @@ pub(crate) async fn update_index(config: &AcemcpConfig, project_root_path: &str)
         "项目路径: {}", project_root_path
     );
 
+    // 加载 projects.json
+    let projects_path = home_projects_file();
+    let mut projects: ProjectsFile = if projects_path.exists() {
+        let data = cache_crypto::read_to_string(&projects_path).unwrap_or_default();
+        serde_json::from_str(&data).unwrap_or_default()
+    } else { ProjectsFile::default() };
+
+    let normalized_root = PathBuf::from(project_root_path).canonicalize().unwrap_or_else(|_| PathBuf::from(project_root_path)).to_string_lossy().replace('\\', "/");
+    let existing_blob_names: std::collections::HashSet<String> = projects.0.get(&normalized_root).cloned().unwrap_or_default().into_iter().collect();
+
     // 收集 blob（根据扩展名与排除规则，简化版 .gitignore 支持）
+    // 借助文件清单跳过未变化的文件：大小/修改时间一致或内容哈希一致时直接复用已有 blob
     log_important!(info, "开始收集代码文件...");
-    let blobs = collect_blobs(project_root_path, &text_exts, &exclude_patterns, max_lines)?;
-    if blobs.is_empty() {
+    let previous_manifest = FileManifest::load(&normalized_root);
+    let limits = ContentLimits::from_config(config);
+    let throttle = IndexThrottle::from_config(config);
+    let secret_mode = SecretScanMode::from_config(config.secret_scan.as_deref());
+    // 反复失败的文件已被隔离，本次扫描直接跳过
+    let mut ledger = FailureLedger::load(&normalized_root);
+    let quarantined = ledger.quarantined_paths();
+    // 扫描包含大量同步文件读取，放到阻塞线程中执行，避免占用异步运行时
+    let scan = {
+        let root = project_root_path.to_string();
+        let text_exts = text_exts.clone();
+        let exclude_patterns = exclude_patterns.clone();
+        let previous = previous_manifest.clone();
+        let existing = existing_blob_names.clone();
+        let quarantined = quarantined.clone();
+        tokio::task::spawn_blocking(move || {
+            scan_changed_blobs(&root, &text_exts, &exclude_patterns, max_lines, &limits, &throttle, secret_mode, &previous, &existing, &quarantined)
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("文件扫描任务异常退出: {}", e))??
+    };
+    let total_blob_count = scan.reused_blob_names.len() + scan.changed_blobs.len();
+    // 密钥扫描警告：记录到项目状态，提醒用户检查（最多列出 5 个文件）
+    let secret_warning = if scan.secret_files.is_empty() {
+        None
+    } else {
+        let shown: Vec<&str> = scan.secret_files.iter().take(5).map(|s| s.as_str()).collect();
+        let more = if scan.secret_files.len() > shown.len() { " 等" } else { "" };
+        Some(format!("{} 个文件包含疑似密钥: {}{}", scan.secret_files.len(), shown.join("; "), more))
+    };
+    if total_blob_count == 0 {
         // 更新状态：失败
         let _ = update_project_status(project_root_path, |status| {
             status.status = IndexStatus::Failed;
             status.last_error = Some("未在项目中找到可索引的文本文件".to_string());
             status.last_failure_time = Some(chrono::Utc::now());
         });
+        IndexProgressTracker::new(&normalized_root, std::iter::empty())
+            .stage(IndexStage::Failed, 0, Some("未在项目中找到可索引的文本文件".to_string()));
         anyhow::bail!("未在项目中找到可索引的文本文件");
     }
 
+    // 移动/重命名的文件：旧路径的 blob 随清单一起移除，新路径按新增处理
+    let added_paths: HashSet<String> = scan.manifest.files.keys()
+        .filter(|path| !previous_manifest.files.contains_key(*path))
+        .cloned()
+        .collect();
+    for (old_path, new_path) in detect_moves(&previous_manifest, &scan.manifest, &added_paths) {
+        log_important!(info, "检测到文件移动: {} -> {}", old_path, new_path);
+    }
+    stats.files_changed = scan.changed_blobs.iter()
+        .map(|b| b.path.split("#chunk").next().unwrap_or(&b.path))
+        .collect::<HashSet<_>>()
+        .len();
+    stats.files_deleted = previous_manifest.files.keys()
+        .filter(|path| !scan.manifest.files.contains_key(*path))
+        .count();
+    log_important!(info,
+        "增量扫描: 未变化文件={}, 仅修改时间变化={}, 新增或内容变化的blobs={}, 已隔离跳过={}",
+        scan.unchanged_files,
+        scan.touched_files,
+        scan.changed_blobs.len(),
+        scan.quarantined_skipped
+    );
+    // 本次各文件的失败原因（同一文件只记一次），索引结束后写入隔离台账
+    let mut file_failures: HashMap<String, String> = scan.unreadable_files.iter()
+        .map(|path| (path.clone(), "无法读取或解码文件内容".to_string()))
+        .collect();
+
     // 更新状态：文件收集完成
     let _ = update_project_status(project_root_path, |status| {
-        status.total_files = blobs.len();
+        status.total_files = total_blob_count;
         status.progress = 20;
     });
 
-    // 加载 projects.json
-    let projects_path = home_projects_file();
-    let mut projects: ProjectsFile = if projects_path.exists() {
-        let data = fs::read_to_string(&projects_path).unwrap_or_default();
-        serde_json::from_str(&data).unwrap_or_default()
-    } else { ProjectsFile::default() };
-
-    let normalized_root = PathBuf::from(project_root_path).canonicalize().unwrap_or_else(|_| PathBuf::from(project_root_path)).to_string_lossy().replace('\\', "/");
-    let existing_blob_names: std::collections::HashSet<String> = projects.0.get(&normalized_root).cloned().unwrap_or_default().into_iter().collect();
-
-    // 计算所有 blob 的哈希值，建立哈希到 blob 的映射
+    // 计算变化 blob 的哈希值，建立哈希到 blob 的映射
     let mut blob_hash_map: std::collections::HashMap<String, BlobItem> = std::collections::HashMap::new();
-    for blob in &blobs {
+    for blob in &scan.changed_blobs {
         let hash = sha256_hex(&blob.path, &blob.content);
         blob_hash_map.insert(hash.clone(), blob.clone());
     }
 
-    // 分离已存在和新增加的 blob（与 Python 版本保持一致）
+    // 分离已存在和新增加的 blob（与 Python 版本保持一致），清单复用的 blob 均视为已存在
     let all_blob_hashes: std::collections::HashSet<String> = blob_hash_map.keys().cloned().collect();
-    let existing_hashes: std::collections::HashSet<String> = all_blob_hashes.intersection(&existing_blob_names).cloned().collect();
+    let existing_hashes: std::collections::HashSet<String> = all_blob_hashes
+        .intersection(&existing_blob_names)
+        .cloned()
+        .chain(scan.reused_blob_names.iter().cloned())
+        .collect();
     let new_hashes: std::collections::HashSet<String> = all_blob_hashes.difference(&existing_blob_names).cloned().collect();
 
     // 需要上传的新 blob
     let new_blobs: Vec<BlobItem> = new_hashes.iter().filter_map(|h| blob_hash_map.get(h).cloned()).collect();
 
+    // 进度事件：文件收集完成
+    let mut tracker = IndexProgressTracker::new(&normalized_root, new_blobs.iter().map(|b| b.path.as_str()));
+    tracker.stage(IndexStage::Collecting, 20, None);
+
     log_important!(info,
         "=== 索引统计 ==="
     );
     log_important!(info,
         "收集到blobs总数: {}, 既有blobs: {}, 新增blobs: {}, 需要上传: {}",
-        blobs.len(),
+        total_blob_count,
         existing_hashes.len(),
         new_hashes.len(),
         new_blobs.len()
@@ -953,132 +1502,144 @@ pub(crate) async fn update_index(config: &AcemcpConfig, project_root_path: &str)
     // 创建 HTTP 客户端（支持代理）
     let client = create_acemcp_client(config)?;
 
-    // 批量上传新增 blobs
+    // 并发批量上传新增 blobs
     let mut uploaded_names: Vec<String> = Vec::new();
     let mut failed_batches: Vec<usize> = Vec::new();
+    let mut failed_blob_count = 0usize;
+    let mut failed_paths: HashSet<String> = HashSet::new();
+    // 被服务端拒绝（非网络原因）的多文件批次，稍后逐个上传以找出问题文件
+    let mut rejected_batches: Vec<(usize, Vec<BlobItem>)> = Vec::new();
     
     if !new_blobs.is_empty() {
         let total_batches = (new_blobs.len() + batch_size - 1) / batch_size;
+        let concurrency = config.upload_concurrency.unwrap_or(4).max(1) as usize;
+        let url = format!("{}/batch-upload", base_url);
         log_important!(info,
             "=== 开始批量上传代码索引 ==="
         );
         log_important!(info,
-            "目标端点: {}/batch-upload, 总批次: {}, 每批上限: {}, 总blobs: {}",
-            base_url,
+            "目标端点: {}, 总批次: {}, 每批上限: {}, 并发数: {}, 总blobs: {}",
+            url,
             total_batches,
             batch_size,
+            concurrency,
             new_blobs.len()
         );
 
-        log_important!(info,
-            "=== 批量上传代码索引 ==="
-        );
+        // 使用信号量限制同时进行的批次数；暂停时尚未开始的批次在闸门处挂起
+        let gate = super::control::get_index_gate(&normalized_root);
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrency));
+        let compression = UploadCompression::from_config(config.upload_compression.as_deref());
+        let mut tasks = tokio::task::JoinSet::new();
+        for (i, batch) in new_blobs.chunks(batch_size).enumerate() {
+            let batch: Vec<BlobItem> = batch.to_vec();
+            let client = client.clone();
+            let url = url.clone();
+            let token = token.clone();
+            let semaphore = semaphore.clone();
+            let gate = gate.clone();
+            tasks.spawn(async move {
+                gate.wait_if_paused().await;
+                let _permit = semaphore.acquire_owned().await;
+                // 等待许可期间可能被暂停
+                gate.wait_if_paused().await;
+                let result = upload_batch(&client, &url, &token, &batch, i + 1, total_batches, compression, throttle).await;
+                (i + 1, batch, result)
+            });
+        }
 
-        for i in 0..total_batches {
-            let start = i * batch_size;
-            let end = usize::min(start + batch_size, new_blobs.len());
-            let batch = &new_blobs[start..end];
-            let url = format!("{}/batch-upload", base_url);
-            
-            log_important!(info,
-                "上传批次 {}/{}: url={}, blobs={}",
-                i + 1,
-                total_batches,
-                url,
-                batch.len()
-            );
-            
-            // 详细记录每个 blob 的信息
-            for (idx, blob) in batch.iter().enumerate() {
-                // 注意：这里的 path 可能包含项目结构信息，默认降级到 debug，避免日志膨胀
-                log_debug!(
-                    "  批次 {} - Blob {}/{}: path={}, content_length={}",
-                    i + 1,
-                    idx + 1,
-                    batch.len(),
-                    blob.path,
-                    blob.content.len()
-                );
-            }
-            
-            let payload = serde_json::json!({"blobs": batch});
-            // 避免对 payload 执行 to_string（会序列化并复制大量代码内容）
-            // 这里仅记录一个近似大小（字符数），用于排查性能问题
-            let approx_chars: usize = batch.iter()
-                .map(|b| b.path.len() + b.content.len())
-                .sum();
-            log_debug!("批次载荷概要: blobs={}, approx_chars={}", batch.len(), approx_chars);
-            
-            match retry_request(|| async {
-                let r = client
-                    .post(&url)
-                    .header(AUTHORIZATION, format!("Bearer {}", token))
-                    .header(CONTENT_TYPE, "application/json")
-                    .json(&payload)
-                    .send()
-                    .await?;
-                
-                let status = r.status();
-                log_important!(info, "HTTP响应状态: {}", status);
-                
-                if !status.is_success() {
-                    let body = r.text().await.unwrap_or_default();
-                    anyhow::bail!("HTTP {} {}", status, body);
+        let mut completed = 0usize;
+        while let Some(joined) = tasks.join_next().await {
+            completed += 1;
+            let (batch_no, batch, result) = match joined {
+                Ok(v) => v,
+                Err(e) => {
+                    log_important!(info, "上传任务异常退出: {}", e);
+                    continue;
                 }
-                
-                let v: serde_json::Value = r.json().await?;
-                // 只记录摘要，避免把响应全文（可能较大）写入日志
-                let keys: Vec<String> = v
-                    .as_object()
-                    .map(|m| m.keys().cloned().collect())
-                    .unwrap_or_default();
-                let blob_names_len = v
-                    .get("blob_names")
-                    .and_then(|x| x.as_array())
-                    .map(|arr| arr.len())
-                    .unwrap_or(0);
-                log_important!(info, "上传响应摘要: keys={:?}, blob_names={}", keys, blob_names_len);
-                Ok(v)
-            }, 3, 1.0).await {
-                Ok(value) => {
-                    if let Some(arr) = value.get("blob_names").and_then(|v| v.as_array()) {
-                        let mut batch_names: Vec<String> = Vec::new();
-                        for v in arr { 
-                            if let Some(s) = v.as_str() { 
-                                batch_names.push(s.to_string()); 
-                            }
-                        }
-                        
-                        if batch_names.is_empty() {
-                            log_important!(info, "批次 {} 返回了空的blob名称列表", i + 1);
-                            failed_batches.push(i + 1);
-                        } else {
-                            uploaded_names.extend(batch_names.clone());
-                            log_important!(info, "批次 {} 上传成功，获得 {} 个blob名称", i + 1, batch_names.len());
-                            // 详细记录每个上传成功的 blob 名称
-                            for (idx, name) in batch_names.iter().enumerate() {
-                                // 默认降级到 debug，避免日志文件过大
-                                log_debug!("  批次 {} - 上传成功 Blob {}/{}: name={}", i + 1, idx + 1, batch_names.len(), name);
+            };
+
+            match result {
+                Ok(batch_names) => {
+                    log_important!(info, "批次 {} 上传成功，获得 {} 个blob名称", batch_no, batch_names.len());
+                    // 详细记录每个上传成功的 blob 名称
+                    for (idx, name) in batch_names.iter().enumerate() {
+                        // 默认降级到 debug，避免日志文件过大
+                        log_debug!("  批次 {} - 上传成功 Blob {}/{}: name={}", batch_no, idx + 1, batch_names.len(), name);
+                    }
+                    stats.blobs_uploaded += batch_names.len();
+                    stats.bytes_uploaded += batch.iter().map(|b| b.content.len() as u64).sum::<u64>();
+                    uploaded_names.extend(batch_names);
+                    // 检查点：每完成一个批次就写回 projects.json，暂停或中断后可从此处继续
+                    let checkpoint: Vec<String> = existing_hashes.iter().chain(uploaded_names.iter()).cloned().collect();
+                    projects.0.insert(normalized_root.clone(), checkpoint);
+                    if let Ok(s) = serde_json::to_string_pretty(&projects) { let _ = cache_crypto::write(&projects_path, s); }
+                }
+                Err(e) => {
+                    log_important!(info, "批次 {} 上传失败: {}", batch_no, e);
+                    let error = e.to_string();
+                    if !is_retryable_error(&error) && batch.len() > 1 {
+                        rejected_batches.push((batch_no, batch.clone()));
+                    } else {
+                        failed_batches.push(batch_no);
+                        failed_blob_count += batch.len();
+                        for blob in &batch {
+                            let file_path = blob_file_path(&blob.path);
+                            failed_paths.insert(file_path.to_string());
+                            // 网络类错误与文件本身无关，不计入隔离
+                            if !is_retryable_error(&error) {
+                                file_failures.entry(file_path.to_string()).or_insert_with(|| failure_reason(&error));
                             }
                         }
-                    } else {
-                        log_important!(info, "批次 {} 响应中缺少blob_names字段", i + 1);
-                        failed_batches.push(i + 1);
                     }
                 }
-                Err(e) => {
-                    log_important!(info, "批次 {} 上传失败: {}", i + 1, e);
-                    failed_batches.push(i + 1);
+            }
+
+            // 上传阶段的进度映射到 20-90
+            let progress = (20 + completed * 70 / total_batches) as u8;
+            let _ = update_project_status(project_root_path, |status| {
+                status.progress = progress;
+            });
+            tracker.advance(batch.iter().map(|b| b.path.as_str()), progress);
+        }
+
+        // 逐个重新上传被拒绝批次中的 blob，只让真正有问题的文件失败
+        for (batch_no, batch) in rejected_batches {
+            log_important!(info, "批次 {} 被服务端拒绝，逐个上传以定位问题文件（{} 个blobs）", batch_no, batch.len());
+            let mut batch_failed = false;
+            for blob in batch {
+                let single = [blob];
+                match upload_batch(&client, &url, &token, &single, batch_no, total_batches, compression, throttle).await {
+                    Ok(names) => {
+                        stats.blobs_uploaded += names.len();
+                        stats.bytes_uploaded += single[0].content.len() as u64;
+                        uploaded_names.extend(names);
+                    }
+                    Err(e) => {
+                        let file_path = blob_file_path(&single[0].path).to_string();
+                        log_important!(info, "文件上传被拒绝: path={}, error={}", file_path, e);
+                        batch_failed = true;
+                        failed_blob_count += 1;
+                        let error = e.to_string();
+                        if !is_retryable_error(&error) {
+                            file_failures.entry(file_path.clone()).or_insert_with(|| failure_reason(&error));
+                        }
+                        failed_paths.insert(file_path);
+                    }
                 }
             }
+            if batch_failed {
+                failed_batches.push(batch_no);
+            }
         }
+        failed_batches.sort_unstable();
         
         // 上传结果总结
         log_important!(info,
             "=== 上传结果总结 ==="
         );
         if !failed_batches.is_empty() {
-            log_important!(info, "上传完成，但有失败的批次: {:?}, 成功上传blobs: {}", failed_batches, uploaded_names.len());
+            log_important!(info, "上传完成，但有失败的批次: {:?}, 失败文件数: {}, 成功上传blobs: {}", failed_batches, failed_paths.len(), uploaded_names.len());
         } else {
             log_important!(info, "所有批次上传成功，共上传 {} 个blobs", uploaded_names.len());
         }
@@ -1090,7 +1651,7 @@ pub(crate) async fn update_index(config: &AcemcpConfig, project_root_path: &str)
     // 只保留当前项目中仍然存在的 blob 的哈希值（自动删除已删除的 blob）
     let all_blob_names: Vec<String> = existing_hashes.into_iter().chain(uploaded_names.into_iter()).collect();
     projects.0.insert(normalized_root.clone(), all_blob_names.clone());
-    if let Ok(s) = serde_json::to_string_pretty(&projects) { let _ = fs::write(projects_path, s); }
+    if let Ok(s) = serde_json::to_string_pretty(&projects) { let _ = cache_crypto::write(projects_path, s); }
 
     // 使用合并后的 blob_names（与 Python 版本保持一致）
     let blob_names = all_blob_names;
@@ -1102,9 +1663,43 @@ pub(crate) async fn update_index(config: &AcemcpConfig, project_root_path: &str)
             status.last_error = Some("索引后未找到 blobs".to_string());
             status.last_failure_time = Some(chrono::Utc::now());
         });
+        tracker.stage(IndexStage::Failed, 100, Some("索引后未找到 blobs".to_string()));
         anyhow::bail!("索引后未找到 blobs");
     }
 
+    // 保存文件清单：只记录 blob 全部就绪的文件，上传失败的文件下次会重新处理
+    let indexed_names: HashSet<&String> = blob_names.iter().collect();
+    let mut manifest = scan.manifest;
+    let recorded_files = manifest.files.len();
+    manifest.files.retain(|_, record| record.blob_names.iter().all(|name| indexed_names.contains(name)));
+    if manifest.files.len() != recorded_files {
+        // 有文件未能索引：不推进 git 基准提交，确保下次增量扫描仍包含这些文件
+        manifest.git_head = previous_manifest.git_head.clone();
+    }
+    if let Err(e) = manifest.save(&normalized_root) {
+        log_debug!("保存文件清单失败（不影响索引）: {}", e);
+    }
+
+    // 更新隔离台账：成功索引的文件清零，本次失败的文件累计次数，达到阈值后隔离
+    let now = chrono::Utc::now();
+    for path in manifest.files.keys() {
+        ledger.record_success(path);
+    }
+    for (path, reason) in &file_failures {
+        if ledger.record_failure(path, reason, now) {
+            log_important!(warn, "文件连续上传失败，已隔离: path={}, 原因={}", path, reason);
+        }
+    }
+    let quarantined_files = ledger.quarantined_files();
+    if let Err(e) = ledger.save(&normalized_root) {
+        log_debug!("保存隔离台账失败（不影响索引）: {}", e);
+    }
+    let quarantine_warning = if quarantined_files.is_empty() {
+        None
+    } else {
+        Some(format!("{} 个文件因反复上传失败已隔离，修复后可手动重试", quarantined_files.len()))
+    };
+
     // 检查是否是首次成功索引（用于 store 集成）
     let is_first_success = {
         let status = get_project_status(project_root_path);
@@ -1112,14 +1707,36 @@ pub(crate) async fn update_index(config: &AcemcpConfig, project_root_path: &str)
     };
 
     // 更新状态：索引成功完成
+    let mut failed_paths: Vec<String> = failed_paths.into_iter().collect();
+    failed_paths.sort();
+    stats.failed_batches = failed_batches.len();
+    stats.failed_files = failed_paths.len();
+    if !failed_batches.is_empty() {
+        stats.partial_error = Some(format!("{} 个批次上传失败（批次 {:?}）", failed_batches.len(), failed_batches));
+    }
     let _ = update_project_status(project_root_path, |status| {
         status.status = IndexStatus::Synced;
         status.progress = 100;
-        status.indexed_files = blobs.len();
+        status.indexed_files = total_blob_count - failed_blob_count;
         status.pending_files = 0;
+        status.failed_files = failed_paths.len();
         status.last_success_time = Some(chrono::Utc::now());
-        status.last_error = None;
+        let upload_error = if failed_batches.is_empty() {
+            None
+        } else {
+            Some(format!("{} 个批次上传失败（批次 {:?}），下次索引时将重试", failed_batches.len(), failed_batches))
+        };
+        let warnings: Vec<String> = [upload_error, quarantine_warning, secret_warning].into_iter().flatten().collect();
+        status.last_error = if warnings.is_empty() { None } else { Some(warnings.join("；")) };
+        status.failed_paths = failed_paths;
+        status.quarantined = quarantined_files;
     });
+    let completion_message = if failed_batches.is_empty() {
+        None
+    } else {
+        Some(format!("{} 个批次上传失败", failed_batches.len()))
+    };
+    tracker.stage(IndexStage::Completed, 100, completion_message);
 
     // 首次成功索引时，写入 store 记忆
     if is_first_success {
@@ -1130,6 +1747,155 @@ pub(crate) async fn update_index(config: &AcemcpConfig, project_root_path: &str)
     Ok(blob_names)
 }
 
+/// 从 blob 路径（可能带 #chunkN 后缀）取出文件相对路径
+fn blob_file_path(blob_path: &str) -> &str {
+    blob_path.split("#chunk").next().unwrap_or(blob_path)
+}
+
+/// 记录到隔离台账的失败原因（截断过长的响应体）
+fn failure_reason(error: &str) -> String {
+    const MAX_CHARS: usize = 200;
+    let error = error.trim();
+    if error.chars().count() > MAX_CHARS {
+        format!("{}…", error.chars().take(MAX_CHARS).collect::<String>())
+    } else {
+        error.to_string()
+    }
+}
+
+/// 上传单个批次（带重试），返回服务端生成的 blob 名称
+async fn upload_batch(
+    client: &Client,
+    url: &str,
+    token: &str,
+    batch: &[BlobItem],
+    batch_no: usize,
+    total_batches: usize,
+    compression: UploadCompression,
+    throttle: IndexThrottle,
+) -> anyhow::Result<Vec<String>> {
+    log_important!(info,
+        "上传批次 {}/{}: url={}, blobs={}",
+        batch_no,
+        total_batches,
+        url,
+        batch.len()
+    );
+
+    // 详细记录每个 blob 的信息
+    for (idx, blob) in batch.iter().enumerate() {
+        // 注意：这里的 path 可能包含项目结构信息，默认降级到 debug，避免日志膨胀
+        log_debug!(
+            "  批次 {} - Blob {}/{}: path={}, content_length={}",
+            batch_no,
+            idx + 1,
+            batch.len(),
+            blob.path,
+            blob.content.len()
+        );
+    }
+
+    let payload = serde_json::json!({"blobs": batch});
+    // 避免对 payload 执行 to_string（会序列化并复制大量代码内容）
+    // 这里仅记录一个近似大小（字符数），用于排查性能问题
+    let approx_chars: usize = batch.iter()
+        .map(|b| b.path.len() + b.content.len())
+        .sum();
+    log_debug!("批次载荷概要: blobs={}, approx_chars={}", batch.len(), approx_chars);
+
+    // 序列化一次，重试时复用；按压缩模式预先生成 gzip 载荷
+    let body = serde_json::to_vec(&payload)?;
+    let gzipped = if should_compress(compression, url, body.len()) {
+        match gzip_payload(&body) {
+            Ok(g) => {
+                log_debug!("批次 {} 载荷已压缩: {} -> {} 字节", batch_no, body.len(), g.len());
+                Some(g)
+            }
+            Err(e) => {
+                log_debug!("压缩载荷失败，改为不压缩上传: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let value: serde_json::Value = retry_request(|| async {
+        let send_plain = || {
+            client
+                .post(url)
+                .header(AUTHORIZATION, format!("Bearer {}", token))
+                .header(CONTENT_TYPE, "application/json")
+                .body(body.clone())
+                .send()
+        };
+
+        let mut r = match &gzipped {
+            Some(g) if should_compress(compression, url, body.len()) => {
+                throttle.pace_upload(g.len()).await;
+                client
+                    .post(url)
+                    .header(AUTHORIZATION, format!("Bearer {}", token))
+                    .header(CONTENT_TYPE, "application/json")
+                    .header(CONTENT_ENCODING, "gzip")
+                    .body(g.clone())
+                    .send()
+                    .await?
+            }
+            _ => {
+                throttle.pace_upload(body.len()).await;
+                send_plain().await?
+            }
+        };
+
+        // auto 模式：端点不接受压缩请求体时回退并记住
+        if gzipped.is_some()
+            && compression == UploadCompression::Auto
+            && matches!(r.status().as_u16(), 400 | 415)
+        {
+            log_important!(info, "端点不接受压缩请求体（HTTP {}），回退为不压缩上传: {}", r.status(), url);
+            mark_compression_rejected(url);
+            throttle.pace_upload(body.len()).await;
+            r = send_plain().await?;
+        }
+
+        let status = r.status();
+        log_important!(info, "HTTP响应状态: {}", status);
+
+        if !status.is_success() {
+            let body = r.text().await.unwrap_or_default();
+            anyhow::bail!("HTTP {} {}", status, body);
+        }
+
+        let v: serde_json::Value = r.json().await?;
+        // 只记录摘要，避免把响应全文（可能较大）写入日志
+        let keys: Vec<String> = v
+            .as_object()
+            .map(|m| m.keys().cloned().collect())
+            .unwrap_or_default();
+        let blob_names_len = v
+            .get("blob_names")
+            .and_then(|x| x.as_array())
+            .map(|arr| arr.len())
+            .unwrap_or(0);
+        log_important!(info, "上传响应摘要: keys={:?}, blob_names={}", keys, blob_names_len);
+        Ok(v)
+    }, 4, 1.0).await?;
+
+    let names: Vec<String> = value
+        .get("blob_names")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| anyhow::anyhow!("响应中缺少blob_names字段"))?
+        .iter()
+        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+        .collect();
+
+    if names.is_empty() {
+        anyhow::bail!("返回了空的blob名称列表");
+    }
+    Ok(names)
+}
+
 /// 将索引配置信息写入 store（记忆）工具
 fn write_index_memory_to_store(project_root_path: &str, config: &AcemcpConfig) {
     use super::super::memory::MemoryManager;
@@ -1166,16 +1932,98 @@ fn write_index_memory_to_store(project_root_path: &str, config: &AcemcpConfig) {
     }
 }
 
+/// 带缓存的检索：同一项目、同一查询、同一索引检查点在有效期内直接返回缓存结果
+async fn cached_search(config: &AcemcpConfig, project_root_path: &str, query: &str) -> anyhow::Result<String> {
+    let ttl_secs = config
+        .search_cache_ttl_secs
+        .unwrap_or(super::search_cache::DEFAULT_SEARCH_CACHE_TTL_SECS);
+    if ttl_secs == 0 {
+        return search_only(config, project_root_path, query).await;
+    }
+
+    let normalized_root = normalize_project_root(project_root_path);
+    let checkpoint = index_checkpoint(config, project_root_path);
+    if let Some(hit) = super::search_cache::get_cached(&normalized_root, query, &checkpoint, Duration::from_secs(ttl_secs)) {
+        log_important!(info, "命中检索缓存: project_root_path={}, query={}", project_root_path, query);
+        return Ok(hit);
+    }
+
+    let result = search_only(config, project_root_path, query).await?;
+    super::search_cache::put_cached(&normalized_root, query, &checkpoint, result.clone());
+    Ok(result)
+}
+
+/// 索引检查点：后端与最近一次成功索引的时间/文件数，任一变化即视为索引已更新
+fn index_checkpoint(config: &AcemcpConfig, project_root_path: &str) -> String {
+    let status = get_project_status(project_root_path);
+    let backend = if config.is_local_backend() {
+        "local"
+    } else {
+        config.endpoint_name_for(&normalize_project_root(project_root_path)).unwrap_or("remote")
+    };
+    format!(
+        "{}:{}:{}",
+        backend,
+        status.last_success_time.map(|t| t.timestamp_millis()).unwrap_or(0),
+        status.indexed_files
+    )
+}
+
+/// 解析项目使用的端点配置（未分配端点时即为默认配置）
+fn resolve_project_endpoint(config: &AcemcpConfig, project_root_path: &str) -> anyhow::Result<AcemcpConfig> {
+    let normalized_root = normalize_project_root(project_root_path);
+    let mut resolved = config.for_endpoint(&normalized_root)?;
+    if let Some(name) = config.endpoint_name_for(&normalized_root) {
+        log_debug!("项目使用索引端点: project_root={}, endpoint={}", normalized_root, name);
+        if let Some(base) = &resolved.base_url {
+            resolved.base_url = Some(normalize_base_url(base));
+        }
+    }
+    Ok(resolved)
+}
+
+/// 清空项目在远程端点上的索引记录（blob 列表、文件清单与检索缓存）
+/// 切换端点后旧租户的 blob 无法在新租户中使用，需要重新上传
+pub(crate) fn reset_project_blobs(normalized_root: &str) -> Result<()> {
+    let projects_path = home_projects_file();
+    if projects_path.exists() {
+        let data = cache_crypto::read_to_string(&projects_path)?;
+        let mut projects: ProjectsFile = serde_json::from_str(&data).unwrap_or_default();
+        if projects.0.remove(normalized_root).is_some() {
+            cache_crypto::write(&projects_path, serde_json::to_string_pretty(&projects)?)?;
+        }
+    }
+    FileManifest::remove(normalized_root);
+    super::search_cache::invalidate_project(normalized_root);
+    Ok(())
+}
+
 /// 只执行搜索，不触发索引
 /// 使用已有的索引数据进行搜索
 async fn search_only(config: &AcemcpConfig, project_root_path: &str, query: &str) -> anyhow::Result<String> {
+    // 可选的查询预处理（去除代码块围栏、展开缩写、中文术语补充英文关键词）
+    let rewritten = rewrite_query(query, &QueryRewriteOptions::from_config(config));
+    if rewritten != query {
+        log_debug!("查询已改写: {} -> {}", query, rewritten);
+    }
+    let query = rewritten.as_str();
+
+    if config.is_local_backend() {
+        log_important!(info, "使用本地索引检索: project_root_path={}, query={}", project_root_path, query);
+        return super::local_index::search_local(project_root_path, query);
+    }
+
+    // 按项目分配的端点（租户）路由
+    let endpoint_config = resolve_project_endpoint(config, project_root_path)?;
+    let config = &endpoint_config;
+
     let base_url = config.base_url.clone().ok_or_else(|| anyhow::anyhow!("未配置 base_url"))?;
     let token = config.token.clone().ok_or_else(|| anyhow::anyhow!("未配置 token"))?;
 
     // 从 projects.json 读取已有的 blob 名称
     let projects_path = home_projects_file();
     let projects: ProjectsFile = if projects_path.exists() {
-        let data = fs::read_to_string(&projects_path).unwrap_or_default();
+        let data = cache_crypto::read_to_string(&projects_path).unwrap_or_default();
         serde_json::from_str(&data).unwrap_or_default()
     } else {
         ProjectsFile::default()
@@ -1258,58 +2106,8 @@ async fn search_only(config: &AcemcpConfig, project_root_path: &str, query: &str
     }
 }
 
-/// 创建支持代理的 HTTP 客户端
-/// 根据配置决定是否使用代理
+/// 获取支持代理的 HTTP 客户端
+/// 按代理配置复用共享客户端（连接池 + keep-alive），避免每次请求重新握手
 fn create_acemcp_client(config: &AcemcpConfig) -> anyhow::Result<Client> {
-    let mut client_builder = Client::builder()
-        .timeout(Duration::from_secs(60));
-    
-    // 检查是否启用代理
-    if config.proxy_enabled.unwrap_or(false) {
-        let host = config.proxy_host.clone().unwrap_or_else(|| "127.0.0.1".to_string());
-        let port = config.proxy_port.unwrap_or(7890);
-        let proxy_type = config.proxy_type.clone().unwrap_or_else(|| "http".to_string());
-        
-        // 校验代理类型，避免拼接出无效 URL
-        match proxy_type.as_str() {
-            "http" | "https" | "socks5" => {}
-            other => anyhow::bail!("不支持的代理类型: {}（仅支持 http/https/socks5）", other),
-        }
-
-        // 仅用于日志提示（避免泄露密码）
-        let has_auth = config
-            .proxy_username
-            .as_deref()
-            .map(|u| !u.trim().is_empty())
-            .unwrap_or(false);
-
-        if has_auth {
-            log_important!(info, "🔧 使用代理: {}://{}:{}（带认证）", proxy_type, host, port);
-        } else {
-            log_important!(info, "🔧 使用代理: {}://{}:{}", proxy_type, host, port);
-        }
-        
-        // 构建代理 URL
-        let proxy_url = format!("{}://{}:{}", proxy_type, host, port);
-        
-        // 使用 Proxy::all() 让所有请求都走代理
-        let mut reqwest_proxy = reqwest::Proxy::all(&proxy_url)
-            .map_err(|e| anyhow::anyhow!("创建代理失败: {}", e))?;
-
-        // 代理认证（Basic Auth）
-        if let Some(username) = config.proxy_username.as_deref() {
-            let username = username.trim();
-            if !username.is_empty() {
-                let password = config.proxy_password.as_deref().unwrap_or("");
-                reqwest_proxy = reqwest_proxy.basic_auth(username, password);
-            }
-        }
-
-        client_builder = client_builder.proxy(reqwest_proxy);
-    } else {
-        log_debug!("使用直连模式（未启用代理）");
-    }
-    
-    client_builder.build()
-        .map_err(|e| anyhow::anyhow!("构建 HTTP 客户端失败: {}", e))
+    super::http_pool::shared_client(config)
 }
@@ -5,6 +5,32 @@ pub mod mcp;
 pub mod types;
 pub mod commands;
 pub mod watcher;
+pub mod local_index;
+pub mod chunker;
+pub mod progress;
+pub mod control;
+pub mod federated;
+pub mod search_cache;
+pub mod file_manifest;
+pub mod git_diff;
+pub mod project_config;
+pub mod file_filter;
+pub mod compression;
+pub mod throttle;
+pub mod scheduler;
+pub mod watch_plan;
+pub mod index_history;
+pub mod query_rewrite;
+pub mod secret_scan;
+pub mod path_scope;
+pub mod hits;
+pub mod preview;
+pub mod stale;
+pub mod http_pool;
+pub mod health;
+pub mod cache_crypto;
+pub mod filter_explain;
+pub mod quarantine;
 
 // 重新导出工具以便访问
 pub use mcp::AcemcpTool;
@@ -0,0 +1,94 @@
+// 检索结果的路径范围过滤
+// 调用方可在请求中携带 include_paths / exclude_paths（glob），例如只看 `src/**`、排除 `**/tests/**`，
+// 过滤作用于检索返回的代码片段，在格式化 CallToolResult 之前完成
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// 路径范围（include 为空表示不限制）
+pub(crate) struct PathScope {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathScope {
+    /// 根据请求参数构建路径范围；两者都为空时返回 None
+    pub(crate) fn new(include: &[String], exclude: &[String]) -> anyhow::Result<Option<Self>> {
+        if include.is_empty() && exclude.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            include: build_globset(include)?,
+            exclude: build_globset(exclude)?,
+        }))
+    }
+
+    /// 路径是否在范围内（不含 `/` 的模式同时匹配文件名，如 `*.rs`）
+    pub(crate) fn allows(&self, path: &str) -> bool {
+        let path = path.trim().trim_start_matches("./").replace('\\', "/");
+        let file_name = path.rsplit('/').next().unwrap_or(&path).to_string();
+        let matches = |set: &GlobSet| set.is_match(&path) || set.is_match(&file_name);
+
+        if let Some(exclude) = &self.exclude {
+            if matches(exclude) {
+                return false;
+            }
+        }
+        self.include.as_ref().map(|include| matches(include)).unwrap_or(true)
+    }
+
+    /// 过滤检索片段（每段以 `Path: ` 行开头），返回保留的片段与被过滤的数量
+    pub(crate) fn filter_sections(&self, sections: Vec<String>) -> (Vec<String>, usize) {
+        let total = sections.len();
+        let kept: Vec<String> = sections
+            .into_iter()
+            .filter(|section| section_path(section).map(|p| self.allows(p)).unwrap_or(true))
+            .collect();
+        let removed = total - kept.len();
+        (kept, removed)
+    }
+}
+
+/// 取片段首行 `Path: ` 之后的文件路径
+fn section_path(section: &str) -> Option<&str> {
+    section.lines().next()?.strip_prefix("Path: ").map(str::trim)
+}
+
+fn build_globset(patterns: &[String]) -> anyhow::Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = GlobBuilder::new(pattern.trim().trim_start_matches("./"))
+            .literal_separator(true)
+            .build()
+            .map_err(|e| anyhow::anyhow!("无效的路径模式 {}: {}", pattern, e))?;
+        builder.add(glob);
+    }
+    Ok(Some(builder.build()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_scope_filters_sections() {
+        let scope = PathScope::new(&["src/**".to_string()], &["**/tests/**".to_string(), "*.md".to_string()])
+            .unwrap()
+            .unwrap();
+        assert!(scope.allows("src/mcp/server.rs"));
+        assert!(!scope.allows("src/mcp/tests/server.rs"));
+        assert!(!scope.allows("src/README.md"));
+        assert!(!scope.allows("lib/a.rs"));
+
+        let (kept, removed) = scope.filter_sections(vec![
+            "Path: src/a.rs\n1 fn a() {}".to_string(),
+            "Path: docs/b.rs\n1 fn b() {}".to_string(),
+        ]);
+        assert_eq!(kept, vec!["Path: src/a.rs\n1 fn a() {}"]);
+        assert_eq!(removed, 1);
+
+        assert!(PathScope::new(&[], &[]).unwrap().is_none());
+    }
+}
@@ -0,0 +1,180 @@
+// 索引预览（dry-run）
+// 执行与正式索引相同的候选文件收集、密钥扫描与分块流程，但不上传任何内容，
+// 汇报将要发送的文件数/blob 数/字节数以及各项排除规则的命中情况，便于用户在发送代码前核对过滤配置
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use super::file_filter::ContentLimits;
+use super::mcp::{
+    home_projects_file, normalize_project_root, read_file_with_encoding, sha256_hex, split_content,
+    walk_indexable_files_tallied, ProjectsFile,
+};
+use super::secret_scan::{describe as describe_secrets, redact as redact_secrets, scan_secrets, SecretScanMode};
+use super::types::AcemcpConfig;
+
+/// 每类排除原因最多保留的示例路径数
+const MAX_SAMPLES_PER_REASON: usize = 10;
+
+/// 预览结果中最多列出的大文件数
+const MAX_LARGEST_FILES: usize = 10;
+
+/// 排除原因统计
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ExclusionSummary {
+    pub count: usize,
+    /// 示例路径（相对项目根目录）
+    pub samples: Vec<String>,
+}
+
+/// 遍历过程中按原因累计被排除的文件/目录
+#[derive(Debug, Default)]
+pub(crate) struct ExclusionTally {
+    reasons: BTreeMap<&'static str, ExclusionSummary>,
+}
+
+impl ExclusionTally {
+    pub(crate) fn record(&mut self, reason: &'static str, rel_path: &str) {
+        let entry = self.reasons.entry(reason).or_default();
+        entry.count += 1;
+        if entry.samples.len() < MAX_SAMPLES_PER_REASON {
+            entry.samples.push(rel_path.to_string());
+        }
+    }
+}
+
+/// 索引预览结果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IndexPreview {
+    pub project_root: String,
+    /// 索引后端（local 表示不会上传）
+    pub backend: String,
+    /// 可索引文件数
+    pub files: usize,
+    /// 分块后的 blob 总数与字节数
+    pub blobs: usize,
+    pub bytes: u64,
+    /// 尚未上传、实际会发送的 blob 数与字节数
+    pub new_blobs: usize,
+    pub new_bytes: u64,
+    /// 排除原因 -> 统计（gitignore / exclude_pattern / opt_out_marker / extension / too_large / binary / unreadable / secret_skipped）
+    pub excluded: BTreeMap<String, ExclusionSummary>,
+    /// 检测到疑似密钥的文件（含处理方式）
+    pub secret_files: Vec<String>,
+    /// 体积最大的文件（相对路径, 字节数）
+    pub largest_files: Vec<(String, u64)>,
+}
+
+/// 生成索引预览（阻塞执行，调用方需放到阻塞线程中）
+pub(crate) fn preview_index(config: &AcemcpConfig, project_root_path: &str) -> anyhow::Result<IndexPreview> {
+    let max_lines = config.max_lines_per_blob.unwrap_or(800) as usize;
+    let text_exts = config.text_extensions.clone().unwrap_or_default();
+    let exclude_patterns = config.exclude_patterns.clone().unwrap_or_default();
+    let limits = ContentLimits::from_config(config);
+    let secret_mode = SecretScanMode::from_config(config.secret_scan.as_deref());
+    let normalized_root = normalize_project_root(project_root_path);
+
+    let mut tally = ExclusionTally::default();
+    let files = walk_indexable_files_tallied(project_root_path, &text_exts, &exclude_patterns, &limits, &mut tally)?;
+
+    // 已上传的 blob 不会重复发送
+    let existing: HashSet<String> = super::cache_crypto::read_to_string(home_projects_file())
+        .ok()
+        .and_then(|data| serde_json::from_str::<ProjectsFile>(&data).ok())
+        .and_then(|projects| projects.0.get(&normalized_root).cloned())
+        .unwrap_or_default()
+        .into_iter()
+        .collect();
+
+    let mut preview = IndexPreview {
+        project_root: normalized_root,
+        backend: if config.is_local_backend() { "local" } else { "remote" }.to_string(),
+        ..Default::default()
+    };
+    let mut sizes: Vec<(String, u64)> = Vec::new();
+
+    for (path, rel) in &files {
+        let Some(mut content) = read_file_with_encoding(path) else {
+            tally.record("unreadable", rel);
+            continue;
+        };
+
+        if secret_mode != SecretScanMode::Off {
+            let secrets = scan_secrets(&content);
+            if !secrets.is_empty() {
+                let kinds = describe_secrets(&secrets);
+                if secret_mode == SecretScanMode::Skip {
+                    tally.record("secret_skipped", rel);
+                    preview.secret_files.push(format!("{}（将跳过: {}）", rel, kinds));
+                    continue;
+                }
+                preview.secret_files.push(format!("{}（将脱敏: {}）", rel, kinds));
+                content = redact_secrets(&content, &secrets);
+            }
+        }
+
+        preview.files += 1;
+        sizes.push((rel.clone(), content.len() as u64));
+        for blob in split_content(rel, &content, max_lines) {
+            let bytes = blob.content.len() as u64;
+            preview.blobs += 1;
+            preview.bytes += bytes;
+            if !existing.contains(&sha256_hex(&blob.path, &blob.content)) {
+                preview.new_blobs += 1;
+                preview.new_bytes += bytes;
+            }
+        }
+    }
+
+    sizes.sort_by(|a, b| b.1.cmp(&a.1));
+    sizes.truncate(MAX_LARGEST_FILES);
+    preview.largest_files = sizes;
+    preview.excluded = tally.reasons.into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+    Ok(preview)
+}
+
+/// 将预览结果渲染为便于命令行阅读的文本
+pub fn render_preview(preview: &IndexPreview) -> String {
+    let mut lines = vec![
+        format!("项目: {}", preview.project_root),
+        format!("后端: {}", preview.backend),
+        format!("可索引文件: {}", preview.files),
+        format!("blob 总数: {}（{} 字节）", preview.blobs, preview.bytes),
+        format!("将上传: {} 个 blob（{} 字节）", preview.new_blobs, preview.new_bytes),
+    ];
+    if preview.backend == "local" {
+        lines.push("本地后端不会上传任何内容".to_string());
+    }
+    if !preview.excluded.is_empty() {
+        lines.push("排除统计:".to_string());
+        for (reason, summary) in &preview.excluded {
+            lines.push(format!("  {}: {}（示例: {}）", reason, summary.count, summary.samples.join(", ")));
+        }
+    }
+    if !preview.secret_files.is_empty() {
+        lines.push("疑似密钥:".to_string());
+        lines.extend(preview.secret_files.iter().map(|f| format!("  {}", f)));
+    }
+    if !preview.largest_files.is_empty() {
+        lines.push("最大的文件:".to_string());
+        lines.extend(preview.largest_files.iter().map(|(path, size)| format!("  {} ({} 字节)", path, size)));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exclusion_tally_caps_samples() {
+        let mut tally = ExclusionTally::default();
+        for i in 0..(MAX_SAMPLES_PER_REASON + 5) {
+            tally.record("binary", &format!("assets/{}.bin", i));
+        }
+        let summary = &tally.reasons["binary"];
+        assert_eq!(summary.count, MAX_SAMPLES_PER_REASON + 5);
+        assert_eq!(summary.samples.len(), MAX_SAMPLES_PER_REASON);
+    }
+}
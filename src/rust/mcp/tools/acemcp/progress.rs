@@ -0,0 +1,139 @@
+// 索引进度事件
+// 索引过程中向前端推送 `acemcp://index-progress` 事件，设置界面无需轮询即可展示实时进度
+// MCP 独立进程中没有 AppHandle，此时事件静默丢弃
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+/// 索引进度事件名
+pub const INDEX_PROGRESS_EVENT: &str = "acemcp://index-progress";
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+/// 注册用于推送进度事件的 AppHandle（GUI 启动时调用一次）
+pub fn set_progress_app_handle(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// 索引阶段
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexStage {
+    /// 扫描并收集文件
+    Collecting,
+    /// 上传/构建索引中
+    Uploading,
+    /// 已完成
+    Completed,
+    /// 失败
+    Failed,
+}
+
+/// 索引进度事件载荷
+#[derive(Debug, Clone, Serialize)]
+pub struct IndexProgressEvent {
+    /// 项目根路径（规范化后）
+    pub project_root: String,
+    /// 当前阶段
+    pub stage: IndexStage,
+    /// 总体进度百分比（0-100）
+    pub progress: u8,
+    /// 需要上传的 blob 总数
+    pub total_blobs: usize,
+    /// 已处理的 blob 数
+    pub processed_blobs: usize,
+    /// 预计剩余时间（秒），无法估算时为 None
+    pub eta_secs: Option<u64>,
+    /// 按顶层目录聚合的进度（目录 -> (已完成, 总数)）
+    pub directories: HashMap<String, (usize, usize)>,
+    /// 附加信息（如错误原因）
+    pub message: Option<String>,
+}
+
+/// 单次索引运行的进度跟踪器
+pub(crate) struct IndexProgressTracker {
+    project_root: String,
+    started_at: Instant,
+    total_blobs: usize,
+    processed_blobs: usize,
+    directories: HashMap<String, (usize, usize)>,
+}
+
+impl IndexProgressTracker {
+    /// 以待处理的 blob 路径创建跟踪器
+    pub(crate) fn new<'a>(project_root: &str, blob_paths: impl Iterator<Item = &'a str>) -> Self {
+        let mut directories: HashMap<String, (usize, usize)> = HashMap::new();
+        let mut total_blobs = 0;
+        for path in blob_paths {
+            directories.entry(top_level_dir(path)).or_insert((0, 0)).1 += 1;
+            total_blobs += 1;
+        }
+
+        Self {
+            project_root: project_root.to_string(),
+            started_at: Instant::now(),
+            total_blobs,
+            processed_blobs: 0,
+            directories,
+        }
+    }
+
+    /// 推送阶段事件（不改变计数）
+    pub(crate) fn stage(&self, stage: IndexStage, progress: u8, message: Option<String>) {
+        emit(self.event(stage, progress, message));
+    }
+
+    /// 记录一批 blob 处理完成并推送进度
+    pub(crate) fn advance<'a>(&mut self, blob_paths: impl Iterator<Item = &'a str>, progress: u8) {
+        for path in blob_paths {
+            if let Some(entry) = self.directories.get_mut(&top_level_dir(path)) {
+                entry.0 += 1;
+            }
+            self.processed_blobs += 1;
+        }
+        emit(self.event(IndexStage::Uploading, progress, None));
+    }
+
+    fn event(&self, stage: IndexStage, progress: u8, message: Option<String>) -> IndexProgressEvent {
+        IndexProgressEvent {
+            project_root: self.project_root.clone(),
+            stage,
+            progress,
+            total_blobs: self.total_blobs,
+            processed_blobs: self.processed_blobs,
+            eta_secs: self.eta_secs(stage),
+            directories: self.directories.clone(),
+            message,
+        }
+    }
+
+    /// 按已处理速度线性估算剩余时间
+    fn eta_secs(&self, stage: IndexStage) -> Option<u64> {
+        if stage != IndexStage::Uploading || self.processed_blobs == 0 {
+            return None;
+        }
+        let elapsed = self.started_at.elapsed().as_secs_f64();
+        let remaining = self.total_blobs.saturating_sub(self.processed_blobs) as f64;
+        Some((elapsed / self.processed_blobs as f64 * remaining).ceil() as u64)
+    }
+}
+
+/// blob 路径的顶层目录（根目录文件归入 "."）
+fn top_level_dir(path: &str) -> String {
+    match path.split_once('/') {
+        Some((dir, _)) => dir.to_string(),
+        None => ".".to_string(),
+    }
+}
+
+fn emit(event: IndexProgressEvent) {
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = app.emit(INDEX_PROGRESS_EVENT, &event) {
+            log::debug!("推送索引进度事件失败: {}", e);
+        }
+    }
+}
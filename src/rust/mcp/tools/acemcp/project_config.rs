@@ -0,0 +1,93 @@
+// 项目级索引配置覆盖
+// 项目根目录下的 `.sanshu/acemcp.toml`（或 `.sanshu/acemcp.json`）可覆盖全局 mcp_config 中的部分索引参数，
+// 仅对该项目生效；未设置的字段沿用全局配置
+
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use super::types::AcemcpConfig;
+use crate::log_debug;
+
+/// 项目级覆盖配置
+#[derive(Debug, Clone, Default, Deserialize, PartialEq)]
+pub struct ProjectIndexOverrides {
+    /// 要索引的文件扩展名列表
+    pub text_extensions: Option<Vec<String>>,
+    /// 要排除的模式列表
+    pub exclude_patterns: Option<Vec<String>>,
+    /// 每批上传的文件数量
+    pub batch_size: Option<u32>,
+    /// 文件监听防抖延迟（毫秒）
+    pub watch_debounce_ms: Option<u64>,
+}
+
+/// 读取项目覆盖配置，优先 toml，其次 json；不存在或解析失败时返回 None
+pub fn load_project_overrides(project_root: &str) -> Option<ProjectIndexOverrides> {
+    let dir = Path::new(project_root).join(".sanshu");
+
+    let toml_path = dir.join("acemcp.toml");
+    if let Ok(data) = fs::read_to_string(&toml_path) {
+        return match toml::from_str::<ProjectIndexOverrides>(&data) {
+            Ok(overrides) => Some(overrides),
+            Err(e) => {
+                log_debug!("解析项目索引配置失败，忽略: {:?}: {}", toml_path, e);
+                None
+            }
+        };
+    }
+
+    let json_path = dir.join("acemcp.json");
+    if let Ok(data) = fs::read_to_string(&json_path) {
+        return match serde_json::from_str::<ProjectIndexOverrides>(&data) {
+            Ok(overrides) => Some(overrides),
+            Err(e) => {
+                log_debug!("解析项目索引配置失败，忽略: {:?}: {}", json_path, e);
+                None
+            }
+        };
+    }
+
+    None
+}
+
+impl ProjectIndexOverrides {
+    /// 将覆盖项合并到全局配置之上
+    pub fn apply(&self, config: &AcemcpConfig) -> AcemcpConfig {
+        let mut merged = config.clone();
+        if let Some(exts) = &self.text_extensions {
+            merged.text_extensions = Some(exts.clone());
+        }
+        if let Some(patterns) = &self.exclude_patterns {
+            merged.exclude_patterns = Some(patterns.clone());
+        }
+        if let Some(batch_size) = self.batch_size {
+            merged.batch_size = Some(batch_size.max(1));
+        }
+        merged
+    }
+}
+
+/// 返回合并了项目覆盖配置后的索引配置
+pub fn config_for_project(config: &AcemcpConfig, project_root: &str) -> AcemcpConfig {
+    match load_project_overrides(project_root) {
+        Some(overrides) => overrides.apply(config),
+        None => config.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_toml_overrides() {
+        let data = "text_extensions = [\".rs\", \".md\"]\nbatch_size = 20\n";
+        let overrides: ProjectIndexOverrides = toml::from_str(data).unwrap();
+        assert_eq!(overrides.text_extensions, Some(vec![".rs".to_string(), ".md".to_string()]));
+        assert_eq!(overrides.batch_size, Some(20));
+        assert_eq!(overrides.exclude_patterns, None);
+        assert_eq!(overrides.watch_debounce_ms, None);
+    }
+}
@@ -0,0 +1,163 @@
+// 失败文件隔离
+// 个别文件反复上传失败（无法读取/解码、被服务端拒绝）时，不再让它们在每次索引中重复失败，
+// 而是在连续失败达到阈值后隔离：后续索引跳过这些文件，并在 ProjectIndexStatus 中列出原因，
+// 用户修复后可通过命令解除隔离并重试
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Utc};
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+
+use super::types::QuarantinedFile;
+
+/// 连续失败多少次后隔离
+pub const QUARANTINE_AFTER_FAILURES: u32 = 3;
+
+/// 每个文件最多保留的失败原因数
+const MAX_REASONS: usize = 5;
+
+/// 单个文件的失败记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct FailureRecord {
+    /// 连续失败次数（成功一次即清零）
+    attempts: u32,
+    /// 最近的失败原因（最新的在后，已去重）
+    reasons: Vec<String>,
+    last_failed_at: DateTime<Utc>,
+    quarantined: bool,
+}
+
+/// 项目的失败文件台账
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub(crate) struct FailureLedger {
+    files: BTreeMap<String, FailureRecord>,
+}
+
+fn ledger_file(normalized_root: &str) -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    let dir = home.join(".acemcp").join("data").join("quarantine");
+    let _ = fs::create_dir_all(&dir);
+    let hash = hex::encode(digest(&SHA256, normalized_root.as_bytes()).as_ref());
+    dir.join(format!("{}.json", &hash[..16]))
+}
+
+impl FailureLedger {
+    /// 读取项目台账，不存在或损坏时返回空台账
+    pub(crate) fn load(normalized_root: &str) -> Self {
+        super::cache_crypto::read_to_string(ledger_file(normalized_root))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn save(&self, normalized_root: &str) -> anyhow::Result<()> {
+        let path = ledger_file(normalized_root);
+        if self.files.is_empty() {
+            let _ = fs::remove_file(path);
+            return Ok(());
+        }
+        super::cache_crypto::write(path, serde_json::to_string(self)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn remove(normalized_root: &str) {
+        let _ = fs::remove_file(ledger_file(normalized_root));
+    }
+
+    /// 记录一次失败，返回该文件是否在本次被隔离
+    pub(crate) fn record_failure(&mut self, path: &str, reason: &str, now: DateTime<Utc>) -> bool {
+        let record = self.files.entry(path.to_string()).or_insert_with(|| FailureRecord {
+            attempts: 0,
+            reasons: Vec::new(),
+            last_failed_at: now,
+            quarantined: false,
+        });
+        record.attempts += 1;
+        record.last_failed_at = now;
+        record.reasons.retain(|r| r != reason);
+        record.reasons.push(reason.to_string());
+        if record.reasons.len() > MAX_REASONS {
+            let overflow = record.reasons.len() - MAX_REASONS;
+            record.reasons.drain(..overflow);
+        }
+
+        let newly_quarantined = !record.quarantined && record.attempts >= QUARANTINE_AFTER_FAILURES;
+        record.quarantined |= newly_quarantined;
+        newly_quarantined
+    }
+
+    /// 文件成功索引后清除失败记录
+    pub(crate) fn record_success(&mut self, path: &str) {
+        self.files.remove(path);
+    }
+
+    /// 当前被隔离的文件路径
+    pub(crate) fn quarantined_paths(&self) -> HashSet<String> {
+        self.files
+            .iter()
+            .filter(|(_, record)| record.quarantined)
+            .map(|(path, _)| path.clone())
+            .collect()
+    }
+
+    /// 隔离文件列表（用于项目状态展示）
+    pub(crate) fn quarantined_files(&self) -> Vec<QuarantinedFile> {
+        self.files
+            .iter()
+            .filter(|(_, record)| record.quarantined)
+            .map(|(path, record)| QuarantinedFile {
+                path: path.clone(),
+                attempts: record.attempts,
+                reasons: record.reasons.clone(),
+                last_failed_at: Some(record.last_failed_at),
+            })
+            .collect()
+    }
+
+    /// 解除隔离（paths 为空时解除全部），返回被解除的文件
+    pub(crate) fn release(&mut self, paths: &[String]) -> Vec<String> {
+        let targets: Vec<String> = self
+            .quarantined_paths()
+            .into_iter()
+            .filter(|path| paths.is_empty() || paths.contains(path))
+            .collect();
+        for path in &targets {
+            self.files.remove(path);
+        }
+        let mut released = targets;
+        released.sort();
+        released
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quarantine_after_repeated_failures() {
+        let mut ledger = FailureLedger::default();
+        let now = Utc::now();
+        assert!(!ledger.record_failure("a.rs", "HTTP 400", now));
+        assert!(!ledger.record_failure("a.rs", "HTTP 400", now));
+        assert!(ledger.record_failure("a.rs", "HTTP 413", now));
+        // 已隔离的文件不会重复报告
+        assert!(!ledger.record_failure("a.rs", "HTTP 413", now));
+
+        let files = ledger.quarantined_files();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].attempts, 4);
+        assert_eq!(files[0].reasons, vec!["HTTP 400", "HTTP 413"]);
+
+        // 成功一次即清零
+        ledger.record_failure("b.rs", "unreadable", now);
+        ledger.record_success("b.rs");
+        assert!(!ledger.files.contains_key("b.rs"));
+
+        assert_eq!(ledger.release(&[]), vec!["a.rs".to_string()]);
+        assert!(ledger.quarantined_paths().is_empty());
+    }
+}
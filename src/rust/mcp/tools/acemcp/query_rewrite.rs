@@ -0,0 +1,250 @@
+// 检索查询预处理
+// 客户端（AI 助手）传来的查询常夹带代码块标记、缩写或中文描述，直接检索命中率偏低。
+// 可选的改写阶段（默认关闭，由配置控制）：
+// - 去除 Markdown 代码块围栏与行内反引号，保留代码内容
+// - 展开常见缩写（如 auth → authentication），支持用户自定义缩写表
+// - 后端偏好英文时，将查询中的常见中文术语翻译为英文关键词（内置词表，离线完成）
+
+use std::collections::HashMap;
+
+use super::types::AcemcpConfig;
+
+/// 内置缩写表（小写匹配，按单词替换为「缩写 全称」，保留原词以兼顾精确匹配）
+const ABBREVIATIONS: &[(&str, &str)] = &[
+    ("auth", "authentication"),
+    ("authz", "authorization"),
+    ("cfg", "config"),
+    ("conf", "configuration"),
+    ("db", "database"),
+    ("repo", "repository"),
+    ("ctx", "context"),
+    ("req", "request"),
+    ("resp", "response"),
+    ("msg", "message"),
+    ("err", "error"),
+    ("env", "environment"),
+    ("impl", "implementation"),
+    ("fn", "function"),
+    ("func", "function"),
+    ("init", "initialize"),
+    ("util", "utility"),
+    ("utils", "utilities"),
+    ("args", "arguments"),
+    ("params", "parameters"),
+    ("deps", "dependencies"),
+    ("perf", "performance"),
+    ("ws", "websocket"),
+    ("ui", "user interface"),
+];
+
+/// 内置中英术语表（按词条长度优先匹配）
+const ZH_EN_TERMS: &[(&str, &str)] = &[
+    ("登录", "login"),
+    ("登出", "logout"),
+    ("注册", "register"),
+    ("认证", "authentication"),
+    ("鉴权", "authorization"),
+    ("权限", "permission"),
+    ("用户", "user"),
+    ("配置", "config"),
+    ("设置", "settings"),
+    ("数据库", "database"),
+    ("缓存", "cache"),
+    ("索引", "index"),
+    ("搜索", "search"),
+    ("检索", "retrieval"),
+    ("上传", "upload"),
+    ("下载", "download"),
+    ("请求", "request"),
+    ("响应", "response"),
+    ("接口", "api"),
+    ("路由", "route"),
+    ("中间件", "middleware"),
+    ("错误处理", "error handling"),
+    ("错误", "error"),
+    ("异常", "exception"),
+    ("日志", "log"),
+    ("测试", "test"),
+    ("文件", "file"),
+    ("目录", "directory"),
+    ("路径", "path"),
+    ("监听", "watch"),
+    ("事件", "event"),
+    ("消息", "message"),
+    ("通知", "notification"),
+    ("队列", "queue"),
+    ("任务", "task"),
+    ("定时", "schedule"),
+    ("并发", "concurrency"),
+    ("线程", "thread"),
+    ("异步", "async"),
+    ("网络", "network"),
+    ("代理", "proxy"),
+    ("超时", "timeout"),
+    ("重试", "retry"),
+    ("解析", "parse"),
+    ("序列化", "serialize"),
+    ("加密", "encrypt"),
+    ("解密", "decrypt"),
+    ("签名", "signature"),
+    ("令牌", "token"),
+    ("会话", "session"),
+    ("组件", "component"),
+    ("页面", "page"),
+    ("按钮", "button"),
+    ("弹窗", "popup"),
+    ("窗口", "window"),
+    ("主题", "theme"),
+    ("状态", "state"),
+    ("初始化", "initialize"),
+    ("启动", "startup"),
+    ("关闭", "shutdown"),
+    ("更新", "update"),
+    ("删除", "delete"),
+    ("创建", "create"),
+    ("查询", "query"),
+    ("函数", "function"),
+    ("方法", "method"),
+    ("结构体", "struct"),
+    ("类型", "type"),
+    ("模块", "module"),
+    ("命令", "command"),
+    ("工具", "tool"),
+    ("插件", "plugin"),
+];
+
+/// 查询改写选项
+#[derive(Debug, Clone, Default)]
+pub(crate) struct QueryRewriteOptions {
+    pub enabled: bool,
+    /// 后端偏好英文查询时翻译中文术语
+    pub prefer_english: bool,
+    /// 用户自定义缩写（优先于内置表）
+    pub custom_abbreviations: HashMap<String, String>,
+}
+
+impl QueryRewriteOptions {
+    pub(crate) fn from_config(config: &AcemcpConfig) -> Self {
+        Self {
+            enabled: config.query_rewrite.unwrap_or(false),
+            prefer_english: config.query_prefer_english.unwrap_or(false),
+            custom_abbreviations: config
+                .query_abbreviations
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(k, v)| (k.to_lowercase(), v))
+                .collect(),
+        }
+    }
+}
+
+/// 改写查询；未启用或改写后为空时返回原查询
+pub(crate) fn rewrite_query(query: &str, options: &QueryRewriteOptions) -> String {
+    if !options.enabled {
+        return query.to_string();
+    }
+
+    let mut rewritten = strip_code_fences(query);
+    rewritten = expand_abbreviations(&rewritten, &options.custom_abbreviations);
+    if options.prefer_english {
+        rewritten = append_english_terms(&rewritten);
+    }
+
+    let rewritten = rewritten.split_whitespace().collect::<Vec<_>>().join(" ");
+    if rewritten.is_empty() { query.to_string() } else { rewritten }
+}
+
+/// 去除代码块围栏行（```lang）与行内反引号
+fn strip_code_fences(query: &str) -> String {
+    query
+        .lines()
+        .filter(|line| !line.trim_start().starts_with("```"))
+        .collect::<Vec<_>>()
+        .join("\n")
+        .replace('`', "")
+}
+
+/// 按单词展开缩写（保留原词）
+fn expand_abbreviations(query: &str, custom: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(query.len());
+    let mut word = String::new();
+
+    let flush = |word: &mut String, out: &mut String| {
+        if word.is_empty() {
+            return;
+        }
+        out.push_str(word);
+        let lower = word.to_lowercase();
+        let expansion = custom
+            .get(&lower)
+            .map(|s| s.as_str())
+            .or_else(|| ABBREVIATIONS.iter().find(|(abbr, _)| *abbr == lower).map(|(_, full)| *full));
+        if let Some(full) = expansion {
+            if !full.eq_ignore_ascii_case(word) {
+                out.push(' ');
+                out.push_str(full);
+            }
+        }
+        word.clear();
+    };
+
+    for c in query.chars() {
+        if c.is_ascii_alphanumeric() || c == '_' {
+            word.push(c);
+        } else {
+            flush(&mut word, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut word, &mut out);
+    out
+}
+
+/// 查询含中文时，追加内置词表中命中的英文关键词
+fn append_english_terms(query: &str) -> String {
+    if !query.chars().any(|c| ('\u{4e00}'..='\u{9fff}').contains(&c)) {
+        return query.to_string();
+    }
+
+    let mut terms: Vec<(&str, &str)> = ZH_EN_TERMS.to_vec();
+    terms.sort_by_key(|(zh, _)| std::cmp::Reverse(zh.chars().count()));
+
+    // 长词优先匹配，已匹配部分替换为空格，避免「错误处理」再命中「错误」
+    let mut remaining = query.to_string();
+    let mut english: Vec<&str> = Vec::new();
+    for (zh, en) in terms {
+        if remaining.contains(zh) {
+            remaining = remaining.replace(zh, " ");
+            if !english.contains(&en) {
+                english.push(en);
+            }
+        }
+    }
+
+    if english.is_empty() {
+        query.to_string()
+    } else {
+        format!("{} {}", query, english.join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_query() {
+        let enabled = QueryRewriteOptions { enabled: true, ..Default::default() };
+        assert_eq!(
+            rewrite_query("```rust\nfn load_cfg()\n```\nwhere is `auth` checked", &enabled),
+            "fn function load_cfg() where is auth authentication checked"
+        );
+
+        let english = QueryRewriteOptions { enabled: true, prefer_english: true, ..Default::default() };
+        assert_eq!(rewrite_query("登录错误处理", &english), "登录错误处理 error handling login");
+
+        let disabled = QueryRewriteOptions::default();
+        assert_eq!(rewrite_query("`auth`", &disabled), "`auth`");
+    }
+}
@@ -0,0 +1,205 @@
+// 定时重新索引
+// 使用 cron 表达式（分 时 日 月 周）配置重新索引窗口，在窗口内对所有正在监听的项目触发索引更新；
+// 开启 schedule_only 后，文件监听只记录变更，不再立即索引，适合不希望白天产生网络流量的用户
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+
+use crate::log_debug;
+use crate::log_important;
+
+/// cron 表达式（5 段：分 时 日 月 周）
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronExpr {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    /// 日字段是否为 *（用于日/周的“或”语义）
+    any_day: bool,
+    /// 周字段是否为 *
+    any_weekday: bool,
+}
+
+impl CronExpr {
+    /// 解析 cron 表达式，支持 `*`、数字、范围 `a-b`、列表 `a,b` 与步长 `*/n`、`a-b/n`
+    pub fn parse(expr: &str) -> anyhow::Result<Self> {
+        let fields: Vec<&str> = expr.split_whitespace().collect();
+        if fields.len() != 5 {
+            anyhow::bail!("cron 表达式需要 5 段（分 时 日 月 周）: {}", expr);
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7)?;
+        // 7 与 0 都表示周日
+        if weekdays[7] {
+            weekdays[0] = true;
+        }
+        weekdays.truncate(7);
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            weekdays,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        })
+    }
+
+    /// 指定时间（精确到分钟）是否命中
+    pub fn matches(&self, time: &DateTime<Local>) -> bool {
+        if !self.minutes[time.minute() as usize]
+            || !self.hours[time.hour() as usize]
+            || !self.months[time.month() as usize]
+        {
+            return false;
+        }
+
+        let day_ok = self.days[time.day() as usize];
+        let weekday_ok = self.weekdays[time.weekday().num_days_from_sunday() as usize];
+        // 与标准 cron 一致：日和周都受限时满足其一即可
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (true, false) => weekday_ok,
+            (false, true) => day_ok,
+            (false, false) => day_ok || weekday_ok,
+        }
+    }
+}
+
+/// 解析单个字段，返回下标 0..=max 的命中表
+fn parse_field(field: &str, min: u32, max: u32) -> anyhow::Result<Vec<bool>> {
+    let mut hits = vec![false; max as usize + 1];
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step.parse().map_err(|_| anyhow::anyhow!("无效的步长: {}", part))?;
+                if step == 0 {
+                    anyhow::bail!("步长不能为 0: {}", part);
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            let a: u32 = a.parse().map_err(|_| anyhow::anyhow!("无效的范围: {}", part))?;
+            let b: u32 = b.parse().map_err(|_| anyhow::anyhow!("无效的范围: {}", part))?;
+            (a, b)
+        } else {
+            let v: u32 = range.parse().map_err(|_| anyhow::anyhow!("无效的值: {}", part))?;
+            // 单个值带步长（如 5/15）表示从该值开始到最大值
+            if step > 1 { (v, max) } else { (v, v) }
+        };
+
+        if start < min || end > max || start > end {
+            anyhow::bail!("取值超出范围 {}-{}: {}", min, max, part);
+        }
+
+        let mut v = start;
+        while v <= end {
+            hits[v as usize] = true;
+            v += step;
+        }
+    }
+
+    Ok(hits)
+}
+
+/// 校验一组 cron 表达式
+pub fn validate_schedules(schedules: &[String]) -> anyhow::Result<()> {
+    for expr in schedules {
+        CronExpr::parse(expr)?;
+    }
+    Ok(())
+}
+
+static SCHEDULER_STARTED: AtomicBool = AtomicBool::new(false);
+
+/// 启动定时重新索引任务（进程内只启动一次）
+/// 每分钟检查一次配置中的 cron 表达式，命中时对所有正在监听的项目触发索引更新
+pub fn ensure_reindex_scheduler() {
+    if SCHEDULER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    tokio::spawn(async move {
+        log_important!(info, "定时重新索引任务已启动");
+        loop {
+            // 对齐到下一分钟的开始
+            let now = Local::now();
+            let wait_secs = 60 - now.second() as u64;
+            tokio::time::sleep(std::time::Duration::from_secs(wait_secs)).await;
+
+            let config = match super::AcemcpTool::get_acemcp_config().await {
+                Ok(c) => c,
+                Err(e) => {
+                    log_debug!("定时索引读取配置失败: {}", e);
+                    continue;
+                }
+            };
+            let schedules = config.reindex_schedules.clone().unwrap_or_default();
+            if schedules.is_empty() {
+                continue;
+            }
+
+            let now = Local::now();
+            let hit = schedules.iter().any(|expr| match CronExpr::parse(expr) {
+                Ok(cron) => cron.matches(&now),
+                Err(e) => {
+                    log_debug!("忽略无效的 cron 表达式: {}", e);
+                    false
+                }
+            });
+            if !hit {
+                continue;
+            }
+
+            let projects = super::watcher::get_watcher_manager().get_watching_projects();
+            log_important!(info, "进入定时索引窗口，触发 {} 个监听项目的索引更新", projects.len());
+            for project in projects {
+                if super::control::get_index_gate(&project).is_paused() {
+                    log_debug!("项目索引已暂停，跳过定时索引: {}", project);
+                    continue;
+                }
+                super::search_cache::invalidate_project(&project);
+                match super::AcemcpTool::trigger_index_update(project.clone()).await {
+                    Ok(msg) => log_important!(info, "定时索引完成: project={}, {}", project, msg),
+                    Err(e) => log_important!(info, "定时索引失败: project={}, error={}", project, e),
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_cron_matches_nightly_window() {
+        let cron = CronExpr::parse("*/30 1-3 * * 1-5").unwrap();
+        // 2024-01-03 是周三
+        let hit = Local.with_ymd_and_hms(2024, 1, 3, 2, 30, 0).unwrap();
+        let wrong_minute = Local.with_ymd_and_hms(2024, 1, 3, 2, 15, 0).unwrap();
+        let weekend = Local.with_ymd_and_hms(2024, 1, 6, 2, 0, 0).unwrap();
+        assert!(cron.matches(&hit));
+        assert!(!cron.matches(&wrong_minute));
+        assert!(!cron.matches(&weekend));
+    }
+
+    #[test]
+    fn test_cron_rejects_invalid() {
+        assert!(CronExpr::parse("0 2 * *").is_err());
+        assert!(CronExpr::parse("60 2 * * *").is_err());
+        assert!(CronExpr::parse("*/0 2 * * *").is_err());
+        assert!(CronExpr::parse("0 2 * * 7").is_ok());
+    }
+}
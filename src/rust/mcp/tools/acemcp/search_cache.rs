@@ -0,0 +1,76 @@
+// 检索结果缓存
+// 以（项目, 查询, 索引检查点）为键缓存检索结果，避免 agent 循环中重复查询消耗 API 配额
+// 索引检查点变化（重新索引完成）或文件监听触发重新索引时缓存自动失效
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+/// 默认缓存有效期（秒）
+pub const DEFAULT_SEARCH_CACHE_TTL_SECS: u64 = 300;
+
+/// 最多缓存的条目数
+const MAX_ENTRIES: usize = 256;
+
+struct CacheEntry {
+    checkpoint: String,
+    result: String,
+    created_at: Instant,
+}
+
+/// (规范化项目路径, 查询) -> 缓存条目
+static SEARCH_CACHE: Lazy<Mutex<HashMap<(String, String), CacheEntry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn cache_key(project_root: &str, query: &str) -> (String, String) {
+    (project_root.to_string(), query.trim().to_string())
+}
+
+/// 读取缓存：检查点一致且未过期时命中
+pub fn get_cached(project_root: &str, query: &str, checkpoint: &str, ttl: Duration) -> Option<String> {
+    let mut cache = SEARCH_CACHE.lock().ok()?;
+    let key = cache_key(project_root, query);
+    let entry = cache.get(&key)?;
+    if entry.checkpoint == checkpoint && entry.created_at.elapsed() < ttl {
+        return Some(entry.result.clone());
+    }
+    cache.remove(&key);
+    None
+}
+
+/// 写入缓存（超过容量时淘汰最旧的条目）
+pub fn put_cached(project_root: &str, query: &str, checkpoint: &str, result: String) {
+    let Ok(mut cache) = SEARCH_CACHE.lock() else { return };
+    if cache.len() >= MAX_ENTRIES {
+        if let Some(oldest) = cache
+            .iter()
+            .min_by_key(|(_, entry)| entry.created_at)
+            .map(|(key, _)| key.clone())
+        {
+            cache.remove(&oldest);
+        }
+    }
+    cache.insert(
+        cache_key(project_root, query),
+        CacheEntry {
+            checkpoint: checkpoint.to_string(),
+            result,
+            created_at: Instant::now(),
+        },
+    );
+}
+
+/// 使指定项目的所有缓存失效
+pub fn invalidate_project(project_root: &str) {
+    if let Ok(mut cache) = SEARCH_CACHE.lock() {
+        cache.retain(|(root, _), _| root != project_root);
+    }
+}
+
+/// 清空全部缓存
+pub fn clear_all() {
+    if let Ok(mut cache) = SEARCH_CACHE.lock() {
+        cache.clear();
+    }
+}
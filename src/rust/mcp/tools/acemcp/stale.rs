@@ -0,0 +1,52 @@
+// 失效项目检测
+// 项目目录从磁盘删除后，其记录会一直残留在 projects.json / projects_status.json 中。
+// 启动时（以及前端请求时）检查所有已记录项目的根目录是否存在，不存在的标记为 stale，
+// 再由用户通过批量删除命令清理（复用 remove_acemcp_project_index 的删除逻辑）
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use super::mcp::{home_projects_file, load_projects_status, save_projects_status, ProjectsFile};
+use super::types::ProjectIndexStatus;
+
+/// 检测失效项目并同步 stale 标记，返回根目录已不存在的项目（已排序）
+pub(crate) fn reconcile_stale_projects() -> anyhow::Result<Vec<String>> {
+    let mut all_status = load_projects_status();
+
+    // projects.json 中可能存在没有状态记录的项目
+    let mut roots: BTreeSet<String> = all_status.projects.keys().cloned().collect();
+    if let Ok(data) = super::cache_crypto::read_to_string(home_projects_file()) {
+        if let Ok(projects) = serde_json::from_str::<ProjectsFile>(&data) {
+            roots.extend(projects.0.into_keys());
+        }
+    }
+
+    let stale: Vec<String> = roots.into_iter().filter(|root| !Path::new(root).exists()).collect();
+
+    let mut changed = false;
+    for (root, status) in all_status.projects.iter_mut() {
+        let is_stale = stale.contains(root);
+        if status.stale != is_stale {
+            status.stale = is_stale;
+            changed = true;
+        }
+    }
+    for root in &stale {
+        if !all_status.projects.contains_key(root) {
+            all_status.projects.insert(
+                root.clone(),
+                ProjectIndexStatus {
+                    project_root: root.clone(),
+                    stale: true,
+                    ..Default::default()
+                },
+            );
+            changed = true;
+        }
+    }
+
+    if changed {
+        save_projects_status(&all_status)?;
+    }
+    Ok(stale)
+}
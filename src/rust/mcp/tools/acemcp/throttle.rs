@@ -0,0 +1,125 @@
+// 后台索引限流
+// 避免后台索引（尤其是文件监听触发的重新索引）占满带宽和 CPU，影响同一台机器上的交互操作：
+// - 上传带宽：按字节数排队，全局限制每秒上传量
+// - 文件读取：全局限制同时读取/分块的文件数
+// - CPU 让出（niceness 0-19）：每处理完一个文件按耗时比例休眠，数值越大让出越多
+
+use std::sync::{Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+
+use super::types::AcemcpConfig;
+
+/// 默认同时读取的文件数
+pub const DEFAULT_MAX_CONCURRENT_READS: usize = 4;
+
+/// 单个文件处理后休眠的上限，避免个别大文件导致长时间停顿
+const MAX_YIELD: Duration = Duration::from_millis(500);
+
+/// 索引限流参数
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct IndexThrottle {
+    /// 上传带宽上限（字节/秒），None 表示不限制
+    pub upload_bytes_per_sec: Option<u64>,
+    /// 同时读取的文件数上限
+    pub max_concurrent_reads: usize,
+    /// CPU 让出程度（0 表示不让出，19 最大）
+    pub niceness: u8,
+}
+
+impl IndexThrottle {
+    pub(crate) fn from_config(config: &AcemcpConfig) -> Self {
+        Self {
+            upload_bytes_per_sec: config
+                .max_upload_mbps
+                .filter(|mbps| *mbps > 0.0)
+                .map(|mbps| (mbps * 1024.0 * 1024.0) as u64)
+                .filter(|bytes| *bytes > 0),
+            max_concurrent_reads: config
+                .max_concurrent_reads
+                .map(|n| n.max(1) as usize)
+                .unwrap_or(DEFAULT_MAX_CONCURRENT_READS),
+            niceness: config.index_niceness.unwrap_or(0).min(19),
+        }
+    }
+
+    /// 读取一个文件前获取读取许可（阻塞等待，需在阻塞线程中调用）
+    pub(crate) fn acquire_read(&self) -> ReadPermit {
+        let (lock, cvar) = &*READ_SLOTS;
+        let mut in_use = lock.lock().unwrap_or_else(|e| e.into_inner());
+        while *in_use >= self.max_concurrent_reads {
+            in_use = cvar.wait(in_use).unwrap_or_else(|e| e.into_inner());
+        }
+        *in_use += 1;
+        ReadPermit
+    }
+
+    /// 处理完一个文件后按 niceness 让出 CPU（阻塞休眠，需在阻塞线程中调用）
+    pub(crate) fn yield_after(&self, busy: Duration) {
+        let pause = yield_duration(busy, self.niceness);
+        if !pause.is_zero() {
+            std::thread::sleep(pause);
+        }
+    }
+
+    /// 上传前按带宽上限排队等待
+    pub(crate) async fn pace_upload(&self, bytes: usize) {
+        let Some(rate) = self.upload_bytes_per_sec else { return };
+        let cost = Duration::from_secs_f64(bytes as f64 / rate as f64);
+
+        let start = {
+            let mut next_free = UPLOAD_NEXT_FREE.lock().await;
+            let now = Instant::now();
+            let start = (*next_free).max(now);
+            *next_free = start + cost;
+            start
+        };
+
+        let wait = start.saturating_duration_since(Instant::now());
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+/// 当前正在读取的文件数
+static READ_SLOTS: Lazy<(Mutex<usize>, Condvar)> = Lazy::new(|| (Mutex::new(0), Condvar::new()));
+
+/// 上传通道下一次空闲的时间点
+static UPLOAD_NEXT_FREE: Lazy<tokio::sync::Mutex<Instant>> = Lazy::new(|| tokio::sync::Mutex::new(Instant::now()));
+
+/// 文件读取许可，离开作用域时释放
+pub(crate) struct ReadPermit;
+
+impl Drop for ReadPermit {
+    fn drop(&mut self) {
+        let (lock, cvar) = &*READ_SLOTS;
+        let mut in_use = lock.lock().unwrap_or_else(|e| e.into_inner());
+        *in_use = in_use.saturating_sub(1);
+        cvar.notify_one();
+    }
+}
+
+/// 让出时长：使工作时间占比约为 (20 - niceness) / 20
+fn yield_duration(busy: Duration, niceness: u8) -> Duration {
+    if niceness == 0 {
+        return Duration::ZERO;
+    }
+    let niceness = niceness.min(19) as u32;
+    (busy * niceness / (20 - niceness)).min(MAX_YIELD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yield_duration() {
+        let busy = Duration::from_millis(10);
+        assert_eq!(yield_duration(busy, 0), Duration::ZERO);
+        assert_eq!(yield_duration(busy, 10), Duration::from_millis(10));
+        assert_eq!(yield_duration(busy, 19), Duration::from_millis(190));
+        assert_eq!(yield_duration(Duration::from_secs(1), 19), MAX_YIELD);
+    }
+}
@@ -9,6 +9,31 @@ pub struct AcemcpRequest {
     pub project_root_path: String,
     /// 用于查找相关代码上下文的自然语言搜索查询
     pub query: String,
+    /// 只保留路径匹配这些 glob 的结果（如 `src/**`）
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+    /// 排除路径匹配这些 glob 的结果（如 `**/tests/**`）
+    #[serde(default)]
+    pub exclude_paths: Vec<String>,
+}
+
+/// 多项目联合检索请求
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcemcpMultiRequest {
+    /// 要联合检索的项目根目录列表
+    #[serde(default)]
+    pub project_root_paths: Vec<String>,
+    /// 配置中的工作区分组名（与 project_root_paths 合并去重）
+    #[serde(default)]
+    pub workspace_group: Option<String>,
+    /// 用于查找相关代码上下文的自然语言搜索查询
+    pub query: String,
+    /// 只保留路径匹配这些 glob 的结果（相对各项目根目录）
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+    /// 排除路径匹配这些 glob 的结果
+    #[serde(default)]
+    pub exclude_paths: Vec<String>,
 }
 
 /// Acemcp配置
@@ -43,6 +68,87 @@ pub struct AcemcpConfig {
     pub proxy_username: Option<String>,
     /// 代理密码（可选）
     pub proxy_password: Option<String>,
+    /// 索引后端: "remote"（默认，远程 codebase-retrieval）| "local"（本地 BM25 离线索引）
+    pub backend: Option<String>,
+    /// 并发上传的批次数（默认 4）
+    pub upload_concurrency: Option<u32>,
+    /// 工作区分组：分组名 -> 项目根目录列表（用于多项目联合检索）
+    pub workspace_groups: Option<HashMap<String, Vec<String>>>,
+    /// 检索结果缓存有效期（秒），默认 300，设为 0 禁用缓存
+    pub search_cache_ttl_secs: Option<u64>,
+    /// 单文件大小上限（KB），默认 1024，设为 0 不限制
+    pub max_file_size_kb: Option<u64>,
+    /// 是否通过内容嗅探跳过二进制文件（默认 true）
+    pub skip_binary_files: Option<bool>,
+    /// 上传压缩: "off"（默认）| "gzip" | "auto"（不被接受时自动回退）
+    pub upload_compression: Option<String>,
+    /// 上传带宽上限（MB/s），未设置或 0 表示不限制
+    pub max_upload_mbps: Option<f64>,
+    /// 同时读取的文件数上限（默认 4）
+    pub max_concurrent_reads: Option<u32>,
+    /// 索引时的 CPU 让出程度（0-19，默认 0 不让出）
+    pub index_niceness: Option<u8>,
+    /// 定时重新索引窗口（cron 表达式：分 时 日 月 周）
+    pub reindex_schedules: Option<Vec<String>>,
+    /// 是否仅在定时窗口内索引（文件监听不再立即触发索引）
+    pub schedule_only: Option<bool>,
+    /// 文件监听目录数上限（超过后降级监听）
+    pub watch_max_dirs: Option<usize>,
+    /// 文件监听文件数上限（超过后仅监听项目根目录）
+    pub watch_max_files: Option<usize>,
+    /// 检索前是否改写查询（去除代码块围栏、展开缩写）
+    pub query_rewrite: Option<bool>,
+    /// 后端偏好英文时，为查询中的中文术语追加英文关键词
+    pub query_prefer_english: Option<bool>,
+    /// 自定义缩写表（缩写 -> 全称）
+    pub query_abbreviations: Option<HashMap<String, String>>,
+    /// 上传前密钥扫描: "redact"（默认，脱敏后上传）| "skip"（跳过文件）| "off"
+    pub secret_scan: Option<String>,
+    /// 后端健康检查间隔（秒），0 表示关闭
+    pub health_check_interval_secs: Option<u64>,
+    /// 是否加密本地缓存（~/.acemcp/data）
+    pub cache_encryption: Option<bool>,
+    /// 额外的远程索引端点（多租户）
+    pub endpoints: Option<Vec<crate::config::AcemcpEndpoint>>,
+    /// 项目路径（规范化后）-> 端点名称
+    pub project_endpoints: Option<HashMap<String, String>>,
+}
+
+impl AcemcpConfig {
+    /// 项目分配的端点名称（未分配时返回 None，使用默认端点）
+    pub fn endpoint_name_for(&self, normalized_root: &str) -> Option<&str> {
+        self.project_endpoints.as_ref()?.get(normalized_root).map(|s| s.as_str())
+    }
+
+    /// 解析项目实际使用的配置：若项目分配了端点，用端点的 base_url/token/代理覆盖默认值
+    pub fn for_endpoint(&self, normalized_root: &str) -> anyhow::Result<AcemcpConfig> {
+        let Some(name) = self.endpoint_name_for(normalized_root) else {
+            return Ok(self.clone());
+        };
+        let endpoint = self
+            .endpoints
+            .as_ref()
+            .and_then(|eps| eps.iter().find(|ep| ep.name == name))
+            .ok_or_else(|| anyhow::anyhow!("项目分配的索引端点不存在: {}", name))?;
+
+        let mut resolved = self.clone();
+        resolved.base_url = Some(endpoint.base_url.clone());
+        resolved.token = Some(endpoint.token.clone());
+        if endpoint.proxy_enabled.is_some() {
+            resolved.proxy_enabled = endpoint.proxy_enabled;
+            resolved.proxy_host = endpoint.proxy_host.clone();
+            resolved.proxy_port = endpoint.proxy_port;
+            resolved.proxy_type = endpoint.proxy_type.clone();
+            resolved.proxy_username = endpoint.proxy_username.clone();
+            resolved.proxy_password = endpoint.proxy_password.clone();
+        }
+        Ok(resolved)
+    }
+
+    /// 是否使用本地离线索引后端
+    pub fn is_local_backend(&self) -> bool {
+        self.backend.as_deref().map(|b| b.eq_ignore_ascii_case("local")).unwrap_or(false)
+    }
 }
 
 
@@ -58,6 +164,8 @@ pub enum IndexStatus {
     Synced,
     /// 索引失败
     Failed,
+    /// 索引已暂停（可从最后完成的批次恢复）
+    Paused,
 }
 
 /// 项目索引状态信息
@@ -83,8 +191,29 @@ pub struct ProjectIndexStatus {
     pub last_failure_time: Option<DateTime<Utc>>,
     /// 最后错误信息
     pub last_error: Option<String>,
+    /// 最近一次索引中上传失败的文件（相对路径）
+    #[serde(default)]
+    pub failed_paths: Vec<String>,
     /// 按目录聚合的统计信息（目录路径 -> (已索引, 待处理)）
     pub directory_stats: HashMap<String, (usize, usize)>,
+    /// 项目根目录已不存在（由启动时的失效项目检测标记）
+    #[serde(default)]
+    pub stale: bool,
+    /// 反复上传失败而被隔离的文件（后续索引跳过，需手动重试）
+    #[serde(default)]
+    pub quarantined: Vec<QuarantinedFile>,
+}
+
+/// 被隔离的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuarantinedFile {
+    /// 相对项目根目录的路径
+    pub path: String,
+    /// 连续失败次数
+    pub attempts: u32,
+    /// 最近的失败原因
+    pub reasons: Vec<String>,
+    pub last_failed_at: Option<DateTime<Utc>>,
 }
 
 impl Default for ProjectIndexStatus {
@@ -100,7 +229,10 @@ impl Default for ProjectIndexStatus {
             last_success_time: None,
             last_failure_time: None,
             last_error: None,
+            failed_paths: Vec::new(),
             directory_stats: HashMap::new(),
+            stale: false,
+            quarantined: Vec::new(),
         }
     }
 }
@@ -153,6 +285,9 @@ pub struct DetectedProxy {
     pub proxy_type: String,
     /// 响应时间（毫秒），用于排序
     pub response_time_ms: Option<u64>,
+    /// 来源: "scan"（本地端口扫描）| "env:<变量名>" | "system" | "pac"
+    #[serde(default)]
+    pub source: String,
 }
 
 /// 代理测速结果
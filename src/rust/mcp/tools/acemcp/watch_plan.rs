@@ -0,0 +1,262 @@
+// 文件监听规划
+// 对超大仓库直接递归监听根目录会耗尽文件描述符 / inotify 监听数。监听前先扫描目录树：
+// - 被 .gitignore 或排除模式忽略、且体量较大的目录（如 node_modules、target）不监听
+// - 含有这类目录的父目录改为非递归监听，其余子树仍递归监听
+// - 带不索引标记（.acemcpignore / .noindex）的目录同样不监听
+// - 目录数或文件数超过上限时降级：只监听部分目录，并在监听状态中标记原因
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use globset::GlobSet;
+use ignore::gitignore::Gitignore;
+use notify_debouncer_full::notify::RecursiveMode;
+use serde::Serialize;
+
+use super::file_filter::has_opt_out_marker;
+use super::mcp::{build_exclude_globset, build_gitignore, should_exclude};
+
+/// 默认最多监听的目录数
+pub const DEFAULT_WATCH_MAX_DIRS: usize = 8192;
+/// 默认最多监听的文件数
+pub const DEFAULT_WATCH_MAX_FILES: usize = 200_000;
+/// 被忽略目录的子目录数达到该值时视为“重型目录”，不再监听
+const HEAVY_IGNORED_DIRS: usize = 32;
+
+/// 监听上限
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WatchLimits {
+    pub max_dirs: usize,
+    pub max_files: usize,
+}
+
+/// 项目监听状态（供前端展示）
+#[derive(Debug, Clone, Serialize)]
+pub struct WatchedProject {
+    /// 项目根路径（规范化后）
+    pub project_root: String,
+    /// 是否处于降级监听
+    pub degraded: bool,
+    /// 降级原因
+    pub degraded_reason: Option<String>,
+    /// 实际监听的目录数（估算）
+    pub watched_dirs: usize,
+    /// 项目中未被忽略的目录总数
+    pub total_dirs: usize,
+    /// 项目中未被忽略的文件总数
+    pub total_files: usize,
+    /// 自动排除的目录（重型忽略目录与带不索引标记的目录，相对路径）
+    pub excluded_dirs: Vec<String>,
+}
+
+/// 监听计划
+pub(crate) struct WatchPlan {
+    pub targets: Vec<(PathBuf, RecursiveMode)>,
+    pub status: WatchedProject,
+}
+
+struct DirNode {
+    path: PathBuf,
+    ignored: bool,
+    /// 目录带有不索引标记
+    opt_out: bool,
+    /// 子树目录数（含自身；被忽略目录最多统计到 HEAVY_IGNORED_DIRS）
+    subtree_dirs: usize,
+    /// 子树中是否包含不监听的目录
+    has_heavy: bool,
+    children: Vec<DirNode>,
+}
+
+impl DirNode {
+    /// 不监听的目录：带不索引标记，或被忽略且体量较大
+    fn is_unwatched(&self) -> bool {
+        self.opt_out || (self.ignored && self.subtree_dirs >= HEAVY_IGNORED_DIRS)
+    }
+}
+
+struct ScanContext<'a> {
+    root: &'a Path,
+    gitignore: Option<Gitignore>,
+    globset: Option<GlobSet>,
+    total_dirs: usize,
+    total_files: usize,
+}
+
+/// 规划项目的监听方式
+pub(crate) fn plan_watch(root: &Path, normalized_root: &str, exclude_patterns: &[String], limits: WatchLimits) -> WatchPlan {
+    let mut ctx = ScanContext {
+        root,
+        gitignore: build_gitignore(root),
+        globset: if exclude_patterns.is_empty() { None } else { build_exclude_globset(exclude_patterns).ok() },
+        total_dirs: 0,
+        total_files: 0,
+    };
+    let tree = scan_dir(&mut ctx, root.to_path_buf(), false);
+
+    let mut status = WatchedProject {
+        project_root: normalized_root.to_string(),
+        degraded: false,
+        degraded_reason: None,
+        watched_dirs: 0,
+        total_dirs: ctx.total_dirs,
+        total_files: ctx.total_files,
+        excluded_dirs: Vec::new(),
+    };
+    let mut targets = Vec::new();
+
+    if ctx.total_files > limits.max_files {
+        // 文件过多：只监听根目录本身，依赖手动/定时索引发现深层变更
+        status.degraded = true;
+        status.degraded_reason = Some(format!(
+            "文件数 {} 超过监听上限 {}，仅监听项目根目录",
+            ctx.total_files, limits.max_files
+        ));
+        status.watched_dirs = 1;
+        targets.push((root.to_path_buf(), RecursiveMode::NonRecursive));
+        return WatchPlan { targets, status };
+    }
+
+    plan_node(&tree, root, &mut targets, &mut status);
+
+    if status.watched_dirs > limits.max_dirs {
+        // 目录过多：按广度优先只监听前 max_dirs 个未忽略目录（非递归）
+        targets = breadth_first_dirs(&tree, limits.max_dirs)
+            .into_iter()
+            .map(|p| (p, RecursiveMode::NonRecursive))
+            .collect();
+        status.degraded = true;
+        status.degraded_reason = Some(format!(
+            "目录数 {} 超过监听上限 {}，仅监听较浅的 {} 个目录",
+            status.watched_dirs, limits.max_dirs, targets.len()
+        ));
+        status.watched_dirs = targets.len();
+    }
+
+    WatchPlan { targets, status }
+}
+
+fn scan_dir(ctx: &mut ScanContext, path: PathBuf, ignored: bool) -> DirNode {
+    if ignored {
+        let subtree_dirs = count_dirs_capped(&path, HEAVY_IGNORED_DIRS);
+        return DirNode { path, ignored, opt_out: false, subtree_dirs, has_heavy: false, children: Vec::new() };
+    }
+    if path != ctx.root && has_opt_out_marker(&path) {
+        return DirNode { path, ignored: true, opt_out: true, subtree_dirs: 1, has_heavy: false, children: Vec::new() };
+    }
+
+    ctx.total_dirs += 1;
+    let mut children = Vec::new();
+    if let Ok(entries) = fs::read_dir(&path) {
+        for entry in entries.flatten() {
+            let p = entry.path();
+            let is_dir = entry.file_type().map(|t| t.is_dir()).unwrap_or(false);
+            if !is_dir {
+                ctx.total_files += 1;
+                continue;
+            }
+            let child_ignored = ctx
+                .gitignore
+                .as_ref()
+                .map(|gi| gi.matched_path_or_any_parents(&p, true).is_ignore())
+                .unwrap_or(false)
+                || should_exclude(&p, ctx.root, ctx.globset.as_ref());
+            children.push(scan_dir(ctx, p, child_ignored));
+        }
+    }
+
+    let subtree_dirs = 1 + children.iter().map(|c| c.subtree_dirs).sum::<usize>();
+    let has_heavy = children.iter().any(|c| c.is_unwatched() || c.has_heavy);
+    DirNode { path, ignored, opt_out: false, subtree_dirs, has_heavy, children }
+}
+
+/// 统计目录数，达到上限即停止（只用于判断是否为重型目录）
+fn count_dirs_capped(path: &Path, cap: usize) -> usize {
+    let mut count = 0;
+    let mut queue = VecDeque::from([path.to_path_buf()]);
+    while let Some(dir) = queue.pop_front() {
+        count += 1;
+        if count >= cap {
+            break;
+        }
+        if let Ok(entries) = fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                    queue.push_back(entry.path());
+                }
+            }
+        }
+    }
+    count
+}
+
+fn plan_node(node: &DirNode, root: &Path, targets: &mut Vec<(PathBuf, RecursiveMode)>, status: &mut WatchedProject) {
+    if !node.has_heavy {
+        targets.push((node.path.clone(), RecursiveMode::Recursive));
+        status.watched_dirs += node.subtree_dirs;
+        return;
+    }
+
+    targets.push((node.path.clone(), RecursiveMode::NonRecursive));
+    status.watched_dirs += 1;
+    for child in &node.children {
+        if child.is_unwatched() {
+            let rel = child.path.strip_prefix(root).unwrap_or(&child.path).to_string_lossy().replace('\\', "/");
+            status.excluded_dirs.push(rel);
+        } else {
+            plan_node(child, root, targets, status);
+        }
+    }
+}
+
+fn breadth_first_dirs(tree: &DirNode, limit: usize) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let mut queue = VecDeque::from([tree]);
+    while let Some(node) = queue.pop_front() {
+        if out.len() >= limit {
+            break;
+        }
+        out.push(node.path.clone());
+        queue.extend(node.children.iter().filter(|c| !c.ignored));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(path: &str, ignored: bool, subtree_dirs: usize, children: Vec<DirNode>) -> DirNode {
+        let has_heavy = children.iter().any(|c| c.is_unwatched() || c.has_heavy);
+        DirNode { path: PathBuf::from(path), ignored, opt_out: false, subtree_dirs, has_heavy, children }
+    }
+
+    #[test]
+    fn test_plan_skips_heavy_ignored_dirs() {
+        let tree = node("/p", false, 1 + 3 + 1 + HEAVY_IGNORED_DIRS, vec![
+            node("/p/src", false, 3, vec![]),
+            node("/p/build", true, 1, vec![]),
+            node("/p/node_modules", true, HEAVY_IGNORED_DIRS, vec![]),
+        ]);
+        let mut status = WatchedProject {
+            project_root: "/p".to_string(),
+            degraded: false,
+            degraded_reason: None,
+            watched_dirs: 0,
+            total_dirs: 0,
+            total_files: 0,
+            excluded_dirs: Vec::new(),
+        };
+        let mut targets = Vec::new();
+        plan_node(&tree, Path::new("/p"), &mut targets, &mut status);
+
+        assert_eq!(targets, vec![
+            (PathBuf::from("/p"), RecursiveMode::NonRecursive),
+            (PathBuf::from("/p/src"), RecursiveMode::Recursive),
+            (PathBuf::from("/p/build"), RecursiveMode::Recursive),
+        ]);
+        assert_eq!(status.excluded_dirs, vec!["node_modules".to_string()]);
+        assert_eq!(status.watched_dirs, 5);
+        assert_eq!(breadth_first_dirs(&tree, 2), vec![PathBuf::from("/p"), PathBuf::from("/p/src")]);
+    }
+}
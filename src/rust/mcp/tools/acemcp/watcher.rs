@@ -1,7 +1,7 @@
 use anyhow::Result;
 use notify_debouncer_full::{
     new_debouncer,
-    notify::{RecommendedWatcher, RecursiveMode, Watcher},
+    notify::{RecommendedWatcher, Watcher},
     DebounceEventResult, Debouncer, FileIdMap,
 };
 use std::collections::HashMap;
@@ -12,6 +12,7 @@ use tokio::sync::mpsc;
 
 use super::types::AcemcpConfig;
 use super::mcp::update_index;
+use super::watch_plan::{plan_watch, WatchLimits, WatchedProject, DEFAULT_WATCH_MAX_DIRS, DEFAULT_WATCH_MAX_FILES};
 use crate::log_important;
 use crate::log_debug;
 
@@ -20,6 +21,8 @@ use crate::log_debug;
 pub struct WatcherManager {
     /// 项目路径 -> 监听器句柄
     watchers: Arc<Mutex<HashMap<String, Debouncer<RecommendedWatcher, FileIdMap>>>>,
+    /// 项目路径 -> 监听状态（是否降级、监听目录数等）
+    statuses: Arc<Mutex<HashMap<String, WatchedProject>>>,
     /// 是否启用自动索引（全局开关）
     auto_index_enabled: Arc<Mutex<bool>>,
 }
@@ -37,6 +40,7 @@ impl WatcherManager {
 
         Self {
             watchers: Arc::new(Mutex::new(HashMap::new())),
+            statuses: Arc::new(Mutex::new(HashMap::new())),
             auto_index_enabled: Arc::new(Mutex::new(enabled_from_config)),
         }
     }
@@ -85,8 +89,10 @@ impl WatcherManager {
         // 创建异步通道用于接收文件变更事件
         let (tx, mut rx) = mpsc::channel::<()>(100);
 
-        // 创建 debouncer（使用配置的防抖延迟，默认 3 分钟）
-        let delay_ms = debounce_ms.unwrap_or(180_000);
+        // 创建 debouncer（项目级配置优先，其次全局配置，默认 3 分钟）
+        let project_debounce_ms = super::project_config::load_project_overrides(&normalized_root)
+            .and_then(|o| o.watch_debounce_ms);
+        let delay_ms = project_debounce_ms.or(debounce_ms).unwrap_or(180_000);
         log_important!(info, "文件监听防抖延迟: {}ms", delay_ms);
         let mut debouncer = new_debouncer(
             Duration::from_millis(delay_ms),
@@ -107,25 +113,72 @@ impl WatcherManager {
             },
         )?;
 
-        // 添加监听路径
-        debouncer
-            .watcher()
-            .watch(&watch_path, RecursiveMode::Recursive)?;
+        // 规划监听路径：跳过体量较大的忽略目录，超过上限时降级监听
+        let limits = WatchLimits {
+            max_dirs: config.watch_max_dirs.unwrap_or(DEFAULT_WATCH_MAX_DIRS).max(1),
+            max_files: config.watch_max_files.unwrap_or(DEFAULT_WATCH_MAX_FILES).max(1),
+        };
+        let exclude_patterns = config.exclude_patterns.clone().unwrap_or_default();
+        let plan = {
+            let watch_path = watch_path.clone();
+            let normalized_root = normalized_root.clone();
+            tokio::task::spawn_blocking(move || plan_watch(&watch_path, &normalized_root, &exclude_patterns, limits)).await?
+        };
+        let mut status = plan.status;
 
-        log_important!(info, "文件监听已启动: {}", normalized_root);
+        // 添加监听路径（单个子目录监听失败不影响其他目录，根目录失败则直接报错）
+        for (path, mode) in &plan.targets {
+            if let Err(e) = debouncer.watcher().watch(path, *mode) {
+                if *path == watch_path {
+                    return Err(e.into());
+                }
+                log_debug!("监听目录失败，已跳过: {:?}, error={}", path, e);
+                status.watched_dirs = status.watched_dirs.saturating_sub(1);
+                if !status.degraded {
+                    status.degraded = true;
+                    status.degraded_reason = Some(format!("部分目录监听失败（可能超出系统监听数限制）: {}", e));
+                }
+            }
+        }
+
+        if !status.excluded_dirs.is_empty() {
+            log_important!(info, "文件监听自动排除 {} 个大型忽略目录: {:?}", status.excluded_dirs.len(), status.excluded_dirs);
+        }
+        if let Some(reason) = &status.degraded_reason {
+            log_important!(info, "文件监听已降级: project_root={}, 原因={}", normalized_root, reason);
+        }
+        log_important!(
+            info,
+            "文件监听已启动: {}, 监听目录={}, 目录总数={}, 文件总数={}",
+            normalized_root, status.watched_dirs, status.total_dirs, status.total_files
+        );
+
+        // 定时重新索引任务针对所有监听中的项目，随首个监听一起启动
+        super::scheduler::ensure_reindex_scheduler();
 
         // 保存 debouncer 到管理器
         {
             let mut watchers = self.watchers.lock().unwrap();
             watchers.insert(normalized_root.clone(), debouncer);
         }
+        self.statuses.lock().unwrap().insert(normalized_root.clone(), status);
 
         // 启动后台任务处理索引更新
         let project_root_clone = normalized_root.clone();
         let config_fallback = config.clone();
+        let gate = super::control::get_index_gate(&normalized_root);
         tokio::spawn(async move {
             while let Some(_) = rx.recv().await {
+                // 项目已暂停：推迟到恢复后再索引（暂停期间的多次变更合并为一次）
+                if gate.is_paused() {
+                    log_debug!("项目索引已暂停，等待恢复后再更新: {}", project_root_clone);
+                    gate.wait_if_paused().await;
+                    while rx.try_recv().is_ok() {}
+                }
+
                 log_important!(info, "触发自动索引更新: project_root={}", project_root_clone);
+                // 文件已变更，旧的检索缓存不再可靠
+                super::search_cache::invalidate_project(&project_root_clone);
                 
                 // 每次触发时读取最新配置，避免“用户修改配置但监听仍沿用旧配置”的情况
                 let latest_config = match super::mcp::AcemcpTool::get_acemcp_config().await {
@@ -136,6 +189,14 @@ impl WatcherManager {
                     }
                 };
 
+                // 仅在定时窗口内索引：监听只负责发现变更，由定时任务统一触发
+                let schedule_only = latest_config.schedule_only.unwrap_or(false)
+                    && latest_config.reindex_schedules.as_ref().map(|s| !s.is_empty()).unwrap_or(false);
+                if schedule_only {
+                    log_debug!("已启用仅定时索引，推迟到下一个索引窗口: {}", project_root_clone);
+                    continue;
+                }
+
                 match update_index(&latest_config, &project_root_clone).await {
                     Ok(blob_names) => {
                         log_important!(info, "自动索引更新成功: project_root={}, blobs={}", project_root_clone, blob_names.len());
@@ -158,6 +219,7 @@ impl WatcherManager {
             .to_string_lossy()
             .replace('\\', "/");
 
+        self.statuses.lock().unwrap().remove(&normalized_root);
         let mut watchers = self.watchers.lock().unwrap();
         if watchers.remove(&normalized_root).is_some() {
             log_important!(info, "已停止文件监听: {}", normalized_root);
@@ -173,6 +235,7 @@ impl WatcherManager {
         let mut watchers = self.watchers.lock().unwrap();
         let count = watchers.len();
         watchers.clear();
+        self.statuses.lock().unwrap().clear();
         log_important!(info, "已停止所有文件监听，共 {} 个项目", count);
     }
 
@@ -182,6 +245,26 @@ impl WatcherManager {
         watchers.keys().cloned().collect()
     }
 
+    /// 获取正在监听的项目及其监听状态（含降级信息）
+    pub fn get_watching_projects_detail(&self) -> Vec<WatchedProject> {
+        let watchers = self.watchers.lock().unwrap();
+        let statuses = self.statuses.lock().unwrap();
+        watchers
+            .keys()
+            .map(|root| {
+                statuses.get(root).cloned().unwrap_or_else(|| WatchedProject {
+                    project_root: root.clone(),
+                    degraded: false,
+                    degraded_reason: None,
+                    watched_dirs: 0,
+                    total_dirs: 0,
+                    total_files: 0,
+                    excluded_dirs: Vec::new(),
+                })
+            })
+            .collect()
+    }
+
     /// 检查指定项目是否正在监听
     pub fn is_watching(&self, project_root: &str) -> bool {
         let normalized_root = PathBuf::from(project_root)
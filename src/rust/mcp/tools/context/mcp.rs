@@ -0,0 +1,248 @@
+use rmcp::model::{ErrorData as McpError, Tool, ToolAnnotations, CallToolResult, Content};
+use serde_json::json;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::types::ContextRequest;
+use crate::mcp::tools::acemcp::types::AcemcpRequest;
+use crate::mcp::tools::docs::budget::fit_to_budget;
+use crate::mcp::tools::docs::dependencies::{detect_dependencies, DetectedDependency};
+use crate::mcp::tools::docs::types::DocsRequest;
+use crate::mcp::tools::{AcemcpTool, DocsTool};
+use crate::log_important;
+
+/// Upper bound for libraries whose docs are fetched in one call
+const MAX_LIBRARIES: usize = 3;
+
+/// Character budget for the code search section
+const CODE_MAX_CHARS: usize = 20_000;
+
+/// Character budget shared by all docs sections
+const DOCS_MAX_CHARS: usize = 20_000;
+
+/// Combined code + docs context tool
+pub struct ContextTool;
+
+impl ContextTool {
+    /// Run the code search and the docs queries concurrently and merge them into one answer
+    pub async fn gather(request: ContextRequest) -> Result<CallToolResult, McpError> {
+        log_important!(info,
+            "Context request: project_root_path={}, query={}, libraries={:?}",
+            request.project_root_path, request.query, request.libraries
+        );
+
+        let mut docs_config = DocsTool::get_config()
+            .await
+            .map_err(|e| McpError::internal_error(format!("Failed to get docs config: {}", e), None))?;
+
+        let libraries = if request.libraries.is_empty() {
+            let dependencies = detect_dependencies(Path::new(&request.project_root_path));
+            libraries_in_query(&dependencies, &request.query)
+        } else {
+            let mut seen = HashSet::new();
+            request
+                .libraries
+                .iter()
+                .map(|l| l.trim().to_string())
+                .filter(|l| !l.is_empty() && seen.insert(l.to_lowercase()))
+                .take(MAX_LIBRARIES)
+                .collect()
+        };
+        if !libraries.is_empty() {
+            docs_config.max_chars = Some(DOCS_MAX_CHARS / libraries.len());
+        }
+
+        let code_request = AcemcpRequest {
+            project_root_path: request.project_root_path.clone(),
+            query: request.query.clone(),
+            include_paths: Vec::new(),
+            exclude_paths: Vec::new(),
+        };
+        let topic = request.topic.clone().unwrap_or_else(|| request.query.clone());
+
+        let mut docs_tasks = tokio::task::JoinSet::new();
+        for (idx, library) in libraries.iter().enumerate() {
+            let config = docs_config.clone();
+            let docs_request = DocsRequest {
+                library: library.clone(),
+                topic: Some(topic.clone()),
+                version: None,
+                page: None,
+                max_pages: None,
+                project_root_path: Some(request.project_root_path.clone()),
+                no_cache: None,
+                action: None,
+            };
+            docs_tasks.spawn(async move { (idx, DocsTool::run_query(&config, docs_request, None).await) });
+        }
+
+        let collect_docs = async {
+            let mut outcomes = Vec::new();
+            while let Some(joined) = docs_tasks.join_next().await {
+                if let Ok(outcome) = joined {
+                    outcomes.push(outcome);
+                }
+            }
+            // Keep the order the libraries were requested in
+            outcomes.sort_by_key(|(idx, _)| *idx);
+            outcomes
+        };
+        let (code_result, docs_outcomes) = tokio::join!(AcemcpTool::search_context(code_request), collect_docs);
+
+        let (code_text, code_ok) = match code_result {
+            Ok(result) => (text_of(&result), result.is_error != Some(true)),
+            Err(e) => (format!("Code search failed: {}", e.message), false),
+        };
+
+        let mut output = format!("# Code + Docs Context\n\n**Query**: {}\n\n", request.query);
+
+        output.push_str("## Your code\n\n");
+        output.push_str(&limit(&code_text, CODE_MAX_CHARS));
+        output.push_str("\n\n## Library docs\n\n");
+
+        let mut docs_ok = 0;
+        if libraries.is_empty() {
+            output.push_str(
+                "No project dependency is mentioned in the query; pass `libraries` to include their docs.\n\n",
+            );
+        }
+        for (idx, result) in docs_outcomes {
+            output.push_str(&format!("### {}\n\n", libraries[idx]));
+            match result {
+                Ok(docs) => {
+                    docs_ok += 1;
+                    output.push_str(docs.trim_end());
+                }
+                Err(e) => output.push_str(&format!("Docs query failed: {}", e)),
+            }
+            output.push_str("\n\n");
+        }
+
+        output.push_str(&format!(
+            "---\nSources: code index ({}), Docs ({})\n",
+            request.project_root_path,
+            if libraries.is_empty() { "-".to_string() } else { libraries.join(", ") }
+        ));
+
+        log_important!(info,
+            "Context response: code_ok={}, docs {}/{} succeeded, {} chars",
+            code_ok, docs_ok, libraries.len(), output.len()
+        );
+
+        Ok(CallToolResult {
+            content: vec![Content::text(output)],
+            is_error: Some(!code_ok && docs_ok == 0),
+            meta: None,
+            structured_content: None,
+        })
+    }
+
+    /// Get tool definition
+    pub fn get_tool_definition() -> Tool {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "project_root_path": {
+                    "type": "string",
+                    "description": "Absolute path to project root directory using forward slashes"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Natural language question; used both for the code search and as the docs topic"
+                },
+                "libraries": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Optional. Package names or owner/repo identifiers to fetch docs for (max 3). Defaults to project dependencies mentioned in the query"
+                },
+                "topic": {
+                    "type": "string",
+                    "description": "Optional. Docs topic, defaults to the query"
+                }
+            },
+            "required": ["project_root_path", "query"]
+        });
+
+        if let serde_json::Value::Object(schema_map) = schema {
+            Tool {
+                name: Cow::Borrowed("context"),
+                description: Some(Cow::Borrowed("Combined code and library docs context. Searches the project index and fetches docs for the relevant dependencies in parallel, returning both in one sectioned answer.")),
+                input_schema: Arc::new(schema_map),
+                annotations: Some(ToolAnnotations {
+                    title: Some("Code + Docs Context".to_string()),
+                    read_only_hint: Some(true),       // Only searches code and reads docs
+                    destructive_hint: Some(false),    // Not destructive
+                    idempotent_hint: Some(true),      // Same query = same result
+                    open_world_hint: Some(true),      // Interacts with external services
+                }),
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: Some("Code + Docs Context".to_string()),
+            }
+        } else {
+            panic!("Schema creation failed");
+        }
+    }
+}
+
+/// Project dependencies whose names appear as words in the query
+fn libraries_in_query(dependencies: &[DetectedDependency], query: &str) -> Vec<String> {
+    let words: HashSet<String> = query
+        .split(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | '@' | '/')))
+        .map(|w| w.trim_matches('.').to_lowercase())
+        .filter(|w| !w.is_empty())
+        .collect();
+
+    dependencies
+        .iter()
+        .filter(|d| words.contains(&d.name.to_lowercase()))
+        .map(|d| d.name.clone())
+        .take(MAX_LIBRARIES)
+        .collect()
+}
+
+/// Concatenate the text parts of a tool result
+fn text_of(result: &CallToolResult) -> String {
+    result
+        .content
+        .iter()
+        .filter_map(|c| c.as_text())
+        .map(|t| t.text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Apply a character budget to a section, noting the truncation
+fn limit(text: &str, max_chars: usize) -> String {
+    match fit_to_budget(text.trim_end(), max_chars) {
+        Some(budgeted) => format!("{}\n\n{}", budgeted.content.trim_end(), budgeted.note(max_chars)),
+        None => text.trim_end().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dependency(name: &str) -> DetectedDependency {
+        DetectedDependency {
+            name: name.to_string(),
+            version: None,
+            ecosystem: "npm".to_string(),
+            manifest: "package.json".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_libraries_in_query() {
+        let deps = [dependency("react"), dependency("react-dom"), dependency("@tanstack/react-query"), dependency("zod")];
+        assert_eq!(
+            libraries_in_query(&deps, "How does React render with @tanstack/react-query?"),
+            vec!["react".to_string(), "@tanstack/react-query".to_string()]
+        );
+        assert!(libraries_in_query(&deps, "where is the config loaded").is_empty());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod types;
+pub mod mcp;
+
+pub use mcp::ContextTool;
+pub use types::ContextRequest;
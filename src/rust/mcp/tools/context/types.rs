@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// 代码 + 文档联合上下文请求
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ContextRequest {
+    /// 项目根目录的绝对路径（代码检索与依赖检测均基于此目录）
+    #[schemars(description = "项目根目录的绝对路径")]
+    pub project_root_path: String,
+    /// 自然语言问题，同时用于代码检索和文档主题
+    #[schemars(description = "自然语言问题，同时用于代码检索和文档主题")]
+    pub query: String,
+    /// 要查询文档的依赖包名或库标识符 (可选)；为空时取问题中提到的项目依赖
+    #[schemars(description = "要查询文档的依赖包名或库标识符 (可选)；为空时取问题中提到的项目依赖")]
+    #[serde(default)]
+    pub libraries: Vec<String>,
+    /// 文档查询主题 (可选，默认使用 query)
+    #[schemars(description = "文档查询主题 (可选，默认使用 query)")]
+    #[serde(default)]
+    pub topic: Option<String>,
+}
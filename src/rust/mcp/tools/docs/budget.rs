@@ -0,0 +1,242 @@
+/// 默认的响应长度上限（字符数），约合 15k tokens
+pub const DEFAULT_MAX_CHARS: usize = 60_000;
+
+/// 低于该值的上限视为配置错误，按该值处理
+const MIN_MAX_CHARS: usize = 2_000;
+
+/// 按长度预算截断后的结果
+#[derive(Debug, PartialEq)]
+pub struct BudgetedDocs {
+    pub content: String,
+    /// 原文字符数
+    pub original_chars: usize,
+    /// 丢弃的说明段落数
+    pub dropped_prose: usize,
+    /// 丢弃的末尾标题/代码块数
+    pub dropped_blocks: usize,
+}
+
+impl BudgetedDocs {
+    /// 附在响应中的截断说明
+    pub fn note(&self, max_chars: usize) -> String {
+        let mut dropped = vec![format!("{} prose paragraph(s)", self.dropped_prose)];
+        if self.dropped_blocks > 0 {
+            dropped.push(format!("{} trailing heading/code block(s)", self.dropped_blocks));
+        }
+        format!(
+            "> Truncated from {} to fit the {}-character budget: dropped {}. \
+            Narrow the query with `topic` or `page` to see the rest.",
+            self.original_chars,
+            max_chars,
+            dropped.join(" and ")
+        )
+    }
+}
+
+/// 将配置的上限规范为实际使用的值：None 使用默认值，0 表示不限制
+pub fn effective_max_chars(configured: Option<usize>) -> Option<usize> {
+    match configured.unwrap_or(DEFAULT_MAX_CHARS) {
+        0 => None,
+        max => Some(max.max(MIN_MAX_CHARS)),
+    }
+}
+
+/// 清理文档内容：统一换行、去掉控制字符与零宽字符、去掉行尾空白，
+/// 代码块外连续的空行合并为一个
+pub fn sanitize(content: &str) -> String {
+    let normalized = content.replace("\r\n", "\n").replace('\r', "\n");
+    let mut output = String::with_capacity(normalized.len());
+    let mut in_code = false;
+    let mut blank_run = 0;
+
+    for line in normalized.lines() {
+        let line: String = line
+            .chars()
+            .filter(|c| *c == '\t' || !(c.is_control() || matches!(c, '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{FEFF}')))
+            .collect();
+        let line = line.trim_end();
+
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if line.is_empty() && !in_code {
+            blank_run += 1;
+            if blank_run > 1 {
+                continue;
+            }
+        } else {
+            blank_run = 0;
+        }
+        output.push_str(line);
+        output.push('\n');
+    }
+
+    let trimmed = output.trim_matches('\n');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", trimmed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BlockKind {
+    Heading,
+    Code,
+    /// 章节中第一段说明（保留优先级高于后续段落）
+    LeadProse,
+    Prose,
+}
+
+struct Block<'a> {
+    kind: BlockKind,
+    text: &'a str,
+    chars: usize,
+}
+
+/// 超出预算时按优先级丢弃内容：先从后往前丢弃各章节除首段外的说明段落，
+/// 再丢弃首段说明，标题与代码块最后才从末尾丢弃。未超出预算时返回 None
+pub fn fit_to_budget(markdown: &str, max_chars: usize) -> Option<BudgetedDocs> {
+    let original_chars = markdown.chars().count();
+    if original_chars <= max_chars {
+        return None;
+    }
+
+    let blocks = split_blocks(markdown);
+    let mut keep = vec![true; blocks.len()];
+    let mut total = original_chars;
+    let mut dropped_prose = 0;
+    let mut dropped_blocks = 0;
+
+    for kind in [BlockKind::Prose, BlockKind::LeadProse] {
+        for (idx, block) in blocks.iter().enumerate().rev() {
+            if total <= max_chars {
+                break;
+            }
+            if block.kind == kind {
+                keep[idx] = false;
+                total -= block.chars;
+                dropped_prose += 1;
+            }
+        }
+    }
+    for (idx, block) in blocks.iter().enumerate().rev() {
+        // 至少保留一个块，超长的单个块在下面硬截断
+        if total <= max_chars || keep[..idx].iter().all(|k| !k) {
+            break;
+        }
+        if keep[idx] {
+            keep[idx] = false;
+            total -= block.chars;
+            dropped_blocks += 1;
+        }
+    }
+
+    let mut content: String = blocks
+        .iter()
+        .zip(&keep)
+        .filter(|(_, k)| **k)
+        .map(|(block, _)| block.text)
+        .collect();
+    if total > max_chars {
+        content = content.chars().take(max_chars).collect();
+        if content.matches("```").count() % 2 == 1 {
+            content.push_str("\n```");
+        }
+        content.push('\n');
+    }
+
+    Some(BudgetedDocs {
+        content,
+        original_chars,
+        dropped_prose,
+        dropped_blocks,
+    })
+}
+
+/// 切分为标题行、代码块与说明段落，块后的空行归入该块
+fn split_blocks(markdown: &str) -> Vec<Block<'_>> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut start = 0;
+    let mut kind: Option<BlockKind> = None;
+    let mut in_code = false;
+    let mut section_has_prose = false;
+    let mut offset = 0;
+
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim();
+        let line_end = offset + line.len();
+
+        if in_code {
+            if trimmed.starts_with("```") {
+                in_code = false;
+            }
+        } else if trimmed.starts_with("```") {
+            push_block(&mut blocks, kind, &markdown[start..offset]);
+            start = offset;
+            kind = Some(BlockKind::Code);
+            in_code = true;
+        } else if is_heading(trimmed) {
+            push_block(&mut blocks, kind, &markdown[start..offset]);
+            start = offset;
+            kind = Some(BlockKind::Heading);
+            section_has_prose = false;
+        } else if trimmed.is_empty() {
+            // 空行归入前一个块；开头的空行单独作为说明段落
+            if kind.is_none() {
+                kind = Some(BlockKind::Prose);
+            }
+        } else if !matches!(kind, Some(BlockKind::Prose | BlockKind::LeadProse))
+            || markdown[start..offset].ends_with("\n\n")
+        {
+            push_block(&mut blocks, kind, &markdown[start..offset]);
+            start = offset;
+            kind = Some(if section_has_prose { BlockKind::Prose } else { BlockKind::LeadProse });
+            section_has_prose = true;
+        }
+        offset = line_end;
+    }
+    push_block(&mut blocks, kind, &markdown[start..]);
+    blocks
+}
+
+fn push_block<'a>(blocks: &mut Vec<Block<'a>>, kind: Option<BlockKind>, text: &'a str) {
+    if let Some(kind) = kind {
+        blocks.push(Block { kind, text, chars: text.chars().count() });
+    }
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        let raw = "# Title\r\n\r\n\r\n\r\nText\u{200B} here   \n```\ncode\n\n\n```\n\n\n";
+        assert_eq!(sanitize(raw), "# Title\n\nText here\n```\ncode\n\n\n```\n");
+    }
+
+    #[test]
+    fn test_fit_to_budget() {
+        let doc = "## Install\nLead paragraph.\n\nLong extra prose that goes on and on.\n\n```sh\ncargo add tokio\n```\n## Usage\nAnother lead.\n\nMore prose here.\n";
+        assert!(fit_to_budget(doc, doc.len()).is_none());
+
+        let fitted = fit_to_budget(doc, doc.len() - 20).unwrap();
+        assert_eq!(fitted.dropped_prose, 2);
+        assert_eq!(fitted.dropped_blocks, 0);
+        assert!(fitted.content.contains("Lead paragraph."));
+        assert!(fitted.content.contains("cargo add tokio"));
+        assert!(!fitted.content.contains("More prose here."));
+        assert!(!fitted.content.contains("Long extra prose"));
+
+        let tight = fit_to_budget(doc, 40).unwrap();
+        assert!(tight.dropped_blocks > 0);
+        assert!(tight.content.chars().count() <= 45);
+        assert!(tight.content.starts_with("## Install"));
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use super::types::DocsRequest;
+use crate::log_debug;
+
+/// 默认缓存有效期（秒）：24 小时
+pub const DEFAULT_CACHE_TTL_SECS: u64 = 86_400;
+
+/// 磁盘缓存条目
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    /// 缓存键对应的查询参数（便于排查）
+    key: String,
+    /// 写入时间（Unix 秒）
+    fetched_at: u64,
+    /// 单页文档原文
+    content: String,
+}
+
+/// Docs 缓存目录
+fn cache_dir() -> Result<PathBuf> {
+    let root = dirs::cache_dir()
+        .or_else(dirs::data_dir)
+        .or_else(dirs::config_dir)
+        .unwrap_or_else(std::env::temp_dir);
+    let dir = root.join("devkit").join("docs");
+    fs::create_dir_all(&dir)
+        .map_err(|e| anyhow::anyhow!("无法创建缓存目录 {}: {}", dir.display(), e))?;
+    Ok(dir)
+}
+
+/// 缓存键：(base_url, library, topic, version, page)，库名与主题不区分大小写，页码缺省为 1
+///
+/// 包含 API 基础 URL，切换到自建或镜像服务后不会命中其他服务的缓存
+fn cache_key(base_url: &str, request: &DocsRequest) -> String {
+    let normalize = |s: &Option<String>| s.as_deref().map(|v| v.trim().to_lowercase()).unwrap_or_default();
+    format!(
+        "{}|{}|{}|{}|{}",
+        base_url,
+        request.library.trim().trim_matches('/').to_lowercase(),
+        normalize(&request.topic),
+        request.version.as_deref().map(str::trim).unwrap_or_default(),
+        request.page.unwrap_or(1)
+    )
+}
+
+fn cache_file(key: &str) -> Result<PathBuf> {
+    let hash = hex::encode(digest(&SHA256, key.as_bytes()).as_ref());
+    Ok(cache_dir()?.join(format!("{}.json", &hash[..16])))
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 读取未过期的缓存内容
+pub fn get(base_url: &str, request: &DocsRequest, ttl_secs: u64) -> Option<String> {
+    if ttl_secs == 0 {
+        return None;
+    }
+    let key = cache_key(base_url, request);
+    let data = fs::read_to_string(cache_file(&key).ok()?).ok()?;
+    let entry: CacheEntry = serde_json::from_str(&data).ok()?;
+    // 哈希截断后理论上可能碰撞，校验完整键
+    if entry.key != key || now_secs().saturating_sub(entry.fetched_at) >= ttl_secs {
+        return None;
+    }
+    Some(entry.content)
+}
+
+/// 写入缓存（失败只记录日志，不影响查询）
+pub fn put(base_url: &str, request: &DocsRequest, content: &str) {
+    let key = cache_key(base_url, request);
+    let result = cache_file(&key).and_then(|path| {
+        let entry = CacheEntry {
+            key,
+            fetched_at: now_secs(),
+            content: content.to_string(),
+        };
+        fs::write(path, serde_json::to_string(&entry)?)?;
+        Ok(())
+    });
+    if let Err(e) = result {
+        log_debug!("Failed to write docs cache: {}", e);
+    }
+}
+
+/// 清空缓存，返回删除的条目数
+pub fn clear() -> Result<usize> {
+    let mut removed = 0;
+    for entry in fs::read_dir(cache_dir()?)?.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) == Some("json") && fs::remove_file(&path).is_ok() {
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(library: &str, topic: Option<&str>, page: Option<u32>) -> DocsRequest {
+        DocsRequest {
+            library: library.to_string(),
+            topic: topic.map(str::to_string),
+            version: None,
+            page,
+            max_pages: None,
+            project_root_path: None,
+            no_cache: None,
+            action: None,
+        }
+    }
+
+    const BASE: &str = "https://example.com/api/v2";
+
+    #[test]
+    fn test_cache_key_normalization() {
+        assert_eq!(
+            cache_key(BASE, &request("/Facebook/React", Some(" Hooks "), None)),
+            cache_key(BASE, &request("facebook/react", Some("hooks"), Some(1)))
+        );
+        assert_ne!(
+            cache_key(BASE, &request("facebook/react", Some("hooks"), Some(1))),
+            cache_key(BASE, &request("facebook/react", Some("hooks"), Some(2)))
+        );
+        assert_ne!(
+            cache_key(BASE, &request("facebook/react", None, None)),
+            cache_key(BASE, &request("facebook/react", Some("hooks"), None))
+        );
+    }
+
+    #[test]
+    fn test_cache_key_includes_base_url() {
+        let req = request("facebook/react", Some("hooks"), None);
+        assert_ne!(cache_key(BASE, &req), cache_key("https://mirror.internal/api/v2", &req));
+    }
+}
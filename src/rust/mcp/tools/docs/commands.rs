@@ -1,6 +1,14 @@
 use tauri::State;
 use crate::config::AppState;
-use super::types::{DocsRequest, DocsConfig, TestConnectionResponse, docs_api_base_url};
+use super::budget::{effective_max_chars, DEFAULT_MAX_CHARS};
+use super::cache::DEFAULT_CACHE_TTL_SECS;
+use super::registry::{normalize, resolve_alias, LibraryRegistry};
+use super::relevance::DEFAULT_CONTEXT_SECTIONS;
+use super::dependencies::LibraryResolution;
+use super::history::{self, DocsQueryRecord};
+use super::rate_limit;
+use super::DocsTool;
+use super::types::{DocsRequest, DocsConfig, TestConnectionResponse, docs_api_base_url, resolve_docs_base_url};
 
 /// 测试 Docs 连接
 #[tauri::command]
@@ -17,12 +25,19 @@ pub async fn test_docs_connection(
 
         DocsConfig {
             api_key: config.mcp_config.docs_api_key.clone(),
-            base_url: docs_api_base_url(),
+            base_url: resolve_docs_base_url(config.mcp_config.docs_base_url.as_deref()),
+            cache_ttl_secs: config.mcp_config.docs_cache_ttl_secs,
+            library_aliases: config.mcp_config.docs_library_aliases.clone().unwrap_or_default(),
+            topic_filter: config.mcp_config.docs_topic_filter.unwrap_or(true),
+            topic_filter_context: config.mcp_config.docs_topic_filter_context.unwrap_or(DEFAULT_CONTEXT_SECTIONS),
+            max_chars: effective_max_chars(config.mcp_config.docs_max_chars),
         }
     }; // config 在这里自动 drop
 
     // 使用用户指定的库，或默认使用 Spring Framework
-    let test_library = library.unwrap_or_else(|| "spring-projects/spring-framework".to_string());
+    let test_library = library
+        .map(|l| resolve_alias(&docs_config.library_aliases, &l))
+        .unwrap_or_else(|| "spring-projects/spring-framework".to_string());
     let test_topic = topic.or_else(|| Some("core".to_string()));
 
     // 执行测试查询
@@ -31,6 +46,10 @@ pub async fn test_docs_connection(
         topic: test_topic,
         version: None,
         page: Some(1),
+        max_pages: None,
+        project_root_path: None,
+        no_cache: None,
+        action: None,
     };
 
     // 调用内部方法执行查询
@@ -40,6 +59,7 @@ pub async fn test_docs_connection(
                 success: true,
                 message: format!("连接成功! 已获取 {} 文档", test_library),
                 preview: Some(preview),
+                rate_limit: rate_limit::latest(),
             })
         }
         Err(e) => {
@@ -47,6 +67,7 @@ pub async fn test_docs_connection(
                 success: false,
                 message: format!("连接失败: {}", e),
                 preview: None,
+                rate_limit: rate_limit::latest(),
             })
         }
     }
@@ -89,6 +110,11 @@ async fn execute_test_query(
     let response = req_builder.send().await
         .map_err(|e| format!("请求失败: {}", e))?;
 
+    // 记录速率限制信息，供设置界面展示剩余额度
+    if let Some(info) = rate_limit::from_headers(response.headers(), config.api_key.is_some()) {
+        rate_limit::record(&info);
+    }
+
     let status = response.status();
 
     // 处理错误状态码
@@ -127,7 +153,15 @@ fn format_test_error(status_code: u16, error_text: &str, library: &str) -> Strin
     match status_code {
         401 => "API 密钥无效或已过期".to_string(),
         404 => format!("库 \"{}\" 不存在，请检查库标识符是否正确", library),
-        429 => "速率限制已达上限，建议配置 API Key".to_string(),
+        429 => match rate_limit::latest().and_then(|info| info.reset_at) {
+            Some(reset_at) => format!(
+                "速率限制已达上限（{}），建议配置 API Key",
+                chrono::DateTime::from_timestamp(reset_at as i64, 0)
+                    .map(|t| format!("{} 重置", t.with_timezone(&chrono::Local).format("%H:%M:%S")))
+                    .unwrap_or_else(|| "稍后重置".to_string())
+            ),
+            None => "速率限制已达上限，建议配置 API Key".to_string(),
+        },
         500..=599 => format!("Docs 服务器错误: {}", error_text),
         _ => format!("请求失败 (状态码: {}): {}", status_code, error_text),
     }
@@ -144,6 +178,13 @@ pub async fn get_docs_config(
     
     Ok(DocsConfigResponse {
         api_key: config.mcp_config.docs_api_key.clone(),
+        base_url: config.mcp_config.docs_base_url.clone(),
+        default_base_url: docs_api_base_url(),
+        cache_ttl_secs: config.mcp_config.docs_cache_ttl_secs.unwrap_or(DEFAULT_CACHE_TTL_SECS),
+        topic_filter: config.mcp_config.docs_topic_filter.unwrap_or(true),
+        topic_filter_context: config.mcp_config.docs_topic_filter_context.unwrap_or(DEFAULT_CONTEXT_SECTIONS),
+        max_chars: config.mcp_config.docs_max_chars.unwrap_or(DEFAULT_MAX_CHARS),
+        rate_limit: rate_limit::latest(),
     })
 }
 
@@ -151,12 +192,31 @@ pub async fn get_docs_config(
 #[derive(serde::Serialize)]
 pub struct DocsConfigResponse {
     pub api_key: Option<String>,
+    /// 自定义 API 基础 URL（为空表示使用官方地址）
+    pub base_url: Option<String>,
+    /// 官方 API 基础 URL（前端占位提示）
+    pub default_base_url: String,
+    /// 查询结果缓存有效期（秒），0 表示不缓存
+    pub cache_ttl_secs: u64,
+    /// 是否按主题关键词过滤返回的章节
+    pub topic_filter: bool,
+    /// 主题过滤时额外保留的相邻章节数
+    pub topic_filter_context: usize,
+    /// 响应字符数上限，0 表示不限制
+    pub max_chars: usize,
+    /// 最近一次请求返回的速率限制信息（剩余额度、重置时间）
+    pub rate_limit: Option<rate_limit::RateLimitInfo>,
 }
 
 /// 保存 Docs 配置
 #[tauri::command]
 pub async fn save_docs_config(
     api_key: String,
+    base_url: Option<String>,
+    cache_ttl_secs: Option<u64>,
+    topic_filter: Option<bool>,
+    topic_filter_context: Option<usize>,
+    max_chars: Option<usize>,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
 ) -> Result<(), String> {
@@ -172,6 +232,25 @@ pub async fn save_docs_config(
         } else {
             Some(api_key.trim().to_string())
         };
+        if let Some(url) = base_url {
+            let url = url.trim().trim_end_matches('/');
+            if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err("API 基础 URL 必须以 http:// 或 https:// 开头".to_string());
+            }
+            config.mcp_config.docs_base_url = if url.is_empty() { None } else { Some(url.to_string()) };
+        }
+        if let Some(ttl) = cache_ttl_secs {
+            config.mcp_config.docs_cache_ttl_secs = Some(ttl);
+        }
+        if let Some(enabled) = topic_filter {
+            config.mcp_config.docs_topic_filter = Some(enabled);
+        }
+        if let Some(context) = topic_filter_context {
+            config.mcp_config.docs_topic_filter_context = Some(context);
+        }
+        if let Some(max_chars) = max_chars {
+            config.mcp_config.docs_max_chars = Some(max_chars);
+        }
     }
 
     // 保存配置到文件
@@ -181,3 +260,105 @@ pub async fn save_docs_config(
     Ok(())
 }
 
+
+/// 清空 Docs 查询缓存，返回删除的条目数
+#[tauri::command]
+pub async fn clear_docs_cache() -> Result<usize, String> {
+    super::cache::clear().map_err(|e| format!("清空缓存失败: {}", e))
+}
+
+/// 获取库别名与收藏列表
+#[tauri::command]
+pub async fn get_docs_library_registry(
+    state: State<'_, AppState>
+) -> Result<LibraryRegistry, String> {
+    let config = state.config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+
+    Ok(LibraryRegistry {
+        aliases: config.mcp_config.docs_library_aliases.clone().unwrap_or_default(),
+        favorites: config.mcp_config.docs_favorites.clone().unwrap_or_default(),
+    })
+}
+
+/// 保存库别名与收藏列表，返回规范化后的结果
+#[tauri::command]
+pub async fn save_docs_library_registry(
+    registry: LibraryRegistry,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<LibraryRegistry, String> {
+    let registry = normalize(registry);
+    {
+        let mut config = state.config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+
+        config.mcp_config.docs_library_aliases = Some(registry.aliases.clone());
+        config.mcp_config.docs_favorites = Some(registry.favorites.clone());
+    }
+
+    crate::config::save_config(&state, &app).await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    log::info!("已保存 Docs 库别名 {} 个，收藏 {} 个", registry.aliases.len(), registry.favorites.len());
+    Ok(registry)
+}
+
+/// 将裸包名解析为库标识符与项目中使用的版本（读取项目的 Cargo.toml / package.json / pyproject.toml）
+#[tauri::command]
+pub async fn resolve_docs_library(
+    project_root_path: String,
+    name: String,
+) -> Result<LibraryResolution, String> {
+    let config = DocsTool::get_config()
+        .await
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(DocsTool::resolve_library(&config, &project_root_path, &name).await)
+}
+
+/// 获取 Docs 查询记录（最新的在前）
+#[tauri::command]
+pub async fn get_docs_query_history(limit: Option<usize>) -> Result<Vec<DocsQueryRecord>, String> {
+    Ok(history::list(limit))
+}
+
+/// 重新查询结果
+#[derive(serde::Serialize)]
+pub struct DocsRerunResponse {
+    pub success: bool,
+    /// 文档内容或错误信息
+    pub content: String,
+    /// 与原记录相比文档是否变化（原记录失败时为 None）
+    pub changed: Option<bool>,
+}
+
+/// 按记录重新执行 Docs 查询（跳过缓存）
+#[tauri::command]
+pub async fn rerun_docs_query(id: String) -> Result<DocsRerunResponse, String> {
+    let record = history::find(&id).ok_or_else(|| format!("查询记录不存在: {}", id))?;
+    let config = DocsTool::get_config()
+        .await
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+
+    log::info!("重新执行 Docs 查询: library={}, topic={:?}", record.library, record.topic);
+    match DocsTool::run_query(&config, record.to_request(), None).await {
+        Ok(content) => Ok(DocsRerunResponse {
+            success: true,
+            changed: record.result_hash.as_ref().map(|hash| *hash != history::result_hash(&content)),
+            content,
+        }),
+        Err(e) => Ok(DocsRerunResponse {
+            success: false,
+            content: e.to_string(),
+            changed: None,
+        }),
+    }
+}
+
+/// 清空 Docs 查询记录
+#[tauri::command]
+pub async fn clear_docs_query_history() -> Result<(), String> {
+    history::clear().map_err(|e| format!("清空查询记录失败: {}", e))
+}
@@ -0,0 +1,264 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use super::registry::resolve_alias;
+
+/// 常见包名 -> 库标识符（未命中时再走别名或搜索）
+const KNOWN_LIBRARIES: &[(&str, &str)] = &[
+    ("react", "facebook/react"),
+    ("react-dom", "facebook/react"),
+    ("vue", "vuejs/vue"),
+    ("next", "vercel/next.js"),
+    ("svelte", "sveltejs/svelte"),
+    ("vite", "vitejs/vite"),
+    ("express", "expressjs/express"),
+    ("typescript", "microsoft/TypeScript"),
+    ("tailwindcss", "tailwindlabs/tailwindcss"),
+    ("tokio", "tokio-rs/tokio"),
+    ("axum", "tokio-rs/axum"),
+    ("serde", "serde-rs/serde"),
+    ("reqwest", "seanmonstar/reqwest"),
+    ("tauri", "tauri-apps/tauri"),
+    ("fastapi", "tiangolo/fastapi"),
+    ("django", "django/django"),
+    ("flask", "pallets/flask"),
+    ("pydantic", "pydantic/pydantic"),
+    ("requests", "psf/requests"),
+    ("pandas", "pandas-dev/pandas"),
+    ("numpy", "numpy/numpy"),
+];
+
+/// 项目清单中检测到的依赖
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct DetectedDependency {
+    pub name: String,
+    /// 规范化后的版本号 (例如: v1.2.3)，无法确定时为 None
+    pub version: Option<String>,
+    /// 生态: cargo / npm / pypi
+    pub ecosystem: String,
+    /// 来源清单文件名
+    pub manifest: String,
+}
+
+/// 包名解析结果（可直接用于 DocsRequest）
+#[derive(Debug, Clone, Serialize)]
+pub struct LibraryResolution {
+    /// 查询的包名
+    pub name: String,
+    /// 解析出的库标识符 (owner/repo)
+    pub library: Option<String>,
+    /// 项目中检测到的版本
+    pub version: Option<String>,
+    pub ecosystem: Option<String>,
+    /// 解析来源: alias / known / search / unresolved
+    pub source: String,
+}
+
+/// 解析项目根目录下的 Cargo.toml / package.json / pyproject.toml
+pub fn detect_dependencies(project_root: &Path) -> Vec<DetectedDependency> {
+    let mut deps = Vec::new();
+    if let Ok(content) = fs::read_to_string(project_root.join("Cargo.toml")) {
+        deps.extend(parse_cargo_toml(&content));
+    }
+    if let Ok(content) = fs::read_to_string(project_root.join("package.json")) {
+        deps.extend(parse_package_json(&content));
+    }
+    if let Ok(content) = fs::read_to_string(project_root.join("pyproject.toml")) {
+        deps.extend(parse_pyproject_toml(&content));
+    }
+    deps
+}
+
+/// 在不访问网络的前提下解析包名：别名 → 内置表；同时附上项目中检测到的版本
+pub fn resolve_locally(
+    aliases: &HashMap<String, String>,
+    dependencies: &[DetectedDependency],
+    name: &str,
+) -> LibraryResolution {
+    let name = name.trim();
+    let dependency = dependencies.iter().find(|d| d.name.eq_ignore_ascii_case(name));
+    let mut resolution = LibraryResolution {
+        name: name.to_string(),
+        library: None,
+        version: dependency.and_then(|d| d.version.clone()),
+        ecosystem: dependency.map(|d| d.ecosystem.clone()),
+        source: "unresolved".to_string(),
+    };
+
+    let aliased = resolve_alias(aliases, name);
+    if aliased != name {
+        resolution.library = Some(aliased);
+        resolution.source = "alias".to_string();
+    } else if let Some((_, library)) = KNOWN_LIBRARIES.iter().find(|(pkg, _)| pkg.eq_ignore_ascii_case(name)) {
+        resolution.library = Some(library.to_string());
+        resolution.source = "known".to_string();
+    }
+    resolution
+}
+
+fn parse_cargo_toml(content: &str) -> Vec<DetectedDependency> {
+    let Ok(value) = toml::from_str::<toml::Table>(content) else {
+        return Vec::new();
+    };
+    let tables = [
+        value.get("dependencies"),
+        value.get("dev-dependencies"),
+        value.get("build-dependencies"),
+        value.get("workspace").and_then(|w| w.get("dependencies")),
+    ];
+
+    let mut deps = Vec::new();
+    for table in tables.into_iter().flatten().filter_map(|t| t.as_table()) {
+        for (name, spec) in table {
+            let version = match spec {
+                toml::Value::String(v) => Some(v.as_str()),
+                toml::Value::Table(t) => t.get("version").and_then(|v| v.as_str()),
+                _ => None,
+            };
+            // 带 package 重命名的依赖以真实包名为准
+            let name = spec.get("package").and_then(|p| p.as_str()).unwrap_or(name);
+            push_unique(&mut deps, name, version.and_then(normalize_version), "cargo", "Cargo.toml");
+        }
+    }
+    deps
+}
+
+fn parse_package_json(content: &str) -> Vec<DetectedDependency> {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return Vec::new();
+    };
+
+    let mut deps = Vec::new();
+    for key in ["dependencies", "devDependencies", "peerDependencies"] {
+        let Some(map) = value.get(key).and_then(|v| v.as_object()) else {
+            continue;
+        };
+        for (name, spec) in map {
+            let version = spec.as_str().and_then(normalize_version);
+            push_unique(&mut deps, name, version, "npm", "package.json");
+        }
+    }
+    deps
+}
+
+fn parse_pyproject_toml(content: &str) -> Vec<DetectedDependency> {
+    let Ok(value) = toml::from_str::<toml::Table>(content) else {
+        return Vec::new();
+    };
+
+    let mut deps = Vec::new();
+    // PEP 621: [project] dependencies = ["fastapi>=0.110", ...]
+    let pep621 = value
+        .get("project")
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_array());
+    for requirement in pep621.into_iter().flatten().filter_map(|r| r.as_str()) {
+        let (name, spec) = split_requirement(requirement);
+        push_unique(&mut deps, name, normalize_version(spec), "pypi", "pyproject.toml");
+    }
+
+    // Poetry: [tool.poetry.dependencies] fastapi = "^0.110"
+    let poetry = value
+        .get("tool")
+        .and_then(|t| t.get("poetry"))
+        .and_then(|p| p.get("dependencies"))
+        .and_then(|d| d.as_table());
+    for (name, spec) in poetry.into_iter().flatten() {
+        if name == "python" {
+            continue;
+        }
+        let version = match spec {
+            toml::Value::String(v) => Some(v.as_str()),
+            toml::Value::Table(t) => t.get("version").and_then(|v| v.as_str()),
+            _ => None,
+        };
+        push_unique(&mut deps, name, version.and_then(normalize_version), "pypi", "pyproject.toml");
+    }
+    deps
+}
+
+fn push_unique(deps: &mut Vec<DetectedDependency>, name: &str, version: Option<String>, ecosystem: &str, manifest: &str) {
+    if deps.iter().any(|d| d.name == name) {
+        return;
+    }
+    deps.push(DetectedDependency {
+        name: name.to_string(),
+        version,
+        ecosystem: ecosystem.to_string(),
+        manifest: manifest.to_string(),
+    });
+}
+
+/// 拆分 PEP 508 依赖声明："fastapi[all]>=0.110,<1" -> ("fastapi", ">=0.110,<1")
+fn split_requirement(requirement: &str) -> (&str, &str) {
+    let requirement = requirement.split(';').next().unwrap_or("").trim();
+    let end = requirement
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.'))
+        .unwrap_or(requirement.len());
+    let rest = requirement[end..].trim_start();
+    let rest = match rest.strip_prefix('[') {
+        Some(extras) => extras.split_once(']').map(|(_, r)| r).unwrap_or(""),
+        None => rest,
+    };
+    (&requirement[..end], rest.trim())
+}
+
+/// 从版本约束中取出第一个具体版本号："^18.2.0" -> "v18.2.0"，"*" / "workspace:*" -> None
+fn normalize_version(spec: &str) -> Option<String> {
+    let start = spec.find(|c: char| c.is_ascii_digit())?;
+    let version: String = spec[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit() || *c == '.')
+        .collect();
+    let version = version.trim_end_matches('.');
+    if version.is_empty() {
+        None
+    } else {
+        Some(format!("v{}", version))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_manifests() {
+        let cargo = parse_cargo_toml(
+            "[dependencies]\ntokio = { version = \"1.36\", features = [\"full\"] }\nserde = \"^1.0.197\"\nlocal = { path = \"../local\" }\n",
+        );
+        let version = |name: &str| cargo.iter().find(|d| d.name == name).and_then(|d| d.version.clone());
+        assert_eq!(cargo.len(), 3);
+        assert_eq!(version("tokio").as_deref(), Some("v1.36"));
+        assert_eq!(version("serde").as_deref(), Some("v1.0.197"));
+        assert_eq!(version("local"), None);
+
+        let npm = parse_package_json(r#"{"dependencies":{"react":"^18.2.0","shared":"workspace:*"}}"#);
+        let react = npm.iter().find(|d| d.name == "react").unwrap();
+        assert_eq!(react.version.as_deref(), Some("v18.2.0"));
+        assert_eq!(npm.iter().find(|d| d.name == "shared").unwrap().version, None);
+
+        let py = parse_pyproject_toml(
+            "[project]\ndependencies = [\"fastapi[all]>=0.110,<1\", \"requests; python_version > '3.8'\"]\n",
+        );
+        assert_eq!(py[0].name, "fastapi");
+        assert_eq!(py[0].version.as_deref(), Some("v0.110"));
+        assert_eq!(py[1].name, "requests");
+        assert_eq!(py[1].version, None);
+    }
+
+    #[test]
+    fn test_resolve_locally() {
+        let deps = parse_package_json(r#"{"dependencies":{"react":"~18.3.1"}}"#);
+        let resolved = resolve_locally(&HashMap::new(), &deps, "React");
+        assert_eq!(resolved.library.as_deref(), Some("facebook/react"));
+        assert_eq!(resolved.version.as_deref(), Some("v18.3.1"));
+        assert_eq!(resolved.source, "known");
+
+        let aliases = HashMap::from([("react".to_string(), "reactjs/react.dev".to_string())]);
+        assert_eq!(resolve_locally(&aliases, &deps, "react").source, "alias");
+        assert_eq!(resolve_locally(&aliases, &deps, "left-pad").source, "unresolved");
+    }
+}
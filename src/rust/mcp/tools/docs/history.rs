@@ -0,0 +1,128 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use ring::digest::{digest, SHA256};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use super::types::DocsRequest;
+use crate::log_debug;
+
+/// 最多保留的查询记录数
+const MAX_ENTRIES: usize = 200;
+
+/// 读写锁，避免并发查询同时改写文件
+static HISTORY_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+/// 一次 docs 查询记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocsQueryRecord {
+    pub id: String,
+    pub queried_at: DateTime<Utc>,
+    /// 调用方传入的库标识符（可能是别名或裸包名）
+    pub library: String,
+    /// 实际查询的库标识符
+    pub resolved_library: String,
+    pub topic: Option<String>,
+    pub version: Option<String>,
+    pub page: Option<u32>,
+    pub max_pages: Option<u32>,
+    pub project_root_path: Option<String>,
+    pub success: bool,
+    /// 结果内容哈希（前 16 位），用于判断重新查询后文档是否变化
+    pub result_hash: Option<String>,
+    /// 结果长度（字符数）
+    pub result_chars: usize,
+    pub error: Option<String>,
+}
+
+impl DocsQueryRecord {
+    /// 由记录还原查询请求（重新查询时跳过缓存）
+    pub fn to_request(&self) -> DocsRequest {
+        DocsRequest {
+            library: self.library.clone(),
+            topic: self.topic.clone(),
+            version: self.version.clone(),
+            page: self.page,
+            max_pages: self.max_pages,
+            project_root_path: self.project_root_path.clone(),
+            no_cache: Some(true),
+            action: None,
+        }
+    }
+}
+
+fn history_file() -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .or_else(dirs::config_dir)
+        .ok_or_else(|| anyhow::anyhow!("无法获取数据目录"))?
+        .join("devkit");
+    fs::create_dir_all(&dir)?;
+    Ok(dir.join("docs_history.json"))
+}
+
+/// 结果内容哈希
+pub fn result_hash(content: &str) -> String {
+    hex::encode(digest(&SHA256, content.as_bytes()).as_ref())[..16].to_string()
+}
+
+fn load_unlocked() -> Vec<DocsQueryRecord> {
+    history_file()
+        .ok()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// 记录一次查询（失败只记录日志，不影响查询）
+pub fn record(original: &DocsRequest, resolved_library: &str, result: &Result<String>) {
+    let entry = DocsQueryRecord {
+        id: uuid::Uuid::new_v4().to_string(),
+        queried_at: Utc::now(),
+        library: original.library.clone(),
+        resolved_library: resolved_library.to_string(),
+        topic: original.topic.clone(),
+        version: original.version.clone(),
+        page: original.page,
+        max_pages: original.max_pages,
+        project_root_path: original.project_root_path.clone(),
+        success: result.is_ok(),
+        result_hash: result.as_ref().ok().map(|content| result_hash(content)),
+        result_chars: result.as_ref().map(|content| content.chars().count()).unwrap_or(0),
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries = load_unlocked();
+    entries.insert(0, entry);
+    entries.truncate(MAX_ENTRIES);
+    let saved = history_file().and_then(|path| Ok(fs::write(path, serde_json::to_string(&entries)?)?));
+    if let Err(e) = saved {
+        log_debug!("Failed to save docs query history: {}", e);
+    }
+}
+
+/// 最近的查询记录（最新的在前）
+pub fn list(limit: Option<usize>) -> Vec<DocsQueryRecord> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let mut entries = load_unlocked();
+    entries.truncate(limit.unwrap_or(MAX_ENTRIES));
+    entries
+}
+
+/// 按 id 查找记录
+pub fn find(id: &str) -> Option<DocsQueryRecord> {
+    list(None).into_iter().find(|entry| entry.id == id)
+}
+
+/// 清空查询记录
+pub fn clear() -> Result<()> {
+    let _guard = HISTORY_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = history_file()?;
+    if path.exists() {
+        fs::remove_file(path)?;
+    }
+    Ok(())
+}
@@ -1,36 +1,57 @@
 use anyhow::Result;
 use rmcp::model::{ErrorData as McpError, Tool, ToolAnnotations, CallToolResult, Content};
 use reqwest::header::AUTHORIZATION;
-use reqwest::Client;
+use reqwest::{Client, RequestBuilder, Response};
 use serde_json::json;
 use std::borrow::Cow;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
 
-use super::types::{DocsRequest, DocsConfig, SearchResponse, SearchResult, docs_api_base_url, docs_website_url};
+use super::budget::{effective_max_chars, fit_to_budget, sanitize};
+use super::cache::{self, DEFAULT_CACHE_TTL_SECS};
+use super::dependencies::{detect_dependencies, resolve_locally, LibraryResolution};
+use super::history;
+use super::streaming::{split_into_parts, DocsProgress, MAX_PART_CHARS};
+use super::rate_limit::{self, MAX_RATE_LIMIT_RETRIES};
+use super::registry::resolve_alias;
+use super::relevance::{filter_by_topic, DEFAULT_CONTEXT_SECTIONS};
+use super::types::{DocsRequest, DocsConfig, SearchResponse, SearchResult, docs_website_url, resolve_docs_base_url};
 use crate::log_debug;
 use crate::log_important;
 
+/// Highest page number accepted by the docs API
+const MAX_PAGE: u32 = 10;
+
+/// Upper bound for pages merged into a single response
+const MAX_AGGREGATE_PAGES: u32 = 5;
+
+/// Result of fetching a single docs page
+enum PageContent {
+    Docs(String),
+    Empty,
+    NotFound,
+}
+
 /// Docs tool implementation
 pub struct DocsTool;
 
 impl DocsTool {
     /// Query framework documentation
-    pub async fn query_docs(request: DocsRequest) -> Result<CallToolResult, McpError> {
-        log_important!(info,
-            "Docs query: library={}, topic={:?}, version={:?}, page={:?}",
-            request.library, request.topic, request.version, request.page
-        );
-
+    ///
+    /// Large responses are delivered as several text parts; page fetches are reported as
+    /// progress notifications when the client supplied a progress token
+    pub async fn query_docs(request: DocsRequest, progress: Option<DocsProgress>) -> Result<CallToolResult, McpError> {
         let config = Self::get_config()
             .await
             .map_err(|e| McpError::internal_error(format!("Failed to get docs config: {}", e), None))?;
 
-        match Self::fetch_docs(&config, &request).await {
+        match Self::run_query(&config, request, progress.as_ref()).await {
             Ok(result) => {
-                log_important!(info, "Docs query success");
+                let parts = split_into_parts(&result, MAX_PART_CHARS);
+                log_important!(info, "Docs query success: {} chars in {} part(s)", result.len(), parts.len());
                 Ok(CallToolResult {
-                    content: vec![Content::text(result)],
+                    content: parts.into_iter().map(Content::text).collect(),
                     is_error: Some(false),
                     meta: None,
                     structured_content: None,
@@ -49,6 +70,53 @@ impl DocsTool {
         }
     }
 
+    /// Resolve the library, fetch the docs and record the query in the docs history
+    pub async fn run_query(config: &DocsConfig, request: DocsRequest, progress: Option<&DocsProgress>) -> Result<String> {
+        log_important!(info,
+            "Docs query: library={}, topic={:?}, version={:?}, page={:?}, max_pages={:?}, no_cache={:?}",
+            request.library, request.topic, request.version, request.page, request.max_pages, request.no_cache
+        );
+
+        let original = request.clone();
+        let mut request = request;
+
+        let resolved = resolve_alias(&config.library_aliases, &request.library);
+        if resolved != request.library {
+            log_important!(info, "Docs library alias resolved: {} -> {}", request.library, resolved);
+            request.library = resolved;
+        }
+
+        // Bare package name: resolve against the project's dependency manifests
+        if let Some(project_root) = request.project_root_path.clone().filter(|_| !request.library.contains('/')) {
+            let resolution = Self::resolve_library(config, &project_root, &request.library).await;
+            if let Some(library) = resolution.library {
+                log_important!(info,
+                    "Docs library resolved from project: {} -> {} (version={:?}, source={})",
+                    request.library, library, resolution.version, resolution.source
+                );
+                request.library = library;
+                if request.version.is_none() {
+                    request.version = resolution.version;
+                }
+            }
+        }
+
+        match request.action.as_deref().unwrap_or("query") {
+            "query" => {}
+            "list_versions" => return Self::list_versions(config, &request).await,
+            other => {
+                return Err(anyhow::anyhow!(
+                    "Unknown action '{}', expected 'query' or 'list_versions'",
+                    other
+                ))
+            }
+        }
+
+        let result = Self::fetch_docs(config, &request, progress).await;
+        history::record(&original, &request.library, &result);
+        result
+    }
+
     /// Get tool definition
     pub fn get_tool_definition() -> Tool {
         let schema = json!({
@@ -56,7 +124,7 @@ impl DocsTool {
             "properties": {
                 "library": {
                     "type": "string",
-                    "description": "Library identifier in format: owner/repo (e.g., vercel/next.js, facebook/react)"
+                    "description": "Library identifier in format: owner/repo (e.g., vercel/next.js, facebook/react), or a user-configured alias"
                 },
                 "topic": {
                     "type": "string",
@@ -71,6 +139,25 @@ impl DocsTool {
                     "description": "Page number (optional, default 1, max 10)",
                     "minimum": 1,
                     "maximum": 10
+                },
+                "max_pages": {
+                    "type": "integer",
+                    "description": "Fetch this many consecutive pages starting at `page` and merge them into one response (optional, default 1, max 5)",
+                    "minimum": 1,
+                    "maximum": 5
+                },
+                "project_root_path": {
+                    "type": "string",
+                    "description": "Project root (optional). When library is a bare package name (e.g. tokio), resolve it and its version from Cargo.toml/package.json/pyproject.toml"
+                },
+                "no_cache": {
+                    "type": "boolean",
+                    "description": "Bypass the local response cache and fetch fresh docs (optional, default false)"
+                },
+                "action": {
+                    "type": "string",
+                    "enum": ["query", "list_versions"],
+                    "description": "query (default) fetches docs; list_versions returns the versions indexed for the library, use one of them as `version` instead of guessing"
                 }
             },
             "required": ["library"]
@@ -98,19 +185,145 @@ impl DocsTool {
         }
     }
 
+    /// Resolve a bare package name to a library identifier and the version used by the project
+    ///
+    /// Tries user aliases and the built-in table first, then falls back to the library search API
+    pub async fn resolve_library(config: &DocsConfig, project_root: &str, name: &str) -> LibraryResolution {
+        let dependencies = detect_dependencies(Path::new(project_root));
+        let mut resolution = resolve_locally(&config.library_aliases, &dependencies, name);
+        if resolution.library.is_some() {
+            return resolution;
+        }
+
+        match Self::search_libraries(config, name).await {
+            Ok(results) => {
+                if let Some(first) = results.first() {
+                    resolution.library = Some(first.id.trim_start_matches('/').to_string());
+                    resolution.source = "search".to_string();
+                }
+            }
+            Err(e) => log_debug!("Library search failed while resolving '{}': {}", name, e),
+        }
+        resolution
+    }
+
     /// Get config
-    async fn get_config() -> Result<DocsConfig> {
+    pub(crate) async fn get_config() -> Result<DocsConfig> {
         let config = crate::config::load_standalone_config()
             .map_err(|e| anyhow::anyhow!("Failed to read config: {}", e))?;
 
         Ok(DocsConfig {
             api_key: config.mcp_config.docs_api_key,
-            base_url: docs_api_base_url(),
+            base_url: resolve_docs_base_url(config.mcp_config.docs_base_url.as_deref()),
+            cache_ttl_secs: config.mcp_config.docs_cache_ttl_secs,
+            library_aliases: config.mcp_config.docs_library_aliases.unwrap_or_default(),
+            topic_filter: config.mcp_config.docs_topic_filter.unwrap_or(true),
+            topic_filter_context: config
+                .mcp_config
+                .docs_topic_filter_context
+                .unwrap_or(DEFAULT_CONTEXT_SECTIONS),
+            max_chars: effective_max_chars(config.mcp_config.docs_max_chars),
         })
     }
 
-    /// Fetch docs via HTTP
-    async fn fetch_docs(config: &DocsConfig, request: &DocsRequest) -> Result<String> {
+    /// Fetch docs: a single page, or pages `page..page+max_pages-1` merged into one response
+    async fn fetch_docs(config: &DocsConfig, request: &DocsRequest, progress: Option<&DocsProgress>) -> Result<String> {
+        let first_page = request.page.unwrap_or(1).clamp(1, MAX_PAGE);
+        let page_count = request
+            .max_pages
+            .unwrap_or(1)
+            .clamp(1, MAX_AGGREGATE_PAGES)
+            .min(MAX_PAGE - first_page + 1);
+        let aggregate = page_count > 1;
+
+        let mut pages: Vec<(u32, String)> = Vec::new();
+        let mut stop_note = None;
+        for page in first_page..first_page + page_count {
+            let page_request = if aggregate {
+                DocsRequest { page: Some(page), ..request.clone() }
+            } else {
+                request.clone()
+            };
+            if let Some(progress) = progress {
+                progress
+                    .report(page - first_page, page_count, format!("Fetching {} page {}", request.library, page))
+                    .await;
+            }
+            match Self::fetch_page(config, &page_request).await {
+                Ok(PageContent::Docs(content)) => {
+                    pages.push((page, Self::apply_topic_filter(config, request, sanitize(&content))))
+                }
+                Ok(PageContent::Empty) => break,
+                Ok(PageContent::NotFound) if pages.is_empty() => {
+                    log_important!(info, "Library '{}' not found, triggering search", request.library);
+                    return Self::handle_not_found_with_search(config, request).await;
+                }
+                Ok(PageContent::NotFound) => break,
+                Err(e) if pages.is_empty() => return Err(e),
+                Err(e) => {
+                    log_important!(warn, "Docs page {} failed, returning earlier pages: {}", page, e);
+                    stop_note = Some(format!("Stopped at page {}: {}", page, e));
+                    break;
+                }
+            }
+        }
+
+        // Share the length budget evenly between the merged pages
+        if let Some(max_chars) = config.max_chars.filter(|_| !pages.is_empty()) {
+            let per_page = max_chars / pages.len();
+            for (_, content) in pages.iter_mut() {
+                *content = Self::apply_budget(content, per_page);
+            }
+        }
+
+        match pages.as_slice() {
+            [] => Ok("No documentation found. Try adjusting query parameters.".to_string()),
+            [(_, content)] if !aggregate => Ok(Self::format_text_response(content, request)),
+            _ => Ok(Self::format_multi_page_response(&pages, request, page_count, stop_note.as_deref())),
+        }
+    }
+
+    /// Keep only sections whose headings match the topic keywords, noting how much was dropped
+    fn apply_topic_filter(config: &DocsConfig, request: &DocsRequest, content: String) -> String {
+        let Some(topic) = request.topic.as_deref().filter(|_| config.topic_filter) else {
+            return content;
+        };
+        match filter_by_topic(&content, topic, config.topic_filter_context) {
+            Some(filtered) => {
+                log_debug!("Docs topic filter kept {}/{} sections", filtered.kept, filtered.total);
+                format!(
+                    "> Showing {} of {} sections matching topic \"{}\"; query without a topic for the full page.\n\n{}",
+                    filtered.kept, filtered.total, topic, filtered.content
+                )
+            }
+            None => content,
+        }
+    }
+
+    /// Trim content that exceeds the length budget, keeping headings and code blocks
+    fn apply_budget(content: &str, max_chars: usize) -> String {
+        match fit_to_budget(content, max_chars) {
+            Some(budgeted) => {
+                log_important!(info,
+                    "Docs response truncated: {} -> {} chars",
+                    budgeted.original_chars, budgeted.content.chars().count()
+                );
+                format!("{}\n\n{}", budgeted.note(max_chars), budgeted.content)
+            }
+            None => content.to_string(),
+        }
+    }
+
+    /// Fetch a single page via HTTP, serving from the on-disk cache when a fresh entry exists
+    async fn fetch_page(config: &DocsConfig, request: &DocsRequest) -> Result<PageContent> {
+        let ttl_secs = config.cache_ttl_secs.unwrap_or(DEFAULT_CACHE_TTL_SECS);
+        if !request.no_cache.unwrap_or(false) {
+            if let Some(cached) = cache::get(&config.base_url, request, ttl_secs) {
+                log_important!(info, "Docs cache hit: library={}, page={:?}", request.library, request.page);
+                return Ok(PageContent::Docs(cached));
+            }
+        }
+
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()?;
@@ -137,7 +350,7 @@ impl DocsTool {
             req_builder = req_builder.query(&[("page", page.to_string())]);
         }
 
-        let response = req_builder.send().await?;
+        let response = Self::send_with_rate_limit_retry(req_builder, config.api_key.is_some()).await?;
         let status = response.status();
 
         log_debug!("Docs response status: {}", status);
@@ -146,8 +359,14 @@ impl DocsTool {
             let error_text = response.text().await.unwrap_or_else(|_| "Unable to read error".to_string());
 
             if status.as_u16() == 404 {
-                log_important!(info, "Library '{}' not found, triggering search", request.library);
-                return Self::handle_not_found_with_search(config, request).await;
+                return Ok(PageContent::NotFound);
+            }
+            if status.as_u16() == 429 {
+                return Err(anyhow::anyhow!(
+                    "API request failed (status: {}): {}",
+                    status,
+                    rate_limit::describe_limit(rate_limit::latest().as_ref())
+                ));
             }
 
             return Err(anyhow::anyhow!(
@@ -160,10 +379,40 @@ impl DocsTool {
         let response_text = response.text().await?;
 
         if response_text.trim().is_empty() {
-            return Ok("No documentation found. Try adjusting query parameters.".to_string());
+            return Ok(PageContent::Empty);
+        }
+
+        if ttl_secs > 0 {
+            cache::put(&config.base_url, request, &response_text);
         }
+        Ok(PageContent::Docs(response_text))
+    }
+
+    /// Send a request, recording rate-limit headers and waiting out short 429 windows
+    async fn send_with_rate_limit_retry(req_builder: RequestBuilder, with_api_key: bool) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            let request = req_builder
+                .try_clone()
+                .ok_or_else(|| anyhow::anyhow!("Failed to clone docs request"))?;
+            let response = request.send().await?;
+            if let Some(info) = rate_limit::from_headers(response.headers(), with_api_key) {
+                rate_limit::record(&info);
+            }
 
-        Ok(Self::format_text_response(&response_text, request))
+            if response.status().as_u16() != 429 || attempt >= MAX_RATE_LIMIT_RETRIES {
+                return Ok(response);
+            }
+            let Some(delay) = rate_limit::retry_delay(response.headers()) else {
+                return Ok(response);
+            };
+            attempt += 1;
+            log_important!(warn,
+                "Docs API rate limited, retrying in {}s ({}/{})",
+                delay.as_secs(), attempt, MAX_RATE_LIMIT_RETRIES
+            );
+            tokio::time::sleep(delay).await;
+        }
     }
 
     /// Format error message
@@ -201,6 +450,45 @@ impl DocsTool {
         output
     }
 
+    /// Format several pages into one Markdown response with per-page source markers
+    fn format_multi_page_response(
+        pages: &[(u32, String)],
+        request: &DocsRequest,
+        requested_pages: u32,
+        stop_note: Option<&str>,
+    ) -> String {
+        let first = pages.first().map(|(p, _)| *p).unwrap_or(1);
+        let last = pages.last().map(|(p, _)| *p).unwrap_or(first);
+        let mut output = String::new();
+
+        output.push_str(&format!("# {} Documentation\n\n", request.library));
+
+        if let Some(topic) = &request.topic {
+            output.push_str(&format!("**Topic**: {}\n", topic));
+        }
+        if let Some(version) = &request.version {
+            output.push_str(&format!("**Version**: {}\n", version));
+        }
+        output.push_str(&format!("**Pages**: {}-{} ({} merged)\n", first, last, pages.len()));
+        output.push_str("\n---\n\n");
+
+        for (page, content) in pages {
+            output.push_str(&format!("<!-- page {} -->\n## Page {}\n\n", page, page));
+            output.push_str(content.trim_end());
+            output.push_str(&format!("\n\n*Source: Docs - {} (page {})*\n\n---\n\n", request.library, page));
+        }
+
+        if let Some(note) = stop_note {
+            output.push_str(&format!("**Note**: {}\n\n", note));
+        } else if pages.len() as u32 == requested_pages && last < MAX_PAGE {
+            output.push_str(&format!("More pages may be available, continue with page {}.\n\n", last + 1));
+        }
+
+        output.push_str(&format!("Source: Docs - {}\n", request.library));
+
+        output
+    }
+
     /// Handle 404 error: search for candidate libraries
     async fn handle_not_found_with_search(
         config: &DocsConfig,
@@ -229,8 +517,84 @@ impl DocsTool {
         }
     }
 
-    /// Search libraries
+    /// List the versions indexed for a library, marking the version requested or used by the project
+    async fn list_versions(config: &DocsConfig, request: &DocsRequest) -> Result<String> {
+        let library = request.library.trim_start_matches('/');
+        let search_query = library.rsplit('/').next().unwrap_or(library);
+
+        let results = Self::search_catalog(config, search_query).await?;
+        let Some(entry) = results
+            .iter()
+            .find(|r| r.id.trim_start_matches('/').eq_ignore_ascii_case(library))
+        else {
+            let suggestions: Vec<SearchResult> = results.into_iter().take(5).collect();
+            return Ok(if suggestions.is_empty() {
+                Self::format_not_found_no_suggestions(library)
+            } else {
+                Self::format_not_found_with_suggestions(library, &suggestions)
+            });
+        };
+
+        log_important!(info, "Docs versions for {}: {}", library, entry.versions.len());
+        Ok(Self::format_versions_response(library, entry, request.version.as_deref()))
+    }
+
+    /// Format the version catalog of a library
+    fn format_versions_response(library: &str, entry: &SearchResult, wanted: Option<&str>) -> String {
+        let mut output = format!("# {} Versions
+
+", library);
+
+        if let Some(branch) = &entry.branch {
+            output.push_str(&format!("**Default branch**: {} (used when `version` is omitted)
+
+", branch));
+        }
+
+        if entry.versions.is_empty() {
+            output.push_str("No versioned docs are indexed for this library; query without `version` to get the latest docs.
+");
+            return output;
+        }
+
+        let is_wanted = |v: &str| {
+            wanted.is_some_and(|w| v.trim_start_matches('v').eq_ignore_ascii_case(w.trim_start_matches('v')))
+        };
+        for version in &entry.versions {
+            if is_wanted(version) {
+                output.push_str(&format!("- {} ← requested
+", version));
+            } else {
+                output.push_str(&format!("- {}
+", version));
+            }
+        }
+
+        if let Some(wanted) = wanted.filter(|_| !entry.versions.iter().any(|v| is_wanted(v))) {
+            output.push_str(&format!(
+                "
+**Note**: {} is not indexed; pick the closest version above or omit `version`.
+",
+                wanted
+            ));
+        }
+        output.push_str(&format!(
+            "
+Use one of these as `version`, e.g. {{ \"library\": \"{}\", \"version\": \"{}\" }}
+",
+            library, entry.versions[0]
+        ));
+
+        output
+    }
+
+    /// Search libraries (top 5 matches)
     async fn search_libraries(config: &DocsConfig, query: &str) -> Result<Vec<SearchResult>> {
+        Ok(Self::search_catalog(config, query).await?.into_iter().take(5).collect())
+    }
+
+    /// Query the library catalog, returning every match with its metadata
+    async fn search_catalog(config: &DocsConfig, query: &str) -> Result<Vec<SearchResult>> {
         let client = Client::builder()
             .timeout(Duration::from_secs(15))
             .build()?;
@@ -255,7 +619,7 @@ impl DocsTool {
         let search_response: SearchResponse = serde_json::from_str(&response_text)
             .map_err(|e| anyhow::anyhow!("Failed to parse search response: {}", e))?;
 
-        Ok(search_response.results.into_iter().take(5).collect())
+        Ok(search_response.results)
     }
 
     /// Format 404 error message (no suggestions)
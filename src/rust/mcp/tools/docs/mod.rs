@@ -1,7 +1,15 @@
 pub mod types;
 pub mod mcp;
 pub mod commands;
+pub mod cache;
+pub mod registry;
+pub mod relevance;
+pub mod dependencies;
+pub mod rate_limit;
+pub mod history;
+pub mod streaming;
+pub mod budget;
 
 pub use mcp::DocsTool;
 pub use types::{DocsRequest, DocsConfig};
-pub use commands::{test_docs_connection, get_docs_config, save_docs_config};
+pub use commands::{test_docs_connection, get_docs_config, save_docs_config, clear_docs_cache, get_docs_library_registry, save_docs_library_registry, resolve_docs_library, get_docs_query_history, rerun_docs_query, clear_docs_query_history};
@@ -0,0 +1,166 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::log_debug;
+
+/// 429 后自动重试的最长等待时间（秒），超过则直接返回错误
+pub const MAX_RETRY_WAIT_SECS: u64 = 60;
+
+/// 429 后最多自动重试次数
+pub const MAX_RATE_LIMIT_RETRIES: usize = 2;
+
+/// 未给出重置时间时的默认等待（秒）
+const DEFAULT_RETRY_WAIT_SECS: u64 = 5;
+
+/// 重置时间最多记录到此时长之后（秒），避免异常响应头让额度长期显示为耗尽
+const MAX_RESET_SECS: u64 = 24 * 60 * 60;
+
+/// 最近一次响应中的速率限制信息
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RateLimitInfo {
+    /// 周期内的请求上限
+    pub limit: Option<u64>,
+    /// 剩余请求数
+    pub remaining: Option<u64>,
+    /// 额度重置时间（Unix 秒）
+    pub reset_at: Option<u64>,
+    /// 记录时间（Unix 秒）
+    pub observed_at: u64,
+    /// 是否使用了 API Key（免费模式与 Key 模式额度不同）
+    pub with_api_key: bool,
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn header_u64(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names.iter().find_map(|name| {
+        headers
+            .get(*name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse::<f64>().ok())
+            .map(|v| v.max(0.0) as u64)
+    })
+}
+
+/// 解析速率限制响应头（X-RateLimit-* / RateLimit-*），均不存在时返回 None
+///
+/// 重置时间既可能是 Unix 时间戳，也可能是距重置的秒数，按数值大小区分；
+/// 结果不超过 MAX_RESET_SECS 之后
+pub fn from_headers(headers: &HeaderMap, with_api_key: bool) -> Option<RateLimitInfo> {
+    let now = now_secs();
+    let limit = header_u64(headers, &["x-ratelimit-limit", "ratelimit-limit"]);
+    let remaining = header_u64(headers, &["x-ratelimit-remaining", "ratelimit-remaining"]);
+    let reset_at = header_u64(headers, &["x-ratelimit-reset", "ratelimit-reset"])
+        .map(|reset| if reset > 1_000_000_000 { reset } else { now.saturating_add(reset) })
+        .or_else(|| header_u64(headers, &[RETRY_AFTER.as_str()]).map(|secs| now.saturating_add(secs)))
+        .map(|reset| reset.min(now.saturating_add(MAX_RESET_SECS)));
+
+    if limit.is_none() && remaining.is_none() && reset_at.is_none() {
+        return None;
+    }
+    Some(RateLimitInfo {
+        limit,
+        remaining,
+        reset_at,
+        observed_at: now,
+        with_api_key,
+    })
+}
+
+/// 429 后的重试等待时间：优先 Retry-After，其次重置时间；超过上限时返回 None
+pub fn retry_delay(headers: &HeaderMap) -> Option<Duration> {
+    let now = now_secs();
+    let wait = header_u64(headers, &[RETRY_AFTER.as_str()])
+        .or_else(|| from_headers(headers, false).and_then(|info| info.reset_at).map(|reset| reset.saturating_sub(now)))
+        .unwrap_or(DEFAULT_RETRY_WAIT_SECS)
+        .max(1);
+    (wait <= MAX_RETRY_WAIT_SECS).then_some(Duration::from_secs(wait))
+}
+
+/// 状态文件（MCP 进程写入，设置界面读取）
+fn state_file() -> Option<PathBuf> {
+    let root = dirs::cache_dir()
+        .or_else(dirs::data_dir)
+        .or_else(dirs::config_dir)?;
+    let dir = root.join("devkit");
+    fs::create_dir_all(&dir).ok()?;
+    Some(dir.join("docs_rate_limit.json"))
+}
+
+/// 记录最近一次速率限制信息
+pub fn record(info: &RateLimitInfo) {
+    let result = state_file()
+        .ok_or_else(|| anyhow::anyhow!("无法获取缓存目录"))
+        .and_then(|path| Ok(fs::write(path, serde_json::to_string(info)?)?));
+    if let Err(e) = result {
+        log_debug!("Failed to record docs rate limit: {}", e);
+    }
+}
+
+/// 读取最近一次速率限制信息（已过重置时间的剩余额度不再可信，标记为未知）
+pub fn latest() -> Option<RateLimitInfo> {
+    let data = fs::read_to_string(state_file()?).ok()?;
+    let mut info: RateLimitInfo = serde_json::from_str(&data).ok()?;
+    if info.reset_at.is_some_and(|reset| reset <= now_secs()) {
+        info.remaining = None;
+        info.reset_at = None;
+    }
+    Some(info)
+}
+
+/// 429 错误提示：附上额度重置时间
+pub fn describe_limit(info: Option<&RateLimitInfo>) -> String {
+    let reset_in = info
+        .and_then(|i| i.reset_at)
+        .map(|reset| reset.saturating_sub(now_secs()));
+    match reset_in {
+        Some(secs) => format!("Rate limit reached (resets in {}s), consider configuring an API Key", secs),
+        None => "Rate limit reached, consider configuring an API Key".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_parse_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        assert!(from_headers(&headers, false).is_none());
+
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("60"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("30"));
+        let info = from_headers(&headers, true).unwrap();
+        assert_eq!(info.limit, Some(60));
+        assert_eq!(info.remaining, Some(0));
+        assert!(info.reset_at.unwrap() >= info.observed_at + 30);
+        assert!(retry_delay(&headers).unwrap() <= Duration::from_secs(30));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("3600"));
+        assert!(retry_delay(&headers).is_none());
+    }
+
+    #[test]
+    fn test_reset_is_clamped() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("18446744073709551615"));
+        let info = from_headers(&headers, false).unwrap();
+        assert_eq!(info.reset_at, Some(info.observed_at + MAX_RESET_SECS));
+
+        headers.remove("x-ratelimit-reset");
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("1e30"));
+        let info = from_headers(&headers, false).unwrap();
+        assert_eq!(info.reset_at, Some(info.observed_at + MAX_RESET_SECS));
+        assert!(retry_delay(&headers).is_none());
+    }
+}
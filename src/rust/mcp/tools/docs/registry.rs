@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 库别名与收藏列表
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LibraryRegistry {
+    /// 别名 -> 库标识符 (例如: "react" -> "facebook/react")
+    pub aliases: HashMap<String, String>,
+    /// 收藏的库标识符
+    pub favorites: Vec<String>,
+}
+
+/// 将别名解析为完整库标识符；别名不区分大小写，未命中时原样返回
+pub fn resolve_alias(aliases: &HashMap<String, String>, library: &str) -> String {
+    let key = library.trim();
+    aliases
+        .iter()
+        .find(|(alias, _)| alias.trim().eq_ignore_ascii_case(key))
+        .map(|(_, target)| target.trim().trim_start_matches('/').to_string())
+        .filter(|target| !target.is_empty())
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// 规范化用户输入：去除空白与空项，别名键统一为小写，收藏去重并保持顺序
+pub fn normalize(registry: LibraryRegistry) -> LibraryRegistry {
+    let aliases = registry
+        .aliases
+        .into_iter()
+        .map(|(alias, target)| (alias.trim().to_lowercase(), target.trim().trim_start_matches('/').to_string()))
+        .filter(|(alias, target)| !alias.is_empty() && !target.is_empty())
+        .collect();
+
+    let mut favorites: Vec<String> = Vec::new();
+    for favorite in registry.favorites {
+        let favorite = favorite.trim().trim_start_matches('/').to_string();
+        if !favorite.is_empty() && !favorites.contains(&favorite) {
+            favorites.push(favorite);
+        }
+    }
+
+    LibraryRegistry { aliases, favorites }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_alias() {
+        let aliases = HashMap::from([
+            ("react".to_string(), "facebook/react".to_string()),
+            ("Spring".to_string(), "/spring-projects/spring-framework".to_string()),
+        ]);
+        assert_eq!(resolve_alias(&aliases, "React"), "facebook/react");
+        assert_eq!(resolve_alias(&aliases, " spring "), "spring-projects/spring-framework");
+        assert_eq!(resolve_alias(&aliases, "vercel/next.js"), "vercel/next.js");
+    }
+}
@@ -0,0 +1,133 @@
+/// 默认保留的相邻章节数（命中章节前后各 N 个）
+pub const DEFAULT_CONTEXT_SECTIONS: usize = 0;
+
+/// 过滤时忽略的常见词
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "the", "of", "in", "on", "for", "to", "with", "how", "what", "use", "using", "is",
+];
+
+/// 按主题关键词过滤后的结果
+#[derive(Debug, PartialEq)]
+pub struct FilteredDocs {
+    pub content: String,
+    /// 保留的章节数
+    pub kept: usize,
+    /// 总章节数
+    pub total: usize,
+}
+
+/// 从主题中提取关键词：按空白与标点切分、转小写，去掉过短的词与常见词
+pub fn topic_keywords(topic: &str) -> Vec<String> {
+    let mut keywords: Vec<String> = Vec::new();
+    for word in topic
+        .split(|c: char| c.is_whitespace() || (c.is_ascii_punctuation() && c != '.' && c != '_'))
+        .map(|w| w.trim_matches('.').to_lowercase())
+    {
+        if word.chars().count() >= 2 && !STOPWORDS.contains(&word.as_str()) && !keywords.contains(&word) {
+            keywords.push(word);
+        }
+    }
+    keywords
+}
+
+/// 只保留标题命中主题关键词的章节（及其前后 context 个章节）
+///
+/// 章节以 Markdown 标题行划分，第一个标题之前的内容视为前言并始终保留。
+/// 没有可用关键词、章节数不足或没有任何章节命中时返回 None，调用方应使用原文
+pub fn filter_by_topic(markdown: &str, topic: &str, context: usize) -> Option<FilteredDocs> {
+    let keywords = topic_keywords(topic);
+    if keywords.is_empty() {
+        return None;
+    }
+
+    let (preamble, sections) = split_sections(markdown);
+    if sections.len() < 2 {
+        return None;
+    }
+
+    let matched: Vec<usize> = sections
+        .iter()
+        .enumerate()
+        .filter(|(_, section)| {
+            let heading = section.lines().next().unwrap_or("").to_lowercase();
+            keywords.iter().any(|k| heading.contains(k.as_str()))
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+    if matched.is_empty() || matched.len() == sections.len() {
+        return None;
+    }
+
+    let mut keep = vec![false; sections.len()];
+    for idx in matched {
+        let start = idx.saturating_sub(context);
+        let end = (idx + context).min(sections.len() - 1);
+        keep[start..=end].iter_mut().for_each(|k| *k = true);
+    }
+
+    let mut content = preamble.to_string();
+    let mut kept = 0;
+    for (section, _) in sections.iter().zip(&keep).filter(|(_, k)| **k) {
+        content.push_str(section);
+        kept += 1;
+    }
+
+    Some(FilteredDocs {
+        content,
+        kept,
+        total: sections.len(),
+    })
+}
+
+/// 按 Markdown 标题行切分（忽略代码块中的 # 行），返回（前言, 章节列表）
+fn split_sections(markdown: &str) -> (&str, Vec<&str>) {
+    let mut starts: Vec<usize> = Vec::new();
+    let mut in_code = false;
+    let mut offset = 0;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+        } else if !in_code && is_heading(trimmed) {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    let preamble = &markdown[..starts.first().copied().unwrap_or(markdown.len())];
+    let sections = starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| &markdown[start..starts.get(i + 1).copied().unwrap_or(markdown.len())])
+        .collect();
+    (preamble, sections)
+}
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = "intro\n## Routing basics\nroutes\n```sh\n# not a heading\n```\n## Data fetching\nfetch\n## Dynamic routes\nslug\n## Styling\ncss\n";
+
+    #[test]
+    fn test_filter_by_topic() {
+        let filtered = filter_by_topic(DOC, "routing and routes", 0).unwrap();
+        assert_eq!(filtered.total, 4);
+        assert_eq!(filtered.kept, 2);
+        assert!(filtered.content.starts_with("intro\n## Routing basics"));
+        assert!(filtered.content.contains("# not a heading"));
+        assert!(!filtered.content.contains("Styling"));
+
+        let with_context = filter_by_topic(DOC, "styling", 1).unwrap();
+        assert_eq!(with_context.kept, 2);
+        assert!(with_context.content.contains("## Dynamic routes"));
+
+        assert!(filter_by_topic(DOC, "authentication", 0).is_none());
+        assert!(filter_by_topic(DOC, "the", 0).is_none());
+    }
+}
@@ -0,0 +1,108 @@
+use rmcp::model::{ProgressNotificationParam, ProgressToken};
+use rmcp::{Peer, RoleServer};
+
+use crate::log_debug;
+
+/// 单个内容分片的最大字符数，超过后拆成多个 Content::text
+pub const MAX_PART_CHARS: usize = 32_000;
+
+/// MCP 进度通知（客户端在请求中携带 progressToken 时才会发送）
+#[derive(Clone)]
+pub struct DocsProgress {
+    peer: Peer<RoleServer>,
+    token: ProgressToken,
+}
+
+impl DocsProgress {
+    pub fn new(peer: Peer<RoleServer>, token: ProgressToken) -> Self {
+        Self { peer, token }
+    }
+
+    /// 发送进度通知，失败只记录日志
+    pub async fn report(&self, progress: u32, total: u32, message: String) {
+        let param = ProgressNotificationParam {
+            progress_token: self.token.clone(),
+            progress: progress as f64,
+            total: Some(total as f64),
+            message: Some(message),
+        };
+        if let Err(e) = self.peer.notify_progress(param).await {
+            log_debug!("Failed to send docs progress notification: {}", e);
+        }
+    }
+}
+
+/// 将较长的文档拆分为多个分片，尽量在标题或空行处断开（不拆开代码块），
+/// 除最后一片外每片末尾附续接标记，客户端可先渲染已到达的分片
+pub fn split_into_parts(content: &str, max_chars: usize) -> Vec<String> {
+    if content.chars().count() <= max_chars {
+        return vec![content.to_string()];
+    }
+
+    let mut parts: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+    // 最近一个可断开位置（字节偏移）
+    let mut last_break: Option<usize> = None;
+    let mut in_code = false;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let line_chars = line.chars().count();
+
+        if !in_code && (trimmed.starts_with('#') || trimmed.trim().is_empty()) && !current.is_empty() {
+            last_break = Some(current.len());
+        }
+        if trimmed.starts_with("```") {
+            in_code = !in_code;
+        }
+
+        if current_chars + line_chars > max_chars && !current.is_empty() {
+            // 优先在可断开位置拆分，否则在当前行前硬拆
+            let split_at = last_break.filter(|&at| at > 0).unwrap_or(current.len());
+            let rest = current.split_off(split_at);
+            parts.push(current);
+            current = rest;
+            current_chars = current.chars().count();
+            last_break = None;
+        }
+
+        current.push_str(line);
+        current_chars += line_chars;
+    }
+    if !current.is_empty() {
+        parts.push(current);
+    }
+
+    let total = parts.len();
+    parts
+        .into_iter()
+        .enumerate()
+        .map(|(idx, part)| {
+            if idx + 1 < total {
+                format!("{}\n\n[part {}/{} — continued in next part]", part.trim_end(), idx + 1, total)
+            } else if total > 1 {
+                format!("[part {}/{}]\n\n{}", idx + 1, total, part)
+            } else {
+                part
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_into_parts() {
+        assert_eq!(split_into_parts("short", 100), vec!["short".to_string()]);
+
+        let doc = "# A\naaaa aaaa\n\n# B\n```\nline one\n\nline two\n```\n# C\ncccc\n";
+        let parts = split_into_parts(doc, 30);
+        assert!(parts.len() > 1);
+        assert!(parts[0].ends_with(&format!("[part 1/{} — continued in next part]", parts.len())));
+        // 代码块不会在中间的空行处被拆开
+        assert!(parts.iter().any(|p| p.contains("line one\n\nline two")));
+    }
+}
@@ -1,4 +1,9 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::budget::DEFAULT_MAX_CHARS;
+use super::rate_limit::RateLimitInfo;
+use super::relevance::DEFAULT_CONTEXT_SECTIONS;
 
 /// Docs 查询请求参数
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
@@ -18,6 +23,22 @@ pub struct DocsRequest {
     #[schemars(description = "分页页码 (可选，默认1，最大10)")]
     #[serde(default)]
     pub page: Option<u32>,
+    /// 合并的页数 (可选，默认1，最大5)：从 page 开始连续获取多页并合并为一个响应
+    #[schemars(description = "合并的页数 (可选，默认1，最大5)：从 page 开始连续获取多页并合并为一个响应")]
+    #[serde(default)]
+    pub max_pages: Option<u32>,
+    /// 项目根目录 (可选)：library 为裸包名时，根据项目依赖清单解析库标识符与版本
+    #[schemars(description = "项目根目录 (可选)：library 为裸包名时，根据项目依赖清单解析库标识符与版本")]
+    #[serde(default)]
+    pub project_root_path: Option<String>,
+    /// 跳过本地缓存，强制重新查询 (可选，默认 false)
+    #[schemars(description = "跳过本地缓存，强制重新查询 (可选，默认 false)")]
+    #[serde(default)]
+    pub no_cache: Option<bool>,
+    /// 操作类型 (可选): query (默认，查询文档) / list_versions (列出库的可用版本)
+    #[schemars(description = "操作类型 (可选): query (默认，查询文档) / list_versions (列出库的可用版本)")]
+    #[serde(default)]
+    pub action: Option<String>,
 }
 
 /// Docs 配置
@@ -27,6 +48,16 @@ pub struct DocsConfig {
     pub api_key: Option<String>,
     /// API 基础 URL
     pub base_url: String,
+    /// 查询结果缓存有效期（秒），None 使用默认值，0 表示不缓存
+    pub cache_ttl_secs: Option<u64>,
+    /// 库别名 -> 库标识符
+    pub library_aliases: HashMap<String, String>,
+    /// 是否按主题关键词过滤返回的章节
+    pub topic_filter: bool,
+    /// 主题过滤时额外保留命中章节前后的章节数
+    pub topic_filter_context: usize,
+    /// 响应字符数上限，None 表示不限制
+    pub max_chars: Option<usize>,
 }
 
 pub fn docs_website_url() -> String {
@@ -37,11 +68,25 @@ pub fn docs_api_base_url() -> String {
     format!("{}/api/v2", docs_website_url())
 }
 
+/// 解析配置的 API 基础 URL：为空时使用官方地址，去除末尾的斜杠
+pub fn resolve_docs_base_url(configured: Option<&str>) -> String {
+    configured
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(docs_api_base_url)
+}
+
 impl Default for DocsConfig {
     fn default() -> Self {
         Self {
             api_key: None,
             base_url: docs_api_base_url(),
+            cache_ttl_secs: None,
+            library_aliases: HashMap::new(),
+            topic_filter: true,
+            topic_filter_context: DEFAULT_CONTEXT_SECTIONS,
+            max_chars: Some(DEFAULT_MAX_CHARS),
         }
     }
 }
@@ -86,6 +131,8 @@ pub struct TestConnectionResponse {
     pub message: String,
     /// 文档预览 (可选)
     pub preview: Option<String>,
+    /// 本次请求返回的速率限制信息 (可选)
+    pub rate_limit: Option<RateLimitInfo>,
 }
 
 /// 库搜索响应
@@ -112,5 +159,24 @@ pub struct SearchResult {
     /// 基准测试分数
     #[serde(rename = "benchmarkScore")]
     pub benchmark_score: Option<f64>,
+    /// 默认分支（未指定版本时查询的内容）
+    #[serde(default)]
+    pub branch: Option<String>,
+    /// 已收录文档的版本列表
+    #[serde(default)]
+    pub versions: Vec<String>,
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_docs_base_url() {
+        assert_eq!(resolve_docs_base_url(None), docs_api_base_url());
+        assert_eq!(resolve_docs_base_url(Some("  ")), docs_api_base_url());
+        assert_eq!(resolve_docs_base_url(Some(" https://mirror.internal/api/v2// ")), "https://mirror.internal/api/v2");
+        assert_eq!(resolve_docs_base_url(Some("http://localhost:8080")), "http://localhost:8080");
+    }
+}
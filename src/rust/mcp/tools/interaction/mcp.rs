@@ -10,23 +10,33 @@ use tokio::time::{sleep, Duration, Instant};
 use crate::config::load_standalone_config;
 use crate::mcp::{CacheRequest, PopupRequest};
 use crate::mcp::save_history_entry;
+use crate::mcp::history_stats::record_cancelled_interaction;
+use crate::mcp::notify_webhook::{notify_prompt_answered, notify_prompt_opened};
 use crate::mcp::handlers::{find_ui_command, parse_mcp_response};
 use crate::mcp::utils::{generate_request_id, popup_error};
 
-fn should_skip_history_save(response_str: &str) -> bool {
+fn is_cancelled_response(response_str: &str) -> bool {
     let s = response_str.trim();
-    s.is_empty() || s == "CANCELLED" || s == "\"CANCELLED\""
+    s == "CANCELLED" || s == "\"CANCELLED\""
 }
 
 fn try_save_history(request: Option<PopupRequest>, response_str: &str) {
-    if should_skip_history_save(response_str) {
+    if is_cancelled_response(response_str) {
+        // 取消的交互不保存历史，只记录用于统计取消率
+        if let Err(e) = record_cancelled_interaction(request.as_ref()) {
+            log::warn!("记录取消的交互失败: {}", e);
+        }
         return;
     }
-
     let s = response_str.trim();
+    if s.is_empty() {
+        return;
+    }
+
     let response_value: serde_json::Value = serde_json::from_str(s)
         .unwrap_or_else(|_| serde_json::Value::String(s.to_string()));
 
+    notify_prompt_answered(request.as_ref(), &response_value);
     if let Err(e) = save_history_entry(request, response_value) {
         log::warn!("保存 MCP 历史记录失败: {}", e);
     }
@@ -232,6 +242,8 @@ impl InteractionTool {
             },
             chalkboard: request.format,
             project_root_path: request.project_root_path,
+            session_id: request.session_id,
+            requested_at: Some(chrono::Utc::now().to_rfc3339()),
         };
 
         // Create temp files
@@ -276,6 +288,7 @@ impl InteractionTool {
         std::thread::spawn(move || {
             let _ = child.wait();
         });
+        notify_prompt_opened(&popup_request);
 
         persist_task(&PersistedPendingTask {
             task_id: task_id.clone(),
@@ -385,6 +398,8 @@ impl InteractionTool {
             },
             chalkboard: request.format,
             project_root_path: request.project_root_path,
+            session_id: request.session_id,
+            requested_at: Some(chrono::Utc::now().to_rfc3339()),
         };
 
         let temp_dir = std::env::temp_dir();
@@ -425,6 +440,7 @@ impl InteractionTool {
         std::thread::spawn(move || {
             let _ = child.wait();
         });
+        notify_prompt_opened(&popup_request);
 
         persist_task(&PersistedPendingTask {
             task_id: task_id.clone(),
@@ -629,8 +645,11 @@ impl InteractionTool {
             },
             chalkboard: request.format,
             project_root_path: request.project_root_path,
+            session_id: request.session_id,
+            requested_at: Some(chrono::Utc::now().to_rfc3339()),
         };
 
+        notify_prompt_opened(&popup_request);
         match crate::mcp::handlers::create_tauri_popup(&popup_request) {
             Ok(response) => {
                 try_save_history(Some(popup_request.clone()), &response);
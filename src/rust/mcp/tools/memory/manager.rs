@@ -1,5 +1,6 @@
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use ring::digest::{digest, SHA256};
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -9,6 +10,8 @@ use super::types::{MemoryEntry, MemoryCategory, MemoryMetadata};
 pub struct MemoryManager {
     memory_dir: PathBuf,
     project_path: String,
+    /// 可选的上下文键（如 git 分支名），用于按分支/特性隔离记忆
+    context_key: Option<String>,
 }
 
 impl MemoryManager {
@@ -29,6 +32,7 @@ impl MemoryManager {
         let manager = Self {
             memory_dir,
             project_path: normalized_path.to_string_lossy().to_string(),
+            context_key: None,
         };
 
         // 初始化记忆文件结构
@@ -37,6 +41,37 @@ impl MemoryManager {
         Ok(manager)
     }
 
+    /// 设置上下文键（如 git 分支名）
+    ///
+    /// 设置后新增记忆写入 `contexts/<key>/`，读取时分支记忆合并覆盖项目级记忆
+    pub fn with_context_key(mut self, context_key: Option<&str>) -> Result<Self> {
+        let key = context_key.map(str::trim).filter(|k| !k.is_empty());
+        if matches!(key, Some(".") | Some("..")) {
+            return Err(anyhow::anyhow!("上下文键不能为 \".\" 或 \"..\""));
+        }
+        self.context_key = key.map(|k| k.to_string());
+
+        if let Some(dir) = self.context_dir() {
+            fs::create_dir_all(&dir)
+                .map_err(|e| anyhow::anyhow!("无法创建上下文记忆目录: {}\n错误: {}", dir.display(), e))?;
+            self.initialize_category_files(&dir)?;
+        }
+
+        Ok(self)
+    }
+
+    /// 当前上下文键
+    pub fn context_key(&self) -> Option<&str> {
+        self.context_key.as_deref()
+    }
+
+    /// 上下文记忆目录（未设置上下文键时为 None）
+    fn context_dir(&self) -> Option<PathBuf> {
+        self.context_key
+            .as_ref()
+            .map(|key| self.memory_dir.join("contexts").join(context_dir_name(key)))
+    }
+
     /// 规范化项目路径
     fn normalize_project_path(project_path: &str) -> Result<PathBuf> {
         // 使用增强的路径解码和规范化功能
@@ -139,6 +174,16 @@ impl MemoryManager {
 
     /// 初始化记忆文件结构
     fn initialize_memory_structure(&self) -> Result<()> {
+        self.initialize_category_files(&self.memory_dir)?;
+
+        // 创建或更新元数据
+        self.update_metadata()?;
+
+        Ok(())
+    }
+
+    /// 在指定目录下创建各分类记忆文件
+    fn initialize_category_files(&self, dir: &Path) -> Result<()> {
         // 创建各类记忆文件，使用新的结构化格式
         let categories = [
             MemoryCategory::Rule,
@@ -155,16 +200,13 @@ impl MemoryManager {
                 MemoryCategory::Context => "context.md",
             };
 
-            let file_path = self.memory_dir.join(filename);
+            let file_path = dir.join(filename);
             if !file_path.exists() {
                 let header_content = self.get_category_header(category);
                 fs::write(&file_path, header_content)?;
             }
         }
 
-        // 创建或更新元数据
-        self.update_metadata()?;
-
         Ok(())
     }
 
@@ -179,10 +221,12 @@ impl MemoryManager {
             category,
             created_at: now,
             updated_at: now,
+            context_key: self.context_key.clone(),
         };
 
-        // 将记忆添加到对应的文件中
-        self.append_to_category_file(&entry)?;
+        // 将记忆添加到对应的文件中（有上下文键时写入上下文目录）
+        let dir = self.context_dir().unwrap_or_else(|| self.memory_dir.clone());
+        self.append_to_category_file(&dir, &entry)?;
 
         // 更新元数据
         self.update_metadata()?;
@@ -194,20 +238,8 @@ impl MemoryManager {
     pub fn get_all_memories(&self) -> Result<Vec<MemoryEntry>> {
         let mut memories = Vec::new();
 
-        let categories = [
-            (MemoryCategory::Rule, "rules.md"),
-            (MemoryCategory::Preference, "preferences.md"),
-            (MemoryCategory::Pattern, "patterns.md"),
-            (MemoryCategory::Context, "context.md"),
-        ];
-
-        for (category, filename) in categories.iter() {
-            let file_path = self.memory_dir.join(filename);
-            if file_path.exists() {
-                let content = fs::read_to_string(&file_path)?;
-                let entries = self.parse_memory_file(&content, *category)?;
-                memories.extend(entries);
-            }
+        for category in RECALL_CATEGORY_ORDER.iter() {
+            memories.extend(self.get_memories_by_category(*category)?);
         }
 
         // 按更新时间排序
@@ -217,62 +249,94 @@ impl MemoryManager {
     }
 
     /// 获取指定分类的记忆
+    ///
+    /// 设置了上下文键时，上下文记忆排在前面，内容相同的项目级记忆被覆盖
     pub fn get_memories_by_category(&self, category: MemoryCategory) -> Result<Vec<MemoryEntry>> {
-        let filename = match category {
-            MemoryCategory::Rule => "rules.md",
-            MemoryCategory::Preference => "preferences.md",
-            MemoryCategory::Pattern => "patterns.md",
-            MemoryCategory::Context => "context.md",
+        let project_entries = self.read_category(&self.memory_dir, category, None)?;
+
+        let Some(dir) = self.context_dir() else {
+            return Ok(project_entries);
         };
 
-        let file_path = self.memory_dir.join(filename);
+        let mut merged = self.read_category(&dir, category, self.context_key.as_deref())?;
+        let overridden: std::collections::HashSet<String> =
+            merged.iter().map(|m| m.content.clone()).collect();
+        merged.extend(
+            project_entries
+                .into_iter()
+                .filter(|m| !overridden.contains(&m.content)),
+        );
+
+        Ok(merged)
+    }
+
+    /// 读取指定目录下某个分类的记忆
+    fn read_category(
+        &self,
+        dir: &Path,
+        category: MemoryCategory,
+        context_key: Option<&str>,
+    ) -> Result<Vec<MemoryEntry>> {
+        let file_path = dir.join(Self::category_filename(category));
         if !file_path.exists() {
             return Ok(Vec::new());
         }
 
         let content = fs::read_to_string(&file_path)?;
-        self.parse_memory_file(&content, category)
+        let fallback_time = fs::metadata(&file_path)
+            .and_then(|m| m.modified())
+            .map(DateTime::<Utc>::from)
+            .unwrap_or_else(|_| Utc::now());
+        self.parse_memory_file(&content, category, context_key, fallback_time)
     }
 
     /// 将记忆条目添加到对应分类文件
-    fn append_to_category_file(&self, entry: &MemoryEntry) -> Result<()> {
-        let filename = match entry.category {
-            MemoryCategory::Rule => "rules.md",
-            MemoryCategory::Preference => "preferences.md",
-            MemoryCategory::Pattern => "patterns.md",
-            MemoryCategory::Context => "context.md",
-        };
-
-        let file_path = self.memory_dir.join(filename);
+    fn append_to_category_file(&self, dir: &Path, entry: &MemoryEntry) -> Result<()> {
+        let file_path = dir.join(Self::category_filename(entry.category));
         let mut content = if file_path.exists() {
             fs::read_to_string(&file_path)?
         } else {
             format!("# {}\n\n", self.get_category_title(&entry.category))
         };
 
-        // 简化格式：一行一个记忆
-        content.push_str(&format!("- {}\n", entry.content));
+        // 一行一个记忆，行尾以 HTML 注释保存 ID 与时间戳（渲染 Markdown 时不可见）
+        content.push_str(&format!(
+            "- {} <!-- id:{} created:{} -->\n",
+            entry.content,
+            entry.id,
+            entry.created_at.to_rfc3339()
+        ));
 
         fs::write(&file_path, content)?;
         Ok(())
     }
 
-    /// 解析记忆文件内容 - 简化版本
-    fn parse_memory_file(&self, content: &str, category: MemoryCategory) -> Result<Vec<MemoryEntry>> {
+    /// 解析记忆文件内容
+    ///
+    /// 兼容旧格式（仅 "- 内容"）：旧条目使用内容哈希作为稳定 ID，文件修改时间作为时间戳
+    fn parse_memory_file(
+        &self,
+        content: &str,
+        category: MemoryCategory,
+        context_key: Option<&str>,
+        fallback_time: DateTime<Utc>,
+    ) -> Result<Vec<MemoryEntry>> {
         let mut memories = Vec::new();
 
         // 按列表项解析，每个 "- " 开头的行是一个记忆条目
         for line in content.lines() {
             let line = line.trim();
             if line.starts_with("- ") && line.len() > 2 {
-                let content = line[2..].trim(); // 去掉 "- " 前缀
+                let (content, id, created_at) = Self::split_entry_meta(line[2..].trim());
                 if !content.is_empty() {
+                    let created_at = created_at.unwrap_or(fallback_time);
                     let entry = MemoryEntry {
-                        id: uuid::Uuid::new_v4().to_string(),
+                        id: id.unwrap_or_else(|| Self::legacy_entry_id(content)),
                         content: content.to_string(),
                         category,
-                        created_at: Utc::now(),
-                        updated_at: Utc::now(),
+                        created_at,
+                        updated_at: created_at,
+                        context_key: context_key.map(|k| k.to_string()),
                     };
 
                     memories.push(entry);
@@ -283,6 +347,50 @@ impl MemoryManager {
         Ok(memories)
     }
 
+    /// 拆分条目内容与行尾元数据注释 `<!-- id:xxx created:xxx -->`
+    fn split_entry_meta(raw: &str) -> (&str, Option<String>, Option<DateTime<Utc>>) {
+        let Some(start) = raw.rfind("<!--") else {
+            return (raw, None, None);
+        };
+        if !raw.ends_with("-->") {
+            return (raw, None, None);
+        }
+
+        let meta = &raw[start + 4..raw.len() - 3];
+        let mut id = None;
+        let mut created_at = None;
+        for part in meta.split_whitespace() {
+            if let Some(v) = part.strip_prefix("id:") {
+                id = Some(v.to_string());
+            } else if let Some(v) = part.strip_prefix("created:") {
+                created_at = DateTime::parse_from_rfc3339(v)
+                    .ok()
+                    .map(|t| t.with_timezone(&Utc));
+            }
+        }
+
+        if id.is_none() && created_at.is_none() {
+            return (raw, None, None);
+        }
+        (raw[..start].trim_end(), id, created_at)
+    }
+
+    /// 旧格式条目的稳定 ID（内容 SHA-256 前 12 位）
+    fn legacy_entry_id(content: &str) -> String {
+        let digest = ring::digest::digest(&ring::digest::SHA256, content.as_bytes());
+        format!("legacy-{}", &hex::encode(digest.as_ref())[..12])
+    }
+
+    /// 分类对应的文件名
+    fn category_filename(category: MemoryCategory) -> &'static str {
+        match category {
+            MemoryCategory::Rule => "rules.md",
+            MemoryCategory::Preference => "preferences.md",
+            MemoryCategory::Pattern => "patterns.md",
+            MemoryCategory::Context => "context.md",
+        }
+    }
+
     /// 获取分类标题
     fn get_category_title(&self, category: &MemoryCategory) -> &str {
         match category {
@@ -359,4 +467,128 @@ impl MemoryManager {
             Ok(format!("📚 项目记忆总览: {}", compressed_info.join(" | ")))
         }
     }
+
+    /// 渲染 recall 输出：按分类分组的 Markdown 文档（含 ID 与时间戳）
+    pub fn render_recall_markdown(&self) -> Result<String> {
+        let mut doc = String::from("# 项目记忆\n");
+        if let Some(key) = &self.context_key {
+            doc.push_str(&format!("\n> 上下文: `{}`（分支记忆优先于项目级记忆）\n", key));
+        }
+        let mut total = 0usize;
+
+        for category in RECALL_CATEGORY_ORDER.iter() {
+            let memories = self.get_memories_by_category(*category)?;
+            if memories.is_empty() {
+                continue;
+            }
+            total += memories.len();
+
+            doc.push_str(&format!(
+                "\n## {} ({})\n\n",
+                self.get_category_title(category),
+                memories.len()
+            ));
+            for memory in memories {
+                let content = memory
+                    .content
+                    .split_whitespace()
+                    .collect::<Vec<&str>>()
+                    .join(" ");
+                doc.push_str(&format!(
+                    "- {}\n  - ID: `{}`\n  - 创建时间: {}\n",
+                    content,
+                    memory.id,
+                    memory.created_at.format("%Y-%m-%d %H:%M:%S UTC")
+                ));
+                if let Some(key) = &memory.context_key {
+                    doc.push_str(&format!("  - 上下文: `{}`\n", key));
+                }
+            }
+        }
+
+        if total == 0 {
+            return Ok("📭 暂无项目记忆".to_string());
+        }
+
+        doc.push_str(&format!("\n---\n共 {} 条记忆\n", total));
+        Ok(doc)
+    }
+
+    /// recall 的结构化 JSON 输出，供 structured_content 使用
+    pub fn recall_structured(&self) -> Result<serde_json::Value> {
+        let mut groups = Vec::new();
+        let mut total = 0usize;
+
+        for category in RECALL_CATEGORY_ORDER.iter() {
+            let memories = self.get_memories_by_category(*category)?;
+            total += memories.len();
+            groups.push(serde_json::json!({
+                "category": category_key(*category),
+                "title": self.get_category_title(category),
+                "entries": memories,
+            }));
+        }
+
+        Ok(serde_json::json!({
+            "project_path": self.project_path,
+            "context_key": self.context_key,
+            "total": total,
+            "categories": groups,
+        }))
+    }
+}
+
+/// recall 输出中分类的展示顺序
+const RECALL_CATEGORY_ORDER: [MemoryCategory; 4] = [
+    MemoryCategory::Rule,
+    MemoryCategory::Preference,
+    MemoryCategory::Pattern,
+    MemoryCategory::Context,
+];
+
+/// 分类的小写标识（与 store 的 category 参数一致）
+fn category_key(category: MemoryCategory) -> &'static str {
+    match category {
+        MemoryCategory::Rule => "rule",
+        MemoryCategory::Preference => "preference",
+        MemoryCategory::Pattern => "pattern",
+        MemoryCategory::Context => "context",
+    }
+}
+
+/// 分支名可能包含 "/" 等字符，替换为安全的字符（不同的键可能得到相同结果）
+fn sanitize_context_key(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+/// 上下文记忆目录名：可读的替换结果加上完整键的哈希，替换后相同或只有大小写不同的键也不会共用目录
+fn context_dir_name(key: &str) -> String {
+    let hash = hex::encode(digest(&SHA256, key.as_bytes()).as_ref());
+    format!("{}-{}", sanitize_context_key(key), &hash[..12])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_dir_name_is_distinct() {
+        assert_ne!(context_dir_name("feature/x"), context_dir_name("feature_x"));
+        assert_ne!(context_dir_name("功能"), context_dir_name("测试"));
+        assert_ne!(context_dir_name("Main").to_lowercase(), context_dir_name("main").to_lowercase());
+        assert!(context_dir_name("feature/x").starts_with("feature_x-"));
+    }
+
+    #[test]
+    fn test_context_key_rejects_dot_segments() {
+        let manager = || MemoryManager {
+            memory_dir: std::env::temp_dir().join("devkit-memory-test-missing"),
+            project_path: String::new(),
+            context_key: None,
+        };
+        assert!(manager().with_context_key(Some(".")).is_err());
+        assert!(manager().with_context_key(Some(" .. ")).is_err());
+    }
 }
@@ -22,10 +22,11 @@ impl MemoryTool {
         }
 
         let manager = MemoryManager::new(&request.project_path)
+            .and_then(|m| m.with_context_key(request.context_key.as_deref()))
             .map_err(|e| McpError::internal_error(format!("Failed to create memory manager: {}", e), None))?;
 
         let mut index_hint = String::new();
-        if is_index_enabled() {
+        if is_index_enabled(&request.project_path) {
             if let Err(e) = try_trigger_background_index(&request.project_path).await {
                 log_debug!("Background index trigger failed (not affecting memory): {}", e);
             } else {
@@ -50,12 +51,23 @@ impl MemoryTool {
                 let id = manager.add_memory(&request.content, category)
                     .map_err(|e| McpError::internal_error(format!("Failed to add memory: {}", e), None))?;
 
-                format!("Memory added, ID: {}\nContent: {}\nCategory: {:?}{}", id, request.content, category, index_hint)
+                let scope = manager.context_key()
+                    .map(|k| format!("\nContext: {}", k))
+                    .unwrap_or_default();
+                format!("Memory added, ID: {}\nContent: {}\nCategory: {:?}{}{}", id, request.content, category, scope, index_hint)
             }
             "recall" | "回忆" => {
-                let info = manager.get_project_info()
-                    .map_err(|e| McpError::internal_error(format!("Failed to get project info: {}", e), None))?;
-                format!("{}{}", info, index_hint)
+                let markdown = manager.render_recall_markdown()
+                    .map_err(|e| McpError::internal_error(format!("Failed to render memories: {}", e), None))?;
+                let structured = manager.recall_structured()
+                    .map_err(|e| McpError::internal_error(format!("Failed to collect memories: {}", e), None))?;
+
+                return Ok(CallToolResult {
+                    content: vec![Content::text(format!("{}{}", markdown, index_hint))],
+                    is_error: None,
+                    meta: None,
+                    structured_content: Some(structured),
+                });
             }
             _ => {
                 return Err(McpError::invalid_params(
@@ -69,10 +81,11 @@ impl MemoryTool {
     }
 }
 
-/// Check if index tool is enabled
-fn is_index_enabled() -> bool {
+/// Check if index tool is enabled for the project (per-project overrides first)
+fn is_index_enabled(project_root: &str) -> bool {
+    let project_roots = [super::super::acemcp::mcp::normalize_project_root(project_root)];
     match crate::config::load_standalone_config() {
-        Ok(config) => config.mcp_config.tools.get("index").copied().unwrap_or(false),
+        Ok(config) => config.mcp_config.is_tool_enabled_for("index", &project_roots),
         Err(_) => false,
     }
 }
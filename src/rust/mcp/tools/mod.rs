@@ -5,9 +5,13 @@ pub mod memory;
 pub mod interaction;
 pub mod acemcp;
 pub mod docs;
+pub mod context;
+pub mod recall;
 
 // 重新导出工具以便访问
 pub use memory::MemoryTool;
 pub use interaction::InteractionTool;
 pub use acemcp::AcemcpTool;
 pub use docs::DocsTool;
+pub use context::ContextTool;
+pub use recall::RecallTool;
@@ -0,0 +1,254 @@
+use rmcp::model::{ErrorData as McpError, Tool, ToolAnnotations, CallToolResult, Content};
+use serde_json::json;
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use super::types::HistoryRecallRequest;
+use crate::mcp::history::{entries_in_range, is_same_project, project_of, HistoryEntryMeta};
+use crate::log_important;
+
+/// Look-back window when `days` is not given
+const DEFAULT_DAYS: u32 = 7;
+
+/// Hard upper bound for `days` (keeps the window start representable)
+const MAX_DAYS: u32 = 3650;
+
+/// Entries returned when `limit` is not given
+const DEFAULT_LIMIT: usize = 10;
+
+/// Hard upper bound for `limit`
+const MAX_LIMIT: usize = 50;
+
+/// Character budget for a single text field (question, reply)
+const FIELD_MAX_CHARS: usize = 800;
+
+/// Character budget for the whole answer
+const MAX_CHARS: usize = 12_000;
+
+/// Read-only recall of past interactions for the current project
+pub struct RecallTool;
+
+impl RecallTool {
+    /// Return the most recent matching interactions as Markdown, newest first
+    pub async fn recall(request: HistoryRecallRequest) -> Result<CallToolResult, McpError> {
+        let days = request.days.unwrap_or(DEFAULT_DAYS).clamp(1, MAX_DAYS);
+        let limit = request.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        log_important!(info,
+            "History recall request: project_root_path={}, query={:?}, days={}, limit={}",
+            request.project_root_path, request.query, days, limit
+        );
+
+        let start = chrono::Utc::now() - chrono::Duration::days(days as i64);
+        let entries = entries_in_range(Some(start.to_rfc3339()), None)
+            .map_err(|e| McpError::internal_error(format!("Failed to read history: {}", e), None))?;
+
+        let query = request.query.as_deref().map(|q| q.trim().to_lowercase()).filter(|q| !q.is_empty());
+        let mut matched: Vec<HistoryEntryMeta> = entries
+            .into_iter()
+            .map(|(_, meta)| meta)
+            .filter(|meta| matches_request(meta, &request, query.as_deref()))
+            .collect();
+        matched.reverse();
+        let total = matched.len();
+
+        let mut output = format!(
+            "# Interaction history\n\n**Project**: {}\n**Window**: last {} day(s)\n\n",
+            request.project_root_path, days
+        );
+        let mut shown = 0;
+        for meta in matched.iter().take(limit) {
+            let section = render_entry(meta);
+            if shown > 0 && output.chars().count() + section.chars().count() > MAX_CHARS {
+                break;
+            }
+            output.push_str(&section);
+            shown += 1;
+        }
+        if total == 0 {
+            output.push_str("No recorded interactions match.\n");
+        } else if shown < total {
+            output.push_str(&format!(
+                "---\nShowing {} of {} matching interactions; narrow with `query`, `days` or `tag` to see others.\n",
+                shown, total
+            ));
+        }
+
+        log_important!(info, "History recall response: {} of {} entries, {} chars", shown, total, output.len());
+
+        Ok(CallToolResult {
+            content: vec![Content::text(output)],
+            is_error: Some(false),
+            meta: None,
+            structured_content: None,
+        })
+    }
+
+    /// Get tool definition
+    pub fn get_tool_definition() -> Tool {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "project_root_path": {
+                    "type": "string",
+                    "description": "Absolute path to project root directory using forward slashes"
+                },
+                "query": {
+                    "type": "string",
+                    "description": "Optional. Keyword matched against past questions, chosen options and replies"
+                },
+                "days": {
+                    "type": "integer",
+                    "description": "Optional. How many days to look back, defaults to 7"
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Optional. Maximum number of interactions, defaults to 10 (max 50)"
+                },
+                "tag": {
+                    "type": "string",
+                    "description": "Optional. Only interactions the user tagged with this label"
+                },
+                "starred_only": {
+                    "type": "boolean",
+                    "description": "Optional. Only interactions the user starred"
+                }
+            },
+            "required": ["project_root_path"]
+        });
+
+        if let serde_json::Value::Object(schema_map) = schema {
+            Tool {
+                name: Cow::Borrowed("history_recall"),
+                description: Some(Cow::Borrowed("Recall recent interactive exchanges with the user for this project (what was asked, what they chose and replied), newest first. Use it to stay consistent with earlier decisions across sessions. Images are summarized, not returned.")),
+                input_schema: Arc::new(schema_map),
+                annotations: Some(ToolAnnotations {
+                    title: Some("History Recall".to_string()),
+                    read_only_hint: Some(true),       // Only reads the local history
+                    destructive_hint: Some(false),    // Not destructive
+                    idempotent_hint: Some(true),      // Same query = same result
+                    open_world_hint: Some(false),     // Closed domain, local storage
+                }),
+                icons: None,
+                meta: None,
+                output_schema: None,
+                title: Some("History Recall".to_string()),
+            }
+        } else {
+            panic!("Schema creation failed");
+        }
+    }
+}
+
+/// Project, tag, starred and keyword filters; `query` is already lowercased
+fn matches_request(meta: &HistoryEntryMeta, request: &HistoryRecallRequest, query: Option<&str>) -> bool {
+    project_of(meta).is_some_and(|p| is_same_project(&p, &request.project_root_path))
+        && request.tag.as_ref().is_none_or(|tag| meta.tags.iter().any(|t| t == tag))
+        && (!request.starred_only.unwrap_or(false) || meta.starred)
+        && query.is_none_or(|q| searchable_text(meta).to_lowercase().contains(q))
+}
+
+fn searchable_text(meta: &HistoryEntryMeta) -> String {
+    let mut text = meta.request.as_ref().map(|r| r.message.clone()).unwrap_or_default();
+    for value in [meta.response.get("note"), meta.response.get("toppings")].into_iter().flatten() {
+        text.push('\n');
+        text.push_str(&value.to_string());
+    }
+    text
+}
+
+/// Keep at most `max_chars` characters, marking the cut
+fn clip(text: &str, max_chars: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars).collect();
+    format!("{}… (truncated)", kept.trim_end())
+}
+
+/// One interaction as a Markdown section; images are listed by type only
+fn render_entry(meta: &HistoryEntryMeta) -> String {
+    let mut out = format!("## {}\n\n", meta.timestamp);
+    if let Some(request) = &meta.request {
+        out.push_str(&format!("**Asked**: {}\n\n", clip(&request.message, FIELD_MAX_CHARS)));
+    }
+
+    let toppings: Vec<&str> = meta
+        .response
+        .get("toppings")
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+    if !toppings.is_empty() {
+        out.push_str(&format!("**Chose**: {}\n\n", clip(&toppings.join("; "), FIELD_MAX_CHARS)));
+    }
+    match meta.response.get("note").and_then(|v| v.as_str()).filter(|s| !s.trim().is_empty()) {
+        Some(note) => out.push_str(&format!("**Replied**: {}\n\n", clip(note, FIELD_MAX_CHARS))),
+        None if meta.response.is_string() => out.push_str(&format!(
+            "**Replied**: {}\n\n",
+            clip(meta.response.as_str().unwrap_or_default(), FIELD_MAX_CHARS)
+        )),
+        None => {}
+    }
+
+    if !meta.ingredient_files.is_empty() {
+        let types: Vec<&str> = meta
+            .response
+            .get("ingredients")
+            .and_then(|v| v.as_array())
+            .map(|items| items.iter().filter_map(|i| i.get("dish_type").and_then(|t| t.as_str())).collect())
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "**Images**: {} attached{} (not included)\n\n",
+            meta.ingredient_files.len(),
+            if types.is_empty() { String::new() } else { format!(", {}", types.join(", ")) }
+        ));
+    }
+    if !meta.tags.is_empty() {
+        out.push_str(&format!("**Tags**: {}\n\n", meta.tags.join(", ")));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clip() {
+        assert_eq!(clip("  short ", 10), "short");
+        assert_eq!(clip("一二三四五", 3), "一二三… (truncated)");
+    }
+
+    #[test]
+    fn test_matches_request() {
+        let meta: HistoryEntryMeta = serde_json::from_value(json!({
+            "id": "a",
+            "timestamp": "2026-01-01T00:00:00Z",
+            "request_id": null,
+            "source": null,
+            "request": { "id": "r", "message": "Use Postgres?", "menu": null, "chalkboard": false, "project_root_path": "C:\\work\\app\\" },
+            "response": { "note": "Yes, with pgbouncer", "toppings": ["Postgres"] },
+            "tags": ["db"]
+        }))
+        .unwrap();
+        let request = |project: &str, tag: Option<&str>| HistoryRecallRequest {
+            project_root_path: project.to_string(),
+            query: None,
+            days: None,
+            limit: None,
+            tag: tag.map(str::to_string),
+            starred_only: None,
+        };
+
+        assert!(matches_request(&meta, &request("c:/work/app", None), None));
+        assert!(!matches_request(&meta, &request("c:/work/other", None), None));
+        assert!(matches_request(&meta, &request("c:/work/app", Some("db")), Some("pgbouncer")));
+        assert!(!matches_request(&meta, &request("c:/work/app", Some("ui")), None));
+        assert!(!matches_request(&meta, &request("c:/work/app", None), Some("mysql")));
+
+        let mut starred = request("c:/work/app", None);
+        starred.starred_only = Some(true);
+        assert!(!matches_request(&meta, &starred, None));
+    }
+}
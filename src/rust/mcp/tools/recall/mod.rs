@@ -0,0 +1,5 @@
+pub mod types;
+pub mod mcp;
+
+pub use mcp::RecallTool;
+pub use types::HistoryRecallRequest;
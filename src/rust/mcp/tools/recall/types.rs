@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// 交互历史回顾请求
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct HistoryRecallRequest {
+    /// 项目根目录的绝对路径（只返回该项目的交互）
+    #[schemars(description = "项目根目录的绝对路径")]
+    pub project_root_path: String,
+    /// 关键词 (可选)，匹配提问、选项与回复
+    #[schemars(description = "关键词 (可选)，匹配提问、选项与回复")]
+    #[serde(default)]
+    pub query: Option<String>,
+    /// 回溯天数 (可选，默认 7)
+    #[schemars(description = "回溯天数 (可选，默认 7)")]
+    #[serde(default)]
+    pub days: Option<u32>,
+    /// 返回条数上限 (可选，默认 10，最多 50)
+    #[schemars(description = "返回条数上限 (可选，默认 10，最多 50)")]
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// 只返回带该标签的记录 (可选)
+    #[schemars(description = "只返回带该标签的记录 (可选)")]
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// 只返回星标记录 (可选)
+    #[schemars(description = "只返回星标记录 (可选)")]
+    #[serde(default)]
+    pub starred_only: Option<bool>,
+}
@@ -17,6 +17,10 @@ pub struct CacheRequest {
     #[schemars(description = "Project root path for context")]
     #[serde(default)]
     pub project_root_path: Option<String>,
+
+    #[schemars(description = "Optional agent session id used to thread related interactions in history")]
+    #[serde(default)]
+    pub session_id: Option<String>,
 }
 
 fn default_chalkboard() -> bool {
@@ -25,7 +29,7 @@ fn default_chalkboard() -> bool {
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
 pub struct StoreRequest {
-    #[schemars(description = "Operation type: store (add entry), recall (get project info)")]
+    #[schemars(description = "Operation type: store (add entry), recall (get memories as Markdown grouped by category, with structured JSON)")]
     pub action: String,
     #[schemars(description = "Project path (required)")]
     pub project_path: String,
@@ -37,6 +41,11 @@ pub struct StoreRequest {
     )]
     #[serde(default = "default_category")]
     pub category: String,
+    #[schemars(
+        description = "Optional context key (e.g. git branch name) to scope entries; recall merges context entries over project-level ones"
+    )]
+    #[serde(default)]
+    pub context_key: Option<String>,
 }
 
 #[derive(Debug, Deserialize, schemars::JsonSchema)]
@@ -45,6 +54,12 @@ pub struct AcemcpRequest {
     pub project_root_path: String,
     #[schemars(description = "Natural language search query to find relevant code context")]
     pub query: String,
+    #[schemars(description = "Optional globs; only snippets whose path matches one of them are returned (e.g. src/**)")]
+    #[serde(default)]
+    pub include_paths: Vec<String>,
+    #[schemars(description = "Optional globs; snippets whose path matches one of them are dropped (e.g. **/tests/**)")]
+    #[serde(default)]
+    pub exclude_paths: Vec<String>,
 }
 
 fn default_category() -> String {
@@ -58,6 +73,12 @@ pub struct PopupRequest {
     pub menu: Option<Vec<String>>,
     pub chalkboard: bool,
     pub project_root_path: Option<String>,
+    /// Agent session the request belongs to (for history threading)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// When the request was issued (RFC 3339), used for response latency stats
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_at: Option<String>,
 }
 
 /// Structured response data format
@@ -82,6 +103,9 @@ pub struct KitchenTicket {
     pub cooked_at: Option<String>,
     pub ticket_id: Option<String>,
     pub station: Option<String>,
+    /// Who answered, when the reply came from a shared channel (e.g. a Telegram group)
+    #[serde(default)]
+    pub responder: Option<String>,
 }
 
 /// Build MCP response
@@ -116,6 +140,20 @@ pub fn build_serve_response(
     response.to_string()
 }
 
+/// Record who answered in the response ticket
+pub fn with_responder(response: String, responder: &str) -> String {
+    let Ok(mut value) = serde_json::from_str::<serde_json::Value>(&response) else {
+        return response;
+    };
+    match value.get_mut("ticket").and_then(|t| t.as_object_mut()) {
+        Some(ticket) => {
+            ticket.insert("responder".to_string(), serde_json::Value::String(responder.to_string()));
+            value.to_string()
+        }
+        None => response,
+    }
+}
+
 /// Build continue response
 pub fn build_refill_response(ticket_id: Option<String>, station: &str) -> String {
     let continue_prompt = if let Ok(config) = crate::config::load_standalone_config() {
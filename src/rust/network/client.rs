@@ -24,7 +24,7 @@ pub fn create_http_client(
         log::info!("🔧 配置HTTP客户端使用代理: {}:{} ({})", 
             proxy.host, proxy.port, proxy.proxy_type);
         
-        let proxy_url = proxy.to_url();
+        let proxy_url = proxy.connect_url();
         
         // 使用 Proxy::all() 让所有请求（http 和 https）都走代理
         // 这对于 Clash 等混合代理端口尤其重要，因为它们同时支持 HTTP 和 HTTPS 代理
@@ -1,8 +1,9 @@
 // 网络相关模块
-// 包含地理位置检测、代理检测和HTTP客户端构建功能
+// 包含地理位置检测、代理检测（含系统代理读取）和HTTP客户端构建功能
 
 pub mod geo;
 pub mod proxy;
+pub mod system_proxy;
 pub mod client;
 pub mod commands;
 
@@ -1,6 +1,10 @@
 // 代理检测和配置模块
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
 use serde::{Deserialize, Serialize};
 
+/// 代理地址认证信息中需要转义的字符（保留 RFC 3986 的非保留字符）
+const USERINFO_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
 /// 代理类型
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -30,6 +34,12 @@ pub struct ProxyInfo {
     pub proxy_type: ProxyType,
     pub host: String,
     pub port: u16,
+    /// 代理认证用户名（来自 `user:pass@host` 形式的代理地址）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// 代理认证密码，不随检测结果返回给前端
+    #[serde(default, skip_serializing)]
+    pub password: Option<String>,
 }
 
 impl ProxyInfo {
@@ -39,13 +49,35 @@ impl ProxyInfo {
             proxy_type,
             host,
             port,
+            username: None,
+            password: None,
         }
     }
+
+    /// 设置代理认证信息
+    pub fn with_auth(mut self, username: String, password: Option<String>) -> Self {
+        self.username = Some(username);
+        self.password = password;
+        self
+    }
     
-    /// 获取代理URL
+    /// 获取代理URL（不含认证信息，用于去重与日志）
     pub fn to_url(&self) -> String {
         format!("{}://{}:{}", self.proxy_type, self.host, self.port)
     }
+
+    /// 连接代理使用的 URL（含转义后的认证信息）
+    pub fn connect_url(&self) -> String {
+        let Some(username) = &self.username else {
+            return self.to_url();
+        };
+        let mut userinfo = utf8_percent_encode(username, USERINFO_ENCODE_SET).to_string();
+        if let Some(password) = &self.password {
+            userinfo.push(':');
+            userinfo.extend(utf8_percent_encode(password, USERINFO_ENCODE_SET));
+        }
+        format!("{}://{}@{}:{}", self.proxy_type, userinfo, self.host, self.port)
+    }
 }
 
 /// 代理检测器
@@ -69,15 +101,31 @@ impl ProxyDetector {
         (8080, ProxyType::Http),    // 通用HTTP端口
     ];
     
+    /// 读取系统已配置的代理
+    ///
+    /// 来源包括代理环境变量、macOS/Windows 系统代理设置以及 PAC 脚本，不做可用性检测
+    pub async fn detect_system_proxies() -> Vec<super::system_proxy::SystemProxy> {
+        super::system_proxy::detect_system_proxies().await
+    }
+
     /// 检测本地可用的代理
     /// 
-    /// 按优先级顺序检测常用代理端口，返回第一个可用的代理
+    /// 优先检测系统已配置的代理，再按优先级顺序检测常用代理端口，返回第一个可用的代理
     /// 
     /// # 返回值
     /// - `Some(ProxyInfo)`: 找到可用的代理
     /// - `None`: 没有找到可用的代理
     pub async fn detect_available_proxy() -> Option<ProxyInfo> {
         log::info!("🔍 开始检测本地代理");
+
+        for system_proxy in Self::detect_system_proxies().await {
+            log::info!("🔍 检测系统代理: {} ({})", system_proxy.info.to_url(), system_proxy.source);
+
+            if Self::check_proxy(&system_proxy.info).await {
+                log::info!("✅ 找到可用系统代理: {}:{} ({})", system_proxy.info.host, system_proxy.info.port, system_proxy.info.proxy_type);
+                return Some(system_proxy.info);
+            }
+        }
         
         for (port, proxy_type) in Self::COMMON_PORTS {
             let proxy_info = ProxyInfo::new(proxy_type.clone(), "127.0.0.1".to_string(), *port);
@@ -126,7 +174,7 @@ impl ProxyDetector {
         }
 
         // 创建代理URL
-        let proxy_url = proxy_info.to_url();
+        let proxy_url = proxy_info.connect_url();
         
         // 尝试创建带代理的HTTP客户端
         let client_builder = reqwest::Client::builder()
@@ -0,0 +1,140 @@
+use crate::config::{save_config, AppState, SlackConfig};
+use crate::log_important;
+use crate::slack::core::{
+    answered_blocks, parse_action, prompt_blocks, reply_text, responder_label, validate_slack_config,
+    BlockActionsPayload, PromptAction, SlackCore,
+};
+use crate::slack::socket::InteractionSource;
+use crate::telegram::{core::build_feedback_message, toggle_option, TelegramEvent};
+use tauri::{AppHandle, Emitter, State};
+
+/// 获取Slack配置
+#[tauri::command]
+pub async fn get_slack_config(state: State<'_, AppState>) -> Result<SlackConfig, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.slack_config.clone())
+}
+
+/// 设置Slack配置
+#[tauri::command]
+pub async fn set_slack_config(
+    slack_config: SlackConfig,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    validate_slack_config(&slack_config).map_err(|e| e.to_string())?;
+
+    {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        config.slack_config = slack_config;
+    }
+
+    // 保存配置到文件
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    Ok(())
+}
+
+/// 测试Slack连接
+#[tauri::command]
+pub async fn test_slack_connection_cmd(slack_config: SlackConfig) -> Result<String, String> {
+    crate::slack::core::test_slack_connection(&slack_config)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 启动Slack同步：弹窗显示的同时把请求发送到Slack，
+/// Slack中的操作以与Telegram相同的事件格式（slack-event）同步到弹窗，最先发送或继续的回复生效
+#[tauri::command]
+pub async fn start_slack_sync(
+    message: String,
+    menu: Vec<String>,
+    chalkboard: bool,
+    state: State<'_, AppState>,
+    app_handle: AppHandle,
+) -> Result<(), String> {
+    let (slack_config, continue_reply_enabled) = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        (config.slack_config.clone(), config.reply_config.enable_continue_reply)
+    };
+    if !slack_config.enabled {
+        return Ok(());
+    }
+    validate_slack_config(&slack_config).map_err(|e| e.to_string())?;
+
+    let core = SlackCore::new(&slack_config).map_err(|e| format!("创建Slack客户端失败: {}", e))?;
+    // 先订阅再发送，避免错过按钮点击
+    let mut source = InteractionSource::start(&core)
+        .await
+        .map_err(|e| format!("连接Slack失败: {}", e))?;
+    let blocks = prompt_blocks(&message, &menu, &[], chalkboard, continue_reply_enabled);
+    let posted = core
+        .post_message(&message, Some(blocks), None)
+        .await
+        .map_err(|e| format!("发送Slack消息失败: {}", e))?;
+
+    tokio::spawn(async move {
+        // 选中的选项（保持 menu 中的顺序）
+        let mut selected_options: Vec<String> = Vec::new();
+        loop {
+            let payload = source.next_interaction().await;
+            let Ok(payload) = serde_json::from_value::<BlockActionsPayload>(payload) else {
+                continue;
+            };
+            // 只处理本次请求消息上的按钮
+            let message_ts = payload.container.as_ref().and_then(|c| c.message_ts.as_deref());
+            if payload.kind != "block_actions" || message_ts != Some(posted.ts.as_str()) {
+                continue;
+            }
+
+            let responder = responder_label(&payload.user);
+            for action in &payload.actions {
+                let (event, feedback) = match parse_action(&action.action_id, &menu) {
+                    Some(PromptAction::Toggle(option)) => {
+                        let selected = toggle_option(&mut selected_options, &option, &menu);
+                        let _ = app_handle.emit("slack-event", &TelegramEvent::OptionToggled { option, selected });
+                        let blocks =
+                            prompt_blocks(&message, &menu, &selected_options, chalkboard, continue_reply_enabled);
+                        if let Err(e) = core.update_message(&posted, &message, blocks).await {
+                            log_important!(warn, "更新Slack消息失败: {}", e);
+                        }
+                        continue;
+                    }
+                    Some(PromptAction::Send) => {
+                        let text = reply_text(payload.state.as_ref()).unwrap_or_default();
+                        let _ = app_handle.emit("slack-event", &TelegramEvent::TextUpdated { text: text.clone() });
+                        let feedback = build_feedback_message(&selected_options, &text, false);
+                        (TelegramEvent::SendPressed { responder: Some(responder.clone()) }, feedback)
+                    }
+                    Some(PromptAction::Continue) => (
+                        TelegramEvent::ContinuePressed { responder: Some(responder.clone()) },
+                        build_feedback_message(&[], "", true),
+                    ),
+                    None => continue,
+                };
+                let _ = app_handle.emit("slack-event", &event);
+
+                let note = format!("✅ 已由 {} 回复", responder);
+                let blocks = answered_blocks(&message, chalkboard, &note);
+                if let Err(e) = core.update_message(&posted, &message, blocks).await {
+                    log_important!(warn, "更新Slack消息失败: {}", e);
+                }
+                let _ = core.post_message(&feedback, None, Some(&posted.ts)).await;
+                return;
+            }
+        }
+    });
+
+    Ok(())
+}
@@ -0,0 +1,362 @@
+// Slack 核心功能
+// 通过 Web API 发送和更新消息；请求以 Block Kit 消息发送到频道或私信：
+// 选项为按钮，点击切换选中状态，补充说明填写在消息中的输入框里，“发送”与“继续”按钮结束对话
+
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::config::SlackConfig;
+use crate::constants::slack::{
+    API_BASE_URL, APP_TOKEN_PREFIX, BOT_TOKEN_PREFIX, MAX_BUTTON_TEXT_LENGTH, MAX_SECTION_TEXT_LENGTH,
+    OPTION_BUTTONS_PER_ROW, REQUEST_TIMEOUT_SECS,
+};
+
+/// 选项按钮 action_id 前缀，后接选项在 menu 中的下标
+pub const OPTION_ACTION_PREFIX: &str = "toggle:";
+pub const SEND_ACTION_ID: &str = "send";
+pub const CONTINUE_ACTION_ID: &str = "continue";
+/// 补充说明输入框
+pub const REPLY_BLOCK_ID: &str = "reply";
+pub const REPLY_ACTION_ID: &str = "reply_text";
+
+/// 按钮对应的操作
+#[derive(Debug, Clone, PartialEq)]
+pub enum PromptAction {
+    Toggle(String),
+    Send,
+    Continue,
+}
+
+/// block_actions 交互负载（只解析用到的字段）
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockActionsPayload {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub user: SlackUser,
+    #[serde(default)]
+    pub container: Option<ActionContainer>,
+    #[serde(default)]
+    pub actions: Vec<BlockAction>,
+    #[serde(default)]
+    pub state: Option<Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlackUser {
+    pub id: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ActionContainer {
+    #[serde(default)]
+    pub message_ts: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockAction {
+    pub action_id: String,
+}
+
+/// 已发送的请求消息位置
+#[derive(Debug, Clone)]
+pub struct PostedMessage {
+    pub channel: String,
+    pub ts: String,
+}
+
+/// 检查 Slack 配置，未启用时不检查
+pub fn validate_slack_config(config: &SlackConfig) -> Result<()> {
+    if !config.enabled {
+        return Ok(());
+    }
+    if !config.bot_token.trim().starts_with(BOT_TOKEN_PREFIX) {
+        bail!("Bot Token 应以 {} 开头", BOT_TOKEN_PREFIX);
+    }
+    if !config.app_token.trim().starts_with(APP_TOKEN_PREFIX) {
+        bail!("App-Level Token 应以 {} 开头", APP_TOKEN_PREFIX);
+    }
+    if config.channel_id.trim().is_empty() {
+        bail!("请填写频道ID或用户ID");
+    }
+    Ok(())
+}
+
+/// 解析按钮的 action_id
+pub fn parse_action(action_id: &str, menu: &[String]) -> Option<PromptAction> {
+    match action_id {
+        SEND_ACTION_ID => Some(PromptAction::Send),
+        CONTINUE_ACTION_ID => Some(PromptAction::Continue),
+        _ => action_id
+            .strip_prefix(OPTION_ACTION_PREFIX)
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| menu.get(index))
+            .map(|option| PromptAction::Toggle(option.clone())),
+    }
+}
+
+/// 从交互负载的 state 中读取补充说明
+pub fn reply_text(state: Option<&Value>) -> Option<String> {
+    state?
+        .pointer(&format!("/values/{}/{}/value", REPLY_BLOCK_ID, REPLY_ACTION_ID))
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_string)
+}
+
+/// 回复人描述，如 “zhangsan (U123) · Slack”
+pub fn responder_label(user: &SlackUser) -> String {
+    match user.username.as_deref().or(user.name.as_deref()) {
+        Some(name) => format!("{} ({}) · Slack", name, user.id),
+        None => format!("{} · Slack", user.id),
+    }
+}
+
+/// 按字符截断，超出部分以省略号代替
+fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(max - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// 按 section 块的长度上限拆分文本，尽量在换行处拆开
+fn split_section_text(text: &str) -> Vec<String> {
+    let mut sections = Vec::new();
+    let mut current = String::new();
+    for line in text.split_inclusive('\n') {
+        if current.chars().count() + line.chars().count() > MAX_SECTION_TEXT_LENGTH && !current.is_empty() {
+            sections.push(std::mem::take(&mut current));
+        }
+        // 单行超长时按字符硬拆
+        let mut rest = line;
+        while rest.chars().count() > MAX_SECTION_TEXT_LENGTH {
+            let split_at = rest
+                .char_indices()
+                .nth(MAX_SECTION_TEXT_LENGTH)
+                .map_or(rest.len(), |(i, _)| i);
+            sections.push(rest[..split_at].to_string());
+            rest = &rest[split_at..];
+        }
+        current.push_str(rest);
+    }
+    if !current.trim().is_empty() || sections.is_empty() {
+        sections.push(current);
+    }
+    sections
+}
+
+fn message_blocks(message: &str, chalkboard: bool) -> Vec<Value> {
+    let text_type = if chalkboard { "mrkdwn" } else { "plain_text" };
+    split_section_text(message)
+        .into_iter()
+        .filter(|text| !text.trim().is_empty())
+        .map(|text| json!({ "type": "section", "text": { "type": text_type, "text": text } }))
+        .collect()
+}
+
+/// 生成请求消息的 blocks；selected_options 中的选项以主色按钮显示
+pub fn prompt_blocks(
+    message: &str,
+    menu: &[String],
+    selected_options: &[String],
+    chalkboard: bool,
+    continue_reply_enabled: bool,
+) -> Vec<Value> {
+    let mut blocks = message_blocks(message, chalkboard);
+
+    for (row_index, chunk) in menu.chunks(OPTION_BUTTONS_PER_ROW).enumerate() {
+        let buttons: Vec<Value> = chunk
+            .iter()
+            .enumerate()
+            .map(|(column, option)| {
+                let index = row_index * OPTION_BUTTONS_PER_ROW + column;
+                let selected = selected_options.contains(option);
+                let text = if selected { format!("✅ {}", option) } else { option.clone() };
+                let mut button = json!({
+                    "type": "button",
+                    "action_id": format!("{}{}", OPTION_ACTION_PREFIX, index),
+                    "text": { "type": "plain_text", "text": truncate_chars(&text, MAX_BUTTON_TEXT_LENGTH), "emoji": true },
+                    "value": index.to_string(),
+                });
+                if selected {
+                    button["style"] = json!("primary");
+                }
+                button
+            })
+            .collect();
+        blocks.push(json!({ "type": "actions", "block_id": format!("options_{}", row_index), "elements": buttons }));
+    }
+
+    blocks.push(json!({
+        "type": "input",
+        "block_id": REPLY_BLOCK_ID,
+        "optional": true,
+        "label": { "type": "plain_text", "text": "补充说明" },
+        "element": { "type": "plain_text_input", "action_id": REPLY_ACTION_ID, "multiline": true },
+    }));
+
+    let mut operations = vec![json!({
+        "type": "button",
+        "action_id": SEND_ACTION_ID,
+        "style": "primary",
+        "text": { "type": "plain_text", "text": "↗️ 发送", "emoji": true },
+    })];
+    if continue_reply_enabled {
+        operations.push(json!({
+            "type": "button",
+            "action_id": CONTINUE_ACTION_ID,
+            "text": { "type": "plain_text", "text": "⏩ 继续", "emoji": true },
+        }));
+    }
+    blocks.push(json!({ "type": "actions", "block_id": "operations", "elements": operations }));
+    blocks
+}
+
+/// 已回复的请求消息：保留原文，去掉按钮和输入框
+pub fn answered_blocks(message: &str, chalkboard: bool, note: &str) -> Vec<Value> {
+    let mut blocks = message_blocks(message, chalkboard);
+    blocks.push(json!({ "type": "context", "elements": [{ "type": "plain_text", "text": note, "emoji": true }] }));
+    blocks
+}
+
+/// Slack核心客户端
+#[derive(Clone)]
+pub struct SlackCore {
+    client: reqwest::Client,
+    bot_token: String,
+    app_token: String,
+    pub channel_id: String,
+}
+
+impl SlackCore {
+    pub fn new(config: &SlackConfig) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()?;
+        Ok(Self {
+            client,
+            bot_token: config.bot_token.trim().to_string(),
+            app_token: config.app_token.trim().to_string(),
+            channel_id: config.channel_id.trim().to_string(),
+        })
+    }
+
+    /// 调用 Web API，ok 为 false 时返回 Slack 的错误码
+    async fn call(&self, method: &str, token: &str, body: Value) -> Result<Value> {
+        let response: Value = self
+            .client
+            .post(format!("{}/{}", API_BASE_URL, method))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| anyhow!("请求Slack失败: {}", e))?
+            .json()
+            .await
+            .map_err(|e| anyhow!("解析Slack响应失败: {}", e))?;
+        if response.get("ok").and_then(Value::as_bool) != Some(true) {
+            let error = response.get("error").and_then(Value::as_str).unwrap_or("unknown_error");
+            bail!("Slack {} 失败: {}", method, error);
+        }
+        Ok(response)
+    }
+
+    /// 检查 Bot Token，返回工作区与机器人名称
+    pub async fn auth_test(&self) -> Result<(String, String)> {
+        let response = self.call("auth.test", &self.bot_token, json!({})).await?;
+        let field = |key: &str| response.get(key).and_then(Value::as_str).unwrap_or_default().to_string();
+        Ok((field("team"), field("user")))
+    }
+
+    /// 获取 Socket Mode 连接地址（每次连接都需要重新获取）
+    pub async fn open_socket_url(&self) -> Result<String> {
+        let response = self.call("apps.connections.open", &self.app_token, json!({})).await?;
+        response
+            .get("url")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("Slack未返回Socket Mode地址"))
+    }
+
+    /// 发送消息；blocks 为空时发送纯文本，thread_ts 不为空时回复到该消息的讨论串
+    pub async fn post_message(&self, text: &str, blocks: Option<Vec<Value>>, thread_ts: Option<&str>) -> Result<PostedMessage> {
+        let mut body = json!({ "channel": self.channel_id, "text": text });
+        if let Some(blocks) = blocks {
+            body["blocks"] = json!(blocks);
+        }
+        if let Some(thread_ts) = thread_ts {
+            body["thread_ts"] = json!(thread_ts);
+        }
+        let response = self.call("chat.postMessage", &self.bot_token, body).await?;
+        // 发送给用户ID时 Slack 返回私信频道的ID，更新消息需要使用该ID
+        let field = |key: &str| response.get(key).and_then(Value::as_str).map(str::to_string);
+        match (field("channel"), field("ts")) {
+            (Some(channel), Some(ts)) => Ok(PostedMessage { channel, ts }),
+            _ => bail!("Slack未返回消息ID"),
+        }
+    }
+
+    /// 更新已发送的消息
+    pub async fn update_message(&self, posted: &PostedMessage, text: &str, blocks: Vec<Value>) -> Result<()> {
+        self.call(
+            "chat.update",
+            &self.bot_token,
+            json!({ "channel": posted.channel, "ts": posted.ts, "text": text, "blocks": blocks }),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// 测试Slack连接：检查两个Token并发送测试消息
+pub async fn test_slack_connection(config: &SlackConfig) -> Result<String> {
+    validate_slack_config(&SlackConfig {
+        enabled: true,
+        ..config.clone()
+    })?;
+    let core = SlackCore::new(config)?;
+    let (team, bot) = core.auth_test().await?;
+    core.open_socket_url().await?;
+    core.post_message("✅ Slack 连接测试成功", None, None).await?;
+    Ok(format!("连接成功：{} 工作区的 {}", team, bot))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prompt_blocks() {
+        let menu: Vec<String> = (0..7).map(|i| format!("选项{}", i)).collect();
+        let blocks = prompt_blocks("请选择", &menu, &["选项6".to_string()], false, true);
+        // 文本、两行选项、输入框、操作按钮
+        assert_eq!(blocks.len(), 5);
+        assert_eq!(blocks[2]["elements"][1]["action_id"], "toggle:6");
+        assert_eq!(blocks[2]["elements"][1]["style"], "primary");
+
+        assert_eq!(parse_action("toggle:6", &menu), Some(PromptAction::Toggle("选项6".to_string())));
+        assert_eq!(parse_action("toggle:7", &menu), None);
+        assert_eq!(parse_action(SEND_ACTION_ID, &menu), Some(PromptAction::Send));
+
+        let state = json!({ "values": { REPLY_BLOCK_ID: { REPLY_ACTION_ID: { "value": " 补充 " } } } });
+        assert_eq!(reply_text(Some(&state)).as_deref(), Some("补充"));
+        assert_eq!(reply_text(None), None);
+    }
+
+    #[test]
+    fn test_split_section_text() {
+        let long = format!("{}\n{}", "a".repeat(2000), "b".repeat(4000));
+        let sections = split_section_text(&long);
+        assert!(sections.iter().all(|s| s.chars().count() <= MAX_SECTION_TEXT_LENGTH));
+        assert_eq!(sections.concat(), long);
+        assert_eq!(split_section_text(""), vec![String::new()]);
+    }
+}
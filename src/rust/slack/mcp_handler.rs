@@ -0,0 +1,122 @@
+use anyhow::{bail, Result};
+
+use crate::config::load_standalone_config;
+use crate::log_important;
+use crate::mcp::types::{build_refill_response, build_serve_response, with_responder, PopupRequest};
+use crate::slack::core::{
+    answered_blocks, parse_action, prompt_blocks, reply_text, responder_label, validate_slack_config,
+    BlockActionsPayload, PostedMessage, PromptAction, SlackCore,
+};
+use crate::slack::socket::InteractionSource;
+use crate::telegram::{core::build_feedback_message, toggle_option};
+
+/// 处理Slack模式的MCP请求（不启动GUI）
+pub async fn handle_slack_only_mcp_request(request_file: &str) -> Result<()> {
+    // 读取MCP请求文件
+    let request_json = std::fs::read_to_string(request_file)?;
+    let request: PopupRequest = serde_json::from_str(&request_json)?;
+
+    // 加载完整配置
+    let app_config = load_standalone_config()?;
+    let slack_config = &app_config.slack_config;
+    if !slack_config.enabled {
+        log_important!(warn, "Slack未启用，无法处理请求");
+        return Ok(());
+    }
+    validate_slack_config(slack_config)?;
+
+    let core = SlackCore::new(slack_config)?;
+    let menu = request.menu.clone().unwrap_or_default();
+    let continue_reply_enabled = app_config.reply_config.enable_continue_reply;
+
+    // 先订阅再发送，避免错过按钮点击
+    let mut socket = InteractionSource::start(&core).await?;
+    let blocks = prompt_blocks(&request.message, &menu, &[], request.chalkboard, continue_reply_enabled);
+    let posted = core.post_message(&request.message, Some(blocks), None).await?;
+
+    // 选中的选项（保持 menu 中的顺序）
+    let mut selected_options: Vec<String> = Vec::new();
+
+    loop {
+        let payload = socket.next_interaction().await;
+        let Ok(payload) = serde_json::from_value::<BlockActionsPayload>(payload) else {
+            continue;
+        };
+        // 只处理本次请求消息上的按钮
+        let message_ts = payload.container.as_ref().and_then(|c| c.message_ts.as_deref());
+        if payload.kind != "block_actions" || message_ts != Some(posted.ts.as_str()) {
+            continue;
+        }
+
+        let responder = responder_label(&payload.user);
+        for action in &payload.actions {
+            match parse_action(&action.action_id, &menu) {
+                Some(PromptAction::Toggle(option)) => {
+                    toggle_option(&mut selected_options, &option, &menu);
+                    let blocks = prompt_blocks(
+                        &request.message,
+                        &menu,
+                        &selected_options,
+                        request.chalkboard,
+                        continue_reply_enabled,
+                    );
+                    if let Err(e) = core.update_message(&posted, &request.message, blocks).await {
+                        log_important!(warn, "更新Slack消息失败: {}", e);
+                    }
+                }
+                Some(PromptAction::Send) => {
+                    let user_input = reply_text(payload.state.as_ref()).unwrap_or_default();
+                    let response = build_serve_response(
+                        (!user_input.is_empty()).then(|| user_input.clone()),
+                        selected_options.clone(),
+                        vec![],
+                        Some(request.id.clone()),
+                        "slack",
+                    );
+                    let feedback = build_feedback_message(&selected_options, &user_input, false);
+                    return finish(&core, &posted, &request, response, &responder, &feedback).await;
+                }
+                Some(PromptAction::Continue) => {
+                    let response = build_refill_response(Some(request.id.clone()), "slack_continue");
+                    let feedback = build_feedback_message(&[], "", true);
+                    return finish(&core, &posted, &request, response, &responder, &feedback).await;
+                }
+                None => {}
+            }
+        }
+    }
+}
+
+/// 输出回复（记录回复人）并把请求消息标记为已回复
+async fn finish(
+    core: &SlackCore,
+    posted: &PostedMessage,
+    request: &PopupRequest,
+    response: String,
+    responder: &str,
+    feedback: &str,
+) -> Result<()> {
+    write_response(&with_responder(response, responder))?;
+
+    let note = format!("✅ 已由 {} 回复", responder);
+    let blocks = answered_blocks(&request.message, request.chalkboard, &note);
+    if let Err(e) = core.update_message(posted, &request.message, blocks).await {
+        log_important!(warn, "更新Slack消息失败: {}", e);
+    }
+    let _ = core.post_message(feedback, None, Some(&posted.ts)).await;
+    Ok(())
+}
+
+/// 写入响应文件（异步模式由 cache_get 读取），没有响应文件时输出到stdout
+fn write_response(response: &str) -> Result<()> {
+    match std::env::var("MCP_RESPONSE_FILE") {
+        Ok(response_file) => {
+            if let Err(e) = std::fs::write(&response_file, response) {
+                bail!("写入响应文件失败: {}", e);
+            }
+            log_important!(info, "MCP响应已写入文件: {}", response_file);
+        }
+        Err(_) => println!("{}", response),
+    }
+    Ok(())
+}
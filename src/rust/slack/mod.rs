@@ -0,0 +1,8 @@
+pub mod commands;
+pub mod core;
+pub mod mcp_handler;
+pub mod socket;
+
+pub use commands::*;
+pub use core::{test_slack_connection, validate_slack_config, SlackCore};
+pub use mcp_handler::handle_slack_only_mcp_request;
@@ -0,0 +1,252 @@
+// Slack Socket Mode
+// 通过 apps.connections.open 获取 WebSocket 地址后连接，Slack 以信封（envelope）推送交互事件，
+// 每个信封都需要回传 envelope_id 确认，否则 Slack 会重发并在客户端显示错误。
+// 收到 disconnect 或连接断开时重新获取地址并重连，无需公网地址。
+// Slack 只把每个交互事件推送给应用的其中一个连接，而每个请求都在独立的进程中等待回复，
+// 因此同一时间只由一个进程（占用本机转发端口的进程）保持 Socket Mode 连接，
+// 并通过本机转发服务（utils::local_relay）把交互事件转发给所有进程，各进程按消息 ts 认领自己的事件。
+// 持有连接的进程退出后，其他进程重新竞争转发端口
+
+use anyhow::{anyhow, Result};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use serde_json::Value;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+use super::core::SlackCore;
+use crate::constants::slack::{RECONNECT_DELAY_SECS, RELAY_PORT};
+use crate::log_important;
+use crate::utils::local_relay::{self, RelayInfo};
+
+/// 转发信息文件名
+const RELAY_NAME: &str = "slack-socket";
+
+/// 转发通道容量
+const RELAY_CHANNEL_CAPACITY: usize = 64;
+
+/// 竞争转发端口或连接转发服务的次数（持有连接的进程可能正在启动或退出）
+const JOIN_ATTEMPTS: u32 = 5;
+
+/// Socket Mode 推送的消息
+#[derive(Debug, Clone, Deserialize)]
+pub struct Envelope {
+    #[serde(rename = "type")]
+    pub kind: String,
+    #[serde(default)]
+    pub envelope_id: Option<String>,
+    #[serde(default)]
+    pub payload: Option<Value>,
+}
+
+/// Socket Mode 连接，断开后自动重连
+pub struct SocketModeClient {
+    core: SlackCore,
+    stream: Option<WebSocketStream<MaybeTlsStream<TcpStream>>>,
+}
+
+impl SocketModeClient {
+    /// 建立连接（在发送请求消息之前调用，避免错过按钮点击）
+    pub async fn connect(core: &SlackCore) -> Result<Self> {
+        let mut client = Self {
+            core: core.clone(),
+            stream: None,
+        };
+        client.reconnect().await?;
+        Ok(client)
+    }
+
+    async fn reconnect(&mut self) -> Result<()> {
+        if let Some(mut old) = self.stream.take() {
+            let _ = old.close(None).await;
+        }
+        let url = self.core.open_socket_url().await?;
+        let (stream, _) = connect_async(url.as_str())
+            .await
+            .map_err(|e| anyhow!("连接Slack Socket Mode失败: {}", e))?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+
+    /// 等待下一个交互事件（interactive 信封的 payload），信封在返回前已确认
+    pub async fn next_interaction(&mut self) -> Value {
+        loop {
+            match self.next_envelope().await {
+                Ok(Some(envelope)) => {
+                    if envelope.kind == "disconnect" {
+                        // Slack 定期要求刷新连接
+                        self.reconnect_with_retry().await;
+                        continue;
+                    }
+                    if envelope.kind == "interactive" {
+                        if let Some(payload) = envelope.payload {
+                            return payload;
+                        }
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    log_important!(warn, "Slack连接中断: {}", e);
+                    self.reconnect_with_retry().await;
+                }
+            }
+        }
+    }
+
+    async fn reconnect_with_retry(&mut self) {
+        while let Err(e) = self.reconnect().await {
+            log_important!(warn, "重连Slack失败: {}", e);
+            tokio::time::sleep(tokio::time::Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+        }
+    }
+
+    /// 读取一条消息并确认，非 JSON 文本消息返回 None
+    async fn next_envelope(&mut self) -> Result<Option<Envelope>> {
+        let stream = self.stream.as_mut().ok_or_else(|| anyhow!("Slack连接未建立"))?;
+        let message = stream
+            .next()
+            .await
+            .ok_or_else(|| anyhow!("连接已关闭"))?
+            .map_err(|e| anyhow!("{}", e))?;
+        let text = match message {
+            Message::Text(text) => text,
+            Message::Close(_) => return Err(anyhow!("连接已关闭")),
+            _ => return Ok(None),
+        };
+        let Ok(envelope) = serde_json::from_str::<Envelope>(&text) else {
+            return Ok(None);
+        };
+        if let Some(envelope_id) = &envelope.envelope_id {
+            let ack = serde_json::json!({ "envelope_id": envelope_id }).to_string();
+            stream
+                .send(Message::Text(ack))
+                .await
+                .map_err(|e| anyhow!("确认Slack事件失败: {}", e))?;
+        }
+        Ok(Some(envelope))
+    }
+}
+
+/// 本进程的交互事件来源：持有 Socket Mode 连接，或订阅持有连接的进程转发的事件
+pub struct InteractionSource {
+    core: SlackCore,
+    receiver: broadcast::Receiver<String>,
+    task: JoinHandle<()>,
+}
+
+impl InteractionSource {
+    /// 开始接收交互事件（在发送请求消息之前调用，避免错过按钮点击）
+    pub async fn start(core: &SlackCore) -> Result<Self> {
+        let (receiver, task) = subscribe(core).await?;
+        Ok(Self {
+            core: core.clone(),
+            receiver,
+            task,
+        })
+    }
+
+    /// 等待下一个交互事件的 payload；持有连接的进程退出后重新竞争
+    pub async fn next_interaction(&mut self) -> Value {
+        loop {
+            match self.receiver.recv().await {
+                Ok(payload) => {
+                    if let Ok(payload) = serde_json::from_str(&payload) {
+                        return payload;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log_important!(warn, "Slack事件处理不及时，已跳过 {} 条", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => match subscribe(&self.core).await {
+                    Ok((receiver, task)) => {
+                        self.task.abort();
+                        self.receiver = receiver;
+                        self.task = task;
+                    }
+                    Err(e) => {
+                        log_important!(warn, "重新订阅Slack事件失败: {}", e);
+                        tokio::time::sleep(tokio::time::Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl Drop for InteractionSource {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// 占用转发端口则建立 Socket Mode 连接并转发事件，端口已被占用则订阅其转发
+async fn subscribe(core: &SlackCore) -> Result<(broadcast::Receiver<String>, JoinHandle<()>)> {
+    let mut last_error = anyhow!("无法订阅Slack事件");
+    for attempt in 0..JOIN_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        }
+        let started = match TcpListener::bind(("127.0.0.1", RELAY_PORT)).await {
+            Ok(listener) => start_connection(core, listener).await,
+            Err(bind_error) => join_relay()
+                .await
+                .map_err(|e| anyhow!("转发端口 {} 已被占用 ({})，{}", RELAY_PORT, bind_error, e)),
+        };
+        match started {
+            Ok(started) => return Ok(started),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+async fn start_connection(
+    core: &SlackCore,
+    listener: TcpListener,
+) -> Result<(broadcast::Receiver<String>, JoinHandle<()>)> {
+    let relay = RelayInfo::for_listener(&listener)?;
+    // 先写入转发信息，其他进程可在连接建立期间订阅
+    local_relay::write_info(RELAY_NAME, &relay)?;
+    let mut client = match SocketModeClient::connect(core).await {
+        Ok(client) => client,
+        Err(e) => {
+            local_relay::remove_info(RELAY_NAME, &relay.token);
+            return Err(e);
+        }
+    };
+
+    let (sender, receiver) = broadcast::channel(RELAY_CHANNEL_CAPACITY);
+    let relay_sender = sender.clone();
+    let task = tokio::spawn(async move {
+        let forward = async {
+            loop {
+                let payload = client.next_interaction().await;
+                let _ = sender.send(payload.to_string());
+            }
+        };
+        tokio::select! {
+            _ = forward => {}
+            _ = local_relay::serve(listener, relay.token.clone(), relay_sender) => {
+                log_important!(warn, "Slack事件转发服务已停止");
+            }
+        }
+        local_relay::remove_info(RELAY_NAME, &relay.token);
+    });
+    Ok((receiver, task))
+}
+
+async fn join_relay() -> Result<(broadcast::Receiver<String>, JoinHandle<()>)> {
+    let info = local_relay::read_info(RELAY_NAME).map_err(|e| anyhow!("读取Slack事件转发信息失败: {}", e))?;
+    let lines = local_relay::connect(&info)
+        .await
+        .map_err(|e| anyhow!("连接Slack事件转发服务失败: {}", e))?;
+    let (sender, receiver) = broadcast::channel(RELAY_CHANNEL_CAPACITY);
+    let task = tokio::spawn(async move {
+        local_relay::forward(lines, sender).await;
+        log_important!(info, "Slack事件转发连接已断开");
+    });
+    log_important!(info, "Slack Socket Mode由其他进程连接，已订阅其转发的事件");
+    Ok((receiver, task))
+}
@@ -1,9 +1,12 @@
 use crate::config::{save_config, AppState, TelegramConfig};
 use crate::constants::telegram as telegram_constants;
+use crate::telegram::recipients::{chat_access_list, find_chat, responder_label, ChatAccess};
 use crate::telegram::{
-    handle_callback_query, handle_text_message, TelegramCore,
+    handle_callback_query, handle_text_message, has_option_keyboard, toggle_option, TelegramCore,
+    TelegramEvent, UpdateSource,
 };
 use crate::log_important;
+use std::collections::HashMap;
 use tauri::{AppHandle, Emitter, Manager, State};
 use teloxide::prelude::*;
 
@@ -24,6 +27,11 @@ pub async fn set_telegram_config(
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<(), String> {
+    crate::telegram::webhook::validate_webhook_config(&telegram_config).map_err(|e| e.to_string())?;
+    if telegram_config.enabled && !telegram_config.chat_id.trim().is_empty() {
+        chat_access_list(&telegram_config).map_err(|e| format!("Telegram接收者配置无效: {}", e))?;
+    }
+
     {
         let mut config = state
             .config
@@ -79,6 +87,10 @@ pub async fn auto_get_chat_id(
     
     if let Some(state) = app_handle.try_state::<AppState>() {
         if let Ok(config) = state.config.lock() {
+            // webhook注册期间getUpdates不可用
+            if config.telegram_config.webhook_enabled {
+                return Err("Webhook 模式下无法自动获取 Chat ID，请先关闭 Webhook 或手动填写".to_string());
+            }
             let api_url = &config.telegram_config.api_base_url;
             if api_url != telegram_constants::API_BASE_URL {
                 if let Ok(url) = reqwest::Url::parse(api_url) {
@@ -182,75 +194,73 @@ pub async fn start_telegram_sync(
     app_handle: AppHandle,
 ) -> Result<(), String> {
     // 获取Telegram配置
-    let (enabled, bot_token, chat_id, continue_reply_enabled) = {
+    let (telegram_config, continue_reply_enabled) = {
         let config = state
             .config
             .lock()
             .map_err(|e| format!("获取配置失败: {}", e))?;
         (
-            config.telegram_config.enabled,
-            config.telegram_config.bot_token.clone(),
-            config.telegram_config.chat_id.clone(),
+            config.telegram_config.clone(),
             config.reply_config.enable_continue_reply,
         )
     };
 
-    if !enabled {
+    if !telegram_config.enabled {
         return Ok(());
     }
 
-    if bot_token.trim().is_empty() || chat_id.trim().is_empty() {
+    if telegram_config.bot_token.trim().is_empty() || telegram_config.chat_id.trim().is_empty() {
         return Err("Telegram配置不完整".to_string());
     }
 
-    // 获取API URL配置
-    let api_url = {
-        let config = state
-            .config
-            .lock()
-            .map_err(|e| format!("获取配置失败: {}", e))?;
-        config.telegram_config.api_base_url.clone()
-    };
-
     // 使用默认API URL时传递None，否则传递自定义URL
-    let api_url_option = if api_url == telegram_constants::API_BASE_URL {
+    let api_url_option = if telegram_config.api_base_url == telegram_constants::API_BASE_URL {
         None
     } else {
-        Some(api_url)
+        Some(telegram_config.api_base_url.clone())
     };
 
     // 创建Telegram核心实例
-    let core = TelegramCore::new_with_api_url(bot_token.clone(), chat_id.clone(), api_url_option)
-        .map_err(|e| format!("创建Telegram核心失败: {}", e))?;
-
-    // 发送选项消息
-    core.send_options_message(&message, &menu, chalkboard)
-        .await
-        .map_err(|e| format!("发送选项消息失败: {}", e))?;
-
-    // 短暂延迟确保消息顺序
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-
-    // 发送操作消息
-    core.send_operation_message(continue_reply_enabled)
-        .await
-        .map_err(|e| format!("发送操作消息失败: {}", e))?;
+    let core = TelegramCore::new_with_api_url(
+        telegram_config.bot_token.clone(),
+        telegram_config.chat_id.clone(),
+        api_url_option,
+    )
+    .map_err(|e| format!("创建Telegram核心失败: {}", e))?;
+    let chats = chat_access_list(&telegram_config).map_err(|e| format!("Telegram接收者配置无效: {}", e))?;
+
+    // 发送到主聊天和额外接收者，主聊天发送失败时报错，其他聊天只记录日志
+    let mut reachable = Vec::new();
+    for (index, chat) in chats.into_iter().enumerate() {
+        let chat_core = core.for_chat(chat.chat_id);
+        let sent = async {
+            chat_core
+                .send_options_message(&message, &menu, chalkboard)
+                .await
+                .map_err(|e| format!("发送选项消息失败: {}", e))?;
+
+            // 短暂延迟确保消息顺序
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+
+            chat_core
+                .send_operation_message(continue_reply_enabled)
+                .await
+                .map_err(|e| format!("发送操作消息失败: {}", e))
+        }
+        .await;
+        match sent {
+            Ok(_) => reachable.push(chat),
+            Err(e) if index == 0 => return Err(e),
+            Err(e) => log_important!(warn, "发送Telegram消息到 {} 失败: {}", chat.name, e),
+        }
+    }
 
     // 启动消息监听（根据是否有预定义选项选择监听模式）
-    let bot_token_clone = bot_token.clone();
-    let chat_id_clone = chat_id.clone();
     let app_handle_clone = app_handle.clone();
 
     tokio::spawn(async move {
         // 使用统一的监听器，传递选项参数
-        match start_telegram_listener(
-            bot_token_clone,
-            chat_id_clone,
-            app_handle_clone,
-            menu,
-        )
-        .await
-        {
+        match start_telegram_listener(core, telegram_config, reachable, app_handle_clone, menu).await {
             Ok(_) => {}
             Err(e) => log_important!(warn, "Telegram消息监听出错: {}", e),
         }
@@ -260,188 +270,181 @@ pub async fn start_telegram_sync(
 }
 
 /// 启动Telegram消息监听（统一版本，支持有选项和无选项模式）
+/// 弹窗只有一份回复内容，各聊天中的选择和输入共同作用于弹窗，最先发送或继续的回复生效
 async fn start_telegram_listener(
-    bot_token: String,
-    chat_id: String,
+    core: TelegramCore,
+    telegram_config: TelegramConfig,
+    chats: Vec<ChatAccess>,
     app_handle: AppHandle,
     menu_list: Vec<String>,
 ) -> Result<(), String> {
-    // 从AppHandle获取应用状态来读取API URL配置
-    let api_url = match app_handle.try_state::<AppState>() {
-        Some(state) => {
-            let config = state
-                .config
-                .lock()
-                .map_err(|e| format!("获取配置失败: {}", e))?;
-            let api_url = config.telegram_config.api_base_url.clone();
-                         if api_url == telegram_constants::API_BASE_URL {
-                None
-            } else {
-                Some(api_url)
-            }
-        }
-        None => None, // 如果无法获取状态，使用默认API
-    };
-
-    let core = TelegramCore::new_with_api_url(bot_token, chat_id, api_url)
-        .map_err(|e| format!("创建Telegram核心失败: {}", e))?;
-
-    let mut offset = 0i32;
+    // 按配置使用长轮询或webhook接收更新
+    let mut source = UpdateSource::start(&core.bot, &telegram_config)
+        .await
+        .map_err(|e| format!("启动Telegram更新接收失败: {}", e))?;
 
-    // 用于跟踪选项状态和消息ID
-    let mut selected_options: std::collections::HashSet<String> = std::collections::HashSet::new();
-    let mut options_message_id: Option<i32> = None;
+    // 用于跟踪选项状态和各聊天中选项消息的ID
+    let mut selected_options: Vec<String> = Vec::new(); // 保持 menu 中的顺序
+    let mut options_message_ids: HashMap<ChatId, i32> = HashMap::new();
     let mut user_input: String = String::new(); // 存储用户输入的文本
     let menu = menu_list;
     let has_options = !menu.is_empty();
 
-    // 获取当前最新的消息ID作为基准
-    if let Ok(updates) = core.bot.get_updates().limit(10).await {
-        if let Some(update) = updates.last() {
-            offset = update.id.0 as i32 + 1;
-        }
-    }
-
     // 监听循环
     loop {
-        match core.bot.get_updates().offset(offset).timeout(10).await {
-            Ok(updates) => {
-                for update in updates {
-                    offset = update.id.0 as i32 + 1;
-
-                    match update.kind {
-                        teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
-                            // 只有当有预定义选项时才处理 callback queries
-                            if has_options {
-                                // 从callback_query中提取消息ID
-                                if let Some(message) = &callback_query.message {
-                                    if options_message_id.is_none() {
-                                        options_message_id = Some(message.id().0);
-                                    }
-                                }
-
-                                if let Ok(Some(option)) =
-                                    handle_callback_query(&core.bot, &callback_query, core.chat_id)
-                                        .await
-                                {
-                                    // 切换选项状态
-                                    let selected = if selected_options.contains(&option) {
-                                        selected_options.remove(&option);
-                                        false
-                                    } else {
-                                        selected_options.insert(option.clone());
-                                        true
-                                    };
-
-                                    // 发送事件到前端
-                                    use crate::telegram::TelegramEvent;
-                                    let event = TelegramEvent::OptionToggled {
-                                        option: option.clone(),
-                                        selected,
-                                    };
-
-                                    let _ = app_handle.emit("telegram-event", &event);
-
-                                    // 更新按钮状态
-                                    if let Some(msg_id) = options_message_id {
-                                        let selected_vec: Vec<String> =
-                                            selected_options.iter().cloned().collect();
-                                        if let Ok(_) = core
-                                            .update_inline_keyboard(
-                                                msg_id,
-                                                &menu,
-                                                &selected_vec,
-                                            )
-                                            .await {}
-                                    }
-                                }
-                            }
+        for update in source.next_updates().await {
+            match update.kind {
+                teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
+                    // 只有当有预定义选项时才处理 callback queries
+                    if !has_options {
+                        continue;
+                    }
+                    let Some(message) = &callback_query.message else {
+                        continue;
+                    };
+                    let Some(chat) = find_chat(&chats, message.chat().id) else {
+                        continue;
+                    };
+                    if !chat.may_answer(Some(callback_query.from.id.0)) {
+                        let _ = core
+                            .bot
+                            .answer_callback_query(&callback_query.id)
+                            .text("无权回复此请求")
+                            .await;
+                        continue;
+                    }
+
+                    // 从callback_query中提取消息ID
+                    options_message_ids.entry(chat.chat_id).or_insert(message.id().0);
+
+                    if let Ok(Some(option)) =
+                        handle_callback_query(&core.bot, &callback_query, chat.chat_id, &menu).await
+                    {
+                        // 切换选项状态
+                        let selected = toggle_option(&mut selected_options, &option, &menu);
+
+                        // 发送事件到前端
+                        let event = TelegramEvent::OptionToggled {
+                            option: option.clone(),
+                            selected,
+                        };
+
+                        let _ = app_handle.emit("telegram-event", &event);
+
+                        // 更新各聊天中的按钮状态
+                        for (chat_id, msg_id) in &options_message_ids {
+                            let _ = core
+                                .for_chat(*chat_id)
+                                .update_inline_keyboard(*msg_id, &menu, &selected_options)
+                                .await;
                         }
-                        teloxide::types::UpdateKind::Message(message) => {
-                            // 只有当有预定义选项时才检查 inline keyboard
-                            if has_options {
-                                // 检查是否是包含 inline keyboard 的选项消息
-                                if let Some(inline_keyboard) = message.reply_markup() {
-                                    // 检查是否包含我们的选项按钮
-                                    let mut contains_our_options = false;
-                                    for row in &inline_keyboard.inline_keyboard {
-                                        for button in row {
-                                            if let teloxide::types::InlineKeyboardButtonKind::CallbackData(callback_data) = &button.kind {
-                                                if callback_data.starts_with("toggle:") {
-                                                    contains_our_options = true;
-                                                    break;
-                                                }
-                                            }
-                                        }
-                                        if contains_our_options {
-                                            break;
-                                        }
-                                    }
-
-                                    if contains_our_options {
-                                        options_message_id = Some(message.id.0);
-                                    }
-                                }
-                            }
+                    }
+                }
+                teloxide::types::UpdateKind::Message(message) => {
+                    let Some(chat) = find_chat(&chats, message.chat.id) else {
+                        continue;
+                    };
+                    let chat_core = core.for_chat(chat.chat_id);
+
+                    // 只有当有预定义选项时才检查 inline keyboard
+                    if has_options {
+                        // 检查是否是包含选项按钮的选项消息
+                        if has_option_keyboard(&message) {
+                            options_message_ids.insert(chat.chat_id, message.id.0);
+                        }
+                    }
 
-                            if let Ok(Some(event)) = handle_text_message(
-                                &message,
-                                core.chat_id,
-                                None, // 简化版本不过滤消息ID
-                            )
-                            .await
-                            {
-                                // 处理发送和继续按钮，发送反馈消息
-                                match &event {
-                                    crate::telegram::TelegramEvent::SendPressed => {
-                                        let selected_list: Vec<String> =
-                                            selected_options.iter().cloned().collect();
-
-                                        // 使用统一的反馈消息生成函数
-                                        let feedback_message =
-                                            crate::telegram::core::build_feedback_message(
-                                                &selected_list,
-                                                &user_input,
-                                                false, // 不是继续操作
-                                            );
-
-                                        let _ = core.send_message(&feedback_message).await;
-                                    }
-                                    crate::telegram::TelegramEvent::ContinuePressed => {
-                                        // 使用统一的反馈消息生成函数
-                                        let feedback_message =
-                                            crate::telegram::core::build_feedback_message(
-                                                &[],  // 继续操作没有选项
-                                                "",   // 继续操作没有用户输入
-                                                true, // 是继续操作
-                                            );
-
-                                        let _ = core.send_message(&feedback_message).await;
-                                    }
-                                    crate::telegram::TelegramEvent::TextUpdated { text } => {
-                                        // 保存用户输入的文本
-                                        user_input = text.clone();
-                                    }
-                                    _ => {
-                                        // 其他事件不需要发送反馈消息
-                                    }
-                                }
-
-                                let _ = app_handle.emit("telegram-event", &event);
-                            }
+                    // 群组中无权回复的成员的消息不影响回复内容
+                    let user = message.from.as_ref();
+                    if !chat.may_answer(user.map(|u| u.id.0)) {
+                        if let Ok(Some(TelegramEvent::SendPressed { .. } | TelegramEvent::ContinuePressed { .. })) =
+                            handle_text_message(&message, chat.chat_id, None).await
+                        {
+                            let _ = chat_core
+                                .send_message(&format!("⚠️ {} 无权回复此请求", responder_label(user, chat)))
+                                .await;
+                        }
+                        continue;
+                    }
+
+                    // 图片和文件保存到 pantry 后交给弹窗，与粘贴的食材一起提交
+                    match chat_core.stash_message_attachment(&message).await {
+                        Ok(Some(ingredient)) => {
+                            let event = TelegramEvent::IngredientReceived { ingredient };
+                            let _ = app_handle.emit("telegram-event", &event);
+                        }
+                        Ok(None) => {}
+                        Err(e) => {
+                            log_important!(warn, "保存Telegram附件失败: {}", e);
+                            let _ = chat_core.send_message(&format!("⚠️ 附件未能添加：{}", e)).await;
+                        }
+                    }
+
+                    let Ok(Some(mut event)) = handle_text_message(
+                        &message,
+                        chat.chat_id,
+                        None, // 简化版本不过滤消息ID
+                    )
+                    .await
+                    else {
+                        continue;
+                    };
+
+                    // 处理发送和继续按钮，发送反馈消息并记录回复人
+                    let finished = match &mut event {
+                        TelegramEvent::SendPressed { responder } => {
+                            *responder = Some(responder_label(user, chat));
+                            // 使用统一的反馈消息生成函数
+                            let feedback_message = crate::telegram::core::build_feedback_message(
+                                &selected_options,
+                                &user_input,
+                                false, // 不是继续操作
+                            );
+
+                            let _ = chat_core.send_message(&feedback_message).await;
+                            responder.clone()
+                        }
+                        TelegramEvent::ContinuePressed { responder } => {
+                            *responder = Some(responder_label(user, chat));
+                            // 使用统一的反馈消息生成函数
+                            let feedback_message = crate::telegram::core::build_feedback_message(
+                                &[],  // 继续操作没有选项
+                                "",   // 继续操作没有用户输入
+                                true, // 是继续操作
+                            );
+
+                            let _ = chat_core.send_message(&feedback_message).await;
+                            responder.clone()
+                        }
+                        TelegramEvent::TextUpdated { text } => {
+                            // 保存用户输入的文本
+                            user_input = text.clone();
+                            None
                         }
                         _ => {
-                            // 忽略其他类型的更新
+                            // 其他事件不需要发送反馈消息
+                            None
+                        }
+                    };
+
+                    let _ = app_handle.emit("telegram-event", &event);
+
+                    // 已有人回复，通知其他聊天后停止监听
+                    if let Some(responder) = finished {
+                        for other in chats.iter().filter(|c| c.chat_id != chat.chat_id) {
+                            let _ = core
+                                .for_chat(other.chat_id)
+                                .send_message(&format!("✅ 已由 {} 回复", responder))
+                                .await;
                         }
+                        source.finish().await;
+                        return Ok(());
                     }
                 }
-            }
-            Err(_) => {
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                _ => {
+                    // 忽略其他类型的更新
+                }
             }
         }
-
-        // 短暂延迟避免过于频繁的请求
-        tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
     }
 }
@@ -2,15 +2,25 @@ use anyhow::Result;
 use serde::Serialize;
 // use tauri::{AppHandle, Emitter}; // 暂时不需要，由调用方处理事件
 use teloxide::{
+    net::Download,
     prelude::*,
     types::{
-        ChatId, InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup,
+        ChatId, FileId, InlineKeyboardButton, InlineKeyboardMarkup, KeyboardButton, KeyboardMarkup,
         MessageId, ParseMode,
     },
     Bot,
 };
 
 use super::markdown::process_telegram_markdown;
+use super::recipients::parse_chat_id;
+use crate::mcp::ingredient_kind;
+use crate::ui::commands::{stash_ingredient, CachedIngredient};
+
+/// 选项按钮的 callback_data 前缀
+pub const OPTION_CALLBACK_PREFIX: &str = "toggle:";
+
+/// Bot API 允许机器人下载的最大文件大小
+pub const MAX_DOWNLOAD_BYTES: u64 = 20 * 1024 * 1024;
 
 /// Telegram事件类型
 #[derive(Debug, Clone, Serialize)]
@@ -20,10 +30,56 @@ pub enum TelegramEvent {
     OptionToggled { option: String, selected: bool },
     /// 文本输入更新
     TextUpdated { text: String },
-    /// 继续按钮点击
-    ContinuePressed,
+    /// 继续按钮点击，responder 为回复人（多接收者时由监听循环填写）
+    ContinuePressed { responder: Option<String> },
     /// 发送按钮点击
-    SendPressed,
+    SendPressed { responder: Option<String> },
+    /// 收到图片或文件（已保存到 pantry）
+    IngredientReceived { ingredient: CachedIngredient },
+}
+
+/// 消息中的图片或文件
+#[derive(Debug, Clone)]
+pub struct TelegramAttachment {
+    pub file_id: FileId,
+    pub size: u64,
+    pub dish_type: String,
+    pub tag: Option<String>,
+}
+
+/// 提取消息中的附件：图片取最大尺寸，文件类型优先使用 Telegram 提供的 MIME，其次按扩展名推断
+pub fn message_attachment(message: &Message) -> Option<TelegramAttachment> {
+    if let Some(photo) = message.photo().and_then(|sizes| sizes.iter().max_by_key(|p| p.width * p.height)) {
+        return Some(TelegramAttachment {
+            file_id: photo.file.id.clone(),
+            size: photo.file.size as u64,
+            dish_type: "image/jpeg".to_string(),
+            tag: None,
+        });
+    }
+
+    let document = message.document()?;
+    let from_extension = || {
+        document
+            .file_name
+            .as_deref()
+            .and_then(|name| name.rsplit_once('.'))
+            .and_then(|(_, ext)| ingredient_kind::dish_type_from_extension(ext))
+            .map(str::to_string)
+    };
+    let dish_type = document
+        .mime_type
+        .as_ref()
+        .map(|mime| mime.essence_str().to_string())
+        .filter(|mime| ingredient_kind::kind_of(mime).is_some())
+        .or_else(from_extension)
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+    Some(TelegramAttachment {
+        file_id: document.file.id.clone(),
+        size: document.file.size as u64,
+        dish_type,
+        tag: document.file_name.clone(),
+    })
 }
 
 /// Telegram Bot 核心功能
@@ -50,18 +106,19 @@ impl TelegramCore {
         }
 
         // 解析chat_id
-        let chat_id = if chat_id.starts_with('@') {
-            return Err(anyhow::anyhow!("暂不支持@username格式，请使用数字Chat ID"));
-        } else {
-            let id = chat_id
-                .parse::<i64>()
-                .map_err(|_| anyhow::anyhow!("无效的Chat ID格式，请使用数字ID"))?;
-            ChatId(id)
-        };
+        let chat_id = parse_chat_id(&chat_id)?;
 
         Ok(Self { bot, chat_id })
     }
 
+    /// 使用同一个Bot向另一个聊天发送消息
+    pub fn for_chat(&self, chat_id: ChatId) -> Self {
+        Self {
+            bot: self.bot.clone(),
+            chat_id,
+        }
+    }
+
     /// 发送普通消息
     pub async fn send_message(&self, message: &str) -> Result<()> {
         self.send_message_with_markdown(message, false).await
@@ -170,10 +227,10 @@ impl TelegramCore {
         let mut keyboard_rows = Vec::new();
 
         // 添加选项按钮（每行最多2个）
-        for chunk in menu.chunks(2) {
+        for (row_index, chunk) in menu.chunks(2).enumerate() {
             let mut row = Vec::new();
-            for option in chunk {
-                let callback_data = format!("toggle:{}", option);
+            for (column, option) in chunk.iter().enumerate() {
+                let callback_data = option_callback_data(row_index * 2 + column);
                 // 根据选中状态显示按钮
                 let button_text = if selected_options.contains(option) {
                     format!("✅ {}", option)
@@ -203,6 +260,33 @@ impl TelegramCore {
             .one_time_keyboard()
     }
 
+    /// 下载消息中的附件并保存到 pantry，与 GUI 中粘贴的食材走同样的类型识别和图片处理；消息没有附件时返回 None
+    pub async fn stash_message_attachment(&self, message: &Message) -> Result<Option<CachedIngredient>> {
+        let Some(attachment) = message_attachment(message) else {
+            return Ok(None);
+        };
+        if attachment.size > MAX_DOWNLOAD_BYTES {
+            return Err(anyhow::anyhow!(
+                "文件太大（Telegram 机器人最多下载 {} MB）",
+                MAX_DOWNLOAD_BYTES / (1024 * 1024)
+            ));
+        }
+
+        let file = self
+            .bot
+            .get_file(attachment.file_id)
+            .await
+            .map_err(|e| anyhow::anyhow!("获取文件信息失败: {}", e))?;
+        let mut bytes = Vec::with_capacity(attachment.size as usize);
+        self.bot
+            .download_file(&file.path, &mut bytes)
+            .await
+            .map_err(|e| anyhow::anyhow!("下载文件失败: {}", e))?;
+
+        let ingredient = stash_ingredient(bytes, &attachment.dish_type, attachment.tag).map_err(|e| anyhow::anyhow!(e))?;
+        Ok(Some(ingredient))
+    }
+
     /// 更新inline keyboard中的选项状态
     pub async fn update_inline_keyboard(
         &self,
@@ -227,11 +311,54 @@ impl TelegramCore {
     }
 }
 
+/// 选项按钮的 callback_data
+///
+/// Telegram 限制 callback_data 最长 64 字节，选项文本可能超出，因此只携带选项在 menu 中的序号
+pub fn option_callback_data(index: usize) -> String {
+    format!("{}{}", OPTION_CALLBACK_PREFIX, index)
+}
+
+/// 从 callback_data 解析出对应的选项
+///
+/// 兼容旧版本直接携带选项文本的按钮；不在 menu 中的选项（如上一次请求的按钮）返回 None
+pub fn parse_option_callback(data: &str, menu: &[String]) -> Option<String> {
+    let value = data.strip_prefix(OPTION_CALLBACK_PREFIX)?;
+    match value.parse::<usize>() {
+        Ok(index) => menu.get(index).cloned(),
+        Err(_) => menu.iter().find(|option| option.as_str() == value).cloned(),
+    }
+}
+
+/// 切换选项的选中状态，选中列表始终保持 menu 中的顺序；返回切换后是否选中
+pub fn toggle_option(selected_options: &mut Vec<String>, option: &str, menu: &[String]) -> bool {
+    let selected = !selected_options.iter().any(|o| o == option);
+    if selected {
+        selected_options.push(option.to_string());
+    } else {
+        selected_options.retain(|o| o != option);
+    }
+    selected_options.sort_by_key(|o| menu.iter().position(|m| m == o).unwrap_or(usize::MAX));
+    selected
+}
+
+/// 消息是否带有选项按钮（用于识别选项消息）
+pub fn has_option_keyboard(message: &Message) -> bool {
+    message.reply_markup().is_some_and(|keyboard| {
+        keyboard.inline_keyboard.iter().flatten().any(|button| {
+            matches!(
+                &button.kind,
+                teloxide::types::InlineKeyboardButtonKind::CallbackData(data) if data.starts_with(OPTION_CALLBACK_PREFIX)
+            )
+        })
+    })
+}
+
 /// 处理callback query的通用函数（不发送事件，由调用方处理）
 pub async fn handle_callback_query(
     bot: &Bot,
     callback_query: &CallbackQuery,
     target_chat_id: ChatId,
+    menu: &[String],
 ) -> ResponseResult<Option<String>> {
     // 检查是否是目标聊天
     if let Some(message) = &callback_query.message {
@@ -240,14 +367,10 @@ pub async fn handle_callback_query(
         }
     }
 
-    let mut toggled_option = None;
-
-    if let Some(data) = &callback_query.data {
-        if data.starts_with("toggle:") {
-            let option = data.strip_prefix("toggle:").unwrap().to_string();
-            toggled_option = Some(option);
-        }
-    }
+    let toggled_option = callback_query
+        .data
+        .as_deref()
+        .and_then(|data| parse_option_callback(data, menu));
 
     // 回答callback query
     bot.answer_callback_query(&callback_query.id).await?;
@@ -273,10 +396,11 @@ pub async fn handle_text_message(
         }
     }
 
-    if let Some(text) = message.text() {
+    // 图片和文件的说明文字同样作为补充说明
+    if let Some(text) = message.text().or(message.caption()) {
         let event = match text {
-            "⏩继续" => TelegramEvent::ContinuePressed,
-            "↗️发送" => TelegramEvent::SendPressed,
+            "⏩继续" => TelegramEvent::ContinuePressed { responder: None },
+            "↗️发送" => TelegramEvent::SendPressed { responder: None },
             _ => TelegramEvent::TextUpdated {
                 text: text.to_string(),
             },
@@ -366,3 +490,29 @@ pub async fn test_telegram_connection_with_api_url(
         Err(e) => Err(anyhow::anyhow!("发送测试消息失败: {}", e)),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_option_callback() {
+        let menu: Vec<String> = ["继续优化代码结构并补充完整的单元测试用例", "B", "C"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let data = option_callback_data(0);
+        assert!(data.len() <= 64);
+        assert_eq!(parse_option_callback(&data, &menu).as_deref(), Some(menu[0].as_str()));
+        assert_eq!(parse_option_callback("toggle:B", &menu).as_deref(), Some("B"));
+        assert_eq!(parse_option_callback("toggle:9", &menu), None);
+        assert_eq!(parse_option_callback("other:1", &menu), None);
+
+        let mut selected = Vec::new();
+        assert!(toggle_option(&mut selected, "C", &menu));
+        assert!(toggle_option(&mut selected, "B", &menu));
+        assert_eq!(selected, vec!["B", "C"]);
+        assert!(!toggle_option(&mut selected, "C", &menu));
+        assert_eq!(selected, vec!["B"]);
+    }
+}
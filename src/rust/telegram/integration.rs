@@ -4,7 +4,7 @@ use tauri::{AppHandle, Emitter};
 use teloxide::prelude::*;
 use tokio::sync::Mutex;
 
-use super::core::{handle_text_message, TelegramCore, TelegramEvent};
+use super::core::{handle_text_message, parse_option_callback, toggle_option, TelegramCore, TelegramEvent};
 use crate::log_important;
 
 /// Telegram集成管理器
@@ -73,7 +73,7 @@ impl TelegramIntegration {
         }
 
         // 启动消息监听
-        self.start_message_listener().await?;
+        self.start_message_listener(menu).await?;
 
         Ok(())
     }
@@ -81,7 +81,7 @@ impl TelegramIntegration {
 
 
     /// 启动消息监听
-    async fn start_message_listener(&mut self) -> Result<()> {
+    async fn start_message_listener(&mut self, menu: Vec<String>) -> Result<()> {
         let bot = self.core.bot.clone();
         let chat_id = self.core.chat_id;
         let app_handle = self.app_handle.clone();
@@ -119,19 +119,11 @@ impl TelegramIntegration {
                                             }
 
                                             if let Some(data) = &callback_query.data {
-                                                if data.starts_with("toggle:") {
-                                                    let option = data.strip_prefix("toggle:").unwrap().to_string();
-
+                                                if let Some(option) = parse_option_callback(data, &menu) {
                                                     // 切换选项状态
                                                     let selected = {
                                                         let mut selected_opts = selected_options.lock().await;
-                                                        if selected_opts.contains(&option) {
-                                                            selected_opts.retain(|x| x != &option);
-                                                            false
-                                                        } else {
-                                                            selected_opts.push(option.clone());
-                                                            true
-                                                        }
+                                                        toggle_option(&mut selected_opts, &option, &menu)
                                                     };
 
                                                     // 发送更新后的事件到前端
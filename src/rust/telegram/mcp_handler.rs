@@ -1,10 +1,13 @@
 use anyhow::Result;
-use std::collections::HashSet;
 use teloxide::prelude::*;
 
 use crate::config::load_standalone_config;
-use crate::mcp::types::{build_refill_response, build_serve_response, PopupRequest};
-use crate::telegram::{handle_callback_query, handle_text_message, TelegramCore, TelegramEvent};
+use crate::mcp::types::{build_refill_response, build_serve_response, with_responder, PopupRequest};
+use crate::telegram::recipients::{chat_access_list, responder_label, ChatAccess};
+use crate::telegram::{
+    handle_callback_query, handle_text_message, has_option_keyboard, toggle_option, TelegramCore,
+    TelegramEvent, UpdateSource,
+};
 use crate::log_important;
 
 /// 处理纯Telegram模式的MCP请求（不启动GUI）
@@ -40,124 +43,159 @@ pub async fn handle_telegram_only_mcp_request(request_file: &str) -> Result<()>
         api_url,
     )?;
 
-    // 发送消息到Telegram
+    // 发送到主聊天和额外接收者
     let menu = request.menu.clone().unwrap_or_default();
+    let mut sessions = Vec::new();
+    for access in chat_access_list(telegram_config)? {
+        let chat_core = core.for_chat(access.chat_id);
+
+        // 发送选项消息
+        if let Err(e) = chat_core
+            .send_options_message(&request.message, &menu, request.chalkboard)
+            .await
+        {
+            log_important!(warn, "发送Telegram消息到 {} 失败: {}", access.name, e);
+            continue;
+        }
 
-    // 发送选项消息
-    core.send_options_message(&request.message, &menu, request.chalkboard)
-        .await?;
+        // 短暂延迟确保消息顺序
+        tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
 
-    // 短暂延迟确保消息顺序
-    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+        // 发送操作消息（假设启用继续回复）
+        chat_core.send_operation_message(true).await?;
+        sessions.push(ChatSession::new(chat_core, access));
+    }
+    if sessions.is_empty() {
+        anyhow::bail!("Telegram消息未能发送到任何聊天");
+    }
 
-    // 发送操作消息（假设启用继续回复）
-    core.send_operation_message(true).await?;
+    // 按配置使用长轮询或webhook接收更新
+    let source = UpdateSource::start(&core.bot, telegram_config).await?;
 
     // 启动消息监听循环
-    start_telegram_mcp_listener(core, request, menu).await
+    start_telegram_mcp_listener(sessions, source, request, menu).await
+}
+
+/// 单个聊天中的回复状态
+struct ChatSession {
+    core: TelegramCore,
+    access: ChatAccess,
+    // 选中的选项（保持 menu 中的顺序）
+    selected_options: Vec<String>,
+    user_input: String,
+    // 收到的图片和文件（pantry 中的 spice_id）
+    spice_ids: Vec<String>,
+    options_message_id: Option<i32>,
+}
+
+impl ChatSession {
+    fn new(core: TelegramCore, access: ChatAccess) -> Self {
+        Self {
+            core,
+            access,
+            selected_options: Vec::new(),
+            user_input: String::new(),
+            spice_ids: Vec::new(),
+            options_message_id: None,
+        }
+    }
+
+    fn discard_spices(&mut self) {
+        for spice_id in self.spice_ids.drain(..) {
+            let _ = crate::mcp::discard_spice(&spice_id);
+        }
+    }
 }
 
 /// 启动Telegram MCP消息监听循环
 async fn start_telegram_mcp_listener(
-    core: TelegramCore,
+    mut sessions: Vec<ChatSession>,
+    mut source: UpdateSource,
     request: PopupRequest,
     menu: Vec<String>,
 ) -> Result<()> {
-    let mut offset = 0i32;
-    let mut selected_options: HashSet<String> = HashSet::new();
-    let mut user_input = String::new();
-    let mut options_message_id: Option<i32> = None;
-
-    // 获取当前最新的消息ID作为基准
-    if let Ok(updates) = core.bot.get_updates().limit(10).await {
-        if let Some(update) = updates.last() {
-            offset = update.id.0 as i32 + 1;
-        }
-    }
-
-    // 监听循环（简化版本，只等待发送或继续操作）
+    // 监听循环（简化版本，只等待发送或继续操作），最先发送或继续的回复生效
     loop {
-        match core.bot.get_updates().offset(offset).timeout(10).await {
-            Ok(updates) => {
-                for update in updates {
-                    offset = update.id.0 as i32 + 1;
-
-                    match update.kind {
-                        teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
-                            if let Err(e) = handle_callback_query_update(
-                                &core,
-                                &callback_query,
-                                &menu,
-                                &mut selected_options,
-                                &mut options_message_id,
-                            ).await {
-                                log_important!(warn, "处理callback query失败: {}", e);
-                            }
-                        }
-                        teloxide::types::UpdateKind::Message(message) => {
-                            // 处理选项消息ID识别
-                            if let Err(e) = handle_message_update(
-                                &core,
-                                &message,
-                                &menu,
-                                &mut options_message_id,
-                                &mut user_input,
-                                &selected_options,
-                                &request,
-                            ).await {
-                                if let Some(_result) = e.downcast_ref::<ProcessingComplete>() {
-                                    return Ok(());
+        for update in source.next_updates().await {
+            match update.kind {
+                teloxide::types::UpdateKind::CallbackQuery(callback_query) => {
+                    let Some(chat_id) = callback_query.message.as_ref().map(|m| m.chat().id) else {
+                        continue;
+                    };
+                    let Some(session) = sessions.iter_mut().find(|s| s.access.chat_id == chat_id) else {
+                        continue;
+                    };
+                    if let Err(e) = handle_callback_query_update(session, &callback_query, &menu).await {
+                        log_important!(warn, "处理callback query失败: {}", e);
+                    }
+                }
+                teloxide::types::UpdateKind::Message(message) => {
+                    let Some(index) = sessions.iter().position(|s| s.access.chat_id == message.chat.id) else {
+                        continue;
+                    };
+                    // 处理选项消息ID识别
+                    match handle_message_update(&mut sessions[index], &message, &menu, &request).await {
+                        Ok(Some(responder)) => {
+                            // 通知其他聊天已有人回复，并释放其中已保存的食材
+                            for (i, other) in sessions.iter_mut().enumerate() {
+                                if i == index {
+                                    continue;
                                 }
-                                log_important!(warn, "处理消息失败: {}", e);
+                                other.discard_spices();
+                                let _ = other.core.send_message(&format!("✅ 已由 {} 回复", responder)).await;
                             }
+                            source.finish().await;
+                            return Ok(());
                         }
-                        _ => {}
+                        Ok(None) => {}
+                        Err(e) => log_important!(warn, "处理消息失败: {}", e),
                     }
                 }
-            }
-            Err(_) => {
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                _ => {}
             }
         }
-
-        // 短暂延迟避免过于频繁的请求
-        tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
     }
 }
 
 /// 处理callback query更新
 async fn handle_callback_query_update(
-    core: &TelegramCore,
+    session: &mut ChatSession,
     callback_query: &teloxide::types::CallbackQuery,
     menu: &[String],
-    selected_options: &mut HashSet<String>,
-    options_message_id: &mut Option<i32>,
 ) -> Result<()> {
     // 只有当有预定义选项时才处理 callback queries
     if menu.is_empty() {
         return Ok(());
     }
 
+    if !session.access.may_answer(Some(callback_query.from.id.0)) {
+        session
+            .core
+            .bot
+            .answer_callback_query(&callback_query.id)
+            .text("无权回复此请求")
+            .await?;
+        return Ok(());
+    }
+
     // 从callback_query中提取消息ID
     if let Some(message) = &callback_query.message {
-        if options_message_id.is_none() {
-            *options_message_id = Some(message.id().0);
+        if session.options_message_id.is_none() {
+            session.options_message_id = Some(message.id().0);
         }
     }
 
-    if let Ok(Some(option)) = handle_callback_query(&core.bot, callback_query, core.chat_id).await {
+    if let Ok(Some(option)) =
+        handle_callback_query(&session.core.bot, callback_query, session.core.chat_id, menu).await
+    {
         // 切换选项状态
-        if selected_options.contains(&option) {
-            selected_options.remove(&option);
-        } else {
-            selected_options.insert(option.clone());
-        }
+        toggle_option(&mut session.selected_options, &option, menu);
 
         // 更新按钮状态
-        if let Some(msg_id) = *options_message_id {
-            let selected_vec: Vec<String> = selected_options.iter().cloned().collect();
-            let _ = core
-                .update_inline_keyboard(msg_id, menu, &selected_vec)
+        if let Some(msg_id) = session.options_message_id {
+            let _ = session
+                .core
+                .update_inline_keyboard(msg_id, menu, &session.selected_options)
                 .await;
         }
     }
@@ -165,38 +203,67 @@ async fn handle_callback_query_update(
     Ok(())
 }
 
-/// 处理消息更新
+/// 处理消息更新，回复完成时返回回复人
 async fn handle_message_update(
-    core: &TelegramCore,
+    session: &mut ChatSession,
     message: &teloxide::types::Message,
     menu: &[String],
-    options_message_id: &mut Option<i32>,
-    user_input: &mut String,
-    selected_options: &HashSet<String>,
     request: &PopupRequest,
-) -> Result<()> {
+) -> Result<Option<String>> {
     // 识别选项消息ID
-    identify_options_message_id(message, menu, options_message_id);
+    identify_options_message_id(message, menu, &mut session.options_message_id);
+
+    // 群组中无权回复的成员的消息不影响回复内容
+    let user = message.from.as_ref();
+    if !session.access.may_answer(user.map(|u| u.id.0)) {
+        if let Ok(Some(TelegramEvent::SendPressed { .. } | TelegramEvent::ContinuePressed { .. })) =
+            handle_text_message(message, session.core.chat_id, None).await
+        {
+            let _ = session
+                .core
+                .send_message(&format!("⚠️ {} 无权回复此请求", responder_label(user, &session.access)))
+                .await;
+        }
+        return Ok(None);
+    }
+
+    // 保存图片或文件，发送时作为食材附在回复中
+    match session.core.stash_message_attachment(message).await {
+        Ok(Some(ingredient)) => {
+            let name = ingredient.tag.clone().unwrap_or_else(|| ingredient.dish_type.clone());
+            session.spice_ids.push(ingredient.spice_id);
+            let _ = session.core.send_message(&format!("📎 已收到：{}", name)).await;
+        }
+        Ok(None) => {}
+        Err(e) => {
+            log_important!(warn, "保存Telegram附件失败: {}", e);
+            let _ = session.core.send_message(&format!("⚠️ 附件未能添加：{}", e)).await;
+        }
+    }
 
     // 处理文本消息事件
-    if let Ok(Some(event)) = handle_text_message(message, core.chat_id, None).await {
+    if let Ok(Some(event)) = handle_text_message(message, session.core.chat_id, None).await {
         match event {
-            TelegramEvent::SendPressed => {
-                handle_send_pressed(core, selected_options, user_input, request).await?;
-                return Err(ProcessingComplete.into());
+            TelegramEvent::SendPressed { .. } => {
+                let responder = responder_label(user, &session.access);
+                handle_send_pressed(session, request, &responder).await?;
+                return Ok(Some(responder));
             }
-            TelegramEvent::ContinuePressed => {
-                handle_continue_pressed(core, request).await?;
-                return Err(ProcessingComplete.into());
+            TelegramEvent::ContinuePressed { .. } => {
+                // 继续操作不携带附件，释放已保存的食材
+                session.discard_spices();
+                let responder = responder_label(user, &session.access);
+                handle_continue_pressed(&session.core, request, &responder).await?;
+                return Ok(Some(responder));
             }
             TelegramEvent::TextUpdated { text } => {
-                *user_input = text;
+                session.user_input = text;
             }
             _ => {}
         }
     }
 
-    Ok(())
+    Ok(None)
 }
 
 /// 识别选项消息ID
@@ -210,61 +277,50 @@ fn identify_options_message_id(
         return;
     }
 
-    // 检查是否是包含 inline keyboard 的选项消息
-    if let Some(inline_keyboard) = message.reply_markup() {
-        // 检查是否包含我们的选项按钮
-        let mut contains_our_options = false;
-        for row in &inline_keyboard.inline_keyboard {
-            for button in row {
-                if let teloxide::types::InlineKeyboardButtonKind::CallbackData(callback_data) = &button.kind {
-                    if callback_data.starts_with("toggle:") {
-                        contains_our_options = true;
-                        break;
-                    }
-                }
-            }
-            if contains_our_options {
-                break;
-            }
-        }
-
-        if contains_our_options {
-            *options_message_id = Some(message.id.0);
-        }
+    // 检查是否是包含选项按钮的选项消息
+    if has_option_keyboard(message) {
+        *options_message_id = Some(message.id.0);
     }
 }
 
 /// 处理发送按钮按下
-async fn handle_send_pressed(
-    core: &TelegramCore,
-    selected_options: &HashSet<String>,
-    user_input: &str,
-    request: &PopupRequest,
-) -> Result<()> {
-    // 使用统一的响应构建函数
-    let selected_list: Vec<String> = selected_options.iter().cloned().collect();
+async fn handle_send_pressed(session: &ChatSession, request: &PopupRequest, responder: &str) -> Result<()> {
+    let core = &session.core;
+    // 使用统一的响应构建函数（选项按 menu 中的顺序）
+    let selected_list: Vec<String> = session.selected_options.clone();
+
+    // 与 GUI 中粘贴的图片一样转换为回复中的附件
+    let spice_ids: Vec<&str> = session.spice_ids.iter().map(String::as_str).collect();
+    let ingredients = match crate::ui::commands::ingredients_from_spice_ids(&spice_ids) {
+        Ok(ingredients) => ingredients,
+        Err(e) => {
+            log_important!(warn, "处理Telegram附件失败: {}", e);
+            let _ = core.send_message(&format!("⚠️ 附件未能发送：{}", e)).await;
+            vec![]
+        }
+    };
 
-    let user_input_option = if user_input.is_empty() {
+    let user_input_option = if session.user_input.is_empty() {
         None
     } else {
-        Some(user_input.to_string())
+        Some(session.user_input.clone())
     };
 
     let response = build_serve_response(
         user_input_option,
         selected_list.clone(),
-        vec![],
+        ingredients,
         Some(request.id.clone()),
         "telegram",
     );
 
-    // 输出JSON响应到stdout（MCP协议要求）
-    println!("{}", response);
+    // 输出JSON响应到stdout（MCP协议要求），记录回复人
+    println!("{}", with_responder(response, responder));
 
     // 发送确认消息（使用统一的反馈消息生成函数）
     let feedback_message = crate::telegram::core::build_feedback_message(
         &selected_list,
-        user_input,
+        &session.user_input,
         false, // 不是继续操作
     );
     let _ = core.send_message(&feedback_message).await;
@@ -276,12 +332,13 @@ async fn handle_send_pressed(
 async fn handle_continue_pressed(
     core: &TelegramCore,
     request: &PopupRequest,
+    responder: &str,
 ) -> Result<()> {
     // 使用统一的继续响应构建函数
     let response = build_refill_response(Some(request.id.clone()), "telegram_continue");
 
-    // 输出JSON响应到stdout（MCP协议要求）
-    println!("{}", response);
+    // 输出JSON响应到stdout（MCP协议要求），记录回复人
+    println!("{}", with_responder(response, responder));
 
     // 发送确认消息（使用统一的反馈消息生成函数）
     let feedback_message = crate::telegram::core::build_feedback_message(
@@ -293,15 +350,3 @@ async fn handle_continue_pressed(
 
     Ok(())
 }
-
-/// 处理完成标记（用于从监听循环中退出）
-#[derive(Debug)]
-struct ProcessingComplete;
-
-impl std::fmt::Display for ProcessingComplete {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "Processing complete")
-    }
-}
-
-impl std::error::Error for ProcessingComplete {}
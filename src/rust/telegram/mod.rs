@@ -3,12 +3,15 @@ pub mod core;
 pub mod integration;
 pub mod markdown;
 pub mod mcp_handler;
+pub mod recipients;
+pub mod webhook;
 
 pub use commands::*;
 pub use core::{
-    handle_callback_query, handle_text_message, test_telegram_connection, TelegramCore,
-    TelegramEvent,
+    handle_callback_query, handle_text_message, has_option_keyboard, test_telegram_connection,
+    toggle_option, TelegramCore, TelegramEvent,
 };
 pub use integration::TelegramIntegration;
 pub use markdown::process_telegram_markdown;
 pub use mcp_handler::handle_telegram_only_mcp_request;
+pub use webhook::UpdateSource;
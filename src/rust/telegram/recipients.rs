@@ -0,0 +1,131 @@
+// Telegram 多接收者
+// 除主 Chat ID 外，请求还可以同时发到其他聊天（如团队群组），每个聊天可限定允许回复的用户。
+// 谁先按下发送或继续就以谁的回复为准，其余聊天会收到已由谁回复的通知；
+// 回复人写入回复的 ticket.responder，随历史记录保存
+
+use anyhow::{anyhow, Result};
+use teloxide::types::{ChatId, User};
+
+use crate::config::TelegramConfig;
+
+/// 可接收请求的聊天及其回复权限
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatAccess {
+    pub chat_id: ChatId,
+    pub name: String,
+    /// 允许回复的用户ID，为空时聊天中所有人都可回复
+    pub allowed_user_ids: Vec<u64>,
+}
+
+impl ChatAccess {
+    /// 用户是否可以在该聊天中回复（无法识别发送者时只在不限用户的聊天中允许）
+    pub fn may_answer(&self, user_id: Option<u64>) -> bool {
+        self.allowed_user_ids.is_empty() || user_id.is_some_and(|id| self.allowed_user_ids.contains(&id))
+    }
+}
+
+/// 解析数字Chat ID
+pub fn parse_chat_id(chat_id: &str) -> Result<ChatId> {
+    let chat_id = chat_id.trim();
+    if chat_id.starts_with('@') {
+        return Err(anyhow!("暂不支持@username格式，请使用数字Chat ID"));
+    }
+    chat_id
+        .parse::<i64>()
+        .map(ChatId)
+        .map_err(|_| anyhow!("无效的Chat ID格式，请使用数字ID: {}", chat_id))
+}
+
+/// 主聊天与额外接收者（按 Chat ID 去重，主聊天不限回复用户）
+pub fn chat_access_list(config: &TelegramConfig) -> Result<Vec<ChatAccess>> {
+    let mut chats = vec![ChatAccess {
+        chat_id: parse_chat_id(&config.chat_id)?,
+        name: "主聊天".to_string(),
+        allowed_user_ids: Vec::new(),
+    }];
+    for recipient in &config.recipients {
+        let chat_id = parse_chat_id(&recipient.chat_id)?;
+        if chats.iter().any(|c| c.chat_id == chat_id) {
+            continue;
+        }
+        let name = recipient.name.trim();
+        chats.push(ChatAccess {
+            chat_id,
+            name: if name.is_empty() { chat_id.0.to_string() } else { name.to_string() },
+            allowed_user_ids: recipient.allowed_user_ids.clone(),
+        });
+    }
+    Ok(chats)
+}
+
+pub fn find_chat(chats: &[ChatAccess], chat_id: ChatId) -> Option<&ChatAccess> {
+    chats.iter().find(|c| c.chat_id == chat_id)
+}
+
+/// 回复人描述，如 “张三 (@zhangsan, 123456) · 团队群”
+pub fn format_responder(display_name: &str, username: Option<&str>, user_id: Option<u64>, chat_name: &str) -> String {
+    let mut details = Vec::new();
+    if let Some(username) = username {
+        details.push(format!("@{}", username));
+    }
+    if let Some(id) = user_id {
+        details.push(id.to_string());
+    }
+    let display_name = display_name.trim();
+    let name = match (display_name.is_empty(), details.is_empty()) {
+        (true, true) => "未知用户".to_string(),
+        (true, false) => details.join(", "),
+        (false, true) => display_name.to_string(),
+        (false, false) => format!("{} ({})", display_name, details.join(", ")),
+    };
+    format!("{} · {}", name, chat_name)
+}
+
+/// 根据 Telegram 用户生成回复人描述
+pub fn responder_label(user: Option<&User>, chat: &ChatAccess) -> String {
+    match user {
+        Some(user) => format_responder(&user.full_name(), user.username.as_deref(), Some(user.id.0), &chat.name),
+        None => format_responder("", None, None, &chat.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TelegramRecipient;
+
+    #[test]
+    fn test_chat_access_list() {
+        let mut config = crate::config::default_telegram_config();
+        config.chat_id = "100".to_string();
+        config.recipients = vec![
+            TelegramRecipient {
+                chat_id: "-200".to_string(),
+                name: "团队群".to_string(),
+                allowed_user_ids: vec![7],
+            },
+            TelegramRecipient {
+                chat_id: "100".to_string(),
+                name: String::new(),
+                allowed_user_ids: vec![],
+            },
+        ];
+        let chats = chat_access_list(&config).unwrap();
+        assert_eq!(chats.len(), 2);
+        assert!(chats[0].may_answer(None));
+        let group = find_chat(&chats, ChatId(-200)).unwrap();
+        assert!(group.may_answer(Some(7)));
+        assert!(!group.may_answer(Some(8)));
+        assert!(!group.may_answer(None));
+
+        config.recipients[0].chat_id = "@team".to_string();
+        assert!(chat_access_list(&config).is_err());
+    }
+
+    #[test]
+    fn test_format_responder() {
+        assert_eq!(format_responder("张三", Some("zhangsan"), Some(1), "团队群"), "张三 (@zhangsan, 1) · 团队群");
+        assert_eq!(format_responder("", None, Some(1), "主聊天"), "1 · 主聊天");
+        assert_eq!(format_responder("", None, None, "主聊天"), "未知用户 · 主聊天");
+    }
+}
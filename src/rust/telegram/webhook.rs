@@ -0,0 +1,427 @@
+// Telegram 更新接收
+// 默认通过长轮询（getUpdates）获取更新；开启 webhook 后由 axum 服务接收 Telegram 推送的更新，
+// 请求头中的 secret token 与注册时不一致的请求直接拒绝。
+// 每个请求都在独立的进程中等待回复，而监听地址同一时间只能由一个进程占用：
+// 最先绑定成功的进程接收 webhook，并通过本机转发服务（utils::local_relay）把更新转发给其他进程；
+// 其他进程绑定失败时连接该转发服务订阅。接收进程退出后，订阅的进程重新竞争监听地址并重新注册 webhook。
+// 最后一个订阅者结束时删除 webhook，避免 Telegram 继续推送到已退出的服务。
+// 注册 webhook 期间 getUpdates 不可用，因此使用长轮询前会先删除已注册的 webhook
+
+use anyhow::{anyhow, bail, Result};
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::post,
+    Router,
+};
+use once_cell::sync::Lazy;
+use std::net::SocketAddr;
+use teloxide::{
+    prelude::*,
+    types::{AllowedUpdate, Update},
+};
+use tokio::net::TcpListener;
+use tokio::sync::{broadcast, Mutex};
+use tokio::task::JoinHandle;
+
+use crate::config::TelegramConfig;
+use crate::constants::telegram::{WEBHOOK_PATH, WEBHOOK_SECRET_HEADER};
+use crate::log_important;
+use crate::utils::local_relay::{self, RelayInfo};
+
+/// 广播通道容量，监听循环处理不过来时丢弃最旧的更新
+const UPDATE_CHANNEL_CAPACITY: usize = 64;
+
+/// 竞争监听地址或连接转发服务的次数（接收进程可能正在启动或退出）
+const JOIN_ATTEMPTS: u32 = 5;
+
+/// 转发信息文件名
+const RELAY_NAME: &str = "telegram-webhook";
+
+/// 本进程的更新来源：接收 webhook 的进程运行接收服务与转发服务，其他进程运行转发客户端。
+/// 更新以单行 JSON 在通道中传递，收到时再解析
+struct UpdateHub {
+    listen_addr: SocketAddr,
+    url: String,
+    configured_secret: String,
+    /// 是否由本进程接收 webhook
+    receiving: bool,
+    /// 转发令牌，同时用于识别是哪一个来源
+    relay_token: String,
+    sender: broadcast::Sender<String>,
+    task: JoinHandle<()>,
+}
+
+#[derive(Clone)]
+struct WebhookState {
+    secret: String,
+    sender: broadcast::Sender<String>,
+}
+
+static UPDATE_HUB: Lazy<Mutex<Option<UpdateHub>>> = Lazy::new(|| Mutex::new(None));
+
+/// secret token 只能包含字母、数字、下划线和连字符，长度 1-256
+pub fn is_valid_secret_token(token: &str) -> bool {
+    (1..=256).contains(&token.len()) && token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// 生成随机 secret token（未配置时使用，仅在本次接收服务运行期间有效）
+pub fn generate_secret_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// 比较请求携带的 secret token，比较耗时与不一致的位置无关
+pub fn secret_token_matches(provided: Option<&[u8]>, expected: &str) -> bool {
+    let Some(provided) = provided else {
+        return false;
+    };
+    let expected = expected.as_bytes();
+    if expected.is_empty() || provided.len() != expected.len() {
+        return false;
+    }
+    provided.iter().zip(expected).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// 检查 webhook 配置，未开启时不检查
+pub fn validate_webhook_config(config: &TelegramConfig) -> Result<()> {
+    if !config.webhook_enabled {
+        return Ok(());
+    }
+    let url = reqwest::Url::parse(config.webhook_url.trim()).map_err(|e| anyhow!("Webhook 地址无效: {}", e))?;
+    if url.scheme() != "https" {
+        bail!("Webhook 地址必须使用 HTTPS");
+    }
+    parse_listen_addr(&config.webhook_listen_addr)?;
+    if !config.webhook_secret.is_empty() && !is_valid_secret_token(&config.webhook_secret) {
+        bail!("Secret token 只能包含字母、数字、下划线和连字符，长度 1-256");
+    }
+    Ok(())
+}
+
+fn parse_listen_addr(addr: &str) -> Result<SocketAddr> {
+    addr.trim()
+        .parse()
+        .map_err(|_| anyhow!("Webhook 监听地址无效: {}", addr))
+}
+
+async fn receive_update(State(state): State<WebhookState>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    let provided = headers.get(WEBHOOK_SECRET_HEADER).map(|value| value.as_bytes());
+    if !secret_token_matches(provided, &state.secret) {
+        log_important!(warn, "拒绝secret token不匹配的Telegram webhook请求");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    // 无法解析的更新也返回 200，避免 Telegram 反复重试
+    match serde_json::from_slice::<serde_json::Value>(&body) {
+        Ok(update) => {
+            // 没有订阅者时直接丢弃
+            let _ = state.sender.send(update.to_string());
+        }
+        Err(e) => log_important!(warn, "无法解析Telegram webhook更新: {}", e),
+    }
+    StatusCode::OK
+}
+
+/// 接收服务或转发客户端退出时清除本进程的更新来源，订阅者随之收到通道关闭并重新订阅
+async fn clear_hub(relay_token: &str) {
+    let mut hub = UPDATE_HUB.lock().await;
+    if hub.as_ref().is_some_and(|h| h.relay_token == relay_token) {
+        *hub = None;
+    }
+}
+
+/// 占用监听地址：启动接收服务与转发服务，并向 Telegram 注册 webhook
+async fn start_receiver(bot: &Bot, config: &TelegramConfig, listener: TcpListener) -> Result<UpdateHub> {
+    let listen_addr = listener.local_addr()?;
+    let url = config.webhook_url.trim().to_string();
+    let secret = if config.webhook_secret.is_empty() {
+        generate_secret_token()
+    } else {
+        config.webhook_secret.clone()
+    };
+    let relay_listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(|e| anyhow!("无法启动Telegram更新转发服务: {}", e))?;
+    let relay = RelayInfo::for_listener(&relay_listener)?;
+
+    let (sender, _) = broadcast::channel(UPDATE_CHANNEL_CAPACITY);
+    let app = Router::new()
+        .route(WEBHOOK_PATH, post(receive_update))
+        .with_state(WebhookState {
+            secret: secret.clone(),
+            sender: sender.clone(),
+        });
+    let relay_sender = sender.clone();
+    let token = relay.token.clone();
+    let task = tokio::spawn(async move {
+        tokio::select! {
+            result = axum::serve(listener, app) => {
+                if let Err(e) = result {
+                    log_important!(warn, "Telegram webhook服务异常退出: {}", e);
+                }
+            }
+            _ = local_relay::serve(relay_listener, token.clone(), relay_sender) => {
+                log_important!(warn, "Telegram更新转发服务已停止");
+            }
+        }
+        local_relay::remove_info(RELAY_NAME, &token);
+        clear_hub(&token).await;
+    });
+
+    let register = bot
+        .set_webhook(reqwest::Url::parse(&url)?)
+        .secret_token(secret)
+        .allowed_updates(vec![AllowedUpdate::Message, AllowedUpdate::CallbackQuery])
+        .await;
+    if let Err(e) = register {
+        task.abort();
+        bail!("注册Telegram webhook失败: {}", e);
+    }
+    if let Err(e) = local_relay::write_info(RELAY_NAME, &relay) {
+        log_important!(warn, "写入Telegram更新转发信息失败，其他请求将无法共享webhook: {}", e);
+    }
+    log_important!(info, "Telegram webhook已启动: {} -> {}{}", url, listen_addr, WEBHOOK_PATH);
+
+    Ok(UpdateHub {
+        listen_addr,
+        url,
+        configured_secret: config.webhook_secret.clone(),
+        receiving: true,
+        relay_token: relay.token,
+        sender,
+        task,
+    })
+}
+
+/// 监听地址已被其他进程占用：连接该进程的转发服务
+async fn join_relay(config: &TelegramConfig, listen_addr: SocketAddr) -> Result<UpdateHub> {
+    let info = local_relay::read_info(RELAY_NAME).map_err(|e| anyhow!("读取Telegram更新转发信息失败: {}", e))?;
+    let lines = local_relay::connect(&info)
+        .await
+        .map_err(|e| anyhow!("连接Telegram更新转发服务失败: {}", e))?;
+
+    let (sender, _) = broadcast::channel(UPDATE_CHANNEL_CAPACITY);
+    let relay_sender = sender.clone();
+    let token = info.token.clone();
+    let task = tokio::spawn(async move {
+        local_relay::forward(lines, relay_sender).await;
+        log_important!(info, "Telegram更新转发连接已断开");
+        clear_hub(&token).await;
+    });
+    log_important!(info, "Telegram webhook由其他进程接收，已订阅其转发的更新");
+
+    Ok(UpdateHub {
+        listen_addr,
+        url: config.webhook_url.trim().to_string(),
+        configured_secret: config.webhook_secret.clone(),
+        receiving: false,
+        relay_token: info.token,
+        sender,
+        task,
+    })
+}
+
+/// 订阅 webhook 更新；本进程尚无更新来源或配置已变化时，占用监听地址接收 webhook，已被占用则订阅其转发
+async fn subscribe(bot: &Bot, config: &TelegramConfig) -> Result<broadcast::Receiver<String>> {
+    validate_webhook_config(config)?;
+    let listen_addr = parse_listen_addr(&config.webhook_listen_addr)?;
+    let url = config.webhook_url.trim().to_string();
+
+    let mut hub = UPDATE_HUB.lock().await;
+    if let Some(running) = hub.as_ref() {
+        let unchanged = running.listen_addr == listen_addr
+            && running.url == url
+            && running.configured_secret == config.webhook_secret
+            && !running.task.is_finished();
+        if unchanged {
+            return Ok(running.sender.subscribe());
+        }
+    }
+    // 停止旧的来源，旧的订阅者会收到通道关闭并重新订阅
+    if let Some(old) = hub.take() {
+        old.task.abort();
+        if old.receiving {
+            local_relay::remove_info(RELAY_NAME, &old.relay_token);
+        }
+    }
+
+    let mut last_error = anyhow!("无法订阅Telegram webhook更新");
+    for attempt in 0..JOIN_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        }
+        let started = match TcpListener::bind(listen_addr).await {
+            Ok(listener) => start_receiver(bot, config, listener).await,
+            Err(bind_error) => join_relay(config, listen_addr)
+                .await
+                .map_err(|e| anyhow!("无法监听 {} ({})，{}", listen_addr, bind_error, e)),
+        };
+        match started {
+            Ok(started) => {
+                let receiver = started.sender.subscribe();
+                *hub = Some(started);
+                return Ok(receiver);
+            }
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+/// 本进程不再有订阅者（包括转发到的其他进程）时停止更新来源；
+/// 接收 webhook 的进程同时删除 webhook，其他进程仍在等待时由它们重新注册
+async fn release(bot: &Bot) {
+    let mut hub = UPDATE_HUB.lock().await;
+    if hub.as_ref().is_none_or(|h| h.sender.receiver_count() > 0) {
+        return;
+    }
+    let Some(stopped) = hub.take() else {
+        return;
+    };
+    stopped.task.abort();
+    if stopped.receiving {
+        local_relay::remove_info(RELAY_NAME, &stopped.relay_token);
+        if let Err(e) = bot.delete_webhook().await {
+            log_important!(warn, "删除Telegram webhook失败: {}", e);
+        }
+    }
+}
+
+/// 监听循环的更新来源
+pub enum UpdateSource {
+    /// 长轮询，offset 为下一次获取的更新 ID
+    Polling { bot: Bot, offset: i32 },
+    /// webhook 推送
+    Webhook {
+        bot: Bot,
+        config: TelegramConfig,
+        receiver: broadcast::Receiver<String>,
+    },
+}
+
+impl UpdateSource {
+    /// 按配置选择长轮询或 webhook
+    pub async fn start(bot: &Bot, config: &TelegramConfig) -> Result<Self> {
+        if config.webhook_enabled {
+            let receiver = subscribe(bot, config).await?;
+            return Ok(Self::Webhook {
+                bot: bot.clone(),
+                config: config.clone(),
+                receiver,
+            });
+        }
+
+        // 残留的 webhook 会让 getUpdates 被拒绝
+        if let Err(e) = bot.delete_webhook().await {
+            log_important!(warn, "删除Telegram webhook失败: {}", e);
+        }
+
+        // 获取当前最新的消息ID作为基准
+        let mut offset = 0i32;
+        if let Ok(updates) = bot.get_updates().limit(10).await {
+            if let Some(update) = updates.last() {
+                offset = update.id.0 as i32 + 1;
+            }
+        }
+        Ok(Self::Polling {
+            bot: bot.clone(),
+            offset,
+        })
+    }
+
+    /// 监听结束（已有人回复）后调用，最后一个订阅者结束时停止接收服务并删除 webhook
+    pub async fn finish(self) {
+        if let Self::Webhook { bot, receiver, .. } = self {
+            drop(receiver);
+            release(&bot).await;
+        }
+    }
+
+    /// 等待下一批更新，出错时返回空列表
+    pub async fn next_updates(&mut self) -> Vec<Update> {
+        match self {
+            Self::Polling { bot, offset } => {
+                let updates = match bot.get_updates().offset(*offset).timeout(10).await {
+                    Ok(updates) => updates,
+                    Err(_) => {
+                        tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                        return vec![];
+                    }
+                };
+                if let Some(update) = updates.last() {
+                    *offset = update.id.0 as i32 + 1;
+                }
+                // 短暂延迟避免过于频繁的请求
+                tokio::time::sleep(tokio::time::Duration::from_millis(1000)).await;
+                updates
+            }
+            Self::Webhook { bot, config, receiver } => match receiver.recv().await {
+                Ok(update) => {
+                    let mut raw = vec![update];
+                    while let Ok(update) = receiver.try_recv() {
+                        raw.push(update);
+                    }
+                    raw.iter()
+                        .filter_map(|update| match serde_json::from_str::<Update>(update) {
+                            Ok(update) => Some(update),
+                            Err(e) => {
+                                log_important!(warn, "无法解析Telegram webhook更新: {}", e);
+                                None
+                            }
+                        })
+                        .collect()
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log_important!(warn, "Telegram更新处理不及时，已跳过 {} 条", skipped);
+                    vec![]
+                }
+                Err(broadcast::error::RecvError::Closed) => {
+                    match subscribe(bot, config).await {
+                        Ok(new_receiver) => *receiver = new_receiver,
+                        Err(e) => {
+                            log_important!(warn, "重新订阅Telegram webhook失败: {}", e);
+                            tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+                        }
+                    }
+                    vec![]
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_token() {
+        let token = generate_secret_token();
+        assert!(is_valid_secret_token(&token));
+        assert!(!is_valid_secret_token(""));
+        assert!(!is_valid_secret_token("has space"));
+
+        assert!(secret_token_matches(Some(token.as_bytes()), &token));
+        assert!(!secret_token_matches(Some(b"wrong"), &token));
+        assert!(!secret_token_matches(None, &token));
+        assert!(!secret_token_matches(Some(b""), ""));
+    }
+
+    #[test]
+    fn test_validate_webhook_config() {
+        let mut config = crate::config::default_telegram_config();
+        assert!(validate_webhook_config(&config).is_ok());
+
+        config.webhook_enabled = true;
+        config.webhook_url = "http://example.com/telegram/webhook".to_string();
+        assert!(validate_webhook_config(&config).is_err());
+        config.webhook_url = "https://example.com/telegram/webhook".to_string();
+        assert!(validate_webhook_config(&config).is_ok());
+        config.webhook_listen_addr = "localhost".to_string();
+        assert!(validate_webhook_config(&config).is_err());
+        config.webhook_listen_addr = "127.0.0.1:8443".to_string();
+        config.webhook_secret = "bad secret".to_string();
+        assert!(validate_webhook_config(&config).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+// 开机自启与后台模式
+// 开机自启由 tauri-plugin-autostart 注册，自启时带 --background 参数，窗口隐藏在托盘中；
+// 开启后台模式后手动启动也隐藏到托盘，关闭窗口时只隐藏不退出，需要时从托盘或全局快捷键唤起。
+// 托盘只在主程序中创建，MCP 弹窗进程不受影响
+
+use tauri::menu::{Menu, MenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, State};
+use tauri_plugin_autostart::ManagerExt;
+
+use crate::config::{save_config, AppState};
+
+/// 开机自启时附带的命令行参数
+pub const BACKGROUND_ARG: &str = "--background";
+
+const TRAY_MENU_SHOW: &str = "tray_show";
+const TRAY_MENU_QUIT: &str = "tray_quit";
+
+/// 是否为 MCP 弹窗进程
+pub fn is_popup_mode() -> bool {
+    std::env::args().any(|arg| arg == "--mcp-request")
+}
+
+/// 是否以后台方式启动（开机自启）
+pub fn is_background_launch() -> bool {
+    std::env::args().any(|arg| arg == BACKGROUND_ARG)
+}
+
+/// 后台模式是否生效（弹窗进程始终按普通窗口处理）
+pub fn background_mode_active(app: &AppHandle) -> bool {
+    if is_popup_mode() {
+        return false;
+    }
+    app.state::<AppState>()
+        .config
+        .lock()
+        .map(|config| config.ui_config.background_mode)
+        .unwrap_or(false)
+}
+
+/// 显示主窗口并切换到前台
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        if let Err(e) = window.set_focus() {
+            log::warn!("唤起窗口失败: {}", e);
+        }
+    }
+}
+
+/// 创建托盘图标并按启动方式决定是否隐藏主窗口（主程序启动时调用一次）
+pub fn setup_background_mode(app: &AppHandle) -> tauri::Result<()> {
+    if is_popup_mode() {
+        return Ok(());
+    }
+
+    let show = MenuItem::with_id(app, TRAY_MENU_SHOW, "显示窗口", true, None::<&str>)?;
+    let quit = MenuItem::with_id(app, TRAY_MENU_QUIT, "退出", true, None::<&str>)?;
+    let menu = Menu::with_items(app, &[&show, &quit])?;
+
+    let mut tray = TrayIconBuilder::with_id("main")
+        .tooltip("DevKit")
+        .menu(&menu)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| match event.id().as_ref() {
+            TRAY_MENU_SHOW => show_main_window(app),
+            TRAY_MENU_QUIT => {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = crate::ui::exit::force_exit_app(app).await {
+                        log::error!("从托盘退出失败: {}", e);
+                    }
+                });
+            }
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                show_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+
+    if is_background_launch() || background_mode_active(app) {
+        if let Some(window) = app.get_webview_window("main") {
+            window.hide()?;
+            log::info!("以后台模式启动，窗口已隐藏到托盘");
+        }
+    }
+    Ok(())
+}
+
+/// 获取开机自启状态
+#[tauri::command]
+pub async fn get_launch_at_login(app: AppHandle) -> Result<bool, String> {
+    app.autolaunch()
+        .is_enabled()
+        .map_err(|e| format!("获取开机自启状态失败: {}", e))
+}
+
+/// 设置开机自启
+#[tauri::command]
+pub async fn set_launch_at_login(enabled: bool, app: AppHandle) -> Result<(), String> {
+    let autolaunch = app.autolaunch();
+    let result = if enabled { autolaunch.enable() } else { autolaunch.disable() };
+    result.map_err(|e| format!("设置开机自启失败: {}", e))?;
+    log::info!("开机自启已{}", if enabled { "启用" } else { "关闭" });
+    Ok(())
+}
+
+/// 获取后台模式设置
+#[tauri::command]
+pub async fn get_background_mode(state: State<'_, AppState>) -> Result<bool, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.ui_config.background_mode)
+}
+
+/// 设置后台模式
+#[tauri::command]
+pub async fn set_background_mode(
+    enabled: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        config.ui_config.background_mode = enabled;
+    }
+
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    Ok(())
+}
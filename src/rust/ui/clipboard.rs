@@ -0,0 +1,848 @@
+// 剪贴板读取
+// 不同平台、不同桌面环境读取剪贴板的方式差异很大：arboard 覆盖 Windows / macOS / X11，
+// Wayland 下往往只能借助 wl-paste（或 snap 的 wl-clip.paste），部分 X11 环境则需要 xclip。
+// 每种方式实现为一个 ClipboardProvider，按配置 clipboard_backend 选择后端：
+// "auto"（默认，按平台依次尝试）| "arboard" | "wayland" | "x11"
+// 每个后端先尝试读取图片，再读取复制的文件列表或文本中的文件路径。
+// 写入（复制回复内容、图片）同样按后端依次尝试；Linux 下优先使用 wl-copy / xclip，它们会在后台持有剪贴板内容
+
+use arboard::Clipboard;
+use image::codecs::png::PngEncoder;
+use image::{ColorType, ImageEncoder};
+use percent_encoding::percent_decode_str;
+use std::fs;
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+#[cfg(target_os = "linux")]
+use std::sync::Mutex;
+
+#[cfg(target_os = "linux")]
+use once_cell::sync::Lazy;
+
+use crate::config::McpConfig;
+use crate::mcp::ingredient_kind;
+
+/// 命令行工具依次尝试读取的图片类型
+const IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/webp", "image/bmp"];
+
+/// Linux 下 arboard 的剪贴板内容由进程内线程提供，Clipboard 释放后内容随之丢失，写入后在这里保留
+#[cfg(target_os = "linux")]
+static RETAINED_CLIPBOARD: Lazy<Mutex<Option<Clipboard>>> = Lazy::new(|| Mutex::new(None));
+
+/// 从剪贴板读到的食材
+#[derive(Debug, Clone)]
+pub struct ClipboardIngredient {
+    pub dish_type: String,
+    pub tag: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+/// 写入剪贴板的内容
+#[derive(Debug, Clone, Copy)]
+pub enum ClipboardContent<'a> {
+    Text(&'a str),
+    /// PNG 编码的图片
+    Png(&'a [u8]),
+}
+
+/// 剪贴板后端
+pub trait ClipboardProvider {
+    fn name(&self) -> &'static str;
+
+    /// 后端在当前环境是否可用（如命令行工具是否已安装）
+    fn is_available(&mut self) -> bool {
+        true
+    }
+
+    /// 后端不可用时给用户的安装提示
+    fn install_hint(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// 读取剪贴板中的图片；没有图片时返回 Ok(None)
+    fn read_image(&mut self) -> Result<Option<ClipboardIngredient>, String>;
+
+    /// 读取剪贴板中的文本，可能有多个候选（如 text/uri-list 与纯文本）
+    fn read_texts(&mut self) -> Vec<String>;
+
+    /// 读取系统复制的文件列表（如资源管理器 / Finder 中复制的文件）
+    fn read_file_paths(&mut self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// 写入剪贴板
+    fn write(&mut self, content: ClipboardContent) -> Result<(), String>;
+}
+
+/// 配置中选择的剪贴板后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClipboardBackend {
+    Auto,
+    Arboard,
+    Wayland,
+    X11,
+}
+
+impl ClipboardBackend {
+    /// 解析配置值，无法识别时按 auto 处理
+    pub fn parse(value: &str) -> Self {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "arboard" => ClipboardBackend::Arboard,
+            "wayland" => ClipboardBackend::Wayland,
+            "x11" | "xclip" => ClipboardBackend::X11,
+            _ => ClipboardBackend::Auto,
+        }
+    }
+
+    pub fn from_mcp_config(config: &McpConfig) -> Self {
+        config
+            .clipboard_backend
+            .as_deref()
+            .map(Self::parse)
+            .unwrap_or(ClipboardBackend::Auto)
+    }
+
+    pub fn load() -> Self {
+        crate::config::load_standalone_config()
+            .map(|c| Self::from_mcp_config(&c.mcp_config))
+            .unwrap_or(ClipboardBackend::Auto)
+    }
+
+    /// 按尝试顺序排列的后端
+    pub fn providers(self) -> Vec<Box<dyn ClipboardProvider>> {
+        match self {
+            ClipboardBackend::Arboard => vec![Box::new(ArboardProvider::default())],
+            ClipboardBackend::Wayland => vec![Box::new(WaylandCliProvider::default())],
+            ClipboardBackend::X11 => vec![Box::new(X11CliProvider::default())],
+            ClipboardBackend::Auto if cfg!(target_os = "linux") => vec![
+                Box::new(ArboardProvider::default()),
+                Box::new(WaylandCliProvider::default()),
+                Box::new(X11CliProvider::default()),
+            ],
+            ClipboardBackend::Auto => vec![Box::new(ArboardProvider::default())],
+        }
+    }
+
+    /// 写入时的尝试顺序；Linux 下命令行工具优先，arboard 的内容只在本进程存活期间有效
+    pub fn writers(self) -> Vec<Box<dyn ClipboardProvider>> {
+        match self {
+            ClipboardBackend::Auto if cfg!(target_os = "linux") => vec![
+                Box::new(WaylandCliProvider::default()),
+                Box::new(X11CliProvider::default()),
+                Box::new(ArboardProvider::default()),
+            ],
+            backend => backend.providers(),
+        }
+    }
+}
+
+/// 使用配置中的后端读取剪贴板食材
+pub fn read_clipboard_ingredients() -> Result<Vec<ClipboardIngredient>, String> {
+    read_ingredients(&mut ClipboardBackend::load().providers())
+}
+
+/// 依次尝试各个后端，返回第一个读到的食材
+pub fn read_ingredients(providers: &mut [Box<dyn ClipboardProvider>]) -> Result<Vec<ClipboardIngredient>, String> {
+    let mut first_error: Option<String> = None;
+    let mut missing_hints: Vec<&'static str> = Vec::new();
+    let mut hinted = 0;
+
+    for provider in providers.iter_mut() {
+        if provider.install_hint().is_some() {
+            hinted += 1;
+        }
+        if !provider.is_available() {
+            log::debug!("剪贴板后端 {} 不可用", provider.name());
+            missing_hints.extend(provider.install_hint());
+            continue;
+        }
+
+        match provider.read_image() {
+            Ok(Some(item)) => return Ok(vec![item]),
+            Ok(None) => {}
+            Err(e) => {
+                log::debug!("剪贴板后端 {} 读取图片失败: {}", provider.name(), e);
+                first_error.get_or_insert(e);
+            }
+        }
+
+        let items = load_ingredient_files(&provider.read_file_paths());
+        if !items.is_empty() {
+            return Ok(items);
+        }
+        for text in provider.read_texts() {
+            let items = load_ingredient_files(&extract_file_paths_from_clipboard_text(&text));
+            if !items.is_empty() {
+                return Ok(items);
+            }
+        }
+    }
+
+    if let Some(e) = first_error {
+        return Err(e);
+    }
+    if hinted > 0 && missing_hints.len() == hinted {
+        return Err(format!("剪贴板里没有食材（{}）", missing_hints.join("；")));
+    }
+    Err("剪贴板里没有食材".to_string())
+}
+
+/// 使用配置中的后端读取剪贴板文本，读不到时返回 None
+pub fn read_clipboard_text() -> Option<String> {
+    for mut provider in ClipboardBackend::load().providers() {
+        if !provider.is_available() {
+            continue;
+        }
+        if let Some(text) = provider.read_texts().into_iter().find(|text| !text.trim().is_empty()) {
+            return Some(text);
+        }
+    }
+    None
+}
+
+/// 使用配置中的后端写入文本
+pub fn write_clipboard_text(text: &str) -> Result<(), String> {
+    write_content(&mut ClipboardBackend::load().writers(), ClipboardContent::Text(text))
+}
+
+/// 使用配置中的后端写入 PNG 图片
+pub fn write_clipboard_png(png: &[u8]) -> Result<(), String> {
+    write_content(&mut ClipboardBackend::load().writers(), ClipboardContent::Png(png))
+}
+
+/// 依次尝试各个后端，直到写入成功
+pub fn write_content(providers: &mut [Box<dyn ClipboardProvider>], content: ClipboardContent) -> Result<(), String> {
+    let mut first_error: Option<String> = None;
+    let mut missing_hints: Vec<&'static str> = Vec::new();
+
+    for provider in providers.iter_mut() {
+        if !provider.is_available() {
+            missing_hints.extend(provider.install_hint());
+            continue;
+        }
+        match provider.write(content) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                log::debug!("剪贴板后端 {} 写入失败: {}", provider.name(), e);
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    match first_error {
+        Some(e) => Err(e),
+        None if !missing_hints.is_empty() => Err(format!("没有可用的剪贴板后端（{}）", missing_hints.join("；"))),
+        None => Err("没有可用的剪贴板后端".to_string()),
+    }
+}
+
+fn load_ingredient_files(paths: &[PathBuf]) -> Vec<ClipboardIngredient> {
+    paths.iter().filter_map(|p| try_load_ingredient_file(p)).collect()
+}
+
+fn try_load_ingredient_file(path: &Path) -> Option<ClipboardIngredient> {
+    let ext = path.extension()?.to_str()?;
+    let mime = ingredient_kind::dish_type_from_extension(ext)?;
+    let bytes = fs::read(path).ok()?;
+    if bytes.is_empty() {
+        return None;
+    }
+    Some(ClipboardIngredient {
+        dish_type: mime.to_string(),
+        tag: path.file_name().map(|n| n.to_string_lossy().to_string()),
+        bytes,
+    })
+}
+
+fn extract_file_paths_from_clipboard_text(text: &str) -> Vec<PathBuf> {
+    let mut lines = text
+        .lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>();
+
+    if matches!(lines.first().copied(), Some("copy") | Some("cut")) {
+        lines.remove(0);
+    }
+
+    let mut out = Vec::new();
+    for line in lines {
+        if line.starts_with('#') {
+            continue;
+        }
+
+        if let Some(p) = parse_file_url_or_path(line) {
+            out.push(p);
+        }
+    }
+    out
+}
+
+fn parse_file_url_or_path(s: &str) -> Option<PathBuf> {
+    let raw = s.trim();
+
+    if let Some(rest) = raw.strip_prefix("file://") {
+        let rest = rest.strip_prefix("localhost").unwrap_or(rest);
+        let mut path_str = rest.to_string();
+        if !path_str.starts_with('/') {
+            path_str.insert(0, '/');
+        }
+        let decoded = percent_decode_str(&path_str).decode_utf8_lossy();
+        let decoded = decoded.as_ref();
+        let decoded = if decoded.len() >= 4
+            && decoded.starts_with('/')
+            && decoded.as_bytes()[2] == b':'
+            && (decoded.as_bytes()[1] as char).is_ascii_alphabetic()
+            && (decoded.as_bytes()[3] == b'/' || decoded.as_bytes()[3] == b'\\')
+        {
+            &decoded[1..]
+        } else {
+            decoded
+        };
+        let p = PathBuf::from(decoded);
+        if p.exists() { return Some(p); }
+        return None;
+    }
+
+    let p = PathBuf::from(raw);
+    if p.exists() {
+        return Some(p);
+    }
+    None
+}
+
+/// 系统剪贴板（arboard）；Windows / macOS 下同时读取复制的文件列表
+#[derive(Default)]
+pub struct ArboardProvider {
+    clipboard: Option<Clipboard>,
+}
+
+impl ArboardProvider {
+    fn clipboard(&mut self) -> Result<&mut Clipboard, String> {
+        if self.clipboard.is_none() {
+            let clipboard = Clipboard::new().map_err(|e| format!("打开系统剪贴板失败: {}", e))?;
+            self.clipboard = Some(clipboard);
+        }
+        self.clipboard.as_mut().ok_or_else(|| "打开系统剪贴板失败".to_string())
+    }
+}
+
+impl ClipboardProvider for ArboardProvider {
+    fn name(&self) -> &'static str {
+        "arboard"
+    }
+
+    fn read_image(&mut self) -> Result<Option<ClipboardIngredient>, String> {
+        let img = match self.clipboard()?.get_image() {
+            Ok(img) => img,
+            Err(arboard::Error::ContentNotAvailable) => return Ok(None),
+            Err(e) => return Err(format!("读取剪贴板食材失败: {}", e)),
+        };
+
+        let width = u32::try_from(img.width).map_err(|_| "食材宽度过大，无法处理".to_string())?;
+        let height = u32::try_from(img.height).map_err(|_| "食材高度过大，无法处理".to_string())?;
+
+        let expected_len = img
+            .width
+            .checked_mul(img.height)
+            .and_then(|v| v.checked_mul(4))
+            .ok_or_else(|| "食材尺寸异常，无法处理".to_string())?;
+
+        if img.bytes.len() < expected_len {
+            return Err("剪贴板食材长度不正确".to_string());
+        }
+
+        let mut png_bytes: Vec<u8> = Vec::new();
+        PngEncoder::new(&mut png_bytes)
+            .write_image(&img.bytes[..expected_len], width, height, ColorType::Rgba8.into())
+            .map_err(|e| format!("编码 PNG 失败: {}", e))?;
+
+        Ok(Some(ClipboardIngredient {
+            dish_type: "image/png".to_string(),
+            tag: None,
+            bytes: png_bytes,
+        }))
+    }
+
+    fn read_texts(&mut self) -> Vec<String> {
+        self.clipboard()
+            .ok()
+            .and_then(|c| c.get_text().ok())
+            .filter(|t| !t.trim().is_empty())
+            .into_iter()
+            .collect()
+    }
+
+    fn read_file_paths(&mut self) -> Vec<PathBuf> {
+        read_native_file_list()
+    }
+
+    fn write(&mut self, content: ClipboardContent) -> Result<(), String> {
+        let clipboard = self.clipboard()?;
+        match content {
+            ClipboardContent::Text(text) => clipboard
+                .set_text(text)
+                .map_err(|e| format!("写入剪贴板失败: {}", e))?,
+            ClipboardContent::Png(png) => {
+                let rgba = image::load_from_memory_with_format(png, image::ImageFormat::Png)
+                    .map_err(|e| format!("读取图片失败: {}", e))?
+                    .to_rgba8();
+                let (width, height) = rgba.dimensions();
+                clipboard
+                    .set_image(arboard::ImageData {
+                        width: width as usize,
+                        height: height as usize,
+                        bytes: rgba.into_raw().into(),
+                    })
+                    .map_err(|e| format!("写入剪贴板失败: {}", e))?
+            }
+        }
+
+        #[cfg(target_os = "linux")]
+        if let Some(clipboard) = self.clipboard.take() {
+            *RETAINED_CLIPBOARD.lock().unwrap_or_else(|e| e.into_inner()) = Some(clipboard);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn read_native_file_list() -> Vec<PathBuf> {
+    let script = "[Console]::OutputEncoding=[System.Text.Encoding]::UTF8; Get-Clipboard -Format FileDropList -ErrorAction SilentlyContinue | ForEach-Object { $_.FullName }";
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-Sta", "-Command", script])
+        .output();
+    lines_of_successful_output(output.ok())
+}
+
+#[cfg(target_os = "macos")]
+fn read_native_file_list() -> Vec<PathBuf> {
+    let output = Command::new("osascript")
+        .args([
+            "-e",
+            "set out to \"\"",
+            "-e",
+            "try",
+            "-e",
+            "set theFiles to the clipboard as alias list",
+            "-e",
+            "on error",
+            "-e",
+            "set theFiles to {the clipboard as alias}",
+            "-e",
+            "end try",
+            "-e",
+            "repeat with f in theFiles",
+            "-e",
+            "set out to out & (POSIX path of f) & linefeed",
+            "-e",
+            "end repeat",
+            "-e",
+            "return out",
+        ])
+        .output();
+    lines_of_successful_output(output.ok())
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn read_native_file_list() -> Vec<PathBuf> {
+    Vec::new()
+}
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn lines_of_successful_output(output: Option<Output>) -> Vec<PathBuf> {
+    output
+        .filter(|o| o.status.success())
+        .and_then(|o| String::from_utf8(o.stdout).ok())
+        .map(|text| {
+            text.lines()
+                .map(|l| l.trim())
+                .filter(|l| !l.is_empty())
+                .map(PathBuf::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 命令行工具的候选路径；除 PATH 外也在 snap 与常见 bin 目录中查找（GUI 进程的 PATH 可能不完整）
+fn command_candidates(cmd: &str) -> [String; 5] {
+    [
+        cmd.to_string(),
+        format!("/snap/bin/{cmd}"),
+        format!("/usr/local/bin/{cmd}"),
+        format!("/usr/bin/{cmd}"),
+        format!("/bin/{cmd}"),
+    ]
+}
+
+fn run_command_output(cmd: &str, args: &[String]) -> Option<Output> {
+    for program in command_candidates(cmd) {
+        match Command::new(&program).args(args).output() {
+            Ok(o) => return Some(o),
+            Err(e) if e.kind() == ErrorKind::NotFound => continue,
+            Err(_) => continue,
+        }
+    }
+    None
+}
+
+/// 运行命令行工具并通过标准输入写入内容；找不到命令时返回 None。
+/// wl-copy / xclip 会派生后台进程持有剪贴板，标准输出必须丢弃，否则等待会一直阻塞
+fn run_command_with_input(cmd: &str, args: &[String], input: &[u8]) -> Option<Result<(), String>> {
+    for program in command_candidates(cmd) {
+        let mut child = match Command::new(&program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(_) => continue,
+        };
+        let written = child
+            .stdin
+            .take()
+            .map(|mut stdin| stdin.write_all(input))
+            .unwrap_or(Ok(()));
+        let status = child.wait();
+        return Some(match (written, status) {
+            (Ok(()), Ok(status)) if status.success() => Ok(()),
+            (Err(e), _) => Err(format!("{} 写入失败: {}", cmd, e)),
+            (_, Ok(status)) => Err(format!("{} 写入失败: {}", cmd, status)),
+            (_, Err(e)) => Err(format!("{} 写入失败: {}", cmd, e)),
+        });
+    }
+    None
+}
+
+/// 命令行工具的读取参数：(是否读取 primary 选区, MIME 类型)
+type CliArgs = fn(bool, Option<&str>) -> Vec<String>;
+
+/// 命令行工具的写入参数：MIME 类型
+type CliCopyArgs = fn(&str) -> Vec<String>;
+
+/// 基于命令行工具的后端共用逻辑
+struct CliTool {
+    programs: &'static [&'static str],
+    text_types: &'static [Option<&'static str>],
+    args: CliArgs,
+    copy_programs: &'static [&'static str],
+    copy_args: CliCopyArgs,
+    available: Option<bool>,
+}
+
+impl CliTool {
+    fn is_available(&mut self) -> bool {
+        let programs = self.programs;
+        *self.available.get_or_insert_with(|| {
+            programs
+                .iter()
+                .any(|cmd| run_command_output(cmd, &["--version".to_string()]).is_some())
+        })
+    }
+
+    fn read_image(&self) -> Option<ClipboardIngredient> {
+        for cmd in self.programs {
+            for primary in [false, true] {
+                for mime in IMAGE_TYPES {
+                    let output = match run_command_output(cmd, &(self.args)(primary, Some(mime))) {
+                        Some(o) => o,
+                        None => continue,
+                    };
+                    if output.status.success() && !output.stdout.is_empty() {
+                        return Some(ClipboardIngredient {
+                            dish_type: mime.to_string(),
+                            tag: None,
+                            bytes: output.stdout,
+                        });
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    fn read_texts(&self) -> Vec<String> {
+        let mut out: Vec<String> = Vec::new();
+        for cmd in self.programs {
+            for primary in [false, true] {
+                for mime in self.text_types {
+                    let output = match run_command_output(cmd, &(self.args)(primary, *mime)) {
+                        Some(o) => o,
+                        None => continue,
+                    };
+                    if !output.status.success() {
+                        continue;
+                    }
+                    let s = match String::from_utf8(output.stdout) {
+                        Ok(s) => s.trim().to_string(),
+                        Err(_) => continue,
+                    };
+                    if !s.is_empty() && !out.contains(&s) {
+                        out.push(s);
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    fn write(&self, content: ClipboardContent) -> Result<(), String> {
+        let (mime, input) = match content {
+            ClipboardContent::Text(text) => ("text/plain;charset=utf-8", text.as_bytes()),
+            ClipboardContent::Png(png) => ("image/png", png),
+        };
+        let mut last_error = None;
+        for cmd in self.copy_programs {
+            match run_command_with_input(cmd, &(self.copy_args)(mime), input) {
+                Some(Ok(())) => return Ok(()),
+                Some(Err(e)) => last_error = Some(e),
+                None => continue,
+            }
+        }
+        Err(last_error.unwrap_or_else(|| format!("未找到 {}", self.copy_programs.join(" / "))))
+    }
+}
+
+/// Wayland：wl-paste（wl-clipboard）或 snap 的 wl-clip.paste
+pub struct WaylandCliProvider(CliTool);
+
+impl Default for WaylandCliProvider {
+    fn default() -> Self {
+        Self(CliTool {
+            programs: &["wl-paste", "wl-clip.paste"],
+            text_types: &[Some("text/uri-list"), Some("text/plain;charset=utf-8"), None],
+            args: wayland_args,
+            copy_programs: &["wl-copy", "wl-clip.copy"],
+            copy_args: wayland_copy_args,
+            available: None,
+        })
+    }
+}
+
+fn wayland_args(primary: bool, mime: Option<&str>) -> Vec<String> {
+    let mut args = Vec::new();
+    if primary {
+        args.push("--primary");
+    }
+    args.push("--no-newline");
+    if let Some(mime) = mime {
+        args.extend(["--type", mime]);
+    }
+    args.into_iter().map(String::from).collect()
+}
+
+fn wayland_copy_args(mime: &str) -> Vec<String> {
+    vec!["--type".to_string(), mime.to_string()]
+}
+
+impl ClipboardProvider for WaylandCliProvider {
+    fn name(&self) -> &'static str {
+        "wayland"
+    }
+
+    fn is_available(&mut self) -> bool {
+        self.0.is_available()
+    }
+
+    fn install_hint(&self) -> Option<&'static str> {
+        Some("未检测到 wl-paste / wl-clip.paste：Wayland 建议安装 wl-clipboard 或 snap 的 wl-clip")
+    }
+
+    fn read_image(&mut self) -> Result<Option<ClipboardIngredient>, String> {
+        Ok(self.0.read_image())
+    }
+
+    fn read_texts(&mut self) -> Vec<String> {
+        self.0.read_texts()
+    }
+
+    fn write(&mut self, content: ClipboardContent) -> Result<(), String> {
+        self.0.write(content)
+    }
+}
+
+/// X11：xclip
+pub struct X11CliProvider(CliTool);
+
+impl Default for X11CliProvider {
+    fn default() -> Self {
+        Self(CliTool {
+            programs: &["xclip"],
+            text_types: &[Some("text/uri-list"), None],
+            args: x11_args,
+            copy_programs: &["xclip"],
+            copy_args: x11_copy_args,
+            available: None,
+        })
+    }
+}
+
+fn x11_args(primary: bool, mime: Option<&str>) -> Vec<String> {
+    let mut args = vec!["-selection", if primary { "primary" } else { "clipboard" }];
+    if let Some(mime) = mime {
+        args.extend(["-t", mime]);
+    }
+    args.push("-o");
+    args.into_iter().map(String::from).collect()
+}
+
+fn x11_copy_args(mime: &str) -> Vec<String> {
+    ["-selection", "clipboard", "-t", mime, "-i"].into_iter().map(String::from).collect()
+}
+
+impl ClipboardProvider for X11CliProvider {
+    fn name(&self) -> &'static str {
+        "x11"
+    }
+
+    fn is_available(&mut self) -> bool {
+        self.0.is_available()
+    }
+
+    fn install_hint(&self) -> Option<&'static str> {
+        Some("未检测到 xclip：X11 下可安装 xclip")
+    }
+
+    fn read_image(&mut self) -> Result<Option<ClipboardIngredient>, String> {
+        Ok(self.0.read_image())
+    }
+
+    fn read_texts(&mut self) -> Vec<String> {
+        self.0.read_texts()
+    }
+
+    fn write(&mut self, content: ClipboardContent) -> Result<(), String> {
+        self.0.write(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 测试用后端：返回预设的内容
+    #[derive(Default)]
+    struct MockProvider {
+        available: bool,
+        image: Option<Result<ClipboardIngredient, String>>,
+        texts: Vec<String>,
+        hint: Option<&'static str>,
+        write_error: Option<String>,
+        written: Vec<String>,
+    }
+
+    impl ClipboardProvider for MockProvider {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn is_available(&mut self) -> bool {
+            self.available
+        }
+
+        fn install_hint(&self) -> Option<&'static str> {
+            self.hint
+        }
+
+        fn read_image(&mut self) -> Result<Option<ClipboardIngredient>, String> {
+            self.image.clone().transpose()
+        }
+
+        fn read_texts(&mut self) -> Vec<String> {
+            self.texts.clone()
+        }
+
+        fn write(&mut self, content: ClipboardContent) -> Result<(), String> {
+            if let Some(e) = &self.write_error {
+                return Err(e.clone());
+            }
+            self.written.push(match content {
+                ClipboardContent::Text(text) => text.to_string(),
+                ClipboardContent::Png(png) => format!("png:{}", png.len()),
+            });
+            Ok(())
+        }
+    }
+
+    fn png(tag: &str) -> ClipboardIngredient {
+        ClipboardIngredient {
+            dish_type: "image/png".to_string(),
+            tag: Some(tag.to_string()),
+            bytes: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_read_ingredients_order() {
+        let path = std::env::temp_dir().join(format!("clipboard-test-{}.txt", std::process::id()));
+        fs::write(&path, "notes").unwrap();
+
+        // 不可用的后端被跳过，文本中的文件路径被读取
+        let mut providers: Vec<Box<dyn ClipboardProvider>> = vec![
+            Box::new(MockProvider { image: Some(Ok(png("skipped"))), ..Default::default() }),
+            Box::new(MockProvider {
+                available: true,
+                texts: vec![format!("file://{}", path.display())],
+                ..Default::default()
+            }),
+        ];
+        let items = read_ingredients(&mut providers).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].dish_type, "text/plain");
+        assert_eq!(items[0].bytes, b"notes");
+
+        // 前一个后端读取失败时继续尝试后面的后端
+        let mut providers: Vec<Box<dyn ClipboardProvider>> = vec![
+            Box::new(MockProvider { available: true, image: Some(Err("boom".to_string())), ..Default::default() }),
+            Box::new(MockProvider { available: true, image: Some(Ok(png("second"))), ..Default::default() }),
+        ];
+        assert_eq!(read_ingredients(&mut providers).unwrap()[0].tag.as_deref(), Some("second"));
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_read_ingredients_errors() {
+        let mut providers: Vec<Box<dyn ClipboardProvider>> = vec![
+            Box::new(MockProvider { available: true, image: Some(Err("boom".to_string())), ..Default::default() }),
+        ];
+        assert_eq!(read_ingredients(&mut providers).unwrap_err(), "boom");
+
+        let mut providers: Vec<Box<dyn ClipboardProvider>> = vec![
+            Box::new(MockProvider { available: true, ..Default::default() }),
+            Box::new(MockProvider { hint: Some("install it"), ..Default::default() }),
+        ];
+        assert_eq!(read_ingredients(&mut providers).unwrap_err(), "剪贴板里没有食材（install it）");
+    }
+
+    #[test]
+    fn test_write_content_falls_back() {
+        let mut providers: Vec<Box<dyn ClipboardProvider>> = vec![
+            Box::new(MockProvider { hint: Some("install it"), ..Default::default() }),
+            Box::new(MockProvider { available: true, write_error: Some("boom".to_string()), ..Default::default() }),
+            Box::new(MockProvider { available: true, ..Default::default() }),
+        ];
+        assert!(write_content(&mut providers, ClipboardContent::Text("answer")).is_ok());
+
+        let mut providers: Vec<Box<dyn ClipboardProvider>> = vec![
+            Box::new(MockProvider { hint: Some("install it"), ..Default::default() }),
+        ];
+        assert_eq!(
+            write_content(&mut providers, ClipboardContent::Png(&[1, 2])).unwrap_err(),
+            "没有可用的剪贴板后端（install it）"
+        );
+    }
+
+    #[test]
+    fn test_backend_parse_and_args() {
+        assert_eq!(ClipboardBackend::parse(" Wayland "), ClipboardBackend::Wayland);
+        assert_eq!(ClipboardBackend::parse("xclip"), ClipboardBackend::X11);
+        assert_eq!(ClipboardBackend::parse("unknown"), ClipboardBackend::Auto);
+        assert_eq!(wayland_args(true, Some("image/png")), ["--primary", "--no-newline", "--type", "image/png"]);
+        assert_eq!(x11_args(false, None), ["-selection", "clipboard", "-o"]);
+        assert_eq!(x11_copy_args("image/png"), ["-selection", "clipboard", "-t", "image/png", "-i"]);
+    }
+}
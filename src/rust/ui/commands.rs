@@ -1,26 +1,22 @@
-use crate::config::{save_config, load_config, AppState, ReplyConfig, WindowConfig, CustomPrompt, CustomPromptConfig, ShortcutConfig, ShortcutBinding};
-use crate::constants::{window, ui, validation};
+use crate::config::{save_config, load_config, AppState, ReplyConfig, WindowConfig, CustomPrompt, CustomPromptConfig, ShortcutConfig, ShortcutBinding, ConfigImportResult, ConfigProfiles, ProjectEntry, ProjectInfo, PromptConflictAction, PromptPackImportResult, PromptPackPreview, PromptUsageReport};
+use crate::constants::{window, theme, ui, validation};
 use crate::mcp::types::{build_refill_response, IngredientAttachment, PopupRequest};
-use crate::mcp::{discard_spice, fetch_ingredient_bytes, stash_ingredient_bytes};
+use crate::mcp::{
+    abort_stash_upload, append_stash_upload, begin_stash_upload, clear_pantry, discard_spice, fetch_ingredient_bytes,
+    ingredient_kind, list_pantry_items, pantry_crypto, remove_pantry_items, stash_ingredient_bytes, take_stash_upload, PantryCleanup,
+    get_history_entry, read_history_ingredient, PantryItem, PantryJanitorConfig, MAX_UPLOAD_BYTES,
+};
+use crate::mcp::ingredient_kind::{IngredientKind, IngredientLimits};
+use crate::mcp::pantry_metrics::{pantry_metrics, PantryMetrics};
+use crate::ui::clipboard;
+use crate::ui::ingredient_image::{self, ImagePipelineConfig};
 use crate::mcp::handlers::create_tauri_popup;
-use serde::Serialize;
-use tauri::{AppHandle, Manager, State};
-use arboard::Clipboard;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State, Theme};
 use base64::engine::general_purpose;
 use base64::Engine;
-use image::codecs::png::PngEncoder;
-use image::ColorType;
-use image::ImageFormat;
-use image::ImageEncoder;
-use percent_encoding::percent_decode_str;
 use std::fs;
 use std::path::PathBuf;
-#[cfg(target_os = "linux")]
-use std::process::Command;
-#[cfg(target_os = "linux")]
-use std::io::ErrorKind;
-#[cfg(any(target_os = "windows", target_os = "macos"))]
-use std::process::Command;
 
 #[tauri::command]
 pub async fn get_app_info() -> Result<String, String> {
@@ -35,35 +31,42 @@ pub struct CachedIngredient {
     pub bytes: Vec<u8>,
 }
 
-#[derive(Debug, Clone)]
-struct ClipboardIngredientBytes {
-    dish_type: String,
-    tag: Option<String>,
-    bytes: Vec<u8>,
+fn normalize_ingredient_bytes(bytes: &[u8], dish_type: &str) -> Result<(Vec<u8>, String), String> {
+    if bytes.is_empty() {
+        return Err("食材内容为空".to_string());
+    }
+    let dt = ingredient_kind::resolve_dish_type(bytes, dish_type)
+        .ok_or_else(|| format!("不支持的食材类型: {}", dish_type.trim()))?;
+    let limits = IngredientLimits::load();
+    let (normalized_bytes, normalized_dish_type) = match ingredient_kind::kind_of(&dt) {
+        Some(IngredientKind::Image) => ingredient_image::process_image(
+            bytes,
+            &dt,
+            limits.max_bytes_for(IngredientKind::Image),
+            &ImagePipelineConfig::load(),
+        )?,
+        _ => (bytes.to_vec(), dt),
+    };
+    check_ingredient_size(&normalized_dish_type, normalized_bytes.len() as u64, &limits)?;
+    Ok((normalized_bytes, normalized_dish_type))
 }
 
-fn normalize_ingredient_bytes(bytes: &[u8], dish_type: &str) -> Result<(Vec<u8>, String), String> {
-    let dt = dish_type.trim();
-    match dt {
-        "image/bmp" | "image/x-ms-bmp" => {
-            let img = image::load_from_memory_with_format(bytes, ImageFormat::Bmp)
-                .map_err(|e| format!("读取 BMP 失败: {}", e))?;
-            let rgba = img.to_rgba8();
-            let (width, height) = rgba.dimensions();
-            let raw = rgba.into_raw();
-
-            let mut png_bytes: Vec<u8> = Vec::new();
-            let encoder = PngEncoder::new(&mut png_bytes);
-            encoder
-                .write_image(raw.as_slice(), width, height, ColorType::Rgba8.into())
-                .map_err(|e| format!("转换 PNG 失败: {}", e))?;
-            Ok((png_bytes, "image/png".to_string()))
-        }
-        _ => Ok((bytes.to_vec(), dish_type.to_string())),
+/// 按食材类别检查单个食材大小
+fn check_ingredient_size(dish_type: &str, size_bytes: u64, limits: &IngredientLimits) -> Result<(), String> {
+    let kind = ingredient_kind::kind_of(dish_type)
+        .ok_or_else(|| format!("不支持的食材类型: {}", dish_type))?;
+    let max_bytes = limits.max_bytes_for(kind);
+    if size_bytes > max_bytes {
+        return Err(format!(
+            "{}食材太大（上限 {} MB），建议换一份更小的内容或缩小截图范围",
+            kind.label(),
+            max_bytes / (1024 * 1024)
+        ));
     }
+    Ok(())
 }
 
-fn stash_ingredient(
+pub(crate) fn stash_ingredient(
     bytes: Vec<u8>,
     dish_type: &str,
     tag: Option<String>,
@@ -80,94 +83,6 @@ fn stash_ingredient(
     })
 }
 
-#[cfg(target_os = "linux")]
-fn run_linux_command_output(cmd: &str, args: &[&str]) -> Option<std::process::Output> {
-    let candidates = [
-        cmd.to_string(),
-        format!("/snap/bin/{cmd}"),
-        format!("/usr/local/bin/{cmd}"),
-        format!("/usr/bin/{cmd}"),
-        format!("/bin/{cmd}"),
-    ];
-
-    for program in candidates {
-        match Command::new(&program).args(args).output() {
-            Ok(o) => return Some(o),
-            Err(e) if e.kind() == ErrorKind::NotFound => continue,
-            Err(_) => continue,
-        }
-    }
-    None
-}
-
-#[cfg(target_os = "linux")]
-fn try_read_ingredients_from_linux_clipboard_text() -> Option<Vec<ClipboardIngredientBytes>> {
-    let texts = try_read_linux_text_candidates()?;
-    let mut out: Vec<ClipboardIngredientBytes> = Vec::new();
-
-    for text in texts {
-        let paths = extract_file_paths_from_clipboard_text(&text);
-        for p in paths {
-            if let Some(item) = try_load_ingredient_file_as_clipboard_item(&p) {
-                out.push(item);
-            }
-        }
-        if !out.is_empty() {
-            break;
-        }
-    }
-
-    if out.is_empty() { None } else { Some(out) }
-}
-
-#[cfg(target_os = "linux")]
-fn try_read_linux_text_candidates() -> Option<Vec<String>> {
-    let candidates: Vec<(&str, Vec<&str>)> = vec![
-        ("wl-paste", vec!["--no-newline", "--type", "text/uri-list"]),
-        ("wl-paste", vec!["--no-newline", "--type", "text/plain;charset=utf-8"]),
-        ("wl-paste", vec!["--no-newline"]),
-        ("wl-paste", vec!["--primary", "--no-newline", "--type", "text/uri-list"]),
-        ("wl-paste", vec!["--primary", "--no-newline", "--type", "text/plain;charset=utf-8"]),
-        ("wl-paste", vec!["--primary", "--no-newline"]),
-
-        ("wl-clip.paste", vec!["--no-newline", "--type", "text/uri-list"]),
-        ("wl-clip.paste", vec!["--no-newline", "--type", "text/plain;charset=utf-8"]),
-        ("wl-clip.paste", vec!["--no-newline"]),
-        ("wl-clip.paste", vec!["--primary", "--no-newline", "--type", "text/uri-list"]),
-        ("wl-clip.paste", vec!["--primary", "--no-newline", "--type", "text/plain;charset=utf-8"]),
-        ("wl-clip.paste", vec!["--primary", "--no-newline"]),
-
-        ("xclip", vec!["-selection", "clipboard", "-t", "text/uri-list", "-o"]),
-        ("xclip", vec!["-selection", "clipboard", "-o"]),
-        ("xclip", vec!["-selection", "primary", "-t", "text/uri-list", "-o"]),
-        ("xclip", vec!["-selection", "primary", "-o"]),
-    ];
-
-    let mut out: Vec<String> = Vec::new();
-    for (cmd, args) in candidates {
-        let output = match run_linux_command_output(cmd, &args) {
-            Some(o) => o,
-            None => continue,
-        };
-        if !output.status.success() {
-            continue;
-        }
-        let s = match String::from_utf8(output.stdout) {
-            Ok(s) => s,
-            Err(_) => continue,
-        };
-        let s = s.trim().to_string();
-        if s.is_empty() {
-            continue;
-        }
-        if !out.contains(&s) {
-            out.push(s);
-        }
-    }
-
-    if out.is_empty() { None } else { Some(out) }
-}
-
 #[tauri::command]
 pub async fn get_always_on_top(state: State<'_, AppState>) -> Result<bool, String> {
     let config = state
@@ -246,6 +161,231 @@ pub async fn reload_config(
     Ok(())
 }
 
+/// 导出完整配置到指定文件，redact 为 true 时替换令牌、密码等密钥，返回脱敏的字段数
+#[tauri::command]
+pub async fn export_config_file(
+    path: String,
+    redact: bool,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    if !validation::is_valid_file_path(path.trim()) {
+        return Err("请填写导出文件路径".to_string());
+    }
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?
+        .clone();
+    crate::config::export_config(&config, std::path::Path::new(path.trim()), redact)
+        .map_err(|e| format!("导出配置失败: {}", e))
+}
+
+/// 从文件导入配置并合并到当前配置，写入前备份原配置文件
+#[tauri::command]
+pub async fn import_config_file(
+    path: String,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<ConfigImportResult, String> {
+    if !validation::is_valid_file_path(path.trim()) {
+        return Err("请填写导入文件路径".to_string());
+    }
+    let current = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?
+        .clone();
+    let (imported, missing_secrets) = crate::config::merge_imported_config(&current, std::path::Path::new(path.trim()))
+        .map_err(|e| format!("导入配置失败: {}", e))?;
+    let backup_path = crate::config::backup_config_file()
+        .map_err(|e| format!("备份当前配置失败: {}", e))?;
+
+    {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        *config = imported;
+    }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    log::info!("已从 {} 导入配置", path.trim());
+    let _ = app.emit("config_reloaded", ());
+
+    Ok(ConfigImportResult {
+        backup_path: backup_path.map(|p| p.to_string_lossy().to_string()),
+        missing_secrets,
+    })
+}
+
+#[tauri::command]
+pub async fn get_config_profiles(state: State<'_, AppState>) -> Result<ConfigProfiles, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(crate::config::list_profiles(&config))
+}
+
+/// 修改配置档案并保存，返回最新的档案列表
+async fn update_config_profiles(
+    state: &State<'_, AppState>,
+    app: &AppHandle,
+    update: impl FnOnce(&mut crate::config::AppConfig) -> anyhow::Result<()>,
+) -> Result<ConfigProfiles, String> {
+    let profiles = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        update(&mut config).map_err(|e| e.to_string())?;
+        crate::config::list_profiles(&config)
+    };
+    save_config(state, app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(profiles)
+}
+
+/// 以当前设置新建配置档案
+#[tauri::command]
+pub async fn create_config_profile(
+    name: String,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<ConfigProfiles, String> {
+    update_config_profiles(&state, &app, |config| crate::config::create_profile(config, name.trim())).await
+}
+
+/// 切换当前配置档案，切换后通知前端重新加载设置
+#[tauri::command]
+pub async fn switch_config_profile(
+    name: String,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<ConfigProfiles, String> {
+    let profiles = update_config_profiles(&state, &app, |config| crate::config::switch_profile(config, name.trim())).await?;
+    log::info!("已切换到配置档案: {}", profiles.active);
+    let _ = app.emit("config_reloaded", ());
+    Ok(profiles)
+}
+
+#[tauri::command]
+pub async fn delete_config_profile(
+    name: String,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<ConfigProfiles, String> {
+    update_config_profiles(&state, &app, |config| crate::config::delete_profile(config, name.trim())).await
+}
+
+#[tauri::command]
+pub async fn get_registered_projects(state: State<'_, AppState>) -> Result<Vec<ProjectInfo>, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(crate::config::list_projects(&config))
+}
+
+/// 登记或更新项目，acemcp_endpoint 为空时使用默认端点
+/// 端点变化后清空该项目已有的 blob 记录；开启自动监听时立即开始监听
+#[tauri::command]
+pub async fn save_registered_project(
+    mut project: ProjectEntry,
+    acemcp_endpoint: Option<String>,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<Vec<ProjectInfo>, String> {
+    project.path = crate::mcp::tools::acemcp::mcp::normalize_project_root(project.path.trim());
+    let (endpoint_changed, projects) = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        let changed = crate::config::upsert_project(&mut config, project.clone(), acemcp_endpoint)
+            .map_err(|e| e.to_string())?;
+        (changed, crate::config::list_projects(&config))
+    };
+
+    if endpoint_changed {
+        crate::mcp::tools::acemcp::mcp::reset_project_blobs(&project.path)
+            .map_err(|e| format!("清理项目索引记录失败: {}", e))?;
+    }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    if project.auto_watch {
+        crate::mcp::tools::acemcp::commands::watch_registered_projects(vec![project.path]).await;
+    }
+    Ok(projects)
+}
+
+/// 从登记表移除项目（保留索引与端点分配）
+#[tauri::command]
+pub async fn remove_registered_project(
+    path: String,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<Vec<ProjectInfo>, String> {
+    let projects = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        crate::config::remove_project(&mut config, &path).map_err(|e| e.to_string())?;
+        crate::config::list_projects(&config)
+    };
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+    Ok(projects)
+}
+
+#[tauri::command]
+pub async fn get_keychain_secrets(state: State<'_, AppState>) -> Result<bool, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.keychain_secrets)
+}
+
+/// 切换密钥的保存位置：开启时把配置中的密钥迁移到系统钥匙串，关闭时写回配置文件并删除钥匙串条目；
+/// 返回迁移的密钥数
+#[tauri::command]
+pub async fn set_keychain_secrets(
+    enabled: bool,
+    state: State<'_, AppState>,
+    app: tauri::AppHandle,
+) -> Result<usize, String> {
+    if enabled {
+        crate::config::secrets::check_keychain().map_err(|e| e.to_string())?;
+    }
+    let value = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        config.keychain_secrets = enabled;
+        serde_json::to_value(&*config).map_err(|e| format!("序列化配置失败: {}", e))?
+    };
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    let migrated = if enabled {
+        crate::config::secrets::count_secrets(&value)
+    } else {
+        crate::config::secrets::clear_keychain_secrets(&value)
+    };
+    log::info!("密钥已{}，共 {} 项", if enabled { "迁移到系统钥匙串" } else { "改回保存在配置文件" }, migrated);
+    Ok(migrated)
+}
+
 #[tauri::command]
 pub async fn get_theme(state: State<'_, AppState>) -> Result<String, String> {
     let config = state
@@ -255,16 +395,36 @@ pub async fn get_theme(state: State<'_, AppState>) -> Result<String, String> {
     Ok(config.ui_config.theme.clone())
 }
 
+/// 当前系统主题，无法获取时视为深色
+pub fn system_theme(app: &AppHandle) -> &'static str {
+    match app.get_webview_window("main").and_then(|w| w.theme().ok()) {
+        Some(Theme::Light) => theme::LIGHT,
+        _ => theme::DARK,
+    }
+}
+
+/// 实际使用的主题（auto 时解析为当前系统主题）
+#[tauri::command]
+pub async fn get_effective_theme(state: State<'_, AppState>, app: tauri::AppHandle) -> Result<String, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(theme::resolve_theme(&config.ui_config.theme, system_theme(&app)).to_string())
+}
+
+/// 保存主题设置，返回实际使用的主题
 #[tauri::command]
 pub async fn set_theme(
     theme: String,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<String, String> {
     // 验证主题值
-    if !["light", "dark"].contains(&theme.as_str()) {
-        return Err("无效的主题值，只支持 light、dark".to_string());
+    if !validation::is_valid_theme(&theme) {
+        return Err("无效的主题值，只支持 light、dark、auto".to_string());
     }
+    let effective = theme::resolve_theme(&theme, system_theme(&app)).to_string();
 
     {
         let mut config = state
@@ -279,7 +439,7 @@ pub async fn set_theme(
         .await
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
-    Ok(())
+    Ok(effective)
 }
 
 #[tauri::command]
@@ -296,7 +456,22 @@ pub async fn set_window_config(
     window_config: WindowConfig,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<validation::ValidationReport, String> {
+    let mut window_config = window_config;
+    let mut report = validation::ValidationReport::new();
+    (window_config.fixed_width, window_config.fixed_height) = validation::check_window_size(
+        &mut report,
+        ("fixed_width", "fixed_height"),
+        window_config.fixed_width,
+        window_config.fixed_height,
+    );
+    (window_config.free_width, window_config.free_height) = validation::check_window_size(
+        &mut report,
+        ("free_width", "free_height"),
+        window_config.free_width,
+        window_config.free_height,
+    );
+
     {
         let mut config = state
             .config
@@ -310,7 +485,7 @@ pub async fn set_window_config(
         .await
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
-    Ok(())
+    Ok(report)
 }
 
 #[tauri::command]
@@ -327,12 +502,27 @@ pub async fn set_reply_config(
     reply_config: ReplyConfig,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<validation::ValidationReport, String> {
+    let mut reply_config = reply_config;
+    let mut report = validation::ValidationReport::new();
+    let (max_mb, total_max_mb) = validation::check_ingredient_limits(
+        &mut report,
+        reply_config.ingredient_max_mb,
+        reply_config.ingredients_total_max_mb,
+    );
+    reply_config.ingredient_max_mb = max_mb;
+    reply_config.ingredients_total_max_mb = total_max_mb;
+
     {
         let mut config = state
             .config
             .lock()
             .map_err(|e| format!("获取配置失败: {}", e))?;
+        // 继续提示词为空时保留原值
+        if validation::is_empty_string(&reply_config.continue_prompt) {
+            report.reject("continue_prompt", &reply_config.continue_prompt, "继续提示词不能为空");
+            reply_config.continue_prompt = config.reply_config.continue_prompt.clone();
+        }
         config.reply_config = reply_config;
     }
 
@@ -341,7 +531,7 @@ pub async fn set_reply_config(
         .await
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
-    Ok(())
+    Ok(report)
 }
 
 #[tauri::command]
@@ -456,66 +646,66 @@ pub async fn get_current_window_size(app: tauri::AppHandle) -> Result<serde_json
     Err("无法获取当前窗口大小".to_string())
 }
 
+/// 读取窗口尺寸字段并限制在范围内，类型不对时记录并跳过
+fn read_window_dimension(
+    settings: &serde_json::Value,
+    field: &str,
+    range: (f64, f64),
+    report: &mut validation::ValidationReport,
+) -> Option<f64> {
+    let value = settings.get(field)?;
+    match value.as_f64() {
+        Some(v) => Some(report.clamp_range(field, v, range.0, range.1)),
+        None => {
+            report.reject(field, value, "需要数值");
+            None
+        }
+    }
+}
+
 #[tauri::command]
 pub async fn set_window_settings(
     window_settings: serde_json::Value,
     state: State<'_, AppState>,
     app: tauri::AppHandle,
-) -> Result<(), String> {
+) -> Result<validation::ValidationReport, String> {
+    let mut report = validation::ValidationReport::new();
+    let widths = (window::MIN_WIDTH, window::MAX_WIDTH);
+    let heights = (window::MIN_HEIGHT, window::MAX_HEIGHT);
     {
         let mut config = state
             .config
             .lock()
             .map_err(|e| format!("获取配置失败: {}", e))?;
+        let window_config = &mut config.ui_config.window_config;
 
         // 更新窗口配置
-        if let Some(fixed) = window_settings.get("fixed").and_then(|v| v.as_bool()) {
-            config.ui_config.window_config.fixed = fixed;
-        }
-
-        // 更新固定模式尺寸（添加尺寸验证）
-        if let Some(width) = window_settings.get("fixed_width").and_then(|v| v.as_f64()) {
-            if let Some(height) = window_settings.get("fixed_height").and_then(|v| v.as_f64()) {
-                if validation::is_valid_window_size(width, height) {
-                    config.ui_config.window_config.fixed_width = width;
-                    config.ui_config.window_config.fixed_height = height;
-                }
-            } else if width >= window::MIN_WIDTH {
-                config.ui_config.window_config.fixed_width = width;
-            }
-        } else if let Some(height) = window_settings.get("fixed_height").and_then(|v| v.as_f64()) {
-            if height >= window::MIN_HEIGHT {
-                config.ui_config.window_config.fixed_height = height;
+        if let Some(value) = window_settings.get("fixed") {
+            match value.as_bool() {
+                Some(fixed) => window_config.fixed = fixed,
+                None => report.reject("fixed", value, "需要布尔值"),
             }
         }
 
-        // 更新自由拉伸模式尺寸（添加尺寸验证）
-        if let Some(width) = window_settings.get("free_width").and_then(|v| v.as_f64()) {
-            if let Some(height) = window_settings.get("free_height").and_then(|v| v.as_f64()) {
-                if validation::is_valid_window_size(width, height) {
-                    config.ui_config.window_config.free_width = width;
-                    config.ui_config.window_config.free_height = height;
-                }
-            } else if width >= window::MIN_WIDTH {
-                config.ui_config.window_config.free_width = width;
-            }
-        } else if let Some(height) = window_settings.get("free_height").and_then(|v| v.as_f64()) {
-            if height >= window::MIN_HEIGHT {
-                config.ui_config.window_config.free_height = height;
-            }
+        // 更新固定模式与自由拉伸模式的尺寸，超出范围的值修正后保存
+        if let Some(width) = read_window_dimension(&window_settings, "fixed_width", widths, &mut report) {
+            window_config.fixed_width = width;
+        }
+        if let Some(height) = read_window_dimension(&window_settings, "fixed_height", heights, &mut report) {
+            window_config.fixed_height = height;
+        }
+        if let Some(width) = read_window_dimension(&window_settings, "free_width", widths, &mut report) {
+            window_config.free_width = width;
+        }
+        if let Some(height) = read_window_dimension(&window_settings, "free_height", heights, &mut report) {
+            window_config.free_height = height;
         }
 
-        // 兼容旧的width/height参数，更新当前模式的尺寸（添加尺寸验证）
-        if let (Some(width), Some(height)) = (
-            window_settings.get("width").and_then(|v| v.as_f64()),
-            window_settings.get("height").and_then(|v| v.as_f64()),
-        ) {
-            if validation::is_valid_window_size(width, height) {
-                config
-                    .ui_config
-                    .window_config
-                    .update_current_size(width, height);
-            }
+        // 兼容旧的width/height参数，更新当前模式的尺寸
+        let width = read_window_dimension(&window_settings, "width", widths, &mut report);
+        let height = read_window_dimension(&window_settings, "height", heights, &mut report);
+        if let (Some(width), Some(height)) = (width, height) {
+            window_config.update_current_size(width, height);
         }
     }
 
@@ -524,7 +714,7 @@ pub async fn set_window_settings(
         .await
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
-    Ok(())
+    Ok(report)
 }
 
 #[tauri::command]
@@ -594,51 +784,50 @@ fn resolve_spice_ids_in_dish_response(response: &mut serde_json::Value) -> Resul
         None => return Ok(()),
     };
 
-    if !ingredients.is_empty() {
-        let has_spice_id = ingredients
-            .iter()
-            .any(|v| v.get("spice_id").and_then(|t| t.as_str()).is_some());
-        if !has_spice_id {
-            return Err("食材必须使用 spice_id 提交（旧的提交已移除）".to_string());
-        }
-    }
-
-    let max_single_bytes: u64 = 8 * 1024 * 1024;
-    let max_total_bytes: u64 = 16 * 1024 * 1024;
-    let mut total_bytes: u64 = 0;
+    let spice_ids = ingredients
+        .iter()
+        .map(|item| item.get("spice_id").and_then(|t| t.as_str()))
+        .collect::<Option<Vec<&str>>>()
+        .ok_or_else(|| "食材必须使用 spice_id 提交（旧的提交已移除）".to_string())?;
 
-    let mut out: Vec<IngredientAttachment> = Vec::new();
+    let out = ingredients_from_spice_ids(&spice_ids)?;
 
-    for item in ingredients.iter() {
-        if let Some(spice_id) = item.get("spice_id").and_then(|t| t.as_str()) {
-            let (bytes, label) = fetch_ingredient_bytes(spice_id)
-                .map_err(|e| format!("读取食材失败: {}", e))?;
+    *ingredients_value = serde_json::to_value(out)
+        .map_err(|e| format!("处理食材失败: {}", e))?;
+    Ok(())
+}
 
-            if label.size_bytes > max_single_bytes {
-                return Err("食材太大，建议换一份更小的内容或缩小截图范围".to_string());
-            }
-            total_bytes = total_bytes.saturating_add(label.size_bytes);
-            if total_bytes > max_total_bytes {
-                return Err("食材总大小太大，建议减少数量或换更小的内容".to_string());
-            }
+/// 把已缓存的食材转换为回复中的附件（检查大小限制），转换后释放缓存
+pub(crate) fn ingredients_from_spice_ids(spice_ids: &[&str]) -> Result<Vec<IngredientAttachment>, String> {
+    let limits = IngredientLimits::load();
+    let mut total_bytes: u64 = 0;
 
-            let b64 = general_purpose::STANDARD.encode(bytes);
-            out.push(IngredientAttachment {
-                sauce: b64,
-                dish_type: label.dish_type,
-                tag: label.tag,
-            });
+    let mut out: Vec<IngredientAttachment> = Vec::new();
 
-            let _ = discard_spice(spice_id);
-            continue;
+    for spice_id in spice_ids {
+        let (bytes, label) = fetch_ingredient_bytes(spice_id)
+            .map_err(|e| format!("读取食材失败: {}", e))?;
+
+        check_ingredient_size(&label.dish_type, label.size_bytes, &limits)?;
+        total_bytes = total_bytes.saturating_add(label.size_bytes);
+        if total_bytes > limits.max_total_bytes {
+            return Err(format!(
+                "食材总大小太大（上限 {} MB），建议减少数量或换更小的内容",
+                limits.max_total_bytes / (1024 * 1024)
+            ));
         }
 
-        return Err("食材必须使用 spice_id 提交（旧的提交已移除）".to_string());
+        let b64 = general_purpose::STANDARD.encode(bytes);
+        out.push(IngredientAttachment {
+            sauce: b64,
+            dish_type: label.dish_type,
+            tag: label.tag,
+        });
+
+        let _ = discard_spice(spice_id);
     }
 
-    *ingredients_value = serde_json::to_value(out)
-        .map_err(|e| format!("处理食材失败: {}", e))?;
-    Ok(())
+    Ok(out)
 }
 
  #[tauri::command]
@@ -719,344 +908,217 @@ pub async fn discard_spice_cmd(spice_id: String) -> Result<(), String> {
 }
 
 #[tauri::command]
-pub async fn read_clipboard_ingredients_cached() -> Result<Vec<CachedIngredient>, String> {
-    let items = read_clipboard_ingredients_impl()?;
-    let mut out: Vec<CachedIngredient> = Vec::new();
-    for item in items {
-        out.push(stash_ingredient(item.bytes, item.dish_type.as_str(), item.tag)?);
-    }
-    Ok(out)
+pub async fn begin_stash_upload_cmd() -> Result<String, String> {
+    begin_stash_upload().map_err(|e| format!("开始上传失败: {}", e))
 }
 
-fn read_clipboard_ingredients_impl() -> Result<Vec<ClipboardIngredientBytes>, String> {
-    let mut clipboard = Clipboard::new().map_err(|e| format!("打开系统剪贴板失败: {}", e))?;
-
-    match clipboard.get_image() {
-        Ok(img) => {
-            let width = u32::try_from(img.width).map_err(|_| "食材宽度过大，无法处理".to_string())?;
-            let height = u32::try_from(img.height).map_err(|_| "食材高度过大，无法处理".to_string())?;
-
-            let expected_len = img
-                .width
-                .checked_mul(img.height)
-                .and_then(|v| v.checked_mul(4))
-                .ok_or_else(|| "食材尺寸异常，无法处理".to_string())?;
-
-            if img.bytes.len() < expected_len {
-                return Err("剪贴板食材长度不正确".to_string());
-            }
+/// 追加一块原始字节（请求体为二进制，上传 ID 放在 upload-id 请求头中），返回已上传的总字节数
+#[tauri::command]
+pub async fn append_stash_upload_cmd(request: tauri::ipc::Request<'_>) -> Result<u64, String> {
+    let upload_id = request
+        .headers()
+        .get("upload-id")
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| "缺少 upload-id 请求头".to_string())?;
+    let tauri::ipc::InvokeBody::Raw(chunk) = request.body() else {
+        return Err("上传内容必须是二进制数据".to_string());
+    };
+    append_stash_upload(upload_id, chunk).map_err(|e| format!("上传失败: {}", e))
+}
 
-            let mut png_bytes: Vec<u8> = Vec::new();
-            let encoder = PngEncoder::new(&mut png_bytes);
-            encoder
-                .write_image(
-                    img.bytes.as_ref(),
-                    width,
-                    height,
-                    ColorType::Rgba8.into(),
-                )
-                .map_err(|e| format!("编码 PNG 失败: {}", e))?;
-
-            return Ok(vec![ClipboardIngredientBytes {
-                dish_type: "image/png".to_string(),
-                tag: None,
-                bytes: png_bytes,
-            }]);
-        }
-        Err(arboard::Error::ContentNotAvailable) => {}
-        Err(e) => {
-            let primary_err = format!("读取剪贴板食材失败: {}", e);
-            if let Some(items) = try_read_ingredients_from_clipboard_text(&mut clipboard) {
-                return Ok(items);
-            }
-            #[cfg(target_os = "windows")]
-            {
-                if let Some(items) = try_read_windows_clipboard_ingredient_files() {
-                    return Ok(items);
-                }
-            }
-            #[cfg(target_os = "macos")]
-            {
-                if let Some(items) = try_read_macos_clipboard_ingredient_files() {
-                    return Ok(items);
-                }
-            }
-            #[cfg(target_os = "linux")]
-            {
-                if let Some(items) = try_read_linux_clipboard_ingredient() {
-                    return Ok(items);
-                }
-            }
-            return Err(primary_err);
-        }
-    }
+#[tauri::command]
+pub async fn commit_stash_upload_cmd(
+    upload_id: String,
+    dish_type: String,
+    tag: Option<String>,
+) -> Result<String, String> {
+    let bytes = take_stash_upload(&upload_id).map_err(|e| format!("读取上传内容失败: {}", e))?;
+    stash_ingredient(bytes, dish_type.as_str(), tag).map(|item| item.spice_id)
+}
 
-    if let Some(items) = try_read_ingredients_from_clipboard_text(&mut clipboard) {
-        return Ok(items);
-    }
+#[tauri::command]
+pub async fn abort_stash_upload_cmd(upload_id: String) -> Result<(), String> {
+    abort_stash_upload(&upload_id).map_err(|e| format!("取消上传失败: {}", e))
+}
 
-    #[cfg(target_os = "windows")]
-    {
-        if let Some(items) = try_read_windows_clipboard_ingredient_files() {
-            return Ok(items);
-        }
-    }
+/// 直接按本地路径保存食材，文件内容不经过 IPC
+#[tauri::command]
+pub async fn stash_ingredient_file_cmd(path: String, tag: Option<String>) -> Result<String, String> {
+    let path = PathBuf::from(path);
+    let bytes = fs::read(&path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let dish_type = guess_ingredient_mime_from_path(&path).unwrap_or("application/octet-stream");
+    let tag = tag.or_else(|| path.file_name().map(|n| n.to_string_lossy().to_string()));
+    stash_ingredient(bytes, dish_type, tag).map(|item| item.spice_id)
+}
 
-    #[cfg(target_os = "macos")]
-    {
-        if let Some(items) = try_read_macos_clipboard_ingredient_files() {
-            return Ok(items);
-        }
-    }
+/// 拖放文件的处理结果
+#[derive(Debug, Clone, Serialize)]
+pub struct DroppedIngredients {
+    pub ingredients: Vec<CachedIngredient>,
+    /// 未能保存的文件及原因
+    pub errors: Vec<String>,
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        if let Some(items) = try_read_ingredients_from_linux_clipboard_text() {
-            return Ok(items);
+/// 保存拖放到窗口中的文件：校验、识别类型、规范化后存入 pantry
+pub fn stash_dropped_files(paths: &[PathBuf]) -> DroppedIngredients {
+    let mut dropped = DroppedIngredients { ingredients: Vec::new(), errors: Vec::new() };
+    for path in paths {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        match stash_dropped_file(path, &name) {
+            Ok(item) => dropped.ingredients.push(item),
+            Err(e) => dropped.errors.push(format!("{}: {}", name, e)),
         }
     }
+    dropped
+}
 
-    #[cfg(target_os = "linux")]
-    {
-        if let Some(items) = try_read_linux_clipboard_ingredient() {
-            return Ok(items);
-        }
+fn stash_dropped_file(path: &PathBuf, name: &str) -> Result<CachedIngredient, String> {
+    let metadata = fs::metadata(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    if !metadata.is_file() {
+        return Err("不是文件".to_string());
     }
-
-    #[cfg(target_os = "linux")]
-    {
-        let wl_ok = linux_command_exists("wl-paste");
-        let wl_clip_ok = linux_command_exists("wl-clip.paste");
-        let xclip_ok = linux_command_exists("xclip");
-        if !wl_ok && !wl_clip_ok && !xclip_ok {
-            return Err("剪贴板里没有食材（Linux 下未检测到 wl-paste / wl-clip.paste / xclip：Wayland 建议安装 wl-clipboard 或 snap 的 wl-clip）".to_string());
-        }
+    if metadata.len() > MAX_UPLOAD_BYTES {
+        return Err(format!("文件超过 {} MB", MAX_UPLOAD_BYTES / (1024 * 1024)));
     }
-
-    Err("剪贴板里没有食材".to_string())
+    let bytes = fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+    let dish_type = guess_ingredient_mime_from_path(path).unwrap_or("application/octet-stream");
+    stash_ingredient(bytes, dish_type, Some(name.to_string()))
 }
 
-fn linux_command_exists(cmd: &str) -> bool {
-    #[cfg(target_os = "linux")]
-    {
-        run_linux_command_output(cmd, &["--version"]).is_some()
-    }
+/// 食材缓存统计（命中、未命中、复用、淘汰次数与当前大小）
+#[tauri::command]
+pub async fn get_pantry_metrics() -> Result<PantryMetrics, String> {
+    tokio::task::spawn_blocking(pantry_metrics)
+        .await
+        .map_err(|e| format!("读取缓存统计失败: {}", e))?
+        .map_err(|e| format!("读取缓存统计失败: {}", e))
+}
 
-    #[cfg(not(target_os = "linux"))]
-    {
-        let _ = cmd;
-        false
-    }
+/// 清空食材缓存，返回删除数与释放的字节数
+#[tauri::command]
+pub async fn clear_pantry_cache_cmd() -> Result<PantryCleanup, String> {
+    tokio::task::spawn_blocking(clear_pantry)
+        .await
+        .map_err(|e| format!("清空食材缓存失败: {}", e))?
+        .map_err(|e| format!("清空食材缓存失败: {}", e))
 }
 
-fn try_read_ingredients_from_clipboard_text(
-    clipboard: &mut Clipboard,
-) -> Option<Vec<ClipboardIngredientBytes>> {
-    let text = clipboard.get_text().ok()?;
-    let paths = extract_file_paths_from_clipboard_text(&text);
-    if paths.is_empty() {
-        return None;
-    }
+#[tauri::command]
+pub async fn list_pantry_items_cmd() -> Result<Vec<PantryItem>, String> {
+    list_pantry_items().map_err(|e| format!("读取食材缓存失败: {}", e))
+}
 
-    let mut out: Vec<ClipboardIngredientBytes> = Vec::new();
-    for p in paths {
-        if let Some(item) = try_load_ingredient_file_as_clipboard_item(&p) {
-            out.push(item);
-        }
-    }
+#[tauri::command]
+pub async fn remove_pantry_items_cmd(spice_ids: Vec<String>) -> Result<PantryCleanup, String> {
+    remove_pantry_items(&spice_ids).map_err(|e| format!("删除食材失败: {}", e))
+}
 
-    if out.is_empty() { None } else { Some(out) }
+#[tauri::command]
+pub async fn get_pantry_encryption(state: State<'_, AppState>) -> Result<bool, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(config.mcp_config.pantry_encryption.unwrap_or(false))
 }
 
-fn extract_file_paths_from_clipboard_text(text: &str) -> Vec<PathBuf> {
-    let mut lines = text
-        .lines()
-        .map(|l| l.trim())
-        .filter(|l| !l.is_empty())
-        .collect::<Vec<_>>();
+/// 切换食材缓存加密：先迁移已有食材，成功后再写入配置，返回迁移的文件数
+#[tauri::command]
+pub async fn set_pantry_encryption(
+    enabled: bool,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<usize, String> {
+    let migrated = tokio::task::spawn_blocking(move || pantry_crypto::set_enabled(enabled))
+        .await
+        .map_err(|e| format!("迁移食材缓存失败: {}", e))?
+        .map_err(|e| format!("迁移食材缓存失败: {}", e))?;
 
-    if matches!(lines.first().copied(), Some("copy") | Some("cut")) {
-        lines.remove(0);
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        config.mcp_config.pantry_encryption = Some(enabled);
     }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
 
-    let mut out = Vec::new();
-    for line in lines {
-        if line.starts_with('#') {
-            continue;
-        }
-
-        if let Some(p) = parse_file_url_or_path(line) {
-            out.push(p);
-        }
-    }
-    out
+    Ok(migrated)
 }
 
-fn parse_file_url_or_path(s: &str) -> Option<PathBuf> {
-    let raw = s.trim();
+#[tauri::command]
+pub async fn get_pantry_janitor_config(state: State<'_, AppState>) -> Result<PantryJanitorConfig, String> {
+    let config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(PantryJanitorConfig::from_mcp_config(&config.mcp_config))
+}
 
-    if let Some(rest) = raw.strip_prefix("file://") {
-        let rest = rest.strip_prefix("localhost").unwrap_or(rest);
-        let mut path_str = rest.to_string();
-        if !path_str.starts_with('/') {
-            path_str.insert(0, '/');
-        }
-        let decoded = percent_decode_str(&path_str).decode_utf8_lossy();
-        let decoded = decoded.as_ref();
-        let decoded = if decoded.len() >= 4
-            && decoded.starts_with('/')
-            && decoded.as_bytes()[2] == b':'
-            && (decoded.as_bytes()[1] as char).is_ascii_alphabetic()
-            && (decoded.as_bytes()[3] == b'/' || decoded.as_bytes()[3] == b'\\')
-        {
-            &decoded[1..]
-        } else {
-            decoded
-        };
-        let p = PathBuf::from(decoded);
-        if p.exists() { return Some(p); }
-        return None;
+#[tauri::command]
+pub async fn save_pantry_janitor_config(
+    janitor_config: PantryJanitorConfig,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
+        janitor_config.apply_to(&mut config.mcp_config);
     }
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))
+}
 
-    let p = PathBuf::from(raw);
-    if p.exists() {
-        return Some(p);
+#[tauri::command]
+pub async fn read_clipboard_ingredients_cached() -> Result<Vec<CachedIngredient>, String> {
+    let items = clipboard::read_clipboard_ingredients()?;
+    let mut out: Vec<CachedIngredient> = Vec::new();
+    for item in items {
+        out.push(stash_ingredient(item.bytes, item.dish_type.as_str(), item.tag)?);
     }
-    None
+    Ok(out)
 }
 
-fn try_load_ingredient_file_as_clipboard_item(path: &PathBuf) -> Option<ClipboardIngredientBytes> {
-    let mime = guess_ingredient_mime_from_path(path)?;
-    let bytes = fs::read(path).ok()?;
-    if bytes.is_empty() {
-        return None;
-    }
-    Some(ClipboardIngredientBytes {
-        dish_type: mime.to_string(),
-        tag: None,
-        bytes,
-    })
+/// 复制到剪贴板的内容来源
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClipboardCopySource {
+    /// 当前弹窗中的文本（AI 消息、代码块等）
+    Text { text: String },
+    /// 当前弹窗中已缓存的食材
+    Spice { spice_id: String },
+    /// 历史记录中 AI 的消息原文
+    HistoryMessage { id: String },
+    /// 历史记录中保存的食材
+    HistoryIngredient { id: String, filename: String },
 }
 
-#[cfg(target_os = "windows")]
-fn try_read_windows_clipboard_ingredient_files() -> Option<Vec<ClipboardIngredientBytes>> {
-    let script = "[Console]::OutputEncoding=[System.Text.Encoding]::UTF8; Get-Clipboard -Format FileDropList -ErrorAction SilentlyContinue | ForEach-Object { $_.FullName }";
-    let output = Command::new("powershell")
-        .args(["-NoProfile", "-Sta", "-Command", script])
-        .output()
-        .ok()?;
-    if !output.status.success() {
-        return None;
-    }
-    let text = String::from_utf8(output.stdout).ok()?;
-    let mut out = Vec::new();
-    for line in text.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
-        let p = PathBuf::from(line);
-        if let Some(item) = try_load_ingredient_file_as_clipboard_item(&p) {
-            out.push(item);
-        }
-    }
-    if out.is_empty() { None } else { Some(out) }
-}
-
-#[cfg(target_os = "macos")]
-fn try_read_macos_clipboard_ingredient_files() -> Option<Vec<ClipboardIngredientBytes>> {
-    let output = Command::new("osascript")
-        .args([
-            "-e",
-            "set out to \"\"",
-            "-e",
-            "try",
-            "-e",
-            "set theFiles to the clipboard as alias list",
-            "-e",
-            "on error",
-            "-e",
-            "set theFiles to {the clipboard as alias}",
-            "-e",
-            "end try",
-            "-e",
-            "repeat with f in theFiles",
-            "-e",
-            "set out to out & (POSIX path of f) & linefeed",
-            "-e",
-            "end repeat",
-            "-e",
-            "return out",
-        ])
-        .output()
-        .ok()?;
-    if !output.status.success() {
-        return None;
-    }
-    let text = String::from_utf8(output.stdout).ok()?;
-    let mut out = Vec::new();
-    for line in text.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
-        let p = PathBuf::from(line);
-        if let Some(item) = try_load_ingredient_file_as_clipboard_item(&p) {
-            out.push(item);
+#[tauri::command]
+pub async fn copy_to_clipboard_cmd(source: ClipboardCopySource) -> Result<(), String> {
+    let (bytes, dish_type) = match source {
+        ClipboardCopySource::Text { text } => return clipboard::write_clipboard_text(&text),
+        ClipboardCopySource::HistoryMessage { id } => {
+            let entry = get_history_entry(id).map_err(|e| format!("获取历史详情失败: {}", e))?;
+            let message = entry.request.map(|r| r.message).unwrap_or_default();
+            if message.trim().is_empty() {
+                return Err("该记录没有消息内容".to_string());
+            }
+            return clipboard::write_clipboard_text(&message);
         }
-    }
-    if out.is_empty() { None } else { Some(out) }
-}
+        ClipboardCopySource::Spice { spice_id } => fetch_ingredient_bytes(&spice_id)
+            .map(|(bytes, label)| (bytes, label.dish_type))
+            .map_err(|e| format!("读取食材失败: {}", e))?,
+        ClipboardCopySource::HistoryIngredient { id, filename } => read_history_ingredient(&id, &filename)
+            .map_err(|e| format!("读取历史食材失败: {}", e))?,
+    };
 
-fn guess_ingredient_mime_from_path(path: &PathBuf) -> Option<&'static str> {
-    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
-    match ext.as_str() {
-        "png" => Some("image/png"),
-        "jpg" | "jpeg" => Some("image/jpeg"),
-        "webp" => Some("image/webp"),
-        "gif" => Some("image/gif"),
-        "bmp" => Some("image/bmp"),
-        "tif" | "tiff" => Some("image/tiff"),
-        _ => None,
+    if ingredient_kind::kind_of(&dish_type) != Some(IngredientKind::Image) {
+        return Err(format!("只能复制图片食材，当前类型: {}", dish_type));
     }
+    let png = ingredient_image::to_png(&bytes, &dish_type)?;
+    clipboard::write_clipboard_png(&png)
 }
 
-#[cfg(target_os = "linux")]
-fn try_read_linux_clipboard_ingredient() -> Option<Vec<ClipboardIngredientBytes>> {
-    let candidates: Vec<(&str, Vec<&str>, &str)> = vec![
-        ("wl-paste", vec!["--no-newline", "--type", "image/png"], "image/png"),
-        ("wl-paste", vec!["--no-newline", "--type", "image/jpeg"], "image/jpeg"),
-        ("wl-paste", vec!["--no-newline", "--type", "image/webp"], "image/webp"),
-        ("wl-paste", vec!["--no-newline", "--type", "image/bmp"], "image/bmp"),
-        ("wl-paste", vec!["--primary", "--no-newline", "--type", "image/png"], "image/png"),
-        ("wl-paste", vec!["--primary", "--no-newline", "--type", "image/jpeg"], "image/jpeg"),
-        ("wl-paste", vec!["--primary", "--no-newline", "--type", "image/webp"], "image/webp"),
-        ("wl-paste", vec!["--primary", "--no-newline", "--type", "image/bmp"], "image/bmp"),
-
-        ("wl-clip.paste", vec!["--no-newline", "--type", "image/png"], "image/png"),
-        ("wl-clip.paste", vec!["--no-newline", "--type", "image/jpeg"], "image/jpeg"),
-        ("wl-clip.paste", vec!["--no-newline", "--type", "image/webp"], "image/webp"),
-        ("wl-clip.paste", vec!["--no-newline", "--type", "image/bmp"], "image/bmp"),
-        ("wl-clip.paste", vec!["--primary", "--no-newline", "--type", "image/png"], "image/png"),
-        ("wl-clip.paste", vec!["--primary", "--no-newline", "--type", "image/jpeg"], "image/jpeg"),
-        ("wl-clip.paste", vec!["--primary", "--no-newline", "--type", "image/webp"], "image/webp"),
-        ("wl-clip.paste", vec!["--primary", "--no-newline", "--type", "image/bmp"], "image/bmp"),
-        ("xclip", vec!["-selection", "clipboard", "-t", "image/png", "-o"], "image/png"),
-        ("xclip", vec!["-selection", "clipboard", "-t", "image/jpeg", "-o"], "image/jpeg"),
-        ("xclip", vec!["-selection", "clipboard", "-t", "image/webp", "-o"], "image/webp"),
-        ("xclip", vec!["-selection", "clipboard", "-t", "image/bmp", "-o"], "image/bmp"),
-        ("xclip", vec!["-selection", "primary", "-t", "image/png", "-o"], "image/png"),
-        ("xclip", vec!["-selection", "primary", "-t", "image/jpeg", "-o"], "image/jpeg"),
-        ("xclip", vec!["-selection", "primary", "-t", "image/webp", "-o"], "image/webp"),
-        ("xclip", vec!["-selection", "primary", "-t", "image/bmp", "-o"], "image/bmp"),
-    ];
-
-    for (cmd, args, mime) in candidates {
-        let output = match run_linux_command_output(cmd, &args) {
-            Some(o) => o,
-            None => continue,
-        };
-        if output.status.success() && !output.stdout.is_empty() {
-            return Some(vec![ClipboardIngredientBytes {
-                dish_type: mime.to_string(),
-                tag: None,
-                bytes: output.stdout,
-            }]);
-        }
-    }
-    None
+fn guess_ingredient_mime_from_path(path: &PathBuf) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?;
+    ingredient_kind::dish_type_from_extension(ext)
 }
 
 #[tauri::command]
@@ -1219,6 +1281,7 @@ pub async fn delete_custom_prompt(
         if config.custom_prompt_config.prompts.len() == initial_len {
             return Err("未找到指定的prompt".to_string());
         }
+        crate::config::prune_prompt_usage(&mut config.custom_prompt_config);
     }
 
     // 保存配置到文件
@@ -1340,6 +1403,143 @@ pub async fn update_conditional_prompt_state(
 
 
 
+/// 把选中的 prompt 导出为分享包，返回导出的数量
+#[tauri::command]
+pub async fn export_custom_prompt_pack(
+    prompt_ids: Vec<String>,
+    path: String,
+    name: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<usize, String> {
+    if !validation::is_valid_file_path(path.trim()) {
+        return Err("请填写导出文件路径".to_string());
+    }
+    let pack = {
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        crate::config::build_prompt_pack(&config.custom_prompt_config, &prompt_ids, name.as_deref().unwrap_or_default())
+            .map_err(|e| e.to_string())?
+    };
+    crate::config::write_prompt_pack(&pack, std::path::Path::new(path.trim()))
+        .map_err(|e| format!("导出分享包失败: {}", e))?;
+    log::info!("已导出 {} 个 prompt 到 {}", pack.prompts.len(), path.trim());
+    Ok(pack.prompts.len())
+}
+
+/// 读取分享包并标出与本机同名的 prompt，供导入前选择处理方式
+#[tauri::command]
+pub async fn preview_custom_prompt_pack(
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<PromptPackPreview, String> {
+    let pack = crate::config::read_prompt_pack(std::path::Path::new(path.trim()))
+        .map_err(|e| format!("读取分享包失败: {}", e))?;
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(crate::config::preview_prompt_pack(&config.custom_prompt_config, &pack))
+}
+
+/// 导入分享包，resolutions 按分享包中的 prompt id 指定同名时的处理方式
+#[tauri::command]
+pub async fn import_custom_prompt_pack(
+    path: String,
+    on_conflict: PromptConflictAction,
+    resolutions: Option<std::collections::HashMap<String, PromptConflictAction>>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<PromptPackImportResult, String> {
+    let pack = crate::config::read_prompt_pack(std::path::Path::new(path.trim()))
+        .map_err(|e| format!("读取分享包失败: {}", e))?;
+    let result = {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        crate::config::import_prompt_pack(
+            &mut config.custom_prompt_config,
+            pack,
+            on_conflict,
+            &resolutions.unwrap_or_default(),
+        )
+        .map_err(|e| e.to_string())?
+    };
+
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    log::info!("已导入 prompt 分享包: {:?}", result);
+    let _ = app.emit("custom-prompt-updated", ());
+    Ok(result)
+}
+
+/// 记录一次自定义prompt的使用（插入到输入框时调用）
+#[tauri::command]
+pub async fn record_custom_prompt_usage(
+    prompt_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        if !crate::config::record_prompt_usage(&mut config.custom_prompt_config, &prompt_id, chrono::Utc::now()) {
+            return Err(format!("未找到ID为 {} 的prompt", prompt_id));
+        }
+    }
+
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    Ok(())
+}
+
+/// 获取自定义prompt使用统计，unused_days 默认为 90 天
+#[tauri::command]
+pub async fn get_custom_prompt_usage_report(
+    unused_days: Option<u32>,
+    state: State<'_, AppState>,
+) -> Result<PromptUsageReport, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("获取配置失败: {}", e))?;
+    Ok(crate::config::prompt_usage_report(
+        &config.custom_prompt_config,
+        unused_days.unwrap_or(crate::config::DEFAULT_UNUSED_DAYS),
+        chrono::Utc::now(),
+    ))
+}
+
+/// 按使用频率重新排序自定义prompt
+#[tauri::command]
+pub async fn sort_custom_prompts_by_usage(
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    {
+        let mut config = state
+            .config
+            .lock()
+            .map_err(|e| format!("获取配置失败: {}", e))?;
+        crate::config::sort_prompts_by_usage(&mut config.custom_prompt_config);
+    }
+
+    save_config(&state, &app)
+        .await
+        .map_err(|e| format!("保存配置失败: {}", e))?;
+
+    let _ = app.emit("custom-prompt-updated", ());
+    Ok(())
+}
+
 /// 获取配置文件的真实路径
 #[tauri::command]
 pub async fn get_config_file_path(app: AppHandle) -> Result<String, String> {
@@ -1454,6 +1654,12 @@ pub async fn update_shortcut_binding(
         .await
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
+    // 重新注册全局快捷键
+    let errors = crate::ui::global_shortcut::register_global_shortcuts(&app);
+    if !errors.is_empty() {
+        return Err(format!("快捷键已保存，但全局快捷键注册失败: {}", errors.join("; ")));
+    }
+
     Ok(())
 }
 
@@ -1478,5 +1684,8 @@ pub async fn reset_shortcuts_to_default(
         .await
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
+    // 默认的全局快捷键均未启用，重新注册即注销全部
+    crate::ui::global_shortcut::register_global_shortcuts(&app);
+
     Ok(())
 }
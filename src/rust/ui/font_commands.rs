@@ -1,5 +1,5 @@
 use crate::config::{save_config, AppState, FontConfig};
-use crate::constants::font;
+use crate::constants::{font, validation};
 use tauri::{AppHandle, State};
 
 /// 字体配置信息
@@ -47,7 +47,13 @@ pub async fn set_font_family(
     font_family: String,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<(), String> {
+) -> Result<validation::ValidationReport, String> {
+    let mut report = validation::ValidationReport::new();
+    if !validation::is_valid_font_family(&font_family) {
+        report.reject("font_family", &font_family, "不支持的字体系列");
+        return Ok(report);
+    }
+
     {
         let mut config = state
             .config
@@ -60,7 +66,7 @@ pub async fn set_font_family(
         .await
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
-    Ok(())
+    Ok(report)
 }
 
 #[tauri::command]
@@ -68,7 +74,13 @@ pub async fn set_font_size(
     font_size: String,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<(), String> {
+) -> Result<validation::ValidationReport, String> {
+    let mut report = validation::ValidationReport::new();
+    if !validation::is_valid_font_size(&font_size) {
+        report.reject("font_size", &font_size, "不支持的字体大小");
+        return Ok(report);
+    }
+
     {
         let mut config = state
             .config
@@ -81,7 +93,7 @@ pub async fn set_font_size(
         .await
         .map_err(|e| format!("保存配置失败: {}", e))?;
 
-    Ok(())
+    Ok(report)
 }
 
 
@@ -0,0 +1,152 @@
+// 全局快捷键
+// 作用域为 global 且已启用的快捷键注册为系统级热键，应用不在前台时也能触发。
+// 主程序和 MCP 弹窗是不同的进程，同一组合键只能被其中一个进程注册，已被占用时记录警告并跳过；
+// “全局继续”只对弹窗有意义，只在弹窗进程中注册
+
+use anyhow::{anyhow, bail, Result};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+
+use crate::config::{AppState, ShortcutKey};
+use crate::ui::background::{is_popup_mode, show_main_window};
+
+/// 全局快捷键的作用域
+pub const SCOPE_GLOBAL: &str = "global";
+
+/// 唤起窗口到前台
+pub const ACTION_SHOW_WINDOW: &str = "show_window";
+
+/// 唤起窗口并读取剪贴板食材
+pub const ACTION_PASTE_CLIPBOARD: &str = "paste_clipboard";
+
+/// 在弹窗中发送继续回复
+pub const ACTION_CONTINUE: &str = "global_continue";
+
+/// 已注册的快捷键 id -> 动作
+static REGISTERED_ACTIONS: Lazy<Mutex<HashMap<u32, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 转换为全局快捷键插件识别的组合键，如 "Ctrl+Alt+Space"
+pub fn to_accelerator(key: &ShortcutKey) -> Result<String> {
+    let main = match key.key.trim() {
+        "" => bail!("未设置按键"),
+        "Del" => "Delete",
+        "Ins" => "Insert",
+        "PgUp" => "PageUp",
+        "PgDn" => "PageDown",
+        other => other,
+    };
+    if !(key.ctrl || key.alt || key.shift || key.meta) {
+        bail!("全局快捷键至少需要一个修饰键");
+    }
+
+    let mut parts = Vec::new();
+    if key.ctrl {
+        parts.push("Ctrl");
+    }
+    if key.alt {
+        parts.push("Alt");
+    }
+    if key.shift {
+        parts.push("Shift");
+    }
+    if key.meta {
+        parts.push("Super");
+    }
+    parts.push(main);
+    Ok(parts.join("+"))
+}
+
+/// 按配置重新注册全局快捷键，返回注册失败的说明
+pub fn register_global_shortcuts(app: &AppHandle) -> Vec<String> {
+    let bindings: Vec<_> = match app.state::<AppState>().config.lock() {
+        Ok(config) => config
+            .shortcut_config
+            .shortcuts
+            .values()
+            .filter(|binding| binding.scope == SCOPE_GLOBAL && binding.enabled)
+            .cloned()
+            .collect(),
+        Err(e) => return vec![format!("获取配置失败: {}", e)],
+    };
+    let popup_mode = is_popup_mode();
+
+    let manager = app.global_shortcut();
+    if let Err(e) = manager.unregister_all() {
+        log::warn!("注销全局快捷键失败: {}", e);
+    }
+    let mut actions = REGISTERED_ACTIONS.lock().unwrap_or_else(|e| e.into_inner());
+    actions.clear();
+
+    let mut errors = Vec::new();
+    for binding in bindings {
+        if binding.action == ACTION_CONTINUE && !popup_mode {
+            continue;
+        }
+        let registered = to_accelerator(&binding.key_combination).and_then(|accelerator| {
+            let shortcut: Shortcut = accelerator
+                .parse()
+                .map_err(|e| anyhow!("无法识别的组合键 {}: {}", accelerator, e))?;
+            manager
+                .register(shortcut)
+                .map_err(|e| anyhow!("组合键 {} 可能已被占用: {}", accelerator, e))?;
+            Ok(shortcut)
+        });
+        match registered {
+            Ok(shortcut) => {
+                actions.insert(shortcut.id(), binding.action.clone());
+            }
+            Err(e) => {
+                log::warn!("注册全局快捷键 {} 失败: {}", binding.name, e);
+                errors.push(format!("{}: {}", binding.name, e));
+            }
+        }
+    }
+    if !actions.is_empty() {
+        log::info!("已注册 {} 个全局快捷键", actions.len());
+    }
+    errors
+}
+
+/// 全局快捷键插件的回调
+pub fn handle_global_shortcut(app: &AppHandle, shortcut: &Shortcut, event: ShortcutEvent) {
+    if event.state() != ShortcutState::Pressed {
+        return;
+    }
+    let action = REGISTERED_ACTIONS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&shortcut.id())
+        .cloned();
+    let Some(action) = action else {
+        return;
+    };
+
+    if action != ACTION_CONTINUE {
+        show_main_window(app);
+    }
+    if action != ACTION_SHOW_WINDOW {
+        if let Err(e) = app.emit("global-shortcut", &action) {
+            log::warn!("发送全局快捷键事件失败: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: &str, ctrl: bool, alt: bool, shift: bool, meta: bool) -> ShortcutKey {
+        ShortcutKey { key: key.to_string(), ctrl, alt, shift, meta }
+    }
+
+    #[test]
+    fn test_to_accelerator() {
+        assert_eq!(to_accelerator(&key("Space", true, true, false, false)).unwrap(), "Ctrl+Alt+Space");
+        assert_eq!(to_accelerator(&key("PgUp", false, false, true, true)).unwrap(), "Shift+Super+PageUp");
+        assert!(to_accelerator(&key("V", false, false, false, false)).is_err());
+        assert!(to_accelerator(&key("", true, false, false, false)).is_err());
+    }
+}
@@ -0,0 +1,444 @@
+// 图片食材预处理
+// 大截图经常超过单个食材的大小上限。保存到 pantry 之前按配置处理图片：
+// - ingredient_image_max_dimension：最长边超过该值时等比缩小（0 表示不缩放）
+// - ingredient_image_format：重新编码的目标格式 "keep"（默认，保持原格式）| "png" | "jpeg" | "webp"（WebP 为无损编码）
+// - ingredient_image_quality：JPEG 质量，默认 85
+// 处理后仍超过大小上限时改用 JPEG 并逐步缩小，直到满足上限或达到尝试次数。
+// SVG / HEIC / AVIF 先转为 PNG 再进入上述流程，分别由 svg / heic / avif 功能提供，未启用时提示用户自行转换。
+// 动图（GIF / 动态 WebP）按 ingredient_animation 处理："keep"（默认，超过大小上限时只取首帧）| "first_frame" | "contact_sheet"（均匀抽取若干帧拼成一张图）
+
+use image::codecs::gif::GifDecoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::{WebPDecoder, WebPEncoder};
+use image::imageops::FilterType;
+use image::{AnimationDecoder, DynamicImage, ExtendedColorType, Frames, ImageEncoder, ImageFormat, ImageReader, RgbaImage};
+use std::io::Cursor;
+
+use crate::config::McpConfig;
+
+/// 默认 JPEG 质量
+const DEFAULT_QUALITY: u8 = 85;
+
+/// 超过大小上限时每次缩小的比例
+const SHRINK_FACTOR: f64 = 0.75;
+
+/// 超过大小上限时的最大尝试次数
+const MAX_SHRINK_ATTEMPTS: usize = 6;
+
+/// 拼图默认帧数与允许范围
+const DEFAULT_ANIMATION_FRAMES: u32 = 4;
+const MAX_ANIMATION_FRAMES: u32 = 16;
+
+/// 拼图中帧之间的间隔（像素）
+const CONTACT_SHEET_GAP: u32 = 4;
+
+/// SVG 栅格化的最长边上限（像素）
+#[cfg(feature = "svg")]
+const SVG_MAX_DIMENSION: u32 = 4096;
+
+/// 图片预处理配置（对应 McpConfig 中的 ingredient_image_* 字段）
+#[derive(Debug, Clone)]
+pub struct ImagePipelineConfig {
+    pub max_dimension: u32,
+    pub format: String,
+    pub quality: u8,
+    pub animation: String,
+    pub animation_frames: u32,
+}
+
+impl Default for ImagePipelineConfig {
+    fn default() -> Self {
+        Self {
+            max_dimension: 0,
+            format: "keep".to_string(),
+            quality: DEFAULT_QUALITY,
+            animation: "keep".to_string(),
+            animation_frames: DEFAULT_ANIMATION_FRAMES,
+        }
+    }
+}
+
+impl ImagePipelineConfig {
+    pub fn from_mcp_config(config: &McpConfig) -> Self {
+        Self {
+            max_dimension: config.ingredient_image_max_dimension.unwrap_or(0),
+            format: config
+                .ingredient_image_format
+                .clone()
+                .unwrap_or_else(|| "keep".to_string()),
+            quality: config.ingredient_image_quality.unwrap_or(DEFAULT_QUALITY).clamp(1, 100),
+            animation: config
+                .ingredient_animation
+                .clone()
+                .unwrap_or_else(|| "keep".to_string()),
+            animation_frames: config
+                .ingredient_animation_frames
+                .unwrap_or(DEFAULT_ANIMATION_FRAMES)
+                .clamp(2, MAX_ANIMATION_FRAMES),
+        }
+    }
+
+    pub fn load() -> Self {
+        crate::config::load_standalone_config()
+            .map(|c| Self::from_mcp_config(&c.mcp_config))
+            .unwrap_or_default()
+    }
+}
+
+/// 可以解码并重新编码的图片类型
+fn image_format_of(dish_type: &str) -> Option<ImageFormat> {
+    match dish_type {
+        "image/png" => Some(ImageFormat::Png),
+        "image/jpeg" => Some(ImageFormat::Jpeg),
+        "image/webp" => Some(ImageFormat::WebP),
+        "image/bmp" => Some(ImageFormat::Bmp),
+        _ => None,
+    }
+}
+
+/// 重新编码的目标类型；BMP 不直接提交，保持原格式时转为 PNG
+fn target_dish_type(format: &str, dish_type: &str) -> &'static str {
+    match format.trim().to_ascii_lowercase().as_str() {
+        "png" => "image/png",
+        "jpeg" | "jpg" => "image/jpeg",
+        "webp" => "image/webp",
+        _ => match dish_type {
+            "image/jpeg" => "image/jpeg",
+            "image/webp" => "image/webp",
+            _ => "image/png",
+        },
+    }
+}
+
+/// 等比缩放到最长边不超过 max_dimension 后的尺寸
+fn fit_within(width: u32, height: u32, max_dimension: u32) -> (u32, u32) {
+    let longest = width.max(height);
+    if max_dimension == 0 || longest <= max_dimension {
+        return (width, height);
+    }
+    let scale = max_dimension as f64 / longest as f64;
+    (
+        ((width as f64 * scale).round() as u32).max(1),
+        ((height as f64 * scale).round() as u32).max(1),
+    )
+}
+
+/// 需要先转为 PNG 的类型
+fn needs_rasterize(dish_type: &str) -> bool {
+    matches!(dish_type, "image/svg+xml" | "image/heic" | "image/heif" | "image/avif")
+}
+
+fn encode_png(img: &DynamicImage) -> Result<Vec<u8>, String> {
+    encode(img, "image/png", DEFAULT_QUALITY)
+}
+
+/// 将 SVG / HEIC / AVIF 转为 PNG
+fn rasterize(bytes: &[u8], dish_type: &str) -> Result<Vec<u8>, String> {
+    match dish_type {
+        "image/svg+xml" => rasterize_svg(bytes),
+        "image/heic" | "image/heif" => decode_heic(bytes).and_then(|img| encode_png(&img)),
+        _ => decode_avif(bytes).and_then(|img| encode_png(&img)),
+    }
+}
+
+#[cfg(feature = "svg")]
+fn rasterize_svg(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    use resvg::{tiny_skia, usvg};
+
+    let mut options = usvg::Options::default();
+    options.fontdb_mut().load_system_fonts();
+    let tree = usvg::Tree::from_data(bytes, &options).map_err(|e| format!("解析 SVG 失败: {}", e))?;
+
+    let size = tree.size().to_int_size();
+    let (width, height) = fit_within(size.width(), size.height(), SVG_MAX_DIMENSION);
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or_else(|| "SVG 尺寸无效".to_string())?;
+    let transform = tiny_skia::Transform::from_scale(
+        width as f32 / size.width() as f32,
+        height as f32 / size.height() as f32,
+    );
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    pixmap.encode_png().map_err(|e| format!("转换 PNG 失败: {}", e))
+}
+
+#[cfg(not(feature = "svg"))]
+fn rasterize_svg(_bytes: &[u8]) -> Result<Vec<u8>, String> {
+    Err("当前版本未启用 SVG 支持，请先转换为 PNG 后再粘贴".to_string())
+}
+
+#[cfg(feature = "heic")]
+fn decode_heic(bytes: &[u8]) -> Result<DynamicImage, String> {
+    use libheif_rs::{ColorSpace, HeifContext, LibHeif, RgbChroma};
+
+    let ctx = HeifContext::read_from_bytes(bytes).map_err(|e| format!("读取 HEIC 失败: {}", e))?;
+    let handle = ctx.primary_image_handle().map_err(|e| format!("读取 HEIC 失败: {}", e))?;
+    let decoded = LibHeif::new()
+        .decode(&handle, ColorSpace::Rgb(RgbChroma::Rgba), None)
+        .map_err(|e| format!("解码 HEIC 失败: {}", e))?;
+    let planes = decoded.planes();
+    let plane = planes.interleaved.ok_or_else(|| "解码 HEIC 失败: 缺少像素数据".to_string())?;
+
+    // 每行末尾可能有对齐填充，按行拷贝
+    let row_bytes = plane.width as usize * 4;
+    let mut raw = Vec::with_capacity(row_bytes * plane.height as usize);
+    for row in plane.data.chunks(plane.stride).take(plane.height as usize) {
+        raw.extend_from_slice(&row[..row_bytes]);
+    }
+    image::RgbaImage::from_raw(plane.width, plane.height, raw)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| "解码 HEIC 失败: 像素数据长度不正确".to_string())
+}
+
+#[cfg(not(feature = "heic"))]
+fn decode_heic(_bytes: &[u8]) -> Result<DynamicImage, String> {
+    Err("当前版本未启用 HEIC 支持，请先转换为 PNG/JPG 后再粘贴".to_string())
+}
+
+#[cfg(feature = "avif")]
+fn decode_avif(bytes: &[u8]) -> Result<DynamicImage, String> {
+    image::load_from_memory_with_format(bytes, ImageFormat::Avif).map_err(|e| format!("解码 AVIF 失败: {}", e))
+}
+
+#[cfg(not(feature = "avif"))]
+fn decode_avif(_bytes: &[u8]) -> Result<DynamicImage, String> {
+    Err("当前版本未启用 AVIF 支持，请先转换为 PNG/JPG 后再粘贴".to_string())
+}
+
+/// 动图的帧迭代器；不是动图时返回 None
+fn animation_frames<'a>(bytes: &'a [u8], dish_type: &str) -> Option<Frames<'a>> {
+    match dish_type {
+        "image/gif" => {
+            let frames = GifDecoder::new(Cursor::new(bytes)).ok()?.into_frames();
+            // 只有一帧的 GIF 按静态图处理
+            let count = GifDecoder::new(Cursor::new(bytes)).ok()?.into_frames().take(2).count();
+            (count > 1).then_some(frames)
+        }
+        "image/webp" => {
+            let decoder = WebPDecoder::new(Cursor::new(bytes)).ok()?;
+            decoder.has_animation().then(|| decoder.into_frames())
+        }
+        _ => None,
+    }
+}
+
+/// 从 total 帧中均匀抽取 count 帧的序号（包含首帧）
+fn sample_frame_indices(total: usize, count: usize) -> Vec<usize> {
+    if total == 0 || count == 0 {
+        return Vec::new();
+    }
+    if count >= total {
+        return (0..total).collect();
+    }
+    (0..count).map(|i| i * total / count).collect()
+}
+
+/// 拼图的列数与行数（尽量接近正方形）
+fn contact_sheet_grid(count: u32) -> (u32, u32) {
+    let columns = (count as f64).sqrt().ceil().max(1.0) as u32;
+    (columns, count.div_ceil(columns))
+}
+
+fn first_frame(bytes: &[u8], dish_type: &str) -> Result<DynamicImage, String> {
+    let frame = animation_frames(bytes, dish_type)
+        .and_then(|mut frames| frames.next())
+        .ok_or_else(|| "读取动图失败".to_string())?
+        .map_err(|e| format!("读取动图失败: {}", e))?;
+    Ok(DynamicImage::ImageRgba8(frame.into_buffer()))
+}
+
+/// 均匀抽取若干帧，按网格拼成一张图
+fn contact_sheet(bytes: &[u8], dish_type: &str, max_frames: u32) -> Result<DynamicImage, String> {
+    // 先数总帧数，再解码一遍取出需要的帧，避免把所有帧同时放在内存里
+    let total = animation_frames(bytes, dish_type)
+        .ok_or_else(|| "读取动图失败".to_string())?
+        .count();
+    let indices = sample_frame_indices(total, max_frames as usize);
+    let mut picked: Vec<RgbaImage> = Vec::with_capacity(indices.len());
+    for (index, frame) in animation_frames(bytes, dish_type)
+        .ok_or_else(|| "读取动图失败".to_string())?
+        .enumerate()
+    {
+        if picked.len() == indices.len() {
+            break;
+        }
+        if indices.contains(&index) {
+            picked.push(frame.map_err(|e| format!("读取动图失败: {}", e))?.into_buffer());
+        }
+    }
+    let Some(first) = picked.first() else {
+        return Err("读取动图失败: 没有帧".to_string());
+    };
+
+    let (cell_width, cell_height) = first.dimensions();
+    let (columns, rows) = contact_sheet_grid(picked.len() as u32);
+    let mut sheet = RgbaImage::new(
+        columns * cell_width + (columns - 1) * CONTACT_SHEET_GAP,
+        rows * cell_height + (rows - 1) * CONTACT_SHEET_GAP,
+    );
+    for (i, frame) in picked.iter().enumerate() {
+        let (column, row) = (i as u32 % columns, i as u32 / columns);
+        image::imageops::replace(
+            &mut sheet,
+            frame,
+            (column * (cell_width + CONTACT_SHEET_GAP)) as i64,
+            (row * (cell_height + CONTACT_SHEET_GAP)) as i64,
+        );
+    }
+    Ok(DynamicImage::ImageRgba8(sheet))
+}
+
+/// 按配置把动图转为静态图；保持原样时返回 None
+fn flatten_animation(
+    bytes: &[u8],
+    dish_type: &str,
+    max_bytes: u64,
+    config: &ImagePipelineConfig,
+) -> Result<Option<DynamicImage>, String> {
+    match config.animation.trim().to_ascii_lowercase().as_str() {
+        "contact_sheet" => contact_sheet(bytes, dish_type, config.animation_frames).map(Some),
+        "first_frame" => first_frame(bytes, dish_type).map(Some),
+        _ if bytes.len() as u64 > max_bytes => {
+            log::info!("动图超过大小上限（{} 字节），只保留首帧", bytes.len());
+            first_frame(bytes, dish_type).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn encode(img: &DynamicImage, dish_type: &str, quality: u8) -> Result<Vec<u8>, String> {
+    let mut out: Vec<u8> = Vec::new();
+    let (width, height) = (img.width(), img.height());
+    let result = match dish_type {
+        "image/jpeg" => {
+            let rgb = img.to_rgb8();
+            JpegEncoder::new_with_quality(&mut out, quality).write_image(rgb.as_raw(), width, height, ExtendedColorType::Rgb8)
+        }
+        "image/webp" => {
+            let rgba = img.to_rgba8();
+            WebPEncoder::new_lossless(&mut out).write_image(rgba.as_raw(), width, height, ExtendedColorType::Rgba8)
+        }
+        _ => {
+            let rgba = img.to_rgba8();
+            PngEncoder::new(&mut out).write_image(rgba.as_raw(), width, height, ExtendedColorType::Rgba8)
+        }
+    };
+    result.map_err(|e| format!("编码图片失败: {}", e))?;
+    Ok(out)
+}
+
+/// 转为 PNG（复制图片到剪贴板时使用）
+pub fn to_png(bytes: &[u8], dish_type: &str) -> Result<Vec<u8>, String> {
+    if dish_type == "image/png" {
+        return Ok(bytes.to_vec());
+    }
+    if needs_rasterize(dish_type) {
+        return rasterize(bytes, dish_type);
+    }
+    let format = image_format_of(dish_type).ok_or_else(|| format!("不支持复制该类型的图片: {}", dish_type))?;
+    let img = image::load_from_memory_with_format(bytes, format).map_err(|e| format!("读取图片失败: {}", e))?;
+    encode_png(&img)
+}
+
+/// 按配置缩放、转码图片；不需要处理时原样返回。max_bytes 为该类食材的大小上限
+pub fn process_image(
+    bytes: &[u8],
+    dish_type: &str,
+    max_bytes: u64,
+    config: &ImagePipelineConfig,
+) -> Result<(Vec<u8>, String), String> {
+    if needs_rasterize(dish_type) {
+        let png = rasterize(bytes, dish_type)?;
+        return process_image(&png, "image/png", max_bytes, config);
+    }
+    if animation_frames(bytes, dish_type).is_some() {
+        return match flatten_animation(bytes, dish_type, max_bytes, config)? {
+            Some(still) => process_image(&encode_png(&still)?, "image/png", max_bytes, config),
+            None => Ok((bytes.to_vec(), dish_type.to_string())),
+        };
+    }
+    let Some(format) = image_format_of(dish_type) else {
+        return Ok((bytes.to_vec(), dish_type.to_string()));
+    };
+
+    let (width, height) = ImageReader::with_format(Cursor::new(bytes), format)
+        .into_dimensions()
+        .map_err(|e| format!("读取图片失败: {}", e))?;
+    let target = target_dish_type(&config.format, dish_type);
+    let (fit_width, fit_height) = fit_within(width, height, config.max_dimension);
+    let needs_processing = target != dish_type
+        || (fit_width, fit_height) != (width, height)
+        || bytes.len() as u64 > max_bytes;
+    if !needs_processing {
+        return Ok((bytes.to_vec(), dish_type.to_string()));
+    }
+
+    let mut img = image::load_from_memory_with_format(bytes, format)
+        .map_err(|e| format!("读取图片失败: {}", e))?;
+    if (fit_width, fit_height) != (width, height) {
+        img = img.resize(fit_width, fit_height, FilterType::Lanczos3);
+    }
+    let mut encoded = encode(&img, target, config.quality)?;
+    let mut target = target;
+
+    // 仍然超过上限：改用 JPEG 并逐步缩小
+    let mut attempts = 0;
+    while encoded.len() as u64 > max_bytes && attempts < MAX_SHRINK_ATTEMPTS {
+        attempts += 1;
+        if target == "image/jpeg" {
+            let (w, h) = (
+                ((img.width() as f64 * SHRINK_FACTOR) as u32).max(1),
+                ((img.height() as f64 * SHRINK_FACTOR) as u32).max(1),
+            );
+            img = img.resize(w, h, FilterType::Lanczos3);
+        }
+        target = "image/jpeg";
+        encoded = encode(&img, target, config.quality)?;
+    }
+    if attempts > 0 {
+        log::info!(
+            "图片食材超过大小上限，已压缩为 {}x{} JPEG（{} 字节）",
+            img.width(),
+            img.height(),
+            encoded.len()
+        );
+    }
+
+    Ok((encoded, target.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fit_within() {
+        assert_eq!(fit_within(4000, 3000, 0), (4000, 3000));
+        assert_eq!(fit_within(1000, 800, 2048), (1000, 800));
+        assert_eq!(fit_within(4000, 3000, 2000), (2000, 1500));
+        assert_eq!(fit_within(1000, 5000, 1000), (200, 1000));
+    }
+
+    #[test]
+    fn test_target_dish_type() {
+        assert_eq!(target_dish_type("keep", "image/jpeg"), "image/jpeg");
+        assert_eq!(target_dish_type("keep", "image/bmp"), "image/png");
+        assert_eq!(target_dish_type("JPG", "image/png"), "image/jpeg");
+        assert_eq!(target_dish_type("webp", "image/png"), "image/webp");
+    }
+
+    #[test]
+    fn test_sample_frame_indices() {
+        assert_eq!(sample_frame_indices(3, 4), vec![0, 1, 2]);
+        assert_eq!(sample_frame_indices(100, 4), vec![0, 25, 50, 75]);
+        assert_eq!(sample_frame_indices(10, 3), vec![0, 3, 6]);
+        assert!(sample_frame_indices(0, 4).is_empty());
+    }
+
+    #[test]
+    fn test_contact_sheet_grid() {
+        assert_eq!(contact_sheet_grid(1), (1, 1));
+        assert_eq!(contact_sheet_grid(4), (2, 2));
+        assert_eq!(contact_sheet_grid(5), (3, 2));
+        assert_eq!(contact_sheet_grid(16), (4, 4));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod commands;
+pub mod clipboard;
+pub mod ingredient_image;
 pub mod window;
 pub mod audio;
 pub mod audio_assets;
@@ -6,6 +8,10 @@ pub mod font_commands;
 pub mod updater;
 pub mod exit;
 pub mod window_events;
+pub mod global_shortcut;
+pub mod background;
+pub mod onboarding;
+pub mod prompt_template;
 pub mod exit_handler;
 
 pub use commands::*;
@@ -16,3 +22,6 @@ pub use updater::*;
 pub use exit::*;
 pub use window_events::*;
 pub use exit_handler::*;
+pub use background::*;
+pub use onboarding::*;
+pub use prompt_template::*;
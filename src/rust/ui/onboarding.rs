@@ -0,0 +1,339 @@
+// 首次使用的配置向导
+// 检测本机已安装的 MCP 客户端（Claude Desktop、Cursor、Windsurf），为其生成 mcpServers 配置，
+// 可直接写入客户端的配置文件：写入前把原文件复制为同目录下带时间戳的备份，只增改 devkit 一项，其他服务原样保留。
+// 连通性检查会启动 devkit-mcp 并完成一次 MCP initialize 握手
+
+use anyhow::{anyhow, bail, Result};
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+
+use crate::config::concurrent::write_atomic;
+
+/// 写入客户端配置时使用的服务名
+pub const MCP_SERVER_NAME: &str = "devkit";
+
+/// 连通性检查的超时时间
+const VERIFY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 支持自动配置的 MCP 客户端
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum McpClient {
+    ClaudeDesktop,
+    Cursor,
+    Windsurf,
+}
+
+impl McpClient {
+    pub const ALL: [McpClient; 3] = [McpClient::ClaudeDesktop, McpClient::Cursor, McpClient::Windsurf];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            McpClient::ClaudeDesktop => "claude_desktop",
+            McpClient::Cursor => "cursor",
+            McpClient::Windsurf => "windsurf",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            McpClient::ClaudeDesktop => "Claude Desktop",
+            McpClient::Cursor => "Cursor",
+            McpClient::Windsurf => "Windsurf",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|client| client.id() == id)
+            .ok_or_else(|| anyhow!("不支持的 MCP 客户端: {}", id))
+    }
+
+    /// 客户端的 MCP 配置文件路径
+    pub fn config_path(self) -> Option<PathBuf> {
+        match self {
+            // macOS: ~/Library/Application Support，Windows: %APPDATA%，Linux: ~/.config
+            McpClient::ClaudeDesktop => dirs::config_dir().map(|dir| dir.join("Claude").join("claude_desktop_config.json")),
+            McpClient::Cursor => dirs::home_dir().map(|dir| dir.join(".cursor").join("mcp.json")),
+            McpClient::Windsurf => dirs::home_dir().map(|dir| dir.join(".codeium").join("windsurf").join("mcp_config.json")),
+        }
+    }
+}
+
+/// 客户端检测结果
+#[derive(Debug, Clone, Serialize)]
+pub struct McpClientStatus {
+    pub id: String,
+    pub name: String,
+    pub config_path: Option<String>,
+    /// 配置文件或其所在目录存在，视为已安装
+    pub installed: bool,
+    pub config_exists: bool,
+    /// 配置中已有 devkit 服务
+    pub configured: bool,
+    /// 配置文件存在但无法解析时的说明
+    pub error: Option<String>,
+}
+
+/// 写入客户端配置的结果
+#[derive(Debug, Clone, Serialize)]
+pub struct McpClientInstallResult {
+    pub config_path: String,
+    /// 写入前的配置备份（之前没有配置文件时为空）
+    pub backup_path: Option<String>,
+}
+
+/// 连通性检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct McpVerifyResult {
+    pub success: bool,
+    pub command: String,
+    pub server_name: Option<String>,
+    pub server_version: Option<String>,
+    pub message: String,
+}
+
+/// 查找 devkit-mcp：优先使用与本程序同目录的可执行文件，找不到时依赖 PATH
+pub fn mcp_server_command() -> String {
+    let file_name = format!("devkit-mcp{}", std::env::consts::EXE_SUFFIX);
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.join(&file_name)))
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .unwrap_or_else(|| "devkit-mcp".to_string())
+}
+
+/// 生成 mcpServers 中 devkit 一项
+pub fn server_entry(command: &str) -> Value {
+    json!({ "command": command })
+}
+
+/// 把 devkit 服务合并进客户端配置，保留其他字段和服务
+///
+/// 已有 devkit 一项时只更新 entry 中的字段（如 command），用户自定义的 args、env 等保持不变
+pub fn merge_server_entry(existing: Option<Value>, entry: Value) -> Result<Value> {
+    let mut root = match existing {
+        None | Some(Value::Null) => Map::new(),
+        Some(Value::Object(map)) => map,
+        Some(_) => bail!("配置文件的顶层不是 JSON 对象"),
+    };
+    let servers = root
+        .entry("mcpServers")
+        .or_insert_with(|| Value::Object(Map::new()));
+    let Value::Object(servers) = servers else {
+        bail!("配置文件中的 mcpServers 不是 JSON 对象");
+    };
+    match (servers.get_mut(MCP_SERVER_NAME), entry) {
+        (Some(Value::Object(current)), Value::Object(fields)) => current.extend(fields),
+        (_, entry) => {
+            servers.insert(MCP_SERVER_NAME.to_string(), entry);
+        }
+    }
+    Ok(Value::Object(root))
+}
+
+fn read_client_config(path: &Path) -> Result<Option<Value>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(path)?;
+    if content.trim().is_empty() {
+        return Ok(None);
+    }
+    let value = serde_json::from_str(&content).map_err(|e| anyhow!("配置文件不是有效的 JSON: {}", e))?;
+    Ok(Some(value))
+}
+
+fn detect_client(client: McpClient) -> McpClientStatus {
+    let path = client.config_path();
+    let config_exists = path.as_ref().is_some_and(|p| p.is_file());
+    let installed = config_exists || path.as_ref().and_then(|p| p.parent()).is_some_and(Path::is_dir);
+
+    let (configured, error) = match path.as_deref().map(read_client_config) {
+        Some(Ok(Some(value))) => (value.pointer(&format!("/mcpServers/{}", MCP_SERVER_NAME)).is_some(), None),
+        Some(Err(e)) => (false, Some(e.to_string())),
+        _ => (false, None),
+    };
+
+    McpClientStatus {
+        id: client.id().to_string(),
+        name: client.name().to_string(),
+        config_path: path.map(|p| p.to_string_lossy().to_string()),
+        installed,
+        config_exists,
+        configured,
+        error,
+    }
+}
+
+fn backup_client_config(path: &Path) -> Result<PathBuf> {
+    let timestamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(format!(".devkit-backup-{}", timestamp));
+    let backup = PathBuf::from(backup);
+    fs::copy(path, &backup)?;
+    Ok(backup)
+}
+
+fn install_client_config(client: McpClient) -> Result<McpClientInstallResult> {
+    let path = client
+        .config_path()
+        .ok_or_else(|| anyhow!("无法确定 {} 的配置文件位置", client.name()))?;
+    let existing = read_client_config(&path)?;
+    let merged = merge_server_entry(existing, server_entry(&mcp_server_command()))?;
+
+    let backup_path = if path.is_file() { Some(backup_client_config(&path)?) } else { None };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    write_atomic(&path, &serde_json::to_string_pretty(&merged)?)?;
+    log::info!("已写入 {} 的 MCP 配置: {:?}", client.name(), path);
+
+    Ok(McpClientInstallResult {
+        config_path: path.to_string_lossy().to_string(),
+        backup_path: backup_path.map(|p| p.to_string_lossy().to_string()),
+    })
+}
+
+/// 启动 MCP 服务并完成 initialize 握手，返回服务端信息
+async fn initialize_handshake(command: &str) -> Result<Value> {
+    let mut child = Command::new(command)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| anyhow!("无法启动 {}: {}", command, e))?;
+
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": "2024-11-05",
+            "capabilities": {},
+            "clientInfo": { "name": "devkit-setup", "version": env!("CARGO_PKG_VERSION") }
+        }
+    });
+    let mut stdin = child.stdin.take().ok_or_else(|| anyhow!("无法写入 MCP 服务的标准输入"))?;
+    stdin.write_all(format!("{}\n", request).as_bytes()).await?;
+    stdin.flush().await?;
+
+    let stdout = child.stdout.take().ok_or_else(|| anyhow!("无法读取 MCP 服务的标准输出"))?;
+    let mut lines = BufReader::new(stdout).lines();
+    while let Some(line) = lines.next_line().await? {
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        if message.get("id") != Some(&json!(1)) {
+            continue;
+        }
+        if let Some(error) = message.get("error") {
+            bail!("MCP 服务返回错误: {}", error);
+        }
+        return message
+            .get("result")
+            .cloned()
+            .ok_or_else(|| anyhow!("MCP 服务的响应缺少 result"));
+    }
+    bail!("MCP 服务未响应就退出了")
+}
+
+/// 检测本机的 MCP 客户端
+#[tauri::command]
+pub async fn detect_mcp_clients() -> Result<Vec<McpClientStatus>, String> {
+    Ok(McpClient::ALL.into_iter().map(detect_client).collect())
+}
+
+/// 生成指定客户端的 mcpServers 配置片段
+#[tauri::command]
+pub async fn generate_mcp_client_config(client_id: String) -> Result<String, String> {
+    McpClient::from_id(&client_id).map_err(|e| e.to_string())?;
+    let config = merge_server_entry(None, server_entry(&mcp_server_command())).map_err(|e| e.to_string())?;
+    serde_json::to_string_pretty(&config).map_err(|e| format!("生成配置失败: {}", e))
+}
+
+/// 把 devkit 写入指定客户端的配置文件
+#[tauri::command]
+pub async fn install_mcp_client_config(client_id: String) -> Result<McpClientInstallResult, String> {
+    let client = McpClient::from_id(&client_id).map_err(|e| e.to_string())?;
+    install_client_config(client).map_err(|e| format!("写入 {} 配置失败: {}", client.name(), e))
+}
+
+/// 检查 devkit-mcp 能否启动并响应 MCP 握手
+#[tauri::command]
+pub async fn verify_mcp_connectivity() -> Result<McpVerifyResult, String> {
+    let command = mcp_server_command();
+    let result = match tokio::time::timeout(VERIFY_TIMEOUT, initialize_handshake(&command)).await {
+        Ok(Ok(info)) => {
+            let server_info = info.get("serverInfo");
+            let field = |key: &str| server_info.and_then(|s| s.get(key)).and_then(Value::as_str).map(str::to_string);
+            McpVerifyResult {
+                success: true,
+                command,
+                server_name: field("name"),
+                server_version: field("version"),
+                message: "MCP 服务连接正常".to_string(),
+            }
+        }
+        Ok(Err(e)) => McpVerifyResult {
+            success: false,
+            command,
+            server_name: None,
+            server_version: None,
+            message: e.to_string(),
+        },
+        Err(_) => McpVerifyResult {
+            success: false,
+            command,
+            server_name: None,
+            server_version: None,
+            message: format!("MCP 服务在 {} 秒内未响应", VERIFY_TIMEOUT.as_secs()),
+        },
+    };
+    if !result.success {
+        log::warn!("MCP 连通性检查失败: {}", result.message);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_server_entry() {
+        let existing = json!({
+            "mcpServers": { "other": { "command": "other-mcp" }, "devkit": { "command": "old" } },
+            "theme": "dark"
+        });
+        let merged = merge_server_entry(Some(existing), server_entry("devkit-mcp")).unwrap();
+        assert_eq!(
+            merged,
+            json!({
+                "mcpServers": { "other": { "command": "other-mcp" }, "devkit": { "command": "devkit-mcp" } },
+                "theme": "dark"
+            })
+        );
+
+        // 已有 devkit 一项中的自定义字段保留
+        let existing = json!({ "mcpServers": { "devkit": { "command": "old", "args": ["--verbose"], "env": { "A": "1" } } } });
+        let merged = merge_server_entry(Some(existing), server_entry("devkit-mcp")).unwrap();
+        assert_eq!(
+            merged,
+            json!({ "mcpServers": { "devkit": { "command": "devkit-mcp", "args": ["--verbose"], "env": { "A": "1" } } } })
+        );
+
+        let created = merge_server_entry(None, server_entry("devkit-mcp")).unwrap();
+        assert_eq!(created, json!({ "mcpServers": { "devkit": { "command": "devkit-mcp" } } }));
+        assert!(merge_server_entry(Some(json!({ "mcpServers": [] })), server_entry("x")).is_err());
+        assert!(merge_server_entry(Some(json!([])), server_entry("x")).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+// 自定义 prompt 模板
+// prompt 内容中的 {{变量}} 在插入时替换：内置变量（项目、日期、剪贴板等）自动取值，其余变量由用户在插入前填写。
+// {{变量|默认值}} 在未填写时使用默认值；没有取值也没有默认值的变量原样保留，并在结果中列出。
+// 不合法的标记（如 {{ }}、含空格的名称）按普通文本处理
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// 当前项目的目录名
+pub const VAR_PROJECT: &str = "project";
+/// 当前项目的完整路径
+pub const VAR_PROJECT_PATH: &str = "project_path";
+pub const VAR_DATE: &str = "date";
+pub const VAR_TIME: &str = "time";
+pub const VAR_DATETIME: &str = "datetime";
+/// 剪贴板中的文本
+pub const VAR_CLIPBOARD: &str = "clipboard";
+
+/// 内置变量
+pub const BUILTIN_VARIABLES: &[&str] = &[VAR_PROJECT, VAR_PROJECT_PATH, VAR_DATE, VAR_TIME, VAR_DATETIME, VAR_CLIPBOARD];
+
+/// 模板中的变量
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct TemplateVariable {
+    pub name: String,
+    /// 内置变量自动取值，否则需要用户填写
+    pub builtin: bool,
+    pub default: Option<String>,
+}
+
+/// 渲染结果
+#[derive(Debug, Clone, Serialize)]
+pub struct RenderedPrompt {
+    pub text: String,
+    /// 没有取值的变量（在结果中原样保留）
+    pub missing: Vec<String>,
+}
+
+enum Segment<'a> {
+    Text(&'a str),
+    Variable { raw: &'a str, name: &'a str, default: Option<&'a str> },
+}
+
+fn parse_tag(inner: &str) -> Option<(&str, Option<&str>)> {
+    let (name, default) = match inner.split_once('|') {
+        Some((name, default)) => (name.trim(), Some(default)),
+        None => (inner.trim(), None),
+    };
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+    valid.then_some((name, default))
+}
+
+fn segments(template: &str) -> Vec<Segment<'_>> {
+    let mut result = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let Some(len) = rest[start + 2..].find("}}") else {
+            break;
+        };
+        let end = start + 2 + len + 2;
+        match parse_tag(&rest[start + 2..end - 2]) {
+            Some((name, default)) => {
+                result.push(Segment::Text(&rest[..start]));
+                result.push(Segment::Variable { raw: &rest[start..end], name, default });
+                rest = &rest[end..];
+            }
+            None => {
+                // 不是变量，跳过开头的 "{{" 继续查找
+                result.push(Segment::Text(&rest[..start + 2]));
+                rest = &rest[start + 2..];
+            }
+        }
+    }
+    result.push(Segment::Text(rest));
+    result
+}
+
+/// 按出现顺序列出模板中的变量（同名变量只列一次，取第一个默认值）
+pub fn template_variables(template: &str) -> Vec<TemplateVariable> {
+    let mut variables: Vec<TemplateVariable> = Vec::new();
+    for segment in segments(template) {
+        let Segment::Variable { name, default, .. } = segment else {
+            continue;
+        };
+        match variables.iter_mut().find(|v| v.name == name) {
+            Some(existing) => {
+                if existing.default.is_none() {
+                    existing.default = default.map(str::to_string);
+                }
+            }
+            None => variables.push(TemplateVariable {
+                name: name.to_string(),
+                builtin: BUILTIN_VARIABLES.contains(&name),
+                default: default.map(str::to_string),
+            }),
+        }
+    }
+    variables
+}
+
+/// 用给定的取值渲染模板
+pub fn render_template(template: &str, values: &HashMap<String, String>) -> RenderedPrompt {
+    let mut text = String::with_capacity(template.len());
+    let mut missing: Vec<String> = Vec::new();
+    for segment in segments(template) {
+        match segment {
+            Segment::Text(s) => text.push_str(s),
+            Segment::Variable { raw, name, default } => match values.get(name).map(String::as_str).or(default) {
+                Some(value) => text.push_str(value),
+                None => {
+                    text.push_str(raw);
+                    if !missing.iter().any(|m| m == name) {
+                        missing.push(name.to_string());
+                    }
+                }
+            },
+        }
+    }
+    RenderedPrompt { text, missing }
+}
+
+/// 计算模板用到的内置变量，取不到值的（如没有项目上下文、剪贴板为空）不填
+fn builtin_values(variables: &[TemplateVariable], project_root_path: Option<&str>) -> HashMap<String, String> {
+    let now = chrono::Local::now();
+    let project_root = project_root_path.map(str::trim).filter(|p| !p.is_empty());
+    let mut values = HashMap::new();
+    for variable in variables.iter().filter(|v| v.builtin) {
+        let value = match variable.name.as_str() {
+            VAR_PROJECT => project_root.map(|root| {
+                Path::new(root)
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_else(|| root.to_string())
+            }),
+            VAR_PROJECT_PATH => project_root.map(str::to_string),
+            VAR_DATE => Some(now.format("%Y-%m-%d").to_string()),
+            VAR_TIME => Some(now.format("%H:%M").to_string()),
+            VAR_DATETIME => Some(now.format("%Y-%m-%d %H:%M").to_string()),
+            VAR_CLIPBOARD => crate::ui::clipboard::read_clipboard_text(),
+            _ => None,
+        };
+        if let Some(value) = value {
+            values.insert(variable.name.clone(), value);
+        }
+    }
+    values
+}
+
+/// 列出 prompt 中的变量，前端据此让用户填写非内置变量
+#[tauri::command]
+pub async fn get_prompt_template_variables(content: String) -> Result<Vec<TemplateVariable>, String> {
+    Ok(template_variables(&content))
+}
+
+/// 渲染 prompt（插入时与设置页预览共用），inputs 中的取值优先于内置变量
+#[tauri::command]
+pub async fn render_prompt_template(
+    content: String,
+    project_root_path: Option<String>,
+    inputs: Option<HashMap<String, String>>,
+) -> Result<RenderedPrompt, String> {
+    let variables = template_variables(&content);
+    let mut values = builtin_values(&variables, project_root_path.as_deref());
+    values.extend(inputs.unwrap_or_default());
+    Ok(render_template(&content, &values))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_template() {
+        let template = "{{greeting|你好}}，{{ name }}！{{date}} {{ bad tag }} {{}} {{name}} {{unclosed";
+        let variables = template_variables(template);
+        let names: Vec<_> = variables.iter().map(|v| (v.name.as_str(), v.builtin)).collect();
+        assert_eq!(names, vec![("greeting", false), ("name", false), ("date", true)]);
+        assert_eq!(variables[0].default.as_deref(), Some("你好"));
+
+        let values = HashMap::from([("name".to_string(), "张三".to_string())]);
+        let rendered = render_template(template, &values);
+        assert_eq!(rendered.text, "你好，张三！{{date}} {{ bad tag }} {{}} 张三 {{unclosed");
+        assert_eq!(rendered.missing, vec!["date"]);
+    }
+}
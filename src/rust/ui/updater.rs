@@ -49,8 +49,21 @@ pub struct UpdateInfo {
     pub download_url: String,
     /// 网络状态信息（新增）
     pub network_status: NetworkStatus,
+    /// 当前版本之后各版本的更新说明（从新到旧）
+    pub changelog: Vec<ReleaseNote>,
 }
 
+/// 单个版本的更新说明
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ReleaseNote {
+    pub version: String,
+    pub published_at: String,
+    pub notes: String,
+}
+
+/// 一次最多获取的 release 数量，跨越太多版本时只展示最近的部分
+const CHANGELOG_RELEASES_PER_PAGE: u32 = 30;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdateProgress {
     pub chunk_length: usize,
@@ -143,12 +156,7 @@ pub async fn check_for_updates(app: AppHandle, state: State<'_, AppState>) -> Re
 
     log::info!("🏷️ GitHub tag: {}", tag_name);
 
-    // 移除前缀v和中文字符，只保留数字和点
-    let latest_version = tag_name
-        .replace("v", "")
-        .chars()
-        .filter(|c| c.is_numeric() || *c == '.')
-        .collect::<String>();
+    let latest_version = parse_release_version(&tag_name);
 
     log::info!("🆕 解析后的最新版本: {}", latest_version);
 
@@ -165,19 +173,115 @@ pub async fn check_for_updates(app: AppHandle, state: State<'_, AppState>) -> Re
     // 获取实际的下载URL（从assets中找到对应平台的文件）
     let download_url = get_platform_download_url(&release)?;
 
+    // 跨越多个版本时汇总中间各版本的更新说明，获取失败时只展示最新版本的说明
+    let mut changelog = Vec::new();
+    if has_update {
+        changelog = fetch_changelog(&client, &current_version, &latest_version).await;
+        if changelog.is_empty() {
+            changelog = collect_changelog(std::slice::from_ref(&release), &current_version, &latest_version);
+        }
+    }
+    let release_notes = if changelog.len() > 1 {
+        format_changelog(&changelog)
+    } else {
+        release["body"].as_str().unwrap_or("").to_string()
+    };
+
     let update_info = UpdateInfo {
         available: has_update,
         current_version,
         latest_version,
-        release_notes: release["body"].as_str().unwrap_or("").to_string(),
+        release_notes,
         download_url,
         network_status,
+        changelog,
     };
 
     log::info!("✅ 更新检查完成: {:?}", update_info);
     Ok(update_info)
 }
 
+/// 从 tag 中提取版本号：移除前缀v和中文字符，只保留数字和点
+fn parse_release_version(tag: &str) -> String {
+    tag.replace("v", "")
+        .chars()
+        .filter(|c| c.is_numeric() || *c == '.')
+        .collect()
+}
+
+/// 获取 release 列表并汇总当前版本之后的更新说明
+async fn fetch_changelog(client: &reqwest::Client, current: &str, latest: &str) -> Vec<ReleaseNote> {
+    let url = format!(
+        "https://api.github.com/repos/aspect-build/devkit/releases?per_page={}",
+        CHANGELOG_RELEASES_PER_PAGE
+    );
+    let response = client
+        .get(&url)
+        .header("User-Agent", "devkit-app/1.0")
+        .header("Accept", "application/vnd.github.v3+json")
+        .send()
+        .await;
+    let releases = match response {
+        Ok(response) if response.status().is_success() => response.json::<Vec<serde_json::Value>>().await,
+        Ok(response) => {
+            log::warn!("⚠️ 获取 release 列表失败: {}", response.status());
+            return Vec::new();
+        }
+        Err(e) => {
+            log::warn!("⚠️ 获取 release 列表失败: {}", e);
+            return Vec::new();
+        }
+    };
+    match releases {
+        Ok(releases) => collect_changelog(&releases, current, latest),
+        Err(e) => {
+            log::warn!("⚠️ 解析 release 列表失败: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// 挑出版本号在 (current, latest] 之间的正式版本，按版本从新到旧排列
+fn collect_changelog(releases: &[serde_json::Value], current: &str, latest: &str) -> Vec<ReleaseNote> {
+    let mut notes: Vec<ReleaseNote> = releases
+        .iter()
+        .filter(|release| !release["draft"].as_bool().unwrap_or(false) && !release["prerelease"].as_bool().unwrap_or(false))
+        .filter_map(|release| {
+            let version = parse_release_version(release["tag_name"].as_str()?);
+            let in_range = compare_versions(&version, current) && !compare_versions(&version, latest);
+            in_range.then(|| ReleaseNote {
+                version,
+                published_at: release["published_at"].as_str().unwrap_or("").to_string(),
+                notes: release["body"].as_str().unwrap_or("").trim().to_string(),
+            })
+        })
+        .collect();
+    notes.sort_by(|a, b| {
+        if compare_versions(&a.version, &b.version) {
+            std::cmp::Ordering::Less
+        } else if compare_versions(&b.version, &a.version) {
+            std::cmp::Ordering::Greater
+        } else {
+            std::cmp::Ordering::Equal
+        }
+    });
+    notes
+}
+
+/// 多个版本的说明合并为一份 Markdown
+fn format_changelog(notes: &[ReleaseNote]) -> String {
+    notes
+        .iter()
+        .map(|note| {
+            let date = note.published_at.get(..10).unwrap_or("");
+            let body = if note.notes.is_empty() { "暂无更新说明" } else { &note.notes };
+            let header = format!("## v{} {}", note.version, date);
+            format!("{}\n\n{}", header.trim_end(), body)
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 /// 简单的版本比较函数
 fn compare_versions(v1: &str, v2: &str) -> bool {
     let v1_parts: Vec<u32> = v1.split('.').filter_map(|s| s.parse().ok()).collect();
@@ -1037,3 +1141,26 @@ async fn detect_geo_location_full() -> GeoLocation {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_collect_changelog() {
+        let releases = vec![
+            json!({ "tag_name": "v0.27.1", "body": "修复", "published_at": "2026-10-02T00:00:00Z" }),
+            json!({ "tag_name": "v0.28.0-测试版", "body": "预览", "prerelease": true }),
+            json!({ "tag_name": "v0.26.12", "body": "当前" }),
+            json!({ "tag_name": "v0.27.0", "body": "", "published_at": "2026-09-20T00:00:00Z" }),
+        ];
+        let notes = collect_changelog(&releases, "0.26.12", "0.27.1");
+        let versions: Vec<&str> = notes.iter().map(|n| n.version.as_str()).collect();
+        assert_eq!(versions, vec!["0.27.1", "0.27.0"]);
+        assert_eq!(
+            format_changelog(&notes),
+            "## v0.27.1 2026-10-02\n\n修复\n\n## v0.27.0 2026-09-20\n\n暂无更新说明"
+        );
+    }
+}
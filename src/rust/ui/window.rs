@@ -1,7 +1,8 @@
-use tauri::{State, Manager};
-use crate::config::{AppState, save_config};
-use crate::constants::window;
+use tauri::{AppHandle, Manager, Monitor, PhysicalPosition, State, WebviewWindow};
+use crate::config::{AppState, WindowConfig, WindowPosition, save_config};
+use crate::constants::{validation, window};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct WindowSizeUpdate {
@@ -53,7 +54,12 @@ pub async fn apply_window_constraints(state: State<'_, AppState>, app: tauri::Ap
 }
 
 #[tauri::command]
-pub async fn update_window_size(size_update: WindowSizeUpdate, state: State<'_, AppState>, app: tauri::AppHandle) -> Result<(), String> {
+pub async fn update_window_size(size_update: WindowSizeUpdate, state: State<'_, AppState>, app: tauri::AppHandle) -> Result<validation::ValidationReport, String> {
+    let mut size_update = size_update;
+    let mut report = validation::ValidationReport::new();
+    (size_update.width, size_update.height) =
+        validation::check_window_size(&mut report, ("width", "height"), size_update.width, size_update.height);
+
     // 更新配置
     {
         let mut config = state.config.lock().map_err(|e| format!("获取配置失败: {}", e))?;
@@ -133,5 +139,203 @@ pub async fn update_window_size(size_update: WindowSizeUpdate, state: State<'_,
         }
     }
 
-    Ok(())
+    Ok(report)
+}
+
+/// 窗口移动停止后多久保存位置，避免拖动过程中频繁写配置
+const POSITION_SAVE_DELAY_MS: u64 = 1000;
+
+/// 窗口标题栏区域至少有这么大落在显示器内才视为可见（仍可拖动）
+const MIN_VISIBLE_WIDTH: i64 = 100;
+const MIN_VISIBLE_HEIGHT: i64 = 40;
+
+/// 每次移动递增，延迟保存时发现已变化则放弃本次保存
+static POSITION_SAVE_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// 显示器区域（物理像素）
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonitorRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MonitorRect {
+    fn from_monitor(monitor: &Monitor) -> Self {
+        Self {
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        }
+    }
+
+    fn contains(&self, x: f64, y: f64) -> bool {
+        x >= self.x as f64
+            && x < self.x as f64 + self.width as f64
+            && y >= self.y as f64
+            && y < self.y as f64 + self.height as f64
+    }
+}
+
+/// 显示器布局签名，显示器的数量、位置或分辨率变化时签名随之变化
+pub fn monitor_layout_key(monitors: &[MonitorRect]) -> String {
+    let mut parts: Vec<String> = monitors
+        .iter()
+        .map(|m| format!("{}x{}@{},{}", m.width, m.height, m.x, m.y))
+        .collect();
+    parts.sort();
+    parts.join("|")
+}
+
+/// 窗口标题栏是否有足够区域落在某个显示器内
+pub fn is_position_visible(position: WindowPosition, width: u32, monitors: &[MonitorRect]) -> bool {
+    let (left, top) = (position.x as i64, position.y as i64);
+    let right = left + width as i64;
+    let bottom = top + MIN_VISIBLE_HEIGHT;
+    monitors.iter().any(|m| {
+        let visible_width = right.min(m.x as i64 + m.width as i64) - left.max(m.x as i64);
+        let visible_height = bottom.min(m.y as i64 + m.height as i64) - top.max(m.y as i64);
+        visible_width >= MIN_VISIBLE_WIDTH.min(width as i64) && visible_height >= MIN_VISIBLE_HEIGHT
+    })
+}
+
+/// 窗口在显示器内居中的位置，窗口比显示器大时与显示器左上角对齐
+pub fn centered_position(monitor: &MonitorRect, width: u32, height: u32) -> WindowPosition {
+    WindowPosition {
+        x: monitor.x + (monitor.width.saturating_sub(width) / 2) as i32,
+        y: monitor.y + (monitor.height.saturating_sub(height) / 2) as i32,
+    }
+}
+
+fn monitor_rects(window: &WebviewWindow) -> Vec<MonitorRect> {
+    window
+        .available_monitors()
+        .map(|monitors| monitors.iter().map(MonitorRect::from_monitor).collect())
+        .unwrap_or_default()
+}
+
+/// 记录窗口移动后的位置，停止移动一段时间后再保存到配置文件
+pub fn remember_window_position(app: &AppHandle, position: PhysicalPosition<i32>) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    // 最小化时部分平台会把窗口移到屏幕外，不记录
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    let monitors = monitor_rects(&window);
+    let width = window.outer_size().map(|size| size.width).unwrap_or(0);
+    let position = WindowPosition { x: position.x, y: position.y };
+    if monitors.is_empty() || !is_position_visible(position, width, &monitors) {
+        return;
+    }
+
+    let state = app.state::<AppState>();
+    {
+        let Ok(mut config) = state.config.lock() else {
+            return;
+        };
+        let positions = &mut config.ui_config.window_config.positions;
+        let key = monitor_layout_key(&monitors);
+        if positions.get(&key) == Some(&position) {
+            return;
+        }
+        positions.insert(key, position);
+    }
+
+    let generation = POSITION_SAVE_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(tokio::time::Duration::from_millis(POSITION_SAVE_DELAY_MS)).await;
+        if POSITION_SAVE_GENERATION.load(Ordering::SeqCst) != generation {
+            return;
+        }
+        let state = app.state::<AppState>();
+        if let Err(e) = save_config(&state, &app).await {
+            log::warn!("保存窗口位置失败: {}", e);
+        }
+    });
+}
+
+/// 按当前显示器布局恢复窗口位置；没有记录或记录的位置已不可见时居中显示
+pub fn restore_window_position(window: &WebviewWindow, window_config: &WindowConfig) {
+    let monitors = monitor_rects(window);
+    if monitors.is_empty() {
+        return;
+    }
+    let width = window.outer_size().map(|size| size.width).unwrap_or(0);
+
+    match window_config.positions.get(&monitor_layout_key(&monitors)) {
+        Some(&position) if is_position_visible(position, width, &monitors) => {
+            if let Err(e) = window.set_position(PhysicalPosition::new(position.x, position.y)) {
+                log::warn!("恢复窗口位置失败: {}", e);
+            } else {
+                log::debug!("窗口位置已恢复: ({}, {})", position.x, position.y);
+            }
+        }
+        Some(_) => {
+            log::info!("记录的窗口位置已不在任何显示器内，改为居中显示");
+            if let Err(e) = window.center() {
+                log::warn!("窗口居中失败: {}", e);
+            }
+        }
+        None => {}
+    }
+}
+
+/// 窗口在当前所在显示器内居中
+#[tauri::command]
+pub async fn center_window(app: AppHandle) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("找不到主窗口")?;
+    window.center().map_err(|e| format!("窗口居中失败: {}", e))
+}
+
+/// 把窗口移到鼠标所在的显示器并居中
+#[tauri::command]
+pub async fn move_window_to_cursor_monitor(app: AppHandle) -> Result<(), String> {
+    let window = app.get_webview_window("main").ok_or("找不到主窗口")?;
+    let cursor = window
+        .cursor_position()
+        .map_err(|e| format!("获取鼠标位置失败: {}", e))?;
+    let monitors = monitor_rects(&window);
+    let monitor = monitors
+        .iter()
+        .find(|m| m.contains(cursor.x, cursor.y))
+        .ok_or("找不到鼠标所在的显示器")?;
+    let size = window
+        .outer_size()
+        .map_err(|e| format!("获取窗口大小失败: {}", e))?;
+
+    let position = centered_position(monitor, size.width, size.height);
+    window
+        .set_position(PhysicalPosition::new(position.x, position.y))
+        .map_err(|e| format!("移动窗口失败: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_visibility() {
+        let monitors = [
+            MonitorRect { x: 0, y: 0, width: 1920, height: 1080 },
+            MonitorRect { x: 1920, y: -200, width: 2560, height: 1440 },
+        ];
+        assert_eq!(monitor_layout_key(&monitors[..1]), "1920x1080@0,0");
+
+        assert!(is_position_visible(WindowPosition { x: 100, y: 100 }, 600, &monitors));
+        assert!(is_position_visible(WindowPosition { x: 3000, y: -150 }, 600, &monitors));
+        // 副屏拔掉后落在原副屏上的位置
+        assert!(!is_position_visible(WindowPosition { x: 3000, y: 100 }, 600, &monitors[..1]));
+        // 标题栏在屏幕上方
+        assert!(!is_position_visible(WindowPosition { x: 100, y: -30 }, 600, &monitors[..1]));
+        // 只露出窄窄一条
+        assert!(!is_position_visible(WindowPosition { x: 1880, y: 100 }, 600, &monitors[..1]));
+
+        assert_eq!(centered_position(&monitors[1], 600, 900), WindowPosition { x: 2900, y: 70 });
+        assert_eq!(centered_position(&monitors[0], 2000, 900), WindowPosition { x: 0, y: 90 });
+    }
 }
@@ -1,16 +1,28 @@
 use crate::config::AppState;
+use crate::constants::theme;
 use crate::log_important;
-use tauri::{AppHandle, Manager, WindowEvent};
+use crate::ui::commands::stash_dropped_files;
+use crate::ui::background::background_mode_active;
+use crate::ui::window::remember_window_position;
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, Theme, WindowEvent};
 
 /// 设置窗口事件监听器
 pub fn setup_window_event_listeners(app_handle: &AppHandle) {
     if let Some(window) = app_handle.get_webview_window("main") {
         let app_handle_clone = app_handle.clone();
         
-        window.on_window_event(move |event| {
-            if let WindowEvent::CloseRequested { api, .. } = event {
+        window.on_window_event(move |event| match event {
+            WindowEvent::CloseRequested { api, .. } => {
                 // 阻止默认的关闭行为
                 api.prevent_close();
+
+                // 后台模式下关闭窗口只隐藏到托盘
+                if background_mode_active(&app_handle_clone) {
+                    if let Some(window) = app_handle_clone.get_webview_window("main") {
+                        let _ = window.hide();
+                    }
+                    return;
+                }
                 
                 let app_handle = app_handle_clone.clone();
                 
@@ -39,6 +51,52 @@ pub fn setup_window_event_listeners(app_handle: &AppHandle) {
                     }
                 });
             }
+            WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) if !paths.is_empty() => {
+                let app_handle = app_handle_clone.clone();
+                let paths = paths.clone();
+
+                // 读取、转码文件可能较慢，放到阻塞线程中处理
+                tauri::async_runtime::spawn(async move {
+                    let dropped = match tauri::async_runtime::spawn_blocking(move || stash_dropped_files(&paths)).await {
+                        Ok(dropped) => dropped,
+                        Err(e) => {
+                            log_important!(error, "处理拖放文件失败: {}", e);
+                            return;
+                        }
+                    };
+                    log_important!(
+                        info,
+                        "拖放文件已保存: {} 个，失败 {} 个",
+                        dropped.ingredients.len(),
+                        dropped.errors.len()
+                    );
+                    if let Err(e) = app_handle.emit("ingredients-dropped", &dropped) {
+                        log_important!(error, "发送拖放结果失败: {}", e);
+                    }
+                });
+            }
+            WindowEvent::Moved(position) => {
+                remember_window_position(&app_handle_clone, *position);
+            }
+            WindowEvent::ThemeChanged(system) => {
+                // 只有跟随系统时才通知前端切换主题
+                let preference = match app_handle_clone.state::<AppState>().config.lock() {
+                    Ok(config) => config.ui_config.theme.clone(),
+                    Err(_) => return,
+                };
+                if preference != theme::AUTO {
+                    return;
+                }
+                let system = match system {
+                    Theme::Light => theme::LIGHT,
+                    _ => theme::DARK,
+                };
+                log_important!(info, "系统主题已切换为 {}", system);
+                if let Err(e) = app_handle_clone.emit("theme-changed", theme::resolve_theme(&preference, system)) {
+                    log_important!(error, "发送主题切换事件失败: {}", e);
+                }
+            }
+            _ => {}
         });
     }
 }
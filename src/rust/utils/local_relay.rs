@@ -0,0 +1,137 @@
+// 本机进程间转发
+// 每个 MCP 请求都在独立的进程中等待回复，而 Telegram webhook 的监听地址、Slack 的 Socket Mode 连接
+// 同一时间只应由一个进程持有：持有者在 127.0.0.1 上提供转发服务，把收到的更新（单行 JSON）转发给其他进程。
+// 转发信息（端口与令牌）写入数据目录下的文件，连接后先发送令牌，令牌不一致的连接直接关闭
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::broadcast;
+
+/// 连接后发送令牌的超时（秒）
+const HANDSHAKE_TIMEOUT_SECS: u64 = 5;
+
+/// 转发服务的地址和令牌
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelayInfo {
+    pub port: u16,
+    pub token: String,
+}
+
+impl RelayInfo {
+    /// 为已绑定的转发服务生成随机令牌
+    pub fn for_listener(listener: &TcpListener) -> Result<Self> {
+        Ok(Self {
+            port: listener.local_addr()?.port(),
+            token: uuid::Uuid::new_v4().simple().to_string(),
+        })
+    }
+}
+
+fn info_path(name: &str) -> Result<PathBuf> {
+    let dir = dirs::data_dir()
+        .or_else(dirs::config_dir)
+        .ok_or_else(|| anyhow!("无法获取数据目录"))?
+        .join("bistro");
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir.join(format!("{}-relay.json", name)))
+}
+
+pub fn read_info(name: &str) -> Result<RelayInfo> {
+    let content = std::fs::read_to_string(info_path(name)?)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+pub fn write_info(name: &str, info: &RelayInfo) -> Result<()> {
+    std::fs::write(info_path(name)?, serde_json::to_string(info)?)?;
+    Ok(())
+}
+
+/// 删除转发信息文件（仅当仍是该令牌对应的服务写入的）
+pub fn remove_info(name: &str, token: &str) {
+    if read_info(name).is_ok_and(|info| info.token == token) {
+        if let Ok(path) = info_path(name) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// 比较令牌，比较耗时与不一致的位置无关
+fn token_matches(provided: &str, expected: &str) -> bool {
+    let (provided, expected) = (provided.as_bytes(), expected.as_bytes());
+    !expected.is_empty()
+        && provided.len() == expected.len()
+        && provided.iter().zip(expected).fold(0u8, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// 接受其他进程的连接并转发 sender 中的更新，直到监听出错
+pub async fn serve(listener: TcpListener, token: String, sender: broadcast::Sender<String>) {
+    while let Ok((stream, _)) = listener.accept().await {
+        tokio::spawn(serve_connection(stream, token.clone(), sender.clone()));
+    }
+}
+
+async fn serve_connection(stream: TcpStream, token: String, sender: broadcast::Sender<String>) {
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    let handshake = tokio::time::timeout(
+        tokio::time::Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
+        BufReader::new(reader).read_line(&mut line),
+    )
+    .await;
+    if !matches!(handshake, Ok(Ok(_))) || !token_matches(line.trim_end(), &token) {
+        return;
+    }
+
+    let mut receiver = sender.subscribe();
+    loop {
+        match receiver.recv().await {
+            Ok(update) => {
+                if writer.write_all(format!("{}\n", update).as_bytes()).await.is_err() {
+                    return;
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(_)) => {}
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
+
+/// 连接转发服务，返回按行读取的更新
+pub async fn connect(info: &RelayInfo) -> Result<Lines<BufReader<TcpStream>>> {
+    let mut stream = TcpStream::connect(("127.0.0.1", info.port)).await?;
+    stream.write_all(format!("{}\n", info.token).as_bytes()).await?;
+    Ok(BufReader::new(stream).lines())
+}
+
+/// 把转发来的更新写入 sender，直到连接断开
+pub async fn forward(mut lines: Lines<BufReader<TcpStream>>, sender: broadcast::Sender<String>) {
+    while let Ok(Some(line)) = lines.next_line().await {
+        let _ = sender.send(line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_relay_round_trip() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let info = RelayInfo::for_listener(&listener).unwrap();
+        let (sender, _) = broadcast::channel(8);
+        tokio::spawn(serve(listener, info.token.clone(), sender.clone()));
+
+        let mut rejected = connect(&RelayInfo { token: "wrong".to_string(), ..info.clone() }).await.unwrap();
+        let mut lines = connect(&info).await.unwrap();
+        // 等待连接完成订阅
+        while sender.receiver_count() < 1 {
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        }
+        sender.send(r#"{"update_id":1}"#.to_string()).unwrap();
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some(r#"{"update_id":1}"#));
+        assert_eq!(rejected.next_line().await.unwrap(), None);
+    }
+}
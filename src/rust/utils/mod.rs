@@ -1,3 +1,5 @@
+pub mod local_relay;
 pub mod logger;
+pub mod sealed_store;
 
 pub use logger::{LogConfig, init_logger, auto_init_logger, init_mcp_logger};
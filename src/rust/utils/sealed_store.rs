@@ -0,0 +1,227 @@
+// 本地文件加密
+// 本地索引缓存、交互历史与食材缓存都可以选择使用 AES-256-GCM 加密写入，三者只在文件头、钥匙串条目与提示文字上不同。
+// 密钥随机生成并保存在系统钥匙串（macOS Keychain / Windows 凭据管理器 / Linux Secret Service）中，各自使用不同的条目。
+// 加密格式为 文件头 + 随机 nonce + 密文（含认证标签），文件头同时作为附加认证数据；读取时按文件头自动识别是否加密
+
+use anyhow::Result;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// 原子写入时使用的临时文件后缀
+pub const TMP_SUFFIX: &str = ".tmp";
+
+/// 一类加密文件：文件头、钥匙串条目与用于提示的名称
+pub struct SealedStore {
+    magic: &'static [u8],
+    keychain_service: &'static str,
+    keychain_account: &'static str,
+    label: &'static str,
+    /// 已从钥匙串读取的密钥
+    key: Mutex<Option<[u8; 32]>>,
+}
+
+impl SealedStore {
+    pub const fn new(
+        magic: &'static [u8],
+        keychain_service: &'static str,
+        keychain_account: &'static str,
+        label: &'static str,
+    ) -> Self {
+        Self {
+            magic,
+            keychain_service,
+            keychain_account,
+            label,
+            key: Mutex::new(None),
+        }
+    }
+
+    pub fn is_sealed(&self, data: &[u8]) -> bool {
+        data.starts_with(self.magic)
+    }
+
+    /// 文件是否以加密格式保存（只读取文件头）
+    pub fn is_sealed_file(&self, path: impl AsRef<Path>) -> bool {
+        let mut header = vec![0u8; self.magic.len()];
+        fs::File::open(path)
+            .and_then(|mut f| io::Read::read_exact(&mut f, &mut header))
+            .map(|_| header == self.magic)
+            .unwrap_or(false)
+    }
+
+    /// 加密数据（钥匙串中没有密钥时生成）
+    pub fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        encrypt(self.magic, &self.load_key(true)?, plaintext, self.label)
+    }
+
+    /// 解密 seal 生成的数据
+    pub fn open(&self, data: &[u8]) -> Result<Vec<u8>> {
+        decrypt(self.magic, &self.load_key(false)?, data, self.label)
+    }
+
+    /// 读取文件（加密文件自动解密）
+    pub fn read(&self, path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+        let raw = fs::read(path)?;
+        if !self.is_sealed(&raw) {
+            return Ok(raw);
+        }
+        let key = self
+            .load_key(false)
+            .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e.to_string()))?;
+        decrypt(self.magic, &key, &raw, self.label).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+
+    /// 读取文件为字符串（加密文件自动解密）
+    pub fn read_to_string(&self, path: impl AsRef<Path>) -> io::Result<String> {
+        String::from_utf8(self.read(path)?).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// 原子写入文件，sealed 为 true 时加密写入
+    pub fn write(&self, path: impl AsRef<Path>, data: impl AsRef<[u8]>, sealed: bool) -> io::Result<()> {
+        if !sealed {
+            return write_atomic(path.as_ref(), data.as_ref());
+        }
+        let key = self
+            .load_key(true)
+            .map_err(|e| io::Error::new(io::ErrorKind::PermissionDenied, e.to_string()))?;
+        let sealed = encrypt(self.magic, &key, data.as_ref(), self.label).map_err(|e| io::Error::other(e.to_string()))?;
+        write_atomic(path.as_ref(), &sealed)
+    }
+
+    /// 按开关状态加密或解密已有文件，返回迁移的文件数
+    pub fn migrate(&self, files: impl IntoIterator<Item = PathBuf>, enabled: bool) -> Result<usize> {
+        // 开启前确认钥匙串可用，避免写入后无法解密；关闭时仅在存在加密文件时才需要密钥
+        if enabled {
+            self.load_key(true)?;
+        }
+        let mut migrated = 0;
+        for path in files {
+            let raw = match fs::read(&path) {
+                Ok(raw) => raw,
+                Err(_) => continue,
+            };
+            let converted = match (enabled, self.is_sealed(&raw)) {
+                (true, false) => self.seal(&raw)?,
+                (false, true) => self.open(&raw)?,
+                _ => continue,
+            };
+            write_atomic(&path, &converted)?;
+            migrated += 1;
+        }
+        Ok(migrated)
+    }
+
+    /// 从钥匙串读取密钥；create 为 true 时不存在则生成并保存
+    fn load_key(&self, create: bool) -> Result<[u8; 32]> {
+        let label = self.label;
+        let mut cached = self.key.lock().map_err(|e| anyhow::anyhow!("读取{}密钥失败: {}", label, e))?;
+        if let Some(key) = *cached {
+            return Ok(key);
+        }
+
+        let entry = keyring::Entry::new(self.keychain_service, self.keychain_account)
+            .map_err(|e| anyhow::anyhow!("无法访问系统钥匙串: {}", e))?;
+        let key = match entry.get_password() {
+            Ok(encoded) => {
+                let bytes = hex::decode(encoded.trim()).map_err(|e| anyhow::anyhow!("钥匙串中的{}密钥无效: {}", label, e))?;
+                <[u8; 32]>::try_from(bytes.as_slice()).map_err(|_| anyhow::anyhow!("钥匙串中的{}密钥长度无效", label))?
+            }
+            Err(keyring::Error::NoEntry) if create => {
+                let mut key = [0u8; 32];
+                SystemRandom::new()
+                    .fill(&mut key)
+                    .map_err(|_| anyhow::anyhow!("生成{}密钥失败", label))?;
+                entry
+                    .set_password(&hex::encode(key))
+                    .map_err(|e| anyhow::anyhow!("保存{}密钥到系统钥匙串失败: {}", label, e))?;
+                key
+            }
+            Err(e) => anyhow::bail!("从系统钥匙串读取{}密钥失败: {}", label, e),
+        };
+
+        *cached = Some(key);
+        Ok(key)
+    }
+}
+
+/// 先写同目录下的临时文件再重命名，崩溃时原文件保持完整
+pub fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(TMP_SUFFIX);
+    let tmp = PathBuf::from(tmp);
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+/// 加密：文件头 + 随机 nonce + 密文（含认证标签）
+fn encrypt(magic: &[u8], key: &[u8; 32], plaintext: &[u8], label: &str) -> Result<Vec<u8>> {
+    let sealing_key = aead_key(key, label)?;
+    let mut nonce_bytes = [0u8; NONCE_LEN];
+    SystemRandom::new()
+        .fill(&mut nonce_bytes)
+        .map_err(|_| anyhow::anyhow!("生成随机数失败"))?;
+
+    let mut in_out = plaintext.to_vec();
+    sealing_key
+        .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce_bytes), Aad::from(magic), &mut in_out)
+        .map_err(|_| anyhow::anyhow!("加密{}失败", label))?;
+
+    let mut out = Vec::with_capacity(magic.len() + NONCE_LEN + in_out.len());
+    out.extend_from_slice(magic);
+    out.extend_from_slice(&nonce_bytes);
+    out.extend_from_slice(&in_out);
+    Ok(out)
+}
+
+fn decrypt(magic: &[u8], key: &[u8; 32], data: &[u8], label: &str) -> Result<Vec<u8>> {
+    let body = data
+        .strip_prefix(magic)
+        .filter(|body| body.len() >= NONCE_LEN)
+        .ok_or_else(|| anyhow::anyhow!("{}文件格式无效", label))?;
+    let (nonce_bytes, ciphertext) = body.split_at(NONCE_LEN);
+    let nonce = Nonce::try_assume_unique_for_key(nonce_bytes).map_err(|_| anyhow::anyhow!("{}文件格式无效", label))?;
+
+    let mut in_out = ciphertext.to_vec();
+    let plaintext = aead_key(key, label)?
+        .open_in_place(nonce, Aad::from(magic), &mut in_out)
+        .map_err(|_| anyhow::anyhow!("解密{}失败（密钥不匹配或文件已损坏）", label))?;
+    Ok(plaintext.to_vec())
+}
+
+fn aead_key(key: &[u8; 32], label: &str) -> Result<LessSafeKey> {
+    let unbound = UnboundKey::new(&AES_256_GCM, key).map_err(|_| anyhow::anyhow!("{}密钥无效", label))?;
+    Ok(LessSafeKey::new(unbound))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAGIC: &[u8] = b"TEST-ENC1\n";
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let key = [7u8; 32];
+        let plaintext = "# 请求\n\nAPI_KEY=sk-live".as_bytes();
+        let sealed = encrypt(MAGIC, &key, plaintext, "测试").unwrap();
+        assert!(sealed.starts_with(MAGIC));
+        assert!(!sealed.windows(7).any(|w| w == b"sk-live"));
+        assert_eq!(decrypt(MAGIC, &key, &sealed, "测试").unwrap(), plaintext);
+
+        // 密钥不匹配、内容被篡改或文件头不同时解密失败
+        assert!(decrypt(MAGIC, &[8u8; 32], &sealed, "测试").is_err());
+        let mut tampered = sealed.clone();
+        *tampered.last_mut().unwrap() ^= 1;
+        assert!(decrypt(MAGIC, &key, &tampered, "测试").is_err());
+        let mut relabeled = b"TEST-ENC2\n".to_vec();
+        relabeled.extend_from_slice(&sealed[MAGIC.len()..]);
+        assert!(decrypt(b"TEST-ENC2\n", &key, &relabeled, "测试").is_err());
+    }
+}
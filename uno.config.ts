@@ -195,6 +195,8 @@ export default defineConfig({
     'i-carbon-play',
     'i-carbon-close',
     'i-carbon-send',
+    'i-carbon-logo-slack',
+    'i-carbon-notification',
     'i-carbon-warning',
     'i-carbon-volume-up',
     'i-carbon-arrow-left',